@@ -36,6 +36,28 @@ pub struct Parameters {
     #[serde(default = "Parameters::default_max_forward_time_drift")]
     pub max_forward_time_drift: Duration,
 
+    /// Maximum number of commits to fetch from a peer in one request when catching up via
+    /// commit sync. An authority also only starts commit sync when it is behind the quorum by
+    /// at least this many commits, and relies on block synchronization for smaller gaps.
+    #[serde(default = "Parameters::default_commit_sync_batch_size")]
+    pub commit_sync_batch_size: u32,
+
+    /// Number of rounds below the last committed leader round, for which blocks and commits are
+    /// kept in storage. Older data is removed once the consensus output consumer has handled its
+    /// commits, and its blocks have been committed or garbage collected by the protocol. This
+    /// should be large enough to cover commits that lagging peers may still fetch via commit sync.
+    /// Setting it to 0 disables pruning storage.
+    #[serde(default = "Parameters::default_gc_depth")]
+    pub gc_depth: u32,
+
+    /// The network stack used to communicate with peers.
+    #[serde(default = "Parameters::default_network")]
+    pub network: ConsensusNetwork,
+
+    /// Tonic network settings. Only used when `network` is `Tonic`.
+    #[serde(default = "TonicParameters::default")]
+    pub tonic: TonicParameters,
+
     /// The database path.
     /// Required.
     pub db_path: Option<PathBuf>,
@@ -58,6 +80,18 @@ impl Parameters {
         Duration::from_millis(500)
     }
 
+    pub fn default_commit_sync_batch_size() -> u32 {
+        100
+    }
+
+    pub fn default_gc_depth() -> u32 {
+        50_000
+    }
+
+    pub fn default_network() -> ConsensusNetwork {
+        ConsensusNetwork::Anemo
+    }
+
     pub fn db_path_str_unsafe(&self) -> String {
         self.db_path
             .clone()
@@ -76,7 +110,62 @@ impl Default for Parameters {
             leader_timeout: Parameters::default_leader_timeout(),
             min_round_delay: Parameters::default_min_round_delay(),
             max_forward_time_drift: Parameters::default_max_forward_time_drift(),
+            commit_sync_batch_size: Parameters::default_commit_sync_batch_size(),
+            gc_depth: Parameters::default_gc_depth(),
+            network: Parameters::default_network(),
+            tonic: TonicParameters::default(),
             db_path: None,
         }
     }
 }
+
+/// Network stacks available to consensus. Authorities using different network stacks cannot
+/// communicate with each other, so all authorities of a committee need to use the same one.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ConsensusNetwork {
+    /// QUIC based network with Anemo.
+    #[default]
+    Anemo,
+    /// HTTP/2 based network with Tonic, secured by TLS with the network keys of authorities.
+    Tonic,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TonicParameters {
+    /// Keepalive interval and timeout for both client and server connections.
+    #[serde(default = "TonicParameters::default_keepalive_interval")]
+    pub keepalive_interval: Duration,
+
+    /// Size of the buffer for blocks queued on the block stream to each peer. Sending a block
+    /// waits when the buffer is full.
+    #[serde(default = "TonicParameters::default_block_stream_buffer_size")]
+    pub block_stream_buffer_size: usize,
+
+    /// Maximum size of a message received or sent over the network.
+    #[serde(default = "TonicParameters::default_message_size_limit")]
+    pub message_size_limit: usize,
+}
+
+impl TonicParameters {
+    pub fn default_keepalive_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    pub fn default_block_stream_buffer_size() -> usize {
+        100
+    }
+
+    pub fn default_message_size_limit() -> usize {
+        64 << 20
+    }
+}
+
+impl Default for TonicParameters {
+    fn default() -> Self {
+        Self {
+            keepalive_interval: TonicParameters::default_keepalive_interval(),
+            block_stream_buffer_size: TonicParameters::default_block_stream_buffer_size(),
+            message_size_limit: TonicParameters::default_message_size_limit(),
+        }
+    }
+}
@@ -8,10 +8,11 @@ use std::{
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
-// Build script to generate anemo RPC stubs.
+// Build script to generate anemo and tonic RPC stubs.
 fn main() -> Result<()> {
     let out_dir = PathBuf::from(env::var("OUT_DIR")?);
     build_anemo_services(&out_dir);
+    build_tonic_services(&out_dir);
 
     println!("cargo:rerun-if-changed=build.rs");
 
@@ -46,9 +47,60 @@ fn build_anemo_services(out_dir: &Path) {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("fetch_commits")
+                .route_name("FetchCommits")
+                .request_type("crate::network::FetchCommitsRequest")
+                .response_type("crate::network::FetchCommitsResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
         .build();
 
     anemo_build::manual::Builder::new()
         .out_dir(out_dir)
         .compile(&[consensus_rpc]);
 }
+
+fn build_tonic_services(out_dir: &Path) {
+    let codec_path = "mysten_network::codec::BcsSnappyCodec";
+
+    let consensus_service = tonic_build::manual::Service::builder()
+        .name("ConsensusService")
+        .package("consensus")
+        .comment("Consensus authority interface")
+        .method(
+            tonic_build::manual::Method::builder()
+                .name("send_blocks")
+                .route_name("SendBlocks")
+                .input_type("crate::network::SendBlockRequest")
+                .output_type("crate::network::SendBlockResponse")
+                .codec_path(codec_path)
+                .client_streaming()
+                .build(),
+        )
+        .method(
+            tonic_build::manual::Method::builder()
+                .name("fetch_blocks")
+                .route_name("FetchBlocks")
+                .input_type("crate::network::FetchBlocksRequest")
+                .output_type("crate::network::FetchBlocksResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            tonic_build::manual::Method::builder()
+                .name("fetch_commits")
+                .route_name("FetchCommits")
+                .input_type("crate::network::FetchCommitsRequest")
+                .output_type("crate::network::FetchCommitsResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .build();
+
+    tonic_build::manual::Builder::new()
+        .out_dir(out_dir)
+        .compile(&[consensus_service]);
+}
@@ -1,11 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{sync::Arc, time::Duration, time::Instant, vec};
+use std::{ops::RangeInclusive, sync::Arc, time::Duration, time::Instant, vec};
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use consensus_config::{AuthorityIndex, Committee, NetworkKeyPair, Parameters, ProtocolKeyPair};
+use consensus_config::{
+    AuthorityIndex, Committee, ConsensusNetwork, NetworkKeyPair, Parameters, ProtocolKeyPair,
+};
 use parking_lot::RwLock;
 use prometheus::Registry;
 use sui_protocol_config::ProtocolConfig;
@@ -17,7 +19,9 @@ use crate::{
     block_manager::BlockManager,
     block_verifier::{BlockVerifier, SignedBlockVerifier},
     broadcaster::Broadcaster,
+    commit::CommitIndex,
     commit_observer::CommitObserver,
+    commit_syncer::{CommitSyncer, CommitSyncerHandle, CommitVoteMonitor},
     context::Context,
     core::{Core, CoreSignals},
     core_thread::{ChannelCoreThreadDispatcher, CoreThreadDispatcher, CoreThreadHandle},
@@ -25,8 +29,11 @@ use crate::{
     error::{ConsensusError, ConsensusResult},
     leader_timeout::{LeaderTimeoutTask, LeaderTimeoutTaskHandle},
     metrics::initialise_metrics,
-    network::{anemo_network::AnemoManager, NetworkManager, NetworkService},
-    storage::rocksdb_store::RocksDBStore,
+    network::{
+        anemo_network::AnemoManager, tonic_network::TonicManager, NetworkManager, NetworkService,
+    },
+    stake_aggregator::{QuorumThreshold, StakeAggregator},
+    storage::{rocksdb_store::RocksDBStore, Store},
     synchronizer::{Synchronizer, SynchronizerHandle},
     transaction::{TransactionClient, TransactionConsumer, TransactionVerifier},
     CommitConsumer,
@@ -34,7 +41,13 @@ use crate::{
 
 // This type is used by Sui as part of starting consensus via MysticetiManager.
 // It hides the details of the types.
-pub struct ConsensusAuthority(AuthorityNode<AnemoManager>);
+pub struct ConsensusAuthority(ConsensusAuthorityNode);
+
+// Authority node with the network stack selected in `Parameters`.
+enum ConsensusAuthorityNode {
+    WithAnemo(AuthorityNode<AnemoManager>),
+    WithTonic(AuthorityNode<TonicManager>),
+}
 
 impl ConsensusAuthority {
     pub async fn start(
@@ -48,27 +61,61 @@ impl ConsensusAuthority {
         commit_consumer: CommitConsumer,
         registry: Registry,
     ) -> Self {
-        let authority_node = AuthorityNode::start(
-            own_index,
-            committee,
-            parameters,
-            protocol_config,
-            protocol_keypair,
-            network_keypair,
-            transaction_verifier,
-            commit_consumer,
-            registry,
-        )
-        .await;
-        Self(authority_node)
+        let node = match parameters.network {
+            ConsensusNetwork::Anemo => {
+                let authority_node = AuthorityNode::start(
+                    own_index,
+                    committee,
+                    parameters,
+                    protocol_config,
+                    protocol_keypair,
+                    network_keypair,
+                    transaction_verifier,
+                    commit_consumer,
+                    registry,
+                )
+                .await;
+                ConsensusAuthorityNode::WithAnemo(authority_node)
+            }
+            ConsensusNetwork::Tonic => {
+                let authority_node = AuthorityNode::start(
+                    own_index,
+                    committee,
+                    parameters,
+                    protocol_config,
+                    protocol_keypair,
+                    network_keypair,
+                    transaction_verifier,
+                    commit_consumer,
+                    registry,
+                )
+                .await;
+                ConsensusAuthorityNode::WithTonic(authority_node)
+            }
+        };
+        Self(node)
     }
 
     pub async fn stop(self) {
-        self.0.stop().await;
+        match self.0 {
+            ConsensusAuthorityNode::WithAnemo(node) => node.stop().await,
+            ConsensusAuthorityNode::WithTonic(node) => node.stop().await,
+        }
     }
 
     pub fn transaction_client(&self) -> Arc<TransactionClient> {
-        self.0.transaction_client()
+        match &self.0 {
+            ConsensusAuthorityNode::WithAnemo(node) => node.transaction_client(),
+            ConsensusAuthorityNode::WithTonic(node) => node.transaction_client(),
+        }
+    }
+
+    #[cfg(test)]
+    fn context(&self) -> &Arc<Context> {
+        match &self.0 {
+            ConsensusAuthorityNode::WithAnemo(node) => &node.context,
+            ConsensusAuthorityNode::WithTonic(node) => &node.context,
+        }
     }
 }
 
@@ -80,6 +127,7 @@ where
     start_time: Instant,
     transaction_client: Arc<TransactionClient>,
     synchronizer: Arc<SynchronizerHandle>,
+    commit_syncer: Arc<CommitSyncerHandle>,
     leader_timeout_handle: LeaderTimeoutTaskHandle,
     core_thread_handle: CoreThreadHandle,
     broadcaster: Broadcaster,
@@ -131,6 +179,10 @@ where
 
         let store = Arc::new(RocksDBStore::new(&context.parameters.db_path_str_unsafe()));
         let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        // Storage is only pruned up to the commits handled by the consumer.
+        dag_state
+            .write()
+            .set_commit_consumer_monitor(commit_consumer.monitor());
 
         let block_verifier = Arc::new(SignedBlockVerifier::new(
             context.clone(),
@@ -140,8 +192,12 @@ where
         let block_manager =
             BlockManager::new(context.clone(), dag_state.clone(), block_verifier.clone());
 
-        let commit_observer =
-            CommitObserver::new(context.clone(), commit_consumer, dag_state.clone(), store);
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            commit_consumer,
+            dag_state.clone(),
+            store.clone(),
+        );
 
         let core = Core::new(
             context.clone(),
@@ -160,18 +216,30 @@ where
             LeaderTimeoutTask::start(core_dispatcher.clone(), &signals_receivers, context.clone());
 
         let synchronizer = Synchronizer::start(
-            network_client,
+            network_client.clone(),
             context.clone(),
             core_dispatcher.clone(),
             block_verifier.clone(),
         );
 
+        let commit_vote_monitor = Arc::new(CommitVoteMonitor::new(context.clone()));
+        let commit_syncer = CommitSyncer::start(
+            context.clone(),
+            dag_state.clone(),
+            commit_vote_monitor.clone(),
+            network_client,
+            block_verifier.clone(),
+            core_dispatcher.clone(),
+        );
+
         let network_service = Arc::new(AuthorityService {
             context: context.clone(),
             block_verifier,
             core_dispatcher,
             synchronizer: synchronizer.clone(),
+            commit_vote_monitor,
             dag_state,
+            store,
         });
         network_manager
             .install_service(network_keypair, network_service)
@@ -182,6 +250,7 @@ where
             start_time,
             transaction_client: Arc::new(tx_client),
             synchronizer,
+            commit_syncer,
             leader_timeout_handle,
             core_thread_handle,
             broadcaster,
@@ -200,6 +269,7 @@ where
         self.core_thread_handle.stop().await;
         self.leader_timeout_handle.stop().await;
         self.synchronizer.stop().await;
+        self.commit_syncer.stop().await;
 
         self.context
             .metrics
@@ -219,7 +289,9 @@ pub(crate) struct AuthorityService<C: CoreThreadDispatcher> {
     block_verifier: Arc<dyn BlockVerifier>,
     core_dispatcher: Arc<C>,
     synchronizer: Arc<SynchronizerHandle>,
+    commit_vote_monitor: Arc<CommitVoteMonitor>,
     dag_state: Arc<RwLock<DagState>>,
+    store: Arc<dyn Store>,
 }
 
 #[async_trait]
@@ -259,6 +331,9 @@ impl<C: CoreThreadDispatcher> NetworkService for AuthorityService<C> {
         }
         let verified_block = VerifiedBlock::new_verified(signed_block, serialized_block);
 
+        // Track the commits voted by the peer, to detect when this authority falls behind.
+        self.commit_vote_monitor.observe_block(&verified_block);
+
         // Reject block with timestamp too far in the future.
         let forward_time_drift = Duration::from_millis(
             verified_block
@@ -339,6 +414,57 @@ impl<C: CoreThreadDispatcher> NetworkService for AuthorityService<C> {
 
         Ok(result)
     }
+
+    async fn handle_fetch_commits(
+        &self,
+        _peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+        let (start, end) = (*commit_range.start(), *commit_range.end());
+        if start == 0 || start > end {
+            return Err(ConsensusError::InvalidCommitRange { start, end });
+        }
+
+        // Serve at most a batch of commits per request.
+        let batch_size = self.context.parameters.commit_sync_batch_size.max(1);
+        let end = end.min(start.saturating_add(batch_size - 1));
+        let mut commits = self.store.scan_commits(start..end.saturating_add(1))?;
+
+        // Only the last returned commit needs to be certified by votes from a quorum, since the
+        // earlier commits are chained to it by digests. Trailing commits without a quorum of
+        // votes in stored blocks are not returned yet.
+        let mut certifier_blocks = vec![];
+        while let Some(last_commit) = commits.last() {
+            let votes = self.store.read_commit_votes(last_commit.reference())?;
+            let mut stake_aggregator = StakeAggregator::<QuorumThreshold>::new();
+            let mut certifier_refs = vec![];
+            for vote in votes {
+                certifier_refs.push(vote);
+                if stake_aggregator.add(vote.author, &self.context.committee) {
+                    break;
+                }
+            }
+            if stake_aggregator.reached_threshold(&self.context.committee) {
+                // The voting blocks may have been garbage collected after reading their refs.
+                let blocks = self.store.read_blocks(&certifier_refs)?;
+                if blocks.iter().all(Option::is_some) {
+                    certifier_blocks = blocks.into_iter().flatten().collect();
+                    break;
+                }
+            }
+            commits.pop();
+        }
+
+        let commits = commits
+            .into_iter()
+            .map(|commit| commit.serialized().clone())
+            .collect();
+        let certifier_blocks = certifier_blocks
+            .into_iter()
+            .map(|block| block.serialized().clone())
+            .collect();
+        Ok((commits, certifier_blocks))
+    }
 }
 
 #[cfg(test)]
@@ -351,6 +477,7 @@ mod tests {
     use fastcrypto::traits::KeyPair;
     use parking_lot::Mutex;
     use prometheus::Registry;
+    use rstest::rstest;
     use sui_protocol_config::ProtocolConfig;
     use tempfile::TempDir;
     use tokio::sync::mpsc::unbounded_channel;
@@ -360,10 +487,11 @@ mod tests {
     use crate::authority_node::AuthorityService;
     use crate::block::{timestamp_utc_ms, BlockRef, Round, TestBlock, VerifiedBlock};
     use crate::block_verifier::NoopBlockVerifier;
+    use crate::commit::{CommitDigest, TrustedCommit};
     use crate::context::Context;
     use crate::core_thread::{CoreError, CoreThreadDispatcher};
     use crate::network::NetworkClient;
-    use crate::storage::mem_store::MemStore;
+    use crate::storage::{mem_store::MemStore, WriteBatch};
     use crate::transaction::NoopTransactionVerifier;
 
     struct FakeCoreThreadDispatcher {
@@ -400,6 +528,14 @@ mod tests {
         async fn get_missing_blocks(&self) -> Result<BTreeSet<BlockRef>, CoreError> {
             unimplemented!()
         }
+
+        async fn add_certified_commits(
+            &self,
+            _commits: Vec<TrustedCommit>,
+            _blocks: Vec<VerifiedBlock>,
+        ) -> Result<BTreeSet<BlockRef>, CoreError> {
+            unimplemented!()
+        }
     }
 
     #[derive(Default)]
@@ -422,16 +558,28 @@ mod tests {
         ) -> ConsensusResult<Vec<Bytes>> {
             unimplemented!("Unimplemented")
         }
+
+        async fn fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            _commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            unimplemented!("Unimplemented")
+        }
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn test_authority_start_and_stop() {
+    async fn test_authority_start_and_stop(
+        #[values(ConsensusNetwork::Anemo, ConsensusNetwork::Tonic)] network: ConsensusNetwork,
+    ) {
         let (committee, keypairs) = local_committee_and_keys(0, vec![1]);
         let registry = Registry::new();
 
         let temp_dir = TempDir::new().unwrap();
         let parameters = Parameters {
             db_path: Some(temp_dir.into_path()),
+            network,
             ..Default::default()
         };
         let txn_verifier = NoopTransactionVerifier {};
@@ -456,9 +604,9 @@ mod tests {
         )
         .await;
 
-        assert_eq!(authority.0.context.own_index, own_index);
-        assert_eq!(authority.0.context.committee.epoch(), 0);
-        assert_eq!(authority.0.context.committee.size(), 1);
+        assert_eq!(authority.context().own_index, own_index);
+        assert_eq!(authority.context().committee.epoch(), 0);
+        assert_eq!(authority.context().committee.size(), 1);
 
         authority.stop().await;
     }
@@ -471,7 +619,7 @@ mod tests {
         let core_dispatcher = Arc::new(FakeCoreThreadDispatcher::new());
         let network_client = Arc::new(FakeNetworkClient::default());
         let store = Arc::new(MemStore::new());
-        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
         let synchronizer = Synchronizer::start(
             network_client,
             context.clone(),
@@ -483,7 +631,9 @@ mod tests {
             block_verifier,
             core_dispatcher: core_dispatcher.clone(),
             synchronizer,
+            commit_vote_monitor: Arc::new(CommitVoteMonitor::new(context.clone())),
             dag_state,
+            store,
         });
 
         // Test delaying blocks with time drift.
@@ -513,6 +663,102 @@ mod tests {
         assert_eq!(blocks[0], input_block);
     }
 
+    #[tokio::test]
+    async fn test_authority_service_fetch_commits() {
+        let (context, _keys) = Context::new_for_test(4);
+        let context = Arc::new(context);
+        let block_verifier = Arc::new(NoopBlockVerifier {});
+        let core_dispatcher = Arc::new(FakeCoreThreadDispatcher::new());
+        let network_client = Arc::new(FakeNetworkClient::default());
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let synchronizer = Synchronizer::start(
+            network_client,
+            context.clone(),
+            core_dispatcher.clone(),
+            block_verifier.clone(),
+        );
+        let authority_service = Arc::new(AuthorityService {
+            context: context.clone(),
+            block_verifier,
+            core_dispatcher,
+            synchronizer,
+            commit_vote_monitor: Arc::new(CommitVoteMonitor::new(context.clone())),
+            dag_state,
+            store: store.clone(),
+        });
+
+        // Store a chain of 5 commits. Commits 3 and 4 are voted by a quorum, while commit 5
+        // is only voted by one authority so far.
+        let mut commits = vec![];
+        let mut previous_digest = CommitDigest::MIN;
+        for index in 1..=5 {
+            let leader = VerifiedBlock::new_for_test(TestBlock::new(index, 0).build());
+            let commit = TrustedCommit::new_for_test(
+                index,
+                previous_digest,
+                leader.reference(),
+                vec![leader.reference()],
+            );
+            previous_digest = commit.digest();
+            commits.push(commit);
+        }
+        let voting_blocks = (0..4)
+            .map(|author| {
+                let mut votes = vec![commits[2].reference(), commits[3].reference()];
+                if author == 0 {
+                    votes.push(commits[4].reference());
+                }
+                VerifiedBlock::new_for_test(
+                    TestBlock::new(6, author).set_commit_votes(votes).build(),
+                )
+            })
+            .collect::<Vec<_>>();
+        store
+            .write(
+                WriteBatch::default()
+                    .blocks(voting_blocks)
+                    .commits(commits.clone()),
+            )
+            .unwrap();
+
+        let peer = context.committee.to_authority_index(1).unwrap();
+
+        // Commits up to the last certified one are returned, with a quorum of votes for it.
+        let (serialized_commits, serialized_blocks) = authority_service
+            .handle_fetch_commits(peer, 1..=10)
+            .await
+            .unwrap();
+        let expected_commits = commits[..4]
+            .iter()
+            .map(|commit| commit.serialized().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(serialized_commits, expected_commits);
+        assert_eq!(serialized_blocks.len(), 3);
+        for serialized in serialized_blocks {
+            let block: SignedBlock = bcs::from_bytes(&serialized).unwrap();
+            assert!(block.commit_votes().contains(&commits[3].reference()));
+        }
+
+        // Commits without a quorum of votes are not returned.
+        let (serialized_commits, serialized_blocks) = authority_service
+            .handle_fetch_commits(peer, 1..=2)
+            .await
+            .unwrap();
+        assert!(serialized_commits.is_empty());
+        assert!(serialized_blocks.is_empty());
+
+        // Invalid ranges are rejected.
+        assert!(matches!(
+            authority_service.handle_fetch_commits(peer, 3..=2).await,
+            Err(ConsensusError::InvalidCommitRange { start: 3, end: 2 })
+        ));
+        assert!(matches!(
+            authority_service.handle_fetch_commits(peer, 0..=2).await,
+            Err(ConsensusError::InvalidCommitRange { start: 0, end: 2 })
+        ));
+    }
+
     // TODO: build AuthorityFixture.
     #[tokio::test(flavor = "current_thread", start_paused = true)]
     async fn test_authority_committee() {
@@ -5,10 +5,11 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::iter;
 use std::sync::Arc;
 
+use consensus_config::AuthorityIndex;
 use parking_lot::RwLock;
 use tracing::warn;
 
-use crate::block::{BlockAPI, BlockRef, VerifiedBlock};
+use crate::block::{BlockAPI, BlockDigest, BlockRef, VerifiedBlock};
 use crate::block_verifier::BlockVerifier;
 use crate::context::Context;
 use crate::dag_state::DagState;
@@ -76,71 +77,21 @@ impl BlockManager {
         blocks.sort_by_key(|b| b.round());
 
         let mut accepted_blocks = vec![];
+
+        // Suspended blocks that were only missing ancestors at or below the GC round since the last
+        // call can now be accepted.
+        let unsuspended_blocks = self.try_unsuspend_blocks_for_gc_round();
+        accepted_blocks.extend(self.verify_and_accept_blocks(unsuspended_blocks));
+
         let missing_blocks_before = self.missing_blocks.clone();
 
         for block in blocks {
             if let Some(block) = self.try_accept_one_block(block) {
                 // Try to unsuspend any children blocks.
                 let unsuspended_blocks = self.try_unsuspend_children_blocks(&block);
-
-                // Try to verify the block with ancestor blocks.
-                let mut blocks_to_accept: BTreeMap<BlockRef, VerifiedBlock> = BTreeMap::new();
-                let mut blocks_to_reject: BTreeMap<BlockRef, VerifiedBlock> = BTreeMap::new();
-                {
-                    'block: for b in iter::once(block).chain(unsuspended_blocks) {
-                        let ancestors = self.dag_state.read().get_blocks(b.ancestors());
-                        assert_eq!(b.ancestors().len(), ancestors.len());
-                        let mut ancestor_blocks = vec![];
-                        'ancestor: for (included, found) in
-                            b.ancestors().iter().zip(ancestors.into_iter())
-                        {
-                            if let Some(found_block) = found {
-                                // This invariant should be guaranteed by DagState.
-                                assert_eq!(included, &found_block.reference());
-                                ancestor_blocks.push(found_block);
-                                continue 'ancestor;
-                            }
-                            // blocks_to_accept have not been added to DagState yet, but they
-                            // can appear in ancestors.
-                            if blocks_to_accept.contains_key(included) {
-                                ancestor_blocks.push(blocks_to_accept[included].clone());
-                                continue 'ancestor;
-                            }
-                            // If an ancestor is already rejected, reject this block as well.
-                            if blocks_to_reject.contains_key(included) {
-                                blocks_to_reject.insert(b.reference(), b);
-                                continue 'block;
-                            }
-                            panic!("Unsuspended block {:?} has a missing ancestor! Ancestor not found in DagState: {:?}", b, included);
-                        }
-                        if let Err(e) = self.block_verifier.check_ancestors(&b, &ancestor_blocks) {
-                            warn!("Block {:?} failed to verify ancestors: {}", b, e);
-                            blocks_to_reject.insert(b.reference(), b);
-                        } else {
-                            blocks_to_accept.insert(b.reference(), b);
-                        }
-                    }
-                }
-                for (block_ref, block) in blocks_to_reject {
-                    self.context
-                        .metrics
-                        .node_metrics
-                        .invalid_blocks
-                        .with_label_values(&[&block_ref.author.to_string(), "accept_block"])
-                        .inc();
-                    warn!("Invalid block {:?} is rejected", block);
-                }
-
-                // TODO: report blocks_to_reject to peers.
-
-                // Insert the accepted blocks into DAG state so future blocks including them as
-                // ancestors do not get suspended.
-                let blocks_to_accept: Vec<_> = blocks_to_accept.into_values().collect();
-                self.dag_state
-                    .write()
-                    .accept_blocks(blocks_to_accept.clone());
-
-                accepted_blocks.extend(blocks_to_accept);
+                accepted_blocks.extend(
+                    self.verify_and_accept_blocks(iter::once(block).chain(unsuspended_blocks)),
+                );
             }
         }
 
@@ -157,6 +108,79 @@ impl BlockManager {
         (accepted_blocks, missing_blocks_after)
     }
 
+    /// Verifies the provided blocks, which must have their causal history available and be in
+    /// causal order, against their ancestors, and accepts the valid ones into DagState. Returns
+    /// the accepted blocks.
+    fn verify_and_accept_blocks(
+        &mut self,
+        blocks: impl IntoIterator<Item = VerifiedBlock>,
+    ) -> Vec<VerifiedBlock> {
+        let gc_round = self.dag_state.read().gc_round();
+
+        // Try to verify the block with ancestor blocks.
+        let mut blocks_to_accept: BTreeMap<BlockRef, VerifiedBlock> = BTreeMap::new();
+        let mut blocks_to_reject: BTreeMap<BlockRef, VerifiedBlock> = BTreeMap::new();
+        {
+            'block: for b in blocks {
+                let ancestors = self.dag_state.read().get_blocks(b.ancestors());
+                assert_eq!(b.ancestors().len(), ancestors.len());
+                let mut ancestor_blocks = vec![];
+                'ancestor: for (included, found) in b.ancestors().iter().zip(ancestors.into_iter())
+                {
+                    if let Some(found_block) = found {
+                        // This invariant should be guaranteed by DagState.
+                        assert_eq!(included, &found_block.reference());
+                        ancestor_blocks.push(Some(found_block));
+                        continue 'ancestor;
+                    }
+                    // blocks_to_accept have not been added to DagState yet, but they
+                    // can appear in ancestors.
+                    if blocks_to_accept.contains_key(included) {
+                        ancestor_blocks.push(Some(blocks_to_accept[included].clone()));
+                        continue 'ancestor;
+                    }
+                    // If an ancestor is already rejected, reject this block as well.
+                    if blocks_to_reject.contains_key(included) {
+                        blocks_to_reject.insert(b.reference(), b);
+                        continue 'block;
+                    }
+                    // Ancestors at or below the GC round may never have been accepted.
+                    if included.round <= gc_round {
+                        ancestor_blocks.push(None);
+                        continue 'ancestor;
+                    }
+                    panic!("Unsuspended block {:?} has a missing ancestor! Ancestor not found in DagState: {:?}", b, included);
+                }
+                if let Err(e) = self.block_verifier.check_ancestors(&b, &ancestor_blocks) {
+                    warn!("Block {:?} failed to verify ancestors: {}", b, e);
+                    blocks_to_reject.insert(b.reference(), b);
+                } else {
+                    blocks_to_accept.insert(b.reference(), b);
+                }
+            }
+        }
+        for (block_ref, block) in blocks_to_reject {
+            self.context
+                .metrics
+                .node_metrics
+                .invalid_blocks
+                .with_label_values(&[&block_ref.author.to_string(), "accept_block"])
+                .inc();
+            warn!("Invalid block {:?} is rejected", block);
+        }
+
+        // TODO: report blocks_to_reject to peers.
+
+        // Insert the accepted blocks into DAG state so future blocks including them as
+        // ancestors do not get suspended.
+        let blocks_to_accept: Vec<_> = blocks_to_accept.into_values().collect();
+        self.dag_state
+            .write()
+            .accept_blocks(blocks_to_accept.clone());
+
+        blocks_to_accept
+    }
+
     /// Tries to accept the provided block. To accept a block its ancestors must have been already successfully accepted. If
     /// block is accepted then Some result is returned. None is returned when either the block is suspended or the block
     /// has been already accepted before.
@@ -164,21 +188,29 @@ impl BlockManager {
         let block_ref = block.reference();
         let mut missing_ancestors = BTreeSet::new();
         let dag_state = self.dag_state.read();
+        let gc_round = dag_state.gc_round();
 
         // If block has been already received and suspended, or already processed and stored, or is a genesis block, then skip it.
         if self.suspended_blocks.contains_key(&block_ref) || dag_state.contains_block(&block_ref) {
             return None;
         }
 
+        // Blocks at or below the GC round can no longer be committed, so they are not accepted.
+        if block_ref.round <= gc_round {
+            self.missing_blocks.remove(&block_ref);
+            return None;
+        }
+
         let ancestors = block.ancestors();
 
-        // make sure that we have all the required ancestors in store
+        // make sure that we have all the required ancestors in store. Ancestors at or below the GC
+        // round are not needed, as they are excluded from commits.
         for (found, ancestor) in dag_state
             .contains_blocks(ancestors.to_vec())
             .into_iter()
             .zip(ancestors.iter())
         {
-            if !found {
+            if !found && ancestor.round > gc_round {
                 missing_ancestors.insert(*ancestor);
 
                 // mark the block as having missing ancestors
@@ -264,6 +296,67 @@ impl BlockManager {
         unsuspended_blocks
     }
 
+    /// Garbage collects the suspended blocks and missing ancestors at or below the GC round, and
+    /// unsuspends the blocks that were only missing such ancestors. The unsuspended blocks, and
+    /// any of their suspended children that can now be unsuspended too, are returned in causal
+    /// order.
+    fn try_unsuspend_blocks_for_gc_round(&mut self) -> Vec<VerifiedBlock> {
+        let gc_round = self.dag_state.read().gc_round();
+        let first_kept = BlockRef::new(gc_round + 1, AuthorityIndex::ZERO, BlockDigest::MIN);
+
+        // Suspended blocks at or below the GC round will never be accepted.
+        let kept_blocks = self.suspended_blocks.split_off(&first_kept);
+        let gc_blocks = std::mem::replace(&mut self.suspended_blocks, kept_blocks);
+        self.missing_blocks = self.missing_blocks.split_off(&first_kept);
+        let kept_ancestors = self.missing_ancestors.split_off(&first_kept);
+        let gc_ancestors = std::mem::replace(&mut self.missing_ancestors, kept_ancestors);
+
+        for block in gc_blocks.values() {
+            let hostname = self
+                .context
+                .committee
+                .authority(block.block.author())
+                .hostname
+                .as_str();
+            self.context
+                .metrics
+                .node_metrics
+                .gc_suspended_blocks
+                .with_label_values(&[hostname])
+                .inc();
+        }
+
+        let mut unsuspended_blocks = vec![];
+        for (ancestor, children) in gc_ancestors {
+            for child in children {
+                // Children at or below the GC round have been garbage collected above.
+                if !self.suspended_blocks.contains_key(&child) {
+                    continue;
+                }
+                if let Some(block) = self.try_unsuspend_block(&child, &ancestor) {
+                    let hostname = self
+                        .context
+                        .committee
+                        .authority(block.block.author())
+                        .hostname
+                        .as_str();
+                    self.context
+                        .metrics
+                        .node_metrics
+                        .unsuspended_blocks
+                        .with_label_values(&[hostname])
+                        .inc();
+                    let children = self.try_unsuspend_children_blocks(&block.block);
+                    unsuspended_blocks.push(block.block);
+                    unsuspended_blocks.extend(children);
+                }
+            }
+        }
+
+        unsuspended_blocks.sort_by_key(|b| b.round());
+        unsuspended_blocks
+    }
+
     /// Attempts to unsuspend a block by checking its ancestors and removing the `accepted_dependency` by its local set.
     /// If there is no missing dependency then this block can be unsuspended immediately and is removed from the `suspended_blocks` map.
     fn try_unsuspend_block(
@@ -306,6 +399,7 @@ impl BlockManager {
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
+    use std::ops::RangeInclusive;
     use std::sync::Arc;
 
     use parking_lot::RwLock;
@@ -318,6 +412,7 @@ mod tests {
     };
     use crate::block_manager::BlockManager;
     use crate::block_verifier::{BlockVerifier, NoopBlockVerifier};
+    use crate::commit::{CommitDigest, TrustedCommit};
     use crate::context::Context;
     use crate::dag_state::DagState;
     use crate::error::{ConsensusError, ConsensusResult};
@@ -483,6 +578,71 @@ mod tests {
 
     /// Creates all the blocks to produce a fully connected DAG from round 0 up to `end_round`.
     /// Note: this method also returns the genesis blocks.
+    #[test]
+    fn accept_blocks_with_garbage_collected_ancestors() {
+        // GIVEN
+        let (mut context, _key_pairs) = Context::new_for_test(4);
+        context
+            .protocol_config
+            .set_consensus_gc_depth_for_testing(3);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+
+        let mut block_manager = BlockManager::new(
+            context.clone(),
+            dag_state.clone(),
+            Arc::new(NoopBlockVerifier),
+        );
+
+        // create a DAG of 8 rounds
+        let all_blocks = dag(context, 8);
+        let blocks_in_rounds = |rounds: RangeInclusive<Round>| {
+            all_blocks
+                .iter()
+                .filter(|block| rounds.contains(&block.round()))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        // Accept rounds 1 ~ 3, and skip round 4 so that rounds 5 ~ 8 get suspended.
+        let (accepted_blocks, missing) = block_manager.try_accept_blocks(blocks_in_rounds(1..=3));
+        assert_eq!(accepted_blocks.len(), 12);
+        assert!(missing.is_empty());
+
+        let (accepted_blocks, missing) = block_manager.try_accept_blocks(blocks_in_rounds(5..=8));
+        assert!(accepted_blocks.is_empty());
+        assert_eq!(missing.len(), 4);
+        assert!(missing.iter().all(|block_ref| block_ref.round == 4));
+        assert_eq!(block_manager.suspended_blocks().len(), 16);
+
+        // WHEN a commit at round 8 moves the GC round to 5, so round 4 blocks are no longer needed.
+        dag_state.write().add_commit(TrustedCommit::new_for_test(
+            1,
+            CommitDigest::MIN,
+            all_blocks.last().unwrap().reference(),
+            vec![],
+        ));
+        assert_eq!(dag_state.read().gc_round(), 5);
+        let (accepted_blocks, missing) = block_manager.try_accept_blocks(vec![]);
+
+        // THEN the suspended blocks at round 5 are garbage collected, and the blocks of rounds
+        // 6 ~ 8, whose only missing ancestors were at round 5, are accepted in causal order.
+        assert!(missing.is_empty());
+        assert_eq!(accepted_blocks, blocks_in_rounds(6..=8));
+        assert!(block_manager.suspended_blocks().is_empty());
+        assert!(block_manager.missing_blocks().is_empty());
+
+        // AND blocks at or below the GC round are neither accepted nor reported as missing.
+        let (accepted_blocks, missing) = block_manager.try_accept_blocks(blocks_in_rounds(4..=5));
+        assert!(accepted_blocks.is_empty());
+        assert!(missing.is_empty());
+        assert!(block_manager.suspended_blocks().is_empty());
+        for block in blocks_in_rounds(4..=5) {
+            assert!(!dag_state.read().contains_block(&block.reference()));
+        }
+    }
+
     fn dag(context: Arc<Context>, end_round: u64) -> Vec<VerifiedBlock> {
         let mut last_round_blocks = genesis_blocks(context.clone());
         let mut all_blocks = vec![];
@@ -520,7 +680,7 @@ mod tests {
         fn check_ancestors(
             &self,
             block: &VerifiedBlock,
-            _ancestors: &[VerifiedBlock],
+            _ancestors: &[Option<VerifiedBlock>],
         ) -> ConsensusResult<()> {
             if self.fail.contains(&block.reference()) {
                 Err(ConsensusError::InvalidBlockTimestamp {
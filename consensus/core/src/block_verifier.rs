@@ -23,10 +23,12 @@ pub(crate) trait BlockVerifier: Send + Sync + 'static {
     /// and is ready to be accepted into the DAG.
     ///
     /// Caller must make sure ancestors corresponse to block.ancestors() 1-to-1, in the same order.
+    /// An ancestor is None when it has been garbage collected without being accepted locally, and
+    /// is not checked.
     fn check_ancestors(
         &self,
         block: &VerifiedBlock,
-        ancestors: &[VerifiedBlock],
+        ancestors: &[Option<VerifiedBlock>],
     ) -> ConsensusResult<()>;
 }
 
@@ -142,7 +144,33 @@ impl BlockVerifier for SignedBlockVerifier {
             });
         }
 
-        // TODO: check transaction size, total size and count.
+        // TODO: check transaction count.
+        let max_transaction_size = self
+            .context
+            .protocol_config
+            .consensus_max_transaction_size_bytes();
+        let max_transactions_in_block_bytes = self
+            .context
+            .protocol_config
+            .consensus_max_transactions_in_block_bytes();
+        let mut total_transactions_bytes = 0;
+        for transaction in block.transactions() {
+            let size = transaction.data().len() as u64;
+            if size > max_transaction_size {
+                return Err(ConsensusError::OversizedTransaction {
+                    size,
+                    limit: max_transaction_size,
+                });
+            }
+            total_transactions_bytes += size;
+        }
+        if total_transactions_bytes > max_transactions_in_block_bytes {
+            return Err(ConsensusError::TooManyTransactionBytes {
+                size: total_transactions_bytes,
+                limit: max_transactions_in_block_bytes,
+            });
+        }
+
         let batch: Vec<_> = block.transactions().iter().map(|t| t.data()).collect();
         self.transaction_verifier
             .verify_batch(&self.context.protocol_config, &batch)
@@ -152,12 +180,15 @@ impl BlockVerifier for SignedBlockVerifier {
     fn check_ancestors(
         &self,
         block: &VerifiedBlock,
-        ancestors: &[VerifiedBlock],
+        ancestors: &[Option<VerifiedBlock>],
     ) -> ConsensusResult<()> {
         assert_eq!(block.ancestors().len(), ancestors.len());
         // This checks the invariant that block timestamp >= max ancestor timestamp.
         let mut max_timestamp_ms = BlockTimestampMs::MIN;
         for (ancestor_ref, ancestor_block) in block.ancestors().iter().zip(ancestors.iter()) {
+            let Some(ancestor_block) = ancestor_block else {
+                continue;
+            };
             assert_eq!(ancestor_ref, &ancestor_block.reference());
             max_timestamp_ms = max_timestamp_ms.max(ancestor_block.timestamp_ms());
         }
@@ -181,7 +212,7 @@ impl BlockVerifier for NoopBlockVerifier {
     fn check_ancestors(
         &self,
         _block: &VerifiedBlock,
-        _ancestors: &[VerifiedBlock],
+        _ancestors: &[Option<VerifiedBlock>],
     ) -> ConsensusResult<()> {
         Ok(())
     }
@@ -445,6 +476,48 @@ mod test {
                 Err(ConsensusError::InvalidTransaction(_))
             ));
         }
+
+        // Block with oversized transaction.
+        {
+            let max_transaction_size = context
+                .protocol_config
+                .consensus_max_transaction_size_bytes()
+                as usize;
+            let block = test_block
+                .clone()
+                .set_transactions(vec![Transaction::new(vec![4; max_transaction_size + 1])])
+                .build();
+            let signed_block = SignedBlock::new(block, authority_2_protocol_keypair).unwrap();
+            assert!(matches!(
+                verifier.verify(&signed_block),
+                Err(ConsensusError::OversizedTransaction { size: _, limit: _ })
+            ));
+        }
+
+        // Block with too many transaction bytes.
+        {
+            let max_transaction_size = context
+                .protocol_config
+                .consensus_max_transaction_size_bytes()
+                as usize;
+            let max_transactions_in_block_bytes = context
+                .protocol_config
+                .consensus_max_transactions_in_block_bytes()
+                as usize;
+            let num_transactions = max_transactions_in_block_bytes / max_transaction_size + 1;
+            let block = test_block
+                .clone()
+                .set_transactions(vec![
+                    Transaction::new(vec![4; max_transaction_size]);
+                    num_transactions
+                ])
+                .build();
+            let signed_block = SignedBlock::new(block, authority_2_protocol_keypair).unwrap();
+            assert!(matches!(
+                verifier.verify(&signed_block),
+                Err(ConsensusError::TooManyTransactionBytes { size: _, limit: _ })
+            ));
+        }
     }
 
     #[test]
@@ -459,11 +532,11 @@ mod test {
             let test_block = TestBlock::new(10, i as u32)
                 .set_timestamp_ms(1000 + 100 * i as BlockTimestampMs)
                 .build();
-            ancestor_blocks.push(VerifiedBlock::new_for_test(test_block));
+            ancestor_blocks.push(Some(VerifiedBlock::new_for_test(test_block)));
         }
         let ancestor_refs = ancestor_blocks
             .iter()
-            .map(|block| block.reference())
+            .map(|block| block.as_ref().unwrap().reference())
             .collect::<Vec<_>>();
 
         // Block respecting timestamp invariant.
@@ -493,5 +566,24 @@ mod test {
                 })
             ));
         }
+
+        // Garbage collected ancestors are not checked.
+        {
+            let block = TestBlock::new(11, 0)
+                .set_ancestors(ancestor_refs.clone())
+                .set_timestamp_ms(1250)
+                .build();
+            let verified_block = VerifiedBlock::new_for_test(block);
+            assert!(verifier
+                .check_ancestors(&verified_block, &ancestor_blocks)
+                .is_err());
+
+            let mut gc_ancestor_blocks = ancestor_blocks.clone();
+            gc_ancestor_blocks[2] = None;
+            gc_ancestor_blocks[3] = None;
+            assert!(verifier
+                .check_ancestors(&verified_block, &gc_ancestor_blocks)
+                .is_ok());
+        }
     }
 }
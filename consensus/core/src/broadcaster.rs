@@ -151,7 +151,7 @@ impl Broadcaster {
 
 #[cfg(test)]
 mod test {
-    use std::{collections::BTreeMap, time::Duration};
+    use std::{collections::BTreeMap, ops::RangeInclusive, time::Duration};
 
     use async_trait::async_trait;
     use bytes::Bytes;
@@ -161,6 +161,7 @@ mod test {
     use super::*;
     use crate::{
         block::{BlockRef, TestBlock},
+        commit::CommitIndex,
         core::CoreSignals,
     };
 
@@ -200,6 +201,14 @@ mod test {
         ) -> ConsensusResult<Vec<Bytes>> {
             unimplemented!("Unimplemented")
         }
+
+        async fn fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            _commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            unimplemented!("Unimplemented")
+        }
     }
 
     #[tokio::test(flavor = "current_thread", start_paused = true)]
@@ -0,0 +1,670 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use consensus_config::{AuthorityIndex, Parameters, ProtocolKeyPair};
+use parking_lot::RwLock;
+use tokio::sync::{
+    broadcast,
+    mpsc::{unbounded_channel, UnboundedReceiver},
+};
+use tracing::info;
+
+use crate::{
+    block::{
+        genesis_blocks, timestamp_utc_ms, Block, BlockAPI as _, BlockDigest, BlockRef,
+        BlockTimestampMs, BlockV1, Round, SignedBlock, Transaction, VerifiedBlock,
+    },
+    block_manager::BlockManager,
+    block_verifier::{BlockVerifier, SignedBlockVerifier},
+    commit::{CommitIndex, TrustedCommit},
+    commit_observer::CommitObserver,
+    context::Context,
+    core::{Core, CoreSignals, CoreSignalsReceivers},
+    dag_state::DagState,
+    error::{ConsensusError, ConsensusResult},
+    storage::{mem_store::MemStore, Store},
+    transaction::{NoopTransactionVerifier, TransactionClient, TransactionConsumer},
+    CommitConsumer, CommittedSubDag,
+};
+
+#[path = "tests/byzantine_tests.rs"]
+mod byzantine_tests;
+
+/// Maximum number of times an honest authority fetches missing blocks after receiving blocks.
+const MAX_FETCH_ATTEMPTS: usize = 10;
+
+/// Offset of block timestamps that are too far in the future.
+const FAR_FUTURE_OFFSET: Duration = Duration::from_secs(3600);
+
+/// Adversarial behaviors of a Byzantine authority in `ByzantineHarness`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ByzantineBehavior {
+    /// Proposes two conflicting blocks every round, and sends each of them to a different half
+    /// of the honest authorities.
+    Equivocate,
+    /// Sends its blocks to only one honest authority, and does not serve fetch requests.
+    WithholdBlocks,
+    /// Proposes blocks whose ancestors do not reach a quorum, or do not exist.
+    InvalidAncestors,
+    /// Proposes blocks with timestamps earlier than their ancestors, or too far in the future.
+    InvalidTimestamps,
+    /// Proposes blocks with transactions over the size limits.
+    OversizedTransactions,
+}
+
+/// Runs a local committee where some authorities are replaced by adversarial ones.
+///
+/// Honest authorities run `Core` and verify received blocks like `AuthorityService` and
+/// `Synchronizer`. Authorities are connected by an in-process network, where blocks proposed
+/// in a round are delivered at the start of the next round, and missing blocks are fetched
+/// from peers right away. The harness does not use threads, timers or randomness, so its
+/// results are deterministic including under the simulator.
+///
+/// Blocks are not delayed until their timestamps become current, so adversarial timestamps
+/// should either be in the past or beyond `Parameters::max_forward_time_drift`.
+pub(crate) struct ByzantineHarness {
+    honest: BTreeMap<AuthorityIndex, HonestAuthority>,
+    byzantine: BTreeMap<AuthorityIndex, ByzantineAuthority>,
+    // Blocks sent in the last round, to be delivered in the next round.
+    in_flight: Vec<Message>,
+    round: Round,
+}
+
+/// Liveness metrics of an honest authority.
+#[derive(Debug)]
+pub(crate) struct AuthorityReport {
+    pub(crate) authority: AuthorityIndex,
+    pub(crate) num_commits: usize,
+    pub(crate) last_committed_leader_round: Round,
+    /// Number of blocks from Byzantine authorities accepted into the DAG.
+    pub(crate) accepted_byzantine_blocks: usize,
+    /// Number of blocks rejected, either when received or when accepted into the DAG.
+    pub(crate) rejected_blocks: u64,
+    /// Number of blocks that stay suspended because their ancestors cannot be fetched.
+    pub(crate) missing_blocks: usize,
+}
+
+struct Message {
+    from: AuthorityIndex,
+    to: AuthorityIndex,
+    block: Bytes,
+}
+
+impl ByzantineHarness {
+    /// Creates a committee of `committee_size` authorities with equal stake, where the given
+    /// authorities are Byzantine.
+    pub(crate) fn new(committee_size: usize, byzantine: Vec<(u32, ByzantineBehavior)>) -> Self {
+        let byzantine: BTreeMap<_, _> = byzantine
+            .into_iter()
+            .map(|(index, behavior)| (AuthorityIndex::new_for_test(index), behavior))
+            .collect();
+        let (context, _) = Context::new_for_test(committee_size);
+        let honest_authorities: Vec<_> = context
+            .committee
+            .authorities()
+            .map(|(index, _)| index)
+            .filter(|index| !byzantine.contains_key(index))
+            .collect();
+
+        let honest = honest_authorities
+            .iter()
+            .map(|index| (*index, HonestAuthority::new(committee_size, *index)))
+            .collect();
+        let byzantine = byzantine
+            .into_iter()
+            .map(|(index, behavior)| {
+                (
+                    index,
+                    ByzantineAuthority::new(
+                        committee_size,
+                        index,
+                        behavior,
+                        honest_authorities.clone(),
+                    ),
+                )
+            })
+            .collect();
+
+        Self {
+            honest,
+            byzantine,
+            in_flight: vec![],
+            round: 0,
+        }
+    }
+
+    /// Runs the committee for the given number of rounds.
+    pub(crate) fn run(&mut self, num_rounds: Round) {
+        for _ in 0..num_rounds {
+            self.round += 1;
+            let messages = std::mem::take(&mut self.in_flight);
+            self.deliver(messages);
+
+            let mut messages = self.propose_honest_blocks();
+            for authority in self.byzantine.values_mut() {
+                messages.extend(authority.propose(self.round));
+            }
+            self.in_flight = messages;
+        }
+    }
+
+    /// Checks that commits of all honest authorities are consistent, i.e. one commit sequence
+    /// is a prefix of the other for each pair of honest authorities.
+    pub(crate) fn check_safety(&self) {
+        let commits: BTreeMap<_, _> = self
+            .honest
+            .iter()
+            .map(|(index, authority)| (*index, authority.commits()))
+            .collect();
+        let (longest_index, longest_commits) = commits
+            .iter()
+            .max_by_key(|(_, commits)| commits.len())
+            .expect("There should be honest authorities");
+        for (index, commits) in &commits {
+            for (commit, longest_commit) in commits.iter().zip(longest_commits.iter()) {
+                assert_eq!(
+                    commit, longest_commit,
+                    "Authority {index} has a commit conflicting with authority {longest_index}"
+                );
+            }
+        }
+    }
+
+    /// Returns liveness metrics of honest authorities.
+    pub(crate) fn report(&self) -> Vec<AuthorityReport> {
+        self.honest
+            .values()
+            .map(|authority| authority.report(self.byzantine.keys()))
+            .collect()
+    }
+
+    fn deliver(&mut self, messages: Vec<Message>) {
+        let mut inboxes: BTreeMap<AuthorityIndex, Vec<(AuthorityIndex, Bytes)>> = BTreeMap::new();
+        for message in messages {
+            inboxes
+                .entry(message.to)
+                .or_default()
+                .push((message.from, message.block));
+        }
+
+        for (to, blocks) in inboxes {
+            if let Some(authority) = self.byzantine.get_mut(&to) {
+                authority.receive(blocks);
+                continue;
+            }
+
+            let authority = self.honest.get_mut(&to).unwrap();
+            let verified_blocks = blocks
+                .into_iter()
+                .filter_map(|(from, block)| authority.receive_block(from, block))
+                .collect();
+            authority.add_blocks(verified_blocks);
+
+            // Fetch missing ancestors from peers, similar to the synchronizer.
+            for _ in 0..MAX_FETCH_ATTEMPTS {
+                let missing_blocks = self.honest[&to].core.get_missing_blocks();
+                if missing_blocks.is_empty() {
+                    break;
+                }
+                let fetched_blocks = self.fetch_blocks(to, &missing_blocks);
+                let authority = self.honest.get_mut(&to).unwrap();
+                let verified_blocks: Vec<_> = fetched_blocks
+                    .into_iter()
+                    .filter_map(|block| authority.verify_block(block).ok())
+                    .collect();
+                if verified_blocks.is_empty() {
+                    break;
+                }
+                authority.add_blocks(verified_blocks);
+            }
+        }
+    }
+
+    fn fetch_blocks(
+        &self,
+        requester: AuthorityIndex,
+        block_refs: &BTreeSet<BlockRef>,
+    ) -> Vec<Bytes> {
+        let block_refs: Vec<_> = block_refs.iter().cloned().collect();
+        let mut fetched: Vec<Option<Bytes>> = vec![None; block_refs.len()];
+        let honest_blocks = self
+            .honest
+            .iter()
+            .filter(|(index, _)| **index != requester)
+            .map(|(_, authority)| authority.serve_blocks(&block_refs));
+        let byzantine_blocks = self
+            .byzantine
+            .values()
+            .map(|authority| authority.serve_blocks(&block_refs));
+        for blocks in honest_blocks.chain(byzantine_blocks) {
+            for (slot, block) in fetched.iter_mut().zip(blocks) {
+                if slot.is_none() {
+                    *slot = block;
+                }
+            }
+        }
+        fetched.into_iter().flatten().collect()
+    }
+
+    fn propose_honest_blocks(&mut self) -> Vec<Message> {
+        let authorities: Vec<_> = self
+            .honest
+            .keys()
+            .chain(self.byzantine.keys())
+            .cloned()
+            .collect();
+        let mut messages = vec![];
+        for (from, authority) in self.honest.iter_mut() {
+            for block in authority.propose(self.round) {
+                messages.extend(
+                    authorities
+                        .iter()
+                        .filter(|to| *to != from)
+                        .map(|to| Message {
+                            from: *from,
+                            to: *to,
+                            block: block.serialized().clone(),
+                        }),
+                );
+            }
+        }
+        messages
+    }
+}
+
+struct HonestAuthority {
+    context: Arc<Context>,
+    core: Core,
+    dag_state: Arc<RwLock<DagState>>,
+    store: Arc<MemStore>,
+    block_verifier: Arc<SignedBlockVerifier>,
+    // Receives blocks proposed by Core.
+    block_receiver: broadcast::Receiver<VerifiedBlock>,
+    // Receivers are kept alive, so Core and CommitObserver can send to them.
+    _signal_receivers: CoreSignalsReceivers,
+    _commit_receiver: UnboundedReceiver<CommittedSubDag>,
+    rejected_blocks: u64,
+}
+
+impl HonestAuthority {
+    fn new(committee_size: usize, own_index: AuthorityIndex) -> Self {
+        let (context, mut keys) = Context::new_for_test(committee_size);
+        // Blocks are only proposed when forced, so proposals do not depend on the wall clock.
+        let parameters = Parameters {
+            min_round_delay: Duration::from_secs(3600),
+            ..context.parameters.clone()
+        };
+        let context = Arc::new(
+            context
+                .with_authority_index(own_index)
+                .with_parameters(parameters),
+        );
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let block_verifier = Arc::new(SignedBlockVerifier::new(
+            context.clone(),
+            Arc::new(NoopTransactionVerifier {}),
+        ));
+        let block_manager =
+            BlockManager::new(context.clone(), dag_state.clone(), block_verifier.clone());
+        let (_transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+        let (signals, signal_receivers) = CoreSignals::new();
+        // Subscribe before creating Core, which proposes the first block right away.
+        let block_receiver = signal_receivers.block_broadcast_receiver();
+        let (commit_sender, commit_receiver) = unbounded_channel();
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(commit_sender, 0, 0),
+            dag_state.clone(),
+            store.clone(),
+        );
+        let block_signer = keys.remove(own_index.value()).1;
+        let core = Core::new(
+            context.clone(),
+            transaction_consumer,
+            block_manager,
+            commit_observer,
+            signals,
+            block_signer,
+            dag_state.clone(),
+        );
+
+        Self {
+            context,
+            core,
+            dag_state,
+            store,
+            block_verifier,
+            block_receiver,
+            _signal_receivers: signal_receivers,
+            _commit_receiver: commit_receiver,
+            rejected_blocks: 0,
+        }
+    }
+
+    /// Receives a block sent by a peer, and returns it if it is valid.
+    fn receive_block(&mut self, from: AuthorityIndex, serialized: Bytes) -> Option<VerifiedBlock> {
+        match self.verify_block(serialized) {
+            Ok(block) if block.author() == from => Some(block),
+            Ok(block) => {
+                self.rejected_blocks += 1;
+                info!(
+                    "Block with wrong authority from {from}: {}",
+                    ConsensusError::UnexpectedAuthority(block.author(), from)
+                );
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Verifies a serialized block the same way as `AuthorityService`.
+    fn verify_block(&mut self, serialized: Bytes) -> ConsensusResult<VerifiedBlock> {
+        let result = self.try_verify_block(serialized);
+        if let Err(e) = &result {
+            self.rejected_blocks += 1;
+            info!("Invalid block: {e}");
+        }
+        result
+    }
+
+    fn try_verify_block(&self, serialized: Bytes) -> ConsensusResult<VerifiedBlock> {
+        let signed_block: SignedBlock =
+            bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+        self.block_verifier.verify(&signed_block)?;
+        let verified_block = VerifiedBlock::new_verified(signed_block, serialized);
+        let forward_time_drift = Duration::from_millis(
+            verified_block
+                .timestamp_ms()
+                .saturating_sub(timestamp_utc_ms()),
+        );
+        if forward_time_drift > self.context.parameters.max_forward_time_drift {
+            return Err(ConsensusError::BlockTooFarInFuture {
+                block_timestamp: verified_block.timestamp_ms(),
+                forward_time_drift,
+            });
+        }
+        Ok(verified_block)
+    }
+
+    fn add_blocks(&mut self, blocks: Vec<VerifiedBlock>) {
+        if blocks.is_empty() {
+            return;
+        }
+        self.core
+            .add_blocks(blocks)
+            .unwrap_or_else(|e| panic!("Failed to add blocks to Core: {e}"));
+    }
+
+    /// Proposes a block for the round if possible, and returns all blocks proposed since the
+    /// last call.
+    fn propose(&mut self, round: Round) -> Vec<VerifiedBlock> {
+        self.core
+            .force_new_block(round)
+            .unwrap_or_else(|e| panic!("Failed to propose block: {e}"));
+        let mut blocks = vec![];
+        while let Ok(block) = self.block_receiver.try_recv() {
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    fn serve_blocks(&self, block_refs: &[BlockRef]) -> Vec<Option<Bytes>> {
+        self.dag_state
+            .read()
+            .get_blocks(block_refs)
+            .into_iter()
+            .map(|block| block.map(|block| block.serialized().clone()))
+            .collect()
+    }
+
+    fn commits(&self) -> Vec<TrustedCommit> {
+        self.dag_state.write().flush();
+        self.store
+            .scan_commits(0..CommitIndex::MAX)
+            .expect("Failed to read commits")
+    }
+
+    fn report<'a>(&self, byzantine: impl Iterator<Item = &'a AuthorityIndex>) -> AuthorityReport {
+        let node_metrics = &self.context.metrics.node_metrics;
+        let mut accepted_byzantine_blocks = 0;
+        let mut rejected_blocks = self.rejected_blocks;
+        self.dag_state.write().flush();
+        for authority in byzantine {
+            accepted_byzantine_blocks += self
+                .store
+                .scan_blocks_by_author(*authority, 1)
+                .expect("Failed to read blocks")
+                .len();
+            rejected_blocks += node_metrics
+                .invalid_blocks
+                .with_label_values(&[&authority.to_string(), "accept_block"])
+                .get();
+        }
+        AuthorityReport {
+            authority: self.context.own_index,
+            num_commits: self.commits().len(),
+            last_committed_leader_round: node_metrics.last_committed_leader_round.get() as Round,
+            accepted_byzantine_blocks,
+            rejected_blocks,
+            missing_blocks: self.core.get_missing_blocks().len(),
+        }
+    }
+}
+
+struct ByzantineAuthority {
+    context: Arc<Context>,
+    behavior: ByzantineBehavior,
+    protocol_keypair: ProtocolKeyPair,
+    honest_authorities: Vec<AuthorityIndex>,
+    // Last block of each chain of proposals, to be used as the own ancestor of the next block.
+    // There are multiple chains only when equivocating.
+    last_blocks: Vec<BlockRef>,
+    // Blocks received from honest authorities, by round.
+    honest_blocks: BTreeMap<Round, BTreeMap<AuthorityIndex, BlockRef>>,
+    // All proposed blocks, which can be served to peers.
+    proposed_blocks: BTreeMap<BlockRef, Bytes>,
+}
+
+impl ByzantineAuthority {
+    fn new(
+        committee_size: usize,
+        own_index: AuthorityIndex,
+        behavior: ByzantineBehavior,
+        honest_authorities: Vec<AuthorityIndex>,
+    ) -> Self {
+        let (context, mut keys) = Context::new_for_test(committee_size);
+        let context = Arc::new(context.with_authority_index(own_index));
+        let protocol_keypair = keys.remove(own_index.value()).1;
+
+        let genesis = genesis_blocks(context.clone());
+        let own_genesis = genesis
+            .iter()
+            .find(|block| block.author() == own_index)
+            .expect("Genesis block should exist")
+            .reference();
+        let num_chains = if behavior == ByzantineBehavior::Equivocate {
+            2
+        } else {
+            1
+        };
+        let honest_genesis = genesis
+            .iter()
+            .filter(|block| honest_authorities.contains(&block.author()))
+            .map(|block| (block.author(), block.reference()))
+            .collect();
+
+        Self {
+            context,
+            behavior,
+            protocol_keypair,
+            honest_authorities,
+            last_blocks: vec![own_genesis; num_chains],
+            honest_blocks: BTreeMap::from([(0, honest_genesis)]),
+            proposed_blocks: BTreeMap::new(),
+        }
+    }
+
+    fn receive(&mut self, blocks: Vec<(AuthorityIndex, Bytes)>) {
+        for (from, serialized) in blocks {
+            if !self.honest_authorities.contains(&from) {
+                continue;
+            }
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).expect("Honest blocks should be valid");
+            let block = VerifiedBlock::new_verified(signed_block, serialized);
+            self.honest_blocks
+                .entry(block.round())
+                .or_default()
+                .insert(block.author(), block.reference());
+        }
+    }
+
+    /// Proposes blocks for the round, and returns the recipients of each block.
+    fn propose(&mut self, round: Round) -> Vec<Message> {
+        let parents: Vec<_> = self
+            .honest_blocks
+            .get(&(round - 1))
+            .map(|blocks| blocks.values().cloned().collect())
+            .unwrap_or_default();
+        let now = timestamp_utc_ms();
+        let mut messages = vec![];
+        match self.behavior {
+            ByzantineBehavior::Equivocate => {
+                for chain in 0..self.last_blocks.len() {
+                    // Blocks of each chain differ in their transactions.
+                    let (block_ref, block) = self.sign_block(
+                        round,
+                        now,
+                        self.last_blocks[chain],
+                        parents.clone(),
+                        vec![Transaction::new(vec![chain as u8])],
+                    );
+                    self.last_blocks[chain] = block_ref;
+                    let recipients = self
+                        .honest_authorities
+                        .iter()
+                        .enumerate()
+                        .filter(|(position, _)| position % self.last_blocks.len() == chain)
+                        .map(|(_, to)| *to)
+                        .collect();
+                    messages.extend(self.messages(recipients, block));
+                }
+            }
+            ByzantineBehavior::WithholdBlocks => {
+                let (block_ref, block) =
+                    self.sign_block(round, now, self.last_blocks[0], parents, vec![]);
+                self.last_blocks[0] = block_ref;
+                messages.extend(self.messages(vec![self.honest_authorities[0]], block));
+            }
+            ByzantineBehavior::InvalidAncestors => {
+                let ancestors = if round % 2 == 1 {
+                    // Only the own ancestor, which does not reach a quorum.
+                    vec![]
+                } else {
+                    // Ancestors that do not exist.
+                    parents
+                        .iter()
+                        .map(|parent| BlockRef::new(parent.round, parent.author, BlockDigest::MAX))
+                        .collect()
+                };
+                // None of the blocks can be accepted, so they all link to the genesis block.
+                let (_, block) =
+                    self.sign_block(round, now, self.last_blocks[0], ancestors, vec![]);
+                messages.extend(self.messages(self.honest_authorities.clone(), block));
+            }
+            ByzantineBehavior::InvalidTimestamps => {
+                // Only the first block is valid, so later blocks link to it.
+                let timestamp_ms = if round == 1 {
+                    now
+                } else if round % 2 == 0 {
+                    BlockTimestampMs::MIN
+                } else {
+                    now + FAR_FUTURE_OFFSET.as_millis() as BlockTimestampMs
+                };
+                let (block_ref, block) =
+                    self.sign_block(round, timestamp_ms, self.last_blocks[0], parents, vec![]);
+                if round == 1 {
+                    self.last_blocks[0] = block_ref;
+                }
+                messages.extend(self.messages(self.honest_authorities.clone(), block));
+            }
+            ByzantineBehavior::OversizedTransactions => {
+                let protocol_config = &self.context.protocol_config;
+                let max_transaction_size =
+                    protocol_config.consensus_max_transaction_size_bytes() as usize;
+                let transactions = if round % 2 == 1 {
+                    vec![Transaction::new(vec![0; max_transaction_size + 1])]
+                } else {
+                    let max_transactions_in_block_bytes =
+                        protocol_config.consensus_max_transactions_in_block_bytes() as usize;
+                    vec![
+                        Transaction::new(vec![0; max_transaction_size]);
+                        max_transactions_in_block_bytes / max_transaction_size + 1
+                    ]
+                };
+                let (_, block) =
+                    self.sign_block(round, now, self.last_blocks[0], parents, transactions);
+                messages.extend(self.messages(self.honest_authorities.clone(), block));
+            }
+        }
+        messages
+    }
+
+    fn sign_block(
+        &mut self,
+        round: Round,
+        timestamp_ms: BlockTimestampMs,
+        own_ancestor: BlockRef,
+        ancestors: Vec<BlockRef>,
+        transactions: Vec<Transaction>,
+    ) -> (BlockRef, Bytes) {
+        let block = Block::V1(BlockV1::new(
+            self.context.committee.epoch(),
+            round,
+            self.context.own_index,
+            timestamp_ms,
+            iter::once(own_ancestor).chain(ancestors).collect(),
+            transactions,
+            vec![],
+        ));
+        let signed_block =
+            SignedBlock::new(block, &self.protocol_keypair).expect("Block signing failed.");
+        let serialized = signed_block
+            .serialize()
+            .expect("Block serialization failed.");
+        let block_ref = VerifiedBlock::new_verified(signed_block, serialized.clone()).reference();
+        self.proposed_blocks.insert(block_ref, serialized.clone());
+        (block_ref, serialized)
+    }
+
+    fn messages(&self, recipients: Vec<AuthorityIndex>, block: Bytes) -> Vec<Message> {
+        recipients
+            .into_iter()
+            .map(|to| Message {
+                from: self.context.own_index,
+                to,
+                block: block.clone(),
+            })
+            .collect()
+    }
+
+    fn serve_blocks(&self, block_refs: &[BlockRef]) -> Vec<Option<Bytes>> {
+        if self.behavior == ByzantineBehavior::WithholdBlocks {
+            return vec![None; block_refs.len()];
+        }
+        block_refs
+            .iter()
+            .map(|block_ref| self.proposed_blocks.get(block_ref).cloned())
+            .collect()
+    }
+}
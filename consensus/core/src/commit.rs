@@ -5,7 +5,10 @@ use std::{
     fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     ops::Deref,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
 };
 
 use bytes::Bytes;
@@ -348,6 +351,9 @@ pub struct CommitConsumer {
     // First commit in the replayed sequence will have index last_processed_commit_index + 1.
     // Set 0 to replay from the start (as generated commit sequence starts at index = 1).
     pub last_processed_commit_index: CommitIndex,
+    // Tracks the commits handled by the consumer after startup, so their data can be
+    // removed from storage once they no longer need to be replayed.
+    monitor: Arc<CommitConsumerMonitor>,
 }
 
 impl CommitConsumer {
@@ -360,8 +366,39 @@ impl CommitConsumer {
             sender,
             last_processed_commit_round,
             last_processed_commit_index,
+            monitor: Arc::new(CommitConsumerMonitor::new(last_processed_commit_index)),
         }
     }
+
+    pub fn monitor(&self) -> Arc<CommitConsumerMonitor> {
+        self.monitor.clone()
+    }
+}
+
+/// Shared between consensus and the consumer of committed sub-dags. The consumer reports the
+/// highest commit it has durably handled, and consensus keeps the commits above it in storage.
+pub struct CommitConsumerMonitor {
+    highest_handled_commit: AtomicU32,
+}
+
+impl CommitConsumerMonitor {
+    pub(crate) fn new(last_handled_commit: CommitIndex) -> Self {
+        Self {
+            highest_handled_commit: AtomicU32::new(last_handled_commit),
+        }
+    }
+
+    /// Index of the highest commit handled by the consumer.
+    pub fn highest_handled_commit(&self) -> CommitIndex {
+        self.highest_handled_commit.load(Ordering::Acquire)
+    }
+
+    /// Called by the consumer after the commit with the given index has been handled, and will
+    /// not need to be replayed after restarts.
+    pub fn set_highest_handled_commit(&self, commit_index: CommitIndex) {
+        self.highest_handled_commit
+            .fetch_max(commit_index, Ordering::AcqRel);
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -0,0 +1,755 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeSet, ops::RangeInclusive, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use consensus_config::AuthorityIndex;
+use mysten_metrics::monitored_scope;
+use parking_lot::{Mutex, RwLock};
+#[cfg(not(test))]
+use rand::{rngs::ThreadRng, seq::SliceRandom};
+use tokio::{
+    task::JoinSet,
+    time::{interval, timeout, MissedTickBehavior},
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    block::{BlockAPI, BlockRef, SignedBlock, VerifiedBlock},
+    block_verifier::BlockVerifier,
+    commit::{Commit, CommitAPI as _, CommitDigest, CommitIndex, TrustedCommit},
+    context::Context,
+    core_thread::CoreThreadDispatcher,
+    dag_state::DagState,
+    error::{ConsensusError, ConsensusResult},
+    network::NetworkClient,
+    stake_aggregator::{QuorumThreshold, StakeAggregator},
+    synchronizer::MAX_FETCH_BLOCKS_PER_REQUEST,
+};
+
+// How often to check whether the local authority has fallen behind the quorum.
+const COMMIT_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+const FETCH_COMMITS_TIMEOUT: Duration = Duration::from_secs(10);
+
+const FETCH_BLOCKS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tracks the highest commit index voted by each authority, from the commit votes in blocks
+/// received from peers. This tells how far the rest of the network has committed.
+pub(crate) struct CommitVoteMonitor {
+    context: Arc<Context>,
+    // Highest commit index voted by each authority. Vec index is the AuthorityIndex.
+    highest_voted_commits: Mutex<Vec<CommitIndex>>,
+}
+
+impl CommitVoteMonitor {
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        let highest_voted_commits = Mutex::new(vec![0; context.committee.size()]);
+        Self {
+            context,
+            highest_voted_commits,
+        }
+    }
+
+    /// Keeps track of the highest commit voted by the author of the block.
+    pub(crate) fn observe_block(&self, block: &VerifiedBlock) {
+        let Some(highest_vote) = block.commit_votes().iter().map(|vote| vote.index).max() else {
+            return;
+        };
+        let mut highest_voted_commits = self.highest_voted_commits.lock();
+        let highest = &mut highest_voted_commits[block.author()];
+        *highest = (*highest).max(highest_vote);
+    }
+
+    /// Returns the highest commit index that a quorum of authorities have voted for, or beyond.
+    pub(crate) fn quorum_commit_index(&self) -> CommitIndex {
+        let highest_voted_commits = self.highest_voted_commits.lock();
+        let mut commits_by_authority = highest_voted_commits
+            .iter()
+            .enumerate()
+            .map(|(index, commit_index)| {
+                let authority_index = self.context.committee.to_authority_index(index).unwrap();
+                (*commit_index, authority_index)
+            })
+            .collect::<Vec<_>>();
+        commits_by_authority.sort_by(|a, b| b.cmp(a));
+
+        let mut stake_aggregator = StakeAggregator::<QuorumThreshold>::new();
+        for (commit_index, authority_index) in commits_by_authority {
+            if stake_aggregator.add(authority_index, &self.context.committee) {
+                return commit_index;
+            }
+        }
+        0
+    }
+}
+
+pub(crate) struct CommitSyncerHandle {
+    tasks: Mutex<JoinSet<()>>,
+}
+
+impl CommitSyncerHandle {
+    pub(crate) async fn stop(&self) {
+        let mut tasks = self.tasks.lock();
+        tasks.abort_all();
+    }
+}
+
+/// CommitSyncer lets an authority that has fallen far behind catch up with the rest of the
+/// network, without fetching and accepting the whole DAG block by block.
+///
+/// It periodically compares the local last commit with the commits voted by a quorum of
+/// authorities. When the gap is at least a batch of commits, it fetches the next batch of
+/// commits from a peer, together with the blocks voting for the last commit of the batch.
+/// Votes from a quorum certify the last commit, and the earlier commits are certified by
+/// being chained to it through their digests. The committed blocks are then fetched, and the
+/// commits are applied via Core without proposing blocks.
+pub(crate) struct CommitSyncer<C: NetworkClient, V: BlockVerifier, D: CoreThreadDispatcher> {
+    context: Arc<Context>,
+    dag_state: Arc<RwLock<DagState>>,
+    commit_vote_monitor: Arc<CommitVoteMonitor>,
+    network_client: Arc<C>,
+    block_verifier: Arc<V>,
+    core_dispatcher: Arc<D>,
+}
+
+impl<C: NetworkClient, V: BlockVerifier, D: CoreThreadDispatcher> CommitSyncer<C, V, D> {
+    pub(crate) fn start(
+        context: Arc<Context>,
+        dag_state: Arc<RwLock<DagState>>,
+        commit_vote_monitor: Arc<CommitVoteMonitor>,
+        network_client: Arc<C>,
+        block_verifier: Arc<V>,
+        core_dispatcher: Arc<D>,
+    ) -> Arc<CommitSyncerHandle> {
+        let commit_syncer = Self {
+            context,
+            dag_state,
+            commit_vote_monitor,
+            network_client,
+            block_verifier,
+            core_dispatcher,
+        };
+        let mut tasks = JoinSet::new();
+        tasks.spawn(commit_syncer.run());
+        Arc::new(CommitSyncerHandle {
+            tasks: Mutex::new(tasks),
+        })
+    }
+
+    async fn run(self) {
+        let mut sync_interval = interval(COMMIT_SYNC_INTERVAL);
+        sync_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            sync_interval.tick().await;
+            match self.try_sync().await {
+                Ok(()) => {}
+                Err(ConsensusError::Shutdown) => {
+                    info!("Core is shutting down, commit syncer is shutting down.");
+                    return;
+                }
+                Err(err) => warn!("Error while syncing commits: {err}"),
+            }
+        }
+    }
+
+    /// Fetches and applies batches of certified commits, for as long as the local authority is
+    /// behind the quorum by at least a batch.
+    async fn try_sync(&self) -> ConsensusResult<()> {
+        let _scope = monitored_scope("CommitSyncer::try_sync");
+        let batch_size = self.context.parameters.commit_sync_batch_size.max(1);
+
+        loop {
+            let quorum_commit_index = self.commit_vote_monitor.quorum_commit_index();
+            self.context
+                .metrics
+                .node_metrics
+                .commit_sync_quorum_index
+                .set(quorum_commit_index as i64);
+
+            let (local_commit_index, local_commit_digest) = {
+                let dag_state = self.dag_state.read();
+                (
+                    dag_state.last_commit_index(),
+                    dag_state.last_commit_digest(),
+                )
+            };
+            // Smaller gaps are closed by block synchronization and the regular commit rule.
+            if quorum_commit_index < local_commit_index.saturating_add(batch_size) {
+                return Ok(());
+            }
+
+            let commit_range = local_commit_index + 1..=local_commit_index + batch_size;
+            let Some((commits, blocks)) = self
+                .fetch_from_peers(commit_range.clone(), local_commit_digest)
+                .await
+            else {
+                warn!("Failed to fetch commits {commit_range:?} from any peer");
+                return Ok(());
+            };
+
+            let missing_blocks = self
+                .core_dispatcher
+                .add_certified_commits(commits, blocks)
+                .await
+                .map_err(|_| ConsensusError::Shutdown)?;
+            if !missing_blocks.is_empty() {
+                debug!(
+                    "{} blocks are missing after applying certified commits",
+                    missing_blocks.len()
+                );
+            }
+
+            if self.dag_state.read().last_commit_index() <= local_commit_index {
+                warn!("No progress has been made applying certified commits {commit_range:?}");
+                return Ok(());
+            }
+        }
+    }
+
+    /// Tries to fetch the certified commits in the range and their committed blocks from peers,
+    /// one peer at a time until one succeeds.
+    async fn fetch_from_peers(
+        &self,
+        commit_range: RangeInclusive<CommitIndex>,
+        previous_digest: CommitDigest,
+    ) -> Option<(Vec<TrustedCommit>, Vec<VerifiedBlock>)> {
+        #[allow(unused_mut)]
+        let mut peers = self
+            .context
+            .committee
+            .authorities()
+            .filter_map(|(peer, _)| (peer != self.context.own_index).then_some(peer))
+            .collect::<Vec<_>>();
+
+        // TODO: probably inject the RNG to allow unit testing - this is a work around for now.
+        cfg_if::cfg_if! {
+            if #[cfg(not(test))] {
+                // Shuffle the peers
+                peers.shuffle(&mut ThreadRng::default());
+            }
+        }
+
+        for peer in peers {
+            match self
+                .fetch_certified_commits(peer, commit_range.clone(), previous_digest)
+                .await
+            {
+                Ok((commits, blocks)) => {
+                    self.context
+                        .metrics
+                        .node_metrics
+                        .commit_sync_fetched_commits
+                        .with_label_values(&[&peer.to_string()])
+                        .inc_by(commits.len() as u64);
+                    return Some((commits, blocks));
+                }
+                Err(err) => {
+                    self.context
+                        .metrics
+                        .node_metrics
+                        .commit_sync_fetch_failures
+                        .with_label_values(&[&peer.to_string()])
+                        .inc();
+                    warn!("Failed to fetch commits {commit_range:?} from peer {peer}: {err}");
+                }
+            }
+        }
+        None
+    }
+
+    async fn fetch_certified_commits(
+        &self,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+        previous_digest: CommitDigest,
+    ) -> ConsensusResult<(Vec<TrustedCommit>, Vec<VerifiedBlock>)> {
+        let (serialized_commits, serialized_certifier_blocks) = timeout(
+            FETCH_COMMITS_TIMEOUT,
+            self.network_client
+                .fetch_commits(peer, commit_range.clone()),
+        )
+        .await
+        .map_err(|_| {
+            ConsensusError::NetworkError(format!("Fetching commits from {peer} timed out"))
+        })??;
+
+        let commits = Self::verify_commits(
+            &self.context,
+            self.block_verifier.as_ref(),
+            peer,
+            commit_range,
+            previous_digest,
+            serialized_commits,
+            serialized_certifier_blocks,
+        )?;
+        let blocks = self.fetch_committed_blocks(peer, &commits).await?;
+        Ok((commits, blocks))
+    }
+
+    /// Verifies that the fetched commits follow the local last commit with `previous_digest`,
+    /// and that the last of them is voted by a quorum of the certifier blocks.
+    fn verify_commits(
+        context: &Context,
+        block_verifier: &V,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+        previous_digest: CommitDigest,
+        serialized_commits: Vec<Bytes>,
+        serialized_certifier_blocks: Vec<Bytes>,
+    ) -> ConsensusResult<Vec<TrustedCommit>> {
+        let mut commits = Vec::new();
+        let mut expected_index = *commit_range.start();
+        let mut expected_previous_digest = previous_digest;
+        for serialized in serialized_commits {
+            let commit: Commit =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedCommit)?;
+            // The commits only become trusted once the chain is certified below.
+            let commit = TrustedCommit::new_trusted(commit, serialized);
+            if commit.index() != expected_index
+                || commit.previous_digest() != expected_previous_digest
+                || !commit_range.contains(&commit.index())
+            {
+                return Err(ConsensusError::UnexpectedCommitSequence {
+                    peer,
+                    index: commit.index(),
+                });
+            }
+            expected_index += 1;
+            expected_previous_digest = commit.digest();
+            commits.push(commit);
+        }
+
+        let Some(last_commit) = commits.last() else {
+            return Err(ConsensusError::NoCommitReceived(peer));
+        };
+        let last_commit_ref = last_commit.reference();
+
+        let mut stake_aggregator = StakeAggregator::<QuorumThreshold>::new();
+        for serialized_block in serialized_certifier_blocks {
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized_block).map_err(ConsensusError::MalformedBlock)?;
+            block_verifier.verify(&signed_block)?;
+            let block = VerifiedBlock::new_verified(signed_block, serialized_block);
+            if !block.commit_votes().contains(&last_commit_ref) {
+                return Err(ConsensusError::InvalidCommitCertifier {
+                    peer,
+                    block_ref: block.reference(),
+                    index: last_commit_ref.index,
+                });
+            }
+            stake_aggregator.add(block.author(), &context.committee);
+        }
+        if !stake_aggregator.reached_threshold(&context.committee) {
+            return Err(ConsensusError::NotEnoughCommitVotes {
+                peer,
+                index: last_commit_ref.index,
+                stake: stake_aggregator.stake(),
+                quorum: context.committee.quorum_threshold(),
+            });
+        }
+
+        Ok(commits)
+    }
+
+    /// Fetches the blocks of the commits that are not available locally.
+    async fn fetch_committed_blocks(
+        &self,
+        peer: AuthorityIndex,
+        commits: &[TrustedCommit],
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let block_refs = commits
+            .iter()
+            .flat_map(|commit| commit.blocks().iter().copied())
+            .collect::<Vec<_>>();
+        let exists = self.dag_state.read().contains_blocks(block_refs.clone());
+        let missing_block_refs = block_refs
+            .into_iter()
+            .zip(exists)
+            .filter_map(|(block_ref, exists)| (!exists).then_some(block_ref))
+            .collect::<Vec<_>>();
+
+        let mut blocks = Vec::new();
+        for chunk in missing_block_refs.chunks(MAX_FETCH_BLOCKS_PER_REQUEST) {
+            let serialized_blocks = timeout(
+                FETCH_BLOCKS_TIMEOUT,
+                self.network_client.fetch_blocks(peer, chunk.to_vec()),
+            )
+            .await
+            .map_err(|_| {
+                ConsensusError::NetworkError(format!("Fetching blocks from {peer} timed out"))
+            })??;
+
+            let mut requested_block_refs = chunk.iter().copied().collect::<BTreeSet<BlockRef>>();
+            for serialized_block in serialized_blocks {
+                let signed_block: SignedBlock =
+                    bcs::from_bytes(&serialized_block).map_err(ConsensusError::MalformedBlock)?;
+                self.block_verifier.verify(&signed_block)?;
+                let block = VerifiedBlock::new_verified(signed_block, serialized_block);
+                // The peer should only respond with the committed blocks that were requested.
+                if !requested_block_refs.remove(&block.reference()) {
+                    return Err(ConsensusError::UnexpectedFetchedBlock {
+                        index: peer,
+                        block_ref: block.reference(),
+                    });
+                }
+                blocks.push(block);
+            }
+            if !requested_block_refs.is_empty() {
+                return Err(ConsensusError::MissingCommittedBlocks {
+                    peer,
+                    missing: requested_block_refs.len(),
+                });
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{
+        block::{Round, TestBlock},
+        block_verifier::NoopBlockVerifier,
+        commit::CommitRef,
+        core_thread::CoreError,
+        storage::mem_store::MemStore,
+    };
+
+    #[derive(Default)]
+    struct MockCoreThreadDispatcher {
+        certified_commits: tokio::sync::Mutex<Vec<(Vec<TrustedCommit>, Vec<VerifiedBlock>)>>,
+    }
+
+    #[async_trait]
+    impl CoreThreadDispatcher for MockCoreThreadDispatcher {
+        async fn add_blocks(
+            &self,
+            _blocks: Vec<VerifiedBlock>,
+        ) -> Result<BTreeSet<BlockRef>, CoreError> {
+            todo!()
+        }
+
+        async fn force_new_block(&self, _round: Round) -> Result<(), CoreError> {
+            todo!()
+        }
+
+        async fn get_missing_blocks(&self) -> Result<BTreeSet<BlockRef>, CoreError> {
+            todo!()
+        }
+
+        async fn add_certified_commits(
+            &self,
+            commits: Vec<TrustedCommit>,
+            blocks: Vec<VerifiedBlock>,
+        ) -> Result<BTreeSet<BlockRef>, CoreError> {
+            self.certified_commits.lock().await.push((commits, blocks));
+            Ok(BTreeSet::new())
+        }
+    }
+
+    /// Serves the same commits, certifier blocks and committed blocks from every peer, and
+    /// counts the fetch commits requests.
+    #[derive(Default)]
+    struct MockNetworkClient {
+        commits: Vec<TrustedCommit>,
+        certifier_blocks: Vec<VerifiedBlock>,
+        blocks: BTreeMap<BlockRef, VerifiedBlock>,
+        fetch_commits_requests: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl NetworkClient for MockNetworkClient {
+        async fn send_block(
+            &self,
+            _peer: AuthorityIndex,
+            _serialized_block: &Bytes,
+        ) -> ConsensusResult<()> {
+            todo!()
+        }
+
+        async fn fetch_blocks(
+            &self,
+            _peer: AuthorityIndex,
+            block_refs: Vec<BlockRef>,
+        ) -> ConsensusResult<Vec<Bytes>> {
+            Ok(block_refs
+                .iter()
+                .filter_map(|block_ref| self.blocks.get(block_ref))
+                .map(|block| block.serialized().clone())
+                .collect())
+        }
+
+        async fn fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            self.fetch_commits_requests.fetch_add(1, Ordering::SeqCst);
+            let commits = self
+                .commits
+                .iter()
+                .filter(|commit| commit_range.contains(&commit.index()))
+                .map(|commit| commit.serialized().clone())
+                .collect();
+            let certifier_blocks = self
+                .certifier_blocks
+                .iter()
+                .map(|block| block.serialized().clone())
+                .collect();
+            Ok((commits, certifier_blocks))
+        }
+    }
+
+    /// Creates a chain of commits with the given indices, each committing its leader block of
+    /// the same round from authority 0.
+    fn create_commits(
+        indices: RangeInclusive<CommitIndex>,
+    ) -> (Vec<TrustedCommit>, Vec<VerifiedBlock>) {
+        let mut commits = vec![];
+        let mut blocks = vec![];
+        let mut previous_digest = CommitDigest::MIN;
+        for index in indices {
+            let block = VerifiedBlock::new_for_test(TestBlock::new(index, 0).build());
+            let commit = TrustedCommit::new_for_test(
+                index,
+                previous_digest,
+                block.reference(),
+                vec![block.reference()],
+            );
+            previous_digest = commit.digest();
+            commits.push(commit);
+            blocks.push(block);
+        }
+        (commits, blocks)
+    }
+
+    fn create_certifier_blocks(
+        round: Round,
+        authors: &[u32],
+        commit_ref: CommitRef,
+    ) -> Vec<VerifiedBlock> {
+        authors
+            .iter()
+            .map(|author| {
+                VerifiedBlock::new_for_test(
+                    TestBlock::new(round, *author)
+                        .set_commit_votes(vec![commit_ref])
+                        .build(),
+                )
+            })
+            .collect()
+    }
+
+    fn serialize_commits(commits: &[TrustedCommit]) -> Vec<Bytes> {
+        commits
+            .iter()
+            .map(|commit| commit.serialized().clone())
+            .collect()
+    }
+
+    fn serialize_blocks(blocks: &[VerifiedBlock]) -> Vec<Bytes> {
+        blocks
+            .iter()
+            .map(|block| block.serialized().clone())
+            .collect()
+    }
+
+    type TestCommitSyncer =
+        CommitSyncer<MockNetworkClient, NoopBlockVerifier, MockCoreThreadDispatcher>;
+
+    #[tokio::test]
+    async fn test_commit_vote_monitor() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let monitor = CommitVoteMonitor::new(context);
+        assert_eq!(monitor.quorum_commit_index(), 0);
+
+        let commit_ref = |index| CommitRef {
+            index,
+            digest: CommitDigest::MIN,
+        };
+        for (author, votes) in [(0, vec![8, 10]), (1, vec![20]), (2, vec![5])] {
+            monitor.observe_block(&VerifiedBlock::new_for_test(
+                TestBlock::new(1, author)
+                    .set_commit_votes(votes.into_iter().map(commit_ref).collect())
+                    .build(),
+            ));
+        }
+        // Authorities 1, 0 and 2 form a quorum with their highest votes.
+        assert_eq!(monitor.quorum_commit_index(), 5);
+
+        // Lower votes and blocks without votes do not regress the highest votes.
+        monitor.observe_block(&VerifiedBlock::new_for_test(
+            TestBlock::new(2, 1)
+                .set_commit_votes(vec![commit_ref(3)])
+                .build(),
+        ));
+        monitor.observe_block(&VerifiedBlock::new_for_test(TestBlock::new(2, 0).build()));
+        assert_eq!(monitor.quorum_commit_index(), 5);
+
+        monitor.observe_block(&VerifiedBlock::new_for_test(
+            TestBlock::new(2, 3)
+                .set_commit_votes(vec![commit_ref(30)])
+                .build(),
+        ));
+        assert_eq!(monitor.quorum_commit_index(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_verify_commits() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let peer = AuthorityIndex::new_for_test(1);
+        let (commits, _blocks) = create_commits(1..=5);
+        let last_commit_ref = commits.last().unwrap().reference();
+        let verify = |range: RangeInclusive<CommitIndex>,
+                      previous_digest: CommitDigest,
+                      commits: &[TrustedCommit],
+                      certifier_blocks: &[VerifiedBlock]| {
+            TestCommitSyncer::verify_commits(
+                &context,
+                &NoopBlockVerifier,
+                peer,
+                range,
+                previous_digest,
+                serialize_commits(commits),
+                serialize_blocks(certifier_blocks),
+            )
+        };
+
+        // Commits certified by a quorum are verified.
+        let certifier_blocks = create_certifier_blocks(7, &[0, 2, 3], last_commit_ref);
+        let verified = verify(1..=10, CommitDigest::MIN, &commits, &certifier_blocks).unwrap();
+        assert_eq!(verified, commits);
+
+        // Commits starting after the local last commit are verified too.
+        let verified = verify(
+            3..=10,
+            commits[1].digest(),
+            &commits[2..],
+            &certifier_blocks,
+        )
+        .unwrap();
+        assert_eq!(verified, commits[2..].to_vec());
+
+        // Commits not following the local last commit are rejected.
+        let err = verify(
+            3..=10,
+            commits[0].digest(),
+            &commits[2..],
+            &certifier_blocks,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConsensusError::UnexpectedCommitSequence { index: 3, .. }
+        ));
+
+        // Gaps in the commits are rejected.
+        let gapped = [&commits[..2], &commits[3..]].concat();
+        let err = verify(1..=10, CommitDigest::MIN, &gapped, &certifier_blocks).unwrap_err();
+        assert!(matches!(
+            err,
+            ConsensusError::UnexpectedCommitSequence { index: 4, .. }
+        ));
+
+        // Commits outside of the requested range are rejected.
+        let err = verify(1..=3, CommitDigest::MIN, &commits, &certifier_blocks).unwrap_err();
+        assert!(matches!(
+            err,
+            ConsensusError::UnexpectedCommitSequence { index: 4, .. }
+        ));
+
+        // No commit is rejected.
+        let err = verify(1..=10, CommitDigest::MIN, &[], &certifier_blocks).unwrap_err();
+        assert!(matches!(err, ConsensusError::NoCommitReceived(_)));
+
+        // Votes below quorum are rejected, including duplicated votes from the same authority.
+        let mut certifier_blocks = create_certifier_blocks(7, &[0, 2], last_commit_ref);
+        certifier_blocks.extend(create_certifier_blocks(8, &[2], last_commit_ref));
+        let err = verify(1..=10, CommitDigest::MIN, &commits, &certifier_blocks).unwrap_err();
+        assert!(matches!(
+            err,
+            ConsensusError::NotEnoughCommitVotes {
+                index: 5,
+                stake: 2,
+                ..
+            }
+        ));
+
+        // Certifier blocks not voting for the last commit are rejected.
+        let mut certifier_blocks = create_certifier_blocks(7, &[0, 2, 3], last_commit_ref);
+        certifier_blocks.extend(create_certifier_blocks(7, &[1], commits[3].reference()));
+        let err = verify(1..=10, CommitDigest::MIN, &commits, &certifier_blocks).unwrap_err();
+        assert!(matches!(
+            err,
+            ConsensusError::InvalidCommitCertifier { index: 5, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_commit_syncer_fetches_certified_commits() {
+        let mut context = Context::new_for_test(4).0;
+        context.parameters.commit_sync_batch_size = 5;
+        let context = Arc::new(context);
+        let dag_state = Arc::new(RwLock::new(DagState::new(
+            context.clone(),
+            Arc::new(MemStore::new()),
+        )));
+        let commit_vote_monitor = Arc::new(CommitVoteMonitor::new(context.clone()));
+
+        let (commits, blocks) = create_commits(1..=5);
+        let last_commit_ref = commits.last().unwrap().reference();
+        let certifier_blocks = create_certifier_blocks(7, &[1, 2, 3], last_commit_ref);
+        let network_client = Arc::new(MockNetworkClient {
+            commits: commits.clone(),
+            certifier_blocks: certifier_blocks.clone(),
+            blocks: blocks
+                .iter()
+                .map(|block| (block.reference(), block.clone()))
+                .collect(),
+            ..Default::default()
+        });
+        let core_dispatcher = Arc::new(MockCoreThreadDispatcher::default());
+        let commit_syncer = TestCommitSyncer {
+            context,
+            dag_state,
+            commit_vote_monitor: commit_vote_monitor.clone(),
+            network_client: network_client.clone(),
+            block_verifier: Arc::new(NoopBlockVerifier),
+            core_dispatcher: core_dispatcher.clone(),
+        };
+
+        // Nothing is fetched while the quorum is less than a batch ahead.
+        for block in &certifier_blocks[..2] {
+            commit_vote_monitor.observe_block(block);
+        }
+        commit_syncer.try_sync().await.unwrap();
+        assert_eq!(
+            network_client.fetch_commits_requests.load(Ordering::SeqCst),
+            0
+        );
+
+        // Once a quorum has voted for a batch of commits, they are fetched and sent to Core.
+        // Since the mock Core does not apply them, the syncer stops after the first attempt.
+        commit_vote_monitor.observe_block(&certifier_blocks[2]);
+        commit_syncer.try_sync().await.unwrap();
+        assert_eq!(
+            network_client.fetch_commits_requests.load(Ordering::SeqCst),
+            1
+        );
+        let certified_commits = core_dispatcher.certified_commits.lock().await;
+        assert_eq!(certified_commits.len(), 1);
+        assert_eq!(certified_commits[0].0, commits);
+        assert_eq!(certified_commits[0].1, blocks);
+    }
+}
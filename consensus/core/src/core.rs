@@ -20,10 +20,12 @@ use crate::{
         Slot, VerifiedBlock,
     },
     block_manager::BlockManager,
+    commit::{CommitAPI as _, TrustedCommit},
     commit_observer::CommitObserver,
     context::Context,
     dag_state::DagState,
     error::{ConsensusError, ConsensusResult},
+    leader_schedule::LeaderSchedule,
     threshold_clock::ThresholdClock,
     transaction::TransactionConsumer,
     universal_committer::{
@@ -50,6 +52,9 @@ pub(crate) struct Core {
     block_manager: BlockManager,
     /// Used to make commit decisions for leader blocks in the dag.
     committer: UniversalCommitter,
+    /// The leader schedule used by the committer, updated from reputation scores as commits
+    /// are made.
+    leader_schedule: LeaderSchedule,
     /// The last produced block
     last_proposed_block: VerifiedBlock,
     /// The blocks of the last included ancestors per authority. This vector is basically used as a
@@ -84,10 +89,16 @@ impl Core {
     ) -> Self {
         let last_decided_leader = dag_state.read().last_commit_leader();
 
-        let committer = UniversalCommitterBuilder::new(context.clone(), dag_state.clone())
-            .with_number_of_leaders(NUM_LEADERS_PER_ROUND)
-            .with_pipeline(true)
-            .build();
+        let leader_schedule = LeaderSchedule::from_store(context.clone(), dag_state.clone());
+
+        let committer = UniversalCommitterBuilder::new(
+            context.clone(),
+            leader_schedule.clone(),
+            dag_state.clone(),
+        )
+        .with_number_of_leaders(NUM_LEADERS_PER_ROUND)
+        .with_pipeline(true)
+        .build();
 
         // Recover the last proposed block
         let last_proposed_block = dag_state
@@ -115,6 +126,7 @@ impl Core {
             last_included_ancestors,
             block_manager,
             committer,
+            leader_schedule,
             last_decided_leader,
             commit_observer,
             signals,
@@ -292,24 +304,143 @@ impl Core {
 
     /// Runs commit rule to attempt to commit additional blocks from the DAG.
     fn try_commit(&mut self) -> ConsensusResult<Vec<CommittedSubDag>> {
-        // TODO: Add optimization to abort early without quorum for a round.
-        let sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+        let mut committed_subdags = Vec::new();
+        // The leader schedule can only change at a fixed commit index. So leaders are committed
+        // up to that index, then the schedule is updated and the remaining leaders are decided
+        // again with the updated schedule.
+        loop {
+            let mut commits_until_update = self
+                .leader_schedule
+                .commits_until_leader_schedule_update(self.dag_state.clone());
+            if commits_until_update == 0 {
+                self.leader_schedule
+                    .update_leader_schedule(self.dag_state.clone());
+                commits_until_update = self
+                    .leader_schedule
+                    .commits_until_leader_schedule_update(self.dag_state.clone());
+            }
 
-        if let Some(last) = sequenced_leaders.last() {
-            self.last_decided_leader = last.get_decided_slot();
+            // TODO: Add optimization to abort early without quorum for a round.
+            let sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+            let Some(last_decided) = sequenced_leaders.last().cloned() else {
+                break;
+            };
+
+            let mut committed_leaders = sequenced_leaders
+                .into_iter()
+                .filter_map(|leader| leader.into_committed_block())
+                .collect::<Vec<_>>();
+
+            // Leaders after the schedule update point, committed or skipped, may be elected
+            // differently with the updated schedule. So they are left to be decided again in
+            // the next iteration.
+            let reached_schedule_update = committed_leaders.len() >= commits_until_update;
+            if reached_schedule_update {
+                committed_leaders.truncate(commits_until_update);
+                self.last_decided_leader = committed_leaders.last().unwrap().slot();
+            } else {
+                self.last_decided_leader = last_decided.get_decided_slot();
+            }
             self.context
                 .metrics
                 .node_metrics
                 .last_decided_leader_round
                 .set(self.last_decided_leader.round as i64);
+
+            committed_subdags.extend(self.commit_observer.handle_commit(committed_leaders)?);
+            if !reached_schedule_update {
+                break;
+            }
         }
 
-        let committed_leaders = sequenced_leaders
-            .into_iter()
-            .filter_map(|leader| leader.into_committed_block())
-            .collect::<Vec<_>>();
+        Ok(committed_subdags)
+    }
 
-        self.commit_observer.handle_commit(committed_leaders)
+    /// Adds commits certified by a quorum of authorities, together with their committed blocks.
+    /// This allows an authority that has fallen behind to catch up without deciding the leaders
+    /// itself. Each commit is re-created locally from its leader, which must result in the same
+    /// commit given the same blocks. No block is proposed here. Returns the references of
+    /// ancestors that are missing to accept the blocks.
+    pub(crate) fn add_certified_commits(
+        &mut self,
+        commits: Vec<TrustedCommit>,
+        blocks: Vec<VerifiedBlock>,
+    ) -> ConsensusResult<BTreeSet<BlockRef>> {
+        let _scope = monitored_scope("Core::add_certified_commits");
+
+        let (accepted_blocks, missing_blocks) = self.block_manager.try_accept_blocks(blocks);
+        if !accepted_blocks.is_empty() {
+            self.add_accepted_blocks(accepted_blocks);
+        }
+
+        for commit in commits {
+            let last_commit_index = self.dag_state.read().last_commit_index();
+            // The commit may have been made locally in the meantime.
+            if commit.index() <= last_commit_index {
+                continue;
+            }
+            if commit.index() != last_commit_index + 1 {
+                warn!(
+                    "Certified commit {} does not follow the last commit {}",
+                    commit.index(),
+                    last_commit_index
+                );
+                break;
+            }
+            if !self
+                .dag_state
+                .read()
+                .contains_blocks(commit.blocks().to_vec())
+                .into_iter()
+                .all(|exists| exists)
+            {
+                warn!(
+                    "Blocks of certified commit {} have not been accepted yet",
+                    commit.index()
+                );
+                break;
+            }
+
+            // Same as in try_commit(), the leader schedule has to be updated at the fixed
+            // commit index to keep reputation scores consistent with other authorities.
+            if self
+                .leader_schedule
+                .commits_until_leader_schedule_update(self.dag_state.clone())
+                == 0
+            {
+                self.leader_schedule
+                    .update_leader_schedule(self.dag_state.clone());
+            }
+
+            let leader_block = self
+                .dag_state
+                .read()
+                .get_block(&commit.leader())
+                .expect("Leader of the certified commit should have been accepted");
+            self.commit_observer.handle_commit(vec![leader_block])?;
+
+            let last_commit_digest = self.dag_state.read().last_commit_digest();
+            assert_eq!(
+                last_commit_digest,
+                commit.digest(),
+                "Local commit {} differs from the certified commit",
+                commit.index()
+            );
+
+            if commit.leader().round > self.last_decided_leader.round {
+                self.last_decided_leader = Slot::from(commit.leader());
+                self.context
+                    .metrics
+                    .node_metrics
+                    .last_decided_leader_round
+                    .set(self.last_decided_leader.round as i64);
+            }
+        }
+
+        // Accepted blocks beyond the certified commits may allow more leaders to be committed.
+        self.try_commit()?;
+
+        Ok(missing_blocks)
     }
 
     pub(crate) fn get_missing_blocks(&self) -> BTreeSet<BlockRef> {
@@ -1129,6 +1260,74 @@ mod test {
         assert_eq!(all_stored_commits.len(), 6);
     }
 
+    #[tokio::test]
+    async fn test_core_add_certified_commits() {
+        telemetry_subscribers::init_for_testing();
+
+        // create the cores and their signals for all the authorities
+        let mut cores = create_cores(vec![1, 1, 1, 1]);
+
+        // Authority 3 falls behind and does not receive any block from the others.
+        let lagging_authority = AuthorityIndex::new_for_test(3);
+        let mut last_round_blocks = Vec::new();
+        for round in 1..=10 {
+            let mut this_round_blocks = Vec::new();
+            for (core, _, _, _, _) in &mut cores {
+                if core.context.own_index == lagging_authority {
+                    continue;
+                }
+                core.add_blocks(last_round_blocks.clone()).unwrap();
+                core.force_new_block(round).unwrap();
+                this_round_blocks.push(core.last_proposed_block().clone());
+            }
+            last_round_blocks = this_round_blocks;
+        }
+
+        // Collect the commits of authority 0 and their committed blocks.
+        let source_store = cores[0].4.clone();
+        let commits = source_store.scan_commits(0..CommitIndex::MAX).unwrap();
+        assert_eq!(commits.len(), 6);
+        let block_refs = commits
+            .iter()
+            .flat_map(|commit| commit.blocks().to_vec())
+            .collect::<Vec<_>>();
+        let blocks = source_store
+            .read_blocks(&block_refs)
+            .unwrap()
+            .into_iter()
+            .map(|block| block.expect("Committed block should be stored"))
+            .collect::<Vec<_>>();
+
+        // Authority 3 applies the certified commits.
+        let (core, _, _, commit_receiver, store) = &mut cores[lagging_authority];
+        let missing = core.add_certified_commits(commits.clone(), blocks).unwrap();
+        assert!(missing.is_empty());
+
+        // The same commits should have been made locally and sent to the commit consumer.
+        let local_commits = store.scan_commits(0..CommitIndex::MAX).unwrap();
+        assert_eq!(local_commits, commits);
+        for commit in &commits {
+            let committed_subdag = commit_receiver.try_recv().unwrap();
+            assert_eq!(committed_subdag.commit_index, commit.index());
+            assert_eq!(committed_subdag.leader, commit.leader());
+        }
+        assert_eq!(
+            core.last_decided_leader,
+            Slot::from(commits.last().unwrap().leader())
+        );
+
+        // No block should have been proposed while catching up.
+        assert_eq!(core.last_proposed_block().round(), 1);
+
+        // Applying the same commits again is a no-op.
+        let missing = core.add_certified_commits(commits.clone(), vec![]).unwrap();
+        assert!(missing.is_empty());
+        assert_eq!(
+            store.scan_commits(0..CommitIndex::MAX).unwrap().len(),
+            commits.len()
+        );
+    }
+
     /// Creates cores for the specified number of authorities for their corresponding stakes. The method returns the
     /// cores and their respective signal receivers are returned in `AuthorityIndex` order asc.
     // TODO: return a test fixture instead.
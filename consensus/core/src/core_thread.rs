@@ -11,6 +11,7 @@ use tracing::warn;
 
 use crate::{
     block::{BlockRef, Round, VerifiedBlock},
+    commit::TrustedCommit,
     context::Context,
     core::Core,
     core_thread::CoreError::Shutdown,
@@ -26,6 +27,13 @@ enum CoreThreadCommand {
     ForceNewBlock(Round, oneshot::Sender<()>),
     /// Request missing blocks that need to be synced.
     GetMissing(oneshot::Sender<BTreeSet<BlockRef>>),
+    /// Add commits certified by a quorum, together with their committed blocks, to catch up
+    /// with the rest of the network.
+    AddCertifiedCommits(
+        Vec<TrustedCommit>,
+        Vec<VerifiedBlock>,
+        oneshot::Sender<BTreeSet<BlockRef>>,
+    ),
 }
 
 #[derive(Error, Debug)]
@@ -44,6 +52,12 @@ pub trait CoreThreadDispatcher: Sync + Send + 'static {
     async fn force_new_block(&self, round: Round) -> Result<(), CoreError>;
 
     async fn get_missing_blocks(&self) -> Result<BTreeSet<BlockRef>, CoreError>;
+
+    async fn add_certified_commits(
+        &self,
+        commits: Vec<TrustedCommit>,
+        blocks: Vec<VerifiedBlock>,
+    ) -> Result<BTreeSet<BlockRef>, CoreError>;
 }
 
 pub(crate) struct CoreThreadHandle {
@@ -84,6 +98,10 @@ impl CoreThread {
                 CoreThreadCommand::GetMissing(sender) => {
                     sender.send(self.core.get_missing_blocks()).ok();
                 }
+                CoreThreadCommand::AddCertifiedCommits(commits, blocks, sender) => {
+                    let missing_blocks = self.core.add_certified_commits(commits, blocks)?;
+                    sender.send(missing_blocks).ok();
+                }
             }
         }
 
@@ -171,6 +189,19 @@ impl CoreThreadDispatcher for ChannelCoreThreadDispatcher {
         self.send(CoreThreadCommand::GetMissing(sender)).await;
         receiver.await.map_err(Shutdown)
     }
+
+    async fn add_certified_commits(
+        &self,
+        commits: Vec<TrustedCommit>,
+        blocks: Vec<VerifiedBlock>,
+    ) -> Result<BTreeSet<BlockRef>, CoreError> {
+        let (sender, receiver) = oneshot::channel();
+        self.send(CoreThreadCommand::AddCertifiedCommits(
+            commits, blocks, sender,
+        ))
+        .await;
+        receiver.await.map_err(Shutdown)
+    }
 }
 
 #[cfg(test)]
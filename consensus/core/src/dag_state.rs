@@ -16,11 +16,18 @@ use crate::block::GENESIS_ROUND;
 use crate::stake_aggregator::{QuorumThreshold, StakeAggregator};
 use crate::{
     block::{genesis_blocks, BlockAPI, BlockDigest, BlockRef, Round, Slot, VerifiedBlock},
-    commit::{CommitAPI as _, CommitDigest, CommitIndex, CommitRef, TrustedCommit},
+    commit::{
+        load_committed_subdag_from_store, CommitAPI as _, CommitConsumerMonitor, CommitDigest,
+        CommitIndex, CommitRef, CommittedSubDag, TrustedCommit,
+    },
     context::Context,
+    leader_scoring::ReputationScores,
     storage::{Store, WriteBatch},
 };
 
+// Number of rounds the retention round needs to advance, before storage is garbage collected again.
+const STORE_GC_INTERVAL_ROUNDS: Round = 50;
+
 /// DagState provides the API to write and read accepted blocks from the DAG.
 /// Only uncommited and last committed blocks are cached in memory.
 /// The rest of blocks are stored on disk.
@@ -35,7 +42,7 @@ pub(crate) struct DagState {
     genesis: BTreeMap<BlockRef, VerifiedBlock>,
 
     // Contains recent blocks within CACHED_ROUNDS from the last committed round per authority.
-    // Note: all uncommitted blocks are kept in memory.
+    // Note: all uncommitted blocks above the GC round are kept in memory.
     recent_blocks: BTreeMap<BlockRef, VerifiedBlock>,
 
     // Contains block refs of recent_blocks.
@@ -51,6 +58,14 @@ pub(crate) struct DagState {
     // Last committed rounds per authority.
     last_committed_rounds: Vec<Round>,
 
+    // Committed sub-dags that have not been used to calculate reputation scores yet.
+    unscored_committed_subdags: Vec<CommittedSubDag>,
+
+    // Reputation scores from the last leader schedule update. They are persisted with the
+    // next flush, so the leader schedule can be recovered after restarts.
+    last_reputation_scores: Option<ReputationScores>,
+    reputation_scores_to_write: Option<ReputationScores>,
+
     // Commits to be voted on in new blocks.
     // TODO: limit to 1st commit per round with multi-leader.
     commits_to_vote: VecDeque<CommitRef>,
@@ -64,12 +79,39 @@ pub(crate) struct DagState {
 
     // The number of cached rounds
     cached_rounds: Round,
+
+    // Number of rounds below the last committed leader round, for which blocks and commits are
+    // kept in storage. 0 disables pruning storage.
+    store_retention_depth: Round,
+
+    // Reports the commits handled by the consumer. Commits above it are replayed from storage
+    // after restarts, so they and their blocks are never pruned. When unset, all commits are
+    // considered handled.
+    commit_consumer_monitor: Option<Arc<CommitConsumerMonitor>>,
+
+    // Index and leader round of the last handled commit that has been scanned for pruning.
+    handled_commit_index: CommitIndex,
+    handled_commit_round: Round,
+
+    // Per authority, the highest round of its blocks included in the scanned handled commits.
+    handled_committed_rounds: Vec<Round>,
+
+    // Index and leader round of scanned handled commits that are still in storage.
+    unpruned_commits: VecDeque<(CommitIndex, Round)>,
+
+    // Per authority, the round at or below which blocks have been removed from storage since
+    // startup. Each vector index corresponds to the authority with the index.
+    store_gc_rounds: Vec<Round>,
+
+    // The retention round when storage was last garbage collected.
+    last_store_gc_round: Round,
 }
 
 impl DagState {
     /// Initializes DagState from storage.
     pub(crate) fn new(context: Arc<Context>, store: Arc<dyn Store>) -> Self {
         let cached_rounds = context.parameters.dag_state_cached_rounds as Round;
+        let store_retention_depth = context.parameters.gc_depth as Round;
         let num_authorities = context.committee.size();
 
         let genesis = genesis_blocks(context.clone())
@@ -90,6 +132,30 @@ impl DagState {
                 vec![0; num_authorities]
             }
         };
+        let last_reputation_scores = store
+            .read_last_reputation_scores()
+            .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e));
+
+        // Recover the commits after the ones covered by the last reputation scores, so the
+        // next leader schedule update is calculated from the same commits as before restarting.
+        let unscored_committed_subdags = match &last_commit {
+            Some(last_commit)
+                if context
+                    .protocol_config
+                    .mysticeti_leader_scoring_and_schedule() =>
+            {
+                let first_unscored_index = last_reputation_scores
+                    .as_ref()
+                    .map_or(1, |scores| scores.commit_range.end() + 1);
+                store
+                    .scan_commits(first_unscored_index..last_commit.index() + 1)
+                    .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e))
+                    .into_iter()
+                    .map(|commit| load_committed_subdag_from_store(store.as_ref(), commit))
+                    .collect()
+            }
+            _ => vec![],
+        };
 
         let mut state = Self {
             context,
@@ -99,11 +165,22 @@ impl DagState {
             highest_accepted_round: 0,
             last_commit,
             last_committed_rounds: last_committed_rounds.clone(),
+            unscored_committed_subdags,
+            last_reputation_scores,
+            reputation_scores_to_write: None,
             commits_to_vote: VecDeque::new(),
             blocks_to_write: vec![],
             commits_to_write: vec![],
             store,
             cached_rounds,
+            store_retention_depth,
+            commit_consumer_monitor: None,
+            handled_commit_index: 0,
+            handled_commit_round: GENESIS_ROUND,
+            handled_committed_rounds: vec![GENESIS_ROUND; num_authorities],
+            unpruned_commits: VecDeque::new(),
+            store_gc_rounds: vec![GENESIS_ROUND; num_authorities],
+            last_store_gc_round: GENESIS_ROUND,
         };
 
         for (i, round) in last_committed_rounds.into_iter().enumerate() {
@@ -516,13 +593,50 @@ impl DagState {
         self.last_committed_rounds.clone()
     }
 
+    /// Buffers committed sub-dags to be scored at the next leader schedule update.
+    pub(crate) fn add_unscored_committed_subdags(
+        &mut self,
+        committed_subdags: Vec<CommittedSubDag>,
+    ) {
+        if !self
+            .context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            return;
+        }
+        self.unscored_committed_subdags.extend(committed_subdags);
+    }
+
+    pub(crate) fn unscored_committed_subdags_count(&self) -> u64 {
+        self.unscored_committed_subdags.len() as u64
+    }
+
+    /// Takes the committed sub-dags buffered since the last leader schedule update.
+    pub(crate) fn take_unscored_committed_subdags(&mut self) -> Vec<CommittedSubDag> {
+        std::mem::take(&mut self.unscored_committed_subdags)
+    }
+
+    /// Sets the reputation scores of the current leader schedule. They are persisted with
+    /// the next flushed commit.
+    pub(crate) fn set_reputation_scores(&mut self, reputation_scores: ReputationScores) {
+        self.last_reputation_scores = Some(reputation_scores.clone());
+        self.reputation_scores_to_write = Some(reputation_scores);
+    }
+
+    /// Reputation scores of the current leader schedule, if it has been updated at all.
+    pub(crate) fn last_reputation_scores(&self) -> Option<ReputationScores> {
+        self.last_reputation_scores.clone()
+    }
+
     /// After each flush, DagState becomes persisted in storage and it expected to recover
     /// all internal states from storage after restarts.
     pub(crate) fn flush(&mut self) {
         // Flush buffered data to storage.
         let blocks = std::mem::take(&mut self.blocks_to_write);
         let commits = std::mem::take(&mut self.commits_to_write);
-        if blocks.is_empty() && commits.is_empty() {
+        let reputation_scores = self.reputation_scores_to_write.take();
+        if blocks.is_empty() && commits.is_empty() && reputation_scores.is_none() {
             return;
         }
         self.store
@@ -531,6 +645,7 @@ impl DagState {
                 commits,
                 // TODO: limit to write at most once per commit round with multi-leader.
                 self.last_committed_rounds.clone(),
+                reputation_scores,
             ))
             .unwrap_or_else(|e| panic!("Failed to write to storage: {:?}", e));
         self.context
@@ -540,13 +655,18 @@ impl DagState {
             .inc();
 
         // Clean up old cached data. After flushing, all cached blocks are guaranteed to be persisted.
+        // Blocks at or below the GC round are evicted even if they are not committed, except for
+        // the last block of each authority.
+        let gc_round = self.gc_round();
         for (authority_refs, last_committed_round) in self
             .recent_refs
             .iter_mut()
             .zip(self.last_committed_rounds.iter())
         {
             while let Some(block_ref) = authority_refs.first() {
-                if block_ref.round <= Self::evict_round(*last_committed_round, self.cached_rounds) {
+                if block_ref.round <= Self::evict_round(*last_committed_round, self.cached_rounds)
+                    || (block_ref.round <= gc_round && authority_refs.len() > 1)
+                {
                     self.recent_blocks.remove(block_ref);
                     authority_refs.pop_first();
                 } else {
@@ -554,6 +674,124 @@ impl DagState {
                 }
             }
         }
+
+        self.gc_store();
+    }
+
+    /// Sets the monitor of the commits handled by the consumer, which caps storage pruning.
+    pub(crate) fn set_commit_consumer_monitor(&mut self, monitor: Arc<CommitConsumerMonitor>) {
+        self.commit_consumer_monitor = Some(monitor);
+    }
+
+    /// Removes blocks and commits that are no longer needed from storage. Only data more than
+    /// the retention depth below the last commit, and not needed to replay the commits the
+    /// consumer has not handled yet, is removed:
+    /// - Blocks of an authority at or below its highest round in handled commits. They have been
+    ///   committed, or can no longer be committed.
+    /// - Blocks at or below the GC round of the last handled commit, committed or not. They are
+    ///   excluded from all later commits.
+    /// - Commits whose blocks have all been removed, which are never the last commit.
+    ///
+    /// Blocks of committed sub-dags that have not been scored yet are kept, since they are needed
+    /// to recover the leader schedule.
+    fn gc_store(&mut self) {
+        if self.store_retention_depth == 0 {
+            return;
+        }
+        let retention_round = self
+            .last_commit_round()
+            .saturating_sub(max(self.store_retention_depth, self.cached_rounds));
+        // Pruning scans commits, the unscored sub-dags and storage, so it only runs every so many
+        // rounds.
+        if retention_round < self.last_store_gc_round + STORE_GC_INTERVAL_ROUNDS {
+            return;
+        }
+        self.last_store_gc_round = retention_round;
+        self.context
+            .metrics
+            .node_metrics
+            .dag_state_gc_round
+            .set(self.gc_round() as i64);
+
+        let last_commit_index = self.last_commit_index();
+        let handled_commit_index = self
+            .commit_consumer_monitor
+            .as_ref()
+            .map_or(last_commit_index, |monitor| {
+                monitor.highest_handled_commit().min(last_commit_index)
+            });
+        if handled_commit_index > self.handled_commit_index {
+            let commits = self
+                .store
+                .scan_commits(self.handled_commit_index + 1..handled_commit_index + 1)
+                .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e));
+            for commit in commits {
+                for block_ref in commit.blocks() {
+                    let round = &mut self.handled_committed_rounds[block_ref.author];
+                    *round = max(*round, block_ref.round);
+                }
+                self.handled_commit_round = commit.leader().round;
+                self.unpruned_commits
+                    .push_back((commit.index(), commit.leader().round));
+            }
+            self.handled_commit_index = handled_commit_index;
+        }
+        let handled_gc_round = self.gc_round_at(self.handled_commit_round);
+
+        let min_unscored_round = self
+            .unscored_committed_subdags
+            .iter()
+            .flat_map(|subdag| subdag.blocks.iter().map(|block| block.round()))
+            .min();
+        let prune_round = match min_unscored_round {
+            Some(round) => retention_round.min(round.saturating_sub(1)),
+            None => retention_round,
+        };
+
+        for (index, handled_committed_round) in self.handled_committed_rounds.iter().enumerate() {
+            let authority = self.context.committee.to_authority_index(index).unwrap();
+            let author_prune_round =
+                prune_round.min(max(*handled_committed_round, handled_gc_round));
+            if author_prune_round <= self.store_gc_rounds[index] {
+                continue;
+            }
+            let pruned = self
+                .store
+                .prune_blocks_by_author(
+                    authority,
+                    self.store_gc_rounds[index] + 1..author_prune_round + 1,
+                )
+                .unwrap_or_else(|e| panic!("Failed to prune storage: {:?}", e));
+            self.context
+                .metrics
+                .node_metrics
+                .dag_state_pruned_blocks
+                .inc_by(pruned as u64);
+            self.store_gc_rounds[index] = author_prune_round;
+        }
+
+        // A handled commit only contains blocks at or below its leader round, which are at or
+        // below the highest handled round of their authors, so all of them have been removed
+        // when the leader round is at or below the prune round.
+        let mut prune_commits_end = None;
+        while let Some((commit_index, leader_round)) = self.unpruned_commits.front() {
+            if *leader_round > prune_round {
+                break;
+            }
+            prune_commits_end = Some(commit_index + 1);
+            self.unpruned_commits.pop_front();
+        }
+        if let Some(end) = prune_commits_end {
+            let pruned = self
+                .store
+                .prune_commits(end)
+                .unwrap_or_else(|e| panic!("Failed to prune storage: {:?}", e));
+            self.context
+                .metrics
+                .node_metrics
+                .dag_state_pruned_commits
+                .inc_by(pruned as u64);
+        }
     }
 
     /// Detects and returns the blocks of the round that forms the last quorum. The method will return
@@ -598,7 +836,25 @@ impl DagState {
     /// <= `last_evicted_round` we don't have such guarantees as out of order blocks might exist.
     fn authority_evict_round(&self, authority_index: AuthorityIndex) -> Round {
         let commit_round = self.last_committed_rounds[authority_index];
-        Self::evict_round(commit_round, self.cached_rounds)
+        max(
+            Self::evict_round(commit_round, self.cached_rounds),
+            self.gc_round(),
+        )
+    }
+
+    /// Blocks at or below the GC round are garbage collected, whether they are committed or not.
+    /// Garbage collected blocks are excluded from later commits, so the GC round is derived from
+    /// the last commit and the protocol config, to be the same across authorities.
+    pub(crate) fn gc_round(&self) -> Round {
+        self.gc_round_at(self.last_commit_round())
+    }
+
+    /// The GC round after a commit with the given leader round.
+    fn gc_round_at(&self, commit_round: Round) -> Round {
+        match self.context.protocol_config.consensus_gc_depth_as_option() {
+            Some(gc_depth) => commit_round.saturating_sub(gc_depth),
+            None => GENESIS_ROUND,
+        }
     }
 
     /// Calculates the last eviction round based on the provided `commit_round`. Any blocks with
@@ -611,7 +867,7 @@ impl DagState {
 #[cfg(test)]
 mod test {
     use parking_lot::RwLock;
-    use std::vec;
+    use std::{ops::RangeInclusive, vec};
 
     use super::*;
     use crate::test_dag::build_dag;
@@ -1121,16 +1377,17 @@ mod test {
                 let block = VerifiedBlock::new_for_test(TestBlock::new(round, author).build());
                 blocks.push(block);
             }
+            let leader = blocks.last().unwrap().reference();
             commits.push(TrustedCommit::new_for_test(
                 round as CommitIndex,
                 CommitDigest::MIN,
-                blocks.last().unwrap().reference(),
-                vec![],
+                leader,
+                vec![leader],
             ));
         }
 
         // Add the blocks from first 5 rounds and first 5 commits to the dag state
-        let i = blocks.iter().position(|b| b.round() == 5).unwrap();
+        let i = blocks.iter().position(|b| b.round() == 6).unwrap();
         let temp_blocks = blocks.split_off(i);
         dag_state.accept_blocks(blocks.clone());
         let temp_commits = commits.split_off(5);
@@ -1201,6 +1458,130 @@ mod test {
         assert_eq!(dag_state.last_commit_index(), 5);
     }
 
+    #[test]
+    fn test_garbage_collection() {
+        let num_authorities: u32 = 4;
+        let (mut context, _) = Context::new_for_test(num_authorities as usize);
+        context.parameters.dag_state_cached_rounds = 5;
+        context.parameters.gc_depth = 20;
+        context
+            .protocol_config
+            .set_consensus_gc_depth_for_testing(10);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let mut dag_state = DagState::new(context.clone(), store.clone());
+        // The consumer has only handled the first 70 commits.
+        let monitor = Arc::new(CommitConsumerMonitor::new(70));
+        dag_state.set_commit_consumer_monitor(monitor.clone());
+
+        // Authorities 0 ~ 2 get their blocks committed every round. Blocks of authority 3 are
+        // never committed.
+        let mut blocks = Vec::new();
+        let add_rounds = |dag_state: &mut DagState,
+                          blocks: &mut Vec<Vec<VerifiedBlock>>,
+                          rounds: RangeInclusive<Round>| {
+            for round in rounds {
+                let round_blocks: Vec<_> = (0..num_authorities)
+                    .map(|author| {
+                        VerifiedBlock::new_for_test(TestBlock::new(round, author).build())
+                    })
+                    .collect();
+                dag_state.accept_blocks(round_blocks.clone());
+                let committed_blocks = round_blocks[..3].to_vec();
+                let leader = committed_blocks[0].reference();
+                dag_state.add_commit(TrustedCommit::new_for_test(
+                    round as CommitIndex,
+                    CommitDigest::MIN,
+                    leader,
+                    committed_blocks.iter().map(|b| b.reference()).collect(),
+                ));
+                // Commits after 80 have not been used for reputation scores yet.
+                if round > 80 {
+                    dag_state.add_unscored_committed_subdags(vec![CommittedSubDag::new(
+                        leader,
+                        committed_blocks,
+                        0,
+                        round as CommitIndex,
+                    )]);
+                }
+                blocks.push(round_blocks);
+            }
+        };
+        add_rounds(&mut dag_state, &mut blocks, 1..=100);
+        dag_state.set_reputation_scores(ReputationScores::new(
+            1..=80,
+            vec![0; num_authorities as usize],
+        ));
+        dag_state.flush();
+
+        // Storage is pruned up to round 100 - 20 = 80, which is also below the unscored
+        // sub-dags. Committed blocks are only removed up to round 70 of the last handled commit.
+        // Uncommitted blocks are removed up to round 60, the GC round of the last handled commit.
+        let refs_at_round = |blocks: &Vec<Vec<VerifiedBlock>>, round: Round| -> Vec<BlockRef> {
+            blocks[round as usize - 1]
+                .iter()
+                .map(|b| b.reference())
+                .collect()
+        };
+        assert_eq!(
+            store.contains_blocks(&refs_at_round(&blocks, 60)).unwrap(),
+            vec![false; 4]
+        );
+        assert_eq!(
+            store.contains_blocks(&refs_at_round(&blocks, 61)).unwrap(),
+            vec![false, false, false, true]
+        );
+        assert_eq!(
+            store.contains_blocks(&refs_at_round(&blocks, 71)).unwrap(),
+            vec![true; 4]
+        );
+        // Handled commits whose blocks have all been removed are pruned too.
+        let commits = store.scan_commits(0..101).unwrap();
+        assert_eq!(commits.len(), 30);
+        assert_eq!(commits[0].index(), 71);
+
+        // Uncommitted blocks at or below the GC round 100 - 10 = 90 are evicted from the cache,
+        // and can still be read from storage until they are pruned.
+        assert_eq!(
+            dag_state.recent_refs[3].first().unwrap().round,
+            91,
+            "{:?}",
+            dag_state.recent_refs[3]
+        );
+        assert_eq!(dag_state.recent_refs[0].first().unwrap().round, 96);
+        assert!(dag_state.contains_blocks(refs_at_round(&blocks, 61))[3]);
+        assert!(dag_state
+            .get_block(&refs_at_round(&blocks, 61)[3])
+            .is_some());
+        assert!(!dag_state.contains_blocks(refs_at_round(&blocks, 60))[3]);
+
+        // DagState can be recovered after garbage collection.
+        drop(dag_state);
+        let mut dag_state = DagState::new(context.clone(), store.clone());
+        assert_eq!(dag_state.last_commit_index(), 100);
+        assert_eq!(dag_state.unscored_committed_subdags_count(), 20);
+
+        // After all commits are handled and scored, uncommitted blocks are removed up to the
+        // GC round of the last handled commit, capped by the retention round 150 - 20 = 130.
+        dag_state.set_commit_consumer_monitor(monitor.clone());
+        add_rounds(&mut dag_state, &mut blocks, 101..=150);
+        dag_state.take_unscored_committed_subdags();
+        monitor.set_highest_handled_commit(150);
+        dag_state.flush();
+        assert_eq!(
+            store.contains_blocks(&refs_at_round(&blocks, 130)).unwrap(),
+            vec![false; 4]
+        );
+        assert_eq!(
+            store.contains_blocks(&refs_at_round(&blocks, 131)).unwrap(),
+            vec![true; 4]
+        );
+        let commits = store.scan_commits(0..151).unwrap();
+        assert_eq!(commits.len(), 20);
+        assert_eq!(commits[0].index(), 131);
+        assert_eq!(store.read_last_commit().unwrap().unwrap().index(), 150);
+    }
+
     #[test]
     fn test_get_cached_last_block_per_authority() {
         // GIVEN
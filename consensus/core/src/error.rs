@@ -8,7 +8,10 @@ use fastcrypto::error::FastCryptoError;
 use thiserror::Error;
 use typed_store::TypedStoreError;
 
-use crate::block::{BlockRef, BlockTimestampMs, Round};
+use crate::{
+    block::{BlockRef, BlockTimestampMs, Round},
+    commit::CommitIndex,
+};
 
 /// Errors that can occur when processing blocks, reading from storage, or encountering shutdown.
 #[derive(Clone, Debug, Error)]
@@ -46,6 +49,44 @@ pub enum ConsensusError {
     #[error("Too many blocks have been requested from authority {0}")]
     TooManyFetchBlocksRequested(AuthorityIndex),
 
+    #[error("Invalid commit range requested: {start}..={end}")]
+    InvalidCommitRange {
+        start: CommitIndex,
+        end: CommitIndex,
+    },
+
+    #[error("No certified commit has been returned from authority {0}")]
+    NoCommitReceived(AuthorityIndex),
+
+    #[error("Unexpected commit {index} returned from authority {peer}, which does not follow the previous commit")]
+    UnexpectedCommitSequence {
+        peer: AuthorityIndex,
+        index: CommitIndex,
+    },
+
+    #[error("Certifier block {block_ref} from authority {peer} does not vote for the last fetched commit {index}")]
+    InvalidCommitCertifier {
+        peer: AuthorityIndex,
+        block_ref: BlockRef,
+        index: CommitIndex,
+    },
+
+    #[error(
+        "Insufficient stake voting for commit {index} from authority {peer}: {stake} < {quorum}"
+    )]
+    NotEnoughCommitVotes {
+        peer: AuthorityIndex,
+        index: CommitIndex,
+        stake: Stake,
+        quorum: Stake,
+    },
+
+    #[error("Authority {peer} did not return all committed blocks: {missing} missing")]
+    MissingCommittedBlocks {
+        peer: AuthorityIndex,
+        missing: usize,
+    },
+
     #[error("Invalid authority index: {index} > {max}")]
     InvalidAuthorityIndex { index: AuthorityIndex, max: usize },
 
@@ -83,6 +124,12 @@ pub enum ConsensusError {
     #[error("Invalid transaction: {0}")]
     InvalidTransaction(String),
 
+    #[error("Transaction size ({size}B) is over limit ({limit}B)")]
+    OversizedTransaction { size: u64, limit: u64 },
+
+    #[error("Total size of transactions in block ({size}B) is over limit ({limit}B)")]
+    TooManyTransactionBytes { size: u64, limit: u64 },
+
     #[error("Ancestors max timestamp {max_timestamp_ms} > block timestamp {block_timestamp_ms}")]
     InvalidBlockTimestamp {
         max_timestamp_ms: u64,
@@ -1,29 +1,142 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
+use consensus_config::{AuthorityIndex, Stake};
+use parking_lot::RwLock;
 use rand::{prelude::SliceRandom, rngs::StdRng, SeedableRng};
 
-use consensus_config::AuthorityIndex;
+use crate::{
+    block::Round,
+    context::Context,
+    dag_state::DagState,
+    leader_scoring::{ReputationScoreCalculator, ReputationScores},
+};
 
-use crate::context::Context;
+/// The number of commits after which the leader schedule is updated from the reputation scores
+/// of the authorities over those commits.
+// TODO: move to protocol config once the value is finalized.
+pub(crate) const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 300;
 
 /// The LeaderSchedule is responsible for producing the leader schedule across
-/// an epoch. For now it is a simple wrapper around Context to provide a leader
-/// for a round deterministically.
-// TODO: complete full leader schedule changes
+/// an epoch. Leaders are elected deterministically per round, and every
+/// `num_commits_per_schedule` commits the schedule is updated so that the leader
+/// slots of the lowest scoring authorities are given to the highest scoring ones.
+/// The update happens at the same commit index on every authority and is based
+/// only on committed blocks, so all authorities agree on the schedule.
 #[derive(Clone)]
 pub(crate) struct LeaderSchedule {
     context: Arc<Context>,
+    num_commits_per_schedule: u64,
+    /// Shared by all clones, so the committers observe schedule updates made by Core.
+    leader_swap_table: Arc<RwLock<LeaderSwapTable>>,
 }
 
 impl LeaderSchedule {
     pub fn new(context: Arc<Context>) -> Self {
-        Self { context }
+        Self {
+            context,
+            num_commits_per_schedule: CONSENSUS_COMMITS_PER_SCHEDULE,
+            leader_swap_table: Arc::new(RwLock::new(LeaderSwapTable::default())),
+        }
+    }
+
+    /// Recovers the leader schedule from the reputation scores persisted with the last commit.
+    pub fn from_store(context: Arc<Context>, dag_state: Arc<RwLock<DagState>>) -> Self {
+        let leader_swap_table = dag_state
+            .read()
+            .last_reputation_scores()
+            .map(|scores| {
+                scores.update_metrics(context.clone());
+                LeaderSwapTable::new(context.clone(), &scores)
+            })
+            .unwrap_or_default();
+        let leader_schedule = Self::new(context);
+        *leader_schedule.leader_swap_table.write() = leader_swap_table;
+        leader_schedule
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_num_commits_per_schedule(mut self, num_commits_per_schedule: u64) -> Self {
+        self.num_commits_per_schedule = num_commits_per_schedule;
+        self
+    }
+
+    /// Returns the number of commits that can still be made before the leader schedule has
+    /// to be updated. Commits beyond that point must be decided with the updated schedule.
+    /// The schedule is never updated on protocol versions without leader scoring.
+    pub fn commits_until_leader_schedule_update(&self, dag_state: Arc<RwLock<DagState>>) -> usize {
+        if !self
+            .context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            return usize::MAX;
+        }
+        let unscored_committed_subdags_count = dag_state.read().unscored_committed_subdags_count();
+        assert!(
+            unscored_committed_subdags_count <= self.num_commits_per_schedule,
+            "Unscored committed subdags count {} exceeds the number of commits per schedule {}",
+            unscored_committed_subdags_count,
+            self.num_commits_per_schedule
+        );
+        (self.num_commits_per_schedule - unscored_committed_subdags_count) as usize
+    }
+
+    /// Calculates the reputation scores from the commits since the last update, and updates
+    /// the leader schedule with them. The scores are persisted with the next commit.
+    pub fn update_leader_schedule(&self, dag_state: Arc<RwLock<DagState>>) {
+        let mut dag_state = dag_state.write();
+        let unscored_committed_subdags = dag_state.take_unscored_committed_subdags();
+        let reputation_scores =
+            ReputationScoreCalculator::new(self.context.clone(), &unscored_committed_subdags)
+                .calculate();
+        reputation_scores.update_metrics(self.context.clone());
+
+        let leader_swap_table = LeaderSwapTable::new(self.context.clone(), &reputation_scores);
+        tracing::info!(
+            "Updating leader schedule with reputation scores of commits {:?}: {:?}, {}",
+            reputation_scores.commit_range,
+            reputation_scores.scores_per_authority,
+            leader_swap_table
+        );
+        dag_state.set_reputation_scores(reputation_scores);
+        drop(dag_state);
+
+        *self.leader_swap_table.write() = leader_swap_table;
+        self.context
+            .metrics
+            .node_metrics
+            .leader_schedule_updates
+            .inc();
     }
 
     pub fn elect_leader(&self, round: u32, leader_offset: u32) -> AuthorityIndex {
+        let leader_swap_table = self.leader_swap_table.read();
+        // With multiple leaders per round, the leaders of the lower offsets are elected first,
+        // so that swapped in leaders are distinct from the other leaders of the round.
+        let mut elected_leaders = Vec::with_capacity(leader_offset as usize + 1);
+        for offset in 0..=leader_offset {
+            let leader = self.elect_leader_without_swaps(round, offset);
+            let mut leader = leader_swap_table
+                .swap(leader, round, &elected_leaders)
+                .unwrap_or(leader);
+            // When there are not enough good nodes, the leader can still be elected already.
+            // Then the next authority that is not elected is used instead.
+            while elected_leaders.contains(&leader) {
+                leader = self
+                    .context
+                    .committee
+                    .to_authority_index((leader.value() + 1) % self.context.committee.size())
+                    .unwrap();
+            }
+            elected_leaders.push(leader);
+        }
+        elected_leaders.pop().unwrap()
+    }
+
+    fn elect_leader_without_swaps(&self, round: u32, leader_offset: u32) -> AuthorityIndex {
         cfg_if::cfg_if! {
             // TODO: we need to differentiate the leader strategy in tests, so for
             // some type of testing (ex sim tests) we can use the staked approach.
@@ -65,13 +178,133 @@ impl LeaderSchedule {
     }
 }
 
+/// Decides which leaders get swapped, based on reputation scores. Up to
+/// `consensus_bad_nodes_stake_threshold` percent of stake of the lowest scoring
+/// authorities ("bad nodes") have their leader slots taken over by the same share
+/// of stake of the highest scoring authorities ("good nodes").
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct LeaderSwapTable {
+    /// Authorities that take over the leader slots of the bad nodes, ordered by score.
+    pub(crate) good_nodes: Vec<AuthorityIndex>,
+    /// Authorities whose leader slots are given to the good nodes.
+    pub(crate) bad_nodes: BTreeSet<AuthorityIndex>,
+}
+
+impl LeaderSwapTable {
+    pub(crate) fn new(context: Arc<Context>, reputation_scores: &ReputationScores) -> Self {
+        // Leaders are never swapped for protocol versions without leader scoring.
+        if !context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            return Self::default();
+        }
+        let swap_stake_threshold = context
+            .protocol_config
+            .consensus_bad_nodes_stake_threshold_as_option()
+            .unwrap_or(0);
+        assert!(
+            swap_stake_threshold <= 33,
+            "The swap stake threshold ({swap_stake_threshold}) should be in range [0 - 33]"
+        );
+
+        let authorities = reputation_scores.authorities_by_score_desc(&context);
+        let good_nodes =
+            Self::retrieve_first_nodes(&context, authorities.iter(), swap_stake_threshold);
+        let Some(lowest_good_score) = good_nodes.last().map(|(_, score)| *score) else {
+            return Self::default();
+        };
+
+        // Only authorities scoring strictly lower than all the good nodes are swapped out, so
+        // nothing changes while all authorities perform equally well.
+        let bad_nodes: BTreeSet<_> =
+            Self::retrieve_first_nodes(&context, authorities.iter().rev(), swap_stake_threshold)
+                .into_iter()
+                .filter(|(_, score)| *score < lowest_good_score)
+                .map(|(authority, _)| authority)
+                .collect();
+        if bad_nodes.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            good_nodes: good_nodes
+                .into_iter()
+                .map(|(authority, _)| authority)
+                .collect(),
+            bad_nodes,
+        }
+    }
+
+    /// Returns the authority that replaces `leader` in its slot, if `leader` is a bad node or
+    /// is already one of `elected_leaders`, the leaders of the lower offsets in the same round.
+    /// The replacement is a good node chosen deterministically from the round, which is not
+    /// already elected in the round.
+    pub(crate) fn swap(
+        &self,
+        leader: AuthorityIndex,
+        leader_round: Round,
+        elected_leaders: &[AuthorityIndex],
+    ) -> Option<AuthorityIndex> {
+        if !self.bad_nodes.contains(&leader) && !elected_leaders.contains(&leader) {
+            return None;
+        }
+
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes[32 - 4..].copy_from_slice(&leader_round.to_le_bytes());
+        let mut rng = StdRng::from_seed(seed_bytes);
+        let mut good_nodes = self.good_nodes.clone();
+        good_nodes.shuffle(&mut rng);
+        good_nodes
+            .into_iter()
+            .find(|authority| !elected_leaders.contains(authority))
+    }
+
+    /// Takes authorities in the given order while their accumulated stake stays within
+    /// `stake_threshold` percent of the total stake.
+    fn retrieve_first_nodes<'a>(
+        context: &Context,
+        authorities: impl Iterator<Item = &'a (AuthorityIndex, u64)>,
+        stake_threshold: u64,
+    ) -> Vec<(AuthorityIndex, u64)> {
+        let threshold_stake: Stake = stake_threshold * context.committee.total_stake() / 100;
+        let mut total_stake = 0;
+        let mut filtered_authorities = Vec::new();
+        for (authority, score) in authorities {
+            total_stake += context.committee.stake(*authority);
+            if total_stake > threshold_stake {
+                break;
+            }
+            filtered_authorities.push((*authority, *score));
+        }
+        filtered_authorities
+    }
+}
+
+impl std::fmt::Display for LeaderSwapTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LeaderSwapTable(good_nodes={:?}, bad_nodes={:?})",
+            self.good_nodes, self.bad_nodes
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use consensus_config::{local_committee_and_keys, Parameters};
     use sui_protocol_config::ProtocolConfig;
 
     use super::*;
-    use crate::metrics::test_metrics;
+    use crate::{
+        block::{BlockAPI as _, Slot},
+        commit::DEFAULT_WAVE_LENGTH,
+        linearizer::Linearizer,
+        metrics::test_metrics,
+        storage::mem_store::MemStore,
+        test_dag::{build_dag, build_dag_layer, get_all_leader_blocks},
+    };
 
     #[test]
     fn test_elect_leader() {
@@ -136,4 +369,215 @@ mod tests {
             leader_schedule.elect_leader_stake_based(1, 2)
         );
     }
+
+    /// Context of 4 authorities with equal stake, where up to one authority can be swapped.
+    fn context_with_swap_threshold() -> Arc<Context> {
+        let (mut context, _) = Context::new_for_test(4);
+        context
+            .protocol_config
+            .set_mysticeti_leader_scoring_and_schedule_for_testing(true);
+        context
+            .protocol_config
+            .set_consensus_bad_nodes_stake_threshold(33);
+        Arc::new(context)
+    }
+
+    #[test]
+    fn test_leader_swap_table() {
+        let context = context_with_swap_threshold();
+        let reputation_scores = ReputationScores::new(1..=10, vec![5, 10, 1, 7]);
+
+        let leader_swap_table = LeaderSwapTable::new(context, &reputation_scores);
+
+        assert_eq!(
+            leader_swap_table.good_nodes,
+            vec![AuthorityIndex::new_for_test(1)]
+        );
+        assert_eq!(
+            leader_swap_table.bad_nodes,
+            BTreeSet::from([AuthorityIndex::new_for_test(2)])
+        );
+        for round in 1..=10 {
+            assert_eq!(
+                leader_swap_table.swap(AuthorityIndex::new_for_test(2), round, &[]),
+                Some(AuthorityIndex::new_for_test(1))
+            );
+            assert_eq!(
+                leader_swap_table.swap(AuthorityIndex::new_for_test(0), round, &[]),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_leader_swap_table_multiple_leaders() {
+        let context = context_with_swap_threshold();
+        let reputation_scores = ReputationScores::new(1..=10, vec![5, 10, 1, 7]);
+        let leader_swap_table = LeaderSwapTable::new(context.clone(), &reputation_scores);
+
+        // A good node that is already a leader of the round is not swapped in again.
+        assert_eq!(
+            leader_swap_table.swap(
+                AuthorityIndex::new_for_test(2),
+                1,
+                &[AuthorityIndex::new_for_test(1)]
+            ),
+            None
+        );
+        // All leaders of a round stay distinct after swaps.
+        let leader_schedule = LeaderSchedule::new(context);
+        *leader_schedule.leader_swap_table.write() = leader_swap_table;
+        for round in 1..=10 {
+            let leaders: BTreeSet<_> = (0..3)
+                .map(|offset| leader_schedule.elect_leader(round, offset))
+                .collect();
+            assert_eq!(leaders.len(), 3);
+        }
+        // The bad node is swapped out when it is the first leader of the round.
+        assert_eq!(
+            leader_schedule.elect_leader(2, 0),
+            AuthorityIndex::new_for_test(1)
+        );
+        assert_eq!(
+            leader_schedule.elect_leader(2, 1),
+            AuthorityIndex::new_for_test(3)
+        );
+    }
+
+    #[test]
+    fn test_leader_swap_table_no_swaps() {
+        // Nobody is swapped out when all authorities score the same.
+        let context = context_with_swap_threshold();
+        let reputation_scores = ReputationScores::new(1..=10, vec![10; 4]);
+        assert_eq!(
+            LeaderSwapTable::new(context, &reputation_scores),
+            LeaderSwapTable::default()
+        );
+
+        // Nor when the protocol version does not enable leader scoring.
+        let (mut context, _) = Context::new_for_test(4);
+        context
+            .protocol_config
+            .set_mysticeti_leader_scoring_and_schedule_for_testing(false);
+        context
+            .protocol_config
+            .set_consensus_bad_nodes_stake_threshold(33);
+        let reputation_scores = ReputationScores::new(1..=10, vec![5, 10, 1, 7]);
+        assert_eq!(
+            LeaderSwapTable::new(Arc::new(context), &reputation_scores),
+            LeaderSwapTable::default()
+        );
+
+        // Nor when the protocol version does not set a threshold.
+        let committee = local_committee_and_keys(0, vec![1, 1, 1, 1]).0;
+        let context = Arc::new(Context::new(
+            AuthorityIndex::new_for_test(0),
+            committee,
+            Parameters::default(),
+            ProtocolConfig::get_for_min_version(),
+            test_metrics(),
+        ));
+        let reputation_scores = ReputationScores::new(1..=10, vec![5, 10, 1, 7]);
+        assert_eq!(
+            LeaderSwapTable::new(context, &reputation_scores),
+            LeaderSwapTable::default()
+        );
+    }
+
+    #[test]
+    fn test_update_leader_schedule() {
+        telemetry_subscribers::init_for_testing();
+        let context = context_with_swap_threshold();
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let leader_schedule = LeaderSchedule::new(context.clone()).with_num_commits_per_schedule(6);
+
+        // Authority 3 stops producing blocks after round 2.
+        let mut ancestors = build_dag(context.clone(), dag_state.clone(), None, 2);
+        for _round in 3..=10 {
+            let connections = (0..3)
+                .map(|author| (AuthorityIndex::new_for_test(author), ancestors.clone()))
+                .collect();
+            ancestors = build_dag_layer(connections, dag_state.clone());
+        }
+
+        // Leaders of rounds 1 to 8 make 6 commits, as authority 3 misses its slots in rounds
+        // 3 and 7. That is enough to update the leader schedule.
+        let leaders = get_all_leader_blocks(
+            dag_state.clone(),
+            leader_schedule.clone(),
+            8,
+            DEFAULT_WAVE_LENGTH,
+            true,
+            1,
+        );
+        let mut linearizer = Linearizer::new(dag_state.clone());
+        assert_eq!(linearizer.handle_commit(leaders).len(), 6);
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            0
+        );
+
+        leader_schedule.update_leader_schedule(dag_state.clone());
+
+        let expected_scores = ReputationScores::new(1..=6, vec![10, 10, 10, 1]);
+        assert_eq!(
+            dag_state.read().last_reputation_scores(),
+            Some(expected_scores.clone())
+        );
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            6
+        );
+
+        // The slots of authority 3 now go to authority 0, the other slots are unchanged.
+        assert_eq!(
+            leader_schedule.elect_leader(11, 0),
+            AuthorityIndex::new_for_test(0)
+        );
+        assert_eq!(
+            leader_schedule.elect_leader(10, 0),
+            AuthorityIndex::new_for_test(2)
+        );
+
+        // The scores are not persisted until the next flush. Restarting at this point
+        // recovers the same unscored commits, so the same update is made again.
+        let recovered_dag_state =
+            Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let recovered_schedule =
+            LeaderSchedule::from_store(context.clone(), recovered_dag_state.clone())
+                .with_num_commits_per_schedule(6);
+        assert_eq!(
+            recovered_schedule.commits_until_leader_schedule_update(recovered_dag_state.clone()),
+            0
+        );
+        recovered_schedule.update_leader_schedule(recovered_dag_state.clone());
+        assert_eq!(
+            *recovered_schedule.leader_swap_table.read(),
+            *leader_schedule.leader_swap_table.read()
+        );
+
+        // Once the next commit is flushed, the scores are recovered with it.
+        let leader = Slot::new(9, leader_schedule.elect_leader(9, 0));
+        let leader_block = dag_state.read().get_uncommitted_blocks_at_slot(leader);
+        assert_eq!(leader_block.len(), 1);
+        assert_eq!(leader_block[0].author(), AuthorityIndex::new_for_test(1));
+        assert_eq!(linearizer.handle_commit(leader_block).len(), 1);
+
+        let recovered_dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        assert_eq!(
+            recovered_dag_state.read().last_reputation_scores(),
+            Some(expected_scores)
+        );
+        let recovered_schedule = LeaderSchedule::from_store(context, recovered_dag_state.clone())
+            .with_num_commits_per_schedule(6);
+        assert_eq!(
+            recovered_schedule.commits_until_leader_schedule_update(recovered_dag_state),
+            5
+        );
+        assert_eq!(
+            *recovered_schedule.leader_swap_table.read(),
+            *leader_schedule.leader_swap_table.read()
+        );
+    }
 }
@@ -0,0 +1,290 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+
+use consensus_config::AuthorityIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{BlockAPI, BlockRef, Round, VerifiedBlock},
+    commit::{CommitIndex, CommittedSubDag},
+    context::Context,
+    stake_aggregator::{QuorumThreshold, StakeAggregator},
+};
+
+/// Reputation scores of all authorities, calculated over a range of commits.
+/// The scores are persisted together with the commit that triggered their
+/// calculation, so that the leader schedule derived from them can be recovered
+/// after a restart.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct ReputationScores {
+    /// Score per authority. Vec index is the AuthorityIndex.
+    pub(crate) scores_per_authority: Vec<u64>,
+    /// The range of commits these scores were calculated from (inclusive).
+    pub(crate) commit_range: RangeInclusive<CommitIndex>,
+}
+
+impl ReputationScores {
+    pub(crate) fn new(
+        commit_range: RangeInclusive<CommitIndex>,
+        scores_per_authority: Vec<u64>,
+    ) -> Self {
+        Self {
+            scores_per_authority,
+            commit_range,
+        }
+    }
+
+    /// Returns the authorities sorted by score in descending order. Ties are broken by
+    /// authority index, so the order is the same on every authority.
+    pub(crate) fn authorities_by_score_desc(
+        &self,
+        context: &Context,
+    ) -> Vec<(AuthorityIndex, u64)> {
+        let mut authorities: Vec<_> = self
+            .scores_per_authority
+            .iter()
+            .enumerate()
+            .map(|(index, score)| {
+                let authority_index = context.committee.to_authority_index(index).unwrap();
+                (authority_index, *score)
+            })
+            .collect();
+        authorities.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        authorities
+    }
+
+    pub(crate) fn update_metrics(&self, context: Arc<Context>) {
+        for (index, score) in self.scores_per_authority.iter().enumerate() {
+            let authority_index = context.committee.to_authority_index(index).unwrap();
+            let hostname = &context.committee.authority(authority_index).hostname;
+            context
+                .metrics
+                .node_metrics
+                .reputation_scores
+                .with_label_values(&[hostname])
+                .set(*score as i64);
+        }
+    }
+}
+
+/// Calculates the reputation scores of authorities from a sequence of committed sub-dags.
+///
+/// Only committed blocks are considered, so every authority computes the same scores for
+/// the same range of commits regardless of which other blocks it has accepted into its
+/// DagState. For every committed leader, an authority scores a point for each of its
+/// blocks in the following round that votes for the leader, and for each of its blocks
+/// two rounds later that certifies the leader, i.e. links to a quorum of those votes.
+/// Authorities that are slow or offline fail to get their blocks included in time and
+/// score accordingly lower.
+pub(crate) struct ReputationScoreCalculator<'a> {
+    context: Arc<Context>,
+    committed_subdags: &'a [CommittedSubDag],
+    commit_range: RangeInclusive<CommitIndex>,
+}
+
+impl<'a> ReputationScoreCalculator<'a> {
+    pub(crate) fn new(context: Arc<Context>, committed_subdags: &'a [CommittedSubDag]) -> Self {
+        assert!(
+            !committed_subdags.is_empty(),
+            "Cannot calculate reputation scores without any committed sub-dags"
+        );
+        let commit_range = committed_subdags.first().unwrap().commit_index
+            ..=committed_subdags.last().unwrap().commit_index;
+        Self {
+            context,
+            committed_subdags,
+            commit_range,
+        }
+    }
+
+    pub(crate) fn calculate(&self) -> ReputationScores {
+        let mut blocks_by_round: BTreeMap<Round, Vec<&VerifiedBlock>> = BTreeMap::new();
+        for block in self
+            .committed_subdags
+            .iter()
+            .flat_map(|subdag| subdag.blocks.iter())
+        {
+            blocks_by_round
+                .entry(block.round())
+                .or_default()
+                .push(block);
+        }
+
+        let mut scores_per_authority = vec![0_u64; self.context.committee.size()];
+        for subdag in self.committed_subdags {
+            let leader = subdag.leader;
+
+            let votes: HashMap<BlockRef, AuthorityIndex> = blocks_by_round
+                .get(&(leader.round + 1))
+                .into_iter()
+                .flatten()
+                .filter(|block| block.ancestors().contains(&leader))
+                .map(|block| (block.reference(), block.author()))
+                .collect();
+            for author in votes.values() {
+                scores_per_authority[*author] += 1;
+            }
+
+            for block in blocks_by_round
+                .get(&(leader.round + 2))
+                .into_iter()
+                .flatten()
+            {
+                if self.is_certificate(block, &votes) {
+                    scores_per_authority[block.author()] += 1;
+                }
+            }
+        }
+
+        ReputationScores::new(self.commit_range.clone(), scores_per_authority)
+    }
+
+    /// Returns true if `block` links to votes for the leader from a quorum of authorities.
+    fn is_certificate(
+        &self,
+        block: &VerifiedBlock,
+        votes: &HashMap<BlockRef, AuthorityIndex>,
+    ) -> bool {
+        let mut aggregator = StakeAggregator::<QuorumThreshold>::new();
+        for ancestor in block.ancestors() {
+            if let Some(author) = votes.get(ancestor) {
+                if aggregator.add(*author, &self.context.committee) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::RwLock;
+
+    use super::*;
+    use crate::{
+        commit::DEFAULT_WAVE_LENGTH,
+        dag_state::DagState,
+        leader_schedule::LeaderSchedule,
+        linearizer::Linearizer,
+        storage::mem_store::MemStore,
+        test_dag::{build_dag, build_dag_layer, get_all_leader_blocks},
+    };
+
+    /// Commits all the leaders of the dag up to and including `leader_rounds`, in order.
+    fn commit_leaders(
+        context: Arc<Context>,
+        dag_state: Arc<RwLock<DagState>>,
+        leader_rounds: u32,
+    ) -> Vec<CommittedSubDag> {
+        let leaders = get_all_leader_blocks(
+            dag_state.clone(),
+            LeaderSchedule::new(context),
+            leader_rounds,
+            DEFAULT_WAVE_LENGTH,
+            true,
+            1,
+        );
+        Linearizer::new(dag_state).handle_commit(leaders)
+    }
+
+    #[test]
+    fn test_reputation_scores_fully_connected_dag() {
+        telemetry_subscribers::init_for_testing();
+        let context = Arc::new(Context::new_for_test(4).0);
+        let dag_state = Arc::new(RwLock::new(DagState::new(
+            context.clone(),
+            Arc::new(MemStore::new()),
+        )));
+
+        build_dag(context.clone(), dag_state.clone(), None, 10);
+        let subdags = commit_leaders(context.clone(), dag_state, 8);
+        assert_eq!(subdags.len(), 8);
+
+        let scores = ReputationScoreCalculator::new(context, &subdags).calculate();
+
+        // Every authority votes for and certifies every leader, except that the votes for the
+        // leader of round 8 and the certificates of the leaders of rounds 7 and 8 have not been
+        // committed yet.
+        assert_eq!(scores.commit_range, 1..=8);
+        assert_eq!(scores.scores_per_authority, vec![7 + 6; 4]);
+    }
+
+    #[test]
+    fn test_reputation_scores_penalize_missing_authority() {
+        telemetry_subscribers::init_for_testing();
+        let context = Arc::new(Context::new_for_test(4).0);
+        let dag_state = Arc::new(RwLock::new(DagState::new(
+            context.clone(),
+            Arc::new(MemStore::new()),
+        )));
+
+        // Authority 3 stops producing blocks after round 2, so from then on the dag only
+        // advances through the remaining quorum of authorities.
+        let mut ancestors = build_dag(context.clone(), dag_state.clone(), None, 2);
+        for _round in 3..=10 {
+            let connections = (0..3)
+                .map(|author| (AuthorityIndex::new_for_test(author), ancestors.clone()))
+                .collect();
+            ancestors = build_dag_layer(connections, dag_state.clone());
+        }
+
+        // The leader slots of authority 3 in rounds 3 and 7 are empty.
+        let subdags = commit_leaders(context.clone(), dag_state, 8);
+        assert_eq!(subdags.len(), 6);
+
+        let scores = ReputationScoreCalculator::new(context.clone(), &subdags).calculate();
+
+        // Authority 3 only voted for the leader of round 1, while everyone else voted for and
+        // certified the leaders of rounds 1, 2, 4, 5 and 6.
+        assert_eq!(scores.scores_per_authority, vec![10, 10, 10, 1]);
+        assert_eq!(
+            scores.authorities_by_score_desc(&context).last(),
+            Some(&(AuthorityIndex::new_for_test(3), 1))
+        );
+    }
+
+    #[test]
+    fn test_reputation_scores_require_quorum_for_certificate() {
+        telemetry_subscribers::init_for_testing();
+        let context = Arc::new(Context::new_for_test(4).0);
+        let dag_state = Arc::new(RwLock::new(DagState::new(
+            context.clone(),
+            Arc::new(MemStore::new()),
+        )));
+
+        // In round 2, only authorities 0 and 1 vote for the leader of round 1.
+        let round_1 = build_dag(context.clone(), dag_state.clone(), None, 1);
+        let leader_1 = round_1[1];
+        let without_leader: Vec<_> = round_1
+            .iter()
+            .copied()
+            .filter(|block| *block != leader_1)
+            .collect();
+        let round_2 = build_dag_layer(
+            vec![
+                (AuthorityIndex::new_for_test(0), round_1.clone()),
+                (AuthorityIndex::new_for_test(1), round_1.clone()),
+                (AuthorityIndex::new_for_test(2), without_leader.clone()),
+                (AuthorityIndex::new_for_test(3), without_leader),
+            ],
+            dag_state.clone(),
+        );
+        build_dag(context.clone(), dag_state.clone(), Some(round_2), 5);
+
+        let subdags = commit_leaders(context.clone(), dag_state, 3);
+        let scores = ReputationScoreCalculator::new(context, &subdags).calculate();
+
+        // Authorities 0 and 1 voted for the leader of round 1, but two votes are not a quorum
+        // so no block of round 3 certifies it. The leader of round 2 (authority 2) only had
+        // its vote from authority 3 committed so far, as part of the leader of round 3.
+        assert_eq!(scores.commit_range, 1..=3);
+        assert_eq!(scores.scores_per_authority, vec![1, 1, 0, 1]);
+    }
+}
@@ -7,14 +7,18 @@ mod block;
 mod block_manager;
 mod block_verifier;
 mod broadcaster;
+#[cfg(test)]
+mod byzantine_harness;
 mod commit;
 mod commit_observer;
+mod commit_syncer;
 mod context;
 mod core;
 mod core_thread;
 mod dag_state;
 mod error;
 mod leader_schedule;
+mod leader_scoring;
 mod leader_timeout;
 mod linearizer;
 mod metrics;
@@ -30,5 +34,5 @@ mod universal_committer;
 
 pub use authority_node::ConsensusAuthority;
 pub use block::{BlockAPI, Round};
-pub use commit::{CommitConsumer, CommitIndex, CommittedSubDag};
+pub use commit::{CommitConsumer, CommitConsumerMonitor, CommitIndex, CommittedSubDag};
 pub use transaction::{TransactionClient, TransactionVerifier, ValidationError};
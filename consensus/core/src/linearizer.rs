@@ -23,13 +23,15 @@ impl Linearizer {
         Self { dag_state }
     }
 
-    /// Collect the sub-dag from a specific leader excluding any duplicates or
-    /// blocks that have already been committed (within previous sub-dags).
+    /// Collect the sub-dag from a specific leader excluding any duplicates,
+    /// blocks that have already been committed (within previous sub-dags), and
+    /// blocks at or below the GC round.
     fn collect_sub_dag(
         &mut self,
         leader_block: VerifiedBlock,
         last_commit_index: CommitIndex,
         last_committed_rounds: Vec<Round>,
+        gc_round: Round,
     ) -> CommittedSubDag {
         let mut to_commit = Vec::new();
         let mut committed = HashSet::new();
@@ -50,10 +52,11 @@ impl Linearizer {
                         .iter()
                         .copied()
                         .filter(|ancestor| {
-                            // We skip the block if we already committed it or we reached a
-                            // round that we already committed.
+                            // We skip the block if we already committed it, we reached a
+                            // round that we already committed, or it has been garbage collected.
                             !committed.contains(ancestor)
                                 && last_committed_rounds[ancestor.author] < ancestor.round
+                                && gc_round < ancestor.round
                         })
                         .collect::<Vec<_>>(),
                 )
@@ -90,6 +93,7 @@ impl Linearizer {
             let last_commit_index = dag_state.last_commit_index();
             let last_commit_digest = dag_state.last_commit_digest();
             let mut last_committed_rounds = dag_state.last_committed_rounds();
+            let gc_round = dag_state.gc_round();
             drop(dag_state);
 
             // Collect the sub-dag generated using each of these leaders.
@@ -97,6 +101,7 @@ impl Linearizer {
                 leader_block,
                 last_commit_index,
                 last_committed_rounds.clone(),
+                gc_round,
             );
 
             // [Optional] sort the sub-dag using a deterministic algorithm.
@@ -121,7 +126,12 @@ impl Linearizer {
                 .serialize()
                 .unwrap_or_else(|e| panic!("Failed to serialize commit: {}", e));
             let commit = TrustedCommit::new_trusted(commit, serialized);
-            self.dag_state.write().add_commit(commit.clone());
+            let mut dag_state = self.dag_state.write();
+            dag_state.add_commit(commit.clone());
+            // Sub-dags are also kept for calculating reputation scores at the next leader
+            // schedule update.
+            dag_state.add_unscored_committed_subdags(vec![sub_dag.clone()]);
+            drop(dag_state);
             committed_sub_dags.push(sub_dag);
         }
         // Committed blocks must be persisted to storage before sending them to Sui and executing
@@ -140,6 +150,7 @@ impl Linearizer {
 mod tests {
     use super::*;
     use crate::{
+        block::{BlockRef, TestBlock},
         commit::{CommitAPI as _, CommitDigest, DEFAULT_WAVE_LENGTH},
         context::Context,
         leader_schedule::LeaderSchedule,
@@ -332,4 +343,64 @@ mod tests {
             assert!(block.round() <= expected_second_commit.leader().round);
         }
     }
+
+    #[test]
+    fn test_handle_commit_skips_garbage_collected_blocks() {
+        telemetry_subscribers::init_for_testing();
+        let (mut context, _) = Context::new_for_test(4);
+        context
+            .protocol_config
+            .set_consensus_gc_depth_for_testing(2);
+        let context = Arc::new(context);
+        let dag_state = Arc::new(RwLock::new(DagState::new(
+            context.clone(),
+            Arc::new(MemStore::new()),
+        )));
+        let mut linearizer = Linearizer::new(dag_state.clone());
+
+        // Authorities 0 ~ 2 only link to each other's blocks, so the round 1 block of
+        // authority 3 is not included in the first commit.
+        let round_1: Vec<_> = (0..4)
+            .map(|author| VerifiedBlock::new_for_test(TestBlock::new(1, author).build()))
+            .collect();
+        dag_state.write().accept_blocks(round_1.clone());
+        let uncommitted = round_1[3].reference();
+        let mut ancestors: Vec<BlockRef> = round_1[..3].iter().map(|b| b.reference()).collect();
+        let mut leaders = vec![];
+        for round in 2..=6 {
+            let blocks: Vec<_> = (0..3)
+                .map(|author| {
+                    let mut block_ancestors = ancestors.clone();
+                    // The leader of round 6 links to the uncommitted block.
+                    if round == 6 && author == 0 {
+                        block_ancestors.push(uncommitted);
+                    }
+                    VerifiedBlock::new_for_test(
+                        TestBlock::new(round, author)
+                            .set_ancestors(block_ancestors)
+                            .build(),
+                    )
+                })
+                .collect();
+            dag_state.write().accept_blocks(blocks.clone());
+            ancestors = blocks.iter().map(|b| b.reference()).collect();
+            if round % DEFAULT_WAVE_LENGTH == 0 {
+                leaders.push(blocks[0].clone());
+            }
+        }
+
+        // After the first commit at round 3, the GC round is 3 - 2 = 1, so the uncommitted
+        // block is excluded from the second commit.
+        let commits = linearizer.handle_commit(leaders);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].blocks.len(), 7);
+        assert_eq!(commits[1].blocks.len(), 9);
+        for subdag in &commits {
+            assert!(subdag
+                .blocks
+                .iter()
+                .all(|block| block.reference() != uncommitted));
+        }
+        assert_eq!(dag_state.read().gc_round(), 4);
+    }
 }
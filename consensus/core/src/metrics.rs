@@ -45,11 +45,15 @@ pub(crate) struct NodeMetrics {
     pub threshold_clock_round: IntGauge,
     pub suspended_blocks: IntCounterVec,
     pub unsuspended_blocks: IntCounterVec,
+    pub gc_suspended_blocks: IntCounterVec,
     pub invalid_blocks: IntCounterVec,
     pub block_timestamp_drift_wait_ms: IntCounterVec,
     pub broadcaster_rtt_estimate_ms: IntGaugeVec,
     pub dag_state_store_read_count: IntCounterVec,
     pub dag_state_store_write_count: IntCounter,
+    pub dag_state_gc_round: IntGauge,
+    pub dag_state_pruned_blocks: IntCounter,
+    pub dag_state_pruned_commits: IntCounter,
     pub last_decided_leader_round: IntGauge,
     pub last_committed_leader_round: IntGauge,
     pub decided_leaders_total: IntCounterVec,
@@ -58,6 +62,11 @@ pub(crate) struct NodeMetrics {
     pub block_commit_latency: Histogram,
     pub fetched_blocks: IntCounterVec,
     pub fetch_blocks_scheduler_inflight: IntGauge,
+    pub reputation_scores: IntGaugeVec,
+    pub leader_schedule_updates: IntCounter,
+    pub commit_sync_quorum_index: IntGauge,
+    pub commit_sync_fetched_commits: IntCounterVec,
+    pub commit_sync_fetch_failures: IntCounterVec,
 }
 
 impl NodeMetrics {
@@ -111,6 +120,12 @@ impl NodeMetrics {
                 &["authority"],
                 registry,
             ).unwrap(),
+            gc_suspended_blocks: register_int_counter_vec_with_registry!(
+                "gc_suspended_blocks",
+                "The number of suspended blocks that were garbage collected before being accepted",
+                &["authority"],
+                registry,
+            ).unwrap(),
             // TODO: add a short status label.
             invalid_blocks: register_int_counter_vec_with_registry!(
                 "invalid_blocks",
@@ -146,6 +161,24 @@ impl NodeMetrics {
                 registry,
             )
             .unwrap(),
+            dag_state_gc_round: register_int_gauge_with_registry!(
+                "dag_state_gc_round",
+                "The round at or below which blocks are garbage collected by DagState",
+                registry,
+            )
+            .unwrap(),
+            dag_state_pruned_blocks: register_int_counter_with_registry!(
+                "dag_state_pruned_blocks",
+                "Number of blocks removed from store by DagState garbage collection",
+                registry,
+            )
+            .unwrap(),
+            dag_state_pruned_commits: register_int_counter_with_registry!(
+                "dag_state_pruned_commits",
+                "Number of commits removed from store by DagState garbage collection",
+                registry,
+            )
+            .unwrap(),
             last_decided_leader_round: register_int_gauge_with_registry!(
                 "last_decided_leader_round",
                 "The last round where a commit decision was made.",
@@ -191,7 +224,35 @@ impl NodeMetrics {
                 "fetch_blocks_scheduler_inflight",
                 "Designates whether the synchronizer scheduler task to fetch blocks is currently running",
                 registry,
-            ).unwrap()
+            ).unwrap(),
+            reputation_scores: register_int_gauge_vec_with_registry!(
+                "reputation_scores",
+                "Reputation scores per authority, from the last leader schedule update",
+                &["authority"],
+                registry,
+            ).unwrap(),
+            leader_schedule_updates: register_int_counter_with_registry!(
+                "leader_schedule_updates",
+                "Number of times the leader schedule has been updated from reputation scores",
+                registry,
+            ).unwrap(),
+            commit_sync_quorum_index: register_int_gauge_with_registry!(
+                "commit_sync_quorum_index",
+                "The highest commit index voted by a quorum of authorities, as observed by the commit syncer",
+                registry,
+            ).unwrap(),
+            commit_sync_fetched_commits: register_int_counter_vec_with_registry!(
+                "commit_sync_fetched_commits",
+                "Number of certified commits fetched per peer authority via the commit syncer",
+                &["authority"],
+                registry,
+            ).unwrap(),
+            commit_sync_fetch_failures: register_int_counter_vec_with_registry!(
+                "commit_sync_fetch_failures",
+                "Number of failed attempts to fetch certified commits per peer authority",
+                &["authority"],
+                registry,
+            ).unwrap(),
         }
     }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::BTreeMap, panic, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, ops::RangeInclusive, panic, sync::Arc, time::Duration};
 
 use anemo::{types::PeerInfo, PeerId, Response};
 use anemo_tower::auth::{AllowedPeers, RequireAuthorizationLayer};
@@ -19,11 +19,12 @@ use super::{
         consensus_rpc_client::ConsensusRpcClient,
         consensus_rpc_server::{ConsensusRpc, ConsensusRpcServer},
     },
-    FetchBlocksRequest, FetchBlocksResponse, NetworkClient, NetworkManager, NetworkService,
-    SendBlockRequest, SendBlockResponse,
+    FetchBlocksRequest, FetchBlocksResponse, FetchCommitsRequest, FetchCommitsResponse,
+    NetworkClient, NetworkManager, NetworkService, SendBlockRequest, SendBlockResponse,
 };
 use crate::{
     block::BlockRef,
+    commit::CommitIndex,
     context::Context,
     error::{ConsensusError, ConsensusResult},
 };
@@ -38,6 +39,7 @@ impl AnemoClient {
     const GET_CLIENT_INTERVAL: Duration = Duration::from_millis(10);
     const SEND_BLOCK_TIMEOUT: Duration = Duration::from_secs(5);
     const FETCH_BLOCK_TIMEOUT: Duration = Duration::from_secs(15);
+    const FETCH_COMMITS_TIMEOUT: Duration = Duration::from_secs(30);
 
     pub(crate) fn new(context: Arc<Context>) -> Self {
         Self {
@@ -140,6 +142,26 @@ impl NetworkClient for AnemoClient {
             .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?;
         Ok(response.into_body().blocks)
     }
+
+    async fn fetch_commits(
+        &self,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+        let mut client = self
+            .get_anemo_client(peer, Self::FETCH_COMMITS_TIMEOUT)
+            .await?;
+        let request = FetchCommitsRequest {
+            start: *commit_range.start(),
+            end: *commit_range.end(),
+        };
+        let response = client
+            .fetch_commits(anemo::Request::new(request).with_timeout(Self::FETCH_COMMITS_TIMEOUT))
+            .await
+            .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?;
+        let response = response.into_body();
+        Ok((response.commits, response.certifier_blocks))
+    }
 }
 
 /// Proxies Anemo RPC handlers to AnemoService.
@@ -222,6 +244,39 @@ impl<S: NetworkService> ConsensusRpc for AnemoServiceProxy<S> {
             })?;
         Ok(Response::new(FetchBlocksResponse { blocks }))
     }
+
+    async fn fetch_commits(
+        &self,
+        request: anemo::Request<FetchCommitsRequest>,
+    ) -> Result<anemo::Response<FetchCommitsResponse>, anemo::rpc::Status> {
+        let Some(peer_id) = request.peer_id() else {
+            return Err(anemo::rpc::Status::new_with_message(
+                anemo::types::response::StatusCode::BadRequest,
+                "peer_id not found",
+            ));
+        };
+        let index = self.peer_map.get(peer_id).ok_or_else(|| {
+            anemo::rpc::Status::new_with_message(
+                anemo::types::response::StatusCode::BadRequest,
+                "peer not found",
+            )
+        })?;
+        let request = request.into_body();
+        let (commits, certifier_blocks) = self
+            .service
+            .handle_fetch_commits(*index, request.start..=request.end)
+            .await
+            .map_err(|e| {
+                anemo::rpc::Status::new_with_message(
+                    anemo::types::response::StatusCode::BadRequest,
+                    format!("{e}"),
+                )
+            })?;
+        Ok(Response::new(FetchCommitsResponse {
+            commits,
+            certifier_blocks,
+        }))
+    }
 }
 
 /// Manages the lifecycle of Anemo network. Typical usage during initialization:
@@ -374,7 +429,7 @@ impl<S: NetworkService> NetworkManager<S> for AnemoManager {
 
 #[cfg(test)]
 mod test {
-    use std::sync::Arc;
+    use std::{ops::RangeInclusive, sync::Arc};
 
     use async_trait::async_trait;
     use bytes::Bytes;
@@ -384,6 +439,7 @@ mod test {
 
     use crate::{
         block::BlockRef,
+        commit::CommitIndex,
         context::Context,
         error::ConsensusResult,
         network::{anemo_network::AnemoManager, NetworkClient, NetworkManager, NetworkService},
@@ -422,6 +478,14 @@ mod test {
             self.lock().handle_fetch_blocks.push((peer, block_refs));
             Ok(vec![])
         }
+
+        async fn handle_fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            _commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            unimplemented!("Unimplemented")
+        }
     }
 
     #[tokio::test(flavor = "current_thread", start_paused = true)]
@@ -1,21 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use consensus_config::{AuthorityIndex, NetworkKeyPair};
 use serde::{Deserialize, Serialize};
 
-use crate::{block::BlockRef, context::Context, error::ConsensusResult};
+use crate::{block::BlockRef, commit::CommitIndex, context::Context, error::ConsensusResult};
 
 // Anemo generated stubs for RPCs.
 mod anemo_gen {
     include!(concat!(env!("OUT_DIR"), "/consensus.ConsensusRpc.rs"));
 }
 
+// Tonic generated stubs for RPCs.
+mod tonic_gen {
+    include!(concat!(env!("OUT_DIR"), "/consensus.ConsensusService.rs"));
+}
+
 pub(crate) mod anemo_network;
+pub(crate) mod tonic_network;
 
 /// Network client for communicating with peers.
 #[async_trait]
@@ -29,11 +35,20 @@ pub(crate) trait NetworkClient: Send + Sync + 'static {
         peer: AuthorityIndex,
         block_refs: Vec<BlockRef>,
     ) -> ConsensusResult<Vec<Bytes>>;
+
+    /// Fetches serialized `Commit`s in the range from a peer, together with the serialized
+    /// `SignedBlock`s that certify the last returned commit.
+    async fn fetch_commits(
+        &self,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)>;
 }
 
 /// Network service for handling requests from peers.
-/// NOTE: using `async_trait` macro because `NetworkService` methods are called in the trait impl
-/// of `anemo_gen::ConsensusRpc`, which itself is annotated with `async_trait`.
+/// NOTE: using `async_trait` macro because `NetworkService` methods are called in the trait impls
+/// of `anemo_gen::ConsensusRpc` and `tonic_gen::ConsensusService`, which are annotated with
+/// `async_trait`.
 #[async_trait]
 pub(crate) trait NetworkService: Send + Sync + 'static {
     async fn handle_send_block(&self, peer: AuthorityIndex, block: Bytes) -> ConsensusResult<()>;
@@ -42,6 +57,11 @@ pub(crate) trait NetworkService: Send + Sync + 'static {
         peer: AuthorityIndex,
         block_refs: Vec<BlockRef>,
     ) -> ConsensusResult<Vec<Bytes>>;
+    async fn handle_fetch_commits(
+        &self,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)>;
 }
 
 /// An `AuthorityNode` holds a `NetworkManager` until shutdown.
@@ -82,3 +102,15 @@ pub(crate) struct FetchBlocksResponse {
     // Serialized SignedBlock.
     blocks: Vec<Bytes>,
 }
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct FetchCommitsRequest {
+    start: CommitIndex,
+    end: CommitIndex,
+}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct FetchCommitsResponse {
+    // Serialized consecutive Commit.
+    commits: Vec<Bytes>,
+    // Serialized SignedBlock that certify the last commit from above.
+    certifier_blocks: Vec<Bytes>,
+}
@@ -0,0 +1,695 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Duration,
+};
+
+use arc_swap::ArcSwapOption;
+use async_trait::async_trait;
+use bytes::Bytes;
+use cfg_if::cfg_if;
+use consensus_config::{AuthorityIndex, NetworkKeyPair, NetworkPublicKey};
+use fastcrypto::traits::KeyPair as _;
+use mysten_network::Multiaddr;
+use parking_lot::{Mutex, RwLock};
+use sui_tls::{
+    rustls, CertVerifier, HashSetAllow, SelfSignedCertificate, ServerCertVerifier,
+    SUI_VALIDATOR_SERVER_NAME,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{
+    transport::{Channel, Endpoint, Server, Uri},
+    Request, Response, Status, Streaming,
+};
+use tracing::{debug, info, warn};
+
+use super::{
+    tonic_gen::{
+        consensus_service_client::ConsensusServiceClient,
+        consensus_service_server::{ConsensusService, ConsensusServiceServer},
+    },
+    FetchBlocksRequest, FetchBlocksResponse, FetchCommitsRequest, FetchCommitsResponse,
+    NetworkClient, NetworkManager, NetworkService, SendBlockRequest, SendBlockResponse,
+};
+use crate::{
+    block::BlockRef,
+    commit::CommitIndex,
+    context::Context,
+    error::{ConsensusError, ConsensusResult},
+};
+
+/// Implements Tonic RPC client for Consensus.
+///
+/// Blocks are sent to each peer over a long lived stream, so `send_block()` returns once the block
+/// is queued on the stream. Blocks lost when a stream breaks are fetched by the synchronizer of
+/// the peer.
+pub(crate) struct TonicClient {
+    context: Arc<Context>,
+    // Set when the network service is installed, since it is needed for TLS.
+    network_keypair: ArcSwapOption<NetworkKeyPair>,
+    channels: RwLock<BTreeMap<AuthorityIndex, Channel>>,
+    // Senders of the block streams to peers. Each vector index corresponds to the authority
+    // with the index.
+    block_streams: Vec<Mutex<Option<mpsc::Sender<SendBlockRequest>>>>,
+}
+
+impl TonicClient {
+    const GET_CLIENT_INTERVAL: Duration = Duration::from_millis(10);
+    const SEND_BLOCK_TIMEOUT: Duration = Duration::from_secs(5);
+    const FETCH_BLOCK_TIMEOUT: Duration = Duration::from_secs(15);
+    const FETCH_COMMITS_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        let block_streams = (0..context.committee.size())
+            .map(|_| Mutex::new(None))
+            .collect();
+        Self {
+            context,
+            network_keypair: ArcSwapOption::default(),
+            channels: RwLock::new(BTreeMap::new()),
+            block_streams,
+        }
+    }
+
+    pub(crate) fn set_network_keypair(&self, network_keypair: NetworkKeyPair) {
+        self.network_keypair.store(Some(Arc::new(network_keypair)));
+    }
+
+    /// Drops connections and block streams to all peers.
+    fn disconnect(&self) {
+        for block_stream in &self.block_streams {
+            block_stream.lock().take();
+        }
+        self.channels.write().clear();
+    }
+
+    async fn get_client(
+        &self,
+        peer: AuthorityIndex,
+        timeout: Duration,
+    ) -> ConsensusResult<ConsensusServiceClient<Channel>> {
+        let channel = self.get_channel(peer, timeout).await?;
+        let message_size_limit = self.context.parameters.tonic.message_size_limit;
+        Ok(ConsensusServiceClient::new(channel)
+            .max_encoding_message_size(message_size_limit)
+            .max_decoding_message_size(message_size_limit))
+    }
+
+    async fn get_channel(
+        &self,
+        peer: AuthorityIndex,
+        timeout: Duration,
+    ) -> ConsensusResult<Channel> {
+        if let Some(channel) = self.channels.read().get(&peer) {
+            return Ok(channel.clone());
+        }
+
+        let network_keypair = loop {
+            if let Some(network_keypair) = self.network_keypair.load_full() {
+                break network_keypair;
+            } else {
+                tokio::time::sleep(Self::GET_CLIENT_INTERVAL).await;
+            }
+        };
+
+        let authority = self.context.committee.authority(peer);
+        let address = to_host_port_str(&authority.address).map_err(|e| {
+            ConsensusError::NetworkError(format!("Invalid address {}: {e}", authority.address))
+        })?;
+        let tls_connector = TlsConnector::from(Arc::new(create_rustls_client_config(
+            network_keypair.copy(),
+            authority.network_key.clone(),
+        )));
+        let keepalive_interval = self.context.parameters.tonic.keepalive_interval;
+        let endpoint = Endpoint::from_shared(format!("http://{address}"))
+            .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?
+            .http2_keep_alive_interval(keepalive_interval)
+            .keep_alive_timeout(keepalive_interval)
+            .keep_alive_while_idle(true)
+            .tcp_nodelay(true);
+        // Connections are made over TLS with the network keys, instead of using certificate
+        // authorities.
+        let connector = tower::service_fn(move |_uri: Uri| {
+            let address = address.clone();
+            let tls_connector = tls_connector.clone();
+            async move {
+                let stream = TcpStream::connect(address).await?;
+                stream.set_nodelay(true)?;
+                let server_name = rustls::ServerName::try_from(SUI_VALIDATOR_SERVER_NAME)
+                    .expect("Server name should be valid");
+                tls_connector.connect(server_name, stream).await
+            }
+        });
+        let channel = tokio::time::timeout(timeout, endpoint.connect_with_connector(connector))
+            .await
+            .map_err(|_| ConsensusError::PeerDisconnected(format!("{peer}")))?
+            .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?;
+
+        self.channels.write().insert(peer, channel.clone());
+        Ok(channel)
+    }
+
+    async fn get_block_stream(
+        &self,
+        peer: AuthorityIndex,
+        timeout: Duration,
+    ) -> ConsensusResult<mpsc::Sender<SendBlockRequest>> {
+        if let Some(sender) = self.block_streams[peer].lock().as_ref() {
+            if !sender.is_closed() {
+                return Ok(sender.clone());
+            }
+        }
+
+        let mut client = self.get_client(peer, timeout).await?;
+        let (sender, receiver) =
+            mpsc::channel(self.context.parameters.tonic.block_stream_buffer_size);
+        // The stream stays open until the sender is dropped or the connection breaks. When the
+        // RPC finishes the receiver is dropped, so the stream gets recreated on the next send.
+        tokio::spawn(async move {
+            if let Err(e) = client.send_blocks(ReceiverStream::new(receiver)).await {
+                debug!("Block stream to {peer} terminated: {e:?}");
+            }
+        });
+        *self.block_streams[peer].lock() = Some(sender.clone());
+        Ok(sender)
+    }
+}
+
+#[async_trait]
+impl NetworkClient for TonicClient {
+    async fn send_block(&self, peer: AuthorityIndex, block: &Bytes) -> ConsensusResult<()> {
+        let sender = self
+            .get_block_stream(peer, Self::SEND_BLOCK_TIMEOUT)
+            .await?;
+        let request = SendBlockRequest {
+            block: block.clone(),
+        };
+        sender
+            .send(request)
+            .await
+            .map_err(|_| ConsensusError::NetworkError(format!("Block stream to {peer} closed")))
+    }
+
+    async fn fetch_blocks(
+        &self,
+        peer: AuthorityIndex,
+        block_refs: Vec<BlockRef>,
+    ) -> ConsensusResult<Vec<Bytes>> {
+        let mut client = self.get_client(peer, Self::FETCH_BLOCK_TIMEOUT).await?;
+        let mut request = Request::new(FetchBlocksRequest { block_refs });
+        request.set_timeout(Self::FETCH_BLOCK_TIMEOUT);
+        let response = client
+            .fetch_blocks(request)
+            .await
+            .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?;
+        Ok(response.into_inner().blocks)
+    }
+
+    async fn fetch_commits(
+        &self,
+        peer: AuthorityIndex,
+        commit_range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+        let mut client = self.get_client(peer, Self::FETCH_COMMITS_TIMEOUT).await?;
+        let mut request = Request::new(FetchCommitsRequest {
+            start: *commit_range.start(),
+            end: *commit_range.end(),
+        });
+        request.set_timeout(Self::FETCH_COMMITS_TIMEOUT);
+        let response = client
+            .fetch_commits(request)
+            .await
+            .map_err(|e| ConsensusError::NetworkError(format!("{e:?}")))?;
+        let response = response.into_inner();
+        Ok((response.commits, response.certifier_blocks))
+    }
+}
+
+/// Proxies Tonic RPC handlers to `NetworkService`.
+struct TonicServiceProxy<S: NetworkService> {
+    peer_map: HashMap<NetworkPublicKey, AuthorityIndex>,
+    service: Arc<S>,
+}
+
+impl<S: NetworkService> TonicServiceProxy<S> {
+    fn new(context: Arc<Context>, service: Arc<S>) -> Self {
+        let peer_map = context
+            .committee
+            .authorities()
+            .map(|(index, authority)| (authority.network_key.clone(), index))
+            .collect();
+        Self { peer_map, service }
+    }
+
+    /// Identifies the peer by the public key of its TLS certificate.
+    fn peer_index<T>(&self, request: &Request<T>) -> Result<AuthorityIndex, Status> {
+        let certificates = request
+            .peer_certs()
+            .ok_or_else(|| Status::unauthenticated("peer certificate not found"))?;
+        let certificate = certificates
+            .first()
+            .ok_or_else(|| Status::unauthenticated("peer certificate not found"))?;
+        let public_key = sui_tls::public_key_from_certificate(&rustls::Certificate(
+            certificate.get_ref().to_vec(),
+        ))
+        .map_err(|e| Status::unauthenticated(format!("{e}")))?;
+        self.peer_map
+            .get(&public_key)
+            .copied()
+            .ok_or_else(|| Status::permission_denied("peer not found"))
+    }
+}
+
+#[async_trait]
+impl<S: NetworkService> ConsensusService for TonicServiceProxy<S> {
+    async fn send_blocks(
+        &self,
+        request: Request<Streaming<SendBlockRequest>>,
+    ) -> Result<Response<SendBlockResponse>, Status> {
+        let peer = self.peer_index(&request)?;
+        let mut stream = request.into_inner();
+        while let Some(request) = stream.message().await? {
+            // An invalid block does not terminate the stream, so later blocks from the peer
+            // can still be received.
+            if let Err(e) = self.service.handle_send_block(peer, request.block).await {
+                warn!("Failed to handle block from {peer}: {e}");
+            }
+        }
+        Ok(Response::new(SendBlockResponse {}))
+    }
+
+    async fn fetch_blocks(
+        &self,
+        request: Request<FetchBlocksRequest>,
+    ) -> Result<Response<FetchBlocksResponse>, Status> {
+        let peer = self.peer_index(&request)?;
+        let block_refs = request.into_inner().block_refs;
+        let blocks = self
+            .service
+            .handle_fetch_blocks(peer, block_refs)
+            .await
+            .map_err(|e| Status::invalid_argument(format!("{e}")))?;
+        Ok(Response::new(FetchBlocksResponse { blocks }))
+    }
+
+    async fn fetch_commits(
+        &self,
+        request: Request<FetchCommitsRequest>,
+    ) -> Result<Response<FetchCommitsResponse>, Status> {
+        let peer = self.peer_index(&request)?;
+        let request = request.into_inner();
+        let (commits, certifier_blocks) = self
+            .service
+            .handle_fetch_commits(peer, request.start..=request.end)
+            .await
+            .map_err(|e| Status::invalid_argument(format!("{e}")))?;
+        Ok(Response::new(FetchCommitsResponse {
+            commits,
+            certifier_blocks,
+        }))
+    }
+}
+
+/// Manages the lifecycle of Tonic network. It is used in the same way as `AnemoManager`.
+pub(crate) struct TonicManager {
+    context: Arc<Context>,
+    client: Arc<TonicClient>,
+    server: Mutex<Option<TonicServer>>,
+}
+
+struct TonicServer {
+    shutdown: oneshot::Sender<()>,
+    accept_task: JoinHandle<()>,
+    server_task: JoinHandle<()>,
+}
+
+impl TonicManager {
+    const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+    const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        Self {
+            context: context.clone(),
+            client: Arc::new(TonicClient::new(context)),
+            server: Mutex::new(None),
+        }
+    }
+}
+
+impl<S: NetworkService> NetworkManager<S> for TonicManager {
+    type Client = TonicClient;
+
+    fn new(context: Arc<Context>) -> Self {
+        TonicManager::new(context)
+    }
+
+    fn client(&self) -> Arc<Self::Client> {
+        self.client.clone()
+    }
+
+    async fn install_service(&self, network_keypair: NetworkKeyPair, service: Arc<S>) {
+        let authority = self.context.committee.authority(self.context.own_index);
+        // Bind to localhost in unit tests since only local networking is needed.
+        // Bind to the unspecified address to allow the actual address to be assigned,
+        // in simtest and production.
+        cfg_if!(
+            if #[cfg(test)] {
+                let address = authority.address.localhost_ip_multi_address();
+            } else {
+                let address = authority.address.zero_ip_multi_address();
+            }
+        );
+        let address = to_host_port_str(&address).unwrap_or_else(|e| panic!("{e}: {address}"));
+
+        let mut retries_left = 90;
+        let listener = loop {
+            match TcpListener::bind(&address).await {
+                Ok(listener) => break listener,
+                Err(e) => {
+                    retries_left -= 1;
+                    if retries_left <= 0 {
+                        panic!("Failed to bind Tonic server at {address}! Last error: {e:#?}");
+                    }
+                    warn!(
+                        "Address {address} should be available for the Consensus service, retrying in one second: {e:#?}",
+                    );
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        };
+
+        // TLS handshakes run in their own tasks, so slow peers cannot block accepting
+        // connections from other peers.
+        let tls_acceptor = TlsAcceptor::from(Arc::new(create_rustls_server_config(
+            &self.context,
+            network_keypair.copy(),
+        )));
+        let (connection_sender, connection_receiver) = mpsc::channel(100);
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (stream, peer_address) = match listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        warn!("Failed to accept connection: {e:?}");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                if let Err(e) = stream.set_nodelay(true) {
+                    debug!("Failed to set nodelay for {peer_address}: {e:?}");
+                }
+                let tls_acceptor = tls_acceptor.clone();
+                let connection_sender = connection_sender.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(
+                        Self::TLS_HANDSHAKE_TIMEOUT,
+                        tls_acceptor.accept(stream),
+                    )
+                    .await
+                    {
+                        Ok(Ok(stream)) => {
+                            let _ = connection_sender
+                                .send(Ok::<_, std::io::Error>(stream))
+                                .await;
+                        }
+                        Ok(Err(e)) => debug!("TLS handshake with {peer_address} failed: {e:?}"),
+                        Err(_) => debug!("TLS handshake with {peer_address} timed out"),
+                    }
+                });
+            }
+        });
+
+        let message_size_limit = self.context.parameters.tonic.message_size_limit;
+        let keepalive_interval = self.context.parameters.tonic.keepalive_interval;
+        let consensus_service =
+            ConsensusServiceServer::new(TonicServiceProxy::new(self.context.clone(), service))
+                .max_encoding_message_size(message_size_limit)
+                .max_decoding_message_size(message_size_limit);
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let server = Server::builder()
+            .http2_keepalive_interval(Some(keepalive_interval))
+            .http2_keepalive_timeout(Some(keepalive_interval))
+            .add_service(consensus_service)
+            .serve_with_incoming_shutdown(ReceiverStream::new(connection_receiver), async move {
+                let _ = shutdown_receiver.await;
+            });
+        let server_task = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                warn!("Tonic server failed: {e:?}");
+            }
+        });
+        info!("Started Tonic server at {address}");
+
+        self.client.set_network_keypair(network_keypair);
+        *self.server.lock() = Some(TonicServer {
+            shutdown: shutdown_sender,
+            accept_task,
+            server_task,
+        });
+    }
+
+    async fn stop(&self) {
+        let server = self.server.lock().take();
+        if let Some(mut server) = server {
+            server.accept_task.abort();
+            let _ = server.shutdown.send(());
+            // Block streams from peers stay open until the peers close them, so graceful
+            // shutdown is bounded by a timeout.
+            if tokio::time::timeout(Self::SHUTDOWN_TIMEOUT, &mut server.server_task)
+                .await
+                .is_err()
+            {
+                server.server_task.abort();
+            }
+        }
+        self.client.disconnect();
+    }
+}
+
+/// Returns `host:port` of an authority address. Tonic network uses TCP on the port of the
+/// authority address.
+fn to_host_port_str(address: &Multiaddr) -> Result<String, &'static str> {
+    let host = address.hostname().ok_or("Hostname not found")?;
+    let port = address.port().ok_or("Port not found")?;
+    if host.contains(':') {
+        // IPv6 address.
+        Ok(format!("[{host}]:{port}"))
+    } else {
+        Ok(format!("{host}:{port}"))
+    }
+}
+
+/// Accepts connections from authorities in the committee, identified by their network keys.
+fn create_rustls_server_config(
+    context: &Context,
+    network_keypair: NetworkKeyPair,
+) -> rustls::ServerConfig {
+    let allower = HashSetAllow::new();
+    allower.inner().write().unwrap().extend(
+        context
+            .committee
+            .authorities()
+            .map(|(_i, authority)| authority.network_key.clone()),
+    );
+    let certificate =
+        SelfSignedCertificate::new(network_keypair.private(), SUI_VALIDATOR_SERVER_NAME);
+    CertVerifier::new(allower)
+        .rustls_server_config(
+            vec![certificate.rustls_certificate()],
+            certificate.rustls_private_key(),
+        )
+        .expect("Failed to create TLS server config")
+}
+
+/// Connects only to the authority with the target network key.
+fn create_rustls_client_config(
+    network_keypair: NetworkKeyPair,
+    target_public_key: NetworkPublicKey,
+) -> rustls::ClientConfig {
+    let certificate =
+        SelfSignedCertificate::new(network_keypair.private(), SUI_VALIDATOR_SERVER_NAME);
+    ServerCertVerifier::new(target_public_key, SUI_VALIDATOR_SERVER_NAME.to_string())
+        .rustls_client_config(
+            vec![certificate.rustls_certificate()],
+            certificate.rustls_private_key(),
+        )
+        .expect("Failed to create TLS client config")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use consensus_config::AuthorityIndex;
+    use fastcrypto::traits::KeyPair;
+    use parking_lot::Mutex;
+
+    use crate::{
+        block::BlockRef,
+        commit::CommitIndex,
+        context::Context,
+        error::ConsensusResult,
+        network::{tonic_network::TonicManager, NetworkClient, NetworkManager, NetworkService},
+    };
+
+    struct TestService {
+        handle_send_block: Vec<(AuthorityIndex, Bytes)>,
+        handle_fetch_blocks: Vec<(AuthorityIndex, Vec<BlockRef>)>,
+    }
+
+    impl TestService {
+        pub(crate) fn new() -> Self {
+            Self {
+                handle_send_block: Vec::new(),
+                handle_fetch_blocks: Vec::new(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl NetworkService for Mutex<TestService> {
+        async fn handle_send_block(
+            &self,
+            peer: AuthorityIndex,
+            block: Bytes,
+        ) -> ConsensusResult<()> {
+            self.lock().handle_send_block.push((peer, block));
+            Ok(())
+        }
+
+        async fn handle_fetch_blocks(
+            &self,
+            peer: AuthorityIndex,
+            block_refs: Vec<BlockRef>,
+        ) -> ConsensusResult<Vec<Bytes>> {
+            self.lock().handle_fetch_blocks.push((peer, block_refs));
+            Ok(vec![Bytes::from_static(b"block")])
+        }
+
+        async fn handle_fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            _commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            unimplemented!("Unimplemented")
+        }
+    }
+
+    /// Waits until the service has received the given number of blocks.
+    async fn wait_for_blocks(service: &Mutex<TestService>, num_blocks: usize) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while service.lock().handle_send_block.len() < num_blocks {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for blocks");
+    }
+
+    #[tokio::test]
+    async fn test_basics() {
+        let (context, keys) = Context::new_for_test(4);
+
+        let context_0 = Arc::new(
+            context
+                .clone()
+                .with_authority_index(context.committee.to_authority_index(0).unwrap()),
+        );
+        let manager_0 = TonicManager::new(context_0.clone());
+        let client_0 = <TonicManager as NetworkManager<Mutex<TestService>>>::client(&manager_0);
+        let service_0 = Arc::new(Mutex::new(TestService::new()));
+        manager_0
+            .install_service(keys[0].0.copy(), service_0.clone())
+            .await;
+
+        let context_1 = Arc::new(
+            context
+                .clone()
+                .with_authority_index(context.committee.to_authority_index(1).unwrap()),
+        );
+        let manager_1 = TonicManager::new(context_1.clone());
+        let client_1 = <TonicManager as NetworkManager<Mutex<TestService>>>::client(&manager_1);
+        let service_1 = Arc::new(Mutex::new(TestService::new()));
+        manager_1
+            .install_service(keys[1].0.copy(), service_1.clone())
+            .await;
+
+        // Test that servers can receive blocks streamed from clients.
+        client_0
+            .send_block(
+                context.committee.to_authority_index(1).unwrap(),
+                &Bytes::from_static(b"msg 0"),
+            )
+            .await
+            .unwrap();
+        client_0
+            .send_block(
+                context.committee.to_authority_index(1).unwrap(),
+                &Bytes::from_static(b"msg 1"),
+            )
+            .await
+            .unwrap();
+        client_1
+            .send_block(
+                context.committee.to_authority_index(0).unwrap(),
+                &Bytes::from_static(b"msg 2"),
+            )
+            .await
+            .unwrap();
+        wait_for_blocks(&service_1, 2).await;
+        wait_for_blocks(&service_0, 1).await;
+        assert_eq!(service_1.lock().handle_send_block[0].0.value(), 0);
+        assert_eq!(
+            service_1.lock().handle_send_block[1].1,
+            Bytes::from_static(b"msg 1")
+        );
+        assert_eq!(service_0.lock().handle_send_block[0].0.value(), 1);
+
+        // Test that servers can respond to unary RPCs.
+        let blocks = client_1
+            .fetch_blocks(context.committee.to_authority_index(0).unwrap(), vec![])
+            .await
+            .unwrap();
+        assert_eq!(blocks, vec![Bytes::from_static(b"block")]);
+        assert_eq!(service_0.lock().handle_fetch_blocks.len(), 1);
+        assert_eq!(service_0.lock().handle_fetch_blocks[0].0.value(), 1);
+
+        // `Committee` is generated with the same random seed in Context::new_for_test(),
+        // so the first 4 authorities are the same.
+        let (context_4, keys_4) = Context::new_for_test(5);
+        let context_4 = Arc::new(
+            context_4
+                .clone()
+                .with_authority_index(context_4.committee.to_authority_index(4).unwrap()),
+        );
+        let manager_4 = TonicManager::new(context_4.clone());
+        let client_4 = <TonicManager as NetworkManager<Mutex<TestService>>>::client(&manager_4);
+        let service_4 = Arc::new(Mutex::new(TestService::new()));
+        manager_4
+            .install_service(keys_4[4].0.copy(), service_4.clone())
+            .await;
+
+        // client_4 should not be able to reach service_0, because its network key is not in
+        // the committee of authority 0.
+        assert!(client_4
+            .fetch_blocks(context.committee.to_authority_index(0).unwrap(), vec![])
+            .await
+            .is_err());
+
+        <TonicManager as NetworkManager<Mutex<TestService>>>::stop(&manager_0).await;
+        <TonicManager as NetworkManager<Mutex<TestService>>>::stop(&manager_1).await;
+        <TonicManager as NetworkManager<Mutex<TestService>>>::stop(&manager_4).await;
+    }
+}
@@ -16,8 +16,9 @@ use crate::block::Slot;
 use crate::commit::{CommitAPI as _, TrustedCommit};
 use crate::{
     block::{BlockAPI as _, BlockDigest, BlockRef, Round, VerifiedBlock},
-    commit::{CommitDigest, CommitIndex},
+    commit::{CommitDigest, CommitIndex, CommitRef},
     error::ConsensusResult,
+    leader_scoring::ReputationScores,
 };
 
 /// In-memory storage for testing.
@@ -31,6 +32,7 @@ struct Inner {
     commits: BTreeMap<(CommitIndex, CommitDigest), TrustedCommit>,
     commit_votes: BTreeSet<(CommitIndex, CommitDigest, BlockRef)>,
     commit_info: BTreeMap<(CommitIndex, CommitDigest), CommitInfo>,
+    reputation_scores: BTreeMap<CommitIndex, ReputationScores>,
 }
 
 impl MemStore {
@@ -43,6 +45,7 @@ impl MemStore {
                 commits: BTreeMap::new(),
                 commit_votes: BTreeSet::new(),
                 commit_info: BTreeMap::new(),
+                reputation_scores: BTreeMap::new(),
             }),
         }
     }
@@ -82,6 +85,11 @@ impl Store for MemStore {
                 .commit_info
                 .insert((last_commit.index(), last_commit.digest()), commit_info);
         }
+        if let Some(reputation_scores) = write_batch.reputation_scores {
+            inner
+                .reputation_scores
+                .insert(*reputation_scores.commit_range.end(), reputation_scores);
+        }
         Ok(())
     }
 
@@ -172,6 +180,56 @@ impl Store for MemStore {
         Ok(blocks)
     }
 
+    fn prune_blocks_by_author(
+        &self,
+        author: AuthorityIndex,
+        rounds: Range<Round>,
+    ) -> ConsensusResult<usize> {
+        let mut inner = self.inner.write();
+        let keys: Vec<_> = inner
+            .digests_by_authorities
+            .range((
+                Included((author, rounds.start, BlockDigest::MIN)),
+                Excluded((author, rounds.end, BlockDigest::MIN)),
+            ))
+            .cloned()
+            .collect();
+        for (author, round, digest) in &keys {
+            inner
+                .digests_by_authorities
+                .remove(&(*author, *round, *digest));
+            let Some(block) = inner.blocks.remove(&(*round, *author, *digest)) else {
+                panic!("Block {:?} not found!", (author, round, digest));
+            };
+            let block_ref = block.reference();
+            for commit in block.commit_votes() {
+                inner
+                    .commit_votes
+                    .remove(&(commit.index, commit.digest, block_ref));
+            }
+        }
+        Ok(keys.len())
+    }
+
+    fn prune_commits(&self, end: CommitIndex) -> ConsensusResult<usize> {
+        let mut inner = self.inner.write();
+        let commits = inner.commits.split_off(&(end, CommitDigest::MIN));
+        let pruned = std::mem::replace(&mut inner.commits, commits).len();
+        let commit_votes =
+            inner
+                .commit_votes
+                .split_off(&(end, CommitDigest::MIN, BlockRef::default()));
+        inner.commit_votes = commit_votes;
+        let commit_info = inner.commit_info.split_off(&(end, CommitDigest::MIN));
+        inner.commit_info = commit_info;
+        // The last reputation scores are needed to recover the leader schedule.
+        if let Some(last_index) = inner.reputation_scores.last_key_value().map(|(k, _)| *k) {
+            let reputation_scores = inner.reputation_scores.split_off(&end.min(last_index));
+            inner.reputation_scores = reputation_scores;
+        }
+        Ok(pruned)
+    }
+
     fn read_last_commit(&self) -> ConsensusResult<Option<TrustedCommit>> {
         let inner = self.inner.read();
         Ok(inner
@@ -192,8 +250,34 @@ impl Store for MemStore {
         Ok(commits)
     }
 
+    fn read_commit_votes(&self, commit_ref: CommitRef) -> ConsensusResult<Vec<BlockRef>> {
+        let inner = self.inner.read();
+        let votes = inner
+            .commit_votes
+            .range((
+                Included((commit_ref.index, commit_ref.digest, BlockRef::default())),
+                Excluded((
+                    commit_ref.index.saturating_add(1),
+                    CommitDigest::MIN,
+                    BlockRef::default(),
+                )),
+            ))
+            .take_while(|(_, digest, _)| *digest == commit_ref.digest)
+            .map(|(_, _, block_ref)| *block_ref)
+            .collect();
+        Ok(votes)
+    }
+
     fn read_last_commit_info(&self) -> ConsensusResult<Option<CommitInfo>> {
         let inner = self.inner.read();
         Ok(inner.commit_info.last_key_value().map(|(_k, v)| v.clone()))
     }
+
+    fn read_last_reputation_scores(&self) -> ConsensusResult<Option<ReputationScores>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .reputation_scores
+            .last_key_value()
+            .map(|(_k, v)| v.clone()))
+    }
 }
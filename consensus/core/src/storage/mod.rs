@@ -15,8 +15,9 @@ use serde::{Deserialize, Serialize};
 use crate::block::Slot;
 use crate::{
     block::{BlockRef, Round, VerifiedBlock},
-    commit::{CommitIndex, TrustedCommit},
+    commit::{CommitIndex, CommitRef, TrustedCommit},
     error::ConsensusResult,
+    leader_scoring::ReputationScores,
 };
 
 /// A common interface for consensus storage.
@@ -50,14 +51,33 @@ pub(crate) trait Store: Send + Sync {
         before_round: Option<Round>,
     ) -> ConsensusResult<Vec<VerifiedBlock>>;
 
+    /// Removes blocks of an authority with rounds in the given range, together with their
+    /// commit votes. Returns the number of removed blocks.
+    fn prune_blocks_by_author(
+        &self,
+        author: AuthorityIndex,
+        rounds: Range<Round>,
+    ) -> ConsensusResult<usize>;
+
+    /// Removes commits with indices below `end`, together with their votes and commit info.
+    /// Reputation scores below `end` are removed as well, except the last ones. Returns the
+    /// number of removed commits.
+    fn prune_commits(&self, end: CommitIndex) -> ConsensusResult<usize>;
+
     /// Reads the last commit.
     fn read_last_commit(&self) -> ConsensusResult<Option<TrustedCommit>>;
 
     /// Reads all commits from start (inclusive) until end (exclusive).
     fn scan_commits(&self, range: Range<CommitIndex>) -> ConsensusResult<Vec<TrustedCommit>>;
 
+    /// Reads the refs of stored blocks that vote for the given commit.
+    fn read_commit_votes(&self, commit_ref: CommitRef) -> ConsensusResult<Vec<BlockRef>>;
+
     /// Reads the last commit info, including last committed round per authority.
     fn read_last_commit_info(&self) -> ConsensusResult<Option<CommitInfo>>;
+
+    /// Reads the reputation scores of the last leader schedule update.
+    fn read_last_reputation_scores(&self) -> ConsensusResult<Option<ReputationScores>>;
 }
 
 /// Represents data to be written to the store together atomically.
@@ -66,6 +86,9 @@ pub(crate) struct WriteBatch {
     pub(crate) blocks: Vec<VerifiedBlock>,
     pub(crate) commits: Vec<TrustedCommit>,
     pub(crate) last_committed_rounds: Vec<Round>,
+    /// Reputation scores of a leader schedule update, written under the last commit index
+    /// they cover.
+    pub(crate) reputation_scores: Option<ReputationScores>,
 }
 
 impl WriteBatch {
@@ -73,11 +96,13 @@ impl WriteBatch {
         blocks: Vec<VerifiedBlock>,
         commits: Vec<TrustedCommit>,
         last_committed_rounds: Vec<Round>,
+        reputation_scores: Option<ReputationScores>,
     ) -> Self {
         WriteBatch {
             blocks,
             commits,
             last_committed_rounds,
+            reputation_scores,
         }
     }
 
@@ -4,7 +4,7 @@
 use std::collections::VecDeque;
 use std::ops::Range;
 use std::{
-    ops::Bound::{Excluded, Included},
+    ops::Bound::{Excluded, Included, Unbounded},
     time::Duration,
 };
 
@@ -19,11 +19,12 @@ use typed_store::{
 
 use super::{CommitInfo, Store, WriteBatch};
 use crate::block::Slot;
-use crate::commit::{CommitAPI as _, CommitDigest, TrustedCommit};
+use crate::commit::{CommitAPI as _, CommitDigest, CommitRef, TrustedCommit};
 use crate::{
     block::{BlockAPI as _, BlockDigest, BlockRef, Round, SignedBlock, VerifiedBlock},
     commit::CommitIndex,
     error::{ConsensusError, ConsensusResult},
+    leader_scoring::ReputationScores,
 };
 
 /// Persistent storage with RocksDB.
@@ -39,6 +40,8 @@ pub(crate) struct RocksDBStore {
     commit_votes: DBMap<(CommitIndex, CommitDigest, BlockRef), ()>,
     /// Stores the latest values of a few properties.
     commit_info: DBMap<(CommitIndex, CommitDigest), CommitInfo>,
+    /// Maps the last commit index covered by reputation scores to the scores.
+    reputation_scores: DBMap<CommitIndex, ReputationScores>,
 }
 
 impl RocksDBStore {
@@ -47,6 +50,7 @@ impl RocksDBStore {
     const COMMITS_CF: &'static str = "commits";
     const COMMIT_VOTES_CF: &'static str = "commit_votes";
     const COMMIT_INFO_CF: &'static str = "commit_info";
+    const REPUTATION_SCORES_CF: &'static str = "reputation_scores";
 
     /// Creates a new instance of RocksDB storage.
     pub(crate) fn new(path: &str) -> Self {
@@ -70,6 +74,7 @@ impl RocksDBStore {
             (Self::COMMITS_CF, cf_options.clone()),
             (Self::COMMIT_VOTES_CF, cf_options.clone()),
             (Self::COMMIT_INFO_CF, cf_options.clone()),
+            (Self::REPUTATION_SCORES_CF, cf_options.clone()),
         ];
         let rocksdb = open_cf_opts(
             path,
@@ -79,12 +84,13 @@ impl RocksDBStore {
         )
         .expect("Cannot open database");
 
-        let (blocks, digests_by_authorities, commits, commit_votes, commit_info) = reopen!(&rocksdb,
+        let (blocks, digests_by_authorities, commits, commit_votes, commit_info, reputation_scores) = reopen!(&rocksdb,
             Self::BLOCKS_CF;<(Round, AuthorityIndex, BlockDigest), bytes::Bytes>,
             Self::DIGESTS_BY_AUTHORITIES_CF;<(AuthorityIndex, Round, BlockDigest), ()>,
             Self::COMMITS_CF;<(CommitIndex, CommitDigest), Bytes>,
             Self::COMMIT_VOTES_CF;<(CommitIndex, CommitDigest, BlockRef), ()>,
-            Self::COMMIT_INFO_CF;<(CommitIndex, CommitDigest), CommitInfo>
+            Self::COMMIT_INFO_CF;<(CommitIndex, CommitDigest), CommitInfo>,
+            Self::REPUTATION_SCORES_CF;<CommitIndex, ReputationScores>
         );
 
         Self {
@@ -93,6 +99,7 @@ impl RocksDBStore {
             commits,
             commit_votes,
             commit_info,
+            reputation_scores,
         }
     }
 }
@@ -145,6 +152,14 @@ impl Store for RocksDBStore {
                 )
                 .map_err(ConsensusError::RocksDBFailure)?;
         }
+        if let Some(reputation_scores) = write_batch.reputation_scores {
+            batch
+                .insert_batch(
+                    &self.reputation_scores,
+                    [(*reputation_scores.commit_range.end(), reputation_scores)],
+                )
+                .map_err(ConsensusError::RocksDBFailure)?;
+        }
         batch.write()?;
         Ok(())
     }
@@ -250,6 +265,95 @@ impl Store for RocksDBStore {
         Ok(blocks)
     }
 
+    fn prune_blocks_by_author(
+        &self,
+        author: AuthorityIndex,
+        rounds: Range<Round>,
+    ) -> ConsensusResult<usize> {
+        let mut refs = vec![];
+        for kv in self.digests_by_authorities.safe_range_iter((
+            Included((author, rounds.start, BlockDigest::MIN)),
+            Excluded((author, rounds.end, BlockDigest::MIN)),
+        )) {
+            let ((author, round, digest), _) = kv?;
+            refs.push(BlockRef::new(round, author, digest));
+        }
+        if refs.is_empty() {
+            return Ok(0);
+        }
+        // Blocks are read to find the commit votes indexed for them.
+        let blocks = self.read_blocks(refs.as_slice())?;
+        let mut batch = self.blocks.batch();
+        for (r, block) in refs.iter().zip(blocks.into_iter()) {
+            let block =
+                block.unwrap_or_else(|| panic!("Storage inconsistency: block {:?} not found!", r));
+            batch
+                .delete_batch(&self.blocks, [(r.round, r.author, r.digest)])
+                .map_err(ConsensusError::RocksDBFailure)?;
+            batch
+                .delete_batch(
+                    &self.digests_by_authorities,
+                    [(r.author, r.round, r.digest)],
+                )
+                .map_err(ConsensusError::RocksDBFailure)?;
+            for commit in block.commit_votes() {
+                batch
+                    .delete_batch(&self.commit_votes, [(commit.index, commit.digest, *r)])
+                    .map_err(ConsensusError::RocksDBFailure)?;
+            }
+        }
+        batch.write()?;
+        Ok(refs.len())
+    }
+
+    fn prune_commits(&self, end: CommitIndex) -> ConsensusResult<usize> {
+        let mut batch = self.commits.batch();
+        let mut pruned = 0;
+        for kv in self
+            .commits
+            .safe_range_iter((Unbounded, Excluded((end, CommitDigest::MIN))))
+        {
+            let (key, _) = kv?;
+            batch
+                .delete_batch(&self.commits, [key])
+                .map_err(ConsensusError::RocksDBFailure)?;
+            pruned += 1;
+        }
+        for kv in self.commit_votes.safe_range_iter((
+            Unbounded,
+            Excluded((end, CommitDigest::MIN, BlockRef::default())),
+        )) {
+            let (key, _) = kv?;
+            batch
+                .delete_batch(&self.commit_votes, [key])
+                .map_err(ConsensusError::RocksDBFailure)?;
+        }
+        for kv in self
+            .commit_info
+            .safe_range_iter((Unbounded, Excluded((end, CommitDigest::MIN))))
+        {
+            let (key, _) = kv?;
+            batch
+                .delete_batch(&self.commit_info, [key])
+                .map_err(ConsensusError::RocksDBFailure)?;
+        }
+        // The last reputation scores are needed to recover the leader schedule.
+        if let Some(result) = self.reputation_scores.safe_iter().skip_to_last().next() {
+            let (last_index, _) = result.map_err(ConsensusError::RocksDBFailure)?;
+            for kv in self
+                .reputation_scores
+                .safe_range_iter(..end.min(last_index))
+            {
+                let (key, _) = kv?;
+                batch
+                    .delete_batch(&self.reputation_scores, [key])
+                    .map_err(ConsensusError::RocksDBFailure)?;
+            }
+        }
+        batch.write()?;
+        Ok(pruned)
+    }
+
     fn read_last_commit(&self) -> ConsensusResult<Option<TrustedCommit>> {
         let Some(result) = self.commits.safe_iter().skip_to_last().next() else {
             return Ok(None);
@@ -280,6 +384,25 @@ impl Store for RocksDBStore {
         Ok(commits)
     }
 
+    fn read_commit_votes(&self, commit_ref: CommitRef) -> ConsensusResult<Vec<BlockRef>> {
+        let mut votes = vec![];
+        for kv in self.commit_votes.safe_range_iter((
+            Included((commit_ref.index, commit_ref.digest, BlockRef::default())),
+            Excluded((
+                commit_ref.index.saturating_add(1),
+                CommitDigest::MIN,
+                BlockRef::default(),
+            )),
+        )) {
+            let ((_index, digest, block_ref), _) = kv?;
+            if digest != commit_ref.digest {
+                break;
+            }
+            votes.push(block_ref);
+        }
+        Ok(votes)
+    }
+
     fn read_last_commit_info(&self) -> ConsensusResult<Option<CommitInfo>> {
         let Some(result) = self.commit_info.safe_iter().skip_to_last().next() else {
             return Ok(None);
@@ -287,4 +410,12 @@ impl Store for RocksDBStore {
         let (_, commit_info) = result.map_err(ConsensusError::RocksDBFailure)?;
         Ok(Some(commit_info))
     }
+
+    fn read_last_reputation_scores(&self) -> ConsensusResult<Option<ReputationScores>> {
+        let Some(result) = self.reputation_scores.safe_iter().skip_to_last().next() else {
+            return Ok(None);
+        };
+        let (_, reputation_scores) = result.map_err(ConsensusError::RocksDBFailure)?;
+        Ok(Some(reputation_scores))
+    }
 }
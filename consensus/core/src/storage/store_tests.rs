@@ -8,7 +8,8 @@ use tempfile::TempDir;
 use super::{mem_store::MemStore, rocksdb_store::RocksDBStore, Store, WriteBatch};
 use crate::{
     block::{BlockAPI, BlockDigest, BlockRef, Slot, TestBlock, VerifiedBlock},
-    commit::{CommitDigest, TrustedCommit},
+    commit::{CommitDigest, CommitRef, TrustedCommit},
+    leader_scoring::ReputationScores,
 };
 
 /// Test fixture for store tests. Wraps around various store implementations.
@@ -293,3 +294,221 @@ async fn read_and_scan_commits(
         assert_eq!(scanned_commits, written_commits,);
     }
 }
+
+#[rstest]
+#[tokio::test]
+async fn read_commit_votes(
+    #[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore,
+) {
+    let store = test_store.store();
+
+    let commit_1 = TrustedCommit::new_for_test(
+        1,
+        CommitDigest::MIN,
+        BlockRef::new(1, AuthorityIndex::new_for_test(0), BlockDigest::default()),
+        vec![],
+    );
+    let commit_2 = TrustedCommit::new_for_test(
+        2,
+        commit_1.digest(),
+        BlockRef::new(2, AuthorityIndex::new_for_test(0), BlockDigest::default()),
+        vec![],
+    );
+    // A conflicting commit at index 2, which must not be confused with commit_2.
+    let conflicting_commit_2 = TrustedCommit::new_for_test(
+        2,
+        commit_1.digest(),
+        BlockRef::new(2, AuthorityIndex::new_for_test(1), BlockDigest::default()),
+        vec![],
+    );
+
+    let written_blocks: Vec<VerifiedBlock> = vec![
+        VerifiedBlock::new_for_test(
+            TestBlock::new(3, 0)
+                .set_commit_votes(vec![commit_1.reference(), commit_2.reference()])
+                .build(),
+        ),
+        VerifiedBlock::new_for_test(
+            TestBlock::new(3, 1)
+                .set_commit_votes(vec![commit_2.reference()])
+                .build(),
+        ),
+        VerifiedBlock::new_for_test(
+            TestBlock::new(3, 2)
+                .set_commit_votes(vec![conflicting_commit_2.reference()])
+                .build(),
+        ),
+    ];
+    store
+        .write(WriteBatch::default().blocks(written_blocks.clone()))
+        .unwrap();
+
+    let votes = store
+        .read_commit_votes(commit_1.reference())
+        .expect("Read commit votes should not fail");
+    assert_eq!(votes, vec![written_blocks[0].reference()]);
+
+    let mut votes = store
+        .read_commit_votes(commit_2.reference())
+        .expect("Read commit votes should not fail");
+    votes.sort();
+    let mut expected = vec![written_blocks[0].reference(), written_blocks[1].reference()];
+    expected.sort();
+    assert_eq!(votes, expected);
+
+    let votes = store
+        .read_commit_votes(conflicting_commit_2.reference())
+        .expect("Read commit votes should not fail");
+    assert_eq!(votes, vec![written_blocks[2].reference()]);
+
+    let votes = store
+        .read_commit_votes(CommitRef {
+            index: 3,
+            digest: CommitDigest::MIN,
+        })
+        .expect("Read commit votes should not fail");
+    assert!(votes.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn prune_blocks(
+    #[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore,
+) {
+    let store = test_store.store();
+
+    let commit_1 = TrustedCommit::new_for_test(
+        1,
+        CommitDigest::MIN,
+        BlockRef::new(1, AuthorityIndex::new_for_test(0), BlockDigest::default()),
+        vec![],
+    );
+    let written_blocks: Vec<VerifiedBlock> = vec![
+        VerifiedBlock::new_for_test(
+            TestBlock::new(2, 1)
+                .set_commit_votes(vec![commit_1.reference()])
+                .build(),
+        ),
+        VerifiedBlock::new_for_test(
+            TestBlock::new(2, 2)
+                .set_commit_votes(vec![commit_1.reference()])
+                .build(),
+        ),
+        VerifiedBlock::new_for_test(TestBlock::new(3, 1).build()),
+        VerifiedBlock::new_for_test(TestBlock::new(4, 1).build()),
+        VerifiedBlock::new_for_test(TestBlock::new(5, 1).build()),
+    ];
+    store
+        .write(WriteBatch::default().blocks(written_blocks.clone()))
+        .unwrap();
+
+    let pruned = store
+        .prune_blocks_by_author(AuthorityIndex::new_for_test(1), 0..4)
+        .expect("Prune blocks should not fail");
+    assert_eq!(pruned, 2);
+
+    // Pruned blocks and their commit votes are removed. Blocks of other authorities or
+    // outside the range are kept.
+    let refs: Vec<_> = written_blocks.iter().map(|b| b.reference()).collect();
+    assert_eq!(
+        store.contains_blocks(&refs).unwrap(),
+        vec![false, true, false, true, true]
+    );
+    assert_eq!(
+        store.read_commit_votes(commit_1.reference()).unwrap(),
+        vec![written_blocks[1].reference()]
+    );
+    assert_eq!(
+        store
+            .scan_blocks_by_author(AuthorityIndex::new_for_test(1), 0)
+            .unwrap(),
+        vec![written_blocks[3].clone(), written_blocks[4].clone()]
+    );
+
+    // Pruning an already pruned range is a no-op.
+    let pruned = store
+        .prune_blocks_by_author(AuthorityIndex::new_for_test(1), 0..4)
+        .expect("Prune blocks should not fail");
+    assert_eq!(pruned, 0);
+}
+
+#[rstest]
+#[tokio::test]
+async fn prune_commits(
+    #[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore,
+) {
+    let store = test_store.store();
+
+    let commits: Vec<_> = (1..=4)
+        .map(|index| {
+            TrustedCommit::new_for_test(
+                index,
+                CommitDigest::MIN,
+                BlockRef::new(
+                    index,
+                    AuthorityIndex::new_for_test(0),
+                    BlockDigest::default(),
+                ),
+                vec![],
+            )
+        })
+        .collect();
+    let block = VerifiedBlock::new_for_test(
+        TestBlock::new(5, 1)
+            .set_commit_votes(vec![commits[0].reference(), commits[3].reference()])
+            .build(),
+    );
+    let reputation_scores = ReputationScores::new(1..=2, vec![1, 2, 3, 4]);
+    store
+        .write(WriteBatch::new(
+            vec![],
+            commits[..2].to_vec(),
+            vec![2, 0, 0, 0],
+            Some(reputation_scores.clone()),
+        ))
+        .unwrap();
+    store
+        .write(WriteBatch::new(
+            vec![block.clone()],
+            commits[2..].to_vec(),
+            vec![4, 0, 0, 0],
+            None,
+        ))
+        .unwrap();
+
+    let pruned = store
+        .prune_commits(4)
+        .expect("Prune commits should not fail");
+    assert_eq!(pruned, 3);
+
+    // Commits below the end and their votes are removed, while the last commit, commit info
+    // and reputation scores are kept.
+    assert_eq!(store.scan_commits(0..5).unwrap(), vec![commits[3].clone()]);
+    assert_eq!(store.read_last_commit().unwrap().as_ref(), commits.last());
+    assert!(store
+        .read_commit_votes(commits[0].reference())
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store.read_commit_votes(commits[3].reference()).unwrap(),
+        vec![block.reference()]
+    );
+    assert_eq!(
+        store
+            .read_last_commit_info()
+            .unwrap()
+            .unwrap()
+            .last_committed_rounds,
+        vec![4, 0, 0, 0]
+    );
+    assert_eq!(
+        store.read_last_reputation_scores().unwrap(),
+        Some(reputation_scores)
+    );
+
+    // Pruning already pruned commits is a no-op.
+    let pruned = store
+        .prune_commits(4)
+        .expect("Prune commits should not fail");
+    assert_eq!(pruned, 0);
+}
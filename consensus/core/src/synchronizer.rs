@@ -34,7 +34,7 @@ const FETCH_REQUEST_TIMEOUT: Duration = Duration::from_millis(2_000);
 
 const FETCH_FROM_PEERS_TIMEOUT: Duration = Duration::from_millis(4_000);
 
-const MAX_FETCH_BLOCKS_PER_REQUEST: usize = 200;
+pub(crate) const MAX_FETCH_BLOCKS_PER_REQUEST: usize = 200;
 
 enum Command {
     FetchBlocks {
@@ -480,6 +480,7 @@ impl<C: NetworkClient, V: BlockVerifier, D: CoreThreadDispatcher> Synchronizer<C
 mod tests {
     use crate::block::{BlockRef, Round, TestBlock, VerifiedBlock};
     use crate::block_verifier::NoopBlockVerifier;
+    use crate::commit::{CommitIndex, TrustedCommit};
     use crate::context::Context;
     use crate::core_thread::{CoreError, CoreThreadDispatcher};
     use crate::error::{ConsensusError, ConsensusResult};
@@ -489,6 +490,7 @@ mod tests {
     use bytes::Bytes;
     use consensus_config::AuthorityIndex;
     use std::collections::{BTreeMap, BTreeSet};
+    use std::ops::RangeInclusive;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::time::sleep;
@@ -533,6 +535,14 @@ mod tests {
             lock.clear();
             Ok(result)
         }
+
+        async fn add_certified_commits(
+            &self,
+            _commits: Vec<TrustedCommit>,
+            _blocks: Vec<VerifiedBlock>,
+        ) -> Result<BTreeSet<BlockRef>, CoreError> {
+            todo!()
+        }
     }
 
     type FetchRequestKey = (Vec<BlockRef>, AuthorityIndex);
@@ -593,6 +603,14 @@ mod tests {
 
             Ok(serialised)
         }
+
+        async fn fetch_commits(
+            &self,
+            _peer: AuthorityIndex,
+            _commit_range: RangeInclusive<CommitIndex>,
+        ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+            todo!()
+        }
     }
 
     #[tokio::test]
@@ -0,0 +1,159 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use consensus_config::AuthorityIndex;
+use sui_macros::sim_test;
+use sui_protocol_config::ProtocolConfig;
+
+use crate::{
+    block::Round,
+    byzantine_harness::{AuthorityReport, ByzantineBehavior, ByzantineHarness},
+};
+
+const NUM_ROUNDS: Round = 20;
+
+/// Checks that every honest authority keeps committing despite the Byzantine authorities.
+fn check_liveness(reports: &[AuthorityReport]) {
+    for report in reports {
+        assert!(
+            report.num_commits >= (NUM_ROUNDS / 2) as usize,
+            "Authority {} is not live: {report:?}",
+            report.authority
+        );
+    }
+}
+
+/// Baseline of the harness without Byzantine authorities.
+#[sim_test]
+async fn test_all_honest() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(4, vec![]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    assert_eq!(reports.len(), 4);
+    for report in reports {
+        // Leaders up to round NUM_ROUNDS - 3 are certified by the delivered blocks.
+        assert!(
+            report.num_commits >= (NUM_ROUNDS - 3) as usize,
+            "{report:?}"
+        );
+        assert_eq!(report.rejected_blocks, 0);
+        assert_eq!(report.missing_blocks, 0);
+    }
+}
+
+#[sim_test]
+async fn test_equivocation() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(4, vec![(3, ByzantineBehavior::Equivocate)]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    check_liveness(&reports);
+    for report in reports {
+        // Conflicting blocks are valid on their own, and are accepted when fetched.
+        assert!(report.accepted_byzantine_blocks > 0, "{report:?}");
+    }
+}
+
+#[sim_test]
+async fn test_withhold_blocks() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(4, vec![(3, ByzantineBehavior::WithholdBlocks)]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    check_liveness(&reports);
+    for report in reports {
+        // Withheld blocks are fetched from the honest authority receiving them.
+        assert!(report.accepted_byzantine_blocks > 0, "{report:?}");
+    }
+}
+
+#[sim_test]
+async fn test_invalid_ancestors() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(4, vec![(3, ByzantineBehavior::InvalidAncestors)]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    check_liveness(&reports);
+    for report in reports {
+        assert_eq!(report.accepted_byzantine_blocks, 0, "{report:?}");
+        assert!(report.rejected_blocks > 0, "{report:?}");
+        // Blocks with ancestors that do not exist stay suspended.
+        assert!(report.missing_blocks > 0, "{report:?}");
+    }
+}
+
+#[sim_test]
+async fn test_invalid_timestamps() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(4, vec![(3, ByzantineBehavior::InvalidTimestamps)]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    check_liveness(&reports);
+    for report in reports {
+        // Only the block of round 1 has a valid timestamp.
+        assert_eq!(report.accepted_byzantine_blocks, 1, "{report:?}");
+        // Blocks of rounds 2 to NUM_ROUNDS - 1 have been delivered and rejected.
+        assert!(
+            report.rejected_blocks >= (NUM_ROUNDS - 2) as u64,
+            "{report:?}"
+        );
+    }
+}
+
+#[sim_test]
+async fn test_oversized_transactions() {
+    telemetry_subscribers::init_for_testing();
+    let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+        config.set_consensus_max_transaction_size_bytes(1_000);
+        config.set_consensus_max_transactions_in_block_bytes(5_000);
+        config
+    });
+    let mut harness = ByzantineHarness::new(4, vec![(3, ByzantineBehavior::OversizedTransactions)]);
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    check_liveness(&reports);
+    for report in reports {
+        assert_eq!(report.accepted_byzantine_blocks, 0, "{report:?}");
+        // Blocks of rounds 1 to NUM_ROUNDS - 1 have been delivered and rejected.
+        assert!(
+            report.rejected_blocks >= (NUM_ROUNDS - 1) as u64,
+            "{report:?}"
+        );
+    }
+}
+
+/// Runs the maximum number of Byzantine authorities tolerated by a committee of 7, and checks
+/// the results are the same across runs under the simulator.
+#[sim_test(check_determinism)]
+async fn test_multiple_byzantine_authorities() {
+    telemetry_subscribers::init_for_testing();
+    let mut harness = ByzantineHarness::new(
+        7,
+        vec![
+            (5, ByzantineBehavior::Equivocate),
+            (6, ByzantineBehavior::WithholdBlocks),
+        ],
+    );
+    harness.run(NUM_ROUNDS);
+
+    harness.check_safety();
+    let reports = harness.report();
+    assert_eq!(reports.len(), 5);
+    assert!(reports
+        .iter()
+        .all(|report| report.authority < AuthorityIndex::new_for_test(5)));
+    check_liveness(&reports);
+}
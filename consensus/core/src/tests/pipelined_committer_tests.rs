@@ -11,6 +11,7 @@ use crate::{
     commit::{LeaderStatus, DEFAULT_WAVE_LENGTH},
     context::Context,
     dag_state::DagState,
+    leader_schedule::LeaderSchedule,
     storage::mem_store::MemStore,
     test_dag::{build_dag, build_dag_layer},
     universal_committer::universal_committer_builder::UniversalCommitterBuilder,
@@ -778,9 +779,13 @@ fn basic_test_setup() -> (
     )));
 
     // Create committer with pipelining and only 1 leader per leader round
-    let committer = UniversalCommitterBuilder::new(context.clone(), dag_state.clone())
-        .with_pipeline(true)
-        .build();
+    let committer = UniversalCommitterBuilder::new(
+        context.clone(),
+        LeaderSchedule::new(context.clone()),
+        dag_state.clone(),
+    )
+    .with_pipeline(true)
+    .build();
 
     // note: with pipelining and without multi-leader enabled there should be
     // three committers.
@@ -11,6 +11,7 @@ use crate::{
     commit::LeaderStatus,
     context::Context,
     dag_state::DagState,
+    leader_schedule::LeaderSchedule,
     storage::mem_store::MemStore,
     test_dag::{build_dag, build_dag_layer},
     universal_committer::universal_committer_builder::UniversalCommitterBuilder,
@@ -747,7 +748,12 @@ fn basic_test_setup() -> (
     )));
 
     // Create committer without pipelining and only 1 leader per leader round
-    let committer = UniversalCommitterBuilder::new(context.clone(), dag_state.clone()).build();
+    let committer = UniversalCommitterBuilder::new(
+        context.clone(),
+        LeaderSchedule::new(context.clone()),
+        dag_state.clone(),
+    )
+    .build();
 
     // note: without pipelining or multi-leader enabled there should only be one committer.
     assert!(committer.committers.len() == 1);
@@ -6,6 +6,7 @@ use crate::options::Opts;
 use crate::util::get_ed25519_keypair_from_keystore;
 use crate::{FullNodeProxy, LocalValidatorAggregatorProxy, ValidatorProxy};
 use anyhow::{anyhow, bail, Context, Result};
+use consensus_config::ConsensusNetwork;
 use prometheus::Registry;
 use rand::seq::SliceRandom;
 use std::path::PathBuf;
@@ -53,6 +54,7 @@ impl Env {
                     registry,
                     opts.committee_size as usize,
                     opts.num_server_threads,
+                    opts.consensus_network,
                 )
                 .await
             }
@@ -78,6 +80,7 @@ impl Env {
         registry: &Registry,
         committee_size: usize,
         num_server_threads: u64,
+        consensus_network: Option<ConsensusNetwork>,
     ) -> Result<BenchmarkSetup> {
         info!("Running benchmark setup in local mode..");
         let (primary_gas_owner, keypair): (SuiAddress, AccountKeyPair) =
@@ -97,15 +100,17 @@ impl Env {
                 .build()
                 .unwrap();
             server_runtime.block_on(async move {
-                let cluster = TestClusterBuilder::new()
+                let mut cluster_builder = TestClusterBuilder::new()
                     .with_accounts(vec![AccountConfig {
                         address: Some(primary_gas_owner),
                         // We can't use TOTAL_SUPPLY_MIST because we need to account for validator stakes in genesis allocation.
                         gas_amounts: vec![TOTAL_SUPPLY_MIST / 2],
                     }])
-                    .with_num_validators(committee_size)
-                    .build()
-                    .await;
+                    .with_num_validators(committee_size);
+                if let Some(network) = consensus_network {
+                    cluster_builder = cluster_builder.with_consensus_network(network);
+                }
+                let cluster = cluster_builder.build().await;
                 let genesis = cluster.swarm.config().genesis.clone();
                 for v in cluster.swarm.config().validator_configs() {
                     eprintln!(
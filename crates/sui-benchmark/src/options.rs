@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::*;
+use consensus_config::ConsensusNetwork;
 
 use strum_macros::EnumString;
 
@@ -109,6 +110,21 @@ pub struct Opts {
     /// built at the same commit as the validators.
     #[clap(long, global = true)]
     pub protocol_version: Option<u64>,
+
+    /// The network stack used by Mysticeti consensus in a local benchmark, either "anemo" or
+    /// "tonic". Defaults to the network in the default consensus parameters.
+    #[clap(long, global = true, value_parser = parse_consensus_network)]
+    pub consensus_network: Option<ConsensusNetwork>,
+}
+
+fn parse_consensus_network(network: &str) -> Result<ConsensusNetwork, String> {
+    match network {
+        "anemo" => Ok(ConsensusNetwork::Anemo),
+        "tonic" => Ok(ConsensusNetwork::Tonic),
+        _ => Err(format!(
+            "invalid consensus network {network:?}, expected \"anemo\" or \"tonic\""
+        )),
+    }
 }
 
 #[derive(Debug, Clone, Parser, Eq, PartialEq, EnumString)]
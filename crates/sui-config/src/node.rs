@@ -7,6 +7,7 @@ use crate::p2p::P2pConfig;
 use crate::transaction_deny_config::TransactionDenyConfig;
 use crate::Config;
 use anyhow::Result;
+use consensus_config::Parameters as MysticetiParameters;
 use narwhal_config::Parameters as ConsensusParameters;
 use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
@@ -150,6 +151,11 @@ pub struct NodeConfig {
     #[serde(default)]
     pub indexer_max_subscriptions: Option<usize>,
 
+    /// Maximum number of requests accepted in a single JSON-RPC batch. If unspecified, the
+    /// server's default limit applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_rpc_max_batch_size: Option<usize>,
+
     #[serde(default = "default_transaction_kv_store_config")]
     pub transaction_kv_store_read_config: TransactionKeyValueStoreReadConfig,
 
@@ -165,6 +171,9 @@ pub struct NodeConfig {
     #[serde(default = "default_authority_overload_config")]
     pub authority_overload_config: AuthorityOverloadConfig,
 
+    #[serde(default)]
+    pub execution_cache: ExecutionCacheConfig,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub run_with_range: Option<RunWithRange>,
 }
@@ -371,6 +380,12 @@ pub struct ConsensusConfig {
 
     pub narwhal_config: ConsensusParameters,
 
+    /// Parameters of the Mysticeti consensus protocol, e.g. the network stack it uses to
+    /// communicate with peers. The defaults are used when not set. The database path of consensus
+    /// is always derived from `db_path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<MysticetiParameters>,
+
     /// The choice of consensus protocol to run. We default to Narwhal.
     #[serde(skip)]
     #[serde(default = "default_consensus_protocol")]
@@ -725,6 +740,44 @@ pub struct AuthorityOverloadConfig {
     // is overloaded.
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub check_system_overload_at_execution: bool,
+
+    // The number of transactions inflight in consensus adapter when entering load
+    // shedding mode. The percentage of transactions to shed increases linearly with
+    // the number of inflight transactions, up to the hard limit.
+    #[serde(default = "default_consensus_inflight_transactions_soft_limit")]
+    pub consensus_inflight_transactions_soft_limit: u64,
+
+    // The number of transactions inflight in consensus adapter when shedding the
+    // maximum percentage of transactions.
+    #[serde(default = "default_consensus_inflight_transactions_hard_limit")]
+    pub consensus_inflight_transactions_hard_limit: u64,
+
+    // The number of synced checkpoints not yet executed when entering load shedding
+    // mode. The percentage of transactions to shed increases linearly with the lag,
+    // up to the hard limit.
+    #[serde(default = "default_checkpoint_execution_lag_soft_limit")]
+    pub checkpoint_execution_lag_soft_limit: u64,
+
+    // The number of synced checkpoints not yet executed when shedding the maximum
+    // percentage of transactions.
+    #[serde(default = "default_checkpoint_execution_lag_hard_limit")]
+    pub checkpoint_execution_lag_hard_limit: u64,
+
+    // The resident memory of the process when entering load shedding mode. Memory is
+    // not monitored unless both the soft and hard limits are set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_soft_limit_bytes: Option<u64>,
+
+    // The resident memory of the process when shedding the maximum percentage of
+    // transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_hard_limit_bytes: Option<u64>,
+
+    // Senders sending more transactions than this in an overload monitor interval are
+    // considered heavy senders. When the validator is overloaded, transactions from heavy
+    // senders are shed before transactions from other senders.
+    #[serde(default = "default_heavy_sender_transaction_threshold")]
+    pub heavy_sender_transaction_threshold: u64,
     // TODO: Move other thresholds here as well, including `MAX_TM_QUEUE_LENGTH`
     // and `MAX_PER_OBJECT_QUEUE_LENGTH`.
 }
@@ -761,6 +814,26 @@ fn default_check_system_overload_at_signing() -> bool {
     true
 }
 
+fn default_consensus_inflight_transactions_soft_limit() -> u64 {
+    20_000
+}
+
+fn default_consensus_inflight_transactions_hard_limit() -> u64 {
+    80_000
+}
+
+fn default_checkpoint_execution_lag_soft_limit() -> u64 {
+    100
+}
+
+fn default_checkpoint_execution_lag_hard_limit() -> u64 {
+    500
+}
+
+fn default_heavy_sender_transaction_threshold() -> u64 {
+    1000
+}
+
 impl Default for AuthorityOverloadConfig {
     fn default() -> Self {
         Self {
@@ -774,6 +847,15 @@ impl Default for AuthorityOverloadConfig {
             safe_transaction_ready_rate: default_safe_transaction_ready_rate(),
             check_system_overload_at_signing: true,
             check_system_overload_at_execution: false,
+            consensus_inflight_transactions_soft_limit:
+                default_consensus_inflight_transactions_soft_limit(),
+            consensus_inflight_transactions_hard_limit:
+                default_consensus_inflight_transactions_hard_limit(),
+            checkpoint_execution_lag_soft_limit: default_checkpoint_execution_lag_soft_limit(),
+            checkpoint_execution_lag_hard_limit: default_checkpoint_execution_lag_hard_limit(),
+            rss_soft_limit_bytes: None,
+            rss_hard_limit_bytes: None,
+            heavy_sender_transaction_threshold: default_heavy_sender_transaction_threshold(),
         }
     }
 }
@@ -782,6 +864,53 @@ fn default_authority_overload_config() -> AuthorityOverloadConfig {
     AuthorityOverloadConfig::default()
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecutionCacheType {
+    /// Transaction outputs are written to the db synchronously during execution.
+    Passthrough,
+    /// Transaction outputs are held in memory, and are written to the db when the checkpoint
+    /// containing them is executed.
+    #[default]
+    Writeback,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExecutionCacheConfig {
+    #[serde(default)]
+    pub cache_type: ExecutionCacheType,
+
+    // The maximum number of entries in each of the caches of committed objects, markers and
+    // packages of the writeback cache.
+    #[serde(default = "default_execution_cache_max_cache_size")]
+    pub max_cache_size: u64,
+
+    // The maximum number of executed transactions whose outputs are not committed to the db yet.
+    // Above this, the validator rejects new transactions until checkpoint execution catches up,
+    // which bounds the memory held by the writeback cache.
+    #[serde(default = "default_execution_cache_max_pending_transactions")]
+    pub max_pending_transactions: usize,
+}
+
+fn default_execution_cache_max_cache_size() -> u64 {
+    10_000
+}
+
+fn default_execution_cache_max_pending_transactions() -> usize {
+    100_000
+}
+
+impl Default for ExecutionCacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_type: ExecutionCacheType::default(),
+            max_cache_size: default_execution_cache_max_cache_size(),
+            max_pending_transactions: default_execution_cache_max_pending_transactions(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq)]
 pub struct Genesis {
     #[serde(flatten)]
@@ -976,10 +1105,12 @@ mod tests {
     use fastcrypto::traits::KeyPair;
     use rand::{rngs::StdRng, SeedableRng};
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
+    use sui_types::base_types::{ObjectID, SuiAddress};
     use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair};
 
     use super::Genesis;
-    use crate::NodeConfig;
+    use crate::transaction_deny_config::TransactionDenyConfigBuilder;
+    use crate::{Config, NodeConfig};
 
     #[test]
     fn serialize_genesis_from_file() {
@@ -998,6 +1129,38 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
 
+    #[test]
+    fn save_and_load_transaction_deny_config() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+        let mut config: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+
+        let object = ObjectID::random();
+        let package = ObjectID::random();
+        let address = SuiAddress::from(ObjectID::random());
+        config.transaction_deny_config = TransactionDenyConfigBuilder::new()
+            .add_denied_object(object)
+            .add_denied_package_upgrade_family(package)
+            .add_denied_address(address)
+            .disable_user_transaction()
+            .build();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fullnode.yaml");
+        config.save(&path).unwrap();
+        let loaded = NodeConfig::load(&path).unwrap().transaction_deny_config;
+
+        assert!(loaded.get_object_deny_set().contains(&object));
+        assert!(loaded
+            .get_package_upgrade_family_deny_set()
+            .contains(&package));
+        assert!(loaded.get_address_deny_set().contains(&address));
+        assert!(loaded.user_transaction_disabled());
+        assert_eq!(
+            serde_yaml::to_string(&loaded).unwrap(),
+            serde_yaml::to_string(&config.transaction_deny_config).unwrap(),
+        );
+    }
+
     #[test]
     fn load_key_pairs_to_node_config() {
         let protocol_key_pair: AuthorityKeyPair =
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashSet;
+use std::hash::Hash;
 
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
@@ -22,13 +23,20 @@ pub struct TransactionDenyConfig {
     /// Note that this does not apply to type arguments.
     /// Also since we only compare the deny list against the upgraded package ID of each dependency
     /// in the used package, when a package ID is denied, newer versions of that package are
-    /// still allowed. If we want to deny the entire upgrade family of a package, we need to
-    /// explicitly specify all the package IDs in the deny list.
+    /// still allowed. To deny the entire upgrade family of a package, use
+    /// `package_upgrade_family_deny_list` instead.
     /// TODO: We could consider making this more flexible, e.g. whether to check in type args,
-    /// whether to block entire upgrade family, whether to allow upgrade and etc.
+    /// whether to allow upgrade and etc.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     package_deny_list: Vec<ObjectID>,
 
+    /// A list of original package IDs (i.e. the ID of the first version of a package) whose
+    /// entire upgrade family is not allowed to be called into in transactions, either directly or
+    /// indirectly through transitive dependencies. Unlike `package_deny_list`, this also covers
+    /// every version of the package that is published after it is denied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    package_upgrade_family_deny_list: Vec<ObjectID>,
+
     /// A list of sui addresses that are not allowed to be used as the sender or sponsor.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     address_deny_list: Vec<SuiAddress>,
@@ -57,6 +65,9 @@ pub struct TransactionDenyConfig {
     #[serde(skip)]
     package_deny_set: OnceCell<HashSet<ObjectID>>,
 
+    #[serde(skip)]
+    package_upgrade_family_deny_set: OnceCell<HashSet<ObjectID>>,
+
     #[serde(skip)]
     address_deny_set: OnceCell<HashSet<SuiAddress>>,
 
@@ -86,6 +97,15 @@ impl TransactionDenyConfig {
             .get_or_init(|| self.package_deny_list.iter().cloned().collect())
     }
 
+    pub fn get_package_upgrade_family_deny_set(&self) -> &HashSet<ObjectID> {
+        self.package_upgrade_family_deny_set.get_or_init(|| {
+            self.package_upgrade_family_deny_list
+                .iter()
+                .cloned()
+                .collect()
+        })
+    }
+
     pub fn get_address_deny_set(&self) -> &HashSet<SuiAddress> {
         self.address_deny_set
             .get_or_init(|| self.address_deny_list.iter().cloned().collect())
@@ -118,6 +138,122 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    pub fn kill_switch(&self, switch: TransactionKillSwitch) -> bool {
+        match switch {
+            TransactionKillSwitch::UserTransaction => self.user_transaction_disabled,
+            TransactionKillSwitch::SharedObject => self.shared_object_disabled,
+            TransactionKillSwitch::PackagePublish => self.package_publish_disabled,
+            TransactionKillSwitch::PackageUpgrade => self.package_upgrade_disabled,
+            TransactionKillSwitch::ReceivingObjects => self.receiving_objects_disabled,
+            TransactionKillSwitch::ZkloginSig => self.zklogin_sig_disabled,
+        }
+    }
+
+    // The following methods modify the config in place, for updating it while the node is
+    // running. Each returns whether the config changed.
+
+    pub fn set_kill_switch(&mut self, switch: TransactionKillSwitch, disabled: bool) -> bool {
+        let flag = match switch {
+            TransactionKillSwitch::UserTransaction => &mut self.user_transaction_disabled,
+            TransactionKillSwitch::SharedObject => &mut self.shared_object_disabled,
+            TransactionKillSwitch::PackagePublish => &mut self.package_publish_disabled,
+            TransactionKillSwitch::PackageUpgrade => &mut self.package_upgrade_disabled,
+            TransactionKillSwitch::ReceivingObjects => &mut self.receiving_objects_disabled,
+            TransactionKillSwitch::ZkloginSig => &mut self.zklogin_sig_disabled,
+        };
+        std::mem::replace(flag, disabled) != disabled
+    }
+
+    pub fn add_denied_object(&mut self, id: ObjectID) -> bool {
+        add_to_list(&mut self.object_deny_list, &mut self.object_deny_set, id)
+    }
+
+    pub fn remove_denied_object(&mut self, id: &ObjectID) -> bool {
+        remove_from_list(&mut self.object_deny_list, &mut self.object_deny_set, id)
+    }
+
+    pub fn add_denied_package(&mut self, id: ObjectID) -> bool {
+        add_to_list(&mut self.package_deny_list, &mut self.package_deny_set, id)
+    }
+
+    pub fn remove_denied_package(&mut self, id: &ObjectID) -> bool {
+        remove_from_list(&mut self.package_deny_list, &mut self.package_deny_set, id)
+    }
+
+    pub fn add_denied_package_upgrade_family(&mut self, original_id: ObjectID) -> bool {
+        add_to_list(
+            &mut self.package_upgrade_family_deny_list,
+            &mut self.package_upgrade_family_deny_set,
+            original_id,
+        )
+    }
+
+    pub fn remove_denied_package_upgrade_family(&mut self, original_id: &ObjectID) -> bool {
+        remove_from_list(
+            &mut self.package_upgrade_family_deny_list,
+            &mut self.package_upgrade_family_deny_set,
+            original_id,
+        )
+    }
+
+    pub fn add_denied_address(&mut self, address: SuiAddress) -> bool {
+        add_to_list(
+            &mut self.address_deny_list,
+            &mut self.address_deny_set,
+            address,
+        )
+    }
+
+    pub fn remove_denied_address(&mut self, address: &SuiAddress) -> bool {
+        remove_from_list(
+            &mut self.address_deny_list,
+            &mut self.address_deny_set,
+            address,
+        )
+    }
+}
+
+/// The switches in `TransactionDenyConfig` that disable a whole class of transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionKillSwitch {
+    UserTransaction,
+    SharedObject,
+    PackagePublish,
+    PackageUpgrade,
+    ReceivingObjects,
+    ZkloginSig,
+}
+
+// The lookup sets are built lazily from the lists, so they are reset whenever a list changes, to
+// be rebuilt on next access.
+
+fn add_to_list<T: Eq + Hash>(
+    list: &mut Vec<T>,
+    set: &mut OnceCell<HashSet<T>>,
+    item: T,
+) -> bool {
+    if list.contains(&item) {
+        return false;
+    }
+    list.push(item);
+    set.take();
+    true
+}
+
+fn remove_from_list<T: Eq + Hash>(
+    list: &mut Vec<T>,
+    set: &mut OnceCell<HashSet<T>>,
+    item: &T,
+) -> bool {
+    let len = list.len();
+    list.retain(|i| i != item);
+    if list.len() == len {
+        return false;
+    }
+    set.take();
+    true
 }
 
 #[derive(Default)]
@@ -174,6 +310,13 @@ impl TransactionDenyConfigBuilder {
         self
     }
 
+    pub fn add_denied_package_upgrade_family(mut self, original_id: ObjectID) -> Self {
+        self.config
+            .package_upgrade_family_deny_list
+            .push(original_id);
+        self
+    }
+
     pub fn disable_zklogin_sig(mut self) -> Self {
         self.config.zklogin_sig_disabled = true;
         self
@@ -197,6 +197,7 @@ pub mod test_authority_builder;
 
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
+pub(crate) mod shared_object_congestion_tracker;
 
 pub static CHAIN_IDENTIFIER: OnceCell<ChainIdentifier> = OnceCell::new();
 
@@ -779,7 +780,8 @@ pub struct AuthorityState {
     /// Config controlling what kind of expensive safety checks to perform.
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
 
-    transaction_deny_config: TransactionDenyConfig,
+    /// Can be updated while the node is running, e.g. through the admin interface.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
 
     certificate_deny_config: CertificateDenyConfig,
 
@@ -864,7 +866,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -995,19 +997,33 @@ impl AuthorityState {
         }
         self.transaction_manager
             .check_execution_overload(self.max_txn_age_in_queue(), tx_data)?;
+        self.execution_cache.check_pending_writes_overload()?;
         consensus_adapter.check_consensus_overload()?;
         Ok(())
     }
 
+    /// Counts a transaction towards its sender's load, to find heavy senders to shed first when
+    /// the authority is overloaded. Senders are tracked even when the authority is not
+    /// overloaded, so heavy senders are known once load shedding starts. Transactions are only
+    /// recorded when they are signed, not again when their certificates are executed.
+    pub(crate) fn record_transaction_sender(&self, tx_data: &SenderSignedData) {
+        self.overload_info
+            .record_sender_transaction(tx_data.transaction_data().sender());
+    }
+
     fn check_authority_overload(&self, tx_data: &SenderSignedData) -> SuiResult {
         if !self.overload_info.is_overload.load(Ordering::Relaxed) {
             return Ok(());
         }
 
+        let is_heavy_sender = self.overload_info.is_heavy_sender(
+            tx_data.transaction_data().sender(),
+            self.authority_overload_config
+                .heavy_sender_transaction_threshold,
+        );
         let load_shedding_percentage = self
             .overload_info
-            .load_shedding_percentage
-            .load(Ordering::Relaxed);
+            .sender_load_shedding_percentage(is_heavy_sender);
         overload_monitor_accept_tx(load_shedding_percentage, tx_data.digest())
     }
 
@@ -1673,7 +1689,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1892,7 +1908,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2643,7 +2659,7 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             expensive_safety_check_config,
-            transaction_deny_config,
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
             certificate_deny_config,
             debug_dump_config,
             authority_overload_config: authority_overload_config.clone(),
@@ -2682,6 +2698,17 @@ impl AuthorityState {
         &self.execution_cache_trait_pointers.backing_store
     }
 
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    /// Replace the transaction deny config. Transactions signed after this returns are checked
+    /// against the new config.
+    pub fn set_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        info!(?config, "Updating transaction deny config");
+        self.transaction_deny_config.store(Arc::new(config));
+    }
+
     pub fn get_backing_package_store(&self) -> &Arc<dyn BackingPackageStore + Send + Sync> {
         &self.execution_cache_trait_pointers.backing_package_store
     }
@@ -2753,6 +2780,15 @@ impl AuthorityState {
             .enqueue_certificates(certs, epoch_store)
     }
 
+    /// Adds transactions sequenced by consensus to transaction manager for ordered execution.
+    pub fn enqueue_transactions_for_execution(
+        &self,
+        txns: Vec<VerifiedExecutableTransaction>,
+        epoch_store: &Arc<AuthorityPerEpochStore>,
+    ) {
+        self.transaction_manager.enqueue(txns, epoch_store)
+    }
+
     fn create_owner_index_if_empty(
         &self,
         genesis_objects: &[Object],
@@ -3733,6 +3769,7 @@ impl AuthorityState {
             // not using "_ =>" because we want to make sure we remember to add new variants here
             EventFilter::Package(_)
             | EventFilter::MoveEventField { .. }
+            | EventFilter::MoveEventFieldPredicate(_)
             | EventFilter::Any(_)
             | EventFilter::And(_, _)
             | EventFilter::Or(_, _) => {
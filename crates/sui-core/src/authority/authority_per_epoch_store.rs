@@ -51,6 +51,7 @@ use typed_store::{
 use super::authority_store_tables::ENV_VAR_LOCKS_BLOCK_CACHE_SIZE;
 use super::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
+use crate::authority::shared_object_congestion_tracker::SharedObjectCongestionTracker;
 use crate::authority::ResolverWrapper;
 use crate::checkpoints::{
     BuilderCheckpointSummary, CheckpointHeight, CheckpointServiceNotify, EpochStats,
@@ -78,7 +79,7 @@ use prometheus::IntCounter;
 use std::str::FromStr;
 use sui_execution::{self, Executor};
 use sui_macros::fail_point;
-use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use sui_protocol_config::{Chain, ConsensusTransactionOrdering, ProtocolConfig, ProtocolVersion};
 use sui_storage::mutex_table::{MutexGuard, MutexTable};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::executable_transaction::{
@@ -511,13 +512,25 @@ impl DeferralKey {
         }
     }
 
-    fn new_for_consensus_round(future_round: Round, deferred_from_round: Round) -> Self {
+    pub(crate) fn new_for_consensus_round(future_round: Round, deferred_from_round: Round) -> Self {
         Self::ConsensusRound {
             future_round,
             deferred_from_round,
         }
     }
 
+    pub(crate) fn deferred_from_round(&self) -> Round {
+        match self {
+            Self::RandomnessDkg {
+                deferred_from_round,
+            }
+            | Self::ConsensusRound {
+                deferred_from_round,
+                ..
+            } => *deferred_from_round,
+        }
+    }
+
     fn full_range_for_randomness() -> (Self, Self) {
         (
             Self::RandomnessDkg {
@@ -529,10 +542,12 @@ impl DeferralKey {
         )
     }
 
-    fn range_for_consensus_round(future_round: Round) -> (Self, Self) {
+    // Consecutive commits are not necessarily from consecutive rounds, so this covers every
+    // transaction deferred to a round up to and including `future_round`.
+    fn range_for_up_to_consensus_round(future_round: Round) -> (Self, Self) {
         (
             Self::ConsensusRound {
-                future_round,
+                future_round: 0,
                 deferred_from_round: 0,
             },
             Self::ConsensusRound {
@@ -590,11 +605,10 @@ fn signed_transactions_table_default_config() -> DBOptions {
 
 fn owned_object_transaction_locks_table_default_config() -> DBOptions {
     DBOptions {
-        options: default_db_options()
+        rw_options: ReadWriteOptions::default().set_ignore_range_deletions(false),
+        ..default_db_options()
             .optimize_for_write_throughput()
             .optimize_for_read(read_size_from_env(ENV_VAR_LOCKS_BLOCK_CACHE_SIZE).unwrap_or(1024))
-            .options,
-        rw_options: ReadWriteOptions::default().set_ignore_range_deletions(false),
     }
 }
 
@@ -1306,6 +1320,13 @@ impl AuthorityPerEpochStore {
         Ok(())
     }
 
+    /// Deletes multiple pending certificates.
+    #[instrument(level = "trace", skip_all)]
+    pub fn multi_remove_pending_execution(&self, digests: &[TransactionDigest]) -> SuiResult<()> {
+        self.tables()?.pending_execution.multi_remove(digests)?;
+        Ok(())
+    }
+
     pub fn get_all_pending_consensus_transactions(&self) -> Vec<ConsensusTransaction> {
         self.tables()
             .expect("recovery should not cross epoch boundary")
@@ -1524,17 +1545,17 @@ impl AuthorityPerEpochStore {
     fn load_deferred_transactions_for_randomness(
         &self,
         batch: &mut DBBatch,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
+    ) -> SuiResult<Vec<(DeferralKey, Vec<VerifiedSequencedConsensusTransaction>)>> {
         let (min, max) = DeferralKey::full_range_for_randomness();
         self.load_deferred_transactions(batch, min, max)
     }
 
-    fn load_deferred_transactions_for_consensus_round(
+    fn load_deferred_transactions_for_up_to_consensus_round(
         &self,
         batch: &mut DBBatch,
         consensus_round: u64,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
-        let (min, max) = DeferralKey::range_for_consensus_round(consensus_round);
+    ) -> SuiResult<Vec<(DeferralKey, Vec<VerifiedSequencedConsensusTransaction>)>> {
+        let (min, max) = DeferralKey::range_for_up_to_consensus_round(consensus_round);
         self.load_deferred_transactions(batch, min, max)
     }
 
@@ -1544,27 +1565,19 @@ impl AuthorityPerEpochStore {
         batch: &mut DBBatch,
         min: DeferralKey,
         max: DeferralKey,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
-        let mut keys = Vec::new();
-        let mut txns = Vec::new();
-        self.tables()?
+    ) -> SuiResult<Vec<(DeferralKey, Vec<VerifiedSequencedConsensusTransaction>)>> {
+        let txns: Vec<_> = self
+            .tables()?
             .deferred_transactions
             .safe_iter_with_bounds(Some(min), Some(max))
-            .try_for_each(|result| match result {
-                Ok((key, txs)) => {
-                    keys.push(key);
-                    txns.extend(txs);
-                    Ok(())
-                }
-                Err(err) => Err(err),
-            })?;
+            .collect::<Result<_, _>>()?;
 
         // verify that there are no duplicates - should be impossible due to
         // is_consensus_message_processed
         #[cfg(debug_assertions)]
         {
             let mut seen = HashSet::new();
-            for txn in &txns {
+            for txn in txns.iter().flat_map(|(_, txs)| txs) {
                 assert!(seen.insert(txn.0.key()));
             }
         }
@@ -1572,7 +1585,10 @@ impl AuthorityPerEpochStore {
         // Transactional DBs do not support range deletes, so we have to delete keys one-by-one.
         // This shouldn't be a problem, there should not usually be more than a small handful of
         // keys loaded in each round.
-        batch.delete_batch(&self.tables()?.deferred_transactions, keys)?;
+        batch.delete_batch(
+            &self.tables()?.deferred_transactions,
+            txns.iter().map(|(key, _)| *key),
+        )?;
 
         Ok(txns)
     }
@@ -1582,6 +1598,8 @@ impl AuthorityPerEpochStore {
         cert: &VerifiedExecutableTransaction,
         commit_round: Round,
         generating_randomness: bool,
+        previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
+        congestion_tracker: &SharedObjectCongestionTracker,
     ) -> Option<DeferralKey> {
         // Defer transaction if it uses randomness but we are not yet generating randomness.
         if !generating_randomness && self.randomness_state_enabled() && cert.is_randomness_reader()
@@ -1589,8 +1607,34 @@ impl AuthorityPerEpochStore {
             return Some(DeferralKey::new_for_randomness(commit_round));
         }
 
-        // placeholder construction to silence lints
-        let _ = DeferralKey::new_for_consensus_round(0, 0);
+        // Defer transaction if it mutates a shared object that has used up its budget for this
+        // commit. Congestion control is skipped once we stop accepting certs at the end of the
+        // epoch, so that deferred transactions are drained rather than delaying reconfiguration.
+        if self.protocol_config().consensus_transaction_ordering()
+            == ConsensusTransactionOrdering::ByGasPriceWithCongestionControl
+            && self
+                .get_reconfig_state_read_lock_guard()
+                .should_accept_consensus_certs()
+        {
+            if let Some((deferral_key, congested_objects)) = congestion_tracker
+                .should_defer_due_to_object_congestion(
+                    cert,
+                    self.protocol_config()
+                        .max_txns_per_shared_object_in_commit(),
+                    self.protocol_config()
+                        .max_deferral_rounds_for_congestion_control(),
+                    previously_deferred_tx_digests,
+                    commit_round,
+                )
+            {
+                debug!(
+                    tx_digest = ?cert.digest(),
+                    ?congested_objects,
+                    "Deferring transaction due to shared object congestion",
+                );
+                return Some(deferral_key);
+            }
+        }
 
         None
     }
@@ -2427,20 +2471,20 @@ impl AuthorityPerEpochStore {
             .expect("Consensus should not be processed past end of epoch");
 
         // Load transactions deferred from previous commits.
-        let deferred_tx: Vec<VerifiedSequencedConsensusTransaction> = self
-            .load_deferred_transactions_for_consensus_round(&mut batch, commit_round)?
-            .into_iter()
-            .collect();
-        let mut previously_deferred_tx_digests: HashSet<_> = deferred_tx
+        let deferred_tx =
+            self.load_deferred_transactions_for_up_to_consensus_round(&mut batch, commit_round)?;
+        let mut previously_deferred_tx_digests: HashMap<_, _> = deferred_tx
             .iter()
-            .map(|tx| match tx.0.transaction.key() {
-                SequencedConsensusTransactionKey::External(
-                    ConsensusTransactionKey::Certificate(digest),
-                ) => digest,
-                _ => panic!("deferred transaction was not a user certificate: {tx:?}"),
+            .flat_map(|(key, txs)| {
+                txs.iter().map(|tx| match tx.0.transaction.key() {
+                    SequencedConsensusTransactionKey::External(
+                        ConsensusTransactionKey::Certificate(digest),
+                    ) => (digest, *key),
+                    _ => panic!("deferred transaction was not a user certificate: {tx:?}"),
+                })
             })
             .collect();
-        for tx in deferred_tx {
+        for tx in deferred_tx.into_iter().flat_map(|(_, txs)| txs) {
             if tx
                 .0
                 .is_user_tx_with_randomness(self.randomness_state_enabled())
@@ -2472,17 +2516,20 @@ impl AuthorityPerEpochStore {
         if generate_randomness {
             let deferred_randomness_tx =
                 self.load_deferred_transactions_for_randomness(&mut batch)?;
-            previously_deferred_tx_digests.extend(deferred_randomness_tx.iter().map(|tx| {
-                match tx.0.transaction.key() {
-                    SequencedConsensusTransactionKey::External(
-                        ConsensusTransactionKey::Certificate(digest),
-                    ) => digest,
-                    _ => {
-                        panic!("deferred randomness transaction was not a user certificate: {tx:?}")
-                    }
-                }
-            }));
-            sequenced_randomness_transactions.extend(deferred_randomness_tx);
+            previously_deferred_tx_digests.extend(deferred_randomness_tx.iter().flat_map(
+                |(key, txs)| {
+                    txs.iter().map(|tx| match tx.0.transaction.key() {
+                        SequencedConsensusTransactionKey::External(
+                            ConsensusTransactionKey::Certificate(digest),
+                        ) => (digest, *key),
+                        _ => panic!(
+                            "deferred randomness transaction was not a user certificate: {tx:?}"
+                        ),
+                    })
+                },
+            ));
+            sequenced_randomness_transactions
+                .extend(deferred_randomness_tx.into_iter().flat_map(|(_, txs)| txs));
         }
 
         // Save roots for checkpoint generation. One set for most tx, one for randomness tx.
@@ -2714,7 +2761,7 @@ impl AuthorityPerEpochStore {
         checkpoint_service: &Arc<C>,
         cache_reader: &dyn ExecutionCacheRead,
         commit_round: Round,
-        previously_deferred_tx_digests: HashSet<TransactionDigest>,
+        previously_deferred_tx_digests: HashMap<TransactionDigest, DeferralKey>,
         generate_randomness: bool,
     ) -> SuiResult<(
         Vec<VerifiedExecutableTransaction>,    // transactions to schedule
@@ -2788,6 +2835,7 @@ impl AuthorityPerEpochStore {
 
         let mut deferred_txns: BTreeMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>> =
             BTreeMap::new();
+        let mut congestion_tracker = SharedObjectCongestionTracker::default();
 
         let mut randomness_state_updated = false;
         for tx in transactions {
@@ -2801,6 +2849,7 @@ impl AuthorityPerEpochStore {
                     checkpoint_service,
                     commit_round,
                     &previously_deferred_tx_digests,
+                    &mut congestion_tracker,
                     generate_randomness,
                 )
                 .await?
@@ -2979,7 +3028,8 @@ impl AuthorityPerEpochStore {
         transaction: &VerifiedSequencedConsensusTransaction,
         checkpoint_service: &Arc<C>,
         commit_round: Round,
-        previously_deferred_tx_digests: &HashSet<TransactionDigest>,
+        previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
+        congestion_tracker: &mut SharedObjectCongestionTracker,
         generating_randomness: bool,
     ) -> SuiResult<ConsensusCertificateResult> {
         let _scope = monitored_scope("HandleConsensusTransaction");
@@ -3006,7 +3056,7 @@ impl AuthorityPerEpochStore {
                     return Ok(ConsensusCertificateResult::Ignored);
                 }
                 if self.has_sent_end_of_publish(certificate_author)?
-                    && !previously_deferred_tx_digests.contains(certificate.digest())
+                    && !previously_deferred_tx_digests.contains_key(certificate.digest())
                 {
                     // This can not happen with valid authority
                     // With some edge cases narwhal might sometimes resend previously seen certificate after EndOfPublish
@@ -3029,16 +3079,20 @@ impl AuthorityPerEpochStore {
                 if !self
                     .get_reconfig_state_read_lock_guard()
                     .should_accept_consensus_certs()
-                    && !previously_deferred_tx_digests.contains(certificate.digest())
+                    && !previously_deferred_tx_digests.contains_key(certificate.digest())
                 {
                     debug!("Ignoring consensus certificate for transaction {:?} because of end of epoch",
                     certificate.digest());
                     return Ok(ConsensusCertificateResult::Ignored);
                 }
 
-                if let Some(deferral_key) =
-                    self.should_defer(&certificate, commit_round, generating_randomness)
-                {
+                if let Some(deferral_key) = self.should_defer(
+                    &certificate,
+                    commit_round,
+                    generating_randomness,
+                    previously_deferred_tx_digests,
+                    congestion_tracker,
+                ) {
                     debug!(
                         "Deferring consensus certificate for transaction {:?} until {deferral_key:?}",
                         certificate.digest(),
@@ -3047,6 +3101,8 @@ impl AuthorityPerEpochStore {
                 }
 
                 if certificate.contains_shared_object() {
+                    congestion_tracker.bump_object_txn_counts(&certificate);
+
                     self.record_shared_object_cert_from_consensus(
                         batch,
                         shared_input_next_versions,
@@ -16,7 +16,6 @@ use crate::transaction_outputs::TransactionOutputs;
 use either::Either;
 use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
 use futures::stream::FuturesUnordered;
-use itertools::izip;
 use move_core_types::resolver::ModuleResolver;
 use serde::{Deserialize, Serialize};
 use sui_macros::fail_point_arg;
@@ -511,7 +510,7 @@ impl AuthorityStore {
     }
 
     /// A function that acquires all locks associated with the objects (in order to avoid deadlocks).
-    async fn acquire_locks(&self, input_objects: &[ObjectRef]) -> Vec<MutexGuard> {
+    pub(crate) async fn acquire_locks(&self, input_objects: &[ObjectRef]) -> Vec<MutexGuard> {
         self.mutex_table
             .acquire_locks(input_objects.iter().map(|(_, _, digest)| *digest))
             .await
@@ -958,29 +957,38 @@ impl AuthorityStore {
         epoch_store: &AuthorityPerEpochStore,
         owned_input_objects: &[ObjectRef],
         transaction: VerifiedSignedTransaction,
+    ) -> SuiResult {
+        // Other writers may be attempting to acquire locks on the same objects, so a mutex is
+        // required.
+        // TODO: replace with optimistic db_transactions (i.e. set lock to tx if none)
+        let _mutexes = self.acquire_locks(owned_input_objects).await;
+        self.acquire_transaction_locks_with_mutexes(epoch_store, owned_input_objects, transaction)
+    }
+
+    /// Same as `acquire_transaction_locks`, but the caller must already hold the mutexes of all
+    /// objects, returned by `acquire_locks`.
+    pub(crate) fn acquire_transaction_locks_with_mutexes(
+        &self,
+        epoch_store: &AuthorityPerEpochStore,
+        owned_input_objects: &[ObjectRef],
+        transaction: VerifiedSignedTransaction,
     ) -> SuiResult {
         let tx_digest = *transaction.digest();
         if epoch_store.object_lock_split_tables_enabled() {
             self.acquire_transaction_locks_v2(epoch_store, owned_input_objects, transaction)
-                .await
         } else {
             self.acquire_transaction_locks_v1(epoch_store, owned_input_objects, tx_digest)
-                .await
         }
     }
 
     /// Acquires a lock for a transaction on the given objects if they have all been initialized previously
-    async fn acquire_transaction_locks_v1(
+    fn acquire_transaction_locks_v1(
         &self,
         epoch_store: &AuthorityPerEpochStore,
         owned_input_objects: &[ObjectRef],
         tx_digest: TransactionDigest,
     ) -> SuiResult {
         let epoch = epoch_store.epoch();
-        // Other writers may be attempting to acquire locks on the same objects, so a mutex is
-        // required.
-        // TODO: replace with optimistic db_transactions (i.e. set lock to tx if none)
-        let _mutexes = self.acquire_locks(owned_input_objects).await;
 
         trace!(?owned_input_objects, "acquire_locks");
         let mut locks_to_write = Vec::new();
@@ -1055,38 +1063,22 @@ impl AuthorityStore {
         Ok(())
     }
 
-    async fn acquire_transaction_locks_v2(
+    fn acquire_transaction_locks_v2(
         &self,
         epoch_store: &AuthorityPerEpochStore,
         owned_input_objects: &[ObjectRef],
         transaction: VerifiedSignedTransaction,
     ) -> SuiResult {
-        let tx_digest = *transaction.digest();
         let epoch = epoch_store.epoch();
-        // Other writers may be attempting to acquire locks on the same objects, so a mutex is
-        // required.
-        // TODO: replace with optimistic db_transactions (i.e. set lock to tx if none)
-        let _mutexes = self.acquire_locks(owned_input_objects).await;
 
         trace!(?owned_input_objects, "acquire_locks");
-        let mut locks_to_write = Vec::new();
 
         let live_object_markers = self
             .perpetual_tables
             .live_owned_object_markers
             .multi_get(owned_input_objects)?;
 
-        let epoch_tables = epoch_store.tables()?;
-
-        let locks = epoch_tables.multi_get_locked_transactions(owned_input_objects)?;
-
-        assert_eq!(locks.len(), live_object_markers.len());
-
-        for (live_marker, lock, obj_ref) in izip!(
-            live_object_markers.into_iter(),
-            locks.into_iter(),
-            owned_input_objects
-        ) {
+        for (live_marker, obj_ref) in live_object_markers.into_iter().zip(owned_input_objects) {
             let Some(live_marker) = live_marker else {
                 let latest_lock = self.get_latest_live_version_for_object_id(obj_ref.0)?;
                 fp_bail!(UserInputError::ObjectVersionUnavailableForConsumption {
@@ -1111,7 +1103,44 @@ impl AuthorityStore {
                     obj_ref
                 );
             }
+        }
 
+        Self::write_transaction_locks_if_unlocked(epoch_store, owned_input_objects, transaction)
+    }
+
+    /// Acquires a lock for a transaction on objects that the caller has already checked to be
+    /// live, while holding their mutexes returned by `acquire_locks`. Their live object markers
+    /// may not have been written to the db yet, e.g. when they were created by transaction
+    /// outputs that the execution cache has not committed, so this requires the lock tables to
+    /// be split.
+    pub(crate) fn acquire_transaction_locks_for_live_objects(
+        epoch_store: &AuthorityPerEpochStore,
+        owned_input_objects: &[ObjectRef],
+        transaction: VerifiedSignedTransaction,
+    ) -> SuiResult {
+        assert!(epoch_store.object_lock_split_tables_enabled());
+
+        trace!(?owned_input_objects, "acquire_locks_for_live_objects");
+        Self::write_transaction_locks_if_unlocked(epoch_store, owned_input_objects, transaction)
+    }
+
+    // Writes the locks of the epoch tables for a transaction, unless an object is already locked
+    // by a different transaction. The caller must hold the mutexes of all objects.
+    fn write_transaction_locks_if_unlocked(
+        epoch_store: &AuthorityPerEpochStore,
+        owned_input_objects: &[ObjectRef],
+        transaction: VerifiedSignedTransaction,
+    ) -> SuiResult {
+        let tx_digest = *transaction.digest();
+        let mut locks_to_write = Vec::new();
+
+        let epoch_tables = epoch_store.tables()?;
+
+        let locks = epoch_tables.multi_get_locked_transactions(owned_input_objects)?;
+
+        assert_eq!(locks.len(), owned_input_objects.len());
+
+        for (lock, obj_ref) in locks.into_iter().zip(owned_input_objects) {
             if let Some(previous_tx_digest) = &lock {
                 if previous_tx_digest == &tx_digest {
                     // no need to re-write lock
@@ -1169,6 +1198,16 @@ impl AuthorityStore {
             });
         }
 
+        self.get_lock_for_live_object(obj_ref, epoch_store)
+    }
+
+    /// Gets the state of the lock on an object that the caller has already checked to be live.
+    /// Requires the lock tables to be split.
+    pub(crate) fn get_lock_for_live_object(
+        &self,
+        obj_ref: ObjectRef,
+        epoch_store: &AuthorityPerEpochStore,
+    ) -> SuiLockResult {
         let tables = epoch_store.tables()?;
         let epoch_id = epoch_store.epoch();
 
@@ -12,7 +12,7 @@ use sui_types::digests::TransactionEventsDigest;
 use sui_types::effects::TransactionEffects;
 use sui_types::storage::MarkerValue;
 use typed_store::metrics::SamplingInterval;
-use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
+use typed_store::rocks::util::{empty_compaction_filter, merge_reference_counts};
 use typed_store::rocks::{
     default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions,
 };
@@ -142,6 +142,12 @@ impl AuthorityPerpetualTables {
         )
     }
 
+    /// Opens the tables against an in-memory database, for tests and tools which do not need the
+    /// data to be persisted
+    pub fn open_in_memory() -> Self {
+        Self::open_tables_in_memory(MetricConf::new("perpetual"))
+    }
+
     pub fn open_readonly(parent_path: &Path) -> AuthorityPerpetualTablesReadOnly {
         Self::get_read_only_handle(
             Self::path(parent_path),
@@ -569,11 +575,10 @@ impl Iterator for LiveSetIter<'_> {
 // These functions are used to initialize the DB tables
 fn owned_object_transaction_locks_table_default_config() -> DBOptions {
     DBOptions {
-        options: default_db_options()
+        rw_options: ReadWriteOptions::default().set_ignore_range_deletions(false),
+        ..default_db_options()
             .optimize_for_write_throughput()
             .optimize_for_read(read_size_from_env(ENV_VAR_LOCKS_BLOCK_CACHE_SIZE).unwrap_or(1024))
-            .options,
-        rw_options: ReadWriteOptions::default().set_ignore_range_deletions(false),
     }
 }
 
@@ -606,18 +611,11 @@ fn events_table_default_config() -> DBOptions {
 }
 
 fn indirect_move_objects_table_default_config() -> DBOptions {
-    let mut options = default_db_options()
+    default_db_options()
         .optimize_for_write_throughput()
         .optimize_for_point_lookup(
             read_size_from_env(ENV_VAR_INDIRECT_OBJECTS_BLOCK_CACHE_SIZE).unwrap_or(512),
-        );
-    options.options.set_merge_operator(
-        "refcount operator",
-        reference_count_merge_operator,
-        reference_count_merge_operator,
-    );
-    options
-        .options
-        .set_compaction_filter("empty filter", empty_compaction_filter);
-    options
+        )
+        .set_merge_operator("refcount operator", merge_reference_counts)
+        .set_compaction_filter("empty filter", empty_compaction_filter)
 }
@@ -0,0 +1,280 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::authority::authority_per_epoch_store::DeferralKey;
+use narwhal_types::Round;
+use std::collections::HashMap;
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::executable_transaction::VerifiedExecutableTransaction;
+
+/// Tracks, within a single consensus commit, how many of the transactions scheduled so far mutate
+/// each shared object. Once an object has used up its per-commit budget, further transactions that
+/// mutate it are deferred to a later commit, rather than all queueing behind each other for
+/// execution.
+#[derive(Debug, Default)]
+pub(crate) struct SharedObjectCongestionTracker {
+    object_txn_counts: HashMap<ObjectID, u64>,
+}
+
+impl SharedObjectCongestionTracker {
+    /// Returns the deferral key for `cert` if any of the shared objects it mutates has already
+    /// reached `max_txns_per_object` transactions in this commit, along with those objects.
+    ///
+    /// A transaction that has already been deferred for `max_deferral_rounds` rounds is never
+    /// deferred again, so that a persistently hot object cannot starve it.
+    pub fn should_defer_due_to_object_congestion(
+        &self,
+        cert: &VerifiedExecutableTransaction,
+        max_txns_per_object: u64,
+        max_deferral_rounds: u64,
+        previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
+        commit_round: Round,
+    ) -> Option<(DeferralKey, Vec<ObjectID>)> {
+        let congested_objects: Vec<_> = mutated_shared_objects(cert)
+            .filter(|id| {
+                self.object_txn_counts.get(id).copied().unwrap_or_default() >= max_txns_per_object
+            })
+            .collect();
+
+        if congested_objects.is_empty() {
+            return None;
+        }
+
+        // Deferral is always measured from the round the transaction was first deferred in, so
+        // repeated deferrals count towards the same limit.
+        let deferred_from_round = previously_deferred_tx_digests
+            .get(cert.digest())
+            .map_or(commit_round, DeferralKey::deferred_from_round);
+
+        if commit_round.saturating_sub(deferred_from_round) >= max_deferral_rounds {
+            return None;
+        }
+
+        Some((
+            DeferralKey::new_for_consensus_round(commit_round + 1, deferred_from_round),
+            congested_objects,
+        ))
+    }
+
+    /// Records that `cert` has been scheduled in the current commit, counting it against the
+    /// budget of every shared object it mutates.
+    pub fn bump_object_txn_counts(&mut self, cert: &VerifiedExecutableTransaction) {
+        for id in mutated_shared_objects(cert) {
+            *self.object_txn_counts.entry(id).or_default() += 1;
+        }
+    }
+}
+
+/// Shared objects taken by mutable reference are the ones that serialize execution. Transactions
+/// that only read a shared object do not contribute to its congestion.
+fn mutated_shared_objects(
+    cert: &VerifiedExecutableTransaction,
+) -> impl Iterator<Item = ObjectID> + '_ {
+    cert.shared_input_objects()
+        .filter(|object| object.mutable)
+        .map(|object| object.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, BTreeSet};
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::base_types::{random_object_ref, SequenceNumber};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+    use sui_types::transaction::{CallArg, ObjectArg, TransactionDataAPI, VerifiedTransaction};
+    use sui_types::utils::to_sender_signed_transaction;
+
+    const MAX_TXNS_PER_OBJECT: u64 = 2;
+    const MAX_DEFERRAL_ROUNDS: u64 = 4;
+
+    /// Build a certificate that takes each of `objects` as a shared input, mutably or not.
+    fn shared_object_cert(
+        gas_price: u64,
+        objects: &[(ObjectID, bool)],
+    ) -> VerifiedExecutableTransaction {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let args = objects
+            .iter()
+            .map(|(id, mutable)| {
+                CallArg::Object(ObjectArg::SharedObject {
+                    id: *id,
+                    initial_shared_version: SequenceNumber::from_u64(1),
+                    mutable: *mutable,
+                })
+            })
+            .collect();
+
+        let data = TestTransactionBuilder::new(sender, random_object_ref(), gas_price)
+            .move_call(ObjectID::random(), "pool", "swap", args)
+            .build();
+
+        VerifiedExecutableTransaction::new_system(
+            VerifiedTransaction::new_unchecked(to_sender_signed_transaction(data, &keypair)),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_budget_per_object() {
+        let hot = ObjectID::random();
+        let cold = ObjectID::random();
+        let mut tracker = SharedObjectCongestionTracker::default();
+        let no_deferrals = HashMap::new();
+
+        for _ in 0..MAX_TXNS_PER_OBJECT {
+            let cert = shared_object_cert(1000, &[(hot, true)]);
+            assert!(tracker
+                .should_defer_due_to_object_congestion(
+                    &cert,
+                    MAX_TXNS_PER_OBJECT,
+                    MAX_DEFERRAL_ROUNDS,
+                    &no_deferrals,
+                    10,
+                )
+                .is_none());
+            tracker.bump_object_txn_counts(&cert);
+        }
+
+        // The hot object has used up its budget, so anything else that mutates it is deferred to
+        // the next round...
+        let cert = shared_object_cert(1000, &[(cold, true), (hot, true)]);
+        let (key, congested) = tracker
+            .should_defer_due_to_object_congestion(
+                &cert,
+                MAX_TXNS_PER_OBJECT,
+                MAX_DEFERRAL_ROUNDS,
+                &no_deferrals,
+                10,
+            )
+            .unwrap();
+        assert_eq!(key, DeferralKey::new_for_consensus_round(11, 10));
+        assert_eq!(congested, vec![hot]);
+
+        // ...but reading it, or mutating other objects, is still allowed.
+        for objects in [vec![(hot, false)], vec![(cold, true)]] {
+            let cert = shared_object_cert(1000, &objects);
+            assert!(tracker
+                .should_defer_due_to_object_congestion(
+                    &cert,
+                    MAX_TXNS_PER_OBJECT,
+                    MAX_DEFERRAL_ROUNDS,
+                    &no_deferrals,
+                    10,
+                )
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn test_deferral_is_bounded() {
+        let hot = ObjectID::random();
+        let mut tracker = SharedObjectCongestionTracker::default();
+        let busy = shared_object_cert(1000, &[(hot, true)]);
+        for _ in 0..MAX_TXNS_PER_OBJECT {
+            tracker.bump_object_txn_counts(&busy);
+        }
+
+        let cert = shared_object_cert(1000, &[(hot, true)]);
+        let mut deferrals = HashMap::new();
+
+        // Re-deferring keeps the round the transaction was originally deferred from.
+        deferrals.insert(*cert.digest(), DeferralKey::new_for_consensus_round(12, 10));
+        let (key, _) = tracker
+            .should_defer_due_to_object_congestion(
+                &cert,
+                MAX_TXNS_PER_OBJECT,
+                MAX_DEFERRAL_ROUNDS,
+                &deferrals,
+                12,
+            )
+            .unwrap();
+        assert_eq!(key, DeferralKey::new_for_consensus_round(13, 10));
+
+        // Once it has been deferred for long enough, it is scheduled despite the congestion.
+        assert!(tracker
+            .should_defer_due_to_object_congestion(
+                &cert,
+                MAX_TXNS_PER_OBJECT,
+                MAX_DEFERRAL_ROUNDS,
+                &deferrals,
+                10 + MAX_DEFERRAL_ROUNDS,
+            )
+            .is_none());
+    }
+
+    /// Simulate a burst of transactions on a single hot object, interleaved with transactions on
+    /// unrelated objects, over a sequence of commits. Each commit orders new and previously
+    /// deferred transactions by gas price, and schedules them subject to the congestion budget.
+    #[test]
+    fn test_simulate_hot_object_burst() {
+        let hot = ObjectID::random();
+        let burst = 10;
+
+        // All transactions arrive in the first commit, with distinct gas prices.
+        let hot_txns = (0..burst).map(|i| shared_object_cert(1000 + i, &[(hot, true)]));
+        let cold_txns =
+            (0..burst).map(|i| shared_object_cert(1000 + i, &[(ObjectID::random(), true)]));
+        let mut arrivals: Vec<_> = hot_txns.chain(cold_txns).collect();
+
+        let mut deferred: BTreeMap<DeferralKey, Vec<VerifiedExecutableTransaction>> =
+            BTreeMap::new();
+        let mut scheduled_in: HashMap<TransactionDigest, Round> = HashMap::new();
+
+        for round in 1..=(burst / MAX_TXNS_PER_OBJECT + MAX_DEFERRAL_ROUNDS) {
+            let mut previously_deferred = HashMap::new();
+            let mut txns = std::mem::take(&mut arrivals);
+            let ready: Vec<_> = deferred
+                .range(..DeferralKey::new_for_consensus_round(round + 1, 0))
+                .map(|(key, _)| *key)
+                .collect();
+            for key in ready {
+                for cert in deferred.remove(&key).unwrap() {
+                    previously_deferred.insert(*cert.digest(), key);
+                    txns.push(cert);
+                }
+            }
+
+            txns.sort_by_key(|cert| std::cmp::Reverse(cert.transaction_data().gas_price()));
+
+            let mut tracker = SharedObjectCongestionTracker::default();
+            let mut hot_in_round = 0;
+            for cert in txns {
+                if let Some((key, congested)) = tracker.should_defer_due_to_object_congestion(
+                    &cert,
+                    MAX_TXNS_PER_OBJECT,
+                    MAX_DEFERRAL_ROUNDS,
+                    &previously_deferred,
+                    round,
+                ) {
+                    assert_eq!(congested, vec![hot]);
+                    deferred.entry(key).or_default().push(cert);
+                    continue;
+                }
+
+                if cert.shared_input_objects().any(|object| object.id == hot) {
+                    hot_in_round += 1;
+                }
+                tracker.bump_object_txn_counts(&cert);
+                assert!(scheduled_in.insert(*cert.digest(), round).is_none());
+            }
+
+            assert!(hot_in_round <= MAX_TXNS_PER_OBJECT);
+        }
+
+        // Everything was eventually scheduled, and unrelated transactions were never deferred.
+        assert!(deferred.is_empty());
+        assert_eq!(scheduled_in.len() as u64, 2 * burst);
+        let rounds: BTreeSet<_> = scheduled_in.values().copied().collect();
+        assert_eq!(
+            rounds.len() as u64,
+            burst / MAX_TXNS_PER_OBJECT,
+            "hot object transactions should be spread over commits"
+        );
+        assert_eq!(
+            scheduled_in.values().filter(|round| **round == 1).count() as u64,
+            burst + MAX_TXNS_PER_OBJECT
+        );
+    }
+}
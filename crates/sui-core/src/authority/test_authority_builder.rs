@@ -162,6 +162,9 @@ impl<'a> TestAuthorityBuilder<'a> {
         let local_network_config = local_network_config_builder.build();
         let genesis = &self.genesis.unwrap_or(&local_network_config.genesis);
         let genesis_committee = genesis.committee().unwrap();
+        // Stores which support it are kept in memory unless a path to persist them in is given.
+        // The epoch and index stores are always backed by RocksDB.
+        let in_memory = self.store_base_path.is_none();
         let path = self.store_base_path.unwrap_or_else(|| {
             let dir = std::env::temp_dir();
             let store_base_path =
@@ -172,8 +175,11 @@ impl<'a> TestAuthorityBuilder<'a> {
         let authority_store = match self.store {
             Some(store) => store,
             None => {
-                let perpetual_tables =
-                    Arc::new(AuthorityPerpetualTables::open(&path.join("store"), None));
+                let perpetual_tables = Arc::new(if in_memory {
+                    AuthorityPerpetualTables::open_in_memory()
+                } else {
+                    AuthorityPerpetualTables::open(&path.join("store"), None)
+                });
                 // unwrap ok - for testing only.
                 AuthorityStore::open_with_committee_for_testing(
                     perpetual_tables,
@@ -234,13 +240,17 @@ impl<'a> TestAuthorityBuilder<'a> {
             &expensive_safety_checks,
             ChainIdentifier::from(*genesis.checkpoint().digest()),
         );
-        let committee_store = Arc::new(CommitteeStore::new(
-            path.join("epochs"),
-            &genesis_committee,
-            None,
-        ));
+        let committee_store = Arc::new(if in_memory {
+            CommitteeStore::new_for_testing(&genesis_committee)
+        } else {
+            CommitteeStore::new(path.join("epochs"), &genesis_committee, None)
+        });
 
-        let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
+        let checkpoint_store = if in_memory {
+            CheckpointStore::new_for_tests()
+        } else {
+            CheckpointStore::new(&path.join("checkpoints"))
+        };
         if self.insert_genesis_checkpoint {
             checkpoint_store.insert_genesis_checkpoint(
                 genesis.checkpoint(),
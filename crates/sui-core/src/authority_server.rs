@@ -321,6 +321,7 @@ impl ValidatorService {
         // the entire epoch. By doing locking but pushback, retrying transaction will have
         // higher chance to succeed.
         let mut validator_pushback_error = None;
+        state.record_transaction_sender(transaction.data());
         let overload_check_res = state.check_system_overload(
             &consensus_adapter,
             transaction.data(),
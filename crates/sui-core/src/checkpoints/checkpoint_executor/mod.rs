@@ -1141,6 +1141,9 @@ async fn finalize_checkpoint(
             .commit_transaction_outputs(epoch_store.epoch(), digest)
             .await?;
     }
+    state
+        .transaction_manager()
+        .notify_outputs_committed(tx_digests, &epoch_store)?;
     if epoch_store.per_epoch_finalized_txns_enabled() {
         epoch_store.insert_finalized_transactions(tx_digests, checkpoint.sequence_number)?;
     }
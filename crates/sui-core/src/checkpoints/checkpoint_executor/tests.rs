@@ -4,7 +4,6 @@
 use super::*;
 use sui_config::node::ExpensiveSafetyCheckConfig;
 use sui_types::gas::GasCostSummary;
-use tempfile::tempdir;
 
 use std::{sync::Arc, time::Duration};
 
@@ -29,8 +28,7 @@ use typed_store::Map;
 #[tokio::test]
 pub async fn test_checkpoint_executor_crash_recovery() {
     let buffer_size = num_cpus::get() * 2;
-    let tempdir = tempdir().unwrap();
-    let checkpoint_store = CheckpointStore::new(tempdir.path());
+    let checkpoint_store = CheckpointStore::new_for_tests();
 
     let (state, mut executor, accumulator, checkpoint_sender, committee): (
         Arc<AuthorityState>,
@@ -111,8 +109,7 @@ pub async fn test_checkpoint_executor_crash_recovery() {
 pub async fn test_checkpoint_executor_cross_epoch() {
     let buffer_size = 10;
     let num_to_sync_per_epoch = buffer_size * 2;
-    let tempdir = tempdir().unwrap();
-    let checkpoint_store = CheckpointStore::new(tempdir.path());
+    let checkpoint_store = CheckpointStore::new_for_tests();
 
     let (authority_state, mut executor, accumulator, checkpoint_sender, first_committee): (
         Arc<AuthorityState>,
@@ -275,8 +272,7 @@ pub async fn test_checkpoint_executor_cross_epoch() {
 #[tokio::test]
 #[ignore]
 pub async fn test_reconfig_crash_recovery() {
-    let tempdir = tempdir().unwrap();
-    let checkpoint_store = CheckpointStore::new(tempdir.path());
+    let checkpoint_store = CheckpointStore::new_for_tests();
 
     // new Node (syncing from checkpoint 0)
     let (authority_state, mut executor, accumulator, checkpoint_sender, first_committee): (
@@ -193,6 +193,12 @@ impl CheckpointStore {
         ))
     }
 
+    /// Opens the store against an in-memory database, for tests which do not need the
+    /// checkpoints to be persisted
+    pub fn new_for_tests() -> Arc<Self> {
+        Arc::new(Self::open_tables_in_memory(MetricConf::new("checkpoint")))
+    }
+
     pub fn open_readonly(path: &Path) -> CheckpointStoreReadOnly {
         Self::get_read_only_handle(
             path.to_path_buf(),
@@ -2059,8 +2065,7 @@ mod tests {
             mpsc::channel::<CertifiedCheckpointSummary>(10);
         let store = Arc::new(store);
 
-        let ckpt_dir = tempfile::tempdir().unwrap();
-        let checkpoint_store = CheckpointStore::new(ckpt_dir.path());
+        let checkpoint_store = CheckpointStore::new_for_tests();
 
         let accumulator = StateAccumulator::new(state.get_accumulator_store().clone());
 
@@ -514,12 +514,16 @@ impl MysticetiConsensusHandler {
     pub fn new(
         mut consensus_handler: ConsensusHandler<CheckpointService>,
         mut receiver: tokio::sync::mpsc::UnboundedReceiver<consensus_core::CommittedSubDag>,
+        commit_consumer_monitor: Arc<consensus_core::CommitConsumerMonitor>,
     ) -> Self {
         let handle = spawn_monitored_task!(async move {
             while let Some(committed_subdag) = receiver.recv().await {
+                let commit_index = committed_subdag.commit_index;
                 consensus_handler
                     .handle_consensus_output_internal(committed_subdag)
                     .await;
+                // The commit does not need to be replayed by consensus after restarts anymore.
+                commit_consumer_monitor.set_highest_handled_commit(commit_index);
             }
         });
         Self { handle }
@@ -809,7 +813,9 @@ mod tests {
     use narwhal_types::{Batch, Certificate, CommittedSubDag, HeaderV1Builder, ReputationScores};
     use prometheus::Registry;
     use shared_crypto::intent::Intent;
-    use sui_protocol_config::{ConsensusTransactionOrdering, SupportedProtocolVersions};
+    use sui_protocol_config::{
+        ConsensusTransactionOrdering, ProtocolConfig, SupportedProtocolVersions,
+    };
     use sui_types::{
         base_types::{random_object_ref, AuthorityName, SuiAddress},
         committee::Committee,
@@ -1046,6 +1052,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_order_by_gas_price_with_congestion_control() {
+        // Congestion control defers transactions after ordering, so the ordering itself is the
+        // same as ordering by gas price.
+        let mut v = vec![user_txn(42), cap_txn(10), user_txn(1000), user_txn(100)];
+        PostConsensusTxReorder::reorder(
+            &mut v,
+            ConsensusTransactionOrdering::ByGasPriceWithCongestionControl,
+        );
+        assert_eq!(
+            extract(v),
+            vec![
+                "cap(10)".to_string(),
+                "user(1000)".to_string(),
+                "user(100)".to_string(),
+                "user(42)".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_congestion_control_defers_transactions() {
+        // GIVEN an authority that schedules at most one transaction per shared object in each
+        // commit, and defers a transaction for at most two rounds.
+        let mut objects = test_gas_objects();
+        objects.push(Object::shared_for_testing());
+
+        let network_config =
+            sui_swarm_config::network_config_builder::ConfigBuilder::new_with_temp_dir()
+                .with_objects(objects.clone())
+                .build();
+
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        protocol_config.set_consensus_transaction_ordering_for_testing(
+            ConsensusTransactionOrdering::ByGasPriceWithCongestionControl,
+        );
+        protocol_config.set_max_txns_per_shared_object_in_commit_for_testing(1);
+        protocol_config.set_max_deferral_rounds_for_congestion_control_for_testing(2);
+
+        let state = TestAuthorityBuilder::new()
+            .with_network_config(&network_config)
+            .with_protocol_config(protocol_config)
+            .build()
+            .await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        // AND four certificates that all mutate the same shared object, at the same gas price.
+        let certificates = test_certificates(&state).await;
+        assert_eq!(certificates.len(), 4);
+        let digest = |cert: &CertifiedTransaction| *cert.digest();
+
+        let process = |certificates: &[CertifiedTransaction]| {
+            let transactions = certificates
+                .iter()
+                .map(|cert| {
+                    SequencedConsensusTransaction::new_test(
+                        ConsensusTransaction::new_certificate_message(&state.name, cert.clone()),
+                    )
+                })
+                .collect();
+            let epoch_store = epoch_store.clone();
+            let state = state.clone();
+            async move {
+                epoch_store
+                    .process_consensus_transactions_for_tests(
+                        transactions,
+                        &Arc::new(CheckpointServiceNoop {}),
+                        state.get_cache_reader().as_ref(),
+                        &state.metrics.skipped_consensus_txns,
+                    )
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|cert| *cert.digest())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // WHEN the certificates are sequenced in one commit
+        // THEN only the first is scheduled, and the rest are deferred to the next commit.
+        let scheduled = process(&certificates).await;
+        assert_eq!(scheduled, vec![digest(&certificates[0])]);
+
+        // WHEN the next commit has no transactions
+        // THEN the deferred transactions are reloaded, the first of them is scheduled and the
+        // rest are deferred again.
+        let scheduled = process(&[]).await;
+        assert_eq!(scheduled, vec![digest(&certificates[1])]);
+
+        // WHEN the commit after that has no transactions either
+        // THEN the remaining transactions were first deferred two rounds ago, so they have
+        // reached the deferral limit and are all scheduled despite the congestion.
+        let scheduled = process(&[]).await;
+        assert_eq!(
+            scheduled.into_iter().collect::<BTreeSet<_>>(),
+            BTreeSet::from([digest(&certificates[2]), digest(&certificates[3])])
+        );
+
+        // AND nothing is left deferred.
+        assert!(process(&[]).await.is_empty());
+    }
+
     fn extract(v: Vec<VerifiedSequencedConsensusTransaction>) -> Vec<String> {
         v.into_iter().map(extract_one).collect()
     }
@@ -86,6 +86,7 @@ impl ConsensusManager {
             config.protocol_key_pair().copy(),
             config.network_key_pair().copy(),
             consensus_config.db_path().to_path_buf(),
+            consensus_config.parameters.clone().unwrap_or_default(),
             metrics,
             registry_service.clone(),
             client,
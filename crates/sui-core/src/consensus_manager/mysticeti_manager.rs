@@ -18,6 +18,7 @@ use sui_types::{
     sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
 };
 use tokio::sync::{mpsc::unbounded_channel, Mutex};
+use tracing::info;
 
 use crate::{
     authority::authority_per_epoch_store::AuthorityPerEpochStore,
@@ -26,6 +27,7 @@ use crate::{
         ConsensusManagerMetrics, ConsensusManagerTrait, Running, RunningLockGuard,
     },
     consensus_validator::SuiTxValidator,
+    epoch::data_removal::remove_old_epoch_data,
     mysticeti_adapter::LazyMysticetiClient,
 };
 
@@ -37,6 +39,7 @@ pub struct MysticetiManager {
     keypair: AuthorityKeyPair,
     network_keypair: NetworkKeyPair,
     storage_base_path: PathBuf,
+    parameters: Parameters,
     running: Mutex<Running>,
     metrics: ConsensusManagerMetrics,
     registry_service: RegistryService,
@@ -52,6 +55,7 @@ impl MysticetiManager {
         keypair: AuthorityKeyPair,
         network_keypair: NetworkKeyPair,
         storage_base_path: PathBuf,
+        parameters: Parameters,
         metrics: ConsensusManagerMetrics,
         registry_service: RegistryService,
         client: Arc<LazyMysticetiClient>,
@@ -60,6 +64,7 @@ impl MysticetiManager {
             keypair,
             network_keypair,
             storage_base_path,
+            parameters,
             running: Mutex::new(Running::False),
             metrics,
             registry_service,
@@ -69,7 +74,6 @@ impl MysticetiManager {
         }
     }
 
-    #[allow(unused)]
     fn get_store_path(&self, epoch: EpochId) -> PathBuf {
         let mut store_path = self.storage_base_path.clone();
         store_path.push(format!("{}", epoch));
@@ -102,11 +106,17 @@ impl ConsensusManagerTrait for MysticetiManager {
             return;
         };
 
-        // TODO(mysticeti): Fill in the other fields
+        // Stores of epochs before the previous one are removed at reconfiguration, but a node
+        // restarting in a later epoch would otherwise keep them until the next epoch change.
+        if epoch > 0 {
+            remove_old_epoch_data(self.storage_base_path.clone(), epoch - 1);
+        }
+
         let parameters = Parameters {
             db_path: Some(self.get_store_path(epoch)),
-            ..Default::default()
+            ..self.parameters.clone()
         };
+        info!("Starting consensus with network {:?}", parameters.network);
 
         let name: AuthorityName = self.keypair.public().into();
 
@@ -133,6 +143,7 @@ impl ConsensusManagerTrait for MysticetiManager {
             consensus_handler.last_executed_sub_dag_round() as Round,
             consensus_handler.last_executed_sub_dag_index() as CommitIndex,
         );
+        let commit_consumer_monitor = consumer.monitor();
 
         // TODO(mysticeti): Investigate if we need to return potential errors from
         // AuthorityNode and add retries here?
@@ -165,7 +176,11 @@ impl ConsensusManagerTrait for MysticetiManager {
         );
 
         // spin up the new mysticeti consensus handler to listen for committed sub dags
-        let handler = MysticetiConsensusHandler::new(consensus_handler, commit_receiver);
+        let handler = MysticetiConsensusHandler::new(
+            consensus_handler,
+            commit_receiver,
+            commit_consumer_monitor,
+        );
         self.consensus_handler.store(Some(Arc::new(handler)));
     }
 
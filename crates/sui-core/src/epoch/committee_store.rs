@@ -6,7 +6,6 @@ use rocksdb::Options;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use sui_types::base_types::ObjectID;
 use sui_types::committee::{Committee, EpochId};
 use sui_types::error::{SuiError, SuiResult};
 use typed_store::rocks::{default_db_options, DBMap, DBOptions, MetricConf};
@@ -15,8 +14,6 @@ use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
 use typed_store_derive::DBMapUtils;
 
-use sui_macros::nondeterministic;
-
 pub struct CommitteeStore {
     tables: CommitteeStoreTables,
     cache: RwLock<HashMap<EpochId, Arc<Committee>>>,
@@ -42,6 +39,15 @@ impl CommitteeStore {
             db_options,
             None,
         );
+        Self::new_with_tables(tables, genesis_committee)
+    }
+
+    pub fn new_for_testing(genesis_committee: &Committee) -> Self {
+        let tables = CommitteeStoreTables::open_tables_in_memory(MetricConf::new("committee"));
+        Self::new_with_tables(tables, genesis_committee)
+    }
+
+    fn new_with_tables(tables: CommitteeStoreTables, genesis_committee: &Committee) -> Self {
         let store = Self {
             tables,
             cache: RwLock::new(HashMap::new()),
@@ -54,12 +60,6 @@ impl CommitteeStore {
         store
     }
 
-    pub fn new_for_testing(genesis_committee: &Committee) -> Self {
-        let dir = std::env::temp_dir();
-        let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
-        Self::new(path, genesis_committee, None)
-    }
-
     pub fn init_genesis_committee(&self, genesis_committee: Committee) -> SuiResult {
         assert_eq!(genesis_committee.epoch, 0);
         self.tables.committee_map.insert(&0, &genesis_committee)?;
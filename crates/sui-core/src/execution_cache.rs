@@ -15,6 +15,7 @@ use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
 use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
+use sui_config::node::{ExecutionCacheConfig, ExecutionCacheType};
 use sui_protocol_config::ProtocolVersion;
 use sui_types::base_types::VerifiedExecutionData;
 use sui_types::digests::{TransactionDigest, TransactionEffectsDigest, TransactionEventsDigest};
@@ -34,13 +35,15 @@ use sui_types::{
     object::Owner,
     storage::InputKey,
 };
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 pub(crate) mod cached_version_map;
 pub mod passthrough_cache;
+pub mod proxy_cache;
 pub mod writeback_cache;
 
 use passthrough_cache::PassthroughCache;
+use proxy_cache::ProxyCache;
 use writeback_cache::WritebackCache;
 
 pub struct ExecutionCacheMetrics {
@@ -60,7 +63,24 @@ impl ExecutionCacheMetrics {
     }
 }
 
-pub type ExecutionCache = PassthroughCache;
+pub type ExecutionCache = ProxyCache;
+
+/// Returns the execution cache type configured for the node. It can be overridden with the
+/// `EXECUTION_CACHE` env var, e.g. to run tests with both implementations.
+pub fn choose_execution_cache(config: &ExecutionCacheConfig) -> ExecutionCacheType {
+    let mut cache_type = config.cache_type;
+    if let Ok(cache_choice) = std::env::var("EXECUTION_CACHE") {
+        match cache_choice.as_str() {
+            "passthrough" => cache_type = ExecutionCacheType::Passthrough,
+            "writeback" => cache_type = ExecutionCacheType::Writeback,
+            _ => warn!(
+                "Execution cache env var was set to an invalid choice, using configured execution cache {:?}",
+                cache_type
+            ),
+        }
+    }
+    cache_type
+}
 
 pub trait ExecutionCacheCommit: Send + Sync {
     /// Durably commit the transaction outputs of the given transaction to the database.
@@ -807,6 +827,7 @@ use implement_passthrough_traits;
 
 implement_storage_traits!(PassthroughCache);
 implement_storage_traits!(WritebackCache);
+implement_storage_traits!(ProxyCache);
 
 pub trait ExecutionCacheAPI:
     ExecutionCacheRead
@@ -0,0 +1,419 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! ProxyCache dispatches to the execution cache implementation selected in the node config, so
+//! that the rest of the node can keep using a single concrete `ExecutionCache` type.
+
+use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::authority::authority_store::{ExecutionLockWriteGuard, SuiLockResult};
+use crate::authority::authority_store_tables::LiveObject;
+use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
+use crate::authority::AuthorityStore;
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::AccumulatorStore;
+use crate::transaction_outputs::TransactionOutputs;
+
+use futures::future::BoxFuture;
+use prometheus::Registry;
+use std::path::Path;
+use std::sync::Arc;
+use sui_config::node::{ExecutionCacheConfig, ExecutionCacheType};
+use sui_protocol_config::ProtocolVersion;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, SequenceNumber, VerifiedExecutionData};
+use sui_types::digests::{TransactionDigest, TransactionEffectsDigest, TransactionEventsDigest};
+use sui_types::effects::{TransactionEffects, TransactionEvents};
+use sui_types::error::SuiResult;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Object;
+use sui_types::storage::{MarkerValue, ObjectKey, ObjectOrTombstone, PackageObject};
+use sui_types::sui_system_state::SuiSystemState;
+use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
+use tracing::info;
+
+use super::{
+    choose_execution_cache, CheckpointCache, ExecutionCacheAPI, ExecutionCacheCommit,
+    ExecutionCacheMetrics, ExecutionCacheRead, ExecutionCacheReconfigAPI, ExecutionCacheWrite,
+    NotifyReadWrapper, PassthroughCache, StateSyncAPI, WritebackCache,
+};
+
+macro_rules! delegate_method {
+    ($self:ident.$method:ident($($args:ident),*)) => {
+        match $self {
+            ProxyCache::Passthrough(cache) => cache.$method($($args),*),
+            ProxyCache::Writeback(cache) => cache.$method($($args),*),
+        }
+    };
+}
+
+pub enum ProxyCache {
+    Passthrough(PassthroughCache),
+    Writeback(WritebackCache),
+}
+
+impl ProxyCache {
+    pub fn new(
+        config: &ExecutionCacheConfig,
+        store: Arc<AuthorityStore>,
+        metrics: Arc<ExecutionCacheMetrics>,
+    ) -> Self {
+        let cache_type = choose_execution_cache(config);
+        info!("using execution cache {:?}", cache_type);
+        match cache_type {
+            ExecutionCacheType::Passthrough => {
+                ProxyCache::Passthrough(PassthroughCache::new(store, metrics))
+            }
+            ExecutionCacheType::Writeback => {
+                ProxyCache::Writeback(WritebackCache::new(config, store, metrics))
+            }
+        }
+    }
+
+    /// Uses the default execution cache, so tests exercise the same cache as production unless
+    /// it is overridden from the environment.
+    pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
+        Self::new(
+            &ExecutionCacheConfig::default(),
+            store,
+            Arc::new(ExecutionCacheMetrics::new(registry)),
+        )
+    }
+
+    pub fn as_notify_read_wrapper(self: Arc<Self>) -> NotifyReadWrapper<Self> {
+        NotifyReadWrapper(self)
+    }
+
+    pub fn store_for_testing(&self) -> &Arc<AuthorityStore> {
+        delegate_method!(self.store_for_testing())
+    }
+
+    pub async fn prune_objects_and_compact_for_testing(
+        &self,
+        checkpoint_store: &Arc<CheckpointStore>,
+    ) {
+        match self {
+            ProxyCache::Passthrough(cache) => {
+                cache
+                    .prune_objects_and_compact_for_testing(checkpoint_store)
+                    .await
+            }
+            ProxyCache::Writeback(cache) => {
+                cache
+                    .prune_objects_and_compact_for_testing(checkpoint_store)
+                    .await
+            }
+        }
+    }
+
+    /// Returns an error if the cache holds too many transaction outputs that are not committed
+    /// to the db yet.
+    pub fn check_pending_writes_overload(&self) -> SuiResult {
+        match self {
+            ProxyCache::Passthrough(_) => Ok(()),
+            ProxyCache::Writeback(cache) => cache.check_pending_writes_overload(),
+        }
+    }
+}
+
+impl ExecutionCacheAPI for ProxyCache {}
+
+impl ExecutionCacheCommit for ProxyCache {
+    fn commit_transaction_outputs(
+        &self,
+        epoch: EpochId,
+        digest: &TransactionDigest,
+    ) -> BoxFuture<'_, SuiResult> {
+        // WritebackCache has an inherent method with the same name.
+        match self {
+            ProxyCache::Passthrough(cache) => {
+                ExecutionCacheCommit::commit_transaction_outputs(cache, epoch, digest)
+            }
+            ProxyCache::Writeback(cache) => {
+                ExecutionCacheCommit::commit_transaction_outputs(cache, epoch, digest)
+            }
+        }
+    }
+}
+
+impl ExecutionCacheRead for ProxyCache {
+    fn get_package_object(&self, id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        delegate_method!(self.get_package_object(id))
+    }
+
+    fn force_reload_system_packages(&self, system_package_ids: &[ObjectID]) {
+        delegate_method!(self.force_reload_system_packages(system_package_ids))
+    }
+
+    fn get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        delegate_method!(self.get_object(id))
+    }
+
+    fn get_latest_object_ref_or_tombstone(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<ObjectRef>> {
+        delegate_method!(self.get_latest_object_ref_or_tombstone(object_id))
+    }
+
+    fn get_latest_object_or_tombstone(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<(ObjectKey, ObjectOrTombstone)>> {
+        delegate_method!(self.get_latest_object_or_tombstone(object_id))
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        delegate_method!(self.get_object_by_key(object_id, version))
+    }
+
+    fn multi_get_objects_by_key(
+        &self,
+        object_keys: &[ObjectKey],
+    ) -> SuiResult<Vec<Option<Object>>> {
+        delegate_method!(self.multi_get_objects_by_key(object_keys))
+    }
+
+    fn object_exists_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<bool> {
+        delegate_method!(self.object_exists_by_key(object_id, version))
+    }
+
+    fn multi_object_exists_by_key(&self, object_keys: &[ObjectKey]) -> SuiResult<Vec<bool>> {
+        delegate_method!(self.multi_object_exists_by_key(object_keys))
+    }
+
+    fn find_object_lt_or_eq_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        delegate_method!(self.find_object_lt_or_eq_version(object_id, version))
+    }
+
+    fn get_lock(&self, obj_ref: ObjectRef, epoch_store: &AuthorityPerEpochStore) -> SuiLockResult {
+        delegate_method!(self.get_lock(obj_ref, epoch_store))
+    }
+
+    fn _get_latest_lock_for_object_id(&self, object_id: ObjectID) -> SuiResult<ObjectRef> {
+        delegate_method!(self._get_latest_lock_for_object_id(object_id))
+    }
+
+    fn check_owned_object_locks_exist(&self, owned_object_refs: &[ObjectRef]) -> SuiResult {
+        delegate_method!(self.check_owned_object_locks_exist(owned_object_refs))
+    }
+
+    fn multi_get_transaction_blocks(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<Arc<VerifiedTransaction>>>> {
+        delegate_method!(self.multi_get_transaction_blocks(digests))
+    }
+
+    fn multi_get_executed_effects_digests(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<TransactionEffectsDigest>>> {
+        delegate_method!(self.multi_get_executed_effects_digests(digests))
+    }
+
+    fn multi_get_effects(
+        &self,
+        digests: &[TransactionEffectsDigest],
+    ) -> SuiResult<Vec<Option<TransactionEffects>>> {
+        delegate_method!(self.multi_get_effects(digests))
+    }
+
+    fn multi_get_events(
+        &self,
+        event_digests: &[TransactionEventsDigest],
+    ) -> SuiResult<Vec<Option<TransactionEvents>>> {
+        delegate_method!(self.multi_get_events(event_digests))
+    }
+
+    fn notify_read_executed_effects_digests<'a>(
+        &'a self,
+        digests: &'a [TransactionDigest],
+    ) -> BoxFuture<'a, SuiResult<Vec<TransactionEffectsDigest>>> {
+        delegate_method!(self.notify_read_executed_effects_digests(digests))
+    }
+
+    fn get_sui_system_state_object_unsafe(&self) -> SuiResult<SuiSystemState> {
+        delegate_method!(self.get_sui_system_state_object_unsafe())
+    }
+
+    fn get_marker_value(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<MarkerValue>> {
+        delegate_method!(self.get_marker_value(object_id, version, epoch_id))
+    }
+
+    fn get_latest_marker(
+        &self,
+        object_id: &ObjectID,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<(SequenceNumber, MarkerValue)>> {
+        delegate_method!(self.get_latest_marker(object_id, epoch_id))
+    }
+}
+
+impl ExecutionCacheWrite for ProxyCache {
+    fn write_transaction_outputs(
+        &self,
+        epoch_id: EpochId,
+        tx_outputs: Arc<TransactionOutputs>,
+    ) -> BoxFuture<'_, SuiResult> {
+        delegate_method!(self.write_transaction_outputs(epoch_id, tx_outputs))
+    }
+
+    fn acquire_transaction_locks<'a>(
+        &'a self,
+        epoch_store: &'a AuthorityPerEpochStore,
+        owned_input_objects: &'a [ObjectRef],
+        transaction: VerifiedSignedTransaction,
+    ) -> BoxFuture<'a, SuiResult> {
+        delegate_method!(self.acquire_transaction_locks(
+            epoch_store,
+            owned_input_objects,
+            transaction
+        ))
+    }
+}
+
+impl CheckpointCache for ProxyCache {
+    fn deprecated_get_transaction_checkpoint(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<(EpochId, CheckpointSequenceNumber)>> {
+        delegate_method!(self.deprecated_get_transaction_checkpoint(digest))
+    }
+
+    fn deprecated_multi_get_transaction_checkpoint(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<(EpochId, CheckpointSequenceNumber)>>> {
+        delegate_method!(self.deprecated_multi_get_transaction_checkpoint(digests))
+    }
+
+    fn deprecated_insert_finalized_transactions(
+        &self,
+        digests: &[TransactionDigest],
+        epoch: EpochId,
+        sequence: CheckpointSequenceNumber,
+    ) -> SuiResult {
+        delegate_method!(self.deprecated_insert_finalized_transactions(digests, epoch, sequence))
+    }
+}
+
+impl ExecutionCacheReconfigAPI for ProxyCache {
+    fn insert_genesis_object(&self, object: Object) -> SuiResult {
+        delegate_method!(self.insert_genesis_object(object))
+    }
+
+    fn bulk_insert_genesis_objects(&self, objects: &[Object]) -> SuiResult {
+        delegate_method!(self.bulk_insert_genesis_objects(objects))
+    }
+
+    fn revert_state_update(&self, digest: &TransactionDigest) -> SuiResult {
+        delegate_method!(self.revert_state_update(digest))
+    }
+
+    fn set_epoch_start_configuration(
+        &self,
+        epoch_start_config: &EpochStartConfiguration,
+    ) -> SuiResult {
+        delegate_method!(self.set_epoch_start_configuration(epoch_start_config))
+    }
+
+    fn update_epoch_flags_metrics(&self, old: &[EpochFlag], new: &[EpochFlag]) {
+        delegate_method!(self.update_epoch_flags_metrics(old, new))
+    }
+
+    fn clear_state_end_of_epoch(&self, execution_guard: &ExecutionLockWriteGuard<'_>) {
+        delegate_method!(self.clear_state_end_of_epoch(execution_guard))
+    }
+
+    fn expensive_check_sui_conservation(
+        &self,
+        old_epoch_store: &AuthorityPerEpochStore,
+    ) -> SuiResult {
+        delegate_method!(self.expensive_check_sui_conservation(old_epoch_store))
+    }
+
+    fn checkpoint_db(&self, path: &Path) -> SuiResult {
+        delegate_method!(self.checkpoint_db(path))
+    }
+
+    fn maybe_reaccumulate_state_hash(
+        &self,
+        cur_epoch_store: &AuthorityPerEpochStore,
+        new_protocol_version: ProtocolVersion,
+    ) {
+        delegate_method!(self.maybe_reaccumulate_state_hash(cur_epoch_store, new_protocol_version))
+    }
+}
+
+impl StateSyncAPI for ProxyCache {
+    fn insert_transaction_and_effects(
+        &self,
+        transaction: &VerifiedTransaction,
+        transaction_effects: &TransactionEffects,
+    ) -> SuiResult {
+        delegate_method!(self.insert_transaction_and_effects(transaction, transaction_effects))
+    }
+
+    fn multi_insert_transaction_and_effects(
+        &self,
+        transactions_and_effects: &[VerifiedExecutionData],
+    ) -> SuiResult {
+        delegate_method!(self.multi_insert_transaction_and_effects(transactions_and_effects))
+    }
+}
+
+impl AccumulatorStore for ProxyCache {
+    fn get_object_ref_prior_to_key_deprecated(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<ObjectRef>> {
+        delegate_method!(self.get_object_ref_prior_to_key_deprecated(object_id, version))
+    }
+
+    fn get_root_state_accumulator_for_epoch(
+        &self,
+        epoch: EpochId,
+    ) -> SuiResult<Option<(CheckpointSequenceNumber, Accumulator)>> {
+        delegate_method!(self.get_root_state_accumulator_for_epoch(epoch))
+    }
+
+    fn get_root_state_accumulator_for_highest_epoch(
+        &self,
+    ) -> SuiResult<Option<(EpochId, (CheckpointSequenceNumber, Accumulator))>> {
+        delegate_method!(self.get_root_state_accumulator_for_highest_epoch())
+    }
+
+    fn insert_state_accumulator_for_epoch(
+        &self,
+        epoch: EpochId,
+        checkpoint_seq_num: &CheckpointSequenceNumber,
+        acc: &Accumulator,
+    ) -> SuiResult {
+        delegate_method!(self.insert_state_accumulator_for_epoch(epoch, checkpoint_seq_num, acc))
+    }
+
+    fn iter_live_object_set(
+        &self,
+        include_wrapped_tombstone: bool,
+    ) -> Box<dyn Iterator<Item = LiveObject> + '_> {
+        delegate_method!(self.iter_live_object_set(include_wrapped_tombstone))
+    }
+}
@@ -19,7 +19,6 @@ use sui_types::{
     object::{MoveObject, Owner, OBJECT_START_VERSION},
     storage::ChildObjectResolver,
 };
-use tempfile::tempdir;
 
 use super::*;
 use crate::{
@@ -33,11 +32,7 @@ async fn init_authority_store() -> Arc<AuthorityStore> {
     let (genesis, _) = init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
     let committee = genesis.committee().unwrap();
 
-    // Create a random directory to store the DB
-    let dir = tempdir().unwrap();
-    let db_path = dir.path();
-
-    let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(db_path, None));
+    let perpetual_tables = Arc::new(AuthorityPerpetualTables::open_in_memory());
     AuthorityStore::open_with_committee_for_testing(perpetual_tables, &committee, &genesis, 0)
         .await
         .unwrap()
@@ -80,7 +75,11 @@ impl Scenario {
         static METRICS: once_cell::sync::Lazy<Arc<ExecutionCacheMetrics>> =
             once_cell::sync::Lazy::new(|| Arc::new(ExecutionCacheMetrics::new(default_registry())));
 
-        let cache = Arc::new(WritebackCache::new(store.clone(), (*METRICS).clone()));
+        let cache = Arc::new(WritebackCache::new(
+            &Default::default(),
+            store.clone(),
+            (*METRICS).clone(),
+        ));
         Self {
             store,
             cache,
@@ -368,6 +367,7 @@ impl Scenario {
 
     fn reset_cache(&mut self) {
         self.cache = Arc::new(WritebackCache::new(
+            &self.cache.config,
             self.store.clone(),
             self.cache.metrics.clone(),
         ));
@@ -873,3 +873,34 @@ async fn test_concurrent_readers() {
     t1.await.unwrap();
     t2.await.unwrap();
 }
+
+#[tokio::test]
+async fn test_consuming_writes_wait_for_lock_mutexes() {
+    telemetry_subscribers::init_for_testing();
+    let mut s = Scenario::new(None, Arc::new(AtomicU32::new(0))).await;
+    s.with_created(&[1]);
+    let tx = s.do_tx().await;
+    s.commit(tx).await.unwrap();
+
+    // Signing holds the mutex of the object while it checks that the object is live and
+    // writes the lock.
+    let obj_ref = s.object(1).compute_object_reference();
+    let mutexes = s.store.acquire_locks(&[obj_ref]).await;
+
+    s.with_mutated(&[1]);
+    let outputs = s.take_outputs();
+    let cache = s.cache.clone();
+    let mut write = tokio::spawn(async move { cache.write_transaction_outputs(1, outputs).await });
+
+    // The object cannot be consumed while its mutex is held.
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(100), &mut write)
+            .await
+            .is_err()
+    );
+    s.cache.check_owned_objects_are_live(&[obj_ref]).unwrap();
+
+    drop(mutexes);
+    write.await.unwrap().unwrap();
+    assert!(s.cache.check_owned_objects_are_live(&[obj_ref]).is_err());
+}
@@ -38,7 +38,7 @@
 //! The above design is used for both objects and markers.
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
-use crate::authority::authority_store::{ExecutionLockWriteGuard, SuiLockResult};
+use crate::authority::authority_store::{ExecutionLockWriteGuard, ObjectLockStatus, SuiLockResult};
 use crate::authority::authority_store_pruner::{
     AuthorityStorePruner, AuthorityStorePruningMetrics,
 };
@@ -63,7 +63,7 @@ use prometheus::Registry;
 use std::collections::BTreeSet;
 use std::hash::Hash;
 use std::sync::Arc;
-use sui_config::node::AuthorityStorePruningConfig;
+use sui_config::node::{AuthorityStorePruningConfig, ExecutionCacheConfig};
 use sui_macros::fail_point_async;
 use sui_protocol_config::ProtocolVersion;
 use sui_types::accumulator::Accumulator;
@@ -79,6 +79,7 @@ use sui_types::object::Object;
 use sui_types::storage::{MarkerValue, ObjectKey, ObjectOrTombstone, ObjectStore, PackageObject};
 use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState};
 use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
+use tap::TapFallible;
 use tracing::{info, instrument};
 
 use super::ExecutionCacheAPI;
@@ -213,18 +214,18 @@ struct CachedCommittedData {
 }
 
 impl CachedCommittedData {
-    fn new() -> Self {
+    fn new(max_cache_size: u64) -> Self {
         let object_cache = MokaCache::builder()
-            .max_capacity(10000)
-            .initial_capacity(10000)
+            .max_capacity(max_cache_size)
+            .initial_capacity(max_cache_size as usize)
             .build();
         let marker_cache = MokaCache::builder()
-            .max_capacity(10000)
-            .initial_capacity(10000)
+            .max_capacity(max_cache_size)
+            .initial_capacity(max_cache_size as usize)
             .build();
         let transaction_objects = MokaCache::builder()
-            .max_capacity(10000)
-            .initial_capacity(10000)
+            .max_capacity(max_cache_size)
+            .initial_capacity(max_cache_size as usize)
             .build();
 
         Self {
@@ -254,6 +255,7 @@ pub struct WritebackCache {
     executed_effects_digests_notify_read: NotifyRead<TransactionDigest, TransactionEffectsDigest>,
     store: Arc<AuthorityStore>,
     metrics: Arc<ExecutionCacheMetrics>,
+    config: ExecutionCacheConfig,
 }
 
 macro_rules! check_cache_entry_by_version {
@@ -287,31 +289,85 @@ macro_rules! check_cache_entry_by_latest {
 }
 
 impl WritebackCache {
-    fn new(store: Arc<AuthorityStore>, metrics: Arc<ExecutionCacheMetrics>) -> Self {
+    pub fn new(
+        config: &ExecutionCacheConfig,
+        store: Arc<AuthorityStore>,
+        metrics: Arc<ExecutionCacheMetrics>,
+    ) -> Self {
         let packages = MokaCache::builder()
-            .max_capacity(10000)
-            .initial_capacity(10000)
+            .max_capacity(config.max_cache_size)
+            .initial_capacity(config.max_cache_size as usize)
             .build();
         Self {
             dirty: UncommittedData::new(),
-            cached: CachedCommittedData::new(),
+            cached: CachedCommittedData::new(config.max_cache_size),
             packages,
             executed_effects_digests_notify_read: NotifyRead::new(),
             store,
             metrics,
+            config: config.clone(),
         }
     }
 
     pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
-        Self::new(store, ExecutionCacheMetrics::new(registry).into())
+        Self::new(
+            &ExecutionCacheConfig::default(),
+            store,
+            ExecutionCacheMetrics::new(registry).into(),
+        )
     }
 
     #[cfg(test)]
     pub fn reset_for_test(&mut self) {
-        let mut new = Self::new(self.store.clone(), self.metrics.clone());
+        let mut new = Self::new(&self.config, self.store.clone(), self.metrics.clone());
         std::mem::swap(self, &mut new);
     }
 
+    /// Returns an error once too many executed transactions have outputs that are not committed
+    /// to the db yet, so that the validator stops accepting new transactions until checkpoint
+    /// execution catches up. This bounds the memory held by the dirty set.
+    pub fn check_pending_writes_overload(&self) -> SuiResult {
+        let queue_len = self.dirty.pending_transaction_writes.len();
+        let threshold = self.config.max_pending_transactions;
+        if queue_len >= threshold {
+            return Err(SuiError::TooManyTransactionsPendingExecution {
+                queue_len,
+                threshold,
+            });
+        }
+        Ok(())
+    }
+
+    // Returns the reference of the latest live version of an object, including versions written
+    // by transactions that are not committed to the db yet.
+    fn get_latest_live_object_ref(&self, object_id: ObjectID) -> SuiResult<ObjectRef> {
+        match self.get_latest_object_ref_or_tombstone(object_id)? {
+            Some(obj_ref) if obj_ref.2.is_alive() => Ok(obj_ref),
+            _ => Err(UserInputError::ObjectNotFound {
+                object_id,
+                version: None,
+            }
+            .into()),
+        }
+    }
+
+    // Owned objects are live if they are the latest version of the object. The live object
+    // markers in the db cannot be used for this, because they are only updated when transaction
+    // outputs are committed.
+    fn check_owned_objects_are_live(&self, owned_object_refs: &[ObjectRef]) -> SuiResult {
+        for obj_ref in owned_object_refs {
+            let latest_ref = self.get_latest_live_object_ref(obj_ref.0)?;
+            if latest_ref != *obj_ref {
+                return Err(UserInputError::ObjectVersionUnavailableForConsumption {
+                    provided_obj_ref: *obj_ref,
+                    current_version: latest_ref.1,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
     async fn write_object_entry(
         &self,
         object_id: &ObjectID,
@@ -481,6 +537,8 @@ impl WritebackCache {
         epoch: EpochId,
         digest: TransactionDigest,
     ) -> SuiResult {
+        fail_point_async!("writeback-cache-commit");
+
         let Some((_, outputs)) = self.dirty.pending_transaction_writes.remove(&digest) else {
             panic!("Attempt to commit unknown transaction {:?}", digest);
         };
@@ -640,13 +698,19 @@ impl WritebackCache {
         NotifyReadWrapper(self)
     }
 
-    fn clear_state_end_of_epoch_impl(&self, _execution_guard: &ExecutionLockWriteGuard<'_>) {
+    fn clear_state_end_of_epoch_impl(&self, execution_guard: &ExecutionLockWriteGuard<'_>) {
         info!("clearing state at end of epoch");
         assert!(
             self.dirty.pending_transaction_writes.is_empty(),
             "should be empty due to revert_state_update"
         );
         self.dirty.clear();
+        self.store
+            .clear_object_per_epoch_marker_table(execution_guard)
+            .tap_err(|e| {
+                tracing::error!(?e, "Failed to clear object per-epoch marker table");
+            })
+            .ok();
     }
 
     fn revert_state_update_impl(&self, tx: &TransactionDigest) -> SuiResult {
@@ -1036,20 +1100,27 @@ impl ExecutionCacheRead for WritebackCache {
         }
     }
 
-    fn get_lock(
-        &self,
-        _obj_ref: ObjectRef,
-        _epoch_store: &AuthorityPerEpochStore,
-    ) -> SuiLockResult {
-        todo!()
+    fn get_lock(&self, obj_ref: ObjectRef, epoch_store: &AuthorityPerEpochStore) -> SuiLockResult {
+        if !epoch_store.object_lock_split_tables_enabled() {
+            // Locks are kept in the live object markers, which are only written on commit.
+            return self.store.get_lock(obj_ref, epoch_store);
+        }
+
+        let latest_ref = self.get_latest_live_object_ref(obj_ref.0)?;
+        if latest_ref != obj_ref {
+            return Ok(ObjectLockStatus::LockedAtDifferentVersion {
+                locked_ref: latest_ref,
+            });
+        }
+        self.store.get_lock_for_live_object(obj_ref, epoch_store)
     }
 
-    fn _get_latest_lock_for_object_id(&self, _object_id: ObjectID) -> SuiResult<ObjectRef> {
-        todo!()
+    fn _get_latest_lock_for_object_id(&self, object_id: ObjectID) -> SuiResult<ObjectRef> {
+        self.get_latest_live_object_ref(object_id)
     }
 
-    fn check_owned_object_locks_exist(&self, _owned_object_refs: &[ObjectRef]) -> SuiResult {
-        todo!()
+    fn check_owned_object_locks_exist(&self, owned_object_refs: &[ObjectRef]) -> SuiResult {
+        self.check_owned_objects_are_live(owned_object_refs)
     }
 }
 
@@ -1057,11 +1128,33 @@ impl ExecutionCacheWrite for WritebackCache {
     #[instrument(level = "trace", skip_all)]
     fn acquire_transaction_locks<'a>(
         &'a self,
-        _epoch_store: &AuthorityPerEpochStore,
-        _owned_input_objects: &'a [ObjectRef],
-        _tx_digest: VerifiedSignedTransaction,
+        epoch_store: &'a AuthorityPerEpochStore,
+        owned_input_objects: &'a [ObjectRef],
+        transaction: VerifiedSignedTransaction,
     ) -> BoxFuture<'a, SuiResult> {
-        todo!()
+        async move {
+            // The same mutexes are held while outputs consuming these objects are written to
+            // the cache, so the objects cannot be consumed between the liveness check and
+            // writing the locks.
+            let _mutexes = self.store.acquire_locks(owned_input_objects).await;
+            self.check_owned_objects_are_live(owned_input_objects)?;
+            if epoch_store.object_lock_split_tables_enabled() {
+                AuthorityStore::acquire_transaction_locks_for_live_objects(
+                    epoch_store,
+                    owned_input_objects,
+                    transaction,
+                )
+            } else {
+                // Locks are kept in the live object markers, so objects written by uncommitted
+                // transactions cannot be locked until they are committed.
+                self.store.acquire_transaction_locks_with_mutexes(
+                    epoch_store,
+                    owned_input_objects,
+                    transaction,
+                )
+            }
+        }
+        .boxed()
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -1079,9 +1172,14 @@ impl ExecutionCacheWrite for WritebackCache {
                 deleted,
                 wrapped,
                 events,
+                locks_to_delete,
                 ..
             } = &*tx_outputs;
 
+            // Signing holds the mutexes of the consumed objects while checking that they are
+            // live, so they must not be consumed in the middle of the check.
+            let _mutexes = self.store.acquire_locks(locks_to_delete).await;
+
             // Deletions and wraps must be written first. The reason is that one of the deletes
             // may be a child object, and if we write the parent object first, a reader may or may
             // not see the previous version of the child object, instead of the deleted/wrapped
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
     sync::{Arc, Weak},
     time::Duration,
 };
@@ -31,6 +33,63 @@ pub const EXECUTION_MAX_ATTEMPTS: u32 = 10;
 const EXECUTION_FAILURE_RETRY_INTERVAL: Duration = Duration::from_secs(1);
 const QUEUEING_DELAY_SAMPLING_RATIO: f64 = 0.05;
 
+/// A ready certificate is executed before certificates with more dependents, once this many
+/// certificates have become ready after it. This bounds how long certificates without dependents
+/// wait behind transactions on hot objects.
+const MAX_READY_CERTIFICATES_AHEAD: u64 = 1_000;
+
+/// Ready certificates waiting for an execution slot. Certificates with more pending transactions
+/// depending on their outputs are executed first, so transactions on hot objects are not
+/// delayed behind independent transactions. Otherwise certificates are executed in the order
+/// they become ready.
+struct ReadyCertificates<T> {
+    // Number of dependents and sequence number of ready certificates. Entries of certificates
+    // that have already been executed in ready order are skipped.
+    queue: BinaryHeap<(usize, Reverse<u64>)>,
+    // Ready certificates by sequence number.
+    certificates: BTreeMap<u64, T>,
+    next_sequence: u64,
+}
+
+impl<T> Default for ReadyCertificates<T> {
+    fn default() -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+            certificates: BTreeMap::new(),
+            next_sequence: 0,
+        }
+    }
+}
+
+impl<T> ReadyCertificates<T> {
+    fn push(&mut self, num_dependents: usize, certificate: T) {
+        self.queue
+            .push((num_dependents, Reverse(self.next_sequence)));
+        self.certificates.insert(self.next_sequence, certificate);
+        self.next_sequence += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let (&oldest, _) = self.certificates.first_key_value()?;
+        let certificate = if self.next_sequence - oldest > MAX_READY_CERTIFICATES_AHEAD {
+            self.certificates
+                .pop_first()
+                .map(|(_, certificate)| certificate)
+        } else {
+            std::iter::from_fn(|| self.queue.pop())
+                .find_map(|(_, Reverse(sequence))| self.certificates.remove(&sequence))
+        };
+        if self.certificates.is_empty() {
+            self.queue.clear();
+        }
+        certificate
+    }
+
+    fn is_empty(&self) -> bool {
+        self.certificates.is_empty()
+    }
+}
+
 /// When a notification that a new pending transaction is received we activate
 /// processing the transaction in a loop.
 pub async fn execution_process(
@@ -43,26 +102,33 @@ pub async fn execution_process(
     // Rate limit concurrent executions to # of cpus.
     let limit = Arc::new(Semaphore::new(num_cpus::get()));
     let mut rng = StdRng::from_rng(&mut OsRng).unwrap();
+    let mut ready_certificates = ReadyCertificates::default();
 
-    // Loop whenever there is a signal that a new transactions is ready to process.
+    // Loop whenever there is a signal that a new transactions is ready to process, or an
+    // execution slot is available for a ready transaction.
     loop {
         let _scope = monitored_scope("ExecutionDriver::loop");
 
-        let certificate;
-        let expected_effects_digest;
-        let txn_ready_time;
+        let permit;
         tokio::select! {
             result = rx_ready_certificates.recv() => {
-                if let Some(pending_cert) = result {
-                    certificate = pending_cert.certificate;
-                    expected_effects_digest = pending_cert.expected_effects_digest;
-                    txn_ready_time = pending_cert.stats.ready_time.unwrap();
-                } else {
+                let Some(pending_cert) = result else {
                     // Should only happen after the AuthorityState has shut down and tx_ready_certificate
                     // has been dropped by TransactionManager.
                     info!("No more certificate will be received. Exiting executor ...");
                     return;
                 };
+                ready_certificates.push(pending_cert.num_dependents, pending_cert);
+                // Receive all ready certificates before picking the next one to execute.
+                while let Ok(pending_cert) = rx_ready_certificates.try_recv() {
+                    ready_certificates.push(pending_cert.num_dependents, pending_cert);
+                }
+                continue;
+            }
+            // hold semaphore permit until task completes. unwrap ok because we never close
+            // the semaphore in this context.
+            result = limit.clone().acquire_owned(), if !ready_certificates.is_empty() => {
+                permit = result.unwrap();
             }
             _ = &mut rx_execution_shutdown => {
                 info!("Shutdown signal received. Exiting executor ...");
@@ -70,6 +136,13 @@ pub async fn execution_process(
             }
         };
 
+        let pending_cert = ready_certificates
+            .pop()
+            .expect("Ready certificates cannot be empty");
+        let certificate = pending_cert.certificate;
+        let expected_effects_digest = pending_cert.expected_effects_digest;
+        let txn_ready_time = pending_cert.stats.ready_time.unwrap();
+
         let authority = if let Some(authority) = authority_state.upgrade() {
             authority
         } else {
@@ -86,11 +159,6 @@ pub async fn execution_process(
         let digest = *certificate.digest();
         trace!(?digest, "Pending certificate execution activated.");
 
-        let limit = limit.clone();
-        // hold semaphore permit until task completes. unwrap ok because we never close
-        // the semaphore in this context.
-        let permit = limit.acquire_owned().await.unwrap();
-
         if rng.gen_range(0.0..1.0) < QUEUEING_DELAY_SAMPLING_RATIO {
             authority
                 .metrics
@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority::AuthorityState;
+use crate::consensus_adapter::ConsensusAdapter;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use parking_lot::RwLock;
 use std::cmp::{max, min};
+use std::collections::HashSet;
 use std::hash::Hasher;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Weak;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 use sui_config::node::AuthorityOverloadConfig;
+use sui_types::base_types::SuiAddress;
 use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiError;
 use sui_types::error::SuiResult;
@@ -28,19 +34,145 @@ pub struct AuthorityOverloadInfo {
 
     /// The calculated percentage of transactions to drop.
     pub load_shedding_percentage: AtomicU32,
+
+    /// The percentage of transactions to drop from heavy senders. Heavy senders are shed first,
+    /// up to all of their transactions, so other senders are shed less.
+    pub heavy_senders_load_shedding_percentage: AtomicU32,
+
+    /// The percentage of transactions to drop from senders that are not heavy senders.
+    pub other_senders_load_shedding_percentage: AtomicU32,
+
+    /// The number of transactions from each sender in the current overload monitor interval.
+    sender_transaction_counts: DashMap<SuiAddress, u64>,
+
+    /// The number of senders in `sender_transaction_counts`, kept separately because
+    /// `DashMap::len` locks every shard.
+    num_tracked_senders: AtomicUsize,
+
+    /// The heavy senders of the last overload monitor interval.
+    heavy_senders: RwLock<HashSet<SuiAddress>>,
 }
 
 impl AuthorityOverloadInfo {
     pub fn set_overload(&self, load_shedding_percentage: u32) {
+        let load_shedding_percentage = min(load_shedding_percentage, 100);
         self.is_overload.store(true, Ordering::Relaxed);
         self.load_shedding_percentage
-            .store(min(load_shedding_percentage, 100), Ordering::Relaxed);
+            .store(load_shedding_percentage, Ordering::Relaxed);
+        self.heavy_senders_load_shedding_percentage
+            .store(load_shedding_percentage, Ordering::Relaxed);
+        self.other_senders_load_shedding_percentage
+            .store(load_shedding_percentage, Ordering::Relaxed);
     }
 
     pub fn clear_overload(&self) {
         self.is_overload.store(false, Ordering::Relaxed);
         self.load_shedding_percentage.store(0, Ordering::Relaxed);
+        self.heavy_senders_load_shedding_percentage
+            .store(0, Ordering::Relaxed);
+        self.other_senders_load_shedding_percentage
+            .store(0, Ordering::Relaxed);
+    }
+
+    /// Sets the percentages of transactions to drop from heavy senders, and from other senders.
+    pub fn set_sender_load_shedding_percentages(
+        &self,
+        heavy_senders_load_shedding_percentage: u32,
+        other_senders_load_shedding_percentage: u32,
+    ) {
+        self.heavy_senders_load_shedding_percentage.store(
+            min(heavy_senders_load_shedding_percentage, 100),
+            Ordering::Relaxed,
+        );
+        self.other_senders_load_shedding_percentage.store(
+            min(other_senders_load_shedding_percentage, 100),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Records a transaction from `sender`. Each transaction should be recorded once, when it
+    /// is signed, so that certificates of the same transaction are not counted again.
+    pub fn record_sender_transaction(&self, sender: SuiAddress) {
+        if let Some(mut count) = self.sender_transaction_counts.get_mut(&sender) {
+            *count += 1;
+            return;
+        }
+
+        // Bound the memory used to track senders. Senders beyond the limit are not counted.
+        if self
+            .num_tracked_senders
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |num_senders| {
+                (num_senders < MAX_TRACKED_SENDERS).then_some(num_senders + 1)
+            })
+            .is_err()
+        {
+            return;
+        }
+        match self.sender_transaction_counts.entry(sender) {
+            Entry::Occupied(mut entry) => {
+                // The sender was added concurrently, so it doesn't take another slot.
+                *entry.get_mut() += 1;
+                self.num_tracked_senders.fetch_sub(1, Ordering::Relaxed);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(1);
+            }
+        }
+    }
+
+    /// Returns whether `sender` is a heavy sender, i.e. it sent more than
+    /// `heavy_sender_threshold` transactions in the current or the last overload monitor
+    /// interval.
+    pub fn is_heavy_sender(&self, sender: SuiAddress, heavy_sender_threshold: u64) -> bool {
+        self.sender_transaction_counts
+            .get(&sender)
+            .is_some_and(|count| *count > heavy_sender_threshold)
+            || self.heavy_senders.read().contains(&sender)
     }
+
+    /// Returns the percentage of transactions to drop from a sender.
+    pub fn sender_load_shedding_percentage(&self, is_heavy_sender: bool) -> u32 {
+        if is_heavy_sender {
+            self.heavy_senders_load_shedding_percentage
+                .load(Ordering::Relaxed)
+        } else {
+            self.other_senders_load_shedding_percentage
+                .load(Ordering::Relaxed)
+        }
+    }
+
+    /// Starts a new overload monitor interval, and updates the heavy senders from the
+    /// transactions of the interval that ended. Returns the number of transactions in the
+    /// interval, and the number of these transactions from heavy senders.
+    fn update_heavy_senders(&self, heavy_sender_threshold: u64) -> (u64, u64) {
+        let mut num_transactions = 0;
+        let mut num_heavy_sender_transactions = 0;
+        let mut num_senders = 0;
+        let mut heavy_senders = HashSet::new();
+        self.sender_transaction_counts.retain(|sender, count| {
+            num_senders += 1;
+            num_transactions += *count;
+            if *count > heavy_sender_threshold {
+                num_heavy_sender_transactions += *count;
+                heavy_senders.insert(*sender);
+            }
+            false
+        });
+        // Senders recorded concurrently with the retain above remain tracked in the new interval.
+        self.num_tracked_senders
+            .fetch_sub(num_senders, Ordering::Relaxed);
+        *self.heavy_senders.write() = heavy_senders;
+        (num_transactions, num_heavy_sender_transactions)
+    }
+}
+
+/// Overload signals from resources other than execution, that are shed in proportion to how
+/// far they are above their soft limits.
+#[derive(Debug, Default)]
+struct ResourceOverloadSignals {
+    consensus_inflight_transactions: u64,
+    checkpoint_execution_lag: u64,
+    rss_bytes: Option<u64>,
 }
 
 const STEADY_OVERLOAD_REDUCTION_PERCENTAGE: u32 = 10;
@@ -50,16 +182,21 @@ const ADDITIONAL_LOAD_SHEDDING: f64 = 0.02;
 // The update interval of the random seed used to determine whether a txn should be rejected.
 const SEED_UPDATE_DURATION_SECS: u64 = 30;
 
+// The maximum number of senders tracked in an overload monitor interval.
+const MAX_TRACKED_SENDERS: usize = 100_000;
+
 // Monitors the overload signals in `authority_state` periodically, and updates its `overload_info`
 // when the signals indicates overload.
 pub async fn overload_monitor(
     authority_state: Weak<AuthorityState>,
+    consensus_adapter: Arc<ConsensusAdapter>,
     config: AuthorityOverloadConfig,
 ) {
     info!("Starting system overload monitor.");
 
     loop {
-        let authority_exist = check_authority_overload(&authority_state, &config);
+        let authority_exist =
+            check_authority_overload(&authority_state, &consensus_adapter, &config);
         if !authority_exist {
             // `authority_state` doesn't exist anymore. Quit overload monitor.
             break;
@@ -74,6 +211,7 @@ pub async fn overload_monitor(
 // Returns whether the authority state exists.
 fn check_authority_overload(
     authority_state: &Weak<AuthorityState>,
+    consensus_adapter: &ConsensusAdapter,
     config: &AuthorityOverloadConfig,
 ) -> bool {
     let authority_arc = authority_state.upgrade();
@@ -91,12 +229,35 @@ fn check_authority_overload(
     let txn_ready_rate = authority.metrics.txn_ready_rate_tracker.lock().rate();
     let execution_rate = authority.metrics.execution_rate_tracker.lock().rate();
 
+    let checkpoint_store = authority.get_checkpoint_store();
+    let highest_synced_checkpoint = checkpoint_store
+        .get_highest_synced_checkpoint()
+        .ok()
+        .flatten()
+        .map(|checkpoint| *checkpoint.sequence_number());
+    let highest_executed_checkpoint = checkpoint_store
+        .get_highest_executed_checkpoint_seq_number()
+        .ok()
+        .flatten();
+    let resource_signals = ResourceOverloadSignals {
+        consensus_inflight_transactions: consensus_adapter.num_inflight_transactions(),
+        checkpoint_execution_lag: highest_synced_checkpoint
+            .unwrap_or_default()
+            .saturating_sub(highest_executed_checkpoint.unwrap_or_default()),
+        rss_bytes: if config.rss_soft_limit_bytes.is_some() && config.rss_hard_limit_bytes.is_some()
+        {
+            get_rss_bytes()
+        } else {
+            None
+        },
+    };
+
     debug!(
-        "Check authority overload signal, queueing latency {:?}, ready rate {:?}, execution rate {:?}.",
-        queueing_latency, txn_ready_rate, execution_rate
+        "Check authority overload signal, queueing latency {:?}, ready rate {:?}, execution rate {:?}, {:?}.",
+        queueing_latency, txn_ready_rate, execution_rate, resource_signals
     );
 
-    let (is_overload, load_shedding_percentage) = check_overload_signals(
+    let (_, execution_load_shedding_percentage) = check_overload_signals(
         config,
         authority
             .overload_info
@@ -106,11 +267,34 @@ fn check_authority_overload(
         txn_ready_rate,
         execution_rate,
     );
+    let resource_load_shedding_percentage =
+        check_resource_overload_signals(config, &resource_signals);
+    let load_shedding_percentage = max(
+        execution_load_shedding_percentage,
+        resource_load_shedding_percentage,
+    );
+    let is_overload = load_shedding_percentage > 0;
+
+    let (num_transactions, num_heavy_sender_transactions) = authority
+        .overload_info
+        .update_heavy_senders(config.heavy_sender_transaction_threshold);
 
     if is_overload {
         authority
             .overload_info
             .set_overload(load_shedding_percentage);
+        let (heavy_senders_load_shedding_percentage, other_senders_load_shedding_percentage) =
+            calculate_sender_load_shedding_percentages(
+                load_shedding_percentage,
+                num_transactions,
+                num_heavy_sender_transactions,
+            );
+        authority
+            .overload_info
+            .set_sender_load_shedding_percentages(
+                heavy_senders_load_shedding_percentage,
+                other_senders_load_shedding_percentage,
+            );
     } else {
         authority.overload_info.clear_overload();
     }
@@ -218,6 +402,133 @@ fn check_overload_signals(
     (overload_status, load_shedding_percentage)
 }
 
+// Calculates the percentage of transactions to drop for a resource signal. It increases linearly
+// from 0 at `soft_limit`, to `max_load_shedding_percentage` at `hard_limit`.
+fn calculate_resource_load_shedding_percentage(
+    value: u64,
+    soft_limit: u64,
+    hard_limit: u64,
+    max_load_shedding_percentage: u32,
+) -> u32 {
+    if value <= soft_limit {
+        return 0;
+    }
+    if value >= hard_limit {
+        return max_load_shedding_percentage;
+    }
+    ((value - soft_limit) as f64 / (hard_limit - soft_limit) as f64
+        * max_load_shedding_percentage as f64)
+        .round() as u32
+}
+
+// Given overload signals of resources other than execution, returns the percentage of
+// transactions to drop, which is driven by the most overloaded resource.
+fn check_resource_overload_signals(
+    config: &AuthorityOverloadConfig,
+    signals: &ResourceOverloadSignals,
+) -> u32 {
+    let consensus_load_shedding_percentage = calculate_resource_load_shedding_percentage(
+        signals.consensus_inflight_transactions,
+        config.consensus_inflight_transactions_soft_limit,
+        config.consensus_inflight_transactions_hard_limit,
+        config.max_load_shedding_percentage,
+    );
+    let checkpoint_load_shedding_percentage = calculate_resource_load_shedding_percentage(
+        signals.checkpoint_execution_lag,
+        config.checkpoint_execution_lag_soft_limit,
+        config.checkpoint_execution_lag_hard_limit,
+        config.max_load_shedding_percentage,
+    );
+    let memory_load_shedding_percentage = match (
+        signals.rss_bytes,
+        config.rss_soft_limit_bytes,
+        config.rss_hard_limit_bytes,
+    ) {
+        (Some(rss_bytes), Some(soft_limit), Some(hard_limit)) => {
+            calculate_resource_load_shedding_percentage(
+                rss_bytes,
+                soft_limit,
+                hard_limit,
+                config.max_load_shedding_percentage,
+            )
+        }
+        _ => 0,
+    };
+    if consensus_load_shedding_percentage > 0
+        || checkpoint_load_shedding_percentage > 0
+        || memory_load_shedding_percentage > 0
+    {
+        info!(
+            "Resource overload detected, consensus {}%, checkpoint execution {}%, memory {}%.",
+            consensus_load_shedding_percentage,
+            checkpoint_load_shedding_percentage,
+            memory_load_shedding_percentage
+        );
+    }
+    max(
+        consensus_load_shedding_percentage,
+        max(
+            checkpoint_load_shedding_percentage,
+            memory_load_shedding_percentage,
+        ),
+    )
+}
+
+// Transactions from heavy senders are dropped first in load shedding mode, only as many as
+// needed to drop `load_shedding_percentage` of all transactions, and at most all of them. Returns
+// the percentages of transactions to drop from heavy senders and from other senders, so that
+// `load_shedding_percentage` of all transactions are dropped.
+fn calculate_sender_load_shedding_percentages(
+    load_shedding_percentage: u32,
+    num_transactions: u64,
+    num_heavy_sender_transactions: u64,
+) -> (u32, u32) {
+    if num_heavy_sender_transactions == 0 {
+        return (load_shedding_percentage, load_shedding_percentage);
+    }
+    // Transactions are counted in hundredths, so that percentages of them are integers.
+    let num_transactions_to_drop = num_transactions * load_shedding_percentage as u64;
+    let heavy_senders_load_shedding_percentage = min(
+        100,
+        (num_transactions_to_drop + num_heavy_sender_transactions - 1)
+            / num_heavy_sender_transactions,
+    );
+
+    let num_other_sender_transactions = num_transactions - num_heavy_sender_transactions;
+    if num_other_sender_transactions == 0 {
+        return (
+            heavy_senders_load_shedding_percentage as u32,
+            load_shedding_percentage,
+        );
+    }
+    let num_other_transactions_to_drop = num_transactions_to_drop
+        .saturating_sub(num_heavy_sender_transactions * heavy_senders_load_shedding_percentage);
+    let other_senders_load_shedding_percentage = min(
+        100,
+        (num_other_transactions_to_drop + num_other_sender_transactions - 1)
+            / num_other_sender_transactions,
+    );
+    (
+        heavy_senders_load_shedding_percentage as u32,
+        other_senders_load_shedding_percentage as u32,
+    )
+}
+
+// Returns the resident memory of the process, when it is available.
+fn get_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines().find_map(|line| {
+        let rss_kb = line
+            .strip_prefix("VmRSS:")?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(rss_kb * 1024)
+    })
+}
+
 // Return true if we should reject the txn with `tx_digest`.
 fn should_reject_tx(
     load_shedding_percentage: u32,
@@ -264,11 +575,13 @@ mod tests {
     use super::*;
 
     use crate::authority::test_authority_builder::TestAuthorityBuilder;
+    use crate::consensus_adapter::{
+        ConnectionMonitorStatusForTests, ConsensusAdapterMetrics, MockSubmitToConsensus,
+    };
     use rand::{
         rngs::{OsRng, StdRng},
         Rng, SeedableRng,
     };
-    use std::sync::Arc;
     use sui_macros::sim_test;
     use tokio::sync::mpsc::unbounded_channel;
     use tokio::sync::mpsc::UnboundedReceiver;
@@ -428,8 +741,13 @@ mod tests {
             .metrics
             .execution_queueing_latency
             .report(Duration::from_secs(20));
+        let consensus_adapter = make_consensus_adapter(&state);
         let authority = Arc::downgrade(&state);
-        assert!(check_authority_overload(&authority, &config));
+        assert!(check_authority_overload(
+            &authority,
+            &consensus_adapter,
+            &config
+        ));
         assert!(state.overload_info.is_overload.load(Ordering::Relaxed));
         assert_eq!(
             state
@@ -443,7 +761,280 @@ mod tests {
         // authority state doesn't exist.
         let authority = Arc::downgrade(&state);
         drop(state);
-        assert!(!check_authority_overload(&authority, &config));
+        assert!(!check_authority_overload(
+            &authority,
+            &consensus_adapter,
+            &config
+        ));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    pub async fn test_check_authority_overload_sheds_heavy_senders_first() {
+        let config = AuthorityOverloadConfig {
+            safe_transaction_ready_rate: 0,
+            heavy_sender_transaction_threshold: 100,
+            ..Default::default()
+        };
+        let state = TestAuthorityBuilder::new()
+            .with_authority_overload_config(config.clone())
+            .build()
+            .await;
+
+        // One sender spams half of the transactions, the other half come from many senders.
+        let spammer = SuiAddress::random_for_testing_only();
+        for _ in 0..500 {
+            state.overload_info.record_sender_transaction(spammer);
+        }
+        let senders: Vec<_> = (0..50)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        for sender in &senders {
+            for _ in 0..10 {
+                state.overload_info.record_sender_transaction(*sender);
+            }
+        }
+
+        state
+            .metrics
+            .execution_queueing_latency
+            .report(Duration::from_secs(20));
+        let consensus_adapter = make_consensus_adapter(&state);
+        assert!(check_authority_overload(
+            &Arc::downgrade(&state),
+            &consensus_adapter,
+            &config
+        ));
+
+        // Half of all transactions are shed, which is achieved by dropping all transactions
+        // from the spammer and none from the other senders.
+        assert_eq!(
+            state
+                .overload_info
+                .load_shedding_percentage
+                .load(Ordering::Relaxed),
+            config.min_load_shedding_percentage_above_hard_limit
+        );
+        let accept_tx = |sender| {
+            let is_heavy_sender = state
+                .overload_info
+                .is_heavy_sender(sender, config.heavy_sender_transaction_threshold);
+            overload_monitor_accept_tx(
+                state
+                    .overload_info
+                    .sender_load_shedding_percentage(is_heavy_sender),
+                TransactionDigest::random(),
+            )
+            .is_ok()
+        };
+        for _ in 0..100 {
+            assert!(!accept_tx(spammer));
+        }
+        for sender in &senders {
+            assert!(accept_tx(*sender));
+        }
+    }
+
+    #[test]
+    pub fn test_max_tracked_senders() {
+        let overload_info = AuthorityOverloadInfo::default();
+        for _ in 0..MAX_TRACKED_SENDERS {
+            overload_info.record_sender_transaction(SuiAddress::random_for_testing_only());
+        }
+
+        // Senders beyond the limit are not counted, while tracked senders still are.
+        let untracked_sender = SuiAddress::random_for_testing_only();
+        overload_info.record_sender_transaction(untracked_sender);
+        assert!(!overload_info.is_heavy_sender(untracked_sender, 0));
+        let tracked_sender = *overload_info
+            .sender_transaction_counts
+            .iter()
+            .next()
+            .unwrap()
+            .key();
+        overload_info.record_sender_transaction(tracked_sender);
+        assert!(overload_info.is_heavy_sender(tracked_sender, 1));
+
+        // Senders are tracked again in the next interval.
+        assert_eq!(
+            overload_info.update_heavy_senders(u64::MAX),
+            (MAX_TRACKED_SENDERS as u64 + 1, 0)
+        );
+        overload_info.record_sender_transaction(untracked_sender);
+        assert!(overload_info.is_heavy_sender(untracked_sender, 0));
+    }
+
+    fn make_consensus_adapter(state: &AuthorityState) -> Arc<ConsensusAdapter> {
+        Arc::new(ConsensusAdapter::new(
+            Arc::new(MockSubmitToConsensus::new()),
+            state.name,
+            Arc::new(ConnectionMonitorStatusForTests {}),
+            100_000,
+            100_000,
+            None,
+            None,
+            ConsensusAdapterMetrics::new_test(),
+            state.epoch_store_for_testing().protocol_config().clone(),
+        ))
+    }
+
+    #[test]
+    pub fn test_calculate_resource_load_shedding_percentage() {
+        // Below or at the soft limit, nothing is shed.
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(50, 100, 200, 90),
+            0
+        );
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(100, 100, 200, 90),
+            0
+        );
+
+        // Between the limits, shedding increases linearly.
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(150, 100, 200, 90),
+            45
+        );
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(175, 100, 200, 80),
+            60
+        );
+
+        // At or above the hard limit, the max percentage is shed.
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(200, 100, 200, 90),
+            90
+        );
+        assert_eq!(
+            calculate_resource_load_shedding_percentage(1000, 100, 200, 90),
+            90
+        );
+    }
+
+    #[test]
+    pub fn test_check_resource_overload_signals() {
+        let config = AuthorityOverloadConfig {
+            consensus_inflight_transactions_soft_limit: 100,
+            consensus_inflight_transactions_hard_limit: 200,
+            checkpoint_execution_lag_soft_limit: 10,
+            checkpoint_execution_lag_hard_limit: 20,
+            max_load_shedding_percentage: 90,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_resource_overload_signals(&config, &ResourceOverloadSignals::default()),
+            0
+        );
+
+        // The most overloaded resource drives load shedding.
+        assert_eq!(
+            check_resource_overload_signals(
+                &config,
+                &ResourceOverloadSignals {
+                    consensus_inflight_transactions: 150,
+                    checkpoint_execution_lag: 18,
+                    rss_bytes: None,
+                }
+            ),
+            72
+        );
+
+        // Memory is not a signal unless its limits are configured.
+        assert_eq!(
+            check_resource_overload_signals(
+                &config,
+                &ResourceOverloadSignals {
+                    rss_bytes: Some(u64::MAX),
+                    ..Default::default()
+                }
+            ),
+            0
+        );
+        let config = AuthorityOverloadConfig {
+            rss_soft_limit_bytes: Some(1000),
+            rss_hard_limit_bytes: Some(2000),
+            ..config
+        };
+        assert_eq!(
+            check_resource_overload_signals(
+                &config,
+                &ResourceOverloadSignals {
+                    rss_bytes: Some(1500),
+                    ..Default::default()
+                }
+            ),
+            45
+        );
+    }
+
+    #[test]
+    pub fn test_calculate_sender_load_shedding_percentages() {
+        // Without heavy senders, all senders are shed equally.
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(50, 1000, 0),
+            (50, 50)
+        );
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(50, 0, 0),
+            (50, 50)
+        );
+
+        // Dropping all transactions of heavy senders first reduces shedding of other senders.
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(50, 1000, 200),
+            (100, 38)
+        );
+
+        // Other senders are not shed when dropping some of the transactions of heavy senders is
+        // enough, and heavy senders are only shed as much as needed.
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(50, 1000, 600),
+            (84, 0)
+        );
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(10, 1000, 500),
+            (20, 0)
+        );
+
+        // Only heavy senders in the last interval.
+        assert_eq!(
+            calculate_sender_load_shedding_percentages(50, 1000, 1000),
+            (50, 50)
+        );
+    }
+
+    #[test]
+    pub fn test_heavy_senders() {
+        let overload_info = AuthorityOverloadInfo::default();
+        let heavy_sender = SuiAddress::random_for_testing_only();
+        let light_sender = SuiAddress::random_for_testing_only();
+
+        for _ in 0..10 {
+            overload_info.record_sender_transaction(heavy_sender);
+            assert!(!overload_info.is_heavy_sender(heavy_sender, 10));
+        }
+        overload_info.record_sender_transaction(heavy_sender);
+        assert!(overload_info.is_heavy_sender(heavy_sender, 10));
+        overload_info.record_sender_transaction(light_sender);
+        assert!(!overload_info.is_heavy_sender(light_sender, 10));
+
+        // Checking whether a sender is heavy does not count its transactions.
+        assert_eq!(overload_info.update_heavy_senders(10), (12, 11));
+
+        // A heavy sender remains heavy in the next interval.
+        overload_info.record_sender_transaction(heavy_sender);
+        assert!(overload_info.is_heavy_sender(heavy_sender, 10));
+        overload_info.record_sender_transaction(light_sender);
+        assert!(!overload_info.is_heavy_sender(light_sender, 10));
+
+        overload_info.set_overload(50);
+        overload_info.set_sender_load_shedding_percentages(80, 20);
+        assert_eq!(overload_info.sender_load_shedding_percentage(true), 80);
+        assert_eq!(overload_info.sender_load_shedding_percentage(false), 20);
+
+        // Heavy senders are cleared after an interval with few transactions from them.
+        assert_eq!(overload_info.update_heavy_senders(10), (2, 0));
+        assert!(!overload_info.is_heavy_sender(heavy_sender, 10));
     }
 
     // Creates an AuthorityState and starts an overload monitor that monitors its metrics.
@@ -453,9 +1044,10 @@ mod tests {
             .with_authority_overload_config(overload_config.clone())
             .build()
             .await;
+        let consensus_adapter = make_consensus_adapter(&state);
         let authority_state = Arc::downgrade(&state);
         let monitor_handle = tokio::spawn(async move {
-            overload_monitor(authority_state, overload_config).await;
+            overload_monitor(authority_state, consensus_adapter, overload_config).await;
         });
         (state, monitor_handle)
     }
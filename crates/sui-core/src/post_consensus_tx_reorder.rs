@@ -15,11 +15,15 @@ impl PostConsensusTxReorder {
         transactions: &mut [VerifiedSequencedConsensusTransaction],
         kind: ConsensusTransactionOrdering,
     ) {
-        // TODO: make the reordering algorithm richer and depend on object hotness as well.
         // Order transactions based on their gas prices. System transactions without gas price
-        // are put to the beginning of the sequenced_transactions vector.
+        // are put to the beginning of the sequenced_transactions vector. With congestion control,
+        // this decides which transactions on a hot shared object fit in the object's budget for
+        // the commit: the rest are deferred when they are processed, in this order.
         match kind {
-            ConsensusTransactionOrdering::ByGasPrice => Self::order_by_gas_price(transactions),
+            ConsensusTransactionOrdering::ByGasPrice
+            | ConsensusTransactionOrdering::ByGasPriceWithCongestionControl => {
+                Self::order_by_gas_price(transactions)
+            }
             ConsensusTransactionOrdering::None => (),
         }
     }
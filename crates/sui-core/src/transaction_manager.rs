@@ -20,7 +20,7 @@ use sui_types::{
     fp_ensure,
     message_envelope::Message,
     storage::InputKey,
-    transaction::{TransactionDataAPI, VerifiedCertificate},
+    transaction::{InputObjectKind, TransactionDataAPI, VerifiedCertificate},
 };
 use sui_types::{executable_transaction::VerifiedExecutableTransaction, fp_bail};
 use tokio::sync::mpsc::UnboundedSender;
@@ -81,6 +81,12 @@ pub struct PendingCertificate {
     pub expected_effects_digest: Option<TransactionEffectsDigest>,
     // The input object this certificate is waiting for to become available in order to be executed.
     pub waiting_input_objects: BTreeSet<InputKey>,
+    // The object versions written by this certificate.
+    pub written_objects: Vec<InputKey>,
+    // The number of pending transactions waiting on the object versions written by this
+    // certificate, when it becomes ready. Execution driver runs certificates with more
+    // dependents first.
+    pub num_dependents: usize,
     // Stores stats about this transaction.
     pub stats: PendingCertificateStats,
 }
@@ -542,10 +548,13 @@ impl TransactionManager {
         let pending_cert_enqueue_time = Instant::now();
 
         for (cert, expected_effects_digest, input_object_keys) in certs {
+            let written_objects = written_object_keys(&cert, &input_object_keys);
             pending.push(PendingCertificate {
                 certificate: cert,
                 expected_effects_digest,
                 waiting_input_objects: input_object_keys,
+                written_objects,
+                num_dependents: 0,
                 stats: PendingCertificateStats {
                     enqueue_time: pending_cert_enqueue_time,
                     ready_time: None,
@@ -553,6 +562,9 @@ impl TransactionManager {
             });
         }
 
+        // Ready certificates are sent after all pending certificates are recorded, so that their
+        // dependents in the same batch are counted.
+        let mut ready_certificates = Vec::new();
         for mut pending_cert in pending {
             // Tx lock is not held here, which makes it possible to send duplicated transactions to
             // the execution driver after crash-recovery, when the same transaction is recovered
@@ -636,8 +648,7 @@ impl TransactionManager {
                     .with_label_values(&["ready"])
                     .inc();
                 pending_cert.stats.ready_time = Some(Instant::now());
-                // Send to execution driver for execution.
-                self.certificate_ready(&mut inner, pending_cert);
+                ready_certificates.push(pending_cert);
                 continue;
             }
 
@@ -656,6 +667,11 @@ impl TransactionManager {
                 .inc();
         }
 
+        for pending_cert in ready_certificates {
+            // Send to execution driver for execution.
+            self.certificate_ready(&mut inner, pending_cert);
+        }
+
         self.metrics
             .transaction_manager_num_missing_objects
             .set(inner.missing_inputs.len() as i64);
@@ -698,14 +714,18 @@ impl TransactionManager {
             return;
         }
 
+        let mut ready_certificates = Vec::new();
         for input_key in input_keys {
             trace!(?input_key, "object available");
-            for mut ready_cert in
-                inner.find_ready_transactions(input_key, update_cache, &self.metrics)
-            {
-                ready_cert.stats.ready_time = Some(available_time);
-                self.certificate_ready(inner, ready_cert);
-            }
+            ready_certificates.extend(inner.find_ready_transactions(
+                input_key,
+                update_cache,
+                &self.metrics,
+            ));
+        }
+        for mut ready_cert in ready_certificates {
+            ready_cert.stats.ready_time = Some(available_time);
+            self.certificate_ready(inner, ready_cert);
         }
 
         self.metrics
@@ -756,14 +776,29 @@ impl TransactionManager {
 
             inner.maybe_shrink_capacity();
         }
+    }
 
-        let _ = epoch_store.remove_pending_execution(digest);
+    /// Notifies TransactionManager that the outputs of the given transactions are committed to
+    /// the db. Only then the certificates can be removed from the pending execution log, because
+    /// the execution cache may hold the outputs in memory until their checkpoint is executed,
+    /// and they must be executed again if the node crashes before.
+    pub(crate) fn notify_outputs_committed(
+        &self,
+        digests: &[TransactionDigest],
+        epoch_store: &AuthorityPerEpochStore,
+    ) -> SuiResult {
+        epoch_store.multi_remove_pending_execution(digests)
     }
 
     /// Sends the ready certificate for execution.
-    fn certificate_ready(&self, inner: &mut Inner, pending_certificate: PendingCertificate) {
+    fn certificate_ready(&self, inner: &mut Inner, mut pending_certificate: PendingCertificate) {
         trace!(tx_digest = ?pending_certificate.certificate.digest(), "certificate ready");
         assert_eq!(pending_certificate.waiting_input_objects.len(), 0);
+        pending_certificate.num_dependents = pending_certificate
+            .written_objects
+            .iter()
+            .map(|key| inner.missing_inputs.get(key).map_or(0, |txns| txns.len()))
+            .sum();
         // Record as an executing certificate.
         assert!(inner
             .executing_certificates
@@ -893,6 +928,42 @@ impl TransactionManager {
     }
 }
 
+/// Returns the object versions written by the transaction. Written objects get the lamport
+/// version of all input objects, so only pending transactions waiting on these exact versions
+/// depend on the transaction.
+fn written_object_keys(
+    cert: &VerifiedExecutableTransaction,
+    input_object_keys: &BTreeSet<InputKey>,
+) -> Vec<InputKey> {
+    let version =
+        SequenceNumber::lamport_increment(input_object_keys.iter().filter_map(|key| key.version()));
+    written_object_ids(cert)
+        .into_iter()
+        .map(|id| InputKey::VersionedObject { id, version })
+        .collect()
+}
+
+/// Returns the ids of input objects that can be written by the transaction: owned objects,
+/// shared objects taken mutably, and receiving objects. Other transactions waiting on these
+/// objects depend on the transaction. Packages and read-only shared objects are only read, so
+/// transactions reading the same versions of them do not depend on each other.
+/// Dynamic-field children are not inputs, so transactions writing different children of the same
+/// shared parent still depend on each other through the parent's version.
+fn written_object_ids(cert: &VerifiedExecutableTransaction) -> BTreeSet<ObjectID> {
+    let tx_data = &cert.data().intent_message().value;
+    tx_data
+        .input_objects()
+        .expect("input_objects() cannot fail")
+        .into_iter()
+        .filter_map(|kind| match kind {
+            InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(id),
+            InputObjectKind::SharedMoveObject { id, mutable, .. } => mutable.then_some(id),
+            InputObjectKind::MovePackage(_) => None,
+        })
+        .chain(tx_data.receiving_objects().into_iter().map(|(id, _, _)| id))
+        .collect()
+}
+
 trait ResizableHashMap<K, V> {
     fn maybe_reserve_capacity(&mut self);
     fn maybe_shrink_capacity(&mut self);
@@ -929,3 +929,30 @@ async fn test_authority_txn_execution_pushback() {
         .await
         .is_ok());
 }
+
+#[test]
+fn ready_certificates_order() {
+    use super::{ReadyCertificates, MAX_READY_CERTIFICATES_AHEAD};
+
+    // Certificates with more dependents are executed first, and otherwise in ready order.
+    let mut ready = ReadyCertificates::default();
+    ready.push(0, "a");
+    ready.push(2, "b");
+    ready.push(0, "c");
+    ready.push(2, "d");
+    assert_eq!(
+        std::iter::from_fn(|| ready.pop()).collect::<Vec<_>>(),
+        vec!["b", "d", "a", "c"]
+    );
+    assert!(ready.is_empty());
+
+    // A certificate without dependents is not starved by later certificates with dependents.
+    ready.push(0, "oldest");
+    for _ in 1..MAX_READY_CERTIFICATES_AHEAD {
+        ready.push(1, "hot");
+    }
+    assert_eq!(ready.pop(), Some("hot"));
+    ready.push(1, "hot");
+    assert_eq!(ready.pop(), Some("oldest"));
+    assert_eq!(ready.pop(), Some("hot"));
+}
@@ -50,6 +50,7 @@ async fn test_mysticeti_manager() {
             config.protocol_key_pair().copy(),
             config.network_key_pair().copy(),
             consensus_config.db_path().to_path_buf(),
+            consensus_config.parameters.clone().unwrap_or_default(),
             metrics,
             registry_service,
             client,
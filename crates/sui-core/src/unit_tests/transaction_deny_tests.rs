@@ -13,7 +13,9 @@ use move_core_types::ident_str;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
-use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
+use sui_config::transaction_deny_config::{
+    TransactionDenyConfig, TransactionDenyConfigBuilder, TransactionKillSwitch,
+};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_test_transaction_builder::TestTransactionBuilder;
@@ -430,6 +432,79 @@ async fn test_package_denied() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_package_upgrade_family_denied() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let (package_c, cap_c) = publish_package_on_single_authority(
+        path.join("src/unit_tests/data/package_deny/c"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[0],
+        [("c", ObjectID::ZERO)],
+        vec![],
+        &state,
+    )
+    .await
+    .unwrap();
+    let package_c_prime = upgrade_package_on_single_authority(
+        path.join("src/unit_tests/data/package_deny/c"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[1],
+        package_c,
+        cap_c,
+        [("c", ObjectID::ZERO)],
+        vec![],
+        &state,
+    )
+    .await
+    .unwrap();
+
+    // Re-create the state such that we could deny the upgrade family of package c.
+    let state = reload_state_with_new_deny_config(
+        &network_config,
+        state,
+        TransactionDenyConfigBuilder::new()
+            .add_denied_package_upgrade_family(package_c)
+            .build(),
+    )
+    .await;
+
+    // Both the original package and its upgrade are denied.
+    let result =
+        handle_move_call_transaction(&state, package_c, "c", "c", vec![], &accounts[0], 2).await;
+    assert_denied(&result);
+    let result =
+        handle_move_call_transaction(&state, package_c_prime, "c", "c", vec![], &accounts[0], 3)
+            .await;
+    assert_denied(&result);
+}
+
+#[tokio::test]
+async fn test_deny_config_updated_at_runtime() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    let mut config = TransactionDenyConfig::clone(&state.transaction_deny_config());
+    assert!(config.add_denied_address(accounts[0].0));
+    assert!(!config.add_denied_address(accounts[0].0));
+    state.set_transaction_deny_config(config.clone());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    assert!(config.remove_denied_address(&accounts[0].0));
+    assert!(config.set_kill_switch(TransactionKillSwitch::UserTransaction, true));
+    state.set_transaction_deny_config(config.clone());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    assert!(config.set_kill_switch(TransactionKillSwitch::UserTransaction, false));
+    state.set_transaction_deny_config(config);
+    assert!(transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .is_ok());
+}
+
 #[tokio::test]
 async fn test_certificate_deny() {
     let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
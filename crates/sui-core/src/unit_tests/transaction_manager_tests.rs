@@ -361,6 +361,141 @@ async fn transaction_manager_object_dependency() {
     transaction_manager.check_empty_for_testing();
 }
 
+// Tests that ready transactions count the pending transactions depending on their writes.
+// Specifically, we have following setup,
+//                      shared_object
+//       /        |          |        |         \
+//    tx_w0     tx_r0      tx_w1     tx_r1     tx_r2
+//    w@1000    r@1000     w@1001    r@1001    r@1002
+// And when shared_object@1000 is available, tx_w0 and tx_r0 are ready, and only tx_w0 has
+// dependents, which are the transactions waiting on the version it writes.
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn transaction_manager_num_dependents() {
+    // Initialize an authority state, with gas objects and a shared object.
+    let (owner, _keypair) = deterministic_random_account_key();
+    let gas_objects: Vec<Object> = (0..5)
+        .map(|_| {
+            let gas_object_id = ObjectID::random();
+            Object::with_id_owner_for_testing(gas_object_id, owner)
+        })
+        .collect();
+    let shared_object = Object::shared_for_testing();
+
+    let state =
+        init_state_with_objects([gas_objects.clone(), vec![shared_object.clone()]].concat()).await;
+
+    // Create a new transaction manager instead of reusing the authority's, to examine
+    // transaction_manager output from rx_ready_certificates.
+    let (transaction_manager, mut rx_ready_certificates) = make_transaction_manager(&state);
+    // TM should output no transaction.
+    assert!(rx_ready_certificates.try_recv().is_err());
+
+    let shared_version_0 = 1000.into();
+    let shared_version_1 = 1001.into();
+    let shared_version_2 = 1002.into();
+    let mut transactions = Vec::new();
+    for (i, (mutable, version)) in [
+        (true, shared_version_0),
+        (false, shared_version_0),
+        (true, shared_version_1),
+        (false, shared_version_1),
+        (false, shared_version_2),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let transaction = make_transaction(
+            gas_objects[i].clone(),
+            vec![CallArg::Object(ObjectArg::SharedObject {
+                id: shared_object.id(),
+                initial_shared_version: 0.into(),
+                mutable,
+            })],
+        );
+        state
+            .epoch_store_for_testing()
+            .set_shared_object_versions_for_testing(
+                transaction.digest(),
+                &vec![(shared_object.id(), version)],
+            )
+            .unwrap();
+        transactions.push(transaction);
+    }
+    let [tx_w0, tx_r0, tx_w1, tx_r1, tx_r2] = transactions.clone().try_into().unwrap();
+
+    transaction_manager.enqueue(transactions, &state.epoch_store_for_testing());
+
+    // TM should output no transaction yet.
+    sleep(Duration::from_secs(1)).await;
+    assert!(rx_ready_certificates.try_recv().is_err());
+
+    // Notify TM about availability of the first version of the shared object.
+    transaction_manager.objects_available(
+        vec![InputKey::VersionedObject {
+            id: shared_object.id(),
+            version: shared_version_0,
+        }],
+        &state.epoch_store_for_testing(),
+    );
+
+    // Only the transaction writing the shared object has dependents.
+    for _ in 0..2 {
+        let pending_certificate = rx_ready_certificates.recv().await.unwrap();
+        let digest = pending_certificate.certificate.digest();
+        if digest == tx_w0.digest() {
+            assert_eq!(pending_certificate.num_dependents, 2);
+        } else {
+            assert_eq!(digest, tx_r0.digest());
+            assert_eq!(pending_certificate.num_dependents, 0);
+        }
+    }
+    sleep(Duration::from_secs(1)).await;
+    assert!(rx_ready_certificates.try_recv().is_err());
+
+    // Notify TM about the commit of the first two transactions.
+    transaction_manager.notify_commit(
+        tx_w0.digest(),
+        vec![InputKey::VersionedObject {
+            id: shared_object.id(),
+            version: shared_version_1,
+        }],
+        &state.epoch_store_for_testing(),
+    );
+    transaction_manager.notify_commit(tx_r0.digest(), vec![], &state.epoch_store_for_testing());
+
+    // The transactions on the second version are ready, and only tx_w1 has tx_r2 as dependent.
+    let mut got_digests = Vec::new();
+    for _ in 0..2 {
+        let pending_certificate = rx_ready_certificates.recv().await.unwrap();
+        let digest = *pending_certificate.certificate.digest();
+        let want_dependents = if digest == *tx_w1.digest() { 1 } else { 0 };
+        assert_eq!(pending_certificate.num_dependents, want_dependents);
+        got_digests.push(digest);
+    }
+    got_digests.sort();
+    let mut want_digests = vec![*tx_w1.digest(), *tx_r1.digest()];
+    want_digests.sort();
+    assert_eq!(want_digests, got_digests);
+
+    transaction_manager.notify_commit(
+        tx_w1.digest(),
+        vec![InputKey::VersionedObject {
+            id: shared_object.id(),
+            version: shared_version_2,
+        }],
+        &state.epoch_store_for_testing(),
+    );
+    transaction_manager.notify_commit(tx_r1.digest(), vec![], &state.epoch_store_for_testing());
+
+    let pending_certificate = rx_ready_certificates.recv().await.unwrap();
+    assert_eq!(pending_certificate.certificate.digest(), tx_r2.digest());
+    assert_eq!(pending_certificate.num_dependents, 0);
+    transaction_manager.notify_commit(tx_r2.digest(), vec![], &state.epoch_store_for_testing());
+
+    // TM should be empty at the end.
+    transaction_manager.check_empty_for_testing();
+}
+
 #[tokio::test(flavor = "current_thread", start_paused = true)]
 async fn transaction_manager_receiving_notify_commit() {
     telemetry_subscribers::init_for_testing();
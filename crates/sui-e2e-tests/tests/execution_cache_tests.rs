@@ -0,0 +1,163 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Crash consistency tests of the write-back execution cache. Outputs of executed transactions
+//! are only persisted when their checkpoint is committed, so these tests crash validators while
+//! outputs are held in memory, and check that no effects are lost or changed after restart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_macros::{register_fail_point_async, sim_test};
+use sui_test_transaction_builder::make_transfer_sui_transaction;
+use sui_types::base_types::TransactionDigest;
+use sui_types::digests::TransactionEffectsDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use test_cluster::{TestCluster, TestClusterBuilder};
+use tokio::time::sleep;
+
+const NUM_TRANSACTIONS: usize = 10;
+
+async fn execute_transfers(
+    test_cluster: &TestCluster,
+    num_transactions: usize,
+) -> Vec<(TransactionDigest, TransactionEffectsDigest)> {
+    let mut executed = Vec::with_capacity(num_transactions);
+    for _ in 0..num_transactions {
+        let tx = make_transfer_sui_transaction(&test_cluster.wallet, None, None).await;
+        let (effects, _) = test_cluster
+            .execute_transaction_return_raw_effects(tx)
+            .await
+            .unwrap();
+        executed.push((*effects.transaction_digest(), effects.digest()));
+    }
+    executed
+}
+
+/// Waits until every transaction is included in a checkpoint on all validators, then checks the
+/// effects persisted by each validator are the ones certified before the crash.
+async fn check_committed_effects(
+    test_cluster: &TestCluster,
+    executed: &[(TransactionDigest, TransactionEffectsDigest)],
+) {
+    for (digest, effects_digest) in executed {
+        let mut all_included = false;
+        for _ in 0..600 {
+            all_included = test_cluster
+                .all_validator_handles()
+                .into_iter()
+                .all(|handle| {
+                    handle.with(|node| {
+                        node.state()
+                            .epoch_store_for_testing()
+                            .is_transaction_executed_in_checkpoint(digest)
+                            .unwrap()
+                    })
+                });
+            if all_included {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        assert!(all_included, "Transaction {digest:?} is not checkpointed");
+
+        for handle in test_cluster.all_validator_handles() {
+            handle.with(|node| {
+                let effects = node
+                    .state()
+                    .database_for_testing()
+                    .get_executed_effects(digest)
+                    .unwrap()
+                    .unwrap_or_else(|| panic!("Effects of {digest:?} are lost"));
+                assert_eq!(effects.digest(), *effects_digest);
+            });
+        }
+    }
+}
+
+/// Restarts all validators while outputs of executed transactions have not been written to the
+/// db yet.
+#[sim_test]
+async fn test_restart_all_validators_with_uncommitted_outputs() {
+    let block_commits = Arc::new(AtomicBool::new(true));
+    let block_commits_clone = block_commits.clone();
+    register_fail_point_async("writeback-cache-commit", move || {
+        let block_commits = block_commits_clone.clone();
+        async move {
+            while block_commits.load(Ordering::Relaxed) {
+                sleep(Duration::from_millis(100)).await;
+            }
+        }
+    });
+
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let executed = execute_transfers(&test_cluster, NUM_TRANSACTIONS).await;
+
+    for handle in test_cluster.all_validator_handles() {
+        handle.with(|node| {
+            for (digest, effects_digest) in &executed {
+                let effects = node
+                    .state()
+                    .get_cache_reader()
+                    .get_executed_effects(digest)
+                    .unwrap()
+                    .unwrap_or_else(|| panic!("{digest:?} is not executed"));
+                assert_eq!(effects.digest(), *effects_digest);
+
+                // Commits are only blocked in the simulator, where fail points are enabled.
+                if cfg!(msim) {
+                    assert!(node
+                        .state()
+                        .database_for_testing()
+                        .get_executed_effects(digest)
+                        .unwrap()
+                        .is_none());
+                }
+            }
+        });
+    }
+
+    test_cluster.stop_all_validators().await;
+    block_commits.store(false, Ordering::Relaxed);
+    test_cluster.start_all_validators().await;
+
+    // Transactions executed after the restart depend on the outputs of the ones before it.
+    let mut executed = executed;
+    executed.extend(execute_transfers(&test_cluster, 1).await);
+    check_committed_effects(&test_cluster, &executed).await;
+}
+
+/// Kills a validator in the middle of committing a checkpoint, and lets it restart while other
+/// transactions are executed.
+#[cfg(msim)]
+#[sim_test]
+async fn test_crash_validator_during_checkpoint_commit() {
+    use std::collections::HashSet;
+
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let executed = execute_transfers(&test_cluster, NUM_TRANSACTIONS).await;
+
+    let validator_node_ids: HashSet<_> = test_cluster
+        .all_validator_handles()
+        .into_iter()
+        .map(|handle| handle.with(|node| node.get_sim_node_id()))
+        .collect();
+    let killed = Arc::new(AtomicBool::new(false));
+    register_fail_point_async("writeback-cache-commit", move || {
+        let validator_node_ids = validator_node_ids.clone();
+        let killed = killed.clone();
+        async move {
+            if validator_node_ids.contains(&sui_simulator::current_simnode_id())
+                && !killed.swap(true, Ordering::Relaxed)
+            {
+                sui_simulator::task::kill_current_node(Some(Duration::from_secs(5)));
+            }
+        }
+    });
+
+    let mut executed = executed;
+    executed.extend(execute_transfers(&test_cluster, NUM_TRANSACTIONS).await);
+    // Wait for the killed validator to restart.
+    sleep(Duration::from_secs(10)).await;
+    check_committed_effects(&test_cluster, &executed).await;
+}
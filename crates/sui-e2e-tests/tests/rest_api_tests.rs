@@ -0,0 +1,155 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::StreamExt;
+use std::time::Duration;
+use sui_macros::sim_test;
+use sui_rest_api::Client;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::SuiAddress;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use test_cluster::{TestCluster, TestClusterBuilder};
+
+fn rest_client(test_cluster: &TestCluster) -> Client {
+    Client::new(format!("{}/rest", test_cluster.rpc_url()))
+}
+
+/// Transfer a bit of SUI to a random address and return the digest once the fullnode has
+/// executed the transaction.
+async fn execute_transfer(test_cluster: &TestCluster) -> TransactionDigest {
+    let transaction = test_cluster
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(1), SuiAddress::random_for_testing_only())
+        .build();
+    let response = test_cluster
+        .sign_and_execute_transaction(&transaction)
+        .await;
+    response.digest
+}
+
+#[sim_test]
+async fn get_transaction_effects_and_events() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = rest_client(&test_cluster);
+
+    // Staking emits an event, so every route has something to return.
+    let (sender, mut objects) = test_cluster.wallet.get_one_account().await.unwrap();
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let validator = test_cluster
+        .swarm
+        .active_validators()
+        .next()
+        .unwrap()
+        .config
+        .sui_address();
+    let transaction = TestTransactionBuilder::new(sender, objects.pop().unwrap(), rgp)
+        .call_staking(objects.pop().unwrap(), validator)
+        .build();
+    let transaction = test_cluster.sign_transaction(&transaction);
+    let digest = *transaction.digest();
+    let (effects, events) = test_cluster
+        .execute_transaction_return_raw_effects(transaction.clone())
+        .await
+        .unwrap();
+    assert!(!events.data.is_empty());
+
+    assert_eq!(
+        client.get_transaction(digest).await.unwrap(),
+        *transaction.data()
+    );
+    assert_eq!(
+        client.get_transaction_effects(digest).await.unwrap(),
+        effects
+    );
+    assert_eq!(client.get_transaction_events(digest).await.unwrap(), events);
+
+    // A transaction without events has no events digest, but the route still succeeds.
+    let digest = execute_transfer(&test_cluster).await;
+    let effects = client.get_transaction_effects(digest).await.unwrap();
+    assert_eq!(*effects.transaction_digest(), digest);
+    assert!(effects.events_digest().is_none());
+    assert!(client
+        .get_transaction_events(digest)
+        .await
+        .unwrap()
+        .data
+        .is_empty());
+
+    // Unknown transactions are reported as not found on every route.
+    let unknown = TransactionDigest::random();
+    for err in [
+        client.get_transaction(unknown).await.unwrap_err(),
+        client.get_transaction_effects(unknown).await.unwrap_err(),
+        client.get_transaction_events(unknown).await.unwrap_err(),
+    ] {
+        assert!(err.to_string().contains("404"), "{err}");
+    }
+}
+
+#[sim_test]
+async fn stream_checkpoints_from_genesis() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = rest_client(&test_cluster);
+
+    // Execute a transaction so the stream has some history to replay.
+    execute_transfer(&test_cluster).await;
+    let latest = client
+        .get_latest_checkpoint()
+        .await
+        .unwrap()
+        .sequence_number;
+
+    let mut stream = Box::pin(client.stream_checkpoints(0).await.unwrap());
+    for sequence_number in 0..=latest {
+        let checkpoint = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            checkpoint.checkpoint_summary.sequence_number,
+            sequence_number
+        );
+
+        // Every frame matches what the single checkpoint route returns.
+        let expected = client.get_full_checkpoint(sequence_number).await.unwrap();
+        assert_eq!(
+            bcs::to_bytes(&checkpoint).unwrap(),
+            bcs::to_bytes(&expected).unwrap()
+        );
+    }
+}
+
+#[sim_test]
+async fn stream_checkpoints_waits_for_new_checkpoints() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = rest_client(&test_cluster);
+
+    // Start the stream past the end of the chain, so it has to wait for new checkpoints.
+    let latest = client
+        .get_latest_checkpoint()
+        .await
+        .unwrap()
+        .sequence_number;
+    let mut stream = Box::pin(client.stream_checkpoints(latest + 1).await.unwrap());
+
+    let digest = execute_transfer(&test_cluster).await;
+
+    tokio::time::timeout(Duration::from_secs(60), async {
+        let mut next = latest + 1;
+        loop {
+            let checkpoint = stream.next().await.unwrap().unwrap();
+            assert_eq!(checkpoint.checkpoint_summary.sequence_number, next);
+            next += 1;
+
+            if let Some(tx) = checkpoint
+                .transactions
+                .iter()
+                .find(|tx| *tx.transaction.digest() == digest)
+            {
+                assert_eq!(*tx.effects.transaction_digest(), digest);
+                break;
+            }
+        }
+    })
+    .await
+    .expect("Timed out waiting for the transaction to be streamed");
+}
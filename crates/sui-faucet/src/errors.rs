@@ -40,6 +40,12 @@ pub enum FaucetError {
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
+    #[error("Too many requests from this client, please retry in {retry_after_secs} seconds")]
+    TooManyRequests { retry_after_secs: u64 },
+
+    #[error("Requests from this client are not accepted")]
+    RequestDenied,
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -6,8 +6,10 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+mod rate_limiter;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::rate_limiter::{client_ip, AccessListEntry, QuotaCharge, RequestLimiter};
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
 use std::{net::Ipv4Addr, path::PathBuf};
@@ -68,6 +70,9 @@ pub trait Faucet {
 
 pub const DEFAULT_AMOUNT: u64 = 1_000_000_000;
 pub const DEFAULT_NUM_OF_COINS: usize = 1;
+pub const DEFAULT_MAX_REQUESTS_PER_RECIPIENT: u64 = 10;
+pub const DEFAULT_MAX_REQUESTS_PER_IP: u64 = 100;
+pub const DEFAULT_QUOTA_WINDOW_SECS: u64 = 24 * 60 * 60;
 
 #[derive(Parser, Clone)]
 #[clap(
@@ -114,6 +119,48 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Where to persist per-client request history. Defaults to a `rate_limits` directory next to
+    /// the write-ahead log.
+    #[clap(long)]
+    pub rate_limit_store: Option<PathBuf>,
+
+    /// Maximum number of requests a single recipient address can make within the quota window.
+    /// Zero disables the limit.
+    #[clap(long, default_value_t = DEFAULT_MAX_REQUESTS_PER_RECIPIENT)]
+    pub max_requests_per_recipient: u64,
+
+    /// Maximum number of requests a single client IP can make within the quota window, across all
+    /// recipients. Zero disables the limit.
+    #[clap(long, default_value_t = DEFAULT_MAX_REQUESTS_PER_IP)]
+    pub max_requests_per_ip: u64,
+
+    /// Length of the sliding window that per-recipient and per-IP quotas are counted over.
+    #[clap(long, default_value_t = DEFAULT_QUOTA_WINDOW_SECS)]
+    pub quota_window_secs: u64,
+
+    /// Addresses and IPs that bypass quotas, comma separated.
+    #[clap(long, value_delimiter = ',')]
+    pub allow_list: Vec<AccessListEntry>,
+
+    /// Addresses and IPs whose requests are always rejected, comma separated.
+    #[clap(long, value_delimiter = ',')]
+    pub deny_list: Vec<AccessListEntry>,
+
+    /// Number of trusted proxies in front of the faucet. Clients are identified by the
+    /// `X-Forwarded-For` entry appended by the outermost trusted proxy, counting this many entries
+    /// from the right, because entries to its left can be set by the client. Zero identifies
+    /// clients by the peer address.
+    #[clap(long, default_value_t = 0)]
+    pub num_trusted_proxies: usize,
+}
+
+impl FaucetConfig {
+    pub fn rate_limit_store_path(&self) -> PathBuf {
+        self.rate_limit_store
+            .clone()
+            .unwrap_or_else(|| self.write_ahead_log.with_file_name("rate_limits"))
+    }
 }
 
 impl Default for FaucetConfig {
@@ -132,6 +179,13 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            rate_limit_store: None,
+            max_requests_per_recipient: DEFAULT_MAX_REQUESTS_PER_RECIPIENT,
+            max_requests_per_ip: DEFAULT_MAX_REQUESTS_PER_IP,
+            quota_window_secs: DEFAULT_QUOTA_WINDOW_SECS,
+            allow_list: vec![],
+            deny_list: vec![],
+            num_trusted_proxies: 0,
         }
     }
 }
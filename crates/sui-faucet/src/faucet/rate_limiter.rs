@@ -0,0 +1,712 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use prometheus::Registry;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use tracing::info;
+use typed_store::rocks::{DBBatch, DBMap};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store_derive::DBMapUtils;
+
+use crate::metrics::RateLimiterMetrics;
+use crate::{FaucetConfig, FaucetError};
+
+/// Maximum number of stale clients removed from the request history at a time while pruning.
+const PRUNE_BATCH_SIZE: usize = 1000;
+
+/// Persistent history of the requests accepted from each client, as timestamps in milliseconds
+/// since the Unix epoch. Only timestamps that fall within the quota window are kept, so the length
+/// of an entry is the number of requests the client has made in the current sliding window.
+///
+/// Persisting the history means that restarting the faucet does not reset anyone's quota.
+#[derive(DBMapUtils)]
+pub struct RequestHistory {
+    pub recipients: DBMap<SuiAddress, Vec<u64>>,
+    pub ips: DBMap<IpAddr, Vec<u64>>,
+    /// Every client in `recipients` and `ips`, keyed by the timestamp of its latest request, so
+    /// that clients whose requests have all fallen out of the window can be found in order.
+    pub latest_requests: DBMap<(u64, AccessListEntry), ()>,
+}
+
+/// A client identity that can appear on the faucet's allow or deny lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccessListEntry {
+    Ip(IpAddr),
+    Address(SuiAddress),
+}
+
+impl FromStr for AccessListEntry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(ip) = IpAddr::from_str(s) {
+            return Ok(Self::Ip(ip));
+        }
+        SuiAddress::from_str(s)
+            .map(Self::Address)
+            .map_err(|_| anyhow::anyhow!("`{s}` is neither an IP address nor a Sui address"))
+    }
+}
+
+impl fmt::Display for AccessListEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ip(ip) => write!(f, "{ip}"),
+            Self::Address(address) => write!(f, "{address}"),
+        }
+    }
+}
+
+/// A request counted against the quotas of its client IP and recipient. It is refunded when the
+/// request could not be served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaCharge {
+    ip: IpAddr,
+    recipient: SuiAddress,
+    timestamp: u64,
+}
+
+/// Enforces per-recipient and per-IP quotas over a sliding window, on top of the allow and deny
+/// lists from the faucet's config.
+pub struct RequestLimiter {
+    // Checking a quota and recording a request against it must happen atomically, otherwise
+    // concurrent requests from the same client could all pass the check.
+    history: Mutex<RequestHistory>,
+    max_requests_per_recipient: u64,
+    max_requests_per_ip: u64,
+    window: Duration,
+    allow_list: HashSet<AccessListEntry>,
+    deny_list: HashSet<AccessListEntry>,
+    metrics: RateLimiterMetrics,
+}
+
+impl RequestLimiter {
+    pub fn new(path: &Path, config: &FaucetConfig, registry: &Registry) -> Self {
+        let history = RequestHistory::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::new("faucet_rate_limiter"),
+            None,
+            None,
+        );
+
+        info!(
+            max_requests_per_recipient = config.max_requests_per_recipient,
+            max_requests_per_ip = config.max_requests_per_ip,
+            quota_window_secs = config.quota_window_secs,
+            "Opened faucet request history at {path:?}"
+        );
+
+        Self {
+            history: Mutex::new(history),
+            max_requests_per_recipient: config.max_requests_per_recipient,
+            max_requests_per_ip: config.max_requests_per_ip,
+            window: Duration::from_secs(config.quota_window_secs),
+            allow_list: config.allow_list.iter().copied().collect(),
+            deny_list: config.deny_list.iter().copied().collect(),
+            metrics: RateLimiterMetrics::new(registry),
+        }
+    }
+
+    /// Check whether a request from `ip` for `recipient` is allowed and, if it is, count it
+    /// against both of their quotas. Rejected requests are not counted. Returns the charge to
+    /// refund if the request fails, or `None` for allow-listed clients that are not counted.
+    pub async fn check(
+        self: &Arc<Self>,
+        ip: IpAddr,
+        recipient: SuiAddress,
+    ) -> Result<Option<QuotaCharge>, FaucetError> {
+        let now = now_ms();
+        self.blocking(move |limiter| limiter.check_at(ip, recipient, now))
+            .await
+    }
+
+    fn check_at(
+        &self,
+        ip: IpAddr,
+        recipient: SuiAddress,
+        now: u64,
+    ) -> Result<Option<QuotaCharge>, FaucetError> {
+        let ip_entry = AccessListEntry::Ip(ip);
+        let recipient_entry = AccessListEntry::Address(recipient);
+
+        if self.deny_list.contains(&ip_entry) || self.deny_list.contains(&recipient_entry) {
+            self.reject("denied");
+            return Err(FaucetError::RequestDenied);
+        }
+
+        if self.allow_list.contains(&ip_entry) || self.allow_list.contains(&recipient_entry) {
+            return Ok(None);
+        }
+
+        let history = self.history.lock();
+        let window_start = now.saturating_sub(self.window.as_millis() as u64);
+
+        let (mut ip_requests, ip_latest) = recent_requests(&history.ips, &ip, window_start)?;
+        if let Some(retry_after_secs) = self.exceeded(&ip_requests, self.max_requests_per_ip, now) {
+            self.reject("ip_quota");
+            return Err(FaucetError::TooManyRequests { retry_after_secs });
+        }
+
+        let (mut recipient_requests, recipient_latest) =
+            recent_requests(&history.recipients, &recipient, window_start)?;
+        if let Some(retry_after_secs) =
+            self.exceeded(&recipient_requests, self.max_requests_per_recipient, now)
+        {
+            self.reject("recipient_quota");
+            return Err(FaucetError::TooManyRequests { retry_after_secs });
+        }
+
+        ip_requests.push(now);
+        recipient_requests.push(now);
+
+        let mut batch = history.ips.batch();
+        batch
+            .insert_batch(&history.ips, [(ip, ip_requests)])
+            .map_err(FaucetError::internal)?;
+        batch
+            .insert_batch(&history.recipients, [(recipient, recipient_requests)])
+            .map_err(FaucetError::internal)?;
+        reindex(&mut batch, &history, ip_entry, ip_latest, Some(now))?;
+        reindex(
+            &mut batch,
+            &history,
+            recipient_entry,
+            recipient_latest,
+            Some(now),
+        )?;
+        batch.write().map_err(FaucetError::internal)?;
+        Ok(Some(QuotaCharge {
+            ip,
+            recipient,
+            timestamp: now,
+        }))
+    }
+
+    /// Remove a request that could not be served from the quotas it was counted against.
+    pub async fn refund(self: &Arc<Self>, charge: QuotaCharge) -> Result<(), FaucetError> {
+        self.blocking(move |limiter| limiter.refund_now(charge))
+            .await
+    }
+
+    fn refund_now(&self, charge: QuotaCharge) -> Result<(), FaucetError> {
+        let history = self.history.lock();
+        let mut batch = history.ips.batch();
+        refund_request(
+            &mut batch,
+            &history,
+            &history.ips,
+            charge.ip,
+            AccessListEntry::Ip(charge.ip),
+            charge.timestamp,
+        )?;
+        refund_request(
+            &mut batch,
+            &history,
+            &history.recipients,
+            charge.recipient,
+            AccessListEntry::Address(charge.recipient),
+            charge.timestamp,
+        )?;
+        batch.write().map_err(FaucetError::internal)?;
+        self.metrics.total_requests_refunded.inc();
+        Ok(())
+    }
+
+    /// Remove clients that have not made any requests within the quota window, so the history does
+    /// not grow without bound. Stale clients are removed in batches, oldest first, so that checking
+    /// quotas is not held up while a large history is pruned.
+    pub async fn prune(self: &Arc<Self>) -> Result<(), FaucetError> {
+        let window_start = now_ms().saturating_sub(self.window.as_millis() as u64);
+        let mut total = 0;
+        loop {
+            let pruned = self
+                .blocking(move |limiter| limiter.prune_batch(window_start, PRUNE_BATCH_SIZE))
+                .await?;
+            total += pruned;
+            if pruned < PRUNE_BATCH_SIZE {
+                break;
+            }
+        }
+        info!("Pruned request history for {total} clients");
+        Ok(())
+    }
+
+    /// Remove up to `limit` of the clients whose latest request is at or before `window_start`,
+    /// returning how many were removed.
+    fn prune_batch(&self, window_start: u64, limit: usize) -> Result<usize, FaucetError> {
+        let history = self.history.lock();
+        let stale: Vec<_> = history
+            .latest_requests
+            .unbounded_iter()
+            .map(|(key, _)| key)
+            .take_while(|(latest, _)| *latest <= window_start)
+            .take(limit)
+            .collect();
+
+        let mut batch = history.ips.batch();
+        for (_, client) in &stale {
+            match client {
+                AccessListEntry::Ip(ip) => batch.delete_batch(&history.ips, [*ip]),
+                AccessListEntry::Address(address) => {
+                    batch.delete_batch(&history.recipients, [*address])
+                }
+            }
+            .map_err(FaucetError::internal)?;
+        }
+        batch
+            .delete_batch(&history.latest_requests, stale.iter().copied())
+            .map_err(FaucetError::internal)?;
+        batch.write().map_err(FaucetError::internal)?;
+        Ok(stale.len())
+    }
+
+    /// Run `f` on a blocking thread, as it reads and writes the request history on disk.
+    async fn blocking<T: Send + 'static>(
+        self: &Arc<Self>,
+        f: impl FnOnce(&Self) -> Result<T, FaucetError> + Send + 'static,
+    ) -> Result<T, FaucetError> {
+        let limiter = self.clone();
+        tokio::task::spawn_blocking(move || f(limiter.as_ref()))
+            .await
+            .map_err(FaucetError::internal)?
+    }
+
+    /// If `requests` already use up a quota of `max_requests`, returns how many seconds remain
+    /// until enough of them fall out of the window to make room for another request. A quota of
+    /// zero is unlimited.
+    fn exceeded(&self, requests: &[u64], max_requests: u64, now: u64) -> Option<u64> {
+        if max_requests == 0 || (requests.len() as u64) < max_requests {
+            return None;
+        }
+
+        let blocking = requests[requests.len() - max_requests as usize];
+        let expires = blocking + self.window.as_millis() as u64;
+        Some(Duration::from_millis(expires.saturating_sub(now)).as_secs() + 1)
+    }
+
+    fn reject(&self, reason: &str) {
+        self.metrics
+            .total_requests_rejected
+            .with_label_values(&[reason])
+            .inc();
+    }
+}
+
+/// Timestamps of the requests recorded against `key` that fall within the window, oldest first,
+/// along with the latest request recorded against it, which `latest_requests` is keyed by.
+fn recent_requests<K: Serialize + DeserializeOwned>(
+    table: &DBMap<K, Vec<u64>>,
+    key: &K,
+    window_start: u64,
+) -> Result<(Vec<u64>, Option<u64>), FaucetError> {
+    let mut requests = table
+        .get(key)
+        .map_err(FaucetError::internal)?
+        .unwrap_or_default();
+    let latest = requests.last().copied();
+    requests.retain(|t| *t > window_start);
+    Ok((requests, latest))
+}
+
+/// Removes one request recorded against `key` at `timestamp`, if there is any, removing the
+/// client from the history altogether once it has no requests left.
+fn refund_request<K: Serialize + DeserializeOwned>(
+    batch: &mut DBBatch,
+    history: &RequestHistory,
+    table: &DBMap<K, Vec<u64>>,
+    key: K,
+    client: AccessListEntry,
+    timestamp: u64,
+) -> Result<(), FaucetError> {
+    let Some(mut requests) = table.get(&key).map_err(FaucetError::internal)? else {
+        return Ok(());
+    };
+    let latest = requests.last().copied();
+    if let Some(index) = requests.iter().rposition(|t| *t == timestamp) {
+        requests.remove(index);
+    }
+    reindex(batch, history, client, latest, requests.last().copied())?;
+    if requests.is_empty() {
+        batch
+            .delete_batch(table, [key])
+            .map_err(FaucetError::internal)?;
+    } else {
+        batch
+            .insert_batch(table, [(key, requests)])
+            .map_err(FaucetError::internal)?;
+    }
+    Ok(())
+}
+
+/// Moves `client` in `latest_requests` from the timestamp of its `previous` latest request to
+/// its new `latest` one.
+fn reindex(
+    batch: &mut DBBatch,
+    history: &RequestHistory,
+    client: AccessListEntry,
+    previous: Option<u64>,
+    latest: Option<u64>,
+) -> Result<(), FaucetError> {
+    if previous == latest {
+        return Ok(());
+    }
+    if let Some(previous) = previous {
+        batch
+            .delete_batch(&history.latest_requests, [(previous, client)])
+            .map_err(FaucetError::internal)?;
+    }
+    if let Some(latest) = latest {
+        batch
+            .insert_batch(&history.latest_requests, [((latest, client), ())])
+            .map_err(FaucetError::internal)?;
+    }
+    Ok(())
+}
+
+/// The IP address to count a request against. Each proxy appends the address it received the
+/// request from to `X-Forwarded-For`, so with `num_trusted_proxies` proxies in front of the faucet,
+/// the client is the entry that many positions from the right. Entries further left are set by
+/// the client and cannot be trusted. Falls back to the peer address without trusted proxies or a
+/// valid entry.
+pub fn client_ip(peer: IpAddr, forwarded_for: Option<&str>, num_trusted_proxies: usize) -> IpAddr {
+    if num_trusted_proxies == 0 {
+        return peer;
+    }
+    let Some(forwarded_for) = forwarded_for else {
+        return peer;
+    };
+    let entries: Vec<_> = forwarded_for.split(',').map(str::trim).collect();
+    // With fewer entries than trusted proxies, all of them were appended by trusted proxies and
+    // the leftmost one is the client.
+    entries[entries.len().saturating_sub(num_trusted_proxies)]
+        .parse()
+        .unwrap_or(peer)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    const OTHER_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+    const WINDOW_MS: u64 = 60_000;
+
+    fn limiter(path: &Path, config: FaucetConfig) -> Arc<RequestLimiter> {
+        Arc::new(RequestLimiter::new(path, &config, &Registry::new()))
+    }
+
+    fn config(max_requests_per_recipient: u64, max_requests_per_ip: u64) -> FaucetConfig {
+        FaucetConfig {
+            max_requests_per_recipient,
+            max_requests_per_ip,
+            quota_window_secs: WINDOW_MS / 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recipient_quota_applies_across_ips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(2, 0));
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        limiter.check_at(LOCALHOST, recipient, now).unwrap();
+        limiter.check_at(OTHER_IP, recipient, now + 1).unwrap();
+
+        assert_eq!(
+            limiter.check_at(OTHER_IP, recipient, now + 2),
+            Err(FaucetError::TooManyRequests {
+                retry_after_secs: WINDOW_MS / 1000
+            })
+        );
+
+        // A different recipient from the same IP is unaffected.
+        limiter
+            .check_at(LOCALHOST, SuiAddress::random_for_testing_only(), now + 3)
+            .unwrap();
+    }
+
+    #[test]
+    fn ip_quota_applies_across_recipients() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(0, 3));
+        let now = now_ms();
+
+        for i in 0..3 {
+            let recipient = SuiAddress::random_for_testing_only();
+            limiter.check_at(LOCALHOST, recipient, now + i).unwrap();
+        }
+
+        let recipient = SuiAddress::random_for_testing_only();
+        assert!(matches!(
+            limiter.check_at(LOCALHOST, recipient, now + 3),
+            Err(FaucetError::TooManyRequests { .. })
+        ));
+        limiter.check_at(OTHER_IP, recipient, now + 4).unwrap();
+    }
+
+    #[test]
+    fn quota_window_slides() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(2, 0));
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        limiter.check_at(LOCALHOST, recipient, now).unwrap();
+        limiter
+            .check_at(LOCALHOST, recipient, now + 30_000)
+            .unwrap();
+        assert!(limiter
+            .check_at(LOCALHOST, recipient, now + WINDOW_MS - 1)
+            .is_err());
+
+        // Once the first request falls out of the window, there is room for exactly one more.
+        limiter
+            .check_at(LOCALHOST, recipient, now + WINDOW_MS)
+            .unwrap();
+        assert!(limiter
+            .check_at(LOCALHOST, recipient, now + WINDOW_MS + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn rejected_requests_are_not_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(1, 2));
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        limiter.check_at(LOCALHOST, recipient, now).unwrap();
+        for i in 1..5 {
+            assert!(limiter.check_at(LOCALHOST, recipient, now + i).is_err());
+        }
+
+        // Only one request from this IP was accepted, so it still has room for another recipient.
+        limiter
+            .check_at(LOCALHOST, SuiAddress::random_for_testing_only(), now + 5)
+            .unwrap();
+    }
+
+    #[test]
+    fn access_lists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let allowed = SuiAddress::random_for_testing_only();
+        let denied = SuiAddress::random_for_testing_only();
+        let limiter = limiter(
+            tmp.path(),
+            FaucetConfig {
+                allow_list: vec![AccessListEntry::Address(allowed)],
+                deny_list: vec![
+                    AccessListEntry::Address(denied),
+                    AccessListEntry::Ip(OTHER_IP),
+                ],
+                ..config(1, 0)
+            },
+        );
+        let now = now_ms();
+
+        for i in 0..5 {
+            limiter.check_at(LOCALHOST, allowed, now + i).unwrap();
+        }
+
+        assert_eq!(
+            limiter.check_at(LOCALHOST, denied, now),
+            Err(FaucetError::RequestDenied)
+        );
+
+        // The deny list takes precedence over the allow list.
+        assert_eq!(
+            limiter.check_at(OTHER_IP, allowed, now),
+            Err(FaucetError::RequestDenied)
+        );
+    }
+
+    #[test]
+    fn history_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        {
+            let limiter = limiter(tmp.path(), config(1, 0));
+            limiter.check_at(LOCALHOST, recipient, now).unwrap();
+        }
+
+        let limiter = limiter(tmp.path(), config(1, 0));
+        assert!(limiter.check_at(LOCALHOST, recipient, now + 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn prune_removes_stale_clients() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(1, 1));
+        let stale = SuiAddress::random_for_testing_only();
+        let fresh = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        limiter
+            .check_at(LOCALHOST, stale, now - 2 * WINDOW_MS)
+            .unwrap();
+        limiter.check_at(OTHER_IP, fresh, now).unwrap();
+        limiter.prune().await.unwrap();
+
+        let history = limiter.history.lock();
+        assert!(!history.recipients.contains_key(&stale).unwrap());
+        assert!(!history.ips.contains_key(&LOCALHOST).unwrap());
+        assert!(history.recipients.contains_key(&fresh).unwrap());
+        assert!(history.ips.contains_key(&OTHER_IP).unwrap());
+
+        // Only the fresh clients are left in the index.
+        let indexed: Vec<_> = history
+            .latest_requests
+            .unbounded_iter()
+            .map(|((_, client), _)| client)
+            .collect();
+        assert_eq!(indexed.len(), 2);
+        assert!(indexed.contains(&AccessListEntry::Ip(OTHER_IP)));
+        assert!(indexed.contains(&AccessListEntry::Address(fresh)));
+    }
+
+    #[test]
+    fn prune_in_batches_oldest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(0, 0));
+        let now = now_ms();
+        let window_start = now - WINDOW_MS;
+
+        // Requests are recorded out of order, and one client makes a fresh request after a stale
+        // one, which moves it out of the stale part of the index.
+        let recipients: Vec<_> = (0..3)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        limiter
+            .check_at(LOCALHOST, recipients[0], window_start - 10)
+            .unwrap();
+        limiter
+            .check_at(OTHER_IP, recipients[1], window_start - 30)
+            .unwrap();
+        limiter
+            .check_at(OTHER_IP, recipients[2], window_start - 20)
+            .unwrap();
+        limiter.check_at(LOCALHOST, recipients[0], now).unwrap();
+
+        assert_eq!(limiter.prune_batch(window_start, 1).unwrap(), 1);
+        assert_eq!(limiter.prune_batch(window_start, 10).unwrap(), 2);
+        assert_eq!(limiter.prune_batch(window_start, 10).unwrap(), 0);
+
+        let history = limiter.history.lock();
+        assert!(history.recipients.contains_key(&recipients[0]).unwrap());
+        assert!(!history.recipients.contains_key(&recipients[1]).unwrap());
+        assert!(!history.recipients.contains_key(&recipients[2]).unwrap());
+        assert!(history.ips.contains_key(&LOCALHOST).unwrap());
+        assert!(!history.ips.contains_key(&OTHER_IP).unwrap());
+    }
+
+    #[tokio::test]
+    async fn refunded_requests_are_not_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), config(1, 1));
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        let charge = limiter.check_at(LOCALHOST, recipient, now).unwrap();
+        assert!(limiter.check_at(LOCALHOST, recipient, now + 1).is_err());
+
+        // A failed request gives the quota back to both the IP and the recipient.
+        limiter.refund(charge.unwrap()).await.unwrap();
+        limiter.check_at(LOCALHOST, recipient, now + 2).unwrap();
+        assert!(limiter.check_at(LOCALHOST, recipient, now + 3).is_err());
+
+        // Refunding a client's only request removes it from the history.
+        let other = SuiAddress::random_for_testing_only();
+        let charge = limiter.check_at(OTHER_IP, other, now).unwrap();
+        limiter.refund(charge.unwrap()).await.unwrap();
+        {
+            let history = limiter.history.lock();
+            assert!(!history.recipients.contains_key(&other).unwrap());
+            assert!(!history.ips.contains_key(&OTHER_IP).unwrap());
+            assert!(!history
+                .latest_requests
+                .contains_key(&(now, AccessListEntry::Address(other)))
+                .unwrap());
+        }
+
+        // Allow-listed clients are not charged.
+        let allowed = SuiAddress::random_for_testing_only();
+        let limiter_with_allow_list = RequestLimiter::new(
+            &tmp.path().join("allow"),
+            &FaucetConfig {
+                allow_list: vec![AccessListEntry::Address(allowed)],
+                ..config(1, 1)
+            },
+            &Registry::new(),
+        );
+        assert_eq!(
+            limiter_with_allow_list.check_at(LOCALHOST, allowed, now),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn client_ip_from_trusted_proxies() {
+        let proxy = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let spoofed = "1.2.3.4";
+
+        // Without trusted proxies, the header is ignored.
+        assert_eq!(client_ip(proxy, Some("127.0.0.1"), 0), proxy);
+
+        // The client is the entry appended by the outermost trusted proxy, regardless of any
+        // entries the client adds itself.
+        assert_eq!(
+            client_ip(proxy, Some(&format!("{spoofed}, 127.0.0.1")), 1),
+            LOCALHOST
+        );
+        assert_eq!(
+            client_ip(proxy, Some(&format!("{spoofed}, 127.0.0.1, 10.0.0.2")), 2),
+            LOCALHOST
+        );
+
+        // With fewer entries than trusted proxies, the leftmost entry is used.
+        assert_eq!(client_ip(proxy, Some("127.0.0.1"), 2), LOCALHOST);
+
+        // Missing or invalid entries fall back to the peer address.
+        assert_eq!(client_ip(proxy, None, 1), proxy);
+        assert_eq!(client_ip(proxy, Some("unknown"), 1), proxy);
+    }
+
+    #[test]
+    fn parse_access_list_entries() {
+        assert_eq!(
+            "127.0.0.1".parse::<AccessListEntry>().unwrap(),
+            AccessListEntry::Ip(LOCALHOST)
+        );
+        let address = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            address.to_string().parse::<AccessListEntry>().unwrap(),
+            AccessListEntry::Address(address)
+        );
+        assert!("not-a-client".parse::<AccessListEntry>().is_err());
+    }
+}
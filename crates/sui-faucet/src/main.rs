@@ -3,8 +3,8 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
-    http::StatusCode,
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -21,19 +21,22 @@ use std::{
 };
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
 use sui_faucet::{
-    BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
+    client_ip, BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
+    FaucetRequest, FaucetResponse, QuotaCharge, RequestLimiter, RequestMetricsLayer, SimpleFaucet,
 };
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
+const RATE_LIMIT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
-struct AppState<F = Arc<SimpleFaucet>> {
-    faucet: F,
+struct AppState<F = SimpleFaucet> {
+    faucet: Arc<F>,
+    limiter: Arc<RequestLimiter>,
     config: FaucetConfig,
 }
 
@@ -79,6 +82,11 @@ async fn main() -> Result<(), anyhow::Error> {
         )
         .await
         .unwrap(),
+        limiter: Arc::new(RequestLimiter::new(
+            &config.rate_limit_store_path(),
+            &config,
+            &prometheus_registry,
+        )),
         config,
     });
 
@@ -88,26 +96,32 @@ async fn main() -> Result<(), anyhow::Error> {
         .allow_headers(Any)
         .allow_origin(Any);
 
-    let app = Router::new()
-        .route("/", get(health))
-        .route("/gas", post(request_gas))
-        .route("/v1/gas", post(batch_request_gas))
-        .route("/v1/status/:task_id", get(request_status))
-        .layer(
-            ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(handle_error))
-                .layer(RequestMetricsLayer::new(&prometheus_registry))
-                .layer(cors)
-                .load_shed()
-                .buffer(request_buffer_size)
-                .layer(RateLimitLayer::new(
-                    max_request_per_second,
-                    Duration::from_secs(1),
-                ))
-                .concurrency_limit(max_concurrency)
-                .layer(Extension(app_state.clone()))
-                .into_inner(),
-        );
+    let app = routes::<SimpleFaucet>().layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(handle_error))
+            .layer(RequestMetricsLayer::new(&prometheus_registry))
+            .layer(cors)
+            .load_shed()
+            .buffer(request_buffer_size)
+            .layer(RateLimitLayer::new(
+                max_request_per_second,
+                Duration::from_secs(1),
+            ))
+            .concurrency_limit(max_concurrency)
+            .layer(Extension(app_state.clone()))
+            .into_inner(),
+    );
+
+    let prune_state = app_state.clone();
+    spawn_monitored_task!(async move {
+        info!("Starting task to prune request history.");
+        loop {
+            tokio::time::sleep(RATE_LIMIT_PRUNE_INTERVAL).await;
+            if let Err(e) = prune_state.limiter.prune().await {
+                warn!("Failed to prune request history: {:?}", e);
+            }
+        }
+    });
 
     spawn_monitored_task!(async move {
         info!("Starting task to clear WAL.");
@@ -121,19 +135,29 @@ async fn main() -> Result<(), anyhow::Error> {
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
 
+fn routes<F: Faucet + Send + Sync + 'static>() -> Router {
+    Router::new()
+        .route("/", get(health))
+        .route("/gas", post(request_gas::<F>))
+        .route("/v1/gas", post(batch_request_gas::<F>))
+        .route("/v1/status/:task_id", get(request_status::<F>))
+}
+
 /// basic handler that responds with a static string
 async fn health() -> &'static str {
     "OK"
 }
 
 /// handler for batch_request_gas requests
-async fn batch_request_gas(
-    Extension(state): Extension<Arc<AppState>>,
+async fn batch_request_gas<F: Faucet + Send + Sync + 'static>(
+    Extension(state): Extension<Arc<AppState<F>>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -149,14 +173,23 @@ async fn batch_request_gas(
         );
     };
 
+    let charge = match check_quota(&state, peer, &headers, request.recipient).await {
+        Ok(charge) => charge,
+        Err(e) => {
+            warn!(uuid = ?id, "Rejected gas request: {:?}", e);
+            return (rejection_status(&e), Json(BatchFaucetResponse::from(e)));
+        }
+    };
+
     if state.config.batch_enabled {
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .batch_send(
                     id,
                     request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
+                    &vec![task_state.config.amount; task_state.config.num_coins],
                 )
                 .await
         })
@@ -170,6 +203,7 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                refund_quota(&state, id, charge).await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -179,13 +213,14 @@ async fn batch_request_gas(
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .send(
                     id,
                     request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
+                    &vec![task_state.config.amount; task_state.config.num_coins],
                 )
                 .await
         })
@@ -199,6 +234,7 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                refund_quota(&state, id, charge).await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -209,8 +245,8 @@ async fn batch_request_gas(
 }
 
 /// handler for batch_get_status requests
-async fn request_status(
-    Extension(state): Extension<Arc<AppState>>,
+async fn request_status<F: Faucet + Send + Sync + 'static>(
+    Extension(state): Extension<Arc<AppState<F>>>,
     Path(id): Path<String>,
 ) -> impl IntoResponse {
     match Uuid::parse_str(&id) {
@@ -237,29 +273,41 @@ async fn request_status(
 }
 
 /// handler for all the request_gas requests
-async fn request_gas(
-    Extension(state): Extension<Arc<AppState>>,
+async fn request_gas<F: Faucet + Send + Sync + 'static>(
+    Extension(state): Extension<Arc<AppState<F>>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
-    let result = match payload {
+    let (result, charge) = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            let charge = match check_quota(&state, peer, &headers, requests.recipient).await {
+                Ok(charge) => charge,
+                Err(e) => {
+                    warn!(uuid = ?id, "Rejected gas request: {:?}", e);
+                    return (rejection_status(&e), Json(FaucetResponse::from(e)));
+                }
+            };
+
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
-            spawn_monitored_task!(async move {
-                state
+            let task_state = state.clone();
+            let result = spawn_monitored_task!(async move {
+                task_state
                     .faucet
                     .send(
                         id,
                         requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
+                        &vec![task_state.config.amount; task_state.config.num_coins],
                     )
                     .await
             })
             .await
-            .unwrap()
+            .unwrap();
+            (result, charge)
         }
         _ => {
             return (
@@ -277,6 +325,7 @@ async fn request_gas(
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            refund_quota(&state, id, charge).await;
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
@@ -285,6 +334,37 @@ async fn request_gas(
     }
 }
 
+/// Check the per-client quotas and access lists, recording the request if it is allowed.
+async fn check_quota<F>(
+    state: &AppState<F>,
+    peer: SocketAddr,
+    headers: &HeaderMap,
+    recipient: SuiAddress,
+) -> Result<Option<QuotaCharge>, FaucetError> {
+    let forwarded_for = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok());
+    let ip = client_ip(peer.ip(), forwarded_for, state.config.num_trusted_proxies);
+    state.limiter.check(ip, recipient).await
+}
+
+/// Return the quota charged for a request that could not be served, so failures do not use up
+/// the client's quota.
+async fn refund_quota<F>(state: &AppState<F>, id: Uuid, charge: Option<QuotaCharge>) {
+    let Some(charge) = charge else {
+        return;
+    };
+    if let Err(e) = state.limiter.refund(charge).await {
+        warn!(uuid = ?id, "Failed to refund quota: {:?}", e);
+    }
+}
+
+fn rejection_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+        FaucetError::RequestDenied => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -308,3 +388,113 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use http::Request;
+    use prometheus::Registry;
+    use sui_faucet::{BatchFaucetReceipt, BatchSendStatus, FaucetReceipt};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// A faucet that sends nothing, and fails every request while `fail` is set.
+    #[derive(Default)]
+    struct MockFaucet {
+        fail: AtomicBool,
+    }
+
+    impl MockFaucet {
+        fn result<T>(&self, ok: T) -> Result<T, FaucetError> {
+            if self.fail.load(Ordering::Relaxed) {
+                Err(FaucetError::Internal("Mock failure".to_string()))
+            } else {
+                Ok(ok)
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Faucet for MockFaucet {
+        async fn send(
+            &self,
+            _id: Uuid,
+            _recipient: SuiAddress,
+            _amounts: &[u64],
+        ) -> Result<FaucetReceipt, FaucetError> {
+            self.result(FaucetReceipt { sent: vec![] })
+        }
+
+        async fn batch_send(
+            &self,
+            id: Uuid,
+            _recipient: SuiAddress,
+            _amounts: &[u64],
+        ) -> Result<BatchFaucetReceipt, FaucetError> {
+            self.result(BatchFaucetReceipt {
+                task: id.to_string(),
+            })
+        }
+
+        async fn get_batch_send_status(
+            &self,
+            _task_id: Uuid,
+        ) -> Result<BatchSendStatus, FaucetError> {
+            Err(FaucetError::Internal("Not supported".to_string()))
+        }
+    }
+
+    async fn post_gas_request(app: &Router, uri: &str, recipient: SuiAddress) -> StatusCode {
+        let mut request = Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"FixedAmountRequest":{{"recipient":"{recipient}"}}}}"#
+            )))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn handlers_enforce_and_refund_quotas() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = FaucetConfig {
+            max_requests_per_recipient: 1,
+            max_requests_per_ip: 0,
+            ..Default::default()
+        };
+        let faucet = Arc::new(MockFaucet::default());
+        let state = Arc::new(AppState {
+            faucet: faucet.clone(),
+            limiter: Arc::new(RequestLimiter::new(tmp.path(), &config, &Registry::new())),
+            config,
+        });
+        let app = routes::<MockFaucet>().layer(Extension(state));
+
+        for uri in ["/gas", "/v1/gas"] {
+            let recipient = SuiAddress::random_for_testing_only();
+
+            // Failed requests are refunded, so they do not use up the recipient's quota.
+            faucet.fail.store(true, Ordering::Relaxed);
+            for _ in 0..3 {
+                assert_eq!(
+                    post_gas_request(&app, uri, recipient).await,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                );
+            }
+
+            faucet.fail.store(false, Ordering::Relaxed);
+            assert!(post_gas_request(&app, uri, recipient).await.is_success());
+            assert_eq!(
+                post_gas_request(&app, uri, recipient).await,
+                StatusCode::TOO_MANY_REQUESTS
+            );
+        }
+    }
+}
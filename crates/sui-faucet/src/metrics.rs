@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, IntCounter,
+    IntCounterVec, IntGauge, Registry,
 };
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
@@ -30,6 +31,13 @@ pub struct FaucetMetrics {
     pub(crate) total_coin_requests_succeeded: IntGauge,
 }
 
+/// Metrics relevant to the per-client quotas and access lists
+#[derive(Clone, Debug)]
+pub struct RateLimiterMetrics {
+    pub(crate) total_requests_rejected: IntCounterVec,
+    pub(crate) total_requests_refunded: IntCounter,
+}
+
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
@@ -115,3 +123,23 @@ impl FaucetMetrics {
         }
     }
 }
+
+impl RateLimiterMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_requests_rejected: register_int_counter_vec_with_registry!(
+                "total_requests_rejected",
+                "Total number of requests rejected by per-client quotas or the deny list, by reason",
+                &["reason"],
+                registry,
+            )
+            .unwrap(),
+            total_requests_refunded: register_int_counter_with_registry!(
+                "total_requests_refunded",
+                "Total number of requests removed from per-client quotas because they failed",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
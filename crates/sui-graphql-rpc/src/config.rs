@@ -3,8 +3,11 @@
 
 use crate::types::big_int::BigInt;
 use async_graphql::*;
-use serde::{Deserialize, Serialize};
-use std::{collections::BTreeSet, time::Duration};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 use sui_json_rpc::name_service::NameServiceConfig;
 
 use crate::functional_group::FunctionalGroup;
@@ -27,6 +30,9 @@ const MAX_TYPE_ARGUMENT_WIDTH: u32 = 32;
 const MAX_TYPE_NODES: u32 = 256;
 const MAX_MOVE_VALUE_DEPTH: u32 = 128;
 
+const MAX_SUBSCRIPTIONS: usize = 1_000;
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 10;
+
 pub(crate) const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 40_000;
 
 const DEFAULT_IDE_TITLE: &str = "Sui GraphQL IDE";
@@ -34,6 +40,9 @@ const DEFAULT_IDE_TITLE: &str = "Sui GraphQL IDE";
 pub(crate) const RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD: Duration = Duration::from_millis(10_000);
 pub(crate) const MAX_CONCURRENT_REQUESTS: usize = 1_000;
 
+/// How often to check the database for newly indexed checkpoints to serve to subscriptions.
+pub(crate) const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 // Default values for the server connection configuration.
 pub(crate) const DEFAULT_SERVER_CONNECTION_PORT: u16 = 8000;
 pub(crate) const DEFAULT_SERVER_CONNECTION_HOST: &str = "127.0.0.1";
@@ -90,6 +99,15 @@ pub struct ServiceConfig {
 
     #[serde(default)]
     pub(crate) name_service: NameServiceConfig,
+
+    #[serde(default)]
+    pub(crate) persisted_queries: PersistedQueryConfig,
+
+    #[serde(default)]
+    pub(crate) budgets: BudgetConfig,
+
+    #[serde(default)]
+    pub(crate) subscriptions: SubscriptionConfig,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Copy)]
@@ -121,6 +139,56 @@ pub struct Limits {
     pub max_move_value_depth: u32,
 }
 
+/// Queries that are registered with the service ahead of time, so that clients can refer to them
+/// by the SHA-256 hash of their text, instead of sending the text itself.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct PersistedQueryConfig {
+    /// Reject any query that is not persisted.
+    #[serde(default)]
+    pub(crate) required: bool,
+    /// The text of each persisted query.
+    #[serde(default)]
+    pub(crate) queries: Vec<String>,
+}
+
+/// Budgets for the total cost of queries that identified clients can issue over time.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct BudgetConfig {
+    /// Reject requests that do not identify their client with a known API key.
+    #[serde(default)]
+    pub(crate) require_api_key: bool,
+    /// Budgets for each client, keyed by the client's name. Each client must have its own API key.
+    #[serde(default, deserialize_with = "deserialize_client_budgets")]
+    pub(crate) clients: BTreeMap<String, ClientBudget>,
+}
+
+/// A token bucket of query cost (as estimated by the number of output nodes in the query), that
+/// requests from a client are charged against.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClientBudget {
+    /// The key that the client identifies itself with.
+    pub(crate) api_key: String,
+    /// The maximum cost that the client can accumulate in its budget.
+    pub(crate) capacity: u64,
+    /// The cost that is added back to the client's budget each second.
+    pub(crate) refill_per_second: u64,
+}
+
+/// Caps on the number of subscriptions that the service streams at the same time.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubscriptionConfig {
+    /// The maximum number of subscriptions open across all connections to the service.
+    #[serde(default = "default_max_subscriptions")]
+    pub(crate) max_subscriptions: usize,
+    /// The maximum number of subscriptions open on a single websocket connection.
+    #[serde(default = "default_max_subscriptions_per_connection")]
+    pub(crate) max_subscriptions_per_connection: usize,
+}
+
 #[derive(Debug)]
 pub struct Version(pub &'static str);
 
@@ -385,6 +453,45 @@ impl Default for Limits {
     }
 }
 
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            max_subscriptions: MAX_SUBSCRIPTIONS,
+            max_subscriptions_per_connection: MAX_SUBSCRIPTIONS_PER_CONNECTION,
+        }
+    }
+}
+
+/// Deserialize client budgets, rejecting budgets that share an API key, which would leave it
+/// ambiguous which client's budget a request should be charged to.
+fn deserialize_client_budgets<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, ClientBudget>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let clients = BTreeMap::<String, ClientBudget>::deserialize(deserializer)?;
+
+    let mut names = BTreeMap::new();
+    for (name, budget) in &clients {
+        if let Some(other) = names.insert(&budget.api_key, name) {
+            return Err(serde::de::Error::custom(format!(
+                "Clients '{other}' and '{name}' share an API key"
+            )));
+        }
+    }
+
+    Ok(clients)
+}
+
+fn default_max_subscriptions() -> usize {
+    MAX_SUBSCRIPTIONS
+}
+
+fn default_max_subscriptions_per_connection() -> usize {
+    MAX_SUBSCRIPTIONS_PER_CONNECTION
+}
+
 impl Default for InternalFeatureConfig {
     fn default() -> Self {
         Self {
@@ -489,6 +596,89 @@ mod tests {
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_persisted_queries_and_budgets_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [persisted-queries]
+                required = true
+                queries = ["{ chainIdentifier }"]
+
+                [budgets]
+                require-api-key = true
+
+                [budgets.clients.partner]
+                api-key = "secret"
+                capacity = 10000
+                refill-per-second = 100
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            persisted_queries: PersistedQueryConfig {
+                required: true,
+                queries: vec!["{ chainIdentifier }".to_string()],
+            },
+            budgets: BudgetConfig {
+                require_api_key: true,
+                clients: BTreeMap::from([(
+                    "partner".to_string(),
+                    ClientBudget {
+                        api_key: "secret".to_string(),
+                        capacity: 10_000,
+                        refill_per_second: 100,
+                    },
+                )]),
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
+    #[test]
+    fn test_reject_shared_api_keys_in_service_config() {
+        let err = ServiceConfig::read(
+            r#" [budgets.clients.partner]
+                api-key = "secret"
+                capacity = 10000
+                refill-per-second = 100
+
+                [budgets.clients.impostor]
+                api-key = "secret"
+                capacity = 10000
+                refill-per-second = 100
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("Clients 'impostor' and 'partner' share an API key"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_read_subscriptions_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [subscriptions]
+                max-subscriptions-per-connection = 3
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            subscriptions: SubscriptionConfig {
+                max_subscriptions_per_connection: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
     #[test]
     fn test_read_everything_in_service_config() {
         let actual = ServiceConfig::read(
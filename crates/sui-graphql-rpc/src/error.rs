@@ -12,8 +12,11 @@ use sui_json_rpc::name_service::NameServiceError;
 pub(crate) mod code {
     pub const BAD_REQUEST: &str = "BAD_REQUEST";
     pub const BAD_USER_INPUT: &str = "BAD_USER_INPUT";
+    pub const BUDGET_EXHAUSTED: &str = "BUDGET_EXHAUSTED";
     pub const INTERNAL_SERVER_ERROR: &str = "INTERNAL_SERVER_ERROR";
+    pub const PERSISTED_QUERY_NOT_FOUND: &str = "PERSISTED_QUERY_NOT_FOUND";
     pub const REQUEST_TIMEOUT: &str = "REQUEST_TIMEOUT";
+    pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
     pub const UNKNOWN: &str = "UNKNOWN";
 }
 
@@ -0,0 +1,435 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery};
+use async_graphql::parser::types::{
+    ExecutableDocument, FragmentDefinition, Selection, SelectionSet,
+};
+use async_graphql::{Name, Positioned, ServerError, ServerResult, Variables};
+use async_trait::async_trait;
+
+use crate::config::{BudgetConfig, ClientBudget, ServiceConfig};
+use crate::error::{code, graphql_error};
+use crate::extensions::query_limits_checker::{
+    estimate_output_nodes_for_curr_node, is_introspection,
+};
+use crate::metrics::Metrics;
+
+/// The API key that a request identified its client with.
+#[derive(Clone, Debug)]
+pub(crate) struct ApiKey(pub String);
+
+/// Budgets of query cost for each client, which requests are charged against. Budgets are token
+/// buckets, which refill at a constant rate up to a maximum capacity.
+///
+/// As an extension, it charges the estimated cost of every query to the budget of the client that
+/// sent it (and rejects requests without an API key, if one is required). It is installed whenever
+/// budgets are configured, regardless of whether the `QueryLimitsChecker` is, so it estimates the
+/// cost of queries itself.
+#[derive(Clone)]
+pub(crate) struct ClientBudgets {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    require_api_key: bool,
+    /// Names of clients, keyed by their API keys.
+    clients: HashMap<String, String>,
+    /// Budgets, keyed by client name.
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+struct TokenBucket {
+    capacity: f64,
+    refill_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum BudgetError {
+    MissingApiKey,
+    UnknownApiKey,
+    /// The request costs more than the client's budget could ever hold.
+    CostExceedsCapacity {
+        cost: u64,
+        capacity: u64,
+    },
+    /// The client's budget does not currently hold enough to pay for the request.
+    Exhausted {
+        retry_after: Duration,
+    },
+}
+
+impl ClientBudgets {
+    pub(crate) fn new(config: &BudgetConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            inner: Arc::new(Inner {
+                require_api_key: config.require_api_key,
+                clients: config
+                    .clients
+                    .iter()
+                    .map(|(name, budget)| (budget.api_key.clone(), name.clone()))
+                    .collect(),
+                buckets: Mutex::new(
+                    config
+                        .clients
+                        .iter()
+                        .map(|(name, budget)| (name.clone(), TokenBucket::new(budget, now)))
+                        .collect(),
+                ),
+            }),
+        }
+    }
+
+    /// Charge a request with `cost` to the budget of the client identified by `api_key`,
+    /// updating `metrics`. Requests that don't identify a client are not charged, unless an API
+    /// key is required, in which case they are rejected.
+    pub(crate) fn charge(
+        &self,
+        api_key: Option<&ApiKey>,
+        cost: u64,
+        metrics: Option<&Metrics>,
+    ) -> ServerResult<()> {
+        let Some(client) = self.client(api_key)? else {
+            return Ok(());
+        };
+
+        let result = self.charge_client_at(client, cost, Instant::now());
+        let Some(metrics) = metrics else {
+            return result.map(|_| ()).map_err(Into::into);
+        };
+
+        let metrics = &metrics.request_metrics;
+        match result {
+            Ok(remaining) => {
+                metrics
+                    .client_budget_spent
+                    .with_label_values(&[client.as_str()])
+                    .inc_by(cost);
+                metrics
+                    .client_budget_remaining
+                    .with_label_values(&[client.as_str()])
+                    .set(remaining);
+                Ok(())
+            }
+            Err(e) => {
+                metrics
+                    .client_budget_exhausted
+                    .with_label_values(&[client.as_str()])
+                    .inc();
+                Err(e.into())
+            }
+        }
+    }
+
+    /// The name of the client identified by `api_key`, or `None` if the request is anonymous and
+    /// anonymous requests are allowed.
+    fn client(&self, api_key: Option<&ApiKey>) -> ServerResult<Option<&str>> {
+        let Some(ApiKey(api_key)) = api_key else {
+            return if self.inner.require_api_key {
+                Err(BudgetError::MissingApiKey.into())
+            } else {
+                Ok(None)
+            };
+        };
+
+        match self.inner.clients.get(api_key) {
+            Some(client) => Ok(Some(client.as_str())),
+            None => Err(BudgetError::UnknownApiKey.into()),
+        }
+    }
+
+    /// Charge `cost` to `client`'s budget as of `now`, returning the cost remaining in the budget.
+    fn charge_client_at(&self, client: &str, cost: u64, now: Instant) -> Result<f64, BudgetError> {
+        let mut buckets = self.inner.buckets.lock().unwrap();
+        let bucket = buckets
+            .get_mut(client)
+            .expect("Every configured client has a budget");
+        bucket.charge(cost, now)
+    }
+}
+
+impl ExtensionFactory for ClientBudgets {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl Extension for ClientBudgets {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        // Turn away unidentified clients before doing any work on their behalf.
+        let api_key = ctx.data_opt::<ApiKey>();
+        self.client(api_key)?;
+
+        let doc = next.run(ctx, query, variables).await?;
+        let default_page_size = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data")
+            .limits
+            .default_page_size;
+
+        let cost = query_cost(&doc, variables, default_page_size);
+        self.charge(api_key, cost, ctx.data_opt::<Metrics>())?;
+        Ok(doc)
+    }
+}
+
+/// Estimate the number of output nodes in the operations of `doc`, in the same way as the
+/// `QueryLimitsChecker`, but without relying on the query to be within any limits: The costs of
+/// fragments are memoized, so that fragments that spread each other many times are only visited
+/// once, and a spread that forms a cycle (which the query's validation will reject) adds nothing.
+fn query_cost(doc: &ExecutableDocument, variables: &Variables, default_page_size: u64) -> u64 {
+    let mut fragment_costs = HashMap::new();
+    let mut cost = 0u64;
+    for (count, (_name, oper)) in doc.operations.iter().enumerate() {
+        let sel_set = &oper.node.selection_set;
+
+        // Pure introspection queries are free, as they are in the `QueryLimitsChecker`.
+        if count == 0 && is_introspection(sel_set) {
+            continue;
+        }
+
+        cost = cost.saturating_add(selection_set_cost(
+            sel_set,
+            &doc.fragments,
+            variables,
+            default_page_size,
+            &mut fragment_costs,
+        ));
+    }
+
+    cost
+}
+
+/// The number of output nodes in one instance of `sel_set`.
+fn selection_set_cost<'d>(
+    sel_set: &'d Positioned<SelectionSet>,
+    fragments: &'d HashMap<Name, Positioned<FragmentDefinition>>,
+    variables: &Variables,
+    default_page_size: u64,
+    fragment_costs: &mut HashMap<&'d Name, u64>,
+) -> u64 {
+    let mut cost = 0u64;
+    for selection in &sel_set.node.items {
+        let selection_cost = match &selection.node {
+            Selection::Field(f) => {
+                let nodes = estimate_output_nodes_for_curr_node(f, variables, default_page_size);
+                let children = selection_set_cost(
+                    &f.node.selection_set,
+                    fragments,
+                    variables,
+                    default_page_size,
+                    fragment_costs,
+                );
+                nodes.saturating_add(nodes.saturating_mul(children))
+            }
+
+            Selection::FragmentSpread(fs) => {
+                let name = &fs.node.fragment_name.node;
+                if let Some(cost) = fragment_costs.get(name) {
+                    *cost
+                } else if let Some(def) = fragments.get(name) {
+                    // Mark the fragment as visited while its cost is being calculated, so that a
+                    // cycle back to it ends the traversal.
+                    fragment_costs.insert(name, 0);
+                    let cost = selection_set_cost(
+                        &def.node.selection_set,
+                        fragments,
+                        variables,
+                        default_page_size,
+                        fragment_costs,
+                    );
+                    fragment_costs.insert(name, cost);
+                    cost
+                } else {
+                    0
+                }
+            }
+
+            Selection::InlineFragment(fs) => selection_set_cost(
+                &fs.node.selection_set,
+                fragments,
+                variables,
+                default_page_size,
+                fragment_costs,
+            ),
+        };
+
+        cost = cost.saturating_add(selection_cost);
+    }
+
+    cost
+}
+
+impl TokenBucket {
+    fn new(budget: &ClientBudget, now: Instant) -> Self {
+        Self {
+            capacity: budget.capacity as f64,
+            refill_per_second: budget.refill_per_second as f64,
+            tokens: budget.capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    fn charge(&mut self, cost: u64, now: Instant) -> Result<f64, BudgetError> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        let cost_f64 = cost as f64;
+        if cost_f64 > self.capacity {
+            return Err(BudgetError::CostExceedsCapacity {
+                cost,
+                capacity: self.capacity as u64,
+            });
+        }
+
+        if cost_f64 > self.tokens {
+            let retry_after = if self.refill_per_second > 0.0 {
+                Duration::from_secs_f64((cost_f64 - self.tokens) / self.refill_per_second)
+            } else {
+                Duration::MAX
+            };
+            return Err(BudgetError::Exhausted { retry_after });
+        }
+
+        self.tokens -= cost_f64;
+        Ok(self.tokens)
+    }
+}
+
+impl From<BudgetError> for ServerError {
+    fn from(e: BudgetError) -> Self {
+        match e {
+            BudgetError::MissingApiKey => graphql_error(
+                code::UNAUTHENTICATED,
+                "Requests to this service must identify their client with an API key",
+            ),
+            BudgetError::UnknownApiKey => {
+                graphql_error(code::UNAUTHENTICATED, "Unrecognized API key")
+            }
+            BudgetError::CostExceedsCapacity { cost, capacity } => graphql_error(
+                code::BUDGET_EXHAUSTED,
+                format!(
+                    "Query cost {cost} exceeds the capacity of the client's budget, {capacity}"
+                ),
+            ),
+            BudgetError::Exhausted { retry_after } => graphql_error(
+                code::BUDGET_EXHAUSTED,
+                format!(
+                    "Client's budget is exhausted. Retry in {:.1}s",
+                    retry_after.as_secs_f32()
+                ),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn budgets(require_api_key: bool) -> ClientBudgets {
+        ClientBudgets::new(&BudgetConfig {
+            require_api_key,
+            clients: BTreeMap::from([(
+                "partner".to_string(),
+                ClientBudget {
+                    api_key: "secret".to_string(),
+                    capacity: 100,
+                    refill_per_second: 10,
+                },
+            )]),
+        })
+    }
+
+    #[test]
+    fn test_anonymous_requests() {
+        assert!(budgets(false).charge(None, 1000, None).is_ok());
+        assert_eq!(
+            budgets(true).charge(None, 1, None).unwrap_err().message,
+            "Requests to this service must identify their client with an API key",
+        );
+        assert_eq!(
+            budgets(false)
+                .charge(Some(&ApiKey("wrong".to_string())), 1, None)
+                .unwrap_err()
+                .message,
+            "Unrecognized API key",
+        );
+    }
+
+    #[test]
+    fn test_budget_refills() {
+        let budgets = budgets(true);
+        let start = Instant::now();
+
+        assert_eq!(budgets.charge_client_at("partner", 60, start), Ok(40.0));
+        assert_eq!(
+            budgets.charge_client_at("partner", 60, start),
+            Err(BudgetError::Exhausted {
+                retry_after: Duration::from_secs(2)
+            }),
+        );
+
+        // A rejected request is not charged, and the budget refills over time.
+        let later = start + Duration::from_secs(2);
+        assert_eq!(budgets.charge_client_at("partner", 60, later), Ok(0.0));
+
+        // The budget never holds more than its capacity.
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(
+            budgets.charge_client_at("partner", 101, much_later),
+            Err(BudgetError::CostExceedsCapacity {
+                cost: 101,
+                capacity: 100
+            }),
+        );
+        assert_eq!(
+            budgets.charge_client_at("partner", 100, much_later),
+            Ok(0.0)
+        );
+    }
+
+    #[test]
+    fn test_query_cost() {
+        let cost = |query: &str| {
+            let doc = async_graphql::parser::parse_query(query).unwrap();
+            query_cost(&doc, &Variables::default(), 10)
+        };
+
+        // A page of ten objects, each with two fields.
+        assert_eq!(cost("{ objects { nodes { address version } } }"), 40);
+        assert_eq!(cost("{ objects(first: 2) { nodes { address } } }"), 6);
+
+        // Fragments are costed every time they are spread, and cycles terminate.
+        assert_eq!(
+            cost("{ ...A } fragment A on Query { ...B ...B } fragment B on Query { chainIdentifier }"),
+            2,
+        );
+        assert_eq!(
+            cost("{ ...A } fragment A on Query { chainIdentifier ...A }"),
+            1
+        );
+
+        // Introspection is free.
+        assert_eq!(cost("{ __schema { types { name } } }"), 0);
+    }
+}
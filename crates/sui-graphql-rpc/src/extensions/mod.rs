@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod client_budget;
 pub(crate) mod feature_gate;
 pub(crate) mod logger;
+pub(crate) mod persisted_queries;
 pub mod query_limits_checker;
 pub(crate) mod timeout;
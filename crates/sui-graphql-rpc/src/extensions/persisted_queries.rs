@@ -0,0 +1,188 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest},
+    Request, ServerResult, Value,
+};
+use async_trait::async_trait;
+use fastcrypto::hash::{HashFunction, Sha256};
+
+use crate::{
+    config::PersistedQueryConfig,
+    error::{code, graphql_error},
+    metrics::Metrics,
+};
+
+/// Serves queries that have been registered with the service ahead of time. Clients refer to a
+/// persisted query by sending the hex-encoded SHA-256 hash of its text in the request's
+/// `extensions.persistedQuery.sha256Hash` field (following Apollo's persisted query protocol),
+/// and can leave the query text out of the request.
+///
+/// If persisted queries are required, requests that do not refer to a persisted query are only
+/// accepted if their query text is the text of a persisted query.
+#[derive(Clone, Debug)]
+pub(crate) struct PersistedQueries {
+    /// The text of persisted queries, keyed by their hash.
+    queries: Arc<HashMap<String, String>>,
+    required: bool,
+}
+
+impl PersistedQueries {
+    pub(crate) fn new(config: &PersistedQueryConfig) -> Self {
+        Self {
+            queries: Arc::new(
+                config
+                    .queries
+                    .iter()
+                    .map(|query| (query_hash(query), query.clone()))
+                    .collect(),
+            ),
+            required: config.required,
+        }
+    }
+}
+
+impl ExtensionFactory for PersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl Extension for PersistedQueries {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let observe = |result: &str| {
+            if let Some(metrics) = ctx.data_opt::<Metrics>() {
+                metrics
+                    .request_metrics
+                    .persisted_queries
+                    .with_label_values(&[result])
+                    .inc();
+            }
+        };
+
+        if let Some(hash) = requested_hash(&request) {
+            let Some(query) = self.queries.get(&hash.to_ascii_lowercase()) else {
+                observe("not_found");
+                return Err(graphql_error(
+                    code::PERSISTED_QUERY_NOT_FOUND,
+                    "PersistedQueryNotFound",
+                ));
+            };
+
+            if !request.query.is_empty() && request.query != *query {
+                observe("mismatch");
+                return Err(graphql_error(
+                    code::BAD_USER_INPUT,
+                    "Query text does not match the persisted query with the provided hash",
+                ));
+            }
+
+            observe("found");
+            request.query = query.clone();
+        } else if self.required && !self.queries.contains_key(&query_hash(&request.query)) {
+            observe("required");
+            return Err(graphql_error(
+                code::PERSISTED_QUERY_NOT_FOUND,
+                "Only persisted queries are accepted by this service",
+            ));
+        }
+
+        next.run(ctx, request).await
+    }
+}
+
+/// The hash of the persisted query that `request` refers to, if any.
+fn requested_hash(request: &Request) -> Option<&str> {
+    let Some(Value::Object(persisted_query)) = request.extensions.get("persistedQuery") else {
+        return None;
+    };
+
+    let Some(Value::String(hash)) = persisted_query.get("sha256Hash") else {
+        return None;
+    };
+
+    Some(hash.as_str())
+}
+
+/// Hex-encoded SHA-256 hash of a query's text.
+fn query_hash(query: &str) -> String {
+    hex::encode(Sha256::digest(query.as_bytes()).digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptySubscription, Schema};
+
+    use crate::{config::ServiceConfig, mutation::Mutation, types::query::Query};
+
+    use super::*;
+
+    const QUERY: &str = "{ serviceConfig { maxQueryDepth } }";
+
+    fn schema(required: bool) -> Schema<Query, Mutation, EmptySubscription> {
+        Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig::default())
+            .extension(PersistedQueries::new(&PersistedQueryConfig {
+                required,
+                queries: vec![QUERY.to_string()],
+            }))
+            .finish()
+    }
+
+    fn persisted(hash: &str) -> Request {
+        let persisted_query = serde_json::json!({ "version": 1, "sha256Hash": hash });
+
+        let mut request = Request::new("");
+        request.extensions.insert(
+            "persistedQuery".to_string(),
+            Value::from_json(persisted_query).unwrap(),
+        );
+        request
+    }
+
+    fn errors(response: async_graphql::Response) -> Vec<String> {
+        response.errors.into_iter().map(|e| e.message).collect()
+    }
+
+    #[tokio::test]
+    async fn test_persisted_query_by_hash() {
+        let response = schema(false).execute(persisted(&query_hash(QUERY))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_persisted_query() {
+        let response = schema(false).execute(persisted(&query_hash("{ a }"))).await;
+        assert_eq!(errors(response), vec!["PersistedQueryNotFound"]);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_query_required() {
+        let response = schema(true)
+            .execute("{ serviceConfig { maxPageSize } }")
+            .await;
+        assert_eq!(
+            errors(response),
+            vec!["Only persisted queries are accepted by this service"]
+        );
+
+        // Sending the full text of a persisted query is still accepted.
+        let response = schema(true).execute(QUERY).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+
+        let response = schema(false)
+            .execute("{ serviceConfig { maxPageSize } }")
+            .await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+    }
+}
@@ -166,16 +166,8 @@ impl Extension for QueryLimitsChecker {
             let sel_set = &oper.node.selection_set;
 
             // If the query is pure introspection, we don't need to check the limits.
-            // Pure introspection queries are queries that only have one operation with one field
-            // and that field is a `__schema` query
-            if (count == 0) && (sel_set.node.items.len() == 1) {
-                if let Some(node) = sel_set.node.items.first() {
-                    if let Selection::Field(field) = &node.node {
-                        if field.node.name.node == "__schema" {
-                            continue;
-                        }
-                    }
-                }
+            if count == 0 && is_introspection(sel_set) {
+                continue;
             }
 
             running_costs.depth = 0;
@@ -189,6 +181,7 @@ impl Extension for QueryLimitsChecker {
             )?;
             max_depth_seen = max_depth_seen.max(running_costs.depth);
         }
+
         let elapsed = instant.elapsed().as_millis() as u64;
 
         if ctx.data_opt::<ShowUsage>().is_some() {
@@ -222,6 +215,16 @@ impl Extension for QueryLimitsChecker {
     }
 }
 
+/// Pure introspection queries are queries that only have one operation with one field, and that
+/// field is a `__schema` query.
+pub(crate) fn is_introspection(sel_set: &Positioned<SelectionSet>) -> bool {
+    let [node] = sel_set.node.items.as_slice() else {
+        return false;
+    };
+
+    matches!(&node.node, Selection::Field(field) if field.node.name.node == "__schema")
+}
+
 impl QueryLimitsChecker {
     /// Parse the selected fields in one operation and check if it conforms to configured limits.
     fn analyze_selection_set(
@@ -430,7 +433,7 @@ fn check_directives(directives: &[Positioned<Directive>]) -> ServerResult<()> {
 }
 
 /// Given a node, estimate the number of output nodes it will produce.
-fn estimate_output_nodes_for_curr_node(
+pub(crate) fn estimate_output_nodes_for_curr_node(
     f: &Positioned<Field>,
     variables: &Variables,
     default_page_size: u64,
@@ -5,10 +5,10 @@ use std::sync::Arc;
 
 use async_graphql::{PathSegment, ServerError};
 use prometheus::{
-    register_gauge_with_registry, register_histogram_vec_with_registry,
-    register_histogram_with_registry, register_int_counter_vec_with_registry,
-    register_int_counter_with_registry, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec,
-    Registry,
+    register_gauge_vec_with_registry, register_gauge_with_registry,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry, Gauge, GaugeVec,
+    Histogram, HistogramVec, IntCounter, IntCounterVec, Registry,
 };
 
 use crate::error::code;
@@ -81,6 +81,14 @@ pub(crate) struct RequestMetrics {
     pub num_queries_top_level: IntCounterVec,
     /// Total inflight requests
     pub inflight_requests: Gauge,
+    /// Number of requests that referred to persisted queries, by whether the query was found
+    pub persisted_queries: IntCounterVec,
+    /// Total query cost charged to each client's budget
+    pub client_budget_spent: IntCounterVec,
+    /// Cost remaining in each client's budget, as of its latest request
+    pub client_budget_remaining: GaugeVec,
+    /// Number of requests rejected because they exceeded their client's budget, by client
+    pub client_budget_exhausted: IntCounterVec,
 }
 
 impl Metrics {
@@ -256,6 +264,34 @@ impl RequestMetrics {
                 registry
             )
             .unwrap(),
+            persisted_queries: register_int_counter_vec_with_registry!(
+                "persisted_queries",
+                "Number of requests for persisted queries, by whether the query was found",
+                &["result"],
+                registry
+            )
+            .unwrap(),
+            client_budget_spent: register_int_counter_vec_with_registry!(
+                "client_budget_spent",
+                "Total query cost charged to each client's budget",
+                &["client"],
+                registry
+            )
+            .unwrap(),
+            client_budget_remaining: register_gauge_vec_with_registry!(
+                "client_budget_remaining",
+                "Query cost remaining in each client's budget, as of its latest request",
+                &["client"],
+                registry
+            )
+            .unwrap(),
+            client_budget_exhausted: register_int_counter_vec_with_registry!(
+                "client_budget_exhausted",
+                "Number of requests rejected for exceeding their client's budget",
+                &["client"],
+                registry
+            )
+            .unwrap(),
         }
     }
 }
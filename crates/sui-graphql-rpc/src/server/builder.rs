@@ -3,11 +3,16 @@
 
 use crate::config::{
     ConnectionConfig, Version, MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+    SUBSCRIPTION_POLL_INTERVAL,
 };
 use crate::context_data::package_cache::DbPackageStore;
 use crate::data::Db;
+use crate::functional_group::FunctionalGroup;
 use crate::metrics::Metrics;
 use crate::mutation::Mutation;
+use crate::subscription::{
+    CheckpointWatermark, ConnectionSubscriptions, ServiceSubscriptions, Subscription,
+};
 use crate::types::move_object::IMoveObject;
 use crate::types::object::IObject;
 use crate::types::owner::IOwner;
@@ -16,8 +21,10 @@ use crate::{
     context_data::db_data_provider::PgManager,
     error::Error,
     extensions::{
+        client_budget::{ApiKey, ClientBudgets},
         feature_gate::FeatureGate,
         logger::Logger,
+        persisted_queries::PersistedQueries,
         query_limits_checker::{QueryLimitsChecker, ShowUsage},
         timeout::Timeout,
     },
@@ -27,15 +34,16 @@ use crate::{
 use async_graphql::dataloader::DataLoader;
 use async_graphql::extensions::ApolloTracing;
 use async_graphql::extensions::Tracing;
-use async_graphql::EmptySubscription;
 use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{http::ALL_WEBSOCKET_PROTOCOLS, Data};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::extract::ws::WebSocketUpgrade;
 use axum::extract::FromRef;
 use axum::extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::middleware::{self};
 use axum::response::IntoResponse;
-use axum::routing::{post, MethodRouter, Route};
+use axum::routing::{get, post, MethodRouter, Route};
 use axum::{headers::Header, Router};
 use http::{HeaderValue, Method, Request};
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
@@ -45,7 +53,7 @@ use mysten_network::callback::{CallbackLayer, MakeCallbackHandler, ResponseHandl
 use std::convert::Infallible;
 use std::net::TcpStream;
 use std::{any::Any, net::SocketAddr, time::Instant};
-use sui_graphql_rpc_headers::{LIMITS_HEADER, VERSION_HEADER};
+use sui_graphql_rpc_headers::{API_KEY_HEADER, LIMITS_HEADER, VERSION_HEADER};
 use sui_package_resolver::{PackageStoreWithLruCache, Resolver};
 use sui_sdk::SuiClientBuilder;
 use tokio::sync::OnceCell;
@@ -69,7 +77,7 @@ impl Server {
 
 pub(crate) struct ServerBuilder {
     state: AppState,
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     router: Option<Router>,
 }
 
@@ -126,11 +134,11 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
-    fn build_components(self) -> (String, Schema<Query, Mutation, EmptySubscription>, Router) {
+    fn build_components(self) -> (String, Schema<Query, Mutation, Subscription>, Router) {
         let address = self.address();
         let ServerBuilder { schema, router, .. } = self;
         (
@@ -145,6 +153,7 @@ impl ServerBuilder {
             let router: Router = Router::new()
                 .route("/", post(graphql_handler))
                 .route("/graphql", post(graphql_handler))
+                .route("/subscriptions", get(subscription_handler))
                 .route("/health", axum::routing::get(health_checks))
                 .with_state(self.state.clone())
                 .route_layer(middleware::from_fn_with_state(
@@ -197,14 +206,15 @@ impl ServerBuilder {
         info!("Access control allow origin set to: {acl:?}");
 
         let cors = CorsLayer::new()
-            // Allow `POST` when accessing the resource
-            .allow_methods([Method::POST])
+            // Allow `POST` when accessing the resource, and `GET` to open subscriptions
+            .allow_methods([Method::POST, Method::GET])
             // Allow requests from any origin
             .allow_origin(acl)
             .allow_headers([
                 hyper::header::CONTENT_TYPE,
                 VERSION_HEADER.clone(),
                 LIMITS_HEADER.clone(),
+                API_KEY_HEADER.clone(),
             ]);
         Ok(cors)
     }
@@ -285,6 +295,20 @@ impl ServerBuilder {
             None
         };
 
+        // SUBSCRIPTIONS
+        if !config
+            .service
+            .disabled_features
+            .contains(&FunctionalGroup::Subscriptions)
+        {
+            builder = builder
+                .context_data(CheckpointWatermark::spawn(
+                    db.clone(),
+                    SUBSCRIPTION_POLL_INTERVAL,
+                ))
+                .context_data(ServiceSubscriptions::default());
+        }
+
         builder = builder
             .context_data(config.service.clone())
             .context_data(DataLoader::new(db.clone(), tokio::spawn))
@@ -299,6 +323,18 @@ impl ServerBuilder {
             .context_data(metrics.clone())
             .context_data(config.clone());
 
+        let persisted_queries = &config.service.persisted_queries;
+        if persisted_queries.required || !persisted_queries.queries.is_empty() {
+            builder = builder.extension(PersistedQueries::new(persisted_queries));
+        }
+
+        // Budgets and API keys are enforced whenever they are configured, independently of the
+        // internal features below.
+        let budgets = &config.service.budgets;
+        if budgets.require_api_key || !budgets.clients.is_empty() {
+            builder = builder.extension(ClientBudgets::new(budgets));
+        }
+
         if config.internal_features.feature_gate {
             builder = builder.extension(FeatureGate);
         }
@@ -325,8 +361,8 @@ impl ServerBuilder {
     }
 }
 
-fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    async_graphql::Schema::build(Query, Mutation, EmptySubscription)
+fn schema_builder() -> SchemaBuilder<Query, Mutation, Subscription> {
+    async_graphql::Schema::build(Query, Mutation, Subscription)
         .register_output_type::<IMoveObject>()
         .register_output_type::<IObject>()
         .register_output_type::<IOwner>()
@@ -348,6 +384,9 @@ async fn graphql_handler(
     if headers.contains_key(ShowUsage::name()) {
         req.data.insert(ShowUsage)
     }
+    if let Some(api_key) = api_key(&headers) {
+        req.data.insert(api_key);
+    }
     // Capture the IP address of the client
     // Note: if a load balancer is used it must be configured to forward the client IP address
     req.data.insert(addr);
@@ -362,6 +401,43 @@ async fn graphql_handler(
     (extensions, result.into())
 }
 
+/// Serve subscriptions over a websocket, using the graphql-ws protocol.
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    schema: axum::Extension<SuiGraphQLSchema>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    websocket: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let api_key = api_key(&headers);
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            // Identify the connection the same way as a request in `graphql_handler`, for the
+            // benefit of extensions that log per-request information.
+            let mut data = Data::default();
+            data.insert(Uuid::new_v4());
+            data.insert(addr);
+            if let Some(api_key) = api_key {
+                data.insert(api_key);
+            }
+
+            // Subscriptions made on this connection are counted against its own cap.
+            data.insert(ConnectionSubscriptions::default());
+
+            GraphQLWebSocket::new(stream, schema.0, protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
+/// The API key that the client identified itself with, if any.
+fn api_key(headers: &HeaderMap) -> Option<ApiKey> {
+    let value = headers.get(&API_KEY_HEADER)?.to_str().ok()?;
+    Some(ApiKey(value.to_string()))
+}
+
 #[derive(Clone)]
 struct MetricsMakeCallbackHandler {
     metrics: Metrics,
@@ -444,7 +520,9 @@ async fn get_or_init_server_start_time() -> &'static Instant {
 pub mod tests {
     use super::*;
     use crate::{
-        config::{ConnectionConfig, Limits, ServiceConfig},
+        config::{
+            BudgetConfig, ClientBudget, ConnectionConfig, Limits, ServiceConfig, SubscriptionConfig,
+        },
         context_data::db_data_provider::PgManager,
         extensions::query_limits_checker::QueryLimitsChecker,
         extensions::timeout::Timeout,
@@ -454,10 +532,14 @@ pub mod tests {
         extensions::{Extension, ExtensionContext, NextExecute},
         Response,
     };
+    use futures::{stream::BoxStream, FutureExt, StreamExt};
     use rand::{rngs::StdRng, SeedableRng};
     use simulacrum::Simulacrum;
     use std::sync::Arc;
     use std::time::Duration;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::effects::TransactionEffectsAPI;
+    use tokio::sync::watch;
     use uuid::Uuid;
 
     async fn prep_cluster() -> ConnectionConfig {
@@ -884,4 +966,451 @@ pub mod tests {
         assert_eq!(req_metrics.output_nodes.get_sample_sum(), 2. + 4.);
         assert_eq!(req_metrics.query_depth.get_sample_sum(), 1. + 3.);
     }
+
+    /// A schema that serves subscriptions from the database at `connection_config`, as the
+    /// checkpoints sent on `rx` are indexed.
+    fn subscription_schema(
+        connection_config: &ConnectionConfig,
+        service_config: ServiceConfig,
+        rx: watch::Receiver<Option<u64>>,
+    ) -> Schema<Query, Mutation, Subscription> {
+        let metrics = metrics();
+        let db_url: String = connection_config.db_url.clone();
+        let reader = PgManager::reader(db_url).expect("Failed to create pg connection pool");
+        let db = Db::new(reader.clone(), service_config.limits, metrics.clone());
+        let pg_conn_pool = PgManager::new(reader);
+        let state = AppState::new(connection_config.clone(), metrics.clone());
+
+        ServerBuilder::new(state)
+            .context_data(db)
+            .context_data(pg_conn_pool)
+            .context_data(service_config)
+            .context_data(query_id())
+            .context_data(ip_address())
+            .context_data(metrics)
+            .context_data(CheckpointWatermark::from_receiver(rx))
+            .context_data(ServiceSubscriptions::default())
+            .build_schema()
+    }
+
+    /// Subscribe to `subscription`, and poll it once, so that it starts streaming from the
+    /// checkpoint the watermark is currently at.
+    fn subscribe(
+        schema: &Schema<Query, Mutation, Subscription>,
+        subscription: &str,
+    ) -> BoxStream<'static, Response> {
+        let mut stream = schema.execute_stream(subscription).boxed();
+        assert!(
+            stream.next().now_or_never().is_none(),
+            "Nothing to stream before the watermark advances"
+        );
+        stream
+    }
+
+    /// Wait for the next `n` responses from a subscription, and return their data.
+    async fn next_data(
+        stream: &mut BoxStream<'static, Response>,
+        n: usize,
+    ) -> Vec<serde_json::Value> {
+        let responses: Vec<Response> =
+            tokio::time::timeout(Duration::from_secs(10), stream.take(n).collect())
+                .await
+                .expect("Timeout waiting for subscription");
+
+        responses
+            .into_iter()
+            .map(|resp| resp.into_result().unwrap().data.into_json().unwrap())
+            .collect()
+    }
+
+    /// Wait for the next `n` transaction digests from a `transactions` subscription.
+    async fn next_digests(stream: &mut BoxStream<'static, Response>, n: usize) -> Vec<String> {
+        next_data(stream, n)
+            .await
+            .into_iter()
+            .map(|data| data["transactions"]["digest"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    pub async fn test_subscription_transactions_impl() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        // Three checkpoints, each with a transfer to each of two recipients.
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+        let mut digests = vec![];
+        let mut alice_digests = vec![];
+        for _ in 0..3 {
+            for recipient in [alice, bob] {
+                let (transaction, _) = sim.transfer_txn(recipient);
+                let (effects, err) = sim.execute_transaction(transaction).unwrap();
+                assert!(err.is_none());
+
+                let digest = effects.transaction_digest().to_string();
+                if recipient == alice {
+                    alice_digests.push(digest.clone());
+                }
+                digests.push(digest);
+            }
+            sim.create_checkpoint();
+        }
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = serve_executor(
+            connection_config.clone(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+        )
+        .await;
+
+        cluster
+            .wait_for_checkpoint_catchup(3, Duration::from_secs(10))
+            .await;
+
+        // Pages of two transactions, so that catching up on a checkpoint takes multiple pages.
+        let service_config = ServiceConfig {
+            limits: Limits {
+                default_page_size: 2,
+                max_page_size: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Subscriptions start from genesis, and only see checkpoints as the watermark advances.
+        let (watermark, rx) = watch::channel(Some(0));
+        let schema = subscription_schema(&connection_config, service_config, rx);
+
+        let mut all = subscribe(&schema, "subscription { transactions { digest } }");
+        let mut to_alice = subscribe(
+            &schema,
+            &format!("subscription {{ transactions(filter: {{ recvAddress: \"{alice}\" }}) {{ digest }} }}"),
+        );
+
+        // Transactions are streamed in order, one checkpoint at a time.
+        watermark.send(Some(1)).unwrap();
+        assert_eq!(next_digests(&mut all, 2).await, digests[..2]);
+
+        // A subscriber that falls behind catches up on every checkpoint it missed, in order.
+        watermark.send(Some(3)).unwrap();
+        assert_eq!(next_digests(&mut all, 4).await, digests[2..]);
+
+        // Filtered subscriptions only stream matching transactions.
+        assert_eq!(next_digests(&mut to_alice, 3).await, alice_digests);
+
+        // Nothing more is streamed until the watermark advances again.
+        assert!(
+            tokio::time::timeout(Duration::from_secs(1), all.next())
+                .await
+                .is_err(),
+            "No transactions after the latest checkpoint"
+        );
+    }
+
+    pub async fn test_subscription_events_impl() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        // A checkpoint without any epoch changes, followed by two epoch changes, each of which
+        // emits an event, and ends its own checkpoint.
+        let (transaction, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+        let (_, err) = sim.execute_transaction(transaction).unwrap();
+        assert!(err.is_none());
+        sim.create_checkpoint();
+        sim.advance_epoch(false);
+        sim.advance_epoch(false);
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = serve_executor(
+            connection_config.clone(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+        )
+        .await;
+
+        cluster
+            .wait_for_checkpoint_catchup(3, Duration::from_secs(10))
+            .await;
+
+        let (watermark, rx) = watch::channel(Some(0));
+        let schema = subscription_schema(&connection_config, ServiceConfig::default(), rx);
+
+        let mut epochs = subscribe(
+            &schema,
+            "subscription { \
+                events(filter: { \
+                    eventType: \"0x3::sui_system_state_inner::SystemEpochInfoEvent\" \
+                }) { json } \
+            }",
+        );
+
+        // The checkpoint without an epoch change has no events to stream.
+        watermark.send(Some(1)).unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_secs(1), epochs.next())
+                .await
+                .is_err(),
+            "No events before the first epoch change"
+        );
+
+        // Each epoch change's event is streamed once its checkpoint is seen, in order.
+        watermark.send(Some(3)).unwrap();
+        let events = next_data(&mut epochs, 2).await;
+        let epochs_changed: Vec<_> = events
+            .iter()
+            .map(|data| data["events"]["json"]["epoch"].as_str().unwrap())
+            .collect();
+        assert_eq!(epochs_changed, vec!["1", "2"]);
+    }
+
+    pub async fn test_subscription_object_changes_impl() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        // Two checkpoints, each with a transfer to each of two recipients. Every transfer creates
+        // the recipient's coin and modifies the sender's gas coin.
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+        let mut alice_changes = vec![];
+        let mut alice_coins = vec![];
+        for _ in 0..2 {
+            for recipient in [alice, bob] {
+                let (transaction, _) = sim.transfer_txn(recipient);
+                let (effects, err) = sim.execute_transaction(transaction).unwrap();
+                assert!(err.is_none());
+
+                if recipient == alice {
+                    alice_coins.push(effects.created()[0].0 .0.to_string());
+                    alice_changes.extend(
+                        effects
+                            .object_changes()
+                            .into_iter()
+                            .map(|change| change.id.to_string()),
+                    );
+                }
+            }
+            sim.create_checkpoint();
+        }
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = serve_executor(
+            connection_config.clone(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+        )
+        .await;
+
+        cluster
+            .wait_for_checkpoint_catchup(2, Duration::from_secs(10))
+            .await;
+
+        let (watermark, rx) = watch::channel(Some(0));
+        let schema = subscription_schema(&connection_config, ServiceConfig::default(), rx);
+
+        let mut to_alice = subscribe(
+            &schema,
+            &format!(
+                "subscription {{ objectChanges(filter: {{ recvAddress: \"{alice}\" }}) {{ \
+                    address \
+                }} }}"
+            ),
+        );
+
+        let first_coin = &alice_coins[0];
+        let mut first_coin_changes = subscribe(
+            &schema,
+            &format!(
+                "subscription {{ objectChanges(filter: {{ changedObject: \"{first_coin}\" }}) {{ \
+                    address \
+                    idCreated \
+                }} }}"
+            ),
+        );
+
+        // Every change made by a matching transaction is streamed, in order.
+        watermark.send(Some(2)).unwrap();
+        let changes: Vec<_> = next_data(&mut to_alice, alice_changes.len())
+            .await
+            .into_iter()
+            .map(|data| {
+                data["objectChanges"]["address"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(changes, alice_changes);
+
+        // Filtering on a changed object only streams changes to that object.
+        let changes = next_data(&mut first_coin_changes, 1).await;
+        assert_eq!(
+            changes[0]["objectChanges"]["address"].as_str().unwrap(),
+            first_coin
+        );
+        assert_eq!(changes[0]["objectChanges"]["idCreated"], true);
+        assert!(
+            tokio::time::timeout(Duration::from_secs(1), first_coin_changes.next())
+                .await
+                .is_err(),
+            "No other changes to the first coin"
+        );
+    }
+
+    pub async fn test_subscription_limits_impl() {
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = serve_executor(
+            connection_config.clone(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(Simulacrum::new()),
+            None,
+        )
+        .await;
+
+        cluster
+            .wait_for_checkpoint_catchup(0, Duration::from_secs(10))
+            .await;
+
+        let service_config = ServiceConfig {
+            subscriptions: SubscriptionConfig {
+                max_subscriptions: 3,
+                max_subscriptions_per_connection: 2,
+            },
+            ..Default::default()
+        };
+
+        let (_watermark, rx) = watch::channel(Some(0));
+        let schema = subscription_schema(&connection_config, service_config, rx);
+        let query = "subscription { transactions { digest } }";
+
+        /// The error that the subscription was rejected with, if it was.
+        fn rejection(stream: &mut BoxStream<'static, Response>) -> Option<String> {
+            let response = stream.next().now_or_never()??;
+            Some(response.errors.first()?.message.clone())
+        }
+
+        let connect = || {
+            let mut data = Data::default();
+            data.insert(ConnectionSubscriptions::default());
+            Arc::new(data)
+        };
+
+        // Each connection is capped on its own.
+        let conn_a = connect();
+        let mut a1 = schema
+            .execute_stream_with_session_data(query, conn_a.clone())
+            .boxed();
+        let mut a2 = schema
+            .execute_stream_with_session_data(query, conn_a.clone())
+            .boxed();
+        let mut a3 = schema
+            .execute_stream_with_session_data(query, conn_a.clone())
+            .boxed();
+        assert_eq!(rejection(&mut a1), None);
+        assert_eq!(rejection(&mut a2), None);
+        let error = rejection(&mut a3).expect("Connection over its subscription limit");
+        assert!(
+            error.contains("Too many concurrent subscriptions"),
+            "{error}"
+        );
+
+        // The service is capped across connections.
+        let conn_b = connect();
+        let mut b1 = schema
+            .execute_stream_with_session_data(query, conn_b.clone())
+            .boxed();
+        let mut b2 = schema
+            .execute_stream_with_session_data(query, conn_b.clone())
+            .boxed();
+        assert_eq!(rejection(&mut b1), None);
+        let error = rejection(&mut b2).expect("Service over its subscription limit");
+        assert!(
+            error.contains("Too many concurrent subscriptions"),
+            "{error}"
+        );
+
+        // Closing a subscription makes room for another.
+        drop(a1);
+        let mut b3 = schema
+            .execute_stream_with_session_data(query, conn_b)
+            .boxed();
+        assert_eq!(rejection(&mut b3), None);
+    }
+
+    #[tokio::test]
+    async fn test_client_budgets_over_http() {
+        use std::collections::BTreeMap;
+        use tower::ServiceExt;
+
+        let budgets = BudgetConfig {
+            require_api_key: true,
+            clients: BTreeMap::from([(
+                "partner".to_string(),
+                ClientBudget {
+                    api_key: "secret".to_string(),
+                    capacity: 2,
+                    refill_per_second: 0,
+                },
+            )]),
+        };
+
+        // Budgets are enforced without the `QueryLimitsChecker` being installed.
+        let schema = schema_builder()
+            .data(ServiceConfig {
+                budgets: budgets.clone(),
+                ..Default::default()
+            })
+            .extension(ClientBudgets::new(&budgets))
+            .finish();
+
+        let router = Router::new()
+            .route("/", post(graphql_handler))
+            .layer(axum::Extension(schema));
+
+        // Send a query that costs two output nodes, and return the error it failed with, if any.
+        let request = |api_key: Option<&str>| {
+            let router = router.clone();
+            let mut request = Request::builder()
+                .method(Method::POST)
+                .uri("/")
+                .header(hyper::header::CONTENT_TYPE, "application/json");
+            if let Some(api_key) = api_key {
+                request = request.header(API_KEY_HEADER.clone(), api_key);
+            }
+
+            let mut request = request
+                .body(Body::from(
+                    r#"{"query": "{ serviceConfig { maxQueryDepth } }"}"#,
+                ))
+                .unwrap();
+            request.extensions_mut().insert(ConnectInfo(ip_address()));
+
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+
+                let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                body["errors"][0]["message"].as_str().map(str::to_string)
+            }
+        };
+
+        assert_eq!(
+            request(None).await.as_deref(),
+            Some("Requests to this service must identify their client with an API key"),
+        );
+
+        assert_eq!(
+            request(Some("wrong")).await.as_deref(),
+            Some("Unrecognized API key"),
+        );
+
+        // The first request uses up the client's whole budget, which never refills.
+        assert_eq!(request(Some("secret")).await, None);
+        let error = request(Some("secret")).await.unwrap();
+        assert!(error.starts_with("Client's budget is exhausted"), "{error}");
+    }
 }
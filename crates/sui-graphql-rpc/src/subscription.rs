@@ -0,0 +1,370 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_graphql::connection::{Connection, CursorType};
+use async_graphql::*;
+use futures::{stream, Stream, StreamExt};
+use sui_types::base_types::ObjectID;
+use sui_types::effects::TransactionEffectsAPI;
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+
+use crate::config::ServiceConfig;
+use crate::data::{Db, QueryExecutor};
+use crate::error::Error;
+use crate::types::checkpoint::Checkpoint;
+use crate::types::cursor::Page;
+use crate::types::event::{self, Event, EventFilter};
+use crate::types::object_change::ObjectChange;
+use crate::types::transaction_block::{self, TransactionBlock, TransactionBlockFilter};
+use crate::types::transaction_block_effects::TransactionBlockEffects;
+
+pub(crate) struct Subscription;
+
+/// The latest checkpoint that the Indexer has written to the database, shared between all
+/// subscriptions so that the database is polled once per interval, rather than once per
+/// subscription. It is `None` until the first poll completes.
+#[derive(Clone)]
+pub(crate) struct CheckpointWatermark(watch::Receiver<Option<u64>>);
+
+/// The number of subscriptions open across all connections to the service.
+#[derive(Clone, Default)]
+pub(crate) struct ServiceSubscriptions(Arc<AtomicUsize>);
+
+/// The number of subscriptions open on a single websocket connection.
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionSubscriptions(Arc<AtomicUsize>);
+
+/// Counts a subscription as open, until it is dropped.
+struct SubscriptionGuard(Arc<AtomicUsize>);
+
+/// Subscriptions stream data as it is indexed, starting from the first checkpoint after the
+/// subscription was made.
+#[Subscription]
+impl Subscription {
+    /// Events emitted by transactions as they are indexed, optionally `filter`-ed.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        let db: Db = ctx.data_unchecked::<Db>().clone();
+        let config: ServiceConfig = ctx.data_unchecked::<ServiceConfig>().clone();
+        let filter = filter.unwrap_or_default();
+
+        checkpoint_stream(ctx, move |after, checkpoint_viewed_at, cursor| {
+            let (db, config, filter) = (db.clone(), config.clone(), filter.clone());
+            async move { events_page(&db, &config, filter, after, checkpoint_viewed_at, cursor).await }
+        })
+    }
+
+    /// Transaction blocks as they are indexed, optionally `filter`-ed.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>>> {
+        let db: Db = ctx.data_unchecked::<Db>().clone();
+        let config: ServiceConfig = ctx.data_unchecked::<ServiceConfig>().clone();
+        let filter = filter.unwrap_or_default();
+
+        checkpoint_stream(ctx, move |after, checkpoint_viewed_at, cursor| {
+            let (db, config, filter) = (db.clone(), config.clone(), filter.clone());
+            async move {
+                transactions_page(&db, &config, filter, after, checkpoint_viewed_at, cursor).await
+            }
+        })
+    }
+
+    /// Changes to objects made by transaction blocks as they are indexed. Only changes made by
+    /// transaction blocks that match `filter` are included, and if the filter selects for a
+    /// `changedObject`, then only changes to that object are included.
+    async fn object_changes(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<ObjectChange>>> {
+        let db: Db = ctx.data_unchecked::<Db>().clone();
+        let config: ServiceConfig = ctx.data_unchecked::<ServiceConfig>().clone();
+        let filter = filter.unwrap_or_default();
+
+        checkpoint_stream(ctx, move |after, checkpoint_viewed_at, cursor| {
+            let (db, config, filter) = (db.clone(), config.clone(), filter.clone());
+            async move {
+                let changed_object = filter.changed_object;
+                let (transactions, cursor) =
+                    transactions_page(&db, &config, filter, after, checkpoint_viewed_at, cursor)
+                        .await?;
+
+                let mut changes = vec![];
+                for transaction in transactions {
+                    let effects = TransactionBlockEffects::try_from(transaction).extend()?;
+                    changes.extend(
+                        effects
+                            .native()
+                            .object_changes()
+                            .into_iter()
+                            .filter(|c| {
+                                changed_object.map_or(true, |id| c.id == ObjectID::from(id))
+                            })
+                            .map(|native| ObjectChange {
+                                native,
+                                checkpoint_viewed_at,
+                            }),
+                    );
+                }
+
+                Ok((changes, cursor))
+            }
+        })
+    }
+}
+
+impl CheckpointWatermark {
+    /// Start a task that polls the database every `interval` for the latest checkpoint that has
+    /// been indexed.
+    pub(crate) fn spawn(db: Db, interval: Duration) -> Self {
+        let (tx, rx) = watch::channel(None);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            while !tx.is_closed() {
+                interval.tick().await;
+                match db.execute(Checkpoint::available_range).await {
+                    Ok((_, latest)) => {
+                        tx.send_if_modified(|current| {
+                            let advanced = current.map_or(true, |c| c < latest);
+                            if advanced {
+                                *current = Some(latest);
+                            }
+                            advanced
+                        });
+                    }
+                    Err(e) => warn!("Failed to fetch latest checkpoint for subscriptions: {e}"),
+                }
+            }
+        });
+
+        Self(rx)
+    }
+
+    /// A watermark that follows the checkpoints sent on `rx`, instead of polling the database.
+    pub(crate) fn from_receiver(rx: watch::Receiver<Option<u64>>) -> Self {
+        Self(rx)
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Count one more subscription against `open`, unless there are already `max` subscriptions
+/// open.
+fn open_subscription(open: &Arc<AtomicUsize>, max: usize) -> Result<SubscriptionGuard> {
+    open.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+        (n < max).then_some(n + 1)
+    })
+    .map_err(|_| {
+        Error::Client(format!("Too many concurrent subscriptions, max is {max}")).extend()
+    })?;
+
+    Ok(SubscriptionGuard(open.clone()))
+}
+
+/// The progress of a subscription through the checkpoints that the Indexer has written.
+struct StreamState<F, C> {
+    rx: watch::Receiver<Option<u64>>,
+    /// The last checkpoint whose results have been streamed in full, or `None` if the first
+    /// checkpoint has not been seen yet.
+    after: Option<u64>,
+    /// The checkpoint that the current range is being viewed at, and the cursor to continue
+    /// reading it from, if there are more pages left in the range.
+    pending: Option<(u64, C)>,
+    fetch: F,
+    /// Keep the subscription counted as open for as long as it is being streamed.
+    _guards: Vec<SubscriptionGuard>,
+}
+
+/// Stream the results of `fetch` for every new range of checkpoints that the Indexer writes.
+/// `fetch` is called with the last checkpoint whose results have already been streamed (exclusive
+/// lower bound), the latest checkpoint (inclusive upper bound), which the results should also be
+/// viewed at, and a cursor to continue from within that range. It returns a single page of
+/// results, and the cursor for the next page, if there is one.
+///
+/// Only one page is fetched per poll of the stream, so a subscriber that falls behind (or a
+/// range that holds many results) is caught up a page at a time, without buffering the whole
+/// range. The next range is only read once every page of the current one has been streamed.
+///
+/// The stream ends after the first error, so that subscribers do not silently miss results from
+/// the checkpoints that could not be read.
+///
+/// The subscription is refused if the service, or the connection it was made on, already has as
+/// many subscriptions open as its `SubscriptionConfig` allows.
+fn checkpoint_stream<T, C, F, Fut>(
+    ctx: &Context<'_>,
+    fetch: F,
+) -> Result<impl Stream<Item = Result<T>>>
+where
+    T: Send + 'static,
+    C: Send + 'static,
+    F: FnMut(u64, u64, Option<C>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(Vec<T>, Option<C>)>> + Send,
+{
+    let CheckpointWatermark(mut rx) = ctx
+        .data_opt::<CheckpointWatermark>()
+        .cloned()
+        .ok_or_else(|| Error::Internal("Subscriptions are not being served".to_string()))
+        .extend()?;
+
+    let limits = &ctx.data_unchecked::<ServiceConfig>().subscriptions;
+    let mut guards = vec![];
+    if let Some(ServiceSubscriptions(open)) = ctx.data_opt::<ServiceSubscriptions>() {
+        guards.push(open_subscription(open, limits.max_subscriptions)?);
+    }
+
+    if let Some(ConnectionSubscriptions(open)) = ctx.data_opt::<ConnectionSubscriptions>() {
+        guards.push(open_subscription(
+            open,
+            limits.max_subscriptions_per_connection,
+        )?);
+    }
+
+    let after = *rx.borrow_and_update();
+    let state = StreamState {
+        rx,
+        after,
+        pending: None,
+        fetch,
+        _guards: guards,
+    };
+
+    let stream = stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        let (after, latest, cursor) = match (state.after, state.pending.take()) {
+            (Some(after), Some((latest, cursor))) => (after, latest, Some(cursor)),
+            (after, _) => {
+                state.rx.changed().await.ok()?;
+                let latest = (*state.rx.borrow_and_update())?;
+
+                // The subscription was made before the first checkpoint was known, so it starts
+                // from the first checkpoint seen.
+                let Some(after) = after else {
+                    state.after = Some(latest);
+                    return Some((vec![], Some(state)));
+                };
+
+                (after, latest, None)
+            }
+        };
+
+        match (state.fetch)(after, latest, cursor).await {
+            Ok((items, next)) => {
+                match next {
+                    Some(cursor) => state.pending = Some((latest, cursor)),
+                    None => state.after = Some(latest),
+                }
+
+                Some((items.into_iter().map(Ok).collect(), Some(state)))
+            }
+            Err(e) => Some((vec![Err(e)], None)),
+        }
+    });
+
+    Ok(stream.flat_map(stream::iter))
+}
+
+/// Fetch a page of events matching `filter` from checkpoints in the range
+/// `(after, checkpoint_viewed_at]`, starting from `cursor`. Pages are as large as the service's
+/// limits allow. Returns the events and the cursor for the next page, if there is one.
+async fn events_page(
+    db: &Db,
+    config: &ServiceConfig,
+    filter: EventFilter,
+    after: u64,
+    checkpoint_viewed_at: u64,
+    cursor: Option<event::Cursor>,
+) -> Result<(Vec<Event>, Option<event::Cursor>)> {
+    let page = Page::from_params(
+        config,
+        Some(config.limits.max_page_size),
+        cursor,
+        None,
+        None,
+    )?;
+
+    let conn =
+        Event::paginate_after_checkpoint(db, page, filter, Some(after), Some(checkpoint_viewed_at))
+            .await
+            .extend()?;
+
+    let cursor = next_cursor(&conn)?;
+    Ok((
+        conn.edges.into_iter().map(|edge| edge.node).collect(),
+        cursor,
+    ))
+}
+
+/// Fetch a page of transaction blocks matching `filter` from checkpoints in the range
+/// `(after, checkpoint_viewed_at]`, starting from `cursor`. Pages are as large as the service's
+/// limits allow. Returns the transaction blocks and the cursor for the next page, if there is
+/// one.
+async fn transactions_page(
+    db: &Db,
+    config: &ServiceConfig,
+    filter: TransactionBlockFilter,
+    after: u64,
+    checkpoint_viewed_at: u64,
+    cursor: Option<transaction_block::Cursor>,
+) -> Result<(Vec<TransactionBlock>, Option<transaction_block::Cursor>)> {
+    let range = TransactionBlockFilter {
+        after_checkpoint: Some(after),
+        ..Default::default()
+    };
+
+    let Some(filter) = filter.intersect(range) else {
+        return Ok((vec![], None));
+    };
+
+    let page = Page::from_params(
+        config,
+        Some(config.limits.max_page_size),
+        cursor,
+        None,
+        None,
+    )?;
+
+    let conn = TransactionBlock::paginate(db, page, filter, Some(checkpoint_viewed_at))
+        .await
+        .extend()?;
+
+    let cursor = next_cursor(&conn)?;
+    Ok((
+        conn.edges.into_iter().map(|edge| edge.node).collect(),
+        cursor,
+    ))
+}
+
+/// The cursor to fetch the page after `conn` from, if there is one.
+fn next_cursor<C: CursorType, T>(conn: &Connection<String, T>) -> Result<Option<C>> {
+    if !conn.has_next_page {
+        return Ok(None);
+    }
+
+    let Some(edge) = conn.edges.last() else {
+        return Ok(None);
+    };
+
+    C::decode_cursor(&edge.cursor)
+        .map(Some)
+        .map_err(|e| Error::Internal(format!("Failed to decode cursor: {e}")).extend())
+}
@@ -57,15 +57,25 @@ impl Address {
 
     /// Total balance of all coins with marker type owned by this address. If type is not supplied,
     /// it defaults to `0x2::sui::SUI`.
+    ///
+    /// If `checkpoint` is supplied, this is the balance at the end of that checkpoint, which can be
+    /// as early as the checkpoint before the first one whose balance changes were indexed, and no
+    /// later than the latest checkpoint in the available range. Balances at a checkpoint do not
+    /// include a coin object count.
     pub(crate) async fn balance(
         &self,
         ctx: &Context<'_>,
         type_: Option<ExactTypeFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Balance>> {
-        OwnerImpl::from(self).balance(ctx, type_).await
+        OwnerImpl::from(self).balance(ctx, type_, checkpoint).await
     }
 
     /// The balances of all coin types owned by this address.
+    ///
+    /// If `checkpoint` is supplied, these are the balances at the end of that checkpoint, which can
+    /// be as early as the checkpoint before the first one whose balance changes were indexed, and
+    /// no later than the latest checkpoint in the available range.
     pub(crate) async fn balances(
         &self,
         ctx: &Context<'_>,
@@ -73,9 +83,10 @@ impl Address {
         after: Option<balance::Cursor>,
         last: Option<u64>,
         before: Option<balance::Cursor>,
+        checkpoint: Option<u64>,
     ) -> Result<Connection<String, Balance>> {
         OwnerImpl::from(self)
-            .balances(ctx, first, after, last, before)
+            .balances(ctx, first, after, last, before, checkpoint)
             .await
     }
 
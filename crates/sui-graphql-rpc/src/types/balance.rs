@@ -4,7 +4,7 @@
 use super::cursor::{self, Page, RawPaginated, Target};
 use super::{big_int::BigInt, move_type::MoveType, sui_address::SuiAddress};
 use crate::consistency::{consistent_range, Checkpointed};
-use crate::data::{Db, DbConnection, QueryExecutor};
+use crate::data::{Conn, Db, DbConnection, QueryExecutor};
 use crate::error::Error;
 use crate::raw_query::RawQuery;
 use crate::{filter, query};
@@ -12,11 +12,14 @@ use async_graphql::connection::{Connection, CursorType, Edge};
 use async_graphql::*;
 use diesel::{
     sql_types::{BigInt as SqlBigInt, Nullable, Text},
-    OptionalExtension, QueryableByName,
+    ExpressionMethods, OptionalExtension, QueryDsl, QueryableByName,
 };
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
-use sui_indexer::types::OwnerType;
+use sui_indexer::{
+    schema::{balance_history_start, checkpoints},
+    types::OwnerType,
+};
 use sui_types::{parse_sui_type_tag, TypeTag};
 
 /// The total balance for a particular coin type.
@@ -59,35 +62,45 @@ impl Balance {
     /// Query for the balance of coins owned by `address`, of coins with type `coin_type`. Note that
     /// `coin_type` is the type of `0x2::coin::Coin`'s type parameter, not the full type of the coin
     /// object.
+    ///
+    /// If `checkpoint` is provided, the balance is read as of the end of that checkpoint, which
+    /// must be one whose balance is available (see `balance_query_at`). Historical balances do not
+    /// include a coin object count.
     pub(crate) async fn query(
         db: &Db,
         address: SuiAddress,
         coin_type: TypeTag,
+        checkpoint: Option<u64>,
         checkpoint_viewed_at: Option<u64>,
     ) -> Result<Option<Balance>, Error> {
-        let stored: Option<StoredBalance> = db
+        let stored: Result<Option<StoredBalance>, Error> = db
             .execute_repeatable(move |conn| {
-                let Some((lhs, rhs)) = consistent_range(conn, checkpoint_viewed_at)? else {
-                    return Ok::<_, diesel::result::Error>(None);
+                let Some(range) = consistent_range(conn, checkpoint_viewed_at)? else {
+                    return Ok::<_, diesel::result::Error>(Ok(None));
                 };
 
-                conn.result(move || {
-                    balance_query(address, Some(coin_type.clone()), lhs as i64, rhs as i64)
-                        .into_boxed()
-                })
-                .optional()
+                let query =
+                    match balance_query_at(conn, address, Some(coin_type), range, checkpoint)? {
+                        Ok(query) => query,
+                        Err(e) => return Ok(Err(e)),
+                    };
+
+                Ok(Ok(conn
+                    .result(move || query.clone().into_boxed())
+                    .optional()?))
             })
             .await?;
 
-        stored.map(Balance::try_from).transpose()
+        stored?.map(Balance::try_from).transpose()
     }
 
     /// Query the database for a `page` of coin balances. Each balance represents the total balance
-    /// for a particular coin type, owned by `address`.
+    /// for a particular coin type, owned by `address`, optionally as of the end of `checkpoint`.
     pub(crate) async fn paginate(
         db: &Db,
         page: Page<Cursor>,
         address: SuiAddress,
+        checkpoint: Option<u64>,
         checkpoint_viewed_at: Option<u64>,
     ) -> Result<Connection<String, Balance>, Error> {
         // If cursors are provided, defer to the `checkpoint_viewed_at` in the cursor if they are
@@ -98,21 +111,22 @@ impl Balance {
 
         let response = db
             .execute_repeatable(move |conn| {
-                let Some((lhs, rhs)) = consistent_range(conn, checkpoint_viewed_at)? else {
-                    return Ok::<_, diesel::result::Error>(None);
+                let Some(range @ (_, rhs)) = consistent_range(conn, checkpoint_viewed_at)? else {
+                    return Ok::<_, diesel::result::Error>(Ok(None));
                 };
 
-                let result = page.paginate_raw_query::<StoredBalance>(
-                    conn,
-                    rhs,
-                    balance_query(address, None, lhs as i64, rhs as i64),
-                )?;
+                let query = match balance_query_at(conn, address, None, range, checkpoint)? {
+                    Ok(query) => query,
+                    Err(e) => return Ok(Err(e)),
+                };
 
-                Ok(Some((result, rhs)))
+                let result = page.paginate_raw_query::<StoredBalance>(conn, rhs, query)?;
+
+                Ok(Ok(Some((result, rhs))))
             })
             .await?;
 
-        let Some(((prev, next, results), checkpoint_viewed_at)) = response else {
+        let Some(((prev, next, results), checkpoint_viewed_at)) = response? else {
             return Err(Error::Client(
                 "Requested data is outside the available range".to_string(),
             ));
@@ -191,6 +205,130 @@ impl TryFrom<StoredBalance> for Balance {
     }
 }
 
+/// Query for the balances owned by `address` within the consistent range `(lhs, rhs)`: the latest
+/// balances in the range if `checkpoint` is `None`, or the balances at the end of `checkpoint`
+/// otherwise. Fails with a client error if the balances at `checkpoint` are not available: if it
+/// is after `rhs`, or before the checkpoint preceding the first one whose balance changes the
+/// indexer has recorded.
+fn balance_query_at(
+    conn: &mut Conn,
+    address: SuiAddress,
+    coin_type: Option<TypeTag>,
+    (lhs, rhs): (u64, u64),
+    checkpoint: Option<u64>,
+) -> Result<Result<RawQuery, Error>, diesel::result::Error> {
+    let Some(checkpoint) = checkpoint else {
+        return Ok(Ok(balance_query(
+            address, coin_type, lhs as i64, rhs as i64,
+        )));
+    };
+
+    if checkpoint > rhs {
+        return Ok(Err(Error::Client(format!(
+            "Balances at checkpoint {checkpoint} are not available: the latest checkpoint is {rhs}"
+        ))));
+    }
+
+    // Balance changes are recorded from the first checkpoint the indexer processed, or if the
+    // indexer was already running when balance history was introduced, from the first checkpoint
+    // it processed after that. Balances are available from the end of the checkpoint before it.
+    let first_indexed: Option<i64> = conn
+        .first(move || {
+            checkpoints::dsl::checkpoints
+                .select(checkpoints::dsl::sequence_number)
+                .order(checkpoints::dsl::sequence_number.asc())
+        })
+        .optional()?;
+
+    let history_start: Option<i64> = conn
+        .first(move || {
+            balance_history_start::dsl::balance_history_start
+                .select(balance_history_start::dsl::checkpoint_sequence_number)
+        })
+        .optional()?;
+
+    let first_recorded = first_indexed.max(history_start).unwrap_or(0) as u64;
+    let first_available = first_recorded.saturating_sub(1);
+    if checkpoint < first_available {
+        return Ok(Err(Error::Client(format!(
+            "Balances at checkpoint {checkpoint} are not available: balances are only available \
+             from checkpoint {first_available}"
+        ))));
+    }
+
+    Ok(Ok(balance_history_query(
+        address,
+        coin_type,
+        (lhs, rhs),
+        checkpoint,
+    )))
+}
+
+/// Query for the balances owned by `address` at the end of `checkpoint`, by undoing the indexer's
+/// per-checkpoint balance changes after `checkpoint`, up to and including `rhs`, from the latest
+/// balances in the consistent range `(lhs, rhs)`. This does not depend on the indexer having
+/// recorded balance changes since genesis, only since `checkpoint`. Coin types whose balance is
+/// zero at that point are omitted, as they would be from the latest balances.
+fn balance_history_query(
+    address: SuiAddress,
+    coin_type: Option<TypeTag>,
+    (lhs, rhs): (u64, u64),
+    checkpoint: u64,
+) -> RawQuery {
+    let latest = balance_query(address, coin_type.clone(), lhs as i64, rhs as i64);
+
+    let mut changes = query!("SELECT * FROM balance_history");
+    changes = filter!(
+        changes,
+        format!("owner_id = '\\x{}'::bytea", hex::encode(address.into_vec()))
+    );
+    changes = filter!(
+        changes,
+        format!(
+            "checkpoint_sequence_number > {} AND checkpoint_sequence_number <= {}",
+            checkpoint, rhs
+        )
+    );
+
+    if let Some(coin_type) = coin_type {
+        changes = filter!(
+            changes,
+            "coin_type = {}",
+            coin_type.to_canonical_display(/* with_prefix */ true)
+        );
+    }
+
+    // Amounts are stored as text because they may not fit in a bigint.
+    let later = query!(
+        r#"SELECT
+            coin_type,
+            SUM(CAST(amount AS NUMERIC)) as amount
+        FROM ({}) changes"#,
+        changes
+    )
+    .group_by("coin_type");
+
+    let balances = query!(
+        r#"SELECT
+            CAST(
+                COALESCE(CAST(latest.balance AS NUMERIC), 0) - COALESCE(later.amount, 0)
+                AS TEXT
+            ) as balance,
+            CAST(NULL AS BIGINT) as count,
+            COALESCE(latest.coin_type, later.coin_type) as coin_type
+        FROM ({}) latest
+        FULL OUTER JOIN ({}) later
+        ON latest.coin_type = later.coin_type"#,
+        latest,
+        later
+    );
+
+    filter!(
+        query!("SELECT * FROM ({}) balances", balances),
+        "balance <> '0'"
+    )
+}
+
 /// Query the database for a `page` of coin balances. Each balance represents the total balance for
 /// a particular coin type, owned by `address`. This function is meant to be called within a thunk
 /// and returns a RawQuery that can be converted into a BoxedSqlQuery with `.into_boxed()`.
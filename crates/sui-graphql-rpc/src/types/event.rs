@@ -157,6 +157,18 @@ impl Event {
         page: Page<Cursor>,
         filter: EventFilter,
         checkpoint_viewed_at: Option<u64>,
+    ) -> Result<Connection<String, Event>, Error> {
+        Self::paginate_after_checkpoint(db, page, filter, None, checkpoint_viewed_at).await
+    }
+
+    /// Like `paginate`, but additionally only includes events emitted in checkpoints strictly
+    /// after `after_checkpoint`, if it is provided.
+    pub(crate) async fn paginate_after_checkpoint(
+        db: &Db,
+        page: Page<Cursor>,
+        filter: EventFilter,
+        after_checkpoint: Option<u64>,
+        checkpoint_viewed_at: Option<u64>,
     ) -> Result<Connection<String, Event>, Error> {
         let cursor_viewed_at = page.validate_cursor_consistency()?;
         let checkpoint_viewed_at: Option<u64> = cursor_viewed_at.or(checkpoint_viewed_at);
@@ -181,6 +193,11 @@ impl Event {
                             events::dsl::checkpoint_sequence_number.le(checkpoint_viewed_at as i64),
                         );
 
+                        if let Some(after) = after_checkpoint {
+                            query = query
+                                .filter(events::dsl::checkpoint_sequence_number.gt(after as i64));
+                        }
+
                         // The transactions table doesn't have an index on the senders column, so use
                         // `tx_senders`.
                         if let Some(sender) = &filter.sender {
@@ -275,6 +292,7 @@ impl Event {
                 .to_canonical_string(/* with_prefix */ true),
             bcs: native_event.contents.clone(),
             timestamp_ms: stored_tx.timestamp_ms,
+            parsed_json: None,
         };
 
         Ok(Self {
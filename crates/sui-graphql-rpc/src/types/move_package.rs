@@ -101,18 +101,30 @@ impl MovePackage {
     ///
     /// Note that coins owned by a package are inaccessible, because packages are immutable and
     /// cannot be owned by an address.
+    ///
+    /// If `checkpoint` is supplied, this is the balance at the end of that checkpoint, which can be
+    /// as early as the checkpoint before the first one whose balance changes were indexed, and no
+    /// later than the latest checkpoint in the available range. Balances at a checkpoint do not
+    /// include a coin object count.
     pub(crate) async fn balance(
         &self,
         ctx: &Context<'_>,
         type_: Option<ExactTypeFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Balance>> {
-        OwnerImpl::from(&self.super_).balance(ctx, type_).await
+        OwnerImpl::from(&self.super_)
+            .balance(ctx, type_, checkpoint)
+            .await
     }
 
     /// The balances of all coin types owned by this package.
     ///
     /// Note that coins owned by a package are inaccessible, because packages are immutable and
     /// cannot be owned by an address.
+    ///
+    /// If `checkpoint` is supplied, these are the balances at the end of that checkpoint, which can
+    /// be as early as the checkpoint before the first one whose balance changes were indexed, and
+    /// no later than the latest checkpoint in the available range.
     pub(crate) async fn balances(
         &self,
         ctx: &Context<'_>,
@@ -120,9 +132,10 @@ impl MovePackage {
         after: Option<balance::Cursor>,
         last: Option<u64>,
         before: Option<balance::Cursor>,
+        checkpoint: Option<u64>,
     ) -> Result<Connection<String, Balance>> {
         OwnerImpl::from(&self.super_)
-            .balances(ctx, first, after, last, before)
+            .balances(ctx, first, after, last, before, checkpoint)
             .await
     }
 
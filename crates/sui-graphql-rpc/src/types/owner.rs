@@ -62,9 +62,14 @@ pub(crate) struct OwnerImpl {
     field(
         name = "balance",
         arg(name = "type", ty = "Option<ExactTypeFilter>"),
+        arg(name = "checkpoint", ty = "Option<u64>"),
         ty = "Option<Balance>",
         desc = "Total balance of all coins with marker type owned by this object or address. If \
-                type is not supplied, it defaults to `0x2::sui::SUI`."
+                type is not supplied, it defaults to `0x2::sui::SUI`.\n\n\
+                If `checkpoint` is supplied, this is the balance at the end of that checkpoint, \
+                which can be as early as the checkpoint before the first one whose balance \
+                changes were indexed, and no later than the latest checkpoint in the available \
+                range. Balances at a checkpoint do not include a coin object count."
     ),
     field(
         name = "balances",
@@ -72,8 +77,13 @@ pub(crate) struct OwnerImpl {
         arg(name = "after", ty = "Option<balance::Cursor>"),
         arg(name = "last", ty = "Option<u64>"),
         arg(name = "before", ty = "Option<balance::Cursor>"),
+        arg(name = "checkpoint", ty = "Option<u64>"),
         ty = "Connection<String, Balance>",
-        desc = "The balances of all coin types owned by this object or address."
+        desc = "The balances of all coin types owned by this object or address.\n\n\
+                If `checkpoint` is supplied, these are the balances at the end of that \
+                checkpoint, which can be as early as the checkpoint before the first one whose \
+                balance changes were indexed, and no later than the latest checkpoint in the \
+                available range."
     ),
     field(
         name = "coins",
@@ -150,15 +160,25 @@ impl Owner {
 
     /// Total balance of all coins with marker type owned by this object or address. If type is not
     /// supplied, it defaults to `0x2::sui::SUI`.
+    ///
+    /// If `checkpoint` is supplied, this is the balance at the end of that checkpoint, which can be
+    /// as early as the checkpoint before the first one whose balance changes were indexed, and no
+    /// later than the latest checkpoint in the available range. Balances at a checkpoint do not
+    /// include a coin object count.
     pub(crate) async fn balance(
         &self,
         ctx: &Context<'_>,
         type_: Option<ExactTypeFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Balance>> {
-        OwnerImpl::from(self).balance(ctx, type_).await
+        OwnerImpl::from(self).balance(ctx, type_, checkpoint).await
     }
 
     /// The balances of all coin types owned by this object or address.
+    ///
+    /// If `checkpoint` is supplied, these are the balances at the end of that checkpoint, which can
+    /// be as early as the checkpoint before the first one whose balance changes were indexed, and
+    /// no later than the latest checkpoint in the available range.
     pub(crate) async fn balances(
         &self,
         ctx: &Context<'_>,
@@ -166,9 +186,10 @@ impl Owner {
         after: Option<balance::Cursor>,
         last: Option<u64>,
         before: Option<balance::Cursor>,
+        checkpoint: Option<u64>,
     ) -> Result<Connection<String, Balance>> {
         OwnerImpl::from(self)
-            .balances(ctx, first, after, last, before)
+            .balances(ctx, first, after, last, before, checkpoint)
             .await
     }
 
@@ -331,12 +352,14 @@ impl OwnerImpl {
         &self,
         ctx: &Context<'_>,
         type_: Option<ExactTypeFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Balance>> {
         let coin = type_.map_or_else(GAS::type_tag, |t| t.0);
         Balance::query(
             ctx.data_unchecked(),
             self.address,
             coin,
+            checkpoint,
             self.checkpoint_viewed_at,
         )
         .await
@@ -350,12 +373,14 @@ impl OwnerImpl {
         after: Option<balance::Cursor>,
         last: Option<u64>,
         before: Option<balance::Cursor>,
+        checkpoint: Option<u64>,
     ) -> Result<Connection<String, Balance>> {
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
         Balance::paginate(
             ctx.data_unchecked(),
             page,
             self.address,
+            checkpoint,
             self.checkpoint_viewed_at,
         )
         .await
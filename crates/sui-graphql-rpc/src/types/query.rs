@@ -38,11 +38,11 @@ use super::{
 };
 use crate::{
     config::ServiceConfig, context_data::db_data_provider::PgManager, data::Db, error::Error,
-    mutation::Mutation,
+    mutation::Mutation, subscription::Subscription,
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[Object]
 impl Query {
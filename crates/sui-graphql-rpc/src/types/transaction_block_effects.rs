@@ -413,7 +413,7 @@ impl TransactionBlockEffects {
 }
 
 impl TransactionBlockEffects {
-    fn native(&self) -> &NativeTransactionEffects {
+    pub(crate) fn native(&self) -> &NativeTransactionEffects {
         match &self.kind {
             TransactionBlockEffectsKind::Stored { native, .. } => native,
             TransactionBlockEffectsKind::Executed { native, .. } => native,
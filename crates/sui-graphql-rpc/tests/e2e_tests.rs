@@ -15,6 +15,7 @@ mod tests {
     use sui_graphql_rpc::client::ClientError;
     use sui_graphql_rpc::config::ConnectionConfig;
     use sui_graphql_rpc::test_infra::cluster::DEFAULT_INTERNAL_DATA_SOURCE_PORT;
+    use sui_types::base_types::SuiAddress;
     use sui_types::digests::ChainIdentifier;
     use sui_types::gas_coin::GAS;
     use sui_types::transaction::CallArg;
@@ -716,6 +717,145 @@ mod tests {
             .is_null());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_historical_balance() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        // The recipient receives SUI in checkpoints 1 and 2, and nothing in checkpoint 3.
+        let recipient = SuiAddress::random_for_testing_only();
+        let mut amounts = vec![];
+        for _ in 0..2 {
+            let (transaction, amount) = sim.transfer_txn(recipient);
+            let (_, err) = sim.execute_transaction(transaction).unwrap();
+            assert!(err.is_none());
+            sim.create_checkpoint();
+            amounts.push(amount);
+        }
+        sim.create_checkpoint();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let db_url = connection_config.db_url();
+        let cluster = sui_graphql_rpc::test_infra::cluster::serve_executor(
+            connection_config,
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(3, Duration::from_secs(10))
+            .await;
+
+        let query = format!(
+            r#"{{
+                address(address: "{recipient}") {{
+                    at0: balance(checkpoint: 0) {{ totalBalance }}
+                    at1: balance(checkpoint: 1) {{ totalBalance }}
+                    at2: balance(checkpoint: 2) {{ totalBalance }}
+                    at3: balance(checkpoint: 3) {{ totalBalance }}
+                    latest: balance {{ totalBalance }}
+                }}
+            }}"#
+        );
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query, true, vec![], vec![])
+            .await
+            .unwrap();
+
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let data = res.response_body().data.clone().into_json().unwrap();
+        let balance_at = |alias: &str| {
+            data["address"][alias]["totalBalance"]
+                .as_str()
+                .map(|b| b.parse::<u64>().unwrap())
+        };
+
+        // Balances at a checkpoint reflect only the transfers up to and including it.
+        assert_eq!(balance_at("at0"), None);
+        assert_eq!(balance_at("at1"), Some(amounts[0]));
+        assert_eq!(balance_at("at2"), Some(amounts[0] + amounts[1]));
+        assert_eq!(balance_at("at3"), Some(amounts[0] + amounts[1]));
+        assert_eq!(balance_at("latest"), Some(amounts[0] + amounts[1]));
+
+        // Balances at checkpoints that have not been indexed yet are an explicit error.
+        let query = format!(
+            r#"{{
+                address(address: "{recipient}") {{
+                    balance(checkpoint: 100) {{ totalBalance }}
+                }}
+            }}"#
+        );
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query, true, vec![], vec![])
+            .await
+            .unwrap();
+
+        let errors = res.errors();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0]
+                .message
+                .contains("Balances at checkpoint 100 are not available"),
+            "{:?}",
+            errors
+        );
+
+        // An indexer that was already running when balance history was introduced only records
+        // balance changes from the next checkpoint it indexes, here checkpoint 3, so balances are
+        // only available from checkpoint 2.
+        use diesel::{Connection, PgConnection, RunQueryDsl};
+        let mut conn = PgConnection::establish(&db_url).unwrap();
+        diesel::sql_query("UPDATE balance_history_start SET checkpoint_sequence_number = 3")
+            .execute(&mut conn)
+            .unwrap();
+
+        let query = format!(
+            r#"{{
+                address(address: "{recipient}") {{
+                    balance(checkpoint: 2) {{ totalBalance }}
+                }}
+            }}"#
+        );
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query, true, vec![], vec![])
+            .await
+            .unwrap();
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let data = res.response_body().data.clone().into_json().unwrap();
+        assert_eq!(
+            data["address"]["balance"]["totalBalance"].as_str(),
+            Some((amounts[0] + amounts[1]).to_string().as_str())
+        );
+
+        let query = format!(
+            r#"{{
+                address(address: "{recipient}") {{
+                    balance(checkpoint: 1) {{ totalBalance }}
+                }}
+            }}"#
+        );
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query, true, vec![], vec![])
+            .await
+            .unwrap();
+
+        let errors = res.errors();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0]
+                .message
+                .contains("balances are only available from checkpoint 2"),
+            "{:?}",
+            errors
+        );
+    }
+
     use sui_graphql_rpc::server::builder::tests::*;
 
     #[tokio::test]
@@ -753,4 +893,28 @@ mod tests {
     async fn test_query_complexity_metrics() {
         test_query_complexity_metrics_impl().await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription_transactions() {
+        test_subscription_transactions_impl().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription_events() {
+        test_subscription_events_impl().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription_object_changes() {
+        test_subscription_object_changes_impl().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription_limits() {
+        test_subscription_limits_impl().await;
+    }
 }
@@ -0,0 +1,4 @@
+-- Event contents decoded to JSON with the event type's layout, the same value that is returned
+-- as `parsedJson` over RPC. Used to filter events by their fields. NULL for events indexed before
+-- this column was added, or whose layout could not be resolved.
+ALTER TABLE events ADD COLUMN parsed_json jsonb;
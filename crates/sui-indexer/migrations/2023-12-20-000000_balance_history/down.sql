@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS balance_history_start;
+DROP TABLE IF EXISTS balance_history;
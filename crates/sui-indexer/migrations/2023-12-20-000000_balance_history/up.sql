@@ -0,0 +1,26 @@
+-- Net change in each address's balance of each coin type, per checkpoint. Subtracting the changes
+-- after a checkpoint from the address's latest balance gives its balance at the end of that
+-- checkpoint, so balances are available from the checkpoint before the first one whose changes
+-- were recorded (see `balance_history_start`).
+CREATE TABLE balance_history
+(
+    -- SuiAddress in bytes.
+    owner_id                    bytea        NOT NULL,
+    -- Canonical coin type with a 0x prefix, in the same format as `objects.coin_type`.
+    coin_type                   text         NOT NULL,
+    checkpoint_sequence_number  bigint       NOT NULL,
+    -- Signed decimal string, as the change in a u64 balance does not always fit in a bigint.
+    amount                      text         NOT NULL,
+    PRIMARY KEY (owner_id, coin_type, checkpoint_sequence_number)
+);
+
+-- The first checkpoint whose balance changes are recorded in `balance_history`. An indexer that had
+-- already indexed checkpoints when this table was created only records the changes from the next
+-- checkpoint it indexes onwards, so balances before then cannot be derived.
+CREATE TABLE balance_history_start
+(
+    checkpoint_sequence_number  bigint       PRIMARY KEY
+);
+
+INSERT INTO balance_history_start (checkpoint_sequence_number)
+SELECT COALESCE(MAX(sequence_number) + 1, 0) FROM checkpoints;
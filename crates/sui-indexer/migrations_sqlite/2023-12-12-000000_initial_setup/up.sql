@@ -0,0 +1,213 @@
+-- Tables for the indexer's SQLite backend. They mirror the tables created by the Postgres
+-- migrations, with two differences:
+-- 1. SQLite has no array types, so columns that are arrays of bytes in Postgres are stored as
+--    BCS-serialized `Vec<Option<Vec<u8>>>` blobs.
+-- 2. Tables are not partitioned.
+
+CREATE TABLE checkpoints
+(
+    sequence_number                     BIGINT       PRIMARY KEY,
+    checkpoint_digest                   BLOB         NOT NULL,
+    epoch                               BIGINT       NOT NULL,
+    network_total_transactions          BIGINT       NOT NULL,
+    previous_checkpoint_digest          BLOB,
+    end_of_epoch                        BOOLEAN      NOT NULL,
+    -- bcs serialized array of TransactionDigest in bytes
+    tx_digests                          BLOB         NOT NULL,
+    timestamp_ms                        BIGINT       NOT NULL,
+    total_gas_cost                      BIGINT       NOT NULL,
+    computation_cost                    BIGINT       NOT NULL,
+    storage_cost                        BIGINT       NOT NULL,
+    storage_rebate                      BIGINT       NOT NULL,
+    non_refundable_storage_fee          BIGINT       NOT NULL,
+    checkpoint_commitments              BLOB         NOT NULL,
+    validator_signature                 BLOB         NOT NULL,
+    end_of_epoch_data                   BLOB
+);
+CREATE INDEX checkpoints_epoch ON checkpoints (epoch, sequence_number);
+CREATE INDEX checkpoints_digest ON checkpoints (checkpoint_digest);
+
+CREATE TABLE transactions (
+    tx_sequence_number          BIGINT       NOT NULL,
+    transaction_digest          BLOB         NOT NULL,
+    raw_transaction             BLOB         NOT NULL,
+    raw_effects                 BLOB         NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    -- bcs serialized array of bcs serialized IndexedObjectChange bytes
+    object_changes              BLOB         NOT NULL,
+    -- bcs serialized array of bcs serialized BalanceChange bytes
+    balance_changes             BLOB         NOT NULL,
+    -- bcs serialized array of bcs serialized StoredEvent bytes
+    events                      BLOB         NOT NULL,
+    transaction_kind            SMALLINT     NOT NULL,
+    success_command_count       SMALLINT     NOT NULL,
+    PRIMARY KEY (tx_sequence_number, checkpoint_sequence_number)
+);
+CREATE INDEX transactions_transaction_digest ON transactions (transaction_digest);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+CREATE INDEX transactions_transaction_kind ON transactions (transaction_kind) WHERE transaction_kind = 0;
+
+CREATE TABLE events
+(
+    tx_sequence_number          BIGINT       NOT NULL,
+    event_sequence_number       BIGINT       NOT NULL,
+    transaction_digest          BLOB         NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    -- bcs serialized array of SuiAddress in bytes. All signers of the transaction.
+    senders                     BLOB         NOT NULL,
+    package                     BLOB         NOT NULL,
+    module                      TEXT         NOT NULL,
+    event_type                  TEXT         NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    bcs                         BLOB         NOT NULL,
+    PRIMARY KEY (tx_sequence_number, event_sequence_number)
+);
+CREATE INDEX events_package ON events (package, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_package_module ON events (package, module, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_event_type ON events (event_type, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_checkpoint_sequence_number ON events (checkpoint_sequence_number);
+
+CREATE TABLE objects (
+    object_id                   BLOB         PRIMARY KEY,
+    object_version              BIGINT       NOT NULL,
+    object_digest               BLOB         NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    owner_type                  SMALLINT     NOT NULL,
+    owner_id                    BLOB,
+    object_type                 TEXT,
+    serialized_object           BLOB         NOT NULL,
+    coin_type                   TEXT,
+    coin_balance                BIGINT,
+    df_kind                     SMALLINT,
+    df_name                     BLOB,
+    df_object_type              TEXT,
+    df_object_id                BLOB
+);
+CREATE INDEX objects_owner ON objects (owner_type, owner_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
+CREATE INDEX objects_coin ON objects (owner_id, coin_type) WHERE coin_type IS NOT NULL AND owner_type = 1;
+CREATE INDEX objects_checkpoint_sequence_number ON objects (checkpoint_sequence_number);
+CREATE INDEX objects_type ON objects (object_type);
+
+CREATE TABLE objects_history (
+    object_id                   BLOB         NOT NULL,
+    object_version              BIGINT       NOT NULL,
+    object_status               SMALLINT     NOT NULL,
+    object_digest               BLOB,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    owner_type                  SMALLINT,
+    owner_id                    BLOB,
+    object_type                 TEXT,
+    serialized_object           BLOB,
+    coin_type                   TEXT,
+    coin_balance                BIGINT,
+    df_kind                     SMALLINT,
+    df_name                     BLOB,
+    df_object_type              TEXT,
+    df_object_id                BLOB,
+    PRIMARY KEY (checkpoint_sequence_number, object_id, object_version)
+);
+CREATE INDEX objects_history_owner ON objects_history (checkpoint_sequence_number, owner_type, owner_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
+CREATE INDEX objects_history_coin ON objects_history (checkpoint_sequence_number, owner_id, coin_type) WHERE coin_type IS NOT NULL AND owner_type = 1;
+CREATE INDEX objects_history_type ON objects_history (checkpoint_sequence_number, object_type);
+
+CREATE TABLE objects_snapshot (
+    object_id                   BLOB         PRIMARY KEY,
+    object_version              BIGINT       NOT NULL,
+    object_status               SMALLINT     NOT NULL,
+    object_digest               BLOB,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    owner_type                  SMALLINT,
+    owner_id                    BLOB,
+    object_type                 TEXT,
+    serialized_object           BLOB,
+    coin_type                   TEXT,
+    coin_balance                BIGINT,
+    df_kind                     SMALLINT,
+    df_name                     BLOB,
+    df_object_type              TEXT,
+    df_object_id                BLOB
+);
+CREATE INDEX objects_snapshot_checkpoint_sequence_number ON objects_snapshot (checkpoint_sequence_number);
+CREATE INDEX objects_snapshot_owner ON objects_snapshot (owner_type, owner_id, object_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
+CREATE INDEX objects_snapshot_coin ON objects_snapshot (owner_id, coin_type, object_id) WHERE coin_type IS NOT NULL AND owner_type = 1;
+CREATE INDEX objects_snapshot_type ON objects_snapshot (object_type, object_id);
+
+CREATE TABLE epochs
+(
+    epoch                           BIGINT      PRIMARY KEY,
+    first_checkpoint_id             BIGINT      NOT NULL,
+    epoch_start_timestamp           BIGINT      NOT NULL,
+    reference_gas_price             BIGINT      NOT NULL,
+    protocol_version                BIGINT      NOT NULL,
+    total_stake                     BIGINT      NOT NULL,
+    storage_fund_balance            BIGINT      NOT NULL,
+    system_state                    BLOB        NOT NULL,
+    epoch_total_transactions        BIGINT,
+    last_checkpoint_id              BIGINT,
+    epoch_end_timestamp             BIGINT,
+    storage_fund_reinvestment       BIGINT,
+    storage_charge                  BIGINT,
+    storage_rebate                  BIGINT,
+    stake_subsidy_amount            BIGINT,
+    total_gas_fees                  BIGINT,
+    total_stake_rewards_distributed BIGINT,
+    leftover_storage_fund_inflow    BIGINT,
+    epoch_commitments               BLOB
+);
+
+CREATE TABLE packages
+(
+    package_id                   BLOB           PRIMARY KEY,
+    move_package                 BLOB           NOT NULL
+);
+
+CREATE TABLE tx_senders (
+    cp_sequence_number          BIGINT       NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    sender                      BLOB         NOT NULL,
+    PRIMARY KEY (sender, tx_sequence_number, cp_sequence_number)
+);
+CREATE INDEX tx_senders_tx_sequence_number_index ON tx_senders (tx_sequence_number ASC, cp_sequence_number ASC);
+
+CREATE TABLE tx_recipients (
+    cp_sequence_number          BIGINT       NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    recipient                   BLOB         NOT NULL,
+    PRIMARY KEY (recipient, tx_sequence_number, cp_sequence_number)
+);
+CREATE INDEX tx_recipients_tx_sequence_number_index ON tx_recipients (tx_sequence_number ASC, cp_sequence_number ASC);
+
+CREATE TABLE tx_input_objects (
+    cp_sequence_number          BIGINT       NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    object_id                   BLOB         NOT NULL,
+    PRIMARY KEY (object_id, tx_sequence_number, cp_sequence_number)
+);
+
+CREATE TABLE tx_changed_objects (
+    cp_sequence_number          BIGINT       NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    object_id                   BLOB         NOT NULL,
+    PRIMARY KEY (object_id, tx_sequence_number, cp_sequence_number)
+);
+
+CREATE TABLE tx_calls (
+    cp_sequence_number          BIGINT       NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    package                     BLOB         NOT NULL,
+    module                      TEXT         NOT NULL,
+    func                        TEXT         NOT NULL,
+    PRIMARY KEY (package, tx_sequence_number, cp_sequence_number)
+);
+CREATE INDEX tx_calls_module ON tx_calls (package, module, tx_sequence_number, cp_sequence_number);
+CREATE INDEX tx_calls_func ON tx_calls (package, module, func, tx_sequence_number, cp_sequence_number);
+CREATE INDEX tx_calls_tx_sequence_number ON tx_calls (tx_sequence_number, cp_sequence_number);
+
+CREATE TABLE display
+(
+    object_type     TEXT        PRIMARY KEY,
+    id              BLOB        NOT NULL,
+    version         SMALLINT    NOT NULL,
+    bcs             BLOB        NOT NULL
+);
@@ -0,0 +1,2 @@
+-- JSON text of the decoded event contents, see the Postgres migration of the same name.
+ALTER TABLE events ADD COLUMN parsed_json TEXT;
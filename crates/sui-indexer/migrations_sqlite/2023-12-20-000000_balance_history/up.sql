@@ -0,0 +1,17 @@
+-- See the Postgres migration of the same name.
+CREATE TABLE balance_history
+(
+    owner_id                    BLOB         NOT NULL,
+    coin_type                   TEXT         NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    amount                      TEXT         NOT NULL,
+    PRIMARY KEY (owner_id, coin_type, checkpoint_sequence_number)
+);
+
+CREATE TABLE balance_history_start
+(
+    checkpoint_sequence_number  BIGINT       PRIMARY KEY
+);
+
+INSERT INTO balance_history_start (checkpoint_sequence_number)
+SELECT COALESCE(MAX(sequence_number) + 1, 0) FROM checkpoints;
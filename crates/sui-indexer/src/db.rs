@@ -11,6 +11,24 @@ use tracing::info;
 
 use crate::errors::IndexerError;
 
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+/// Database URLs with this scheme select the SQLite backend (when built with the `sqlite`
+/// feature). The rest of the URL is the path to the database file, which is created if it does
+/// not exist, e.g. `sqlite://indexer.db`. Only the writer supports SQLite, so readers recognise
+/// these URLs in order to reject them.
+pub const SQLITE_URL_SCHEME: &str = "sqlite://";
+
+/// The path of the SQLite database file that `db_url` refers to, or `None` if `db_url` does not
+/// refer to a SQLite database.
+pub fn sqlite_database_path(db_url: &str) -> Option<&str> {
+    db_url.strip_prefix(SQLITE_URL_SCHEME)
+}
+
 pub type PgConnectionPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>;
 
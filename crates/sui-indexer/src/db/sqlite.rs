@@ -0,0 +1,114 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use diesel::{r2d2::ConnectionManager, RunQueryDsl, SqliteConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use tracing::info;
+
+use crate::errors::IndexerError;
+
+pub type SqliteConnectionPool = diesel::r2d2::Pool<ConnectionManager<SqliteConnection>>;
+pub type SqlitePoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<SqliteConnection>>;
+
+const DEFAULT_SQLITE_POOL_SIZE: u32 = 8;
+const DEFAULT_SQLITE_BUSY_TIMEOUT: u64 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteConnectionConfig {
+    /// How long a connection waits for another connection's write lock to be released, before
+    /// failing with `SQLITE_BUSY`. SQLite only supports one writer at a time.
+    pub busy_timeout: Duration,
+}
+
+impl diesel::r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error>
+    for SqliteConnectionConfig
+{
+    fn on_acquire(
+        &self,
+        conn: &mut SqliteConnection,
+    ) -> std::result::Result<(), diesel::r2d2::Error> {
+        use diesel::sql_query;
+
+        // Write-ahead logging lets readers proceed while the writer holds its lock.
+        sql_query("PRAGMA journal_mode = WAL")
+            .execute(conn)
+            .map_err(diesel::r2d2::Error::QueryError)?;
+
+        sql_query(format!(
+            "PRAGMA busy_timeout = {}",
+            self.busy_timeout.as_millis()
+        ))
+        .execute(conn)
+        .map_err(diesel::r2d2::Error::QueryError)?;
+
+        Ok(())
+    }
+}
+
+/// Create a connection pool for the SQLite database file at `path`.
+pub fn new_sqlite_connection_pool(
+    path: &str,
+    pool_size: Option<u32>,
+) -> Result<SqliteConnectionPool, IndexerError> {
+    let manager = ConnectionManager::<SqliteConnection>::new(path);
+    let busy_timeout = std::env::var("DB_STATEMENT_TIMEOUT")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SQLITE_BUSY_TIMEOUT);
+
+    diesel::r2d2::Pool::builder()
+        .max_size(pool_size.unwrap_or(DEFAULT_SQLITE_POOL_SIZE))
+        .connection_customizer(Box::new(SqliteConnectionConfig {
+            busy_timeout: Duration::from_secs(busy_timeout),
+        }))
+        .build(manager)
+        .map_err(|e| {
+            IndexerError::SqliteConnectionPoolInitError(format!(
+                "Failed to initialize connection pool with error: {:?}",
+                e
+            ))
+        })
+}
+
+pub fn get_sqlite_pool_connection(
+    pool: &SqliteConnectionPool,
+) -> Result<SqlitePoolConnection, IndexerError> {
+    pool.get().map_err(|e| {
+        IndexerError::SqlitePoolConnectionError(format!(
+            "Failed to get connection from SQLite connection pool with error: {:?}",
+            e
+        ))
+    })
+}
+
+const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
+
+/// Brings the SQLite database up to date by running any migrations that have not been applied
+/// to it yet. Unlike Postgres, a SQLite database is usually a fresh file for local development,
+/// so this runs every time the indexer starts.
+///
+/// If `reset` is set to `true`, all migrations are reverted first, which drops all the data in
+/// the database.
+pub fn setup_sqlite_database(
+    conn: &mut SqlitePoolConnection,
+    reset: bool,
+) -> Result<(), anyhow::Error> {
+    if reset {
+        info!("Resetting SQLite database ...");
+        conn.revert_all_migrations(SQLITE_MIGRATIONS)
+            .map_err(|e| anyhow!("Error reverting all migrations {e}"))?;
+    }
+
+    let applied = conn
+        .run_pending_migrations(SQLITE_MIGRATIONS)
+        .map_err(|e| anyhow!("Failed to run migrations {e}"))?;
+    if !applied.is_empty() {
+        info!("Applied {} SQLite migrations.", applied.len());
+    }
+
+    info!("SQLite database is up to date.");
+    Ok(())
+}
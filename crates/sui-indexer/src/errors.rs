@@ -70,6 +70,23 @@ pub enum IndexerError {
     #[error(transparent)]
     PostgresError(#[from] diesel::result::Error),
 
+    #[error("Indexer failed to build SQLite connection pool with error: `{0}`")]
+    SqliteConnectionPoolInitError(String),
+
+    #[error(
+        "Indexer failed to get a pool connection from SQLite connection pool with error: `{0}`"
+    )]
+    SqlitePoolConnectionError(String),
+
+    #[error("Indexer failed to read SQLite DB with error: `{0}`")]
+    SqliteReadError(String),
+
+    #[error("Indexer failed to set up SQLite DB with error: `{0}`")]
+    SqliteResetError(String),
+
+    #[error("Indexer failed to commit changes to SQLite DB with error: `{0}`")]
+    SqliteWriteError(String),
+
     #[error("Indexer failed to initialize fullnode Http client with error: `{0}`")]
     HttpClientInitError(String),
 
@@ -6,7 +6,7 @@ use crate::handlers::tx_processor::IndexingPackageBuffer;
 use crate::models::display::StoredDisplay;
 use async_trait::async_trait;
 use itertools::Itertools;
-use move_core_types::annotated_value::MoveTypeLayout;
+use move_core_types::annotated_value::{MoveStruct, MoveTypeLayout};
 use move_core_types::language_storage::{StructTag, TypeTag};
 use mysten_metrics::{get_metrics, spawn_monitored_task};
 use std::collections::{BTreeMap, HashMap};
@@ -25,7 +25,7 @@ use tokio::sync::watch;
 
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
-use sui_json_rpc_types::SuiMoveValue;
+use sui_json_rpc_types::{SuiMoveStruct, SuiMoveValue};
 use sui_types::base_types::SequenceNumber;
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::event::SystemEpochInfoEvent;
@@ -42,12 +42,13 @@ use crate::errors::IndexerError;
 use crate::framework::interface::Handler;
 use crate::metrics::IndexerMetrics;
 
-use crate::db::PgConnectionPool;
 use crate::store::module_resolver::{IndexerStorePackageModuleResolver, InterimPackageResolver};
+#[cfg(feature = "sqlite")]
+use crate::store::SqliteIndexerStore;
 use crate::store::{IndexerStore, PgIndexerStore};
 use crate::types::{
-    IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject,
-    IndexedPackage, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
+    IndexedBalanceChange, IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent,
+    IndexedObject, IndexedPackage, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
 };
 
 use super::tx_processor::EpochEndIndexingObjectStore;
@@ -141,8 +142,7 @@ where
         let packages = Self::index_packages(checkpoints, &self.metrics);
         let package_objects = Self::get_package_objects(checkpoints);
 
-        let pg_blocking_cp = self.pg_blocking_cp()?;
-        let module_package_db_resolver = IndexerStorePackageModuleResolver::new(pg_blocking_cp);
+        let module_package_db_resolver = self.package_db_resolver()?;
         let in_mem_package_resolver = InterimPackageResolver::new(
             module_package_db_resolver,
             self.package_buffer.clone(),
@@ -328,6 +328,7 @@ where
                 &checkpoint_summary,
                 &checkpoint_contents,
                 &metrics,
+                package_resolver,
             )
             .await?;
 
@@ -345,11 +346,15 @@ where
             )
         };
 
+        let balance_changes =
+            IndexedBalanceChange::from_transactions(checkpoint.sequence_number, &db_transactions);
+
         Ok(CheckpointDataToCommit {
             checkpoint,
             transactions: db_transactions,
             events: db_events,
             tx_indices: db_indices,
+            balance_changes,
             display_updates: db_displays,
             object_changes,
             object_history_changes,
@@ -363,6 +368,7 @@ where
         checkpoint_summary: &CertifiedCheckpointSummary,
         checkpoint_contents: &CheckpointContents,
         metrics: &IndexerMetrics,
+        package_resolver: Arc<Resolver<impl PackageStore>>,
     ) -> IndexerResult<(
         Vec<IndexedTransaction>,
         Vec<IndexedEvent>,
@@ -417,16 +423,21 @@ where
                 TransactionKind::ProgrammableTransaction
             };
 
-            db_events.extend(events.iter().enumerate().map(|(idx, event)| {
-                IndexedEvent::from_event(
+            for (idx, event) in events.iter().enumerate() {
+                let mut indexed_event = IndexedEvent::from_event(
                     tx_sequence_number,
                     idx as u64,
                     *checkpoint_seq,
                     tx_digest,
                     event,
                     checkpoint_summary.timestamp_ms,
-                )
-            }));
+                );
+                indexed_event.parsed_json = event_parsed_json(event, package_resolver.clone())
+                    .await
+                    .tap_err(|e| warn!(tx_digest = ?tx_digest, event_seq = idx, "{e}"))
+                    .ok();
+                db_events.push(indexed_event);
+            }
 
             db_displays.extend(
                 events
@@ -750,13 +761,21 @@ where
             .collect()
     }
 
-    fn pg_blocking_cp(&self) -> Result<PgConnectionPool, IndexerError> {
+    fn package_db_resolver(&self) -> Result<IndexerStorePackageModuleResolver, IndexerError> {
         let state_as_any = self.state.as_any();
         if let Some(pg_state) = state_as_any.downcast_ref::<PgIndexerStore>() {
-            return Ok(pg_state.blocking_cp());
+            return Ok(IndexerStorePackageModuleResolver::new(
+                pg_state.blocking_cp(),
+            ));
+        }
+        #[cfg(feature = "sqlite")]
+        if let Some(sqlite_state) = state_as_any.downcast_ref::<SqliteIndexerStore>() {
+            return Ok(IndexerStorePackageModuleResolver::new_sqlite(
+                sqlite_state.blocking_cp(),
+            ));
         }
         Err(IndexerError::UncategorizedError(anyhow::anyhow!(
-            "Failed to downcast state to PgIndexerStore"
+            "Failed to downcast state to a known IndexerStore"
         )))
     }
 }
@@ -795,6 +814,31 @@ pub fn get_latest_objects(
     (latest_objects, discarded_versions)
 }
 
+/// Decode the contents of `event` into the same JSON value that is served as the event's
+/// `parsedJson`, so that events can be filtered by their fields.
+async fn event_parsed_json(
+    event: &sui_types::event::Event,
+    package_resolver: Arc<Resolver<impl PackageStore>>,
+) -> IndexerResult<serde_json::Value> {
+    let move_type_layout = package_resolver
+        .type_layout(TypeTag::Struct(Box::new(event.type_.clone())))
+        .await
+        .map_err(|e| {
+            IndexerError::ResolveMoveStructError(format!(
+                "Failed to resolve layout of event type {}. Error: {e}",
+                event.type_,
+            ))
+        })?;
+    let MoveTypeLayout::Struct(move_struct_layout) = move_type_layout else {
+        return Err(IndexerError::ResolveMoveStructError(
+            "MoveTypeLayout is not Struct".to_string(),
+        ));
+    };
+    let move_struct = MoveStruct::simple_deserialize(&event.contents, &move_struct_layout)
+        .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
+    Ok(SuiMoveStruct::from(move_struct).to_json_value())
+}
+
 async fn try_create_dynamic_field_info(
     o: &Object,
     written: &HashMap<ObjectID, Object>,
@@ -89,6 +89,7 @@ async fn commit_checkpoints<S>(
     let mut tx_batch = vec![];
     let mut events_batch = vec![];
     let mut tx_indices_batch = vec![];
+    let mut balance_changes_batch = vec![];
     let mut display_updates_batch = BTreeMap::new();
     let mut object_changes_batch = vec![];
     let mut object_history_changes_batch = vec![];
@@ -100,6 +101,7 @@ async fn commit_checkpoints<S>(
             transactions,
             events,
             tx_indices,
+            balance_changes,
             display_updates,
             object_changes,
             object_history_changes,
@@ -110,6 +112,7 @@ async fn commit_checkpoints<S>(
         tx_batch.push(transactions);
         events_batch.push(events);
         tx_indices_batch.push(tx_indices);
+        balance_changes_batch.push(balance_changes);
         display_updates_batch.extend(display_updates.into_iter());
         object_changes_batch.push(object_changes);
         object_history_changes_batch.push(object_history_changes);
@@ -122,6 +125,10 @@ async fn commit_checkpoints<S>(
     let guard = metrics.checkpoint_db_commit_latency.start_timer();
     let tx_batch = tx_batch.into_iter().flatten().collect::<Vec<_>>();
     let tx_indices_batch = tx_indices_batch.into_iter().flatten().collect::<Vec<_>>();
+    let balance_changes_batch = balance_changes_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
     let events_batch = events_batch.into_iter().flatten().collect::<Vec<_>>();
     let packages_batch = packages_batch.into_iter().flatten().collect::<Vec<_>>();
     let checkpoint_num = checkpoint_batch.len();
@@ -132,6 +139,7 @@ async fn commit_checkpoints<S>(
         let mut persist_tasks = vec![
             state.persist_transactions(tx_batch),
             state.persist_tx_indices(tx_indices_batch),
+            state.persist_balance_changes(balance_changes_batch),
             state.persist_events(events_batch),
             state.persist_displays(display_updates_batch),
             state.persist_packages(packages_batch),
@@ -6,8 +6,8 @@ use std::collections::BTreeMap;
 use crate::{
     models::display::StoredDisplay,
     types::{
-        IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject,
-        IndexedPackage, IndexedTransaction, TxIndex,
+        IndexedBalanceChange, IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo,
+        IndexedEvent, IndexedObject, IndexedPackage, IndexedTransaction, TxIndex,
     },
 };
 
@@ -21,6 +21,7 @@ pub struct CheckpointDataToCommit {
     pub transactions: Vec<IndexedTransaction>,
     pub events: Vec<IndexedEvent>,
     pub tx_indices: Vec<TxIndex>,
+    pub balance_changes: Vec<IndexedBalanceChange>,
     pub display_updates: BTreeMap<String, StoredDisplay>,
     pub object_changes: TransactionObjectChangesToCommit,
     pub object_history_changes: TransactionObjectChangesToCommit,
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    db::{PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection},
+    db::{sqlite_database_path, PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection},
     errors::IndexerError,
     models::{
         address_metrics::StoredAddressMetrics,
@@ -21,6 +21,7 @@ use crate::{
         address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
         objects_snapshot, packages, transactions,
     },
+    store::query::to_event_clause,
     types::{IndexerResult, OwnerType},
 };
 use anyhow::{anyhow, Result};
@@ -84,6 +85,14 @@ impl IndexerReader {
         db_url: T,
         config: PgConnectionPoolConfig,
     ) -> Result<Self> {
+        let db_url = db_url.into();
+        if sqlite_database_path(&db_url).is_some() {
+            return Err(anyhow!(
+                "The reader only supports Postgres, SQLite databases can only be written to by the \
+                 indexer's fullnode sync worker"
+            ));
+        }
+
         let manager = ConnectionManager::<PgConnection>::new(db_url);
 
         let connection_config = PgConnectionConfig {
@@ -1166,43 +1175,7 @@ impl IndexerReader {
         } else if let EventFilter::Transaction(tx_digest) = filter {
             self.query_events_by_tx_digest_query(tx_digest, cursor, limit, descending_order)?
         } else {
-            let main_where_clause = match filter {
-                EventFilter::Package(package_id) => {
-                    format!("package = '\\x{}'::bytea", package_id.to_hex())
-                }
-                EventFilter::MoveModule { package, module } => {
-                    format!(
-                        "package = '\\x{}'::bytea AND module = '{}'",
-                        package.to_hex(),
-                        module,
-                    )
-                }
-                EventFilter::MoveEventType(struct_tag) => {
-                    format!("event_type = '{}'", struct_tag)
-                }
-                EventFilter::MoveEventModule { package, module } => {
-                    let package_module_prefix = format!("{}::{}", package.to_hex_literal(), module);
-                    format!("event_type LIKE '{package_module_prefix}::%'")
-                }
-                EventFilter::Sender(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::Transaction(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::MoveEventField { .. }
-                | EventFilter::All(_)
-                | EventFilter::Any(_)
-                | EventFilter::And(_, _)
-                | EventFilter::Or(_, _)
-                | EventFilter::TimeRange { .. } => {
-                    return Err(IndexerError::NotSupportedError(
-                        "This type of EventFilter is not supported.".into(),
-                    ));
-                }
-            };
+            let main_where_clause = to_event_clause(&filter);
 
             let cursor_clause = if descending_order {
                 format!("AND ({TX_SEQUENCE_NUMBER_STR} < {} OR ({TX_SEQUENCE_NUMBER_STR} = {} AND {EVENT_SEQUENCE_NUMBER_STR} < {}))", tx_seq, tx_seq, event_seq)
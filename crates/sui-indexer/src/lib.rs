@@ -13,6 +13,7 @@ use tokio::runtime::Handle;
 use tracing::warn;
 use url::Url;
 
+use sui_json_rpc::axum_router::DEFAULT_MAX_BATCH_SIZE;
 use sui_json_rpc::ServerType;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
 use sui_json_rpc_api::CLIENT_SDK_TYPE_HEADER;
@@ -68,6 +69,8 @@ pub struct IndexerConfig {
     pub rpc_server_url: String,
     #[clap(long, default_value = "9000", global = true)]
     pub rpc_server_port: u16,
+    #[clap(long, default_value_t = DEFAULT_MAX_BATCH_SIZE, global = true)]
+    pub rpc_server_max_batch_size: usize,
     #[clap(long)]
     pub reset_db: bool,
     #[clap(long)]
@@ -121,6 +124,7 @@ impl Default for IndexerConfig {
             client_metric_port: 9184,
             rpc_server_url: "0.0.0.0".to_string(),
             rpc_server_port: 9000,
+            rpc_server_max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             reset_db: false,
             fullnode_sync_worker: true,
             rpc_server_worker: true,
@@ -136,6 +140,7 @@ pub async fn build_json_rpc_server(
     custom_runtime: Option<Handle>,
 ) -> Result<ServerHandle, IndexerError> {
     let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+    builder.set_max_batch_size(config.rpc_server_max_batch_size);
     let http_client = crate::get_http_client(config.rpc_client_url.as_str())?;
 
     builder.register_module(WriteApi::new(http_client.clone()))?;
@@ -29,6 +29,18 @@ async fn main() -> Result<(), IndexerError> {
             e
         ))
     })?;
+
+    if let Some(path) = sui_indexer::db::sqlite_database_path(&db_url) {
+        #[cfg(feature = "sqlite")]
+        return sqlite::run(&indexer_config, path).await;
+
+        #[cfg(not(feature = "sqlite"))]
+        return Err(IndexerError::NotSupportedError(format!(
+            "Cannot index into SQLite database at {path}, sui-indexer was built without the \
+             `sqlite` feature"
+        )));
+    }
+
     let blocking_cp = new_pg_connection_pool(&db_url, None).map_err(|e| {
         error!(
             "Failed creating Postgres connection pool with error {:?}",
@@ -98,3 +110,70 @@ async fn main() -> Result<(), IndexerError> {
     }
     Ok(())
 }
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use tracing::{error, info};
+
+    use sui_indexer::db::{
+        get_sqlite_pool_connection, new_sqlite_connection_pool, setup_sqlite_database,
+    };
+    use sui_indexer::errors::IndexerError;
+    use sui_indexer::indexer::Indexer;
+    use sui_indexer::metrics::{start_prometheus_server, IndexerMetrics};
+    use sui_indexer::store::SqliteIndexerStore;
+    use sui_indexer::IndexerConfig;
+
+    /// Run the indexer writer against the SQLite database file at `path`. SQLite support is
+    /// writer-only: the JSON-RPC reader and analytical worker rely on Postgres-specific queries,
+    /// so they are rejected rather than silently skipped.
+    pub(crate) async fn run(
+        indexer_config: &IndexerConfig,
+        path: &str,
+    ) -> Result<(), IndexerError> {
+        if !indexer_config.fullnode_sync_worker
+            || indexer_config.rpc_server_worker
+            || indexer_config.analytical_worker
+        {
+            return Err(IndexerError::NotSupportedError(
+                "Only the fullnode sync worker can run against a SQLite database, the RPC server \
+                 and analytical workers require Postgres"
+                    .to_string(),
+            ));
+        }
+
+        info!("Using SQLite database at {path}");
+        let blocking_cp = new_sqlite_connection_pool(path, None).map_err(|e| {
+            error!("Failed creating SQLite connection pool with error {:?}", e);
+            e
+        })?;
+
+        let mut conn = get_sqlite_pool_connection(&blocking_cp)?;
+        setup_sqlite_database(&mut conn, indexer_config.reset_db).map_err(|e| {
+            let db_err_msg = format!(
+                "Failed setting up SQLite database at {:?} with error: {:?}",
+                path, e
+            );
+            error!("{}", db_err_msg);
+            IndexerError::SqliteResetError(db_err_msg)
+        })?;
+        drop(conn);
+
+        let (_registry_service, registry) = start_prometheus_server(
+            // NOTE: this parses the input host addr and port number for socket addr,
+            // so unwrap() is safe here.
+            format!(
+                "{}:{}",
+                indexer_config.client_metric_host, indexer_config.client_metric_port
+            )
+            .parse()
+            .unwrap(),
+            indexer_config.rpc_client_url.as_str(),
+        )?;
+        let indexer_metrics = IndexerMetrics::new(&registry);
+        mysten_metrics::init_metrics(&registry);
+
+        let store = SqliteIndexerStore::new(blocking_cp, indexer_metrics.clone());
+        Indexer::start_writer(indexer_config, store, indexer_metrics).await
+    }
+}
@@ -127,6 +127,7 @@ pub struct IndexerMetrics {
     pub checkpoint_db_commit_latency_packages: Histogram,
     pub checkpoint_db_commit_latency_tx_indices: Histogram,
     pub checkpoint_db_commit_latency_tx_indices_chunks: Histogram,
+    pub checkpoint_db_commit_latency_balance_changes: Histogram,
     pub checkpoint_db_commit_latency_checkpoints: Histogram,
     pub checkpoint_db_commit_latency_epoch: Histogram,
     pub advance_epoch_latency: Histogram,
@@ -445,6 +446,13 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            checkpoint_db_commit_latency_balance_changes: register_histogram_with_registry!(
+                "checkpoint_db_commit_latency_balance_changes",
+                "Time spent commiting balance changes",
+                DB_COMMIT_LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             checkpoint_db_commit_latency_tx_indices_chunks: register_histogram_with_registry!(
                 "checkpoint_db_commit_latency_tx_indices_chunks",
                 "Time spent commiting tx_indices chunks",
@@ -0,0 +1,27 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema::balance_history;
+use crate::types::IndexedBalanceChange;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = balance_history)]
+pub struct StoredBalanceChange {
+    pub owner_id: Vec<u8>,
+    pub coin_type: String,
+    pub checkpoint_sequence_number: i64,
+    pub amount: String,
+}
+
+impl From<IndexedBalanceChange> for StoredBalanceChange {
+    fn from(c: IndexedBalanceChange) -> Self {
+        Self {
+            owner_id: c.owner.to_vec(),
+            coin_type: c.coin_type,
+            checkpoint_sequence_number: c.checkpoint_sequence_number as i64,
+            amount: c.amount.to_string(),
+        }
+    }
+}
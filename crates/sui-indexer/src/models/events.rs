@@ -51,6 +51,9 @@ pub struct StoredEvent {
 
     #[diesel(sql_type = diesel::sql_types::Bytea)]
     pub bcs: Vec<u8>,
+
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Jsonb>)]
+    pub parsed_json: Option<serde_json::Value>,
 }
 
 impl From<IndexedEvent> for StoredEvent {
@@ -70,6 +73,7 @@ impl From<IndexedEvent> for StoredEvent {
             event_type: event.event_type.clone(),
             bcs: event.bcs.clone(),
             timestamp_ms: event.timestamp_ms as i64,
+            parsed_json: event.parsed_json,
         }
     }
 }
@@ -33,6 +33,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    balance_history (owner_id, coin_type, checkpoint_sequence_number) {
+        owner_id -> Bytea,
+        coin_type -> Text,
+        checkpoint_sequence_number -> Int8,
+        amount -> Text,
+    }
+}
+
+diesel::table! {
+    balance_history_start (checkpoint_sequence_number) {
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     checkpoints (sequence_number) {
         sequence_number -> Int8,
@@ -107,6 +122,7 @@ diesel::table! {
         event_type -> Text,
         timestamp_ms -> Int8,
         bcs -> Bytea,
+        parsed_json -> Nullable<Jsonb>,
     }
 }
 
@@ -308,6 +324,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     active_addresses,
     address_metrics,
     addresses,
+    balance_history,
+    balance_history_start,
     checkpoints,
     display,
     epoch_peak_tps,
@@ -16,7 +16,10 @@ use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
 
 use crate::models::display::StoredDisplay;
 use crate::models::objects::{StoredDeletedObject, StoredObject};
-use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
+use crate::types::{
+    IndexedBalanceChange, IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction,
+    TxIndex,
+};
 
 #[allow(clippy::large_enum_variant)]
 pub enum ObjectChangeToCommit {
@@ -68,6 +71,11 @@ pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
 
     async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError>;
 
+    async fn persist_balance_changes(
+        &self,
+        balance_changes: Vec<IndexedBalanceChange>,
+    ) -> Result<(), IndexerError>;
+
     async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError>;
     async fn persist_displays(
         &self,
@@ -5,6 +5,8 @@ pub(crate) use indexer_analytical_store::*;
 pub(crate) use indexer_store::*;
 pub use pg_indexer_analytical_store::PgIndexerAnalyticalStore;
 pub use pg_indexer_store::PgIndexerStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite_indexer_store::SqliteIndexerStore;
 
 mod indexer_analytical_store;
 pub mod indexer_store;
@@ -12,7 +14,9 @@ pub mod module_resolver;
 mod pg_indexer_analytical_store;
 mod pg_indexer_store;
 mod pg_partition_manager;
-mod query;
+pub(crate) mod query;
+#[cfg(feature = "sqlite")]
+mod sqlite_indexer_store;
 
 pub(crate) mod diesel_macro {
     macro_rules! read_only_blocking {
@@ -59,6 +63,52 @@ pub(crate) mod diesel_macro {
         }};
     }
 
+    #[cfg(feature = "sqlite")]
+    macro_rules! sqlite_read_blocking {
+        ($pool:expr, $query:expr) => {{
+            let mut sqlite_pool_conn = crate::db::get_sqlite_pool_connection($pool)?;
+            let conn: &mut diesel::SqliteConnection = &mut sqlite_pool_conn;
+            diesel::Connection::transaction(conn, $query)
+                .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+        }};
+    }
+
+    /// SQLite only supports one writer at a time, so write transactions take the database's
+    /// write lock up-front (`BEGIN IMMEDIATE`), and are retried if it stays busy.
+    #[cfg(feature = "sqlite")]
+    macro_rules! sqlite_transactional_blocking_with_retry {
+        ($pool:expr, $query:expr, $max_elapsed:expr) => {{
+            let mut backoff = backoff::ExponentialBackoff::default();
+            backoff.max_elapsed_time = Some($max_elapsed);
+
+            match backoff::retry(backoff, || {
+                let mut sqlite_pool_conn =
+                    crate::db::get_sqlite_pool_connection($pool).map_err(|e| {
+                        backoff::Error::Transient {
+                            err: IndexerError::SqliteWriteError(e.to_string()),
+                            retry_after: None,
+                        }
+                    })?;
+                let conn: &mut diesel::SqliteConnection = &mut sqlite_pool_conn;
+                conn.immediate_transaction($query).map_err(|e| {
+                    tracing::error!("Error with persisting data into DB: {:?}", e);
+                    backoff::Error::Transient {
+                        err: IndexerError::SqliteWriteError(e.to_string()),
+                        retry_after: None,
+                    }
+                })
+            }) {
+                Ok(v) => Ok(v),
+                Err(backoff::Error::Transient { err, .. }) => Err(err),
+                Err(backoff::Error::Permanent(err)) => Err(err),
+            }
+        }};
+    }
+
     pub(crate) use read_only_blocking;
+    #[cfg(feature = "sqlite")]
+    pub(crate) use sqlite_read_blocking;
+    #[cfg(feature = "sqlite")]
+    pub(crate) use sqlite_transactional_blocking_with_retry;
     pub(crate) use transactional_blocking_with_retry;
 }
@@ -16,22 +16,52 @@ use sui_types::move_package::MovePackage;
 use sui_types::object::Object;
 
 use crate::db::PgConnectionPool;
+#[cfg(feature = "sqlite")]
+use crate::db::SqliteConnectionPool;
 use crate::errors::{Context, IndexerError};
 use crate::handlers::tx_processor::IndexingPackageBuffer;
 use crate::metrics::IndexerMetrics;
 use crate::models::packages::StoredPackage;
 use crate::schema::{objects, packages};
 use crate::store::diesel_macro::read_only_blocking;
+#[cfg(feature = "sqlite")]
+use crate::store::diesel_macro::sqlite_read_blocking;
 use crate::types::IndexedPackage;
 
+/// Runs a read-only `$query` against whichever database `$cp` connects to.
+macro_rules! read_blocking {
+    ($cp:expr, $query:expr) => {{
+        match $cp {
+            ResolverConnectionPool::Pg(cp) => read_only_blocking!(cp, $query),
+            #[cfg(feature = "sqlite")]
+            ResolverConnectionPool::Sqlite(cp) => sqlite_read_blocking!(cp, $query),
+        }
+    }};
+}
+
 /// A package resolver that reads packages from the database.
 pub struct IndexerStorePackageModuleResolver {
-    cp: PgConnectionPool,
+    cp: ResolverConnectionPool,
+}
+
+enum ResolverConnectionPool {
+    Pg(PgConnectionPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteConnectionPool),
 }
 
 impl IndexerStorePackageModuleResolver {
     pub fn new(cp: PgConnectionPool) -> Self {
-        Self { cp }
+        Self {
+            cp: ResolverConnectionPool::Pg(cp),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    pub fn new_sqlite(cp: SqliteConnectionPool) -> Self {
+        Self {
+            cp: ResolverConnectionPool::Sqlite(cp),
+        }
     }
 }
 
@@ -44,7 +74,7 @@ impl ModuleResolver for IndexerStorePackageModuleResolver {
 
         // Note: this implementation is potentially vulnerable to package upgrade race conditions
         // for framework packages because they reuse the same package IDs.
-        let stored_package: StoredPackage = read_only_blocking!(&self.cp, |conn| {
+        let stored_package: StoredPackage = read_blocking!(&self.cp, |conn| {
             packages::dsl::packages
                 .filter(packages::dsl::package_id.eq(package_id))
                 .first::<StoredPackage>(conn)
@@ -94,7 +124,7 @@ impl IndexerStorePackageModuleResolver {
         &self,
         id: AccountAddress,
     ) -> Result<SequenceNumber, IndexerError> {
-        let Some(version) = read_only_blocking!(&self.cp, |conn| {
+        let Some(version) = read_blocking!(&self.cp, |conn| {
             let query = objects::dsl::objects
                 .select(objects::dsl::object_version)
                 .filter(objects::dsl::object_id.eq(id.to_vec()));
@@ -111,7 +141,7 @@ impl IndexerStorePackageModuleResolver {
     }
 
     fn get_package_from_db(&self, id: AccountAddress) -> Result<Package, IndexerError> {
-        let Some(bcs) = read_only_blocking!(&self.cp, |conn| {
+        let Some(bcs) = read_blocking!(&self.cp, |conn| {
             let query = objects::dsl::objects
                 .select(objects::dsl::serialized_object)
                 .filter(objects::dsl::object_id.eq(id.to_vec()));
@@ -30,6 +30,7 @@ use crate::handlers::TransactionObjectChangesToCommit;
 use crate::metrics::IndexerMetrics;
 
 use crate::db::PgConnectionPool;
+use crate::models::balance_history::StoredBalanceChange;
 use crate::models::checkpoints::StoredCheckpoint;
 use crate::models::display::StoredDisplay;
 use crate::models::epoch::StoredEpochInfo;
@@ -40,12 +41,16 @@ use crate::models::objects::{
 use crate::models::packages::StoredPackage;
 use crate::models::transactions::StoredTransaction;
 use crate::schema::{
-    checkpoints, display, epochs, events, objects, objects_history, objects_snapshot, packages,
-    transactions, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    balance_history, checkpoints, display, epochs, events, objects, objects_history,
+    objects_snapshot, packages, transactions, tx_calls, tx_changed_objects, tx_input_objects,
+    tx_recipients, tx_senders,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver::IndexerStorePackageModuleResolver;
-use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
+use crate::types::{
+    IndexedBalanceChange, IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction,
+    TxIndex,
+};
 
 use super::pg_partition_manager::{EpochPartitionData, PgPartitionManager};
 use super::IndexerStore;
@@ -491,6 +496,43 @@ impl PgIndexerStore {
         })
     }
 
+    fn persist_balance_changes(
+        &self,
+        balance_changes: Vec<IndexedBalanceChange>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_balance_changes
+            .start_timer();
+        let balance_changes = balance_changes
+            .into_iter()
+            .map(StoredBalanceChange::from)
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in balance_changes.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(balance_history::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write balance_history to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(
+                elapsed,
+                "Persisted {} rows to balance_history",
+                balance_changes.len()
+            )
+        })
+    }
+
     fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
         if packages.is_empty() {
             return Ok(());
@@ -1105,6 +1147,17 @@ impl IndexerStore for PgIndexerStore {
         Ok(())
     }
 
+    async fn persist_balance_changes(
+        &self,
+        balance_changes: Vec<IndexedBalanceChange>,
+    ) -> Result<(), IndexerError> {
+        if balance_changes.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_balance_changes(balance_changes))
+            .await
+    }
+
     async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
         self.execute_in_blocking_worker(move |this| this.persist_epoch(epoch))
             .await
@@ -1137,7 +1190,7 @@ impl IndexerStore for PgIndexerStore {
 /// Construct deleted objects and mutated objects to commit.
 /// In particular, filter mutated objects updates that would
 /// be override immediately.
-fn make_final_list_of_objects_to_commit(
+pub(crate) fn make_final_list_of_objects_to_commit(
     tx_object_changes: Vec<TransactionObjectChangesToCommit>,
 ) -> Vec<ObjectChangeToCommit> {
     let deleted_objects = tx_object_changes
@@ -1178,7 +1231,7 @@ fn make_final_list_of_objects_to_commit(
         .collect()
 }
 
-fn make_objects_history_to_commit(
+pub(crate) fn make_objects_history_to_commit(
     tx_object_changes: Vec<TransactionObjectChangesToCommit>,
 ) -> Vec<ObjectChangeToCommit> {
     let deleted_objects: Vec<StoredDeletedObject> = tx_object_changes
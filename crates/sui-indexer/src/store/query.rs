@@ -1,7 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_json_rpc_types::SuiObjectDataFilter;
+use fastcrypto::encoding::{Encoding, Hex};
+use serde_json::Value;
+use sui_json_rpc_types::{
+    unsigned_integer, EventFieldComparison, EventFieldPredicate, EventFilter, SuiObjectDataFilter,
+};
 use sui_types::base_types::ObjectID;
 
 pub trait DBFilter<C> {
@@ -210,17 +214,140 @@ fn to_outer_clauses(filter: &SuiObjectDataFilter) -> Option<String> {
     }
 }
 
+/// Translates `filter` into a boolean SQL expression over the columns of the `events` table.
+pub(crate) fn to_event_clause(filter: &EventFilter) -> String {
+    match filter {
+        EventFilter::Sender(sender) => {
+            format!(
+                "'\\x{}'::bytea = ANY(senders)",
+                Hex::encode(sender.to_vec())
+            )
+        }
+        EventFilter::Transaction(digest) => format!(
+            "transaction_digest = '\\x{}'::bytea",
+            Hex::encode(digest.into_inner())
+        ),
+        EventFilter::Package(package_id) => {
+            format!("package = '\\x{}'::bytea", package_id.to_hex())
+        }
+        EventFilter::MoveModule { package, module } => format!(
+            "package = '\\x{}'::bytea AND module = '{}'",
+            package.to_hex(),
+            module,
+        ),
+        EventFilter::MoveEventType(struct_tag) => format!("event_type = '{}'", struct_tag),
+        EventFilter::MoveEventModule { package, module } => {
+            let package_module_prefix = format!("{}::{}", package.to_hex_literal(), module);
+            format!("event_type LIKE '{package_module_prefix}::%'")
+        }
+        EventFilter::MoveEventField { path, value } => {
+            to_event_field_clause(&EventFieldPredicate {
+                path: path.clone(),
+                comparison: EventFieldComparison::Eq(value.clone()),
+            })
+        }
+        EventFilter::MoveEventFieldPredicate(predicate) => to_event_field_clause(predicate),
+        EventFilter::TimeRange {
+            start_time,
+            end_time,
+        } => format!("(timestamp_ms >= {start_time} AND timestamp_ms < {end_time})"),
+        EventFilter::All(sub_filters) => {
+            if sub_filters.is_empty() {
+                "TRUE".to_string()
+            } else {
+                let clauses = sub_filters.iter().map(to_event_clause).collect::<Vec<_>>();
+                format!("({})", clauses.join(" AND "))
+            }
+        }
+        EventFilter::Any(sub_filters) => {
+            if sub_filters.is_empty() {
+                // Any default to false
+                "FALSE".to_string()
+            } else {
+                let clauses = sub_filters.iter().map(to_event_clause).collect::<Vec<_>>();
+                format!("({})", clauses.join(" OR "))
+            }
+        }
+        EventFilter::And(f1, f2) => {
+            format!("({} AND {})", to_event_clause(f1), to_event_clause(f2))
+        }
+        EventFilter::Or(f1, f2) => {
+            format!("({} OR {})", to_event_clause(f1), to_event_clause(f2))
+        }
+    }
+}
+
+/// Translates a predicate on a decoded event field into a condition on the `parsed_json` column,
+/// with the same semantics as `EventFieldPredicate::matches`. Events without `parsed_json` never
+/// match.
+fn to_event_field_clause(predicate: &EventFieldPredicate) -> String {
+    let Some(segments) = predicate.path_segments() else {
+        return "FALSE".to_string();
+    };
+
+    // Quote every element of the text[] path, so field names are never parsed as array syntax.
+    let path = segments
+        .iter()
+        .map(|s| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",");
+    let path = sql_string(&format!("{{{path}}}"));
+    let field = format!("parsed_json #> {path}");
+    let text = format!("(parsed_json #>> {path})");
+
+    let compare_integer = |op: &str, bound: &Value| {
+        let Some(bound) = unsigned_integer(bound) else {
+            return "FALSE".to_string();
+        };
+        format!("CASE WHEN {text} ~ '^[0-9]+$' THEN {text}::numeric {op} {bound} ELSE FALSE END")
+    };
+
+    use EventFieldComparison as C;
+    match &predicate.comparison {
+        C::Eq(value) => format!("{field} = {}", sql_jsonb(value)),
+        C::In(values) => {
+            if values.is_empty() {
+                "FALSE".to_string()
+            } else {
+                let values = values.iter().map(sql_jsonb).collect::<Vec<_>>();
+                format!("{field} IN ({})", values.join(", "))
+            }
+        }
+        C::StartsWith(prefix) => format!(
+            "(jsonb_typeof({field}) = 'string' AND starts_with({text}, {}))",
+            sql_string(prefix)
+        ),
+        C::Gt(bound) => compare_integer(">", bound),
+        C::Gte(bound) => compare_integer(">=", bound),
+        C::Lt(bound) => compare_integer("<", bound),
+        C::Lte(bound) => compare_integer("<=", bound),
+    }
+}
+
+/// A SQL string literal containing `s`.
+fn sql_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// A SQL jsonb literal containing `value`.
+fn sql_jsonb(value: &Value) -> String {
+    format!("{}::jsonb", sql_string(&value.to_string()))
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
     use move_core_types::ident_str;
+    use serde_json::json;
 
-    use sui_json_rpc_types::SuiObjectDataFilter;
+    use sui_json_rpc_types::{
+        EventFieldComparison, EventFieldPredicate, EventFilter, SuiObjectDataFilter,
+    };
     use sui_types::base_types::{ObjectID, SuiAddress};
     use sui_types::parse_sui_struct_tag;
 
-    use crate::store::query::DBFilter;
+    use crate::store::query::{to_event_clause, DBFilter};
 
     #[test]
     fn test_address_filter() {
@@ -326,4 +453,91 @@ LIMIT 100;";
             filter.to_objects_history_sql(None, 100, vec!["*"])
         );
     }
+
+    fn field_predicate(path: &str, comparison: EventFieldComparison) -> EventFilter {
+        EventFilter::MoveEventFieldPredicate(EventFieldPredicate {
+            path: path.to_string(),
+            comparison,
+        })
+    }
+
+    #[test]
+    fn test_event_field_comparison_clauses() {
+        assert_eq!(
+            to_event_clause(&field_predicate(
+                "/pool/amount_in",
+                EventFieldComparison::Gte(json!("001000000"))
+            )),
+            "CASE WHEN (parsed_json #>> '{\"pool\",\"amount_in\"}') ~ '^[0-9]+$' \
+             THEN (parsed_json #>> '{\"pool\",\"amount_in\"}')::numeric >= 1000000 ELSE FALSE END",
+        );
+
+        // Bounds that are not unsigned integers can never match.
+        assert_eq!(
+            to_event_clause(&field_predicate("/a", EventFieldComparison::Lt(json!(-1)))),
+            "FALSE",
+        );
+
+        assert_eq!(
+            to_event_clause(&field_predicate(
+                "/coins/0",
+                EventFieldComparison::In(vec![json!("0x2::sui::SUI"), json!(1)])
+            )),
+            "parsed_json #> '{\"coins\",\"0\"}' IN ('\"0x2::sui::SUI\"'::jsonb, '1'::jsonb)",
+        );
+
+        assert_eq!(
+            to_event_clause(&field_predicate(
+                "/name",
+                EventFieldComparison::StartsWith("o'brien".to_string())
+            )),
+            "(jsonb_typeof(parsed_json #> '{\"name\"}') = 'string' \
+             AND starts_with((parsed_json #>> '{\"name\"}'), 'o''brien'))",
+        );
+    }
+
+    #[test]
+    fn test_event_field_path_escaping() {
+        // JSON Pointer escapes are removed, and quotes in field names are escaped for both the
+        // text[] literal and the SQL string.
+        assert_eq!(
+            to_event_clause(&EventFilter::MoveEventField {
+                path: "/a~1b/c\"d'e".to_string(),
+                value: json!(true),
+            }),
+            "parsed_json #> '{\"a/b\",\"c\\\"d''e\"}' = 'true'::jsonb",
+        );
+
+        // Paths that are not JSON Pointers never match, like on the fullnode.
+        assert_eq!(
+            to_event_clause(&field_predicate("a", EventFieldComparison::Eq(json!(1)))),
+            "FALSE",
+        );
+    }
+
+    #[test]
+    fn test_composite_event_filter() {
+        let package = ObjectID::from_str("0x2").unwrap();
+        let filter = EventFilter::All(vec![
+            EventFilter::MoveEventModule {
+                package,
+                module: ident_str!("pool").into(),
+            },
+            EventFilter::Any(vec![
+                field_predicate("/x", EventFieldComparison::Eq(json!(null))),
+                EventFilter::TimeRange {
+                    start_time: 10,
+                    end_time: 20,
+                },
+            ]),
+            EventFilter::Any(vec![]),
+        ]);
+
+        assert_eq!(
+            to_event_clause(&filter),
+            "(event_type LIKE '0x0000000000000000000000000000000000000000000000000000000000000002::pool::%' \
+             AND (parsed_json #> '{\"x\"}' = 'null'::jsonb OR (timestamp_ms >= 10 AND timestamp_ms < 20)) \
+             AND FALSE)",
+        );
+    }
 }
@@ -0,0 +1,1090 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use move_bytecode_utils::module_cache::SyncModuleCache;
+use tap::Tap;
+use tracing::info;
+
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::ObjectRead;
+
+use crate::db::SqliteConnectionPool;
+use crate::errors::{Context, IndexerError};
+use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
+use crate::metrics::IndexerMetrics;
+use crate::models::balance_history::StoredBalanceChange;
+use crate::models::checkpoints::StoredCheckpoint;
+use crate::models::display::StoredDisplay;
+use crate::models::epoch::StoredEpochInfo;
+use crate::models::events::StoredEvent;
+use crate::models::objects::{StoredDeletedHistoryObject, StoredHistoryObject, StoredObject};
+use crate::models::packages::StoredPackage;
+use crate::models::transactions::StoredTransaction;
+use crate::schema::{
+    balance_history, display, epochs, objects, objects_history, objects_snapshot, packages,
+    tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+};
+use crate::store::diesel_macro::{sqlite_read_blocking, sqlite_transactional_blocking_with_retry};
+use crate::store::module_resolver::IndexerStorePackageModuleResolver;
+use crate::types::{
+    IndexedBalanceChange, IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction,
+    TxIndex,
+};
+
+use super::pg_indexer_store::{
+    make_final_list_of_objects_to_commit, make_objects_history_to_commit,
+};
+use super::IndexerStore;
+use super::ObjectChangeToCommit;
+
+/// SQLite limits the number of parameters bound to a single statement, so large batches of rows
+/// are inserted a chunk at a time.
+const SQLITE_COMMIT_CHUNK_SIZE: usize = 500;
+
+/// See `UPDATE_OBJECTS_SNAPSHOT_QUERY` in the Postgres store.
+const UPDATE_OBJECTS_SNAPSHOT_QUERY: &str = r"
+INSERT INTO objects_snapshot (object_id, object_version, object_status, object_digest, checkpoint_sequence_number, owner_type, owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind, df_name, df_object_type, df_object_id)
+SELECT object_id, object_version, object_status, object_digest, checkpoint_sequence_number, owner_type, owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind, df_name, df_object_type, df_object_id
+FROM (
+    SELECT *,
+           ROW_NUMBER() OVER (PARTITION BY object_id ORDER BY object_version DESC) as rn
+    FROM objects_history
+    WHERE checkpoint_sequence_number >= ? AND checkpoint_sequence_number < ?
+) as subquery
+WHERE rn = 1
+ON CONFLICT (object_id) DO UPDATE
+SET object_version = excluded.object_version,
+    object_status = excluded.object_status,
+    object_digest = excluded.object_digest,
+    checkpoint_sequence_number = excluded.checkpoint_sequence_number,
+    owner_type = excluded.owner_type,
+    owner_id = excluded.owner_id,
+    object_type = excluded.object_type,
+    serialized_object = excluded.serialized_object,
+    coin_type = excluded.coin_type,
+    coin_balance = excluded.coin_balance,
+    df_kind = excluded.df_kind,
+    df_name = excluded.df_name,
+    df_object_type = excluded.df_object_type,
+    df_object_id = excluded.df_object_id;
+";
+
+/// Tables whose Postgres schema includes array columns. SQLite does not support arrays, so these
+/// columns are stored as BCS-serialized blobs instead. All other tables share their schema with
+/// Postgres.
+mod schema {
+    diesel::table! {
+        checkpoints (sequence_number) {
+            sequence_number -> BigInt,
+            checkpoint_digest -> Binary,
+            epoch -> BigInt,
+            network_total_transactions -> BigInt,
+            previous_checkpoint_digest -> Nullable<Binary>,
+            end_of_epoch -> Bool,
+            tx_digests -> Binary,
+            timestamp_ms -> BigInt,
+            total_gas_cost -> BigInt,
+            computation_cost -> BigInt,
+            storage_cost -> BigInt,
+            storage_rebate -> BigInt,
+            non_refundable_storage_fee -> BigInt,
+            checkpoint_commitments -> Binary,
+            validator_signature -> Binary,
+            end_of_epoch_data -> Nullable<Binary>,
+        }
+    }
+
+    diesel::table! {
+        events (tx_sequence_number, event_sequence_number) {
+            tx_sequence_number -> BigInt,
+            event_sequence_number -> BigInt,
+            transaction_digest -> Binary,
+            checkpoint_sequence_number -> BigInt,
+            senders -> Binary,
+            package -> Binary,
+            module -> Text,
+            event_type -> Text,
+            timestamp_ms -> BigInt,
+            bcs -> Binary,
+            parsed_json -> Nullable<Text>,
+        }
+    }
+
+    diesel::table! {
+        transactions (tx_sequence_number, checkpoint_sequence_number) {
+            tx_sequence_number -> BigInt,
+            transaction_digest -> Binary,
+            raw_transaction -> Binary,
+            raw_effects -> Binary,
+            checkpoint_sequence_number -> BigInt,
+            timestamp_ms -> BigInt,
+            object_changes -> Binary,
+            balance_changes -> Binary,
+            events -> Binary,
+            transaction_kind -> SmallInt,
+            success_command_count -> SmallInt,
+        }
+    }
+}
+
+use schema::{checkpoints, events, transactions};
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = checkpoints)]
+struct SqliteCheckpoint {
+    sequence_number: i64,
+    checkpoint_digest: Vec<u8>,
+    epoch: i64,
+    network_total_transactions: i64,
+    previous_checkpoint_digest: Option<Vec<u8>>,
+    end_of_epoch: bool,
+    tx_digests: Vec<u8>,
+    timestamp_ms: i64,
+    total_gas_cost: i64,
+    computation_cost: i64,
+    storage_cost: i64,
+    storage_rebate: i64,
+    non_refundable_storage_fee: i64,
+    checkpoint_commitments: Vec<u8>,
+    validator_signature: Vec<u8>,
+    end_of_epoch_data: Option<Vec<u8>>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = events)]
+struct SqliteEvent {
+    tx_sequence_number: i64,
+    event_sequence_number: i64,
+    transaction_digest: Vec<u8>,
+    checkpoint_sequence_number: i64,
+    senders: Vec<u8>,
+    package: Vec<u8>,
+    module: String,
+    event_type: String,
+    timestamp_ms: i64,
+    bcs: Vec<u8>,
+    parsed_json: Option<String>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = transactions)]
+struct SqliteTransaction {
+    tx_sequence_number: i64,
+    transaction_digest: Vec<u8>,
+    raw_transaction: Vec<u8>,
+    raw_effects: Vec<u8>,
+    checkpoint_sequence_number: i64,
+    timestamp_ms: i64,
+    object_changes: Vec<u8>,
+    balance_changes: Vec<u8>,
+    events: Vec<u8>,
+    transaction_kind: i16,
+    success_command_count: i16,
+}
+
+/// A writer-only store that writes the same data as `PgIndexerStore`, to a SQLite database file.
+/// SQLite supports a single writer at a time, so unlike `PgIndexerStore`, chunks of data are
+/// written one after another rather than in parallel, and epochs are not partitioned.
+#[derive(Clone)]
+pub struct SqliteIndexerStore {
+    blocking_cp: SqliteConnectionPool,
+    module_cache: Arc<SyncModuleCache<IndexerStorePackageModuleResolver>>,
+    metrics: IndexerMetrics,
+}
+
+impl SqliteIndexerStore {
+    pub fn new(blocking_cp: SqliteConnectionPool, metrics: IndexerMetrics) -> Self {
+        let module_cache: Arc<SyncModuleCache<IndexerStorePackageModuleResolver>> =
+            Arc::new(SyncModuleCache::new(
+                IndexerStorePackageModuleResolver::new_sqlite(blocking_cp.clone()),
+            ));
+
+        Self {
+            blocking_cp,
+            module_cache,
+            metrics,
+        }
+    }
+
+    pub fn blocking_cp(&self) -> SqliteConnectionPool {
+        self.blocking_cp.clone()
+    }
+
+    fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        sqlite_read_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)
+                .map(|v| v.map(|v| v as u64))
+        })
+        .context("Failed reading latest checkpoint sequence number from SQLite DB")
+    }
+
+    fn get_latest_object_snapshot_checkpoint_sequence_number(
+        &self,
+    ) -> Result<Option<u64>, IndexerError> {
+        sqlite_read_blocking!(&self.blocking_cp, |conn| {
+            objects_snapshot::table
+                .select(max(objects_snapshot::checkpoint_sequence_number))
+                .first::<Option<i64>>(conn)
+                .map(|v| v.map(|v| v as u64))
+        })
+        .context("Failed reading latest object snapshot checkpoint sequence number from SQLite DB")
+    }
+
+    // Note: here we treat Deleted as NotExists too
+    fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        sqlite_read_blocking!(&self.blocking_cp, |conn| {
+            let query = objects::table.filter(objects::object_id.eq(object_id.to_vec()));
+            let boxed_query = if let Some(version) = version {
+                query
+                    .filter(objects::object_version.eq(version.value() as i64))
+                    .into_boxed()
+            } else {
+                query.into_boxed()
+            };
+            match boxed_query.first::<StoredObject>(conn).optional()? {
+                None => Ok(ObjectRead::NotExists(object_id)),
+                Some(obj) => obj.try_into_object_read(self.module_cache.as_ref()),
+            }
+        })
+        .context("Failed to read object from SQLite DB")
+    }
+
+    fn persist_display_updates(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in display_updates
+                    .values()
+                    .collect::<Vec<_>>()
+                    .chunks(SQLITE_COMMIT_CHUNK_SIZE)
+                {
+                    diesel::insert_into(display::table)
+                        .values(chunk.to_vec())
+                        .on_conflict(display::object_type)
+                        .do_update()
+                        .set((
+                            display::id.eq(excluded(display::id)),
+                            display::version.eq(excluded(display::version)),
+                            display::bcs.eq(excluded(display::bcs)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write display updates to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_objects(&self, objects: Vec<ObjectChangeToCommit>) -> Result<(), IndexerError> {
+        let mut mutated_objects = vec![];
+        let mut deleted_object_ids = vec![];
+        for object in objects {
+            match object {
+                ObjectChangeToCommit::MutatedObject(o) => mutated_objects.push(o),
+                ObjectChangeToCommit::DeletedObject(o) => deleted_object_ids.push(o.object_id),
+            }
+        }
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in mutated_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(objects::table)
+                        .values(chunk)
+                        .on_conflict(objects::object_id)
+                        .do_update()
+                        .set((
+                            objects::object_version.eq(excluded(objects::object_version)),
+                            objects::object_digest.eq(excluded(objects::object_digest)),
+                            objects::checkpoint_sequence_number
+                                .eq(excluded(objects::checkpoint_sequence_number)),
+                            objects::owner_type.eq(excluded(objects::owner_type)),
+                            objects::owner_id.eq(excluded(objects::owner_id)),
+                            objects::object_type.eq(excluded(objects::object_type)),
+                            objects::serialized_object.eq(excluded(objects::serialized_object)),
+                            objects::coin_type.eq(excluded(objects::coin_type)),
+                            objects::coin_balance.eq(excluded(objects::coin_balance)),
+                            objects::df_kind.eq(excluded(objects::df_kind)),
+                            objects::df_name.eq(excluded(objects::df_name)),
+                            objects::df_object_type.eq(excluded(objects::df_object_type)),
+                            objects::df_object_id.eq(excluded(objects::df_object_id)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object mutation to SQLite DB")?;
+                }
+
+                for chunk in deleted_object_ids.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::delete(
+                        objects::table.filter(objects::object_id.eq_any(chunk.to_vec())),
+                    )
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write object deletion to SQLite DB")?;
+                }
+
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_object_history(
+        &self,
+        objects: Vec<ObjectChangeToCommit>,
+    ) -> Result<(), IndexerError> {
+        let mut mutated_objects: Vec<StoredHistoryObject> = vec![];
+        let mut deleted_objects: Vec<StoredDeletedHistoryObject> = vec![];
+        for object in objects {
+            match object {
+                ObjectChangeToCommit::MutatedObject(o) => mutated_objects.push(o.into()),
+                ObjectChangeToCommit::DeletedObject(o) => deleted_objects.push(o.into()),
+            }
+        }
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in mutated_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(objects_history::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object mutations to objects_history in DB.")?;
+                }
+
+                for chunk in deleted_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(objects_history::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object deletions to objects_history in DB.")?;
+                }
+
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_object_snapshot(&self, start_cp: u64, end_cp: u64) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::sql_query(UPDATE_OBJECTS_SNAPSHOT_QUERY)
+                    .bind::<diesel::sql_types::BigInt, _>(start_cp as i64)
+                    .bind::<diesel::sql_types::BigInt, _>(end_cp as i64)
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )?;
+        Ok(())
+    }
+
+    fn persist_checkpoints(&self, checkpoints: Vec<IndexedCheckpoint>) -> Result<(), IndexerError> {
+        let checkpoints = checkpoints
+            .iter()
+            .map(|c| SqliteCheckpoint::from(StoredCheckpoint::from(c)))
+            .collect::<Vec<_>>();
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in checkpoints.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(checkpoints::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write checkpoints to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        let transactions = transactions
+            .iter()
+            .map(|t| SqliteTransaction::from(StoredTransaction::from(t)))
+            .collect::<Vec<_>>();
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in transactions.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(transactions::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write transactions to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        let events = events
+            .into_iter()
+            .map(|e| SqliteEvent::from(StoredEvent::from(e)))
+            .collect::<Vec<_>>();
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in events.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(events::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write events to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_balance_changes(
+        &self,
+        balance_changes: Vec<IndexedBalanceChange>,
+    ) -> Result<(), IndexerError> {
+        let balance_changes = balance_changes
+            .into_iter()
+            .map(StoredBalanceChange::from)
+            .collect::<Vec<_>>();
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in balance_changes.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(balance_history::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write balance_history to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        let packages = packages
+            .into_iter()
+            .map(StoredPackage::from)
+            .collect::<Vec<_>>();
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in packages.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    // System packages keep their package ID across upgrades, so their modules
+                    // are overwritten.
+                    diesel::insert_into(packages::table)
+                        .values(chunk)
+                        .on_conflict(packages::package_id)
+                        .do_update()
+                        .set(packages::move_package.eq(excluded(packages::move_package)))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write packages to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        let mut senders = vec![];
+        let mut recipients = vec![];
+        let mut input_objects = vec![];
+        let mut changed_objects = vec![];
+        let mut calls = vec![];
+        for index in indices {
+            let (s, r, i, c, f) = index.split();
+            senders.extend(s);
+            recipients.extend(r);
+            input_objects.extend(i);
+            changed_objects.extend(c);
+            calls.extend(f);
+        }
+
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in senders.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(tx_senders::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_senders to SQLite DB")?;
+                }
+                for chunk in recipients.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(tx_recipients::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_recipients to SQLite DB")?;
+                }
+                for chunk in input_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(tx_input_objects::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_input_objects to SQLite DB")?;
+                }
+                for chunk in changed_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(tx_changed_objects::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_changed_objects to SQLite DB")?;
+                }
+                for chunk in calls.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(tx_calls::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_calls to SQLite DB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
+        let epoch_id = epoch.new_epoch.epoch;
+        sqlite_transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                if let Some(last_epoch) = &epoch.last_epoch {
+                    let last_epoch = StoredEpochInfo::from_epoch_end_info(last_epoch);
+                    // Note: Exclude epoch beginning info except system_state below, so that
+                    // they are not overridden with the default values in `last_epoch`.
+                    diesel::insert_into(epochs::table)
+                        .values(last_epoch)
+                        .on_conflict(epochs::epoch)
+                        .do_update()
+                        .set((
+                            epochs::system_state.eq(excluded(epochs::system_state)),
+                            epochs::epoch_total_transactions
+                                .eq(excluded(epochs::epoch_total_transactions)),
+                            epochs::last_checkpoint_id.eq(excluded(epochs::last_checkpoint_id)),
+                            epochs::epoch_end_timestamp.eq(excluded(epochs::epoch_end_timestamp)),
+                            epochs::storage_fund_reinvestment
+                                .eq(excluded(epochs::storage_fund_reinvestment)),
+                            epochs::storage_charge.eq(excluded(epochs::storage_charge)),
+                            epochs::storage_rebate.eq(excluded(epochs::storage_rebate)),
+                            epochs::stake_subsidy_amount.eq(excluded(epochs::stake_subsidy_amount)),
+                            epochs::total_gas_fees.eq(excluded(epochs::total_gas_fees)),
+                            epochs::total_stake_rewards_distributed
+                                .eq(excluded(epochs::total_stake_rewards_distributed)),
+                            epochs::leftover_storage_fund_inflow
+                                .eq(excluded(epochs::leftover_storage_fund_inflow)),
+                            epochs::epoch_commitments.eq(excluded(epochs::epoch_commitments)),
+                        ))
+                        .execute(conn)?;
+                }
+
+                let new_epoch = StoredEpochInfo::from_epoch_beginning_info(&epoch.new_epoch);
+                diesel::insert_into(epochs::table)
+                    .values(new_epoch)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!(epoch_id, "Persisted epoch beginning info"))
+    }
+
+    fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        sqlite_read_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::epoch.eq(epoch as i64))
+                .select(max(checkpoints::network_total_transactions))
+                .first::<Option<i64>>(conn)
+                .map(|o| o.unwrap_or(0))
+        })
+        .context("Failed to get network total transactions in epoch")
+        .map(|v| v as u64)
+    }
+
+    async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
+    where
+        F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let this = self.clone();
+        let current_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _guard = current_span.enter();
+            f(this)
+        })
+        .await
+        .map_err(Into::into)
+        .and_then(std::convert::identity)
+    }
+}
+
+#[async_trait]
+impl IndexerStore for SqliteIndexerStore {
+    type ModuleCache = SyncModuleCache<IndexerStorePackageModuleResolver>;
+
+    async fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_latest_tx_checkpoint_sequence_number())
+            .await
+    }
+
+    async fn get_latest_object_snapshot_checkpoint_sequence_number(
+        &self,
+    ) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(|this| {
+            this.get_latest_object_snapshot_checkpoint_sequence_number()
+        })
+        .await
+    }
+
+    async fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_object_read(object_id, version))
+            .await
+    }
+
+    async fn persist_objects(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        if object_changes.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_objects
+            .start_timer();
+        let objects = make_final_list_of_objects_to_commit(object_changes);
+        let len = objects.len();
+        self.execute_in_blocking_worker(move |this| this.persist_objects(objects))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} objects", len);
+        Ok(())
+    }
+
+    async fn persist_object_history(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        if object_changes.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_objects_history
+            .start_timer();
+        let objects = make_objects_history_to_commit(object_changes);
+        let len = objects.len();
+        self.execute_in_blocking_worker(move |this| this.persist_object_history(objects))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} objects history", len);
+        Ok(())
+    }
+
+    async fn persist_object_snapshot(
+        &self,
+        start_cp: u64,
+        end_cp: u64,
+    ) -> Result<(), IndexerError> {
+        let guard = self.metrics.update_object_snapshot_latency.start_timer();
+        self.execute_in_blocking_worker(move |this| this.persist_object_snapshot(start_cp, end_cp))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(
+            elapsed,
+            "Persisted snapshot for checkpoints from {} to {}", start_cp, end_cp
+        );
+        Ok(())
+    }
+
+    async fn persist_checkpoints(
+        &self,
+        checkpoints: Vec<IndexedCheckpoint>,
+    ) -> Result<(), IndexerError> {
+        if checkpoints.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_checkpoints
+            .start_timer();
+        let len = checkpoints.len();
+        self.execute_in_blocking_worker(move |this| this.persist_checkpoints(checkpoints))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} checkpoints", len);
+        Ok(())
+    }
+
+    async fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_transactions
+            .start_timer();
+        let len = transactions.len();
+        self.execute_in_blocking_worker(move |this| this.persist_transactions(transactions))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} transactions", len);
+        Ok(())
+    }
+
+    async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_tx_indices
+            .start_timer();
+        let len = indices.len();
+        self.execute_in_blocking_worker(move |this| this.persist_tx_indices(indices))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} tx_indices", len);
+        Ok(())
+    }
+
+    async fn persist_balance_changes(
+        &self,
+        balance_changes: Vec<IndexedBalanceChange>,
+    ) -> Result<(), IndexerError> {
+        if balance_changes.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_balance_changes
+            .start_timer();
+        let len = balance_changes.len();
+        self.execute_in_blocking_worker(move |this| this.persist_balance_changes(balance_changes))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} balance changes", len);
+        Ok(())
+    }
+
+    async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_events
+            .start_timer();
+        let len = events.len();
+        self.execute_in_blocking_worker(move |this| this.persist_events(events))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} events", len);
+        Ok(())
+    }
+
+    async fn persist_displays(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        if display_updates.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_display_updates(display_updates))
+            .await
+    }
+
+    async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_packages
+            .start_timer();
+        let len = packages.len();
+        self.execute_in_blocking_worker(move |this| this.persist_packages(packages))
+            .await?;
+        let elapsed = guard.stop_and_record();
+        info!(elapsed, "Persisted {} packages", len);
+        Ok(())
+    }
+
+    async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_epoch
+            .start_timer();
+        self.execute_in_blocking_worker(move |this| this.persist_epoch(epoch))
+            .await?;
+        guard.stop_and_record();
+        Ok(())
+    }
+
+    async fn advance_epoch(&self, _epoch: EpochToCommit) -> Result<(), IndexerError> {
+        // Tables are not partitioned by epoch in SQLite, so there is nothing to advance.
+        Ok(())
+    }
+
+    async fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.get_network_total_transactions_by_end_of_epoch(epoch)
+        })
+        .await
+    }
+
+    fn module_cache(&self) -> Arc<Self::ModuleCache> {
+        self.module_cache.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// BCS-serialize the contents of a Postgres array column, to store in a SQLite blob column.
+fn array_to_blob(array: Vec<Option<Vec<u8>>>) -> Vec<u8> {
+    bcs::to_bytes(&array).expect("Serializing byte arrays cannot fail")
+}
+
+impl From<StoredCheckpoint> for SqliteCheckpoint {
+    fn from(c: StoredCheckpoint) -> Self {
+        Self {
+            sequence_number: c.sequence_number,
+            checkpoint_digest: c.checkpoint_digest,
+            epoch: c.epoch,
+            network_total_transactions: c.network_total_transactions,
+            previous_checkpoint_digest: c.previous_checkpoint_digest,
+            end_of_epoch: c.end_of_epoch,
+            tx_digests: array_to_blob(c.tx_digests),
+            timestamp_ms: c.timestamp_ms,
+            total_gas_cost: c.total_gas_cost,
+            computation_cost: c.computation_cost,
+            storage_cost: c.storage_cost,
+            storage_rebate: c.storage_rebate,
+            non_refundable_storage_fee: c.non_refundable_storage_fee,
+            checkpoint_commitments: c.checkpoint_commitments,
+            validator_signature: c.validator_signature,
+            end_of_epoch_data: c.end_of_epoch_data,
+        }
+    }
+}
+
+impl From<StoredEvent> for SqliteEvent {
+    fn from(e: StoredEvent) -> Self {
+        Self {
+            tx_sequence_number: e.tx_sequence_number,
+            event_sequence_number: e.event_sequence_number,
+            transaction_digest: e.transaction_digest,
+            checkpoint_sequence_number: e.checkpoint_sequence_number,
+            senders: array_to_blob(e.senders),
+            package: e.package,
+            module: e.module,
+            event_type: e.event_type,
+            timestamp_ms: e.timestamp_ms,
+            bcs: e.bcs,
+            parsed_json: e.parsed_json.map(|json| json.to_string()),
+        }
+    }
+}
+
+impl From<StoredTransaction> for SqliteTransaction {
+    fn from(t: StoredTransaction) -> Self {
+        Self {
+            tx_sequence_number: t.tx_sequence_number,
+            transaction_digest: t.transaction_digest,
+            raw_transaction: t.raw_transaction,
+            raw_effects: t.raw_effects,
+            checkpoint_sequence_number: t.checkpoint_sequence_number,
+            timestamp_ms: t.timestamp_ms,
+            object_changes: array_to_blob(t.object_changes),
+            balance_changes: array_to_blob(t.balance_changes),
+            events: array_to_blob(t.events),
+            transaction_kind: t.transaction_kind,
+            success_command_count: t.success_command_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use sui_types::base_types::SuiAddress;
+
+    use crate::db::{
+        get_sqlite_pool_connection, new_sqlite_connection_pool, setup_sqlite_database,
+    };
+
+    use super::*;
+
+    fn store(dir: &tempfile::TempDir) -> SqliteIndexerStore {
+        let path = dir.path().join("indexer.db");
+        let pool = new_sqlite_connection_pool(path.to_str().unwrap(), None).unwrap();
+        let mut conn = get_sqlite_pool_connection(&pool).unwrap();
+        setup_sqlite_database(&mut conn, /* reset */ false).unwrap();
+        SqliteIndexerStore::new(pool, IndexerMetrics::new(&Registry::default()))
+    }
+
+    #[tokio::test]
+    async fn test_read_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        assert_eq!(
+            store
+                .get_latest_tx_checkpoint_sequence_number()
+                .await
+                .unwrap(),
+            None
+        );
+
+        let checkpoints: Vec<_> = (0..3)
+            .map(|sequence_number| {
+                SqliteCheckpoint::from(StoredCheckpoint {
+                    sequence_number,
+                    network_total_transactions: sequence_number * 2,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let mut conn = get_sqlite_pool_connection(&store.blocking_cp()).unwrap();
+        diesel::insert_into(checkpoints::table)
+            .values(&checkpoints)
+            .execute(&mut *conn)
+            .unwrap();
+
+        // Writing the same checkpoints again is a no-op.
+        diesel::insert_into(checkpoints::table)
+            .values(&checkpoints)
+            .on_conflict_do_nothing()
+            .execute(&mut *conn)
+            .unwrap();
+
+        assert_eq!(
+            store
+                .get_latest_tx_checkpoint_sequence_number()
+                .await
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            store
+                .get_network_total_transactions_by_end_of_epoch(0)
+                .await
+                .unwrap(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_balance_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+
+        let owner = SuiAddress::random_for_testing_only();
+        let change = |checkpoint_sequence_number, amount| IndexedBalanceChange {
+            owner,
+            coin_type: "0x2::sui::SUI".to_string(),
+            checkpoint_sequence_number,
+            amount,
+        };
+
+        // Changes to a u64 balance do not always fit in an i64.
+        let changes = vec![change(0, u64::MAX as i128), change(3, -(u64::MAX as i128))];
+        store
+            .persist_balance_changes(changes.clone())
+            .await
+            .unwrap();
+
+        // Writing the same changes again is a no-op.
+        store.persist_balance_changes(changes).await.unwrap();
+
+        let mut conn = get_sqlite_pool_connection(&store.blocking_cp()).unwrap();
+        let stored: Vec<StoredBalanceChange> = balance_history::table
+            .filter(balance_history::owner_id.eq(owner.to_vec()))
+            .order_by(balance_history::checkpoint_sequence_number)
+            .load(&mut *conn)
+            .unwrap();
+
+        let stored: Vec<_> = stored
+            .into_iter()
+            .map(|c| (c.checkpoint_sequence_number, c.amount))
+            .collect();
+        assert_eq!(
+            stored,
+            vec![(0, u64::MAX.to_string()), (3, format!("-{}", u64::MAX)),]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrations_are_reversible() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+
+        let mut conn = get_sqlite_pool_connection(&store.blocking_cp()).unwrap();
+        setup_sqlite_database(&mut conn, /* reset */ true).unwrap();
+        setup_sqlite_database(&mut conn, /* reset */ false).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use crate::errors::IndexerError;
 use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
@@ -177,6 +179,8 @@ pub struct IndexedEvent {
     pub event_type: String,
     pub bcs: Vec<u8>,
     pub timestamp_ms: u64,
+    /// The event contents decoded with the event type's layout, if it could be resolved.
+    pub parsed_json: Option<serde_json::Value>,
 }
 
 impl IndexedEvent {
@@ -199,6 +203,7 @@ impl IndexedEvent {
             event_type: event.type_.to_canonical_string(/* with_prefix */ true),
             bcs: event.contents.clone(),
             timestamp_ms,
+            parsed_json: None,
         }
     }
 }
@@ -325,6 +330,45 @@ pub struct IndexedPackage {
     pub checkpoint_sequence_number: u64,
 }
 
+/// Net change in `owner`'s balance of `coin_type` over all the transactions in a checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedBalanceChange {
+    pub owner: SuiAddress,
+    /// Canonical coin type, with a `0x` prefix.
+    pub coin_type: String,
+    pub checkpoint_sequence_number: u64,
+    pub amount: i128,
+}
+
+impl IndexedBalanceChange {
+    /// Aggregate the balance changes of the `transactions` in a checkpoint by owner and coin
+    /// type. Only changes to address-owned balances are kept, and changes that net out to zero
+    /// over the checkpoint are dropped.
+    pub fn from_transactions(
+        checkpoint_sequence_number: u64,
+        transactions: &[IndexedTransaction],
+    ) -> Vec<Self> {
+        let mut net: BTreeMap<(SuiAddress, String), i128> = BTreeMap::new();
+        for change in transactions.iter().flat_map(|tx| &tx.balance_change) {
+            let Owner::AddressOwner(owner) = change.owner else {
+                continue;
+            };
+            let coin_type = change.coin_type.to_canonical_string(/* with_prefix */ true);
+            *net.entry((owner, coin_type)).or_default() += change.amount;
+        }
+
+        net.into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((owner, coin_type), amount)| Self {
+                owner,
+                coin_type,
+                checkpoint_sequence_number,
+                amount,
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum TransactionKind {
     SystemTransaction = 0,
@@ -629,3 +673,94 @@ impl From<SuiTransactionBlockResponseWithOptions> for SuiTransactionBlockRespons
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::language_storage::TypeTag;
+    use sui_json_rpc_types::BalanceChange;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+    use sui_types::gas_coin::GAS;
+    use sui_types::parse_sui_type_tag;
+    use sui_types::transaction::{Transaction, TransactionData};
+
+    use super::*;
+
+    fn transaction(balance_change: Vec<BalanceChange>) -> IndexedTransaction {
+        let (sender, key): (_, AccountKeyPair) = get_key_pair();
+        let data = TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            None,
+            random_object_ref(),
+            1_000_000,
+            1_000,
+        );
+        let transaction = Transaction::from_data_and_signer(data, vec![&key]);
+        IndexedTransaction {
+            tx_sequence_number: 0,
+            tx_digest: *transaction.digest(),
+            sender_signed_data: transaction.data().clone(),
+            effects: TransactionEffects::default(),
+            checkpoint_sequence_number: 1,
+            timestamp_ms: 0,
+            object_changes: vec![],
+            balance_change,
+            events: vec![],
+            transaction_kind: TransactionKind::ProgrammableTransaction,
+            successful_tx_num: 1,
+        }
+    }
+
+    #[test]
+    fn test_balance_changes_from_transactions() {
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+        let sui = GAS::type_tag();
+        let coin = parse_sui_type_tag("0xabc::coin::COIN").unwrap();
+        let change = |owner, coin_type: &TypeTag, amount| BalanceChange {
+            owner,
+            coin_type: coin_type.clone(),
+            amount,
+        };
+
+        let transactions = vec![
+            transaction(vec![
+                change(Owner::AddressOwner(alice), &sui, u64::MAX as i128),
+                change(Owner::AddressOwner(alice), &coin, 10),
+                change(Owner::AddressOwner(bob), &sui, 5),
+            ]),
+            transaction(vec![
+                // Changes to the same balance in a checkpoint are summed, even beyond a u64.
+                change(Owner::AddressOwner(alice), &sui, u64::MAX as i128),
+                // Changes that net out to zero over the checkpoint are dropped.
+                change(Owner::AddressOwner(bob), &sui, -5),
+                // Only address-owned balances are kept.
+                change(Owner::ObjectOwner(bob), &sui, 7),
+                change(Owner::Immutable, &coin, 3),
+            ]),
+        ];
+
+        let mut expect = vec![
+            IndexedBalanceChange {
+                owner: alice,
+                coin_type: sui.to_canonical_string(/* with_prefix */ true),
+                checkpoint_sequence_number: 42,
+                amount: 2 * u64::MAX as i128,
+            },
+            IndexedBalanceChange {
+                owner: alice,
+                coin_type: coin.to_canonical_string(/* with_prefix */ true),
+                checkpoint_sequence_number: 42,
+                amount: 10,
+            },
+        ];
+        expect.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+
+        assert_eq!(
+            IndexedBalanceChange::from_transactions(42, &transactions),
+            expect
+        );
+        assert_eq!(IndexedBalanceChange::from_transactions(42, &[]), vec![]);
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! SQLite is only supported by the writer, so these tests run the fullnode sync worker against a
+//! SQLite database file, and read the indexed data back through the store.
+
+#[cfg(feature = "sqlite")]
+mod sqlite_ingestion_tests {
+    use diesel::sql_types::{BigInt, Binary};
+    use diesel::{QueryableByName, RunQueryDsl};
+    use prometheus::Registry;
+    use simulacrum::Simulacrum;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use sui_indexer::db::{
+        get_sqlite_pool_connection, new_sqlite_connection_pool, setup_sqlite_database,
+    };
+    use sui_indexer::errors::IndexerError;
+    use sui_indexer::indexer::Indexer;
+    use sui_indexer::indexer_reader::IndexerReader;
+    use sui_indexer::store::{indexer_store::IndexerStore, SqliteIndexerStore};
+    use sui_indexer::{IndexerConfig, IndexerMetrics};
+    use sui_types::base_types::SuiAddress;
+    use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::object::{ObjectRead, Owner};
+    use sui_types::storage::ReadStore;
+    use tempfile::TempDir;
+    use tokio::task::JoinHandle;
+
+    const DEFAULT_SERVER_PORT: u16 = 3001;
+
+    #[derive(QueryableByName)]
+    struct Count {
+        #[diesel(sql_type = BigInt)]
+        count: i64,
+    }
+
+    /// Set up a test indexer writing to a SQLite database in `dir`, fetching from a REST endpoint
+    /// served by the given Simulacrum.
+    async fn set_up(
+        sim: Arc<Simulacrum>,
+        dir: &TempDir,
+    ) -> (
+        JoinHandle<()>,
+        SqliteIndexerStore,
+        JoinHandle<Result<(), IndexerError>>,
+    ) {
+        let server_url: SocketAddr = format!("127.0.0.1:{}", DEFAULT_SERVER_PORT)
+            .parse()
+            .unwrap();
+
+        let server_handle = tokio::spawn(async move {
+            let chain_id = (*sim
+                .get_checkpoint_by_sequence_number(0)
+                .unwrap()
+                .unwrap()
+                .digest())
+            .into();
+
+            sui_rest_api::RestService::new_without_version(sim, chain_id)
+                .start_service(server_url, Some("/rest".to_owned()))
+                .await;
+        });
+
+        let path = dir.path().join("indexer.db");
+        let db_url = format!("sqlite://{}", path.display());
+        let config = IndexerConfig {
+            db_url: Some(db_url),
+            rpc_client_url: format!("http://{}", server_url),
+            reset_db: true,
+            fullnode_sync_worker: true,
+            rpc_server_worker: false,
+            ..Default::default()
+        };
+
+        let pool = new_sqlite_connection_pool(path.to_str().unwrap(), None).unwrap();
+        let mut conn = get_sqlite_pool_connection(&pool).unwrap();
+        setup_sqlite_database(&mut conn, config.reset_db).unwrap();
+        drop(conn);
+
+        let metrics = IndexerMetrics::new(&Registry::default());
+        let store = SqliteIndexerStore::new(pool, metrics.clone());
+        let store_clone = store.clone();
+        let indexer_handle =
+            tokio::spawn(async move { Indexer::start_writer(&config, store_clone, metrics).await });
+
+        (server_handle, store, indexer_handle)
+    }
+
+    /// Wait for the indexer to catch up to the given checkpoint sequence number.
+    async fn wait_for_checkpoint(store: &SqliteIndexerStore, checkpoint_sequence_number: u64) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while {
+                let cp_opt = store
+                    .get_latest_tx_checkpoint_sequence_number()
+                    .await
+                    .unwrap();
+                cp_opt.is_none() || (cp_opt.unwrap() < checkpoint_sequence_number)
+            } {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        })
+        .await
+        .expect("Timeout waiting for indexer to catchup to checkpoint");
+    }
+
+    fn count(store: &SqliteIndexerStore, table: &str, column: &str, value: Vec<u8>) -> i64 {
+        let mut conn = get_sqlite_pool_connection(&store.blocking_cp()).unwrap();
+        diesel::sql_query(format!(
+            "SELECT COUNT(*) AS count FROM {table} WHERE {column} = ?"
+        ))
+        .bind::<Binary, _>(value)
+        .get_result::<Count>(&mut *conn)
+        .unwrap()
+        .count
+    }
+
+    #[tokio::test]
+    pub async fn test_index_transfer() {
+        let mut sim = Simulacrum::new();
+
+        // Execute a simple transaction.
+        let transfer_recipient = SuiAddress::random_for_testing_only();
+        let (transaction, _) = sim.transfer_txn(transfer_recipient);
+        let (effects, err) = sim.execute_transaction(transaction).unwrap();
+        assert!(err.is_none());
+
+        // Create a checkpoint which should include the transaction we executed.
+        let checkpoint = sim.create_checkpoint();
+
+        let dir = tempfile::tempdir().unwrap();
+        let (_, store, _) = set_up(Arc::new(sim), &dir).await;
+        wait_for_checkpoint(&store, 1).await;
+
+        let digest = effects.transaction_digest();
+        assert_eq!(
+            count(
+                &store,
+                "checkpoints",
+                "checkpoint_digest",
+                checkpoint.digest().inner().to_vec(),
+            ),
+            1
+        );
+        assert_eq!(
+            count(
+                &store,
+                "transactions",
+                "transaction_digest",
+                digest.inner().to_vec(),
+            ),
+            1
+        );
+
+        // The coin sent to the recipient is readable from the live object set.
+        let ((coin_id, version, _), _) = effects.created()[0];
+        let ObjectRead::Exists(object_ref, object, _) =
+            store.get_object_read(coin_id, None).await.unwrap()
+        else {
+            panic!("Transferred coin {coin_id} was not indexed");
+        };
+        assert_eq!(object_ref.1, version);
+        assert_eq!(object.owner, Owner::AddressOwner(transfer_recipient));
+    }
+
+    #[test]
+    fn test_reader_rejects_sqlite() {
+        let Err(err) = IndexerReader::new("sqlite://indexer.db") else {
+            panic!("Reader should not open a SQLite database");
+        };
+
+        assert!(err.to_string().contains("only supports Postgres"), "{err}");
+    }
+}
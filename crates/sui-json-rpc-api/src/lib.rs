@@ -300,3 +300,6 @@ pub const CLIENT_TARGET_API_VERSION_HEADER: &str = "client-target-api-version";
 
 pub const TRANSIENT_ERROR_CODE: i32 = -32050;
 pub const TRANSACTION_EXECUTION_CLIENT_ERROR_CODE: i32 = -32002;
+/// Returned in place of a batch response when the batch contains more requests than the server
+/// accepts.
+pub const BATCH_TOO_LARGE_ERROR_CODE: i32 = -32010;
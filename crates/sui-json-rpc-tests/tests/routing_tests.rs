@@ -2,19 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
-use hyper::header::HeaderValue;
+use flate2::read::GzDecoder;
+use hyper::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING};
 use hyper::HeaderMap;
 use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::BatchRequestBuilder;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClientBuilder;
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::INVALID_REQUEST_CODE;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use serde_json::json;
 use std::env;
+use std::io::Read;
 use sui_config::local_ip_utils;
+use sui_json_rpc::axum_router::{DEFAULT_MAX_BATCH_SIZE, MIN_COMPRESSION_SIZE};
 use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule};
-use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
+use sui_json_rpc_api::{BATCH_TOO_LARGE_ERROR_CODE, CLIENT_TARGET_API_VERSION_HEADER};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 
@@ -131,72 +137,186 @@ async fn test_disable_routing() {
     assert!(response.is_err());
 }
 
-// TODO(chris): clean up this after March 27th, 2023
-// #[tokio::test]
-// async fn test_rpc_backward_compatibility_batched_request() {
-//     let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
-//     builder.register_module(TestApiModule).unwrap();
-
-//     let port = get_available_port("0.0.0.0");
-//     let handle = builder
-//         .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
-//         .await
-//         .unwrap();
-//     let url = format!("http://0.0.0.0:{}", port);
-
-//     // Test with un-versioned client
-//     let client = HttpClientBuilder::default().build(&url).unwrap();
-
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(3, response.num_successful_calls());
-
-//     // try to access old method directly should fail
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo_1_5", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(2, response.num_successful_calls());
-
-//     // One malformed request shouldn't fail the whole batch
-//     let client = Client::new();
-//     let response = client
-//         .post(format!("http://127.0.0.1:{}/", port))
-//         .json(&vec![
-//             json!(&Request {
-//                 jsonrpc: Default::default(),
-//                 id: Id::Number(1),
-//                 method: "test_foo".into(),
-//                 params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
-//             }),
-//             json!("Bad json input"),
-//         ])
-//         .send()
-//         .await
-//         .unwrap();
-
-//     let responses = response.text().await.unwrap();
-//     let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
-
-//     // Should have 2 results
-//     assert_eq!(2, responses.len());
-
-//     // First response should success
-//     let response = serde_json::from_str::<Response<String>>(responses[0].get());
-//     assert!(matches!(response, Ok(result) if result.result == "Some string"));
-
-//     // Second response should fail
-//     let response = serde_json::from_str::<ErrorResponse>(responses[1].get());
-//     assert!(matches!(response, Ok(result) if result.error_object().message() == "Invalid request"));
-
-//     handle.stop().unwrap()
-// }
+#[tokio::test]
+async fn test_batched_requests() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(3, response.num_successful_calls());
+
+    // Try to access old method directly should fail, without failing the rest of the batch
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo_1_5", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(2, response.num_successful_calls());
+
+    // One malformed request shouldn't fail the whole batch
+    let responses = post_json(
+        &url,
+        json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]},
+            {"jsonrpc": "2.0", "id": 2},
+        ]),
+    )
+    .await;
+
+    let responses = responses.as_array().unwrap();
+    assert_eq!(2, responses.len());
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"], "Some string");
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST_CODE);
+
+    // Empty batches are invalid
+    let response = post_json(&url, json!([])).await;
+    assert_eq!(response["error"]["code"], INVALID_REQUEST_CODE);
+
+    // Batches over the size limit are rejected as a whole
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]});
+    let response = post_json(&url, json!(vec![request; DEFAULT_MAX_BATCH_SIZE + 1])).await;
+    assert_eq!(response["error"]["code"], BATCH_TOO_LARGE_ERROR_CODE);
+}
+
+#[tokio::test]
+async fn test_notifications() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let notification = json!({"jsonrpc": "2.0", "method": "test_foo", "params": [true]});
+
+    // Notifications in a batch are not answered
+    let responses = post_json(
+        &url,
+        json!([
+            notification,
+            {"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]},
+            notification,
+        ]),
+    )
+    .await;
+    let responses = responses.as_array().unwrap();
+    assert_eq!(1, responses.len());
+    assert_eq!(responses[0]["id"], 1);
+
+    // Neither are batches of only notifications, nor a single notification
+    assert_eq!(
+        "",
+        post_text(&url, json!([notification, notification])).await
+    );
+    assert_eq!("", post_text(&url, notification).await);
+}
+
+#[tokio::test]
+async fn test_max_batch_size() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_max_batch_size(2);
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]});
+
+    let responses = post_json(&url, json!(vec![request.clone(); 2])).await;
+    assert_eq!(2, responses.as_array().unwrap().len());
+
+    let response = post_json(&url, json!(vec![request; 3])).await;
+    assert_eq!(response["error"]["code"], BATCH_TOO_LARGE_ERROR_CODE);
+}
+
+#[tokio::test]
+async fn test_response_compression() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+
+    // A batch large enough for its response to be worth compressing.
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]});
+    let batch = json!(vec![request; DEFAULT_MAX_BATCH_SIZE]);
+    let client = reqwest::Client::new();
+    let send = |accept_encoding: Option<&'static str>| {
+        let mut request = client.post(&url).json(&batch);
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(ACCEPT_ENCODING, accept_encoding);
+        }
+        request.send()
+    };
+
+    let response = send(None).await.unwrap();
+    assert!(response.headers().get(CONTENT_ENCODING).is_none());
+    let uncompressed = response.bytes().await.unwrap();
+    assert!(uncompressed.len() >= MIN_COMPRESSION_SIZE);
+
+    let response = send(Some("gzip, zstd")).await.unwrap();
+    assert_eq!(response.headers()[CONTENT_ENCODING], "zstd");
+    let body = response.bytes().await.unwrap();
+    assert!(body.len() < uncompressed.len());
+    assert_eq!(zstd::decode_all(body.as_ref()).unwrap(), uncompressed);
+
+    let response = send(Some("gzip")).await.unwrap();
+    assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+    let body = response.bytes().await.unwrap();
+    let mut decoded = Vec::new();
+    GzDecoder::new(body.as_ref())
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, uncompressed);
+
+    // Small responses are not compressed
+    let response = client
+        .post(&url)
+        .header(ACCEPT_ENCODING, "gzip, zstd")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get(CONTENT_ENCODING).is_none());
+}
+
+async fn post_text(url: &str, body: serde_json::Value) -> String {
+    reqwest::Client::new()
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap()
+}
+
+async fn post_json(url: &str, body: serde_json::Value) -> serde_json::Value {
+    reqwest::Client::new()
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
 
 #[open_rpc(namespace = "test")]
 #[rpc(server, client, namespace = "test")]
@@ -26,6 +26,10 @@ use sui_types::sui_serde::SuiStructTag;
 #[cfg(any(feature = "test-utils", test))]
 use std::str::FromStr;
 
+#[cfg(test)]
+#[path = "unit_tests/sui_event_tests.rs"]
+mod sui_event_tests;
+
 pub type EventPage = Page<SuiEvent, EventID>;
 
 #[serde_as]
@@ -248,6 +252,9 @@ pub enum EventFilter {
         path: String,
         value: Value,
     },
+    /// Return events whose decoded Move fields (their `parsedJson`) satisfy a predicate, e.g.
+    /// `{ "path": "/amount_in", "gte": "1000000" }`.
+    MoveEventFieldPredicate(EventFieldPredicate),
     /// Return events emitted in [start_time, end_time] interval
     #[serde(rename_all = "camelCase")]
     TimeRange {
@@ -274,6 +281,7 @@ impl EventFilter {
             EventFilter::MoveEventField { path, value } => {
                 matches!(item.parsed_json.pointer(path), Some(v) if v == value)
             }
+            EventFilter::MoveEventFieldPredicate(predicate) => predicate.matches(&item.parsed_json),
             EventFilter::Sender(sender) => &item.sender == sender,
             EventFilter::Package(object_id) => &item.package_id == object_id,
             EventFilter::MoveModule { package, module } => {
@@ -313,6 +321,99 @@ impl EventFilter {
     }
 }
 
+/// A predicate on one field of a decoded Move event.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EventFieldPredicate {
+    /// JSON Pointer (RFC 6901) to the field in the event's `parsedJson`. Fields of nested structs
+    /// and elements of vectors are reached by adding more segments, e.g. `/pool/coins/0`.
+    pub path: String,
+    #[serde(flatten)]
+    pub comparison: EventFieldComparison,
+}
+
+/// How the field that an `EventFieldPredicate` points to is compared.
+///
+/// Numeric comparisons treat the field as an unsigned integer, which can be a JSON number or a
+/// string of decimal digits (Move `u64`, `u128` and `u256` values are rendered as strings). They
+/// never match fields that are not integers.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EventFieldComparison {
+    /// The field is equal to the given JSON value.
+    Eq(Value),
+    /// The field is an integer greater than the given integer.
+    Gt(Value),
+    /// The field is an integer greater than or equal to the given integer.
+    Gte(Value),
+    /// The field is an integer less than the given integer.
+    Lt(Value),
+    /// The field is an integer less than or equal to the given integer.
+    Lte(Value),
+    /// The field is equal to one of the given JSON values.
+    In(Vec<Value>),
+    /// The field is a string that starts with the given prefix.
+    StartsWith(String),
+}
+
+impl EventFieldPredicate {
+    pub fn matches(&self, parsed_json: &Value) -> bool {
+        let Some(field) = parsed_json.pointer(&self.path) else {
+            return false;
+        };
+
+        use EventFieldComparison as C;
+        match &self.comparison {
+            C::Eq(value) => field == value,
+            C::In(values) => values.contains(field),
+            C::StartsWith(prefix) => matches!(field, Value::String(s) if s.starts_with(prefix)),
+            C::Gt(bound) => compare_integers(field, bound).is_some_and(|o| o.is_gt()),
+            C::Gte(bound) => compare_integers(field, bound).is_some_and(|o| o.is_ge()),
+            C::Lt(bound) => compare_integers(field, bound).is_some_and(|o| o.is_lt()),
+            C::Lte(bound) => compare_integers(field, bound).is_some_and(|o| o.is_le()),
+        }
+    }
+
+    /// The names of the fields (or indices of the vector elements) along `path`, with JSON
+    /// Pointer escapes removed. Returns `None` if `path` is not a valid JSON Pointer.
+    pub fn path_segments(&self) -> Option<Vec<String>> {
+        if self.path.is_empty() {
+            return Some(vec![]);
+        }
+
+        let segments = self.path.strip_prefix('/')?;
+        Some(
+            segments
+                .split('/')
+                .map(|s| s.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+        )
+    }
+}
+
+/// Normalizes `value` into a string of decimal digits with no leading zeros, if it is an unsigned
+/// integer: a JSON number, or a string of decimal digits (how large Move integers are rendered).
+pub fn unsigned_integer(value: &Value) -> Option<String> {
+    let digits = match value {
+        Value::Number(n) => return n.as_u64().map(|n| n.to_string()),
+        Value::String(s) => s.as_str(),
+        _ => return None,
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let trimmed = digits.trim_start_matches('0');
+    Some(if trimmed.is_empty() { "0" } else { trimmed }.to_string())
+}
+
+/// Compares two unsigned integers of arbitrary size, as long as both of them are integers.
+fn compare_integers(lhs: &Value, rhs: &Value) -> Option<std::cmp::Ordering> {
+    let lhs = unsigned_integer(lhs)?;
+    let rhs = unsigned_integer(rhs)?;
+    Some(lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(&rhs)))
+}
+
 impl Filter<SuiEvent> for EventFilter {
     fn matches(&self, item: &SuiEvent) -> bool {
         let _scope = monitored_scope("EventFilter::matches");
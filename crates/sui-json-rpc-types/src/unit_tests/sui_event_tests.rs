@@ -0,0 +1,101 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::json;
+
+use crate::{EventFieldComparison as C, EventFieldPredicate, EventFilter, Filter, SuiEvent};
+
+fn swap_event() -> SuiEvent {
+    SuiEvent {
+        parsed_json: json!({
+            "amount_in": "1500000",
+            "amount_out": 42,
+            "pool": {
+                "coin_type": "0x2::sui::SUI",
+                "fees": ["10", "20"],
+            },
+            "a/b": true,
+        }),
+        ..SuiEvent::random_for_testing()
+    }
+}
+
+fn predicate(path: &str, comparison: C) -> EventFilter {
+    EventFilter::MoveEventFieldPredicate(EventFieldPredicate {
+        path: path.to_string(),
+        comparison,
+    })
+}
+
+#[test]
+fn test_event_field_predicates() {
+    let event = swap_event();
+
+    let matching = [
+        predicate("/amount_in", C::Gt(json!("1000000"))),
+        predicate("/amount_in", C::Gte(json!(1500000))),
+        predicate(
+            "/amount_in",
+            C::Lt(json!("000000000000000000000000000000001500001")),
+        ),
+        predicate("/amount_out", C::Lte(json!("42"))),
+        predicate("/amount_out", C::Eq(json!(42))),
+        predicate("/pool/coin_type", C::StartsWith("0x2::".to_string())),
+        predicate("/pool/fees/1", C::In(vec![json!("10"), json!("20")])),
+        predicate("/a~1b", C::Eq(json!(true))),
+    ];
+
+    for filter in &matching {
+        assert!(filter.matches(&event), "{filter:?}");
+    }
+
+    let not_matching = [
+        predicate("/amount_in", C::Gt(json!(1500000))),
+        predicate("/amount_in", C::Lt(json!("1000"))),
+        // The field and the bound must both be integers.
+        predicate("/pool/coin_type", C::Gt(json!(0))),
+        predicate("/amount_in", C::Gt(json!(-1))),
+        predicate("/amount_in", C::Gt(json!("1e3"))),
+        // Equality is on JSON values, so numbers and strings are different.
+        predicate("/amount_out", C::Eq(json!("42"))),
+        predicate("/amount_out", C::StartsWith("4".to_string())),
+        predicate("/pool/fees/2", C::In(vec![json!("10"), json!("20")])),
+        predicate("/missing", C::Eq(json!(null))),
+    ];
+
+    for filter in &not_matching {
+        assert!(!filter.matches(&event), "{filter:?}");
+    }
+
+    // Predicates combine with other event filters.
+    assert!(EventFilter::All(matching.to_vec()).matches(&event));
+    assert!(!EventFilter::All(vec![matching[0].clone(), not_matching[0].clone()]).matches(&event));
+}
+
+#[test]
+fn test_event_field_predicate_serde() {
+    let filter: EventFilter = serde_json::from_value(json!({
+        "MoveEventFieldPredicate": { "path": "/pool/fees/0", "startsWith": "1" }
+    }))
+    .unwrap();
+
+    let EventFilter::MoveEventFieldPredicate(predicate) = &filter else {
+        panic!("Unexpected filter: {filter:?}");
+    };
+
+    assert_eq!(predicate.comparison, C::StartsWith("1".to_string()));
+    assert_eq!(
+        predicate.path_segments(),
+        Some(vec![
+            "pool".to_string(),
+            "fees".to_string(),
+            "0".to_string()
+        ])
+    );
+
+    let predicate = EventFieldPredicate {
+        path: "amount".to_string(),
+        comparison: C::Eq(json!(0)),
+    };
+    assert_eq!(predicate.path_segments(), None);
+}
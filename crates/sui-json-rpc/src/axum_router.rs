@@ -1,12 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::io::Write;
 use std::sync::Arc;
 
 use axum::extract::Json;
 use axum::extract::State;
+use flate2::{write::GzEncoder, Compression};
 use futures::StreamExt;
+use hyper::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, VARY};
 use hyper::HeaderMap;
+use itertools::Itertools;
 use jsonrpsee::core::server::helpers::BoundedSubscriptions;
 use jsonrpsee::core::server::helpers::MethodResponse;
 use jsonrpsee::core::server::helpers::MethodSink;
@@ -14,15 +18,27 @@ use jsonrpsee::core::server::rpc_module::MethodKind;
 use jsonrpsee::server::logger::{self, TransportProtocol};
 use jsonrpsee::server::RandomIntegerIdProvider;
 use jsonrpsee::types::error::{ErrorCode, BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG};
-use jsonrpsee::types::{ErrorObject, Id, InvalidRequest, Params, Request};
+use jsonrpsee::types::{ErrorObject, Id, InvalidRequest, Notification, Params, Request};
 use jsonrpsee::{core::server::rpc_module::Methods, server::logger::Logger};
 use serde_json::value::RawValue;
+use tracing::warn;
 
+use crate::metrics::HttpMetrics;
 use crate::routing_layer::RpcRouter;
-use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
+use sui_json_rpc_api::{BATCH_TOO_LARGE_ERROR_CODE, CLIENT_TARGET_API_VERSION_HEADER};
 
 pub const MAX_RESPONSE_SIZE: u32 = 2 << 30;
 
+/// Default limit on the number of requests in a single JSON-RPC batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
+/// Maximum number of requests from a single batch that are processed at the same time.
+pub const MAX_CONCURRENT_BATCH_REQUESTS: usize = 10;
+
+/// Responses smaller than this are sent uncompressed, as there is little to gain from compressing
+/// them.
+pub const MIN_COMPRESSION_SIZE: usize = 1024;
+
 #[derive(Clone, Debug)]
 pub struct JsonRpcService<L> {
     logger: L,
@@ -32,15 +48,27 @@ pub struct JsonRpcService<L> {
     /// Registered server methods.
     methods: Methods,
     rpc_router: RpcRouter,
+
+    /// Maximum number of requests accepted in a single batch.
+    max_batch_size: usize,
+    metrics: HttpMetrics,
 }
 
 impl<L> JsonRpcService<L> {
-    pub fn new(methods: Methods, rpc_router: RpcRouter, logger: L) -> Self {
+    pub fn new(
+        methods: Methods,
+        rpc_router: RpcRouter,
+        logger: L,
+        max_batch_size: usize,
+        metrics: HttpMetrics,
+    ) -> Self {
         Self {
             methods,
             rpc_router,
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
+            max_batch_size,
+            metrics,
         }
     }
 }
@@ -91,9 +119,10 @@ fn from_template<S: Into<hyper::Body>>(
         .expect("Unable to parse response body for type conversion")
 }
 
+const JSON: &str = "application/json; charset=utf-8";
+
 /// Create a valid JSON response.
 pub(crate) fn ok_response(body: String) -> hyper::Response<hyper::Body> {
-    const JSON: &str = "application/json; charset=utf-8";
     from_template(hyper::StatusCode::OK, body, JSON)
 }
 
@@ -106,26 +135,220 @@ pub async fn json_rpc_handler<L: Logger>(
     let api_version = headers
         .get(CLIENT_TARGET_API_VERSION_HEADER)
         .and_then(|h| h.to_str().ok());
-    let response = process_raw_request(&service, api_version, raw_request.get()).await;
+    // Notifications are not answered, so their response is empty.
+    let response = process_raw_request(&service, api_version, raw_request.get())
+        .await
+        .unwrap_or_default();
 
-    ok_response(response.result)
+    let encoding = ContentEncoding::negotiate(&headers);
+    let mut response = encode_response(&service, response, encoding).await;
+    response
+        .headers_mut()
+        .insert(VARY, HeaderValue::from_static("accept-encoding"));
+    response
 }
 
+/// Process a single request or a batch, returning the response to send back, or `None` if
+/// there is nothing to respond to.
 async fn process_raw_request<L: Logger>(
     service: &JsonRpcService<L>,
     api_version: Option<&str>,
     raw_request: &str,
-) -> MethodResponse {
+) -> Option<String> {
+    if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+        process_batch(service, api_version, batch).await
+    } else {
+        process_single_request(service, api_version, raw_request)
+            .await
+            .map(|response| response.result)
+    }
+}
+
+/// Process a request that is not part of a batch, or an entry of a batch. Notifications, which
+/// have no id, are dropped without a response, as in jsonrpsee's server.
+async fn process_single_request<L: Logger>(
+    service: &JsonRpcService<L>,
+    api_version: Option<&str>,
+    raw_request: &str,
+) -> Option<MethodResponse> {
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-        process_request(request, api_version, service.call_data()).await
-    } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
-            Id::Null,
-            ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-        )
+        Some(process_request(request, api_version, service.call_data()).await)
+    } else if serde_json::from_str::<Notification<Option<&RawValue>>>(raw_request).is_ok() {
+        None
     } else {
         let (id, code) = prepare_error(raw_request);
-        MethodResponse::error(id, ErrorObject::from(code))
+        Some(MethodResponse::error(id, ErrorObject::from(code)))
+    }
+}
+
+/// Process a JSON-RPC 2.0 batch, returning an array with a response for every request in the
+/// batch other than notifications, in the same order, or `None` if the batch only contains
+/// notifications. Up to `MAX_CONCURRENT_BATCH_REQUESTS` requests in the batch are processed
+/// concurrently, and a malformed request only fails its own entry.
+async fn process_batch<L: Logger>(
+    service: &JsonRpcService<L>,
+    api_version: Option<&str>,
+    batch: Vec<&RawValue>,
+) -> Option<String> {
+    if batch.is_empty() {
+        return Some(
+            MethodResponse::error(Id::Null, ErrorObject::from(ErrorCode::InvalidRequest)).result,
+        );
+    }
+
+    if batch.len() > service.max_batch_size {
+        service.metrics.batches_rejected.inc();
+        return Some(
+            MethodResponse::error(
+                Id::Null,
+                ErrorObject::owned(
+                    BATCH_TOO_LARGE_ERROR_CODE,
+                    format!(
+                        "Batch of {} requests exceeds the limit of {} requests",
+                        batch.len(),
+                        service.max_batch_size
+                    ),
+                    None::<()>,
+                ),
+            )
+            .result,
+        );
+    }
+
+    service.metrics.batch_size.observe(batch.len() as f64);
+    let mut responses: Vec<_> = futures::stream::iter(batch.into_iter().enumerate())
+        .map(|(index, raw)| async move {
+            process_single_request(service, api_version, raw.get())
+                .await
+                .map(|response| (index, response))
+        })
+        .buffer_unordered(MAX_CONCURRENT_BATCH_REQUESTS)
+        .filter_map(futures::future::ready)
+        .collect()
+        .await;
+
+    if responses.is_empty() {
+        return None;
+    }
+    responses.sort_by_key(|(index, _)| *index);
+    Some(format!(
+        "[{}]",
+        responses.into_iter().map(|(_, r)| r.result).join(",")
+    ))
+}
+
+/// Content codings the server can compress responses with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContentEncoding {
+    Zstd,
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// Choose a coding from the client's `Accept-Encoding` headers, preferring zstd over gzip.
+    /// Codings the client has explicitly refused with `q=0` are never chosen.
+    pub(crate) fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let (mut zstd, mut gzip) = (false, false);
+        let directives = headers
+            .get_all(ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','));
+
+        for directive in directives {
+            let mut parts = directive.split(';');
+            let coding = parts.next().unwrap_or_default().trim();
+            let refused = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .map_or(false, |q| q == 0.0)
+            });
+            if refused {
+                continue;
+            }
+
+            if coding.eq_ignore_ascii_case("zstd") {
+                zstd = true;
+            } else if coding.eq_ignore_ascii_case("gzip") {
+                gzip = true;
+            }
+        }
+
+        if zstd {
+            Some(Self::Zstd)
+        } else if gzip {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    fn encode(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            // Level 0 selects zstd's default compression level.
+            Self::Zstd => zstd::encode_all(body, 0),
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Create a JSON response, compressed with `encoding` if the body is large enough to benefit from
+/// it.
+async fn encode_response<L>(
+    service: &JsonRpcService<L>,
+    body: String,
+    encoding: Option<ContentEncoding>,
+) -> hyper::Response<hyper::Body> {
+    let Some(encoding) = encoding.filter(|_| body.len() >= MIN_COMPRESSION_SIZE) else {
+        return ok_response(body);
+    };
+
+    // Compressing a large response is CPU-bound, so keep it off the async runtime's workers.
+    let compressed = tokio::task::spawn_blocking(move || {
+        encoding.encode(body.as_bytes()).map_err(|e| (e, body))
+    })
+    .await;
+
+    match compressed {
+        Ok(Ok(bytes)) => {
+            service
+                .metrics
+                .compressed_responses
+                .with_label_values(&[encoding.as_str()])
+                .inc();
+            let mut response = from_template(hyper::StatusCode::OK, bytes, JSON);
+            response.headers_mut().insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+            response
+        }
+        Ok(Err((e, body))) => {
+            warn!(
+                "Failed to compress response with {}: {e}",
+                encoding.as_str()
+            );
+            ok_response(body)
+        }
+        Err(e) => {
+            warn!("Response compression task failed: {e}");
+            ok_response(
+                MethodResponse::error(Id::Null, ErrorObject::from(ErrorCode::InternalError)).result,
+            )
+        }
     }
 }
 
@@ -423,3 +646,51 @@ pub mod ws {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiate(accept_encoding: &[&str]) -> Option<ContentEncoding> {
+        let mut headers = HeaderMap::new();
+        for value in accept_encoding {
+            headers.append(ACCEPT_ENCODING, HeaderValue::from_str(value).unwrap());
+        }
+        ContentEncoding::negotiate(&headers)
+    }
+
+    #[test]
+    fn test_negotiate_content_encoding() {
+        assert_eq!(negotiate(&[]), None);
+        assert_eq!(negotiate(&["br, deflate"]), None);
+        assert_eq!(negotiate(&["gzip"]), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate(&["GZIP;q=0.5"]), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate(&["gzip, zstd"]), Some(ContentEncoding::Zstd));
+        assert_eq!(negotiate(&["gzip", "zstd"]), Some(ContentEncoding::Zstd));
+        assert_eq!(negotiate(&["gzip, zstd;q=0"]), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate(&["zstd; q=0, gzip;q=0.0"]), None);
+    }
+
+    #[test]
+    fn test_content_encoding_round_trip() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let body = "{\"jsonrpc\":\"2.0\",\"result\":\"Some string\",\"id\":1}".repeat(100);
+
+        let compressed = ContentEncoding::Zstd.encode(body.as_bytes()).unwrap();
+        assert!(compressed.len() < body.len());
+        assert_eq!(
+            zstd::decode_all(compressed.as_slice()).unwrap(),
+            body.as_bytes()
+        );
+
+        let compressed = ContentEncoding::Gzip.encode(body.as_bytes()).unwrap();
+        assert!(compressed.len() < body.len());
+        let mut decoded = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+    }
+}
@@ -24,8 +24,9 @@ use sui_json_rpc_api::{
 };
 use sui_open_rpc::{Module, Project};
 
+use crate::axum_router::DEFAULT_MAX_BATCH_SIZE;
 use crate::error::Error;
-use crate::metrics::MetricsLogger;
+use crate::metrics::{HttpMetrics, MetricsLogger};
 use crate::routing_layer::RpcRouter;
 
 pub mod authority_state;
@@ -53,6 +54,7 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    max_batch_size: usize,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -79,9 +81,16 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: sui_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         }
     }
 
+    /// Limit the number of requests accepted in a single batch, `DEFAULT_MAX_BATCH_SIZE` unless
+    /// set.
+    pub fn set_max_batch_size(&mut self, max_batch_size: usize) {
+        self.max_batch_size = max_batch_size;
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -158,6 +167,8 @@ impl JsonRpcServerBuilder {
         );
         let rpc_router = RpcRouter::new(routing, disable_routing);
 
+        info!("Max JSON-RPC batch size {}.", self.max_batch_size);
+
         let rpc_docs = self.rpc_doc.clone();
         let mut module = self.module.clone();
         module.register_method("rpc.discover", move |_, _| Ok(rpc_docs.clone()))?;
@@ -169,8 +180,13 @@ impl JsonRpcServerBuilder {
             .layer(Self::trace_layer())
             .layer(Self::cors()?);
 
-        let service =
-            crate::axum_router::JsonRpcService::new(module.into(), rpc_router, metrics_logger);
+        let service = crate::axum_router::JsonRpcService::new(
+            module.into(),
+            rpc_router,
+            metrics_logger,
+            self.max_batch_size,
+            HttpMetrics::new(&self.registry),
+        );
 
         let mut router = axum::Router::new();
 
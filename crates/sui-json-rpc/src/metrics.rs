@@ -8,8 +8,10 @@ use std::net::SocketAddr;
 use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
 use jsonrpsee::types::Params;
 use prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec,
 };
 use sui_json_rpc_api::TRANSIENT_ERROR_CODE;
 use sui_json_rpc_api::{CLIENT_SDK_TYPE_HEADER, CLIENT_TARGET_API_VERSION_HEADER};
@@ -43,6 +45,47 @@ pub struct Metrics {
     rpc_response_size: HistogramVec,
 }
 
+/// Metrics for batching and compression, which apply to whole HTTP requests rather than to
+/// individual method calls.
+#[derive(Debug, Clone)]
+pub struct HttpMetrics {
+    /// Number of requests in each accepted batch
+    pub(crate) batch_size: Histogram,
+    /// Batches rejected for exceeding the batch size limit
+    pub(crate) batches_rejected: IntCounter,
+    /// Compressed responses, encoding is a label
+    pub(crate) compressed_responses: IntCounterVec,
+}
+
+impl HttpMetrics {
+    pub fn new(registry: &prometheus::Registry) -> Self {
+        Self {
+            batch_size: register_histogram_with_registry!(
+                "rpc_batch_size",
+                "Number of requests in a JSON-RPC batch",
+                prometheus::exponential_buckets(1.0, 2.0, 10)
+                    .unwrap()
+                    .to_vec(),
+                registry,
+            )
+            .unwrap(),
+            batches_rejected: register_int_counter_with_registry!(
+                "rpc_batches_rejected",
+                "Number of JSON-RPC batches rejected for exceeding the batch size limit",
+                registry,
+            )
+            .unwrap(),
+            compressed_responses: register_int_counter_vec_with_registry!(
+                "rpc_compressed_responses",
+                "Number of compressed responses by content encoding",
+                &["encoding"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MetricsLogger {
     metrics: Metrics,
@@ -0,0 +1,312 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use move_core_types::account_address::AccountAddress;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_package_resolver::error::Error as ResolverError;
+use sui_package_resolver::Result as ResolverResult;
+use sui_package_resolver::{Package, PackageStore};
+use sui_rest_api::{CheckpointData, Client};
+use sui_sdk::SuiClientBuilder;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    committee::Committee,
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEvents},
+    event::{Event, EventID},
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber},
+    object::Object,
+};
+use tracing::info;
+
+use crate::config::Config;
+use crate::source::{CheckpointSource, RestCheckpointSource};
+use crate::store::CheckpointStore;
+use crate::verifier;
+
+/// A light client that keeps the chain of committees since genesis in a local
+/// [`CheckpointStore`], and uses it to verify data read from a [`CheckpointSource`].
+pub struct LightClient<S = RestCheckpointSource> {
+    store: CheckpointStore,
+    source: S,
+    /// Full node to look up objects, and the checkpoints that transactions were executed in.
+    full_node_url: String,
+}
+
+impl LightClient {
+    /// A light client that reads checkpoints from the full node in `config`.
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            CheckpointStore::new(config.checkpoint_summary_dir.clone(), config.genesis_path()),
+            RestCheckpointSource::new(config.rest_url()),
+            config.full_node_url.clone(),
+        )
+    }
+}
+
+impl<S: CheckpointSource> LightClient<S> {
+    pub fn new(store: CheckpointStore, source: S, full_node_url: String) -> Self {
+        Self {
+            store,
+            source,
+            full_node_url,
+        }
+    }
+
+    pub fn store(&self) -> &CheckpointStore {
+        &self.store
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    fn rest_client(&self) -> Client {
+        Client::new(format!("{}/rest", self.full_node_url))
+    }
+
+    /// Run binary search to for each end of epoch checkpoint that is missing
+    /// between the latest on the list and the latest checkpoint.
+    pub async fn sync_checkpoint_list_to_latest(&self) -> anyhow::Result<()> {
+        // Get the local checkpoint list
+        let mut checkpoints_list = self.store.read_checkpoint_list()?;
+        let latest_in_list = checkpoints_list
+            .checkpoints
+            .last()
+            .ok_or(anyhow!("Empty checkpoint list"))?;
+
+        // Download the latest in list checkpoint
+        let summary = self.source.get_checkpoint_summary(*latest_in_list).await?;
+        let mut last_epoch = summary.epoch();
+        let mut last_checkpoint_seq = summary.sequence_number;
+
+        // Download the very latest checkpoint
+        let latest = self.source.get_latest_checkpoint_summary().await?;
+
+        // Binary search to find missing checkpoints
+        while last_epoch + 1 < latest.epoch() {
+            let mut start = last_checkpoint_seq;
+            let mut end = latest.sequence_number;
+
+            let target_epoch = last_epoch + 1;
+            info!(target_epoch, "Searching for end-of-epoch checkpoint");
+            let mut found_summary = None;
+
+            while start < end {
+                let mid = (start + end) / 2;
+                let summary = self.source.get_checkpoint_summary(mid).await?;
+
+                if summary.epoch() == target_epoch && summary.end_of_epoch_data.is_some() {
+                    found_summary = Some(summary);
+                    break;
+                }
+
+                if summary.epoch() <= target_epoch {
+                    start = mid + 1;
+                } else {
+                    end = mid;
+                }
+            }
+
+            let summary = found_summary.ok_or_else(|| {
+                anyhow!("Unable to find the end-of-epoch checkpoint of epoch {target_epoch}")
+            })?;
+
+            // Note: Do not write summary to file, since we must only persist
+            //       checkpoints that have been verified by the previous committee
+
+            // Add to the list
+            checkpoints_list.checkpoints.push(summary.sequence_number);
+            self.store.write_checkpoint_list(&checkpoints_list)?;
+
+            // Update
+            last_epoch = summary.epoch();
+            last_checkpoint_seq = summary.sequence_number;
+        }
+
+        Ok(())
+    }
+
+    /// Sync all end-of-epoch checkpoints up to the latest epoch, verifying each one with the
+    /// committee of the previous epoch, starting from the genesis committee. Returns the
+    /// committee of the latest epoch.
+    pub async fn sync(&self) -> anyhow::Result<Committee> {
+        self.sync_checkpoint_list_to_latest().await?;
+
+        // Get the local checkpoint list
+        let checkpoints_list = self.store.read_checkpoint_list()?;
+
+        // Check the signatures of all checkpoints
+        // And download any missing ones
+        let mut prev_committee = self.store.genesis_committee()?;
+        for ckp_id in &checkpoints_list.checkpoints {
+            // If file exists read the file otherwise download it from the server
+            let summary = if self.store.has_checkpoint(*ckp_id) {
+                self.store.read_checkpoint(*ckp_id)?
+            } else {
+                let summary = self.source.get_checkpoint_summary(*ckp_id).await?;
+                summary.clone().verify(&prev_committee)?;
+                // Write the checkpoint summary to a file
+                self.store.write_checkpoint(&summary)?;
+                summary
+            };
+
+            info!(
+                epoch = summary.epoch(),
+                checkpoint = ?summary.digest(),
+                "Verified end-of-epoch checkpoint"
+            );
+
+            // Extract the new committee information
+            prev_committee = verifier::next_epoch_committee(&summary)?;
+        }
+
+        Ok(prev_committee)
+    }
+
+    /// The committee that certifies `summary`, from the synced end-of-epoch checkpoints. Fails
+    /// if the light client has not been synced up to the epoch of `summary`.
+    pub fn committee_for(&self, summary: &CertifiedCheckpointSummary) -> anyhow::Result<Committee> {
+        // Load the list of stored checkpoints
+        let checkpoints_list = self.store.read_checkpoint_list()?;
+
+        // find the stored checkpoint before the seq checkpoint
+        let prev_ckp_id = checkpoints_list
+            .checkpoints
+            .iter()
+            .filter(|ckp_id| **ckp_id < summary.sequence_number)
+            .last();
+
+        let Some(prev_ckp_id) = prev_ckp_id else {
+            // Since we did not find a small committee checkpoint we use the genesis
+            return self.store.genesis_committee();
+        };
+
+        // Read it from the store
+        let prev_ckp = self.store.read_checkpoint(*prev_ckp_id)?;
+
+        // Check we have the right checkpoint
+        let next_epoch = prev_ckp
+            .epoch()
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Epoch overflow"))?;
+        anyhow::ensure!(
+            next_epoch == summary.epoch(),
+            "Checkpoint sequence number does not match. Need to Sync."
+        );
+
+        // Get the committee from the previous checkpoint
+        verifier::next_epoch_committee(&prev_ckp)
+    }
+
+    /// Download checkpoint `seq` and verify all of its contents. Input objects cannot be
+    /// verified and are removed, see [`verifier::verify_checkpoint`].
+    pub async fn get_verified_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        let checkpoint = self.source.get_full_checkpoint(seq).await?;
+        let committee = self.committee_for(&checkpoint.checkpoint_summary)?;
+        verifier::verify_checkpoint(checkpoint, &committee)
+    }
+
+    /// The sequence number of the checkpoint that executed transaction `tid`, according to the
+    /// full node. This is not trusted: the transaction is verified against the checkpoint.
+    async fn checkpoint_of_transaction(
+        &self,
+        tid: TransactionDigest,
+    ) -> anyhow::Result<CheckpointSequenceNumber> {
+        let sui_client = SuiClientBuilder::default()
+            .build(self.full_node_url.as_str())
+            .await?;
+
+        // Lookup the transaction id and get the checkpoint sequence number
+        let options = SuiTransactionBlockResponseOptions::new();
+        sui_client
+            .read_api()
+            .get_transaction_with_options(tid, options)
+            .await?
+            .checkpoint
+            .ok_or(anyhow!("Transaction not found"))
+    }
+
+    /// Verify transaction `tid` against the checkpoint it was executed in, returning its
+    /// effects and events.
+    pub async fn get_verified_effects_and_events(
+        &self,
+        tid: TransactionDigest,
+    ) -> anyhow::Result<(TransactionEffects, Option<TransactionEvents>)> {
+        let seq = self.checkpoint_of_transaction(tid).await?;
+
+        // Download the full checkpoint for this sequence number
+        let checkpoint = self.source.get_full_checkpoint(seq).await?;
+        let committee = self.committee_for(&checkpoint.checkpoint_summary)?;
+
+        verifier::extract_verified_effects_and_events(&checkpoint, &committee, tid)
+    }
+
+    /// Verify the event `event_id` against the checkpoint it was emitted in.
+    pub async fn get_verified_event(&self, event_id: EventID) -> anyhow::Result<Event> {
+        let seq = self.checkpoint_of_transaction(event_id.tx_digest).await?;
+
+        let checkpoint = self.source.get_full_checkpoint(seq).await?;
+        let committee = self.committee_for(&checkpoint.checkpoint_summary)?;
+
+        verifier::extract_verified_event(&checkpoint, &committee, event_id)
+    }
+
+    /// Read the latest version of object `id` from the full node, and verify it against the
+    /// effects of the transaction that last wrote it.
+    pub async fn get_verified_object(&self, id: ObjectID) -> anyhow::Result<Object> {
+        let object = self.rest_client().get_object(id).await?;
+
+        // Need to authenticate this object
+        let (effects, _) = self
+            .get_verified_effects_and_events(object.previous_transaction)
+            .await?;
+
+        verifier::verify_object_in_effects(&object, &effects)?;
+        Ok(object)
+    }
+}
+
+/// A [`PackageStore`] that only serves packages verified by a light client, so that type layouts
+/// used to display objects and events can be trusted too.
+pub struct RemotePackageStore<S = RestCheckpointSource> {
+    client: Arc<LightClient<S>>,
+}
+
+impl<S: CheckpointSource> RemotePackageStore<S> {
+    pub fn new(client: Arc<LightClient<S>>) -> Self {
+        Self { client }
+    }
+
+    async fn get_verified_object(&self, id: AccountAddress) -> ResolverResult<Object> {
+        self.client
+            .get_verified_object(id.into())
+            .await
+            .map_err(|e| ResolverError::Store {
+                store: "LightClient",
+                source: e.into(),
+            })
+    }
+}
+
+#[async_trait]
+impl<S: CheckpointSource + 'static> PackageStore for RemotePackageStore<S> {
+    /// Latest version of the object at `id`.
+    async fn version(&self, id: AccountAddress) -> ResolverResult<SequenceNumber> {
+        Ok(self.get_verified_object(id).await?.version())
+    }
+    /// Read package contents. Fails if `id` is not an object, not a package, or is malformed in
+    /// some way.
+    async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
+        let object = self.get_verified_object(id).await?;
+        let package = Package::read(&object)?;
+        Ok(Arc::new(package))
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client for the Sui blockchain.
+//!
+//! Starting from the committee in the genesis blob, the light client syncs every end-of-epoch
+//! checkpoint, verifying each one with the committee of the epoch it ends, to learn the committee
+//! of every epoch. With those committees, checkpoints from any [`CheckpointSource`] can be
+//! verified, and so can the transactions, effects, events and objects they contain.
+
+pub mod client;
+pub mod config;
+pub mod source;
+pub mod store;
+pub mod verifier;
+
+pub use client::{LightClient, RemotePackageStore};
+pub use config::Config;
+pub use source::{
+    ArchiveCheckpointSource, CheckpointSource, LocalCheckpointSource, ObjectStoreCheckpointSource,
+    RestCheckpointSource,
+};
+pub use store::{CheckpointStore, CheckpointsList};
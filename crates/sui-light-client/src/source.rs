@@ -0,0 +1,423 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Buf;
+use object_store::path::Path;
+use object_store::{DynObjectStore, ObjectStore};
+use serde::de::DeserializeOwned;
+use sui_archival::{
+    read_manifest_from_bytes, FileMetadata, FileType, Manifest, CHECKPOINT_FILE_MAGIC,
+    SUMMARY_FILE_MAGIC,
+};
+use sui_data_ingestion_core::create_remote_store_client;
+use sui_rest_api::{CheckpointData, Client};
+use sui_storage::blob::Blob;
+use sui_storage::make_iterator;
+use sui_storage::object_store::ObjectStoreGetExt;
+use sui_types::full_checkpoint_content::CheckpointTransaction;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+
+/// Where the light client downloads checkpoints from. Nothing read from a source is trusted:
+/// every checkpoint is verified against the committee of its epoch before it is used.
+#[async_trait]
+pub trait CheckpointSource: Send + Sync {
+    async fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData>;
+
+    async fn get_checkpoint_summary(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CertifiedCheckpointSummary> {
+        Ok(self.get_full_checkpoint(seq).await?.checkpoint_summary)
+    }
+
+    /// The latest checkpoint that the source can serve, used to find end-of-epoch checkpoints
+    /// that have not been synced yet.
+    async fn get_latest_checkpoint_summary(&self) -> anyhow::Result<CertifiedCheckpointSummary>;
+}
+
+/// Reads checkpoints from a full node's REST API.
+pub struct RestCheckpointSource {
+    client: Client,
+}
+
+impl RestCheckpointSource {
+    pub fn new(rest_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(rest_url),
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for RestCheckpointSource {
+    async fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        self.client.get_full_checkpoint(seq).await
+    }
+
+    async fn get_checkpoint_summary(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CertifiedCheckpointSummary> {
+        self.client.get_checkpoint_summary(seq).await
+    }
+
+    async fn get_latest_checkpoint_summary(&self) -> anyhow::Result<CertifiedCheckpointSummary> {
+        self.client.get_latest_checkpoint().await
+    }
+}
+
+/// Reads checkpoints from a local directory of `<seq>.chk` files, the format that full nodes
+/// write for data ingestion.
+pub struct LocalCheckpointSource {
+    dir: PathBuf,
+}
+
+impl LocalCheckpointSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for LocalCheckpointSource {
+    async fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        let path = self.dir.join(format!("{seq}.chk"));
+        let bytes = fs::read(&path)
+            .map_err(|e| anyhow!("Unable to read checkpoint {}: {e}", path.display()))?;
+        Blob::from_bytes::<CheckpointData>(&bytes)
+    }
+
+    async fn get_latest_checkpoint_summary(&self) -> anyhow::Result<CertifiedCheckpointSummary> {
+        let mut latest = None;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "chk") {
+                let seq = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<CheckpointSequenceNumber>().ok());
+                latest = latest.max(seq);
+            }
+        }
+
+        let latest =
+            latest.ok_or_else(|| anyhow!("No checkpoints found in {}", self.dir.display()))?;
+        self.get_checkpoint_summary(latest).await
+    }
+}
+
+/// Reads checkpoints from a remote object store bucket of `<seq>.chk` files, such as the
+/// checkpoint buckets used for data ingestion.
+pub struct ObjectStoreCheckpointSource {
+    store: Box<dyn ObjectStore>,
+}
+
+impl ObjectStoreCheckpointSource {
+    /// `options` configure access to the bucket in the same way as the data ingestion
+    /// framework's remote store options. Without options, `url` is read over plain HTTP.
+    pub fn new(url: String, options: Vec<(String, String)>) -> anyhow::Result<Self> {
+        Ok(Self {
+            store: create_remote_store_client(url, options)?,
+        })
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for ObjectStoreCheckpointSource {
+    async fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        let path = Path::from(format!("{seq}.chk"));
+        let bytes = self.store.get(&path).await?.bytes().await?;
+        Blob::from_bytes::<CheckpointData>(&bytes)
+    }
+
+    async fn get_latest_checkpoint_summary(&self) -> anyhow::Result<CertifiedCheckpointSummary> {
+        // Buckets are append-only and cannot be listed cheaply, so search for the first
+        // checkpoint that is missing.
+        let exists = |seq: CheckpointSequenceNumber| async move {
+            let path = Path::from(format!("{seq}.chk"));
+            match self.store.head(&path).await {
+                Ok(_) => Ok(true),
+                Err(object_store::Error::NotFound { .. }) => Ok(false),
+                Err(e) => Err(e),
+            }
+        };
+
+        anyhow::ensure!(exists(0).await?, "Checkpoint bucket is empty");
+
+        let mut upper = 1;
+        while exists(upper).await? {
+            upper *= 2;
+        }
+
+        // Invariant: `lower` exists and `upper` does not.
+        let mut lower = upper / 2;
+        while lower + 1 < upper {
+            let mid = lower + (upper - lower) / 2;
+            if exists(mid).await? {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+
+        self.get_checkpoint_summary(lower).await
+    }
+}
+
+/// Reads checkpoints from a state archive: a bucket of checkpoint summary and contents files,
+/// indexed by a `MANIFEST`, as written by the archival service.
+///
+/// Archives store transactions and effects, but not events or objects, so they are best suited
+/// to syncing end-of-epoch checkpoints. Full checkpoints are served without events or objects:
+/// whether a transaction emitted events is recorded by the events digest in its effects.
+pub struct ArchiveCheckpointSource {
+    store: Arc<DynObjectStore>,
+}
+
+impl ArchiveCheckpointSource {
+    /// `options` configure access to the bucket in the same way as for
+    /// [`ObjectStoreCheckpointSource`].
+    pub fn new(url: String, options: Vec<(String, String)>) -> anyhow::Result<Self> {
+        let store = create_remote_store_client(url, options)?;
+        Ok(Self::from_object_store(store.into()))
+    }
+
+    pub fn from_object_store(store: Arc<DynObjectStore>) -> Self {
+        Self { store }
+    }
+
+    async fn read_manifest(&self) -> anyhow::Result<Manifest> {
+        let bytes = self.store.get_bytes(&Path::from("MANIFEST")).await?;
+        read_manifest_from_bytes(bytes.to_vec())
+    }
+
+    /// The archive file of type `file_type` that holds checkpoint `seq`.
+    async fn find_file(
+        &self,
+        file_type: FileType,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<FileMetadata> {
+        self.read_manifest()
+            .await?
+            .files()
+            .into_iter()
+            .find(|file| file.file_type == file_type && file.checkpoint_seq_range.contains(&seq))
+            .ok_or_else(|| anyhow!("Checkpoint {seq} is not in the archive"))
+    }
+
+    /// Reads the entry for checkpoint `seq` from the archive file `file`, which holds one entry
+    /// per checkpoint in its range.
+    async fn read_entry<T: DeserializeOwned>(
+        &self,
+        file: &FileMetadata,
+        magic: u32,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<T> {
+        let bytes = self.store.get_bytes(&file.file_path()).await?;
+        let index = seq - file.checkpoint_seq_range.start;
+        make_iterator::<T, _>(magic, bytes.reader())?
+            .nth(index as usize)
+            .ok_or_else(|| anyhow!("Checkpoint {seq} is missing from {}", file.file_path()))
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for ArchiveCheckpointSource {
+    async fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        let checkpoint_summary = self.get_checkpoint_summary(seq).await?;
+        let file = self.find_file(FileType::CheckpointContent, seq).await?;
+        let contents: FullCheckpointContents =
+            self.read_entry(&file, CHECKPOINT_FILE_MAGIC, seq).await?;
+
+        let transactions = contents
+            .iter()
+            .map(|data| CheckpointTransaction {
+                transaction: data.transaction.clone(),
+                effects: data.effects.clone(),
+                events: None,
+                input_objects: vec![],
+                output_objects: vec![],
+            })
+            .collect();
+
+        Ok(CheckpointData {
+            checkpoint_summary,
+            checkpoint_contents: contents.checkpoint_contents(),
+            transactions,
+        })
+    }
+
+    async fn get_checkpoint_summary(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CertifiedCheckpointSummary> {
+        let file = self.find_file(FileType::CheckpointSummary, seq).await?;
+        self.read_entry(&file, SUMMARY_FILE_MAGIC, seq).await
+    }
+
+    async fn get_latest_checkpoint_summary(&self) -> anyhow::Result<CertifiedCheckpointSummary> {
+        let latest = self
+            .read_manifest()
+            .await?
+            .next_checkpoint_seq_num()
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("Archive is empty"))?;
+        self.get_checkpoint_summary(latest).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bytes::Bytes;
+    use sui_archival::{create_file_metadata_from_bytes, finalize_manifest};
+    use sui_storage::blob::BlobEncoding;
+    use sui_storage::{FileCompression, StorageFormat};
+    use sui_types::base_types::ExecutionData;
+
+    use super::*;
+
+    fn read_checkpoint() -> CheckpointData {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("example_config/20958462.bcs");
+        bcs::from_bytes(&fs::read(path).unwrap()).unwrap()
+    }
+
+    /// An archive file holding a single `value`, in the format written by the archival service.
+    fn archive_file<T: serde::Serialize>(magic: u32, value: &T) -> Bytes {
+        let mut buf = magic.to_be_bytes().to_vec();
+        buf.push(StorageFormat::Blob.into());
+        buf.push(FileCompression::None.into());
+        Blob::encode(value, BlobEncoding::Bcs)
+            .unwrap()
+            .write(&mut buf)
+            .unwrap();
+        Bytes::from(buf)
+    }
+
+    #[tokio::test]
+    async fn test_local_checkpoint_source() {
+        let checkpoint = read_checkpoint();
+        let seq = checkpoint.checkpoint_summary.sequence_number;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = LocalCheckpointSource::new(dir.path());
+        assert!(source.get_latest_checkpoint_summary().await.is_err());
+
+        let blob = Blob::encode(&checkpoint, BlobEncoding::Bcs).unwrap();
+        fs::write(dir.path().join(format!("{seq}.chk")), blob.to_bytes()).unwrap();
+        // Files that are not checkpoints are ignored.
+        fs::write(dir.path().join("checkpoints.yaml"), b"checkpoints: []").unwrap();
+
+        let latest = source.get_latest_checkpoint_summary().await.unwrap();
+        assert_eq!(latest, checkpoint.checkpoint_summary);
+
+        let full = source.get_full_checkpoint(seq).await.unwrap();
+        assert_eq!(full.checkpoint_contents, checkpoint.checkpoint_contents);
+        assert!(source.get_full_checkpoint(seq + 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_archive_checkpoint_source() {
+        let checkpoint = read_checkpoint();
+        let summary = checkpoint.checkpoint_summary.clone();
+        let seq = summary.sequence_number;
+        let epoch = summary.epoch();
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = ArchiveCheckpointSource::from_object_store(Arc::new(
+            object_store::local::LocalFileSystem::new_with_prefix(dir.path()).unwrap(),
+        ));
+        assert!(source.get_checkpoint_summary(seq).await.is_err());
+
+        // Archive the checkpoint on its own, as the archival service would.
+        let contents = FullCheckpointContents::from_contents_and_execution_data(
+            checkpoint.checkpoint_contents.clone(),
+            checkpoint
+                .transactions
+                .iter()
+                .map(|tx| ExecutionData::new(tx.transaction.clone(), tx.effects.clone())),
+        );
+        let summary_file = archive_file(SUMMARY_FILE_MAGIC, &summary);
+        let contents_file = archive_file(CHECKPOINT_FILE_MAGIC, &contents);
+        let summary_metadata = create_file_metadata_from_bytes(
+            summary_file.clone(),
+            FileType::CheckpointSummary,
+            epoch,
+            seq..seq + 1,
+        )
+        .unwrap();
+        let contents_metadata = create_file_metadata_from_bytes(
+            contents_file.clone(),
+            FileType::CheckpointContent,
+            epoch,
+            seq..seq + 1,
+        )
+        .unwrap();
+
+        let epoch_dir = dir.path().join(format!("epoch_{epoch}"));
+        fs::create_dir_all(&epoch_dir).unwrap();
+        fs::write(epoch_dir.join(format!("{seq}.sum")), summary_file).unwrap();
+        fs::write(epoch_dir.join(format!("{seq}.chk")), contents_file).unwrap();
+        let mut manifest = Manifest::new(epoch, seq);
+        manifest.update(epoch, seq + 1, contents_metadata, summary_metadata);
+        fs::write(
+            dir.path().join("MANIFEST"),
+            finalize_manifest(manifest).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.get_latest_checkpoint_summary().await.unwrap(),
+            summary
+        );
+        assert_eq!(source.get_checkpoint_summary(seq).await.unwrap(), summary);
+        assert!(source.get_checkpoint_summary(seq + 1).await.is_err());
+
+        // Transactions and effects are served, but events and objects are not archived.
+        assert!(checkpoint.transactions.iter().any(|tx| tx.events.is_some()));
+        let full = source.get_full_checkpoint(seq).await.unwrap();
+        assert_eq!(full.checkpoint_summary, summary);
+        assert_eq!(full.checkpoint_contents, checkpoint.checkpoint_contents);
+        assert_eq!(full.transactions.len(), checkpoint.transactions.len());
+        for (full, tx) in full.transactions.iter().zip(&checkpoint.transactions) {
+            assert_eq!(full.transaction, tx.transaction);
+            assert_eq!(full.effects, tx.effects);
+            assert!(full.events.is_none());
+            assert!(full.input_objects.is_empty() && full.output_objects.is_empty());
+        }
+        assert!(source.get_full_checkpoint(seq + 1).await.is_err());
+
+        // The checkpoint served can still be verified.
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("example_config/20873329.yaml");
+        let prev_epoch: CertifiedCheckpointSummary =
+            bcs::from_bytes(&fs::read(path).unwrap()).unwrap();
+        let committee = crate::verifier::next_epoch_committee(&prev_epoch).unwrap();
+        crate::verifier::verify_checkpoint(full, &committee).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use sui_config::genesis::Genesis;
+use sui_types::committee::Committee;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+
+const CHECKPOINTS_LIST_FILENAME: &str = "checkpoints.yaml";
+
+// The list of checkpoints at the end of each epoch
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct CheckpointsList {
+    // List of end of epoch checkpoints
+    pub checkpoints: Vec<CheckpointSequenceNumber>,
+}
+
+/// The light client's local state: the genesis blob it trusts, and the end-of-epoch checkpoint
+/// summaries it has verified so far, one file per checkpoint, which chain the committee of every
+/// epoch back to genesis.
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    dir: PathBuf,
+    genesis_path: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(dir: impl Into<PathBuf>, genesis_path: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            genesis_path: genesis_path.into(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The committee of the first epoch, which is the root of trust.
+    pub fn genesis_committee(&self) -> anyhow::Result<Committee> {
+        Ok(Genesis::load(&self.genesis_path)?.committee()?)
+    }
+
+    pub fn read_checkpoint_list(&self) -> anyhow::Result<CheckpointsList> {
+        let checkpoints_path = self.dir.join(CHECKPOINTS_LIST_FILENAME);
+        // Read the resulting file and parse the yaml checkpoint list
+        let reader = fs::File::open(checkpoints_path)?;
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+
+    pub fn write_checkpoint_list(&self, checkpoints_list: &CheckpointsList) -> anyhow::Result<()> {
+        // Write the checkpoint list to a file
+        let checkpoints_path = self.dir.join(CHECKPOINTS_LIST_FILENAME);
+        let bytes = serde_yaml::to_vec(&checkpoints_list)?;
+        fs::write(checkpoints_path, bytes)
+            .map_err(|_| anyhow!("Unable to serialize checkpoint list"))
+    }
+
+    pub fn has_checkpoint(&self, seq: CheckpointSequenceNumber) -> bool {
+        self.checkpoint_path(seq).exists()
+    }
+
+    pub fn read_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CertifiedCheckpointSummary> {
+        let buffer = fs::read(self.checkpoint_path(seq))?;
+        bcs::from_bytes(&buffer).map_err(|_| anyhow!("Unable to parse checkpoint file"))
+    }
+
+    /// Only checkpoints that have been verified by the previous epoch's committee may be written
+    /// to the store, because the committees read back from it are trusted.
+    pub fn write_checkpoint(&self, summary: &CertifiedCheckpointSummary) -> anyhow::Result<()> {
+        // Write the checkpoint summary to a file
+        let bytes = bcs::to_bytes(&summary)
+            .map_err(|_| anyhow!("Unable to serialize checkpoint summary"))?;
+        fs::write(self.checkpoint_path(summary.sequence_number), bytes)?;
+        Ok(())
+    }
+
+    fn checkpoint_path(&self, seq: CheckpointSequenceNumber) -> PathBuf {
+        self.dir.join(format!("{}.yaml", seq))
+    }
+}
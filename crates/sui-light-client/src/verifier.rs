@@ -0,0 +1,399 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that tie data downloaded from an untrusted source to a checkpoint certified by a
+//! committee. None of these functions perform any I/O.
+
+use anyhow::anyhow;
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::{
+    committee::Committee,
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    event::{Event, EventID},
+    messages_checkpoint::{CertifiedCheckpointSummary, EndOfEpochData},
+    object::Object,
+};
+
+/// The committee of the epoch after the one that `summary` ends. `summary` must already have
+/// been verified, and must be an end-of-epoch checkpoint.
+pub fn next_epoch_committee(summary: &CertifiedCheckpointSummary) -> anyhow::Result<Committee> {
+    let Some(EndOfEpochData {
+        next_epoch_committee,
+        ..
+    }) = &summary.end_of_epoch_data
+    else {
+        return Err(anyhow!(
+            "Expected all checkpoints to be end-of-epoch checkpoints"
+        ));
+    };
+
+    let next_epoch = summary
+        .epoch()
+        .checked_add(1)
+        .ok_or_else(|| anyhow!("Epoch overflow"))?;
+    Ok(Committee::new(
+        next_epoch,
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
+
+/// Verifies `checkpoint` in full: its summary is certified by `committee`, its contents match
+/// the summary, and every transaction, its effects, its events and its output objects match the
+/// contents. Returns the verified checkpoint.
+///
+/// Effects only commit to the versions of some of the objects a transaction read, not to their
+/// contents, so input objects cannot be verified and are removed from the checkpoint returned.
+/// Events may be missing if the source does not serve them (see
+/// [`crate::source::ArchiveCheckpointSource`]), in which case the effects' events digest is the
+/// only record of whether the transaction emitted any.
+pub fn verify_checkpoint(
+    mut checkpoint: CheckpointData,
+    committee: &Committee,
+) -> anyhow::Result<CheckpointData> {
+    let summary = &checkpoint.checkpoint_summary;
+
+    // Verify the checkpoint summary using the committee
+    summary.verify_with_contents(committee, Some(&checkpoint.checkpoint_contents))?;
+
+    anyhow::ensure!(
+        checkpoint.transactions.len() == checkpoint.checkpoint_contents.size(),
+        "Checkpoint has a different number of transactions than its contents"
+    );
+
+    for (tx, digests) in checkpoint
+        .transactions
+        .iter_mut()
+        .zip(checkpoint.checkpoint_contents.iter())
+    {
+        verify_transaction_against_digests(tx, digests.transaction)?;
+        anyhow::ensure!(
+            tx.effects.execution_digests() == *digests,
+            "Effects of transaction {} do not match checkpoint contents",
+            digests.transaction
+        );
+
+        for object in &tx.output_objects {
+            verify_object_in_effects(object, &tx.effects)?;
+        }
+        tx.input_objects.clear();
+    }
+
+    Ok(checkpoint)
+}
+
+/// Finds transaction `tid` in `checkpoint`, and verifies the checkpoint summary, and the
+/// transaction, its effects and its events against it.
+pub fn extract_verified_transaction<'a>(
+    checkpoint: &'a CheckpointData,
+    committee: &Committee,
+    tid: TransactionDigest,
+) -> anyhow::Result<&'a CheckpointTransaction> {
+    let summary = &checkpoint.checkpoint_summary;
+
+    // Verify the checkpoint summary using the committee
+    summary.verify_with_contents(committee, Some(&checkpoint.checkpoint_contents))?;
+
+    // Check the validity of the transaction
+    let contents = &checkpoint.checkpoint_contents;
+    let (matching_tx, _) = checkpoint
+        .transactions
+        .iter()
+        .zip(contents.iter())
+        // Note that we get the digest of the effects to ensure this is
+        // indeed the correct effects that are authenticated in the contents.
+        .find(|(tx, digest)| {
+            tx.effects.execution_digests() == **digest && digest.transaction == tid
+        })
+        .ok_or(anyhow!("Transaction not found in checkpoint contents"))?;
+
+    verify_transaction_against_digests(matching_tx, tid)?;
+    Ok(matching_tx)
+}
+
+/// Finds transaction `tid` in `checkpoint` and returns its verified effects and events. Fails if
+/// the transaction emitted events that `checkpoint` does not include.
+pub fn extract_verified_effects_and_events(
+    checkpoint: &CheckpointData,
+    committee: &Committee,
+    tid: TransactionDigest,
+) -> anyhow::Result<(TransactionEffects, Option<TransactionEvents>)> {
+    let matching_tx = extract_verified_transaction(checkpoint, committee, tid)?;
+    anyhow::ensure!(
+        matching_tx.events.is_some() || matching_tx.effects.events_digest().is_none(),
+        "Events of transaction {tid} are not available from this source"
+    );
+    Ok((matching_tx.effects.clone(), matching_tx.events.clone()))
+}
+
+/// Finds the event `event_id` in `checkpoint`, verifying it in the same way as the transaction
+/// that emitted it.
+pub fn extract_verified_event(
+    checkpoint: &CheckpointData,
+    committee: &Committee,
+    event_id: EventID,
+) -> anyhow::Result<Event> {
+    let matching_tx = extract_verified_transaction(checkpoint, committee, event_id.tx_digest)?;
+    matching_tx
+        .events
+        .as_ref()
+        .and_then(|events| events.data.get(event_id.event_seq as usize))
+        .cloned()
+        .ok_or_else(|| anyhow!("Event {event_id:?} not found"))
+}
+
+/// Checks that `object` is the exact version of the object that `effects` wrote. `effects` must
+/// already have been verified, e.g. with [`extract_verified_effects_and_events`].
+pub fn verify_object_in_effects(
+    object: &Object,
+    effects: &TransactionEffects,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        object.previous_transaction == *effects.transaction_digest(),
+        "Object was not written by transaction {}",
+        effects.transaction_digest()
+    );
+
+    // check that this object ID, version and hash is in the effects
+    let object_ref = object.compute_object_reference();
+    effects
+        .all_changed_objects()
+        .iter()
+        .find(|(changed_ref, _, _)| *changed_ref == object_ref)
+        .ok_or(anyhow!("Object not found"))?;
+
+    Ok(())
+}
+
+/// Checks that the transaction and events in `tx` are the ones its effects commit to, and that
+/// the transaction's digest is `tid`.
+fn verify_transaction_against_digests(
+    tx: &CheckpointTransaction,
+    tid: TransactionDigest,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        *tx.transaction.digest() == tid && *tx.effects.transaction_digest() == tid,
+        "Transaction does not match digest {tid}"
+    );
+
+    // Check the events are all correct. Missing events are not trusted to mean that there were
+    // none: callers that need them check the effects' events digest.
+    if let Some(events) = &tx.events {
+        anyhow::ensure!(
+            Some(&events.digest()) == tx.effects.events_digest(),
+            "Events digest does not match"
+        );
+    }
+
+    Ok(())
+}
+
+// Make a test namespace
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use sui_types::messages_checkpoint::FullCheckpointContents;
+
+    use super::*;
+
+    fn read_data() -> (Committee, CheckpointData) {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("example_config/20873329.yaml");
+
+        let buffer = fs::read(&d).unwrap();
+        let checkpoint: CertifiedCheckpointSummary = bcs::from_bytes(&buffer)
+            .map_err(|_| anyhow!("Unable to parse checkpoint file"))
+            .unwrap();
+
+        // Make a committee object using this
+        let committee = next_epoch_committee(&checkpoint).unwrap();
+
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("example_config/20958462.bcs");
+
+        let buffer = fs::read(&d).unwrap();
+        let full_checkpoint = bcs::from_bytes(&buffer)
+            .map_err(|_| anyhow!("Unable to parse checkpoint file"))
+            .unwrap();
+
+        (committee, full_checkpoint)
+    }
+
+    #[test]
+    fn test_checkpoint_all_good() {
+        let (committee, full_checkpoint) = read_data();
+
+        extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap(),
+        )
+        .unwrap();
+
+        let verified = verify_checkpoint(full_checkpoint.clone(), &committee).unwrap();
+        assert_eq!(
+            verified.transactions.len(),
+            full_checkpoint.transactions.len()
+        );
+        for (verified, tx) in verified
+            .transactions
+            .iter()
+            .zip(&full_checkpoint.transactions)
+        {
+            assert!(verified.input_objects.is_empty());
+            assert_eq!(verified.output_objects, tx.output_objects);
+            assert_eq!(verified.events, tx.events);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_bad_committee() {
+        let (mut committee, full_checkpoint) = read_data();
+
+        // Change committee
+        committee.epoch += 10;
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+
+        assert!(verify_checkpoint(full_checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_no_transaction() {
+        let (committee, full_checkpoint) = read_data();
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_bad_contents() {
+        let (committee, mut full_checkpoint) = read_data();
+
+        // Change contents
+        let random_contents = FullCheckpointContents::random_for_testing();
+        full_checkpoint.checkpoint_contents = random_contents.checkpoint_contents();
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+
+        assert!(verify_checkpoint(full_checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_bad_events() {
+        let (committee, mut full_checkpoint) = read_data();
+
+        let event = full_checkpoint.transactions[4]
+            .events
+            .as_ref()
+            .unwrap()
+            .data[0]
+            .clone();
+
+        for t in &mut full_checkpoint.transactions {
+            if let Some(events) = &mut t.events {
+                events.data.push(event.clone());
+            }
+        }
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+
+        assert!(verify_checkpoint(full_checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_verified_event_and_object() {
+        let (committee, full_checkpoint) = read_data();
+
+        let tx = &full_checkpoint.transactions[4];
+        let tid = *tx.transaction.digest();
+        let event_id = EventID {
+            tx_digest: tid,
+            event_seq: 0,
+        };
+
+        let event = extract_verified_event(&full_checkpoint, &committee, event_id).unwrap();
+        assert_eq!(event, tx.events.as_ref().unwrap().data[0]);
+
+        let (effects, _) =
+            extract_verified_effects_and_events(&full_checkpoint, &committee, tid).unwrap();
+        for object in &tx.output_objects {
+            verify_object_in_effects(object, &effects).unwrap();
+        }
+
+        // Objects written by other transactions are rejected.
+        let other = full_checkpoint
+            .transactions
+            .iter()
+            .find(|other| other.transaction.digest() != tx.transaction.digest())
+            .unwrap();
+        for object in &other.output_objects {
+            assert!(verify_object_in_effects(object, &effects).is_err());
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_bad_output_objects() {
+        let (committee, mut full_checkpoint) = read_data();
+
+        // Attribute an object to a transaction that did not write it.
+        let (i, object) = full_checkpoint
+            .transactions
+            .iter()
+            .enumerate()
+            .find_map(|(i, tx)| Some((i, tx.output_objects.first()?.clone())))
+            .unwrap();
+        let other = (i + 1) % full_checkpoint.transactions.len();
+        full_checkpoint.transactions[other]
+            .output_objects
+            .push(object);
+
+        assert!(verify_checkpoint(full_checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_missing_events() {
+        let (committee, mut full_checkpoint) = read_data();
+
+        let tx = &mut full_checkpoint.transactions[4];
+        let tid = *tx.transaction.digest();
+        assert!(tx.events.take().is_some());
+
+        // The rest of the checkpoint can still be verified, but the transaction's events cannot
+        // be mistaken for being empty.
+        let verified = verify_checkpoint(full_checkpoint.clone(), &committee).unwrap();
+        assert!(verified.transactions[4].events.is_none());
+        assert!(extract_verified_effects_and_events(&full_checkpoint, &committee, tid).is_err());
+        assert!(extract_verified_event(
+            &full_checkpoint,
+            &committee,
+            EventID {
+                tx_digest: tid,
+                event_seq: 0
+            }
+        )
+        .is_err());
+    }
+}
@@ -6,14 +6,21 @@ use axum::{
     extract::{Query, State},
     http::StatusCode,
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use humantime::parse_duration;
 use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
+use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionKillSwitch};
+use sui_config::{Config, NodeConfig};
+use sui_core::authority::AuthorityState;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::error::SuiError;
 use telemetry_subscribers::TracingHandle;
+use tokio::sync::Mutex;
 use tracing::info;
 
 // Example commands:
@@ -47,6 +54,22 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// View the current transaction deny config, as JSON:
+//
+//   $ curl 'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Deny (or allow again) an object, package, package upgrade family or address. Denying a package
+// upgrade family accepts the ID of any version of the package. Changes take effect immediately
+// and are persisted to the node config file.
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/deny?kind=address&id=0x42'
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/allow?kind=package-upgrade-family&id=0x42'
+//
+// Disable user transactions (or re-enable them with disabled=false). The other switches are
+// shared-object, package-publish, package-upgrade, receiving-objects and zklogin-sig.
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/kill-switch?name=user-transaction&disabled=true'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,15 +79,35 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const TRANSACTION_DENY_CONFIG: &str = "/transaction-deny-config";
+const TRANSACTION_DENY_CONFIG_DENY: &str = "/transaction-deny-config/deny";
+const TRANSACTION_DENY_CONFIG_ALLOW: &str = "/transaction-deny-config/allow";
+const TRANSACTION_DENY_CONFIG_KILL_SWITCH: &str = "/transaction-deny-config/kill-switch";
 
 struct AppState {
     node: Arc<SuiNode>,
     tracing_handle: TracingHandle,
 }
 
-pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: TracingHandle) {
+struct DenyConfigState {
+    authority: Arc<AuthorityState>,
+    /// The file the node config was loaded from, which changes to the transaction deny config are
+    /// persisted to.
+    config_path: PathBuf,
+    /// Serializes updates to the transaction deny config, so that concurrent requests don't
+    /// overwrite each other's changes.
+    lock: Mutex<()>,
+}
+
+pub async fn run_admin_server(
+    node: Arc<SuiNode>,
+    port: u16,
+    tracing_handle: TracingHandle,
+    config_path: PathBuf,
+) {
     let filter = tracing_handle.get_log().unwrap();
 
+    let deny_config_routes = transaction_deny_config_routes(node.state(), config_path);
     let app_state = AppState {
         node,
         tracing_handle,
@@ -86,7 +129,8 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
-        .with_state(Arc::new(app_state));
+        .with_state(Arc::new(app_state))
+        .merge(deny_config_routes);
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     info!(
@@ -288,3 +332,329 @@ async fn force_close_epoch(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+/// The routes that view and update the transaction deny config. They only need the authority
+/// state, rather than the whole node.
+fn transaction_deny_config_routes(authority: Arc<AuthorityState>, config_path: PathBuf) -> Router {
+    let state = DenyConfigState {
+        authority,
+        config_path,
+        lock: Mutex::new(()),
+    };
+
+    Router::new()
+        .route(TRANSACTION_DENY_CONFIG, get(transaction_deny_config))
+        .route(TRANSACTION_DENY_CONFIG_DENY, post(deny))
+        .route(TRANSACTION_DENY_CONFIG_ALLOW, post(allow))
+        .route(TRANSACTION_DENY_CONFIG_KILL_SWITCH, post(set_kill_switch))
+        .with_state(Arc::new(state))
+}
+
+async fn transaction_deny_config(
+    State(state): State<Arc<DenyConfigState>>,
+) -> Json<TransactionDenyConfig> {
+    let config = state.authority.transaction_deny_config();
+    Json(TransactionDenyConfig::clone(&config))
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DenyListKind {
+    Object,
+    Package,
+    PackageUpgradeFamily,
+    Address,
+}
+
+#[derive(Deserialize)]
+struct DenyListEntry {
+    kind: DenyListKind,
+    id: String,
+}
+
+async fn deny(
+    State(state): State<Arc<DenyConfigState>>,
+    entry: Query<DenyListEntry>,
+) -> (StatusCode, String) {
+    let Query(DenyListEntry { kind, id }) = entry;
+    update_deny_list(&state, kind, &id, true).await
+}
+
+async fn allow(
+    State(state): State<Arc<DenyConfigState>>,
+    entry: Query<DenyListEntry>,
+) -> (StatusCode, String) {
+    let Query(DenyListEntry { kind, id }) = entry;
+    update_deny_list(&state, kind, &id, false).await
+}
+
+async fn update_deny_list(
+    state: &DenyConfigState,
+    kind: DenyListKind,
+    id: &str,
+    deny: bool,
+) -> (StatusCode, String) {
+    let parse_object_id =
+        |id: &str| ObjectID::from_str(id).map_err(|err| format!("invalid object id {id:?}: {err}"));
+
+    let update: Box<dyn FnOnce(&mut TransactionDenyConfig) -> bool + Send> = match kind {
+        DenyListKind::Object => match parse_object_id(id) {
+            Ok(id) if deny => Box::new(move |config| config.add_denied_object(id)),
+            Ok(id) => Box::new(move |config| config.remove_denied_object(&id)),
+            Err(err) => return (StatusCode::BAD_REQUEST, err),
+        },
+        DenyListKind::Package => match parse_object_id(id) {
+            Ok(id) if deny => Box::new(move |config| config.add_denied_package(id)),
+            Ok(id) => Box::new(move |config| config.remove_denied_package(&id)),
+            Err(err) => return (StatusCode::BAD_REQUEST, err),
+        },
+        DenyListKind::PackageUpgradeFamily => {
+            let id = match parse_object_id(id) {
+                Ok(id) => id,
+                Err(err) => return (StatusCode::BAD_REQUEST, err),
+            };
+
+            // The upgrade family is identified by the original package ID, so look it up from
+            // whichever version of the package was given.
+            let original_id = match state
+                .authority
+                .get_backing_package_store()
+                .get_package_object(&id)
+            {
+                Ok(Some(package)) => package.move_package().original_package_id(),
+                Ok(None) => return (StatusCode::BAD_REQUEST, format!("package {id} not found")),
+                Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+            };
+
+            if deny {
+                Box::new(move |config| config.add_denied_package_upgrade_family(original_id))
+            } else {
+                Box::new(move |config| config.remove_denied_package_upgrade_family(&original_id))
+            }
+        }
+        DenyListKind::Address => match SuiAddress::from_str(id) {
+            Ok(address) if deny => Box::new(move |config| config.add_denied_address(address)),
+            Ok(address) => Box::new(move |config| config.remove_denied_address(&address)),
+            Err(err) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid address {id:?}: {err}"),
+                )
+            }
+        },
+    };
+
+    update_transaction_deny_config(state, update).await
+}
+
+#[derive(Deserialize)]
+struct SetKillSwitch {
+    name: TransactionKillSwitch,
+    disabled: bool,
+}
+
+async fn set_kill_switch(
+    State(state): State<Arc<DenyConfigState>>,
+    kill_switch: Query<SetKillSwitch>,
+) -> (StatusCode, String) {
+    let Query(SetKillSwitch { name, disabled }) = kill_switch;
+    update_transaction_deny_config(&state, move |config| config.set_kill_switch(name, disabled))
+        .await
+}
+
+/// Apply `update` to a copy of the node's transaction deny config, and if it changed, persist it
+/// to the node config file before swapping it in.
+async fn update_transaction_deny_config(
+    state: &DenyConfigState,
+    update: impl FnOnce(&mut TransactionDenyConfig) -> bool,
+) -> (StatusCode, String) {
+    let _guard = state.lock.lock().await;
+    let authority = &state.authority;
+
+    let mut config = TransactionDenyConfig::clone(&authority.transaction_deny_config());
+    if !update(&mut config) {
+        return (
+            StatusCode::OK,
+            "transaction deny config unchanged\n".to_string(),
+        );
+    }
+
+    if let Err(err) = persist_transaction_deny_config(&state.config_path, &config) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("can't persist transaction deny config: {err:?}"),
+        );
+    }
+
+    authority.set_transaction_deny_config(config);
+    (
+        StatusCode::OK,
+        "transaction deny config updated\n".to_string(),
+    )
+}
+
+fn persist_transaction_deny_config(
+    config_path: &Path,
+    config: &TransactionDenyConfig,
+) -> anyhow::Result<()> {
+    // Re-read the config file rather than saving the node's own config, which includes values
+    // that are overridden at startup (e.g. from command line arguments).
+    let mut node_config = NodeConfig::load(config_path)?;
+    node_config.transaction_deny_config = config.clone();
+    node_config.save(config_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use sui_core::authority::test_authority_builder::TestAuthorityBuilder;
+    use sui_swarm_config::network_config_builder::ConfigBuilder;
+    use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// Write a node config to `config_path` and serve the deny config routes for an authority.
+    async fn setup(config_path: &Path) -> (Arc<AuthorityState>, Router) {
+        let network_config = ConfigBuilder::new_with_temp_dir().build();
+        network_config.validator_configs()[0]
+            .save(config_path)
+            .unwrap();
+
+        let authority = TestAuthorityBuilder::new().build().await;
+        let app = transaction_deny_config_routes(authority.clone(), config_path.to_path_buf());
+        (authority, app)
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    async fn get_deny_config(app: &Router) -> TransactionDenyConfig {
+        let (status, body) = send(app, Method::GET, TRANSACTION_DENY_CONFIG).await;
+        assert_eq!(status, StatusCode::OK);
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn update_transaction_deny_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("validator.yaml");
+        let (authority, app) = setup(&config_path).await;
+
+        let config = get_deny_config(&app).await;
+        assert!(config.get_address_deny_set().is_empty());
+        assert!(!config.user_transaction_disabled());
+
+        let address = SuiAddress::from(ObjectID::random());
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("{TRANSACTION_DENY_CONFIG_DENY}?kind=address&id={address}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Any version of a package identifies its upgrade family.
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!(
+                "{TRANSACTION_DENY_CONFIG_DENY}?kind=package-upgrade-family&id={}",
+                SUI_FRAMEWORK_PACKAGE_ID
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("{TRANSACTION_DENY_CONFIG_KILL_SWITCH}?name=user-transaction&disabled=true"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The changes take effect on the authority immediately, and are served back.
+        for config in [
+            get_deny_config(&app).await,
+            TransactionDenyConfig::clone(&authority.transaction_deny_config()),
+        ] {
+            assert!(config.get_address_deny_set().contains(&address));
+            assert!(config
+                .get_package_upgrade_family_deny_set()
+                .contains(&SUI_FRAMEWORK_PACKAGE_ID));
+            assert!(config.user_transaction_disabled());
+        }
+
+        // They are also persisted to the node config file.
+        let persisted = NodeConfig::load(&config_path)
+            .unwrap()
+            .transaction_deny_config;
+        assert!(persisted.get_address_deny_set().contains(&address));
+        assert!(persisted
+            .get_package_upgrade_family_deny_set()
+            .contains(&SUI_FRAMEWORK_PACKAGE_ID));
+        assert!(persisted.user_transaction_disabled());
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("{TRANSACTION_DENY_CONFIG_ALLOW}?kind=address&id={address}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let config = get_deny_config(&app).await;
+        assert!(config.get_address_deny_set().is_empty());
+        let persisted = NodeConfig::load(&config_path)
+            .unwrap()
+            .transaction_deny_config;
+        assert!(persisted.get_address_deny_set().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_deny_list_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("validator.yaml");
+        let (_authority, app) = setup(&config_path).await;
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("{TRANSACTION_DENY_CONFIG_DENY}?kind=object&id=not-an-id"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!(
+                "{TRANSACTION_DENY_CONFIG_DENY}?kind=package-upgrade-family&id={}",
+                ObjectID::random()
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Rejected requests leave both the running and the persisted config untouched.
+        let config = get_deny_config(&app).await;
+        assert!(config.get_object_deny_set().is_empty());
+        assert!(config.get_package_upgrade_family_deny_set().is_empty());
+        let persisted = NodeConfig::load(&config_path)
+            .unwrap()
+            .transaction_deny_config;
+        assert!(persisted.get_object_deny_set().is_empty());
+        assert!(persisted.get_package_upgrade_family_deny_set().is_empty());
+    }
+}
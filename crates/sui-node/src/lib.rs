@@ -456,7 +456,11 @@ impl SuiNode {
         )
         .await?;
         let execution_cache_metrics = Arc::new(ExecutionCacheMetrics::new(&prometheus_registry));
-        let execution_cache = Arc::new(ExecutionCache::new(store.clone(), execution_cache_metrics));
+        let execution_cache = Arc::new(ExecutionCache::new(
+            &config.execution_cache,
+            store.clone(),
+            execution_cache_metrics,
+        ));
 
         let cur_epoch = store.get_recovery_epoch_at_restart()?;
         let committee = committee_store
@@ -1178,6 +1182,7 @@ impl SuiNode {
             fail_point!("starting_overload_monitor");
             Some(spawn_monitored_task!(overload_monitor(
                 authority_state,
+                consensus_adapter.clone(),
                 overload_config,
             )))
         } else {
@@ -1874,6 +1879,9 @@ pub fn build_http_server(
 
     let json_rpc_router = {
         let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+        if let Some(max_batch_size) = config.json_rpc_max_batch_size {
+            server.set_max_batch_size(max_batch_size);
+        }
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -12,7 +12,7 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-const MAX_PROTOCOL_VERSION: u64 = 40;
+const MAX_PROTOCOL_VERSION: u64 = 41;
 
 // Record history of protocol version allocations here:
 //
@@ -387,6 +387,11 @@ struct FeatureFlags {
     // Reject functions with mutable Random.
     #[serde(skip_serializing_if = "is_false")]
     reject_mutable_random_on_entry_functions: bool,
+
+    // If true, Mysticeti updates its leader schedule from the reputation scores of the
+    // authorities, swapping out the leader slots of the lowest scoring ones.
+    #[serde(skip_serializing_if = "is_false")]
+    mysticeti_leader_scoring_and_schedule: bool,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -405,6 +410,10 @@ pub enum ConsensusTransactionOrdering {
     None,
     /// Order transactions by gas price, highest first.
     ByGasPrice,
+    /// Order transactions by gas price, highest first, and defer transactions that mutate a shared
+    /// object which has already been mutated by `max_txns_per_shared_object_in_commit`
+    /// transactions in the same commit to a later commit.
+    ByGasPriceWithCongestionControl,
 }
 
 impl ConsensusTransactionOrdering {
@@ -955,6 +964,21 @@ pub struct ProtocolConfig {
     consensus_max_transaction_size_bytes: Option<u64>,
     /// The maximum size of transactions included in a consensus proposed block
     consensus_max_transactions_in_block_bytes: Option<u64>,
+    /// The number of rounds below the last committed leader round, at or below which blocks are
+    /// garbage collected by consensus. Garbage collected blocks are not included in later commits,
+    /// even if they have not been committed yet.
+    consensus_gc_depth: Option<u32>,
+
+    /// === congestion control ===
+
+    /// The maximum number of transactions that can mutate the same shared object in one consensus
+    /// commit, when ordering with `ConsensusTransactionOrdering::ByGasPriceWithCongestionControl`.
+    /// Further transactions on that object are deferred to later commits.
+    max_txns_per_shared_object_in_commit: Option<u64>,
+
+    /// The maximum number of rounds a transaction can be deferred for due to shared object
+    /// congestion, after which it is scheduled regardless of the budget.
+    max_deferral_rounds_for_congestion_control: Option<u64>,
 }
 
 // feature flags
@@ -1172,6 +1196,10 @@ impl ProtocolConfig {
     pub fn reject_mutable_random_on_entry_functions(&self) -> bool {
         self.feature_flags.reject_mutable_random_on_entry_functions
     }
+
+    pub fn mysticeti_leader_scoring_and_schedule(&self) -> bool {
+        self.feature_flags.mysticeti_leader_scoring_and_schedule
+    }
 }
 
 #[cfg(not(msim))]
@@ -1603,6 +1631,12 @@ impl ProtocolConfig {
             consensus_max_transaction_size_bytes: None,
 
             consensus_max_transactions_in_block_bytes: None,
+
+            consensus_gc_depth: None,
+
+            max_txns_per_shared_object_in_commit: None,
+
+            max_deferral_rounds_for_congestion_control: None,
             // When adding a new constant, set it to None in the earliest version, like this:
             // new_constant: None,
         };
@@ -1961,6 +1995,14 @@ impl ProtocolConfig {
                     // It is important that we keep this protocol version blank due to an issue with random.move.
                 }
                 40 => {}
+                41 => {
+                    // Enable leader scoring & schedule change and garbage collection of
+                    // uncommitted blocks on mysticeti consensus on devnet
+                    if chain != Chain::Mainnet && chain != Chain::Testnet {
+                        cfg.feature_flags.mysticeti_leader_scoring_and_schedule = true;
+                        cfg.consensus_gc_depth = Some(1_000);
+                    }
+                }
                 // Use this template when making changes:
                 //
                 //     // modify an existing constant.
@@ -2038,6 +2080,9 @@ impl ProtocolConfig {
     pub fn set_consensus_bad_nodes_stake_threshold(&mut self, val: u64) {
         self.consensus_bad_nodes_stake_threshold = Some(val);
     }
+    pub fn set_mysticeti_leader_scoring_and_schedule_for_testing(&mut self, val: bool) {
+        self.feature_flags.mysticeti_leader_scoring_and_schedule = val;
+    }
     pub fn set_receive_object_for_testing(&mut self, val: bool) {
         self.feature_flags.receive_objects = val
     }
@@ -2056,6 +2101,15 @@ impl ProtocolConfig {
     pub fn set_consensus_max_transactions_in_block_bytes(&mut self, val: u64) {
         self.consensus_max_transactions_in_block_bytes = Some(val);
     }
+    pub fn set_consensus_gc_depth_for_testing(&mut self, val: u32) {
+        self.consensus_gc_depth = Some(val);
+    }
+    pub fn set_consensus_transaction_ordering_for_testing(
+        &mut self,
+        val: ConsensusTransactionOrdering,
+    ) {
+        self.feature_flags.consensus_transaction_ordering = val;
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
@@ -1,7 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use axum::extract::{Path, State};
+use std::time::Duration;
+
+use axum::body::StreamBody;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
 use sui_types::{full_checkpoint_content::CheckpointData, messages_checkpoint::CheckpointDigest};
 use sui_types::{
     messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber},
@@ -9,7 +14,10 @@ use sui_types::{
 };
 use tap::Pipe;
 
-use crate::{accept::AcceptFormat, response::Bcs, response::ResponseContent, Result};
+use crate::{
+    accept::AcceptFormat, response::Bcs, response::ResponseContent, RestError, Result,
+    APPLICATION_BCS,
+};
 
 pub const GET_LATEST_CHECKPOINT_PATH: &str = "/checkpoints";
 pub const GET_CHECKPOINT_PATH: &str = "/checkpoints/:checkpoint";
@@ -40,17 +48,146 @@ pub async fn get_full_checkpoint<S: ReadStore>(
     Ok(Bcs(checkpoint_data))
 }
 
-pub async fn get_latest_checkpoint<S: ReadStore>(
+#[derive(Debug, serde::Deserialize)]
+pub struct StreamCheckpoints {
+    /// Stream checkpoints starting from this sequence number, instead of returning the latest
+    /// checkpoint.
+    start: Option<CheckpointSequenceNumber>,
+}
+
+/// How long a checkpoint stream waits before checking for new checkpoints again, once it has
+/// caught up with the latest executed checkpoint.
+const STREAM_CHECKPOINTS_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub async fn get_latest_checkpoint<S>(
+    Query(StreamCheckpoints { start }): Query<StreamCheckpoints>,
     accept: AcceptFormat,
     State(state): State<S>,
-) -> Result<ResponseContent<CertifiedCheckpointSummary>> {
-    let summary = state.get_latest_checkpoint()?.into();
+) -> Result<Response>
+where
+    S: ReadStore + Clone + Send + Sync + 'static,
+{
+    if let Some(start) = start {
+        return stream_checkpoints(start, accept, state);
+    }
 
-    match accept {
+    let summary: CertifiedCheckpointSummary = state.get_latest_checkpoint()?.into();
+
+    let response: ResponseContent<CertifiedCheckpointSummary> = match accept {
         AcceptFormat::Json => ResponseContent::Json(summary),
         AcceptFormat::Bcs => ResponseContent::Bcs(summary),
+    };
+
+    response.into_response().pipe(Ok)
+}
+
+/// Responds with the full contents of every checkpoint from `start` onwards, as a stream of
+/// [`CheckpointData`] frames (see [`encode_checkpoint_frame`]). Once the stream catches up with
+/// the latest executed checkpoint, it waits for new checkpoints to be executed, until the client
+/// disconnects.
+fn stream_checkpoints<S>(
+    start: CheckpointSequenceNumber,
+    accept: AcceptFormat,
+    state: S,
+) -> Result<Response>
+where
+    S: ReadStore + Clone + Send + Sync + 'static,
+{
+    match accept {
+        AcceptFormat::Bcs => {}
+        _ => return Err(anyhow::anyhow!("invalid accept type").into()),
+    }
+
+    let oldest = state.get_lowest_available_checkpoint()?;
+    if start < oldest {
+        return Err(RestError::new(
+            StatusCode::GONE,
+            format!(
+                "Checkpoint {start} has been pruned, the oldest available checkpoint is {oldest}"
+            ),
+        ));
+    }
+
+    let frames = futures::stream::unfold(Some(start), move |next| {
+        let state = state.clone();
+        async move {
+            // A failed stream is not resumed, the client needs to reconnect.
+            let next = next?;
+            loop {
+                match checkpoint_frame(&state, next) {
+                    Ok(Some(frame)) => return Some((Ok(frame), Some(next + 1))),
+                    Ok(None) => tokio::time::sleep(STREAM_CHECKPOINTS_POLL_INTERVAL).await,
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        }
+    });
+
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static(APPLICATION_BCS),
+        )],
+        StreamBody::new(frames),
+    )
+        .into_response())
+}
+
+/// The frame for checkpoint `sequence_number`, or `None` if it has not been executed yet.
+fn checkpoint_frame<S: ReadStore>(
+    state: &S,
+    sequence_number: CheckpointSequenceNumber,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    if sequence_number > state.get_latest_checkpoint_sequence_number()? {
+        return Ok(None);
+    }
+
+    let verified_summary = state
+        .get_checkpoint_by_sequence_number(sequence_number)?
+        .ok_or_else(|| anyhow::anyhow!("Checkpoint {sequence_number} is missing"))?;
+    let checkpoint_contents = state
+        .get_checkpoint_contents_by_digest(&verified_summary.content_digest)?
+        .ok_or_else(|| anyhow::anyhow!("Contents of checkpoint {sequence_number} are missing"))?;
+    let checkpoint_data = state.get_checkpoint_data(verified_summary, checkpoint_contents)?;
+
+    Ok(Some(encode_checkpoint_frame(&bcs::to_bytes(
+        &checkpoint_data,
+    )?)))
+}
+
+/// Each frame in a checkpoint stream is the BCS encoding of a [`CheckpointData`], prefixed with
+/// its length as a little-endian `u32`.
+pub(crate) fn encode_checkpoint_frame(bcs: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(4 + bcs.len());
+    frame.extend_from_slice(&(bcs.len() as u32).to_le_bytes());
+    frame.extend_from_slice(bcs);
+    frame
+}
+
+/// Splits the bytes of a checkpoint stream, received in arbitrarily sized chunks, back into the
+/// BCS encodings of the [`CheckpointData`] in each frame.
+#[derive(Debug, Default)]
+pub(crate) struct CheckpointFrameDecoder {
+    buf: Vec<u8>,
+}
+
+impl CheckpointFrameDecoder {
+    pub fn extend(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// The next complete frame, if it has been fully received.
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let len = u32::from_le_bytes(self.buf.get(..4)?.try_into().unwrap()) as usize;
+        let frame = self.buf.get(4..4 + len)?.to_vec();
+        self.buf.drain(..4 + len);
+        Some(frame)
+    }
+
+    /// Whether there are no bytes left over from a partially received frame.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
     }
-    .pipe(Ok)
 }
 
 pub async fn get_checkpoint<S: ReadStore>(
@@ -132,3 +269,37 @@ impl From<CheckpointNotFoundError> for crate::RestError {
         Self::new(axum::http::StatusCode::NOT_FOUND, value.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_frames() {
+        let payloads: Vec<Vec<u8>> = vec![vec![1, 2, 3], vec![], (0..=255).collect()];
+        let stream: Vec<u8> = payloads
+            .iter()
+            .flat_map(|p| encode_checkpoint_frame(p))
+            .collect();
+
+        // Frames are reassembled regardless of how the stream is split into chunks.
+        for chunk_size in [1, 2, 5, stream.len()] {
+            let mut decoder = CheckpointFrameDecoder::default();
+            let mut decoded = vec![];
+            for chunk in stream.chunks(chunk_size) {
+                decoder.extend(chunk);
+                while let Some(frame) = decoder.next_frame() {
+                    decoded.push(frame);
+                }
+            }
+
+            assert_eq!(decoded, payloads);
+            assert!(decoder.is_empty());
+        }
+
+        let mut decoder = CheckpointFrameDecoder::default();
+        decoder.extend(&stream[..stream.len() - 1]);
+        while decoder.next_frame().is_some() {}
+        assert!(!decoder.is_empty());
+    }
+}
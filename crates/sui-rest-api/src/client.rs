@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use futures::Stream;
 use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::full_checkpoint_content::CheckpointData;
 use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
 use sui_types::object::Object;
+use sui_types::transaction::SenderSignedData;
+
+use crate::checkpoints::CheckpointFrameDecoder;
 
 #[derive(Clone)]
 pub struct Client {
@@ -99,6 +105,100 @@ impl Client {
         self.bcs(response).await
     }
 
+    /// Follow the chain from checkpoint `start` onwards. The stream yields every checkpoint in
+    /// order, waiting for new ones once it has caught up, and ends after the first error.
+    pub async fn stream_checkpoints(
+        &self,
+        start: CheckpointSequenceNumber,
+    ) -> Result<impl Stream<Item = Result<CheckpointData>>> {
+        let url = format!("{}/checkpoints?start={start}", self.base_url);
+
+        let response = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
+            .send()
+            .await?;
+        let response = self.check_response(response)?;
+
+        let stream = futures::stream::unfold(
+            Some((response, CheckpointFrameDecoder::default())),
+            |state| async move {
+                let (mut response, mut decoder) = state?;
+                loop {
+                    if let Some(frame) = decoder.next_frame() {
+                        let checkpoint =
+                            bcs::from_bytes::<CheckpointData>(&frame).map_err(Into::into);
+                        return Some((checkpoint, Some((response, decoder))));
+                    }
+
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => decoder.extend(&chunk),
+                        Ok(None) if decoder.is_empty() => return None,
+                        Ok(None) => {
+                            let err = anyhow::anyhow!("checkpoint stream ended mid-frame");
+                            return Some((Err(err), None));
+                        }
+                        Err(e) => return Some((Err(e.into()), None)),
+                    }
+                }
+            },
+        );
+
+        Ok(stream)
+    }
+
+    pub async fn get_transaction(
+        &self,
+        transaction_digest: TransactionDigest,
+    ) -> Result<SenderSignedData> {
+        let url = format!("{}/transactions/{transaction_digest}", self.base_url);
+
+        let response = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
+            .send()
+            .await?;
+
+        self.bcs(response).await
+    }
+
+    pub async fn get_transaction_effects(
+        &self,
+        transaction_digest: TransactionDigest,
+    ) -> Result<TransactionEffects> {
+        let url = format!(
+            "{}/transactions/{transaction_digest}/effects",
+            self.base_url
+        );
+
+        let response = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
+            .send()
+            .await?;
+
+        self.bcs(response).await
+    }
+
+    pub async fn get_transaction_events(
+        &self,
+        transaction_digest: TransactionDigest,
+    ) -> Result<TransactionEvents> {
+        let url = format!("{}/transactions/{transaction_digest}/events", self.base_url);
+
+        let response = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
+            .send()
+            .await?;
+
+        self.bcs(response).await
+    }
+
     fn check_response(&self, response: reqwest::Response) -> Result<reqwest::Response> {
         if !response.status().is_success() {
             let status = response.status();
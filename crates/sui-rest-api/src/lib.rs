@@ -11,6 +11,7 @@ mod health;
 mod info;
 mod objects;
 mod response;
+mod transactions;
 pub mod types;
 
 pub use client::Client;
@@ -107,5 +108,17 @@ where
             objects::GET_OBJECT_WITH_VERSION_PATH,
             get(objects::get_object_with_version::<S>),
         )
+        .route(
+            transactions::GET_TRANSACTION_PATH,
+            get(transactions::get_transaction::<S>),
+        )
+        .route(
+            transactions::GET_TRANSACTION_EFFECTS_PATH,
+            get(transactions::get_transaction_effects::<S>),
+        )
+        .route(
+            transactions::GET_TRANSACTION_EVENTS_PATH,
+            get(transactions::get_transaction_events::<S>),
+        )
         .with_state(state)
 }
@@ -0,0 +1,94 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::{Path, State};
+use sui_types::{
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    storage::ReadStore,
+    transaction::SenderSignedData,
+};
+use tap::Pipe;
+
+use crate::{accept::AcceptFormat, response::ResponseContent, Result};
+
+pub const GET_TRANSACTION_PATH: &str = "/transactions/:transaction";
+
+pub async fn get_transaction<S: ReadStore>(
+    Path(transaction_digest): Path<TransactionDigest>,
+    accept: AcceptFormat,
+    State(state): State<S>,
+) -> Result<ResponseContent<SenderSignedData>> {
+    let transaction = state
+        .get_transaction(&transaction_digest)?
+        .ok_or(TransactionNotFoundError(transaction_digest))?
+        .data()
+        .clone();
+
+    match accept {
+        AcceptFormat::Json => ResponseContent::Json(transaction),
+        AcceptFormat::Bcs => ResponseContent::Bcs(transaction),
+    }
+    .pipe(Ok)
+}
+
+pub const GET_TRANSACTION_EFFECTS_PATH: &str = "/transactions/:transaction/effects";
+
+pub async fn get_transaction_effects<S: ReadStore>(
+    Path(transaction_digest): Path<TransactionDigest>,
+    accept: AcceptFormat,
+    State(state): State<S>,
+) -> Result<ResponseContent<TransactionEffects>> {
+    let effects = state
+        .get_transaction_effects(&transaction_digest)?
+        .ok_or(TransactionNotFoundError(transaction_digest))?;
+
+    match accept {
+        AcceptFormat::Json => ResponseContent::Json(effects),
+        AcceptFormat::Bcs => ResponseContent::Bcs(effects),
+    }
+    .pipe(Ok)
+}
+
+pub const GET_TRANSACTION_EVENTS_PATH: &str = "/transactions/:transaction/events";
+
+pub async fn get_transaction_events<S: ReadStore>(
+    Path(transaction_digest): Path<TransactionDigest>,
+    accept: AcceptFormat,
+    State(state): State<S>,
+) -> Result<ResponseContent<TransactionEvents>> {
+    let effects = state
+        .get_transaction_effects(&transaction_digest)?
+        .ok_or(TransactionNotFoundError(transaction_digest))?;
+
+    // Transactions that did not emit any events have no events digest.
+    let events = match effects.events_digest() {
+        Some(events_digest) => state.get_events(events_digest)?.ok_or_else(|| {
+            anyhow::anyhow!("Events for transaction {transaction_digest} are missing")
+        })?,
+        None => TransactionEvents::default(),
+    };
+
+    match accept {
+        AcceptFormat::Json => ResponseContent::Json(events),
+        AcceptFormat::Bcs => ResponseContent::Bcs(events),
+    }
+    .pipe(Ok)
+}
+
+#[derive(Debug)]
+pub struct TransactionNotFoundError(TransactionDigest);
+
+impl std::fmt::Display for TransactionNotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction {} not found", self.0)
+    }
+}
+
+impl std::error::Error for TransactionNotFoundError {}
+
+impl From<TransactionNotFoundError> for crate::RestError {
+    fn from(value: TransactionNotFoundError) -> Self {
+        Self::new(axum::http::StatusCode::NOT_FOUND, value.to_string())
+    }
+}
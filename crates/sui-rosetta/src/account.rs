@@ -7,16 +7,18 @@ use axum_extra::extract::WithRejection;
 use futures::StreamExt;
 
 use sui_sdk::rpc_types::StakeStatus;
-use sui_sdk::{SuiClient, SUI_COIN_TYPE};
+use sui_sdk::SuiClient;
 use sui_types::base_types::SuiAddress;
+use sui_types::gas_coin::GAS;
+use sui_types::parse_sui_type_tag;
 use tracing::info;
 
 use crate::errors::Error;
 use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
-    Amount, Coin, SubAccount, SubAccountType, SubBalance,
+    Amount, Coin, Currency, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 use std::time::Duration;
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
@@ -68,14 +70,14 @@ pub async fn balance(
         }
         Err(Error::RetryExhausted(String::from("retry")))
     } else {
+        let currencies = if request.currencies.is_empty() {
+            account_currencies(&ctx, address).await?
+        } else {
+            resolve_currencies(&ctx, &request.currencies).await?
+        };
         // Get current live balance
         while retry_attempts > 0 {
-            let balances_first = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_first = get_balances(&ctx.client, address, &currencies).await?;
 
             // Get current latest checkpoint
             let checkpoint1 = ctx
@@ -101,12 +103,7 @@ pub async fn balance(
             }
 
             // Get live balance again
-            let balances_second = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_second = get_balances(&ctx.client, address, &currencies).await?;
 
             // if those two live balances are equal then that is the current balance for checkpoint2
             if balances_first.eq(&balances_second) {
@@ -116,7 +113,7 @@ pub async fn balance(
                 );
                 return Ok(AccountBalanceResponse {
                     block_identifier: ctx.blocks().create_block_identifier(checkpoint2).await?,
-                    balances: vec![Amount::new(balances_first)],
+                    balances: balances_first,
                 });
             } else {
                 // balances are different so we need to try again.
@@ -131,6 +128,70 @@ pub async fn balance(
     }
 }
 
+async fn get_balances(
+    client: &SuiClient,
+    address: SuiAddress,
+    currencies: &[Currency],
+) -> Result<Vec<Amount>, Error> {
+    let mut balances = vec![];
+    for currency in currencies {
+        let balance = client
+            .coin_read_api()
+            .get_balance(address, Some(currency.metadata.coin_type.clone()))
+            .await?
+            .total_balance as i128;
+        balances.push(Amount::new_with_currency(balance, currency.clone()));
+    }
+    Ok(balances)
+}
+
+/// The currencies of all coins owned by `address`, starting with SUI, which is always included.
+/// Coin types without `CoinMetadata` are skipped, as they have no currency.
+async fn account_currencies(
+    ctx: &OnlineServerContext,
+    address: SuiAddress,
+) -> Result<Vec<Currency>, Error> {
+    let mut balances = ctx.client.coin_read_api().get_all_balances(address).await?;
+    // Keep the order of the balances stable across calls.
+    balances.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+
+    let mut currencies = vec![SUI.clone()];
+    for balance in balances {
+        let coin_type = parse_sui_type_tag(&balance.coin_type)?;
+        if coin_type == GAS::type_tag() {
+            continue;
+        }
+        if let Some(currency) = ctx.coin_metadata_cache.get_currency(&coin_type).await? {
+            currencies.push(currency);
+        }
+    }
+    Ok(currencies)
+}
+
+/// The currencies requested by the client, with their symbol and decimals read from their
+/// `CoinMetadata`.
+async fn resolve_currencies(
+    ctx: &OnlineServerContext,
+    currencies: &[Currency],
+) -> Result<Vec<Currency>, Error> {
+    let mut resolved = vec![];
+    for currency in currencies {
+        let coin_type = currency.coin_type()?;
+        let currency = ctx
+            .coin_metadata_cache
+            .get_currency(&coin_type)
+            .await?
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "No coin metadata found for coin type [{}]",
+                    currency.metadata.coin_type
+                ))
+            })?;
+        resolved.push(currency);
+    }
+    Ok(resolved)
+}
+
 async fn get_sub_account_balances(
     account_type: SubAccountType,
     client: &SuiClient,
@@ -201,16 +262,24 @@ pub async fn coins(
     WithRejection(Json(request), _): WithRejection<Json<AccountCoinsRequest>, Error>,
 ) -> Result<AccountCoinsResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let coins = context
-        .client
-        .coin_read_api()
-        .get_coins_stream(
-            request.account_identifier.address,
-            Some(SUI_COIN_TYPE.to_string()),
-        )
-        .map(Coin::from)
-        .collect()
-        .await;
+    let address = request.account_identifier.address;
+    let currencies = if request.currencies.is_empty() {
+        account_currencies(&context, address).await?
+    } else {
+        resolve_currencies(&context, &request.currencies).await?
+    };
+
+    let mut coins = vec![];
+    for currency in currencies {
+        let currency_coins: Vec<_> = context
+            .client
+            .coin_read_api()
+            .get_coins_stream(address, Some(currency.metadata.coin_type.clone()))
+            .map(|coin| Coin::new(coin, currency.clone()))
+            .collect()
+            .await;
+        coins.extend(currency_coins);
+    }
 
     Ok(AccountCoinsResponse {
         block_identifier: context.blocks().current_block_identifier().await?,
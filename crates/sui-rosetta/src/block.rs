@@ -6,6 +6,7 @@ use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
 use tracing::debug;
 
+use crate::operations::Operations;
 use crate::types::{
     BlockRequest, BlockResponse, BlockTransactionRequest, BlockTransactionResponse, Transaction,
     TransactionIdentifier,
@@ -52,12 +53,13 @@ pub async fn transaction(
                 .with_input()
                 .with_events()
                 .with_effects()
-                .with_balance_changes(),
+                .with_balance_changes()
+                .with_object_changes(),
         )
         .await?;
     let hash = response.digest;
 
-    let operations = response.try_into()?;
+    let operations = Operations::try_from_response(response, &context.coin_metadata_cache).await?;
 
     let transaction = Transaction {
         transaction_identifier: TransactionIdentifier { hash },
@@ -61,10 +61,14 @@ pub async fn payloads(
     let metadata = request.metadata.ok_or(Error::MissingMetadata)?;
     let address = metadata.sender;
 
-    let data = request
-        .operations
-        .into_internal()?
-        .try_into_data(metadata)?;
+    let internal_operation = request.operations.into_internal()?;
+    // The input coins in the metadata were selected for the currency of the operations.
+    if internal_operation.pay_coin_currency() != metadata.currency.as_ref() {
+        return Err(Error::InvalidInput(
+            "Operations currency does not match the metadata".to_string(),
+        ));
+    }
+    let data = internal_operation.try_into_data(metadata)?;
     let intent_msg = IntentMessage::new(Intent::sui_transaction(), data);
     let intent_msg_bytes = bcs::to_bytes(&intent_msg)?;
 
@@ -226,6 +230,7 @@ pub async fn metadata(
     let option = request.options.ok_or(Error::MissingMetadata)?;
     let budget = option.budget;
     let sender = option.internal_operation.sender();
+    let currency = option.internal_operation.pay_coin_currency().cloned();
     let mut gas_price = context
         .client
         .governance_api()
@@ -240,6 +245,41 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![])
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            // The currency is reported back in the transaction's operations, so it has to match
+            // the coin metadata.
+            let coin_type = currency.coin_type()?;
+            let expected = context.coin_metadata_cache.get_currency(&coin_type).await?;
+            if expected.as_ref() != Some(currency) {
+                return Err(Error::InvalidInput(format!(
+                    "Currency {currency:?} does not match the coin metadata of [{}]",
+                    currency.metadata.coin_type
+                )));
+            }
+
+            let amount = amounts.iter().sum::<u64>();
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    *sender,
+                    Some(currency.metadata.coin_type.clone()),
+                    amount.into(),
+                    vec![],
+                )
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+
+            // Only gas is paid in SUI.
+            (Some(0), coins)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![]),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
@@ -300,6 +340,7 @@ pub async fn metadata(
                     gas_price,
                     // MAX BUDGET
                     budget: 50_000_000_000,
+                    currency: currency.clone(),
                 })?;
 
             let dry_run = context
@@ -356,6 +397,7 @@ pub async fn metadata(
             total_coin_value,
             gas_price,
             budget,
+            currency,
         },
         suggested_fee: vec![Amount::new(budget as i128)],
     })
@@ -1,21 +1,25 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum::routing::post;
 use axum::{Extension, Router};
 use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tracing::info;
 
+use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use sui_sdk::SuiClient;
+use sui_types::gas_coin::GAS;
 
 use crate::errors::Error;
 use crate::state::{CheckpointBlockProvider, OnlineServerContext};
-use crate::types::{Currency, SuiEnv};
+use crate::types::{Currency, CurrencyMetadata, SuiEnv};
 
 /// This lib implements the Rosetta online and offline server defined by the [Rosetta API Spec](https://www.rosetta-api.org/docs/Reference.html)
 mod account;
@@ -30,8 +34,60 @@ pub mod types;
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: CurrencyMetadata::default(),
 });
 
+const COIN_METADATA_CACHE_SIZE: usize = 10_000;
+
+/// Resolves the [`Currency`] of a `Coin<T>` type from its `CoinMetadata` object. Coin metadata
+/// rarely changes after the coin type is published, so lookups are cached, including the coin
+/// types that have no metadata.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<HashMap<TypeTag, Option<Currency>>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Default::default(),
+        }
+    }
+
+    /// The currency of `coin_type`, or `None` if the coin type has no `CoinMetadata`.
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Option<Currency>, Error> {
+        if *coin_type == GAS::type_tag() {
+            return Ok(Some(SUI.clone()));
+        }
+        if let Some(currency) = self.currencies.lock().await.get(coin_type) {
+            return Ok(currency.clone());
+        }
+
+        let coin_type_str = coin_type.to_canonical_string(true);
+        let currency = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type_str.clone())
+            .await?
+            .map(|metadata| Currency {
+                symbol: metadata.symbol,
+                decimals: metadata.decimals as u64,
+                metadata: CurrencyMetadata {
+                    coin_type: coin_type_str,
+                },
+            });
+
+        let mut currencies = self.currencies.lock().await;
+        if currencies.len() >= COIN_METADATA_CACHE_SIZE {
+            currencies.clear();
+        }
+        currencies.insert(coin_type.clone(), currency.clone());
+        Ok(currency)
+    }
+}
+
 pub struct RosettaOnlineServer {
     env: SuiEnv,
     context: OnlineServerContext,
@@ -39,10 +95,14 @@ pub struct RosettaOnlineServer {
 
 impl RosettaOnlineServer {
     pub fn new(env: SuiEnv, client: SuiClient) -> Self {
-        let blocks = Arc::new(CheckpointBlockProvider::new(client.clone()));
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        let blocks = Arc::new(CheckpointBlockProvider::new(
+            client.clone(),
+            coin_metadata_cache.clone(),
+        ));
         Self {
             env,
-            context: OnlineServerContext::new(client, blocks),
+            context: OnlineServerContext::new(client, blocks, coin_metadata_cache),
         }
     }
 
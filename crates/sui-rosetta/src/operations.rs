@@ -8,21 +8,22 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
 
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransactionBlock;
-use sui_json_rpc_types::{BalanceChange, SuiArgument};
+use sui_json_rpc_types::{BalanceChange, ObjectChange, SuiArgument};
 use sui_json_rpc_types::{SuiCallArg, SuiCommand};
 use sui_sdk::rpc_types::{
     SuiTransactionBlockData, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
-use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::coin::Coin;
+use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
@@ -30,10 +31,10 @@ use sui_types::transaction::TransactionData;
 use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{CoinMetadataCache, Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -101,6 +102,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -108,11 +110,59 @@ impl Operations {
     }
 
     fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        if currency.is_some_and(|currency| currency != *SUI) {
+            return Err(Error::InvalidInput(
+                "PaySui can only transfer SUI, use PayCoin for other currencies.".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PaySui {
+            sender,
+            recipients,
+            amounts,
+        })
+    }
+
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        let mut currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        if currency == *SUI {
+            return Err(Error::InvalidInput(
+                "PayCoin cannot transfer SUI, use PaySui instead.".to_string(),
+            ));
+        }
+        // The currency is reported back in the transaction's operations, use the same coin type
+        // format as the currencies resolved from coin metadata.
+        currency.metadata.coin_type = currency.coin_type()?.to_canonical_string(true);
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
+    /// The sender, recipients and amounts of a payment, and the currency that all of its amounts
+    /// are in.
+    #[allow(clippy::type_complexity)]
+    fn pay_ops_to_internal(
+        self,
+    ) -> Result<(SuiAddress, Vec<SuiAddress>, Vec<u64>, Option<Currency>), Error> {
         let mut recipients = vec![];
         let mut amounts = vec![];
         let mut sender = None;
+        let mut currency = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                match &currency {
+                    Some(currency) if *currency != amount.currency => {
+                        return Err(Error::MalformedOperationError(
+                            "All amounts of a payment should be in the same currency.".into(),
+                        ))
+                    }
+                    Some(_) => {}
+                    None => currency = Some(amount.currency.clone()),
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -128,11 +178,7 @@ impl Operations {
             }
         }
         let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
-        Ok(InternalOperation::PaySui {
-            sender,
-            recipients,
-            amounts,
-        })
+        Ok((sender, recipients, amounts, currency))
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
@@ -217,10 +263,11 @@ impl Operations {
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, currency)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
@@ -230,6 +277,7 @@ impl Operations {
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
@@ -249,6 +297,7 @@ impl Operations {
             known_results: &[Vec<KnownValue>],
             coin: SuiArgument,
             amounts: &[SuiArgument],
+            currency: Option<&Currency>,
         ) -> Option<Vec<KnownValue>> {
             match coin {
                 SuiArgument::Result(i) => {
@@ -257,9 +306,13 @@ impl Operations {
                 SuiArgument::NestedResult(i, j) => {
                     let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
                 }
-                SuiArgument::GasCoin => (),
+                SuiArgument::GasCoin if currency.is_none() => (),
+                // The coins of a PayCoin transaction, which are all in its currency.
+                SuiArgument::Input(i) if currency.is_some() => {
+                    inputs.get(i as usize)?.object()?;
+                }
                 // Might not be a SUI coin
-                SuiArgument::Input(_) => return None,
+                SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
             };
             let amounts = amounts
                 .iter()
@@ -278,6 +331,19 @@ impl Operations {
                 .collect::<Option<_>>()?;
             Some(amounts)
         }
+        fn merge_coins(
+            inputs: &[SuiCallArg],
+            coin: SuiArgument,
+            coins: &[SuiArgument],
+        ) -> Option<Vec<KnownValue>> {
+            for coin in std::iter::once(&coin).chain(coins) {
+                let SuiArgument::Input(i) = coin else {
+                    return None;
+                };
+                inputs.get(*i as usize)?.object()?;
+            }
+            Some(vec![])
+        }
         fn transfer_object(
             aggregated_recipients: &mut HashMap<SuiAddress, u64>,
             inputs: &[SuiCallArg],
@@ -369,7 +435,10 @@ impl Operations {
         for command in commands {
             let result = match command {
                 SuiCommand::SplitCoins(coin, amounts) => {
-                    split_coins(inputs, &known_results, *coin, amounts)
+                    split_coins(inputs, &known_results, *coin, amounts, currency.as_ref())
+                }
+                SuiCommand::MergeCoins(coin, coins) if currency.is_some() => {
+                    merge_coins(inputs, *coin, coins)
                 }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
@@ -378,7 +447,7 @@ impl Operations {
                     objs,
                     *addr,
                 ),
-                SuiCommand::MoveCall(m) if Self::is_stake_call(m) => {
+                SuiCommand::MoveCall(m) if currency.is_none() && Self::is_stake_call(m) => {
                     stake_call(inputs, &known_results, m)?.map(|(amount, validator)| {
                         let amount = amount.map(|amount| Amount::new(-(amount as i128)));
                         operations.push(Operation {
@@ -410,14 +479,16 @@ impl Operations {
 
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
+            let pay = |address: SuiAddress, amount: i128| match &currency {
+                Some(currency) => Operation::pay_coin(status, address, amount, currency.clone()),
+                None => Operation::pay_sui(status, address, amount),
+            };
             operations.extend(
                 aggregated_recipients
                     .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
+                    .map(|(recipient, amount)| pay(recipient, amount.into())),
             );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            operations.push(pay(sender, -(total_paid as i128)));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -459,27 +530,31 @@ impl Operations {
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
+        currencies: &HashMap<TypeTag, Currency>,
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
+        balances: HashMap<(SuiAddress, Currency), i128>,
     ) -> impl Iterator<Item = Operation> {
         let mut balances = balance_changes
             .iter()
             .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
+                // Rosetta only care about address owner, and coins that have a currency
+                if let (Owner::AddressOwner(owner), Some(currency)) = (
+                    balance_change.owner,
+                    currencies.get(&balance_change.coin_type),
+                ) {
+                    *balances.entry((owner, currency.clone())).or_default() +=
+                        balance_change.amount;
                 }
                 balances
             });
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, amount, currency)
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -499,13 +574,38 @@ impl TryFrom<SuiTransactionBlockData> for Operations {
             data.transaction().clone(),
             sender,
             None,
+            None,
         )?))
     }
 }
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+impl Operations {
+    /// Operations of an executed transaction, including the balance changes of every coin type
+    /// that has `CoinMetadata`, in its currency.
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
+        let mut currencies = HashMap::new();
+        for balance_change in response.balance_changes.iter().flatten() {
+            if !currencies.contains_key(&balance_change.coin_type) {
+                if let Some(currency) = cache.get_currency(&balance_change.coin_type).await? {
+                    currencies.insert(balance_change.coin_type.clone(), currency);
+                }
+            }
+        }
+        let pay_coin_currency = match Self::pay_coin_type(&response) {
+            Some(coin_type) => cache.get_currency(&coin_type).await?,
+            None => None,
+        };
+        Self::try_from_response_with_currencies(response, &currencies, pay_coin_currency)
+    }
+
+    fn try_from_response_with_currencies(
+        response: SuiTransactionBlockResponse,
+        currencies: &HashMap<TypeTag, Currency>,
+        pay_coin_currency: Option<Currency>,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -520,7 +620,15 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.computation_cost as i128;
 
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+        let balance_changes = response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+        let ops = Operations::new(Self::from_transaction(
+            tx.data.transaction().clone(),
+            sender,
+            None,
+            pay_coin_currency,
+        )?);
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
@@ -532,7 +640,9 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -564,8 +674,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -578,9 +688,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            &balance_changes,
+            currencies,
             status,
             accounted_balances,
         );
@@ -591,6 +700,56 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             .chain(staking_balance)
             .collect())
     }
+
+    /// The coin type a PayCoin transaction paid in, which is the type of the first coin it takes
+    /// as an input. The transaction data only refers to its coins by reference, their type is read
+    /// from the object changes.
+    fn pay_coin_type(response: &SuiTransactionBlockResponse) -> Option<TypeTag> {
+        let SuiTransactionBlockKind::ProgrammableTransaction(pt) =
+            response.transaction.as_ref()?.data.transaction()
+        else {
+            return None;
+        };
+        let coin = pt.inputs.iter().find_map(SuiCallArg::object)?;
+        let coin_type =
+            response
+                .object_changes
+                .as_ref()?
+                .iter()
+                .find_map(|change| match change {
+                    ObjectChange::Mutated {
+                        object_id,
+                        object_type,
+                        ..
+                    } if object_id == coin => Some(object_type),
+                    _ => None,
+                })?;
+        if !Coin::is_coin(coin_type) || GasCoin::is_gas_coin(coin_type) {
+            return None;
+        }
+        coin_type.type_params.first().cloned()
+    }
+
+    /// Operations of a transaction before it is executed, `currency` is the currency of a PayCoin
+    /// transaction, which cannot be told from the transaction data alone.
+    pub fn try_from_data(data: TransactionData, currency: Option<Currency>) -> Result<Self, Error> {
+        struct NoOpsModuleResolver;
+        impl ModuleResolver for NoOpsModuleResolver {
+            type Error = Error;
+            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+                Ok(None)
+            }
+        }
+        // Rosetta don't need the call args to be parsed into readable format
+        let data = SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?;
+        let sender = *data.sender();
+        Ok(Self::new(Self::from_transaction(
+            data.transaction().clone(),
+            sender,
+            None,
+            currency,
+        )?))
+    }
 }
 
 fn is_unstake_event(tag: &StructTag) -> bool {
@@ -602,15 +761,7 @@ fn is_unstake_event(tag: &StructTag) -> bool {
 impl TryFrom<TransactionData> for Operations {
     type Error = Error;
     fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
-        struct NoOpsModuleResolver;
-        impl ModuleResolver for NoOpsModuleResolver {
-            type Error = Error;
-            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
-                Ok(None)
-            }
-        }
-        // Rosetta don't need the call args to be parsed into readable format
-        SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?.try_into()
+        Self::try_from_data(data, None)
     }
 }
 
@@ -698,13 +849,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
@@ -5,7 +5,7 @@ use crate::operations::Operations;
 use crate::types::{
     Block, BlockHash, BlockIdentifier, BlockResponse, Transaction, TransactionIdentifier,
 };
-use crate::Error;
+use crate::{CoinMetadataCache, Error};
 use async_trait::async_trait;
 use std::sync::Arc;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
@@ -20,13 +20,19 @@ mod balance_changing_tx_tests;
 #[derive(Clone)]
 pub struct OnlineServerContext {
     pub client: SuiClient,
+    pub coin_metadata_cache: CoinMetadataCache,
     block_provider: Arc<dyn BlockProvider + Send + Sync>,
 }
 
 impl OnlineServerContext {
-    pub fn new(client: SuiClient, block_provider: Arc<dyn BlockProvider + Send + Sync>) -> Self {
+    pub fn new(
+        client: SuiClient,
+        block_provider: Arc<dyn BlockProvider + Send + Sync>,
+        coin_metadata_cache: CoinMetadataCache,
+    ) -> Self {
         Self {
             client,
+            coin_metadata_cache,
             block_provider,
         }
     }
@@ -53,6 +59,7 @@ pub trait BlockProvider {
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -103,8 +110,11 @@ impl BlockProvider for CheckpointBlockProvider {
 }
 
 impl CheckpointBlockProvider {
-    pub fn new(client: SuiClient) -> Self {
-        Self { client }
+    pub fn new(client: SuiClient, coin_metadata_cache: CoinMetadataCache) -> Self {
+        Self {
+            client,
+            coin_metadata_cache,
+        }
     }
 
     async fn create_block_response(&self, checkpoint: Checkpoint) -> Result<BlockResponse, Error> {
@@ -121,13 +131,15 @@ impl CheckpointBlockProvider {
                         .with_input()
                         .with_effects()
                         .with_balance_changes()
-                        .with_events(),
+                        .with_events()
+                        .with_object_changes(),
                 )
                 .await?;
             for tx in transaction_responses.into_iter() {
                 transactions.push(Transaction {
                     transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                    operations: Operations::try_from(tx)?,
+                    operations: Operations::try_from_response(tx, &self.coin_metadata_cache)
+                        .await?,
                     related_transactions: vec![],
                     metadata: None,
                 })
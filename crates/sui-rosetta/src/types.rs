@@ -7,6 +7,7 @@ use std::str::FromStr;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use fastcrypto::encoding::Hex;
+use move_core_types::language_storage::TypeTag;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
@@ -18,12 +19,13 @@ use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::{parse_sui_type_tag, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -95,11 +97,46 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "CurrencyMetadata::is_sui")]
+    pub metadata: CurrencyMetadata,
+}
+
+impl Currency {
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        parse_sui_type_tag(&self.metadata.coin_type).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Invalid coin type [{}]: {e}",
+                self.metadata.coin_type
+            ))
+        })
+    }
+}
+
+/// The Move type `T` of the `Coin<T>` objects a currency is held in. Currencies without metadata
+/// are SUI, so that clients that only know about SUI keep working.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
+impl CurrencyMetadata {
+    fn is_sui(&self) -> bool {
+        *self == Self::default()
+    }
 }
+
+impl Default for CurrencyMetadata {
+    fn default() -> Self {
+        Self {
+            coin_type: GAS::type_tag().to_canonical_string(true),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
     pub network_identifier: NetworkIdentifier,
@@ -153,9 +190,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -197,6 +237,8 @@ pub struct AccountCoinsRequest {
     pub network_identifier: NetworkIdentifier,
     pub account_identifier: AccountIdentifier,
     pub include_mempool: bool,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
 }
 #[derive(Serialize)]
 pub struct AccountCoinsResponse {
@@ -214,8 +256,8 @@ pub struct Coin {
     pub amount: Amount,
 }
 
-impl From<sui_sdk::rpc_types::Coin> for Coin {
-    fn from(coin: sui_sdk::rpc_types::Coin) -> Self {
+impl Coin {
+    pub fn new(coin: sui_sdk::rpc_types::Coin, currency: Currency) -> Self {
         Self {
             coin_identifier: CoinIdentifier {
                 identifier: CoinID {
@@ -223,11 +265,7 @@ impl From<sui_sdk::rpc_types::Coin> for Coin {
                     version: coin.version,
                 },
             },
-            amount: Amount {
-                value: coin.balance as i128,
-                currency: SUI.clone(),
-                metadata: None,
-            },
+            amount: Amount::new_with_currency(coin.balance as i128, currency),
         }
     }
 }
@@ -287,6 +325,28 @@ fn test_coin_id_serde() {
     assert_eq!(SequenceNumber::from_u64(10), deserialized.version)
 }
 
+#[test]
+fn test_currency_serde() {
+    // SUI is serialized without metadata, as before custom coins were supported.
+    let sui = serde_json::to_value(&*SUI).unwrap();
+    assert_eq!(serde_json::json!({"symbol": "SUI", "decimals": 9}), sui);
+    let deserialized: Currency = serde_json::from_value(sui).unwrap();
+    assert_eq!(*SUI, deserialized);
+
+    let coin_type = format!("{}::managed::MANAGED", ObjectID::random());
+    let currency: Currency = serde_json::from_value(serde_json::json!({
+        "symbol": "MANAGED",
+        "decimals": 2,
+        "metadata": { "coin_type": coin_type }
+    }))
+    .unwrap();
+    assert_ne!(*SUI, currency);
+    assert_eq!(
+        coin_type,
+        currency.coin_type().unwrap().to_canonical_string(true)
+    );
+}
+
 impl From<ObjectRef> for CoinID {
     fn from((id, version, _): ObjectRef) -> Self {
         Self { id, version }
@@ -405,6 +465,7 @@ pub enum OperationType {
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -612,6 +673,9 @@ pub struct ConstructionMetadata {
     pub total_coin_value: u64,
     pub gas_price: u64,
     pub budget: u64,
+    /// The currency of a PayCoin operation, which `objects` are coins of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
 }
 
 impl IntoResponse for ConstructionMetadataResponse {
@@ -862,6 +926,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -878,10 +948,19 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
     }
+
+    /// The currency of a PayCoin operation, which cannot be told from the transaction data.
+    pub fn pay_coin_currency(&self) -> Option<&Currency> {
+        match self {
+            InternalOperation::PayCoin { currency, .. } => Some(currency),
+            _ => None,
+        }
+    }
     /// Combine with ConstructionMetadata to form the TransactionData
     pub fn try_into_data(self, metadata: ConstructionMetadata) -> Result<TransactionData, Error> {
         let pt = match self {
@@ -894,6 +973,15 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            Self::PayCoin {
+                recipients,
+                amounts,
+                ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.pay(metadata.objects, recipients, amounts)?;
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
@@ -3,6 +3,7 @@
 
 use crate::operations::Operations;
 use crate::types::{ConstructionMetadata, OperationStatus, OperationType};
+use crate::{CoinMetadataCache, SUI};
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -26,6 +27,7 @@ use sui_sdk::rpc_types::{
 use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::gas_coin::GasCoin;
+use sui_types::parse_sui_type_tag;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::transaction::{
@@ -217,6 +219,175 @@ async fn test_publish_and_move_call() {
     .await;
 }
 
+#[tokio::test]
+async fn test_pay_coin() {
+    let network = TestClusterBuilder::new().build().await;
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+    let rgp = network.get_reference_gas_price().await;
+    let cache = CoinMetadataCache::new(client.clone());
+
+    let addresses = network.get_addresses();
+    let sender = get_random_address(&addresses, vec![]);
+    let recipient = get_random_address(&addresses, vec![sender]);
+
+    // Publish a custom coin, and mint some of it to the sender
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend([
+        "..",
+        "..",
+        "sui_programmability",
+        "examples",
+        "fungible_tokens",
+    ]);
+    let compiled_package = BuildConfig::new_for_testing().build(path).unwrap();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(
+            compiled_package.get_package_bytes(/* with_unpublished_deps */ false),
+            compiled_package.get_dependency_original_package_ids(),
+        );
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+        rgp,
+        false,
+    )
+    .await;
+    let object_changes = response.object_changes.unwrap();
+    let package = object_changes
+        .iter()
+        .find_map(|change| {
+            if let ObjectChange::Published { package_id, .. } = change {
+                Some(*package_id)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    let treasury = find_module_object(&object_changes, "::TreasuryCap")
+        .reference
+        .to_object_ref();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                package,
+                Identifier::from_str("managed").unwrap(),
+                Identifier::from_str("mint").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury)),
+                    CallArg::Pure(bcs::to_bytes(&10000u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+                ],
+            )
+            .unwrap();
+        builder.finish()
+    };
+    test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        rgp,
+        false,
+    )
+    .await;
+
+    // The currency comes from the coin's metadata
+    let coin_type = format!("{package}::managed::MANAGED");
+    let currency = cache
+        .get_currency(&parse_sui_type_tag(&coin_type).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!("MANAGED", currency.symbol);
+    assert_eq!(2, currency.decimals);
+
+    // Pay some of the custom coin to the recipient
+    let ops: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": {"index": 0},
+            "type": "PayCoin",
+            "account": { "address": recipient.to_string() },
+            "amount": { "value": "4000", "currency": currency }
+        },
+        {
+            "operation_identifier": {"index": 1},
+            "type": "PayCoin",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-4000", "currency": currency }
+        }
+    ]))
+    .unwrap();
+    let coins = client
+        .coin_read_api()
+        .get_coins(sender, Some(coin_type), None, None)
+        .await
+        .unwrap()
+        .data
+        .into_iter()
+        .map(|coin| coin.object_ref())
+        .collect();
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price: rgp,
+        budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        currency: Some(currency.clone()),
+    };
+    let pt = match ops
+        .clone()
+        .into_internal()
+        .unwrap()
+        .try_into_data(metadata)
+        .unwrap()
+        .into_kind()
+    {
+        TransactionKind::ProgrammableTransaction(pt) => pt,
+        _ => unreachable!(),
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![recipient],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        rgp,
+        false,
+    )
+    .await;
+
+    let ops2 = Operations::try_from_response(response, &cache)
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+    // The PayCoin operations account for the whole balance change of the custom coin.
+    assert!(ops2.into_iter().all(|op| op.type_ == OperationType::PayCoin
+        || op.amount.map_or(true, |amount| amount.currency == *SUI)));
+}
+
 #[tokio::test]
 async fn test_split_coin() {
     let network = TestClusterBuilder::new().build().await;
@@ -623,6 +794,7 @@ async fn test_delegation_parsing() -> Result<(), anyhow::Error> {
         total_coin_value: 0,
         gas_price: rgp,
         budget: rgp * TEST_ONLY_GAS_UNIT_FOR_STAKING,
+        currency: None,
     };
     let parsed_data = ops.clone().into_internal()?.try_into_data(metadata)?;
     assert_eq!(ops, Operations::try_from(parsed_data)?);
@@ -740,7 +912,10 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
+    let ops =
+        Operations::try_from_response(response.clone(), &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
     let balances_from_ops = extract_balance_changes_from_ops(ops);
 
     // get actual balance changed after transaction
@@ -761,7 +936,11 @@ async fn test_transaction(
 fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128> {
     ops.into_iter()
         .fold(HashMap::<SuiAddress, i128>::new(), |mut changes, op| {
-            if let Some(OperationStatus::Success) = op.status {
+            if let (Some(OperationStatus::Success), Some(amount)) = (op.status, &op.amount) {
+                // Only SUI balances are checked.
+                if amount.currency != *SUI {
+                    return changes;
+                }
                 match op.type_ {
                     OperationType::SuiBalanceChange
                     | OperationType::Gas
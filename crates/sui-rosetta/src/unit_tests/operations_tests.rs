@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_core_types::annotated_value::MoveTypeLayout;
+use serde_json::json;
 use sui_json_rpc_types::SuiCallArg;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, Currency, CurrencyMetadata, OperationType};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -44,12 +45,102 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
         total_coin_value: 0,
         gas_price,
         budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: None,
     };
     let parsed_data = ops.into_internal()?.try_into_data(metadata)?;
     assert_eq!(data, parsed_data);
 
     Ok(())
 }
+#[tokio::test]
+async fn test_pay_coin_operation_data_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let coins = vec![
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        ),
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        ),
+    ];
+
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "MANAGED".to_string(),
+        decimals: 2,
+        metadata: CurrencyMetadata {
+            coin_type: format!("{}::managed::MANAGED", ObjectID::random()),
+        },
+    };
+
+    let ops: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": {"index": 0},
+            "type": "PayCoin",
+            "account": { "address": recipient.to_string() },
+            "amount": { "value": "10000", "currency": currency }
+        },
+        {
+            "operation_identifier": {"index": 1},
+            "type": "PayCoin",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-10000", "currency": currency }
+        }
+    ]))?;
+
+    let gas_price = 10;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: Some(currency.clone()),
+    };
+    let internal_operation = ops.clone().into_internal()?;
+    let pay_coin_currency = internal_operation.pay_coin_currency().cloned();
+    assert_eq!(Some(&currency), pay_coin_currency.as_ref());
+    let data = internal_operation.try_into_data(metadata)?;
+
+    // The transaction data carries no currency, the coin inputs of a PayCoin transaction could be
+    // of any type.
+    let parsed_ops: Operations = data.clone().try_into()?;
+    assert!(parsed_ops
+        .into_iter()
+        .all(|op| op.type_ == OperationType::ProgrammableTransaction));
+    let parsed_ops = Operations::try_from_data(data, pay_coin_currency)?;
+    assert_eq!(ops, parsed_ops);
+
+    // PaySui cannot move other currencies.
+    let pay_sui: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": {"index": 0},
+            "type": "PaySui",
+            "account": { "address": recipient.to_string() },
+            "amount": { "value": "10000", "currency": currency }
+        },
+        {
+            "operation_identifier": {"index": 1},
+            "type": "PaySui",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-10000", "currency": currency }
+        }
+    ]))?;
+    assert!(pay_sui.into_internal().is_err());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
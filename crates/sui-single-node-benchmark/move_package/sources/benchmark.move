@@ -71,4 +71,24 @@ module move_benchmark::benchmark {
             i = i + 1;
         }
     }
+
+    struct Counter has key {
+        id: UID,
+        value: u64,
+    }
+
+    public entry fun create_shared_counter(ctx: &mut TxContext) {
+        transfer::share_object(Counter {
+            id: object::new(ctx),
+            value: 0,
+        })
+    }
+
+    public fun increment_shared_counter(counter: &mut Counter) {
+        counter.value = counter.value + 1;
+    }
+
+    public fun read_shared_counter(counter: &Counter): u64 {
+        counter.value
+    }
 }
@@ -13,12 +13,15 @@ use std::collections::{BTreeMap, HashMap};
 use std::ops::Deref;
 use std::sync::Arc;
 use sui_config::node::RunWithRange;
-use sui_test_transaction_builder::PublishData;
-use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_test_transaction_builder::{PublishData, TestTransactionBuilder};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::messages_grpc::HandleTransactionResponse;
 use sui_types::mock_checkpoint_builder::ValidatorKeypairProvider;
-use sui_types::transaction::{CertifiedTransaction, SignedTransaction, Transaction};
+use sui_types::object::Owner;
+use sui_types::transaction::{
+    CertifiedTransaction, SignedTransaction, Transaction, DEFAULT_VALIDATOR_GAS_PRICE,
+};
 use tracing::info;
 
 pub struct BenchmarkContext {
@@ -130,6 +133,55 @@ impl BenchmarkContext {
         root_objects
     }
 
+    /// In order to benchmark transactions that access shared objects, we must first create
+    /// shared counters. Returns the id and initial shared version of each counter.
+    pub(crate) async fn preparing_shared_objects(
+        &mut self,
+        move_package: ObjectID,
+        num_shared_objects: u64,
+    ) -> Vec<(ObjectID, SequenceNumber)> {
+        let mut shared_objects = vec![];
+
+        if num_shared_objects == 0 {
+            return shared_objects;
+        }
+        assert!(
+            matches!(
+                self.benchmark_component,
+                Component::ValidatorWithFakeConsensus | Component::TxnSigning
+            ),
+            "Shared objects require consensus to assign their versions"
+        );
+
+        info!("Preparing {} shared objects", num_shared_objects);
+        let mut gas_objects = self.admin_account.gas_objects.deref().clone();
+        for _ in 0..num_shared_objects {
+            let transaction = TestTransactionBuilder::new(
+                self.admin_account.sender,
+                gas_objects[0],
+                DEFAULT_VALIDATOR_GAS_PRICE,
+            )
+            .move_call(move_package, "benchmark", "create_shared_counter", vec![])
+            .build_and_sign(self.admin_account.keypair.as_ref());
+            let effects = self.validator.execute_raw_transaction(transaction).await;
+            let shared_object = effects
+                .created()
+                .into_iter()
+                .find_map(|((id, _, _), owner)| match owner {
+                    Owner::Shared {
+                        initial_shared_version,
+                    } => Some((id, initial_shared_version)),
+                    _ => None,
+                })
+                .unwrap();
+            shared_objects.push(shared_object);
+            gas_objects[0] = effects.gas_object().0;
+        }
+        self.admin_account.gas_objects = Arc::new(gas_objects);
+        info!("Finished preparing shared objects");
+        shared_objects
+    }
+
     pub(crate) async fn generate_transactions(
         &self,
         tx_generator: Arc<dyn TxGenerator>,
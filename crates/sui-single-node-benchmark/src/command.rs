@@ -91,6 +91,19 @@ pub enum WorkloadKind {
             specified by this parameter * 100."
         )]
         computation: u8,
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "Number of shared counters accessed by the transactions.\
+            Each transaction accesses one of the counters, so fewer counters means more contention.\
+            Shared objects require the validator-with-fake-consensus or txn-signing component."
+        )]
+        num_shared_objects: u64,
+        #[arg(
+            long,
+            help = "Whether transactions only read the shared counters instead of incrementing them."
+        )]
+        read_only_shared_objects: bool,
     },
     Publish {
         #[arg(
@@ -64,17 +64,25 @@ impl MockConsensusClient {
                 ConsensusMode::Noop => {}
                 ConsensusMode::DirectSequencing(checkpoint_size) => {
                     transactions.push(SequencedConsensusTransaction::new_test(tx));
-                    if transactions.len() == checkpoint_size {
-                        epoch_store
-                            .process_consensus_transactions_for_tests(
-                                mem::take(&mut transactions),
-                                &checkpoint_service,
-                                validator.get_cache_reader().as_ref(),
-                                &counter,
-                            )
-                            .await
-                            .unwrap();
+                    // Sequence a commit once it is full, or when no more transactions are
+                    // submitted, so that transactions waiting for shared object versions
+                    // are never left behind.
+                    while transactions.len() < checkpoint_size {
+                        let Ok(tx) = tx_receiver.try_recv() else {
+                            break;
+                        };
+                        transactions.push(SequencedConsensusTransaction::new_test(tx));
                     }
+                    let executables = epoch_store
+                        .process_consensus_transactions_for_tests(
+                            mem::take(&mut transactions),
+                            &checkpoint_service,
+                            validator.get_cache_reader().as_ref(),
+                            &counter,
+                        )
+                        .await
+                        .unwrap();
+                    validator.enqueue_transactions_for_execution(executables, &epoch_store);
                 }
             }
         }
@@ -6,7 +6,7 @@ use crate::tx_generator::TxGenerator;
 use move_core_types::identifier::Identifier;
 use std::collections::HashMap;
 use sui_test_transaction_builder::TestTransactionBuilder;
-use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{
     Argument, CallArg, ObjectArg, Transaction, DEFAULT_VALIDATOR_GAS_PRICE,
@@ -17,6 +17,8 @@ pub struct MoveTxGenerator {
     num_input_objects: u8,
     computation: u8,
     root_objects: HashMap<SuiAddress, ObjectRef>,
+    shared_objects: Vec<(ObjectID, SequenceNumber)>,
+    read_only_shared_objects: bool,
 }
 
 impl MoveTxGenerator {
@@ -25,12 +27,16 @@ impl MoveTxGenerator {
         num_input_objects: u8,
         computation: u8,
         root_objects: HashMap<SuiAddress, ObjectRef>,
+        shared_objects: Vec<(ObjectID, SequenceNumber)>,
+        read_only_shared_objects: bool,
     ) -> Self {
         Self {
             move_package,
             num_input_objects,
             computation,
             root_objects,
+            shared_objects,
+            read_only_shared_objects,
         }
     }
 }
@@ -72,7 +78,32 @@ impl TxGenerator for MoveTxGenerator {
                 );
             }
 
-            // PT command 4: Run some computation.
+            if !self.shared_objects.is_empty() {
+                // PT command 4: Access one of the shared counters, picked by the sender.
+                let (id, initial_shared_version) = self.shared_objects
+                    [account.sender.to_inner()[0] as usize % self.shared_objects.len()];
+                let counter_arg = builder
+                    .obj(ObjectArg::SharedObject {
+                        id,
+                        initial_shared_version,
+                        mutable: !self.read_only_shared_objects,
+                    })
+                    .unwrap();
+                let function = if self.read_only_shared_objects {
+                    "read_shared_counter"
+                } else {
+                    "increment_shared_counter"
+                };
+                builder.programmable_move_call(
+                    self.move_package,
+                    Identifier::new("benchmark").unwrap(),
+                    Identifier::new(function).unwrap(),
+                    vec![],
+                    vec![counter_arg],
+                );
+            }
+
+            // PT command 5: Run some computation.
             if self.computation > 0 {
                 let computation_arg = builder.pure(self.computation as u64 * 100).unwrap();
                 builder.programmable_move_call(
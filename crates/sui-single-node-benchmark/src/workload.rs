@@ -47,6 +47,8 @@ impl Workload {
             WorkloadKind::Move {
                 num_dynamic_fields,
                 computation,
+                num_shared_objects,
+                read_only_shared_objects,
             } => {
                 assert!(
                     self.num_input_objects >= 2,
@@ -58,11 +60,16 @@ impl Workload {
                 let root_objects = ctx
                     .preparing_dynamic_fields(move_package.0, *num_dynamic_fields)
                     .await;
+                let shared_objects = ctx
+                    .preparing_shared_objects(move_package.0, *num_shared_objects)
+                    .await;
                 Arc::new(MoveTxGenerator::new(
                     move_package.0,
                     self.num_input_objects,
                     *computation,
                     root_objects,
+                    shared_objects,
+                    *read_only_shared_objects,
                 ))
             }
             WorkloadKind::Publish {
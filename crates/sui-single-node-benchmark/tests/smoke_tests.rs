@@ -32,6 +32,8 @@ async fn benchmark_move_transactions_smoke_test() {
                 WorkloadKind::Move {
                     num_dynamic_fields: 1,
                     computation: 1,
+                    num_shared_objects: 0,
+                    read_only_shared_objects: false,
                 },
                 2,
             ),
@@ -43,6 +45,32 @@ async fn benchmark_move_transactions_smoke_test() {
     }
 }
 
+#[sim_test]
+async fn benchmark_shared_object_transactions_smoke_test() {
+    // This test makes sure that the benchmark runs with contended shared objects, accessed
+    // both mutably and read-only.
+    for read_only_shared_objects in [false, true] {
+        for component in [Component::ValidatorWithFakeConsensus, Component::TxnSigning] {
+            run_benchmark(
+                Workload::new(
+                    10,
+                    WorkloadKind::Move {
+                        num_dynamic_fields: 0,
+                        computation: 0,
+                        num_shared_objects: 2,
+                        read_only_shared_objects,
+                    },
+                    2,
+                ),
+                component,
+                1000,
+                false,
+            )
+            .await;
+        }
+    }
+}
+
 #[sim_test]
 async fn benchmark_publish_from_source() {
     // This test makes sure that the benchmark runs.
@@ -8,7 +8,7 @@ use std::io::Read;
 use std::os::unix::fs::FileExt;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+use sui::client_commands::{GasPaymentOpts, SuiClientCommandResult, SuiClientCommands};
 use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI};
 use sui_move_build::{BuildConfig, SuiPackageHooks};
 use sui_sdk::rpc_types::{
@@ -185,6 +185,7 @@ async fn run_publish(
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -215,6 +216,7 @@ async fn run_upgrade(
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
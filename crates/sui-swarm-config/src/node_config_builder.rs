@@ -3,6 +3,7 @@
 
 use crate::genesis_config::{ValidatorGenesisConfig, ValidatorGenesisConfigBuilder};
 use crate::network_config::NetworkConfig;
+use consensus_config::{ConsensusNetwork, Parameters as MysticetiParameters};
 use fastcrypto::encoding::{Encoding, Hex};
 use fastcrypto::traits::KeyPair;
 use narwhal_config::{NetworkAdminServerParameters, PrometheusMetricsParameters};
@@ -12,8 +13,9 @@ use std::time::Duration;
 use sui_config::node::{
     default_enable_index_processing, default_end_of_epoch_broadcast_channel_capacity,
     AuthorityKeyPairWithPath, AuthorityOverloadConfig, AuthorityStorePruningConfig,
-    CheckpointExecutorConfig, DBCheckpointConfig, ExpensiveSafetyCheckConfig, Genesis,
-    KeyPairWithPath, StateArchiveConfig, StateSnapshotConfig, DEFAULT_GRPC_CONCURRENCY_LIMIT,
+    CheckpointExecutorConfig, DBCheckpointConfig, ExecutionCacheConfig, ExpensiveSafetyCheckConfig,
+    Genesis, KeyPairWithPath, StateArchiveConfig, StateSnapshotConfig,
+    DEFAULT_GRPC_CONCURRENCY_LIMIT,
 };
 use sui_config::node::{default_zklogin_oauth_providers, ConsensusProtocol, RunWithRange};
 use sui_config::p2p::{P2pConfig, SeedPeer, StateSyncConfig};
@@ -35,6 +37,7 @@ pub struct ValidatorConfigBuilder {
     jwk_fetch_interval: Option<Duration>,
     authority_overload_config: Option<AuthorityOverloadConfig>,
     data_ingestion_dir: Option<PathBuf>,
+    consensus_network: Option<ConsensusNetwork>,
 }
 
 impl ValidatorConfigBuilder {
@@ -77,6 +80,11 @@ impl ValidatorConfigBuilder {
         self
     }
 
+    pub fn with_consensus_network(mut self, network: ConsensusNetwork) -> Self {
+        self.consensus_network = Some(network);
+        self
+    }
+
     pub fn build(
         self,
         validator: ValidatorGenesisConfig,
@@ -117,6 +125,10 @@ impl ValidatorConfigBuilder {
                 },
                 ..Default::default()
             },
+            parameters: self.consensus_network.map(|network| MysticetiParameters {
+                network,
+                ..Default::default()
+            }),
         };
 
         let p2p_config = P2pConfig {
@@ -185,6 +197,7 @@ impl ValidatorConfigBuilder {
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
             indexer_max_subscriptions: Default::default(),
+            json_rpc_max_batch_size: None,
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: None,
             enable_experimental_rest_api: true,
@@ -194,6 +207,7 @@ impl ValidatorConfigBuilder {
                 .unwrap_or(3600),
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             authority_overload_config: self.authority_overload_config.unwrap_or_default(),
+            execution_cache: ExecutionCacheConfig::default(),
             run_with_range: None,
         }
     }
@@ -439,6 +453,7 @@ impl FullnodeConfigBuilder {
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
             indexer_max_subscriptions: Default::default(),
+            json_rpc_max_batch_size: None,
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: Default::default(),
             enable_experimental_rest_api: true,
@@ -446,6 +461,7 @@ impl FullnodeConfigBuilder {
             jwk_fetch_interval_seconds: 3600,
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             authority_overload_config: Default::default(),
+            execution_cache: ExecutionCacheConfig::default(),
             run_with_range: self.run_with_range,
         }
     }
@@ -3,6 +3,7 @@
 
 use super::Node;
 use anyhow::Result;
+use consensus_config::ConsensusNetwork;
 use futures::future::try_join_all;
 use rand::rngs::OsRng;
 use std::collections::HashMap;
@@ -49,6 +50,7 @@ pub struct SwarmBuilder<R = OsRng> {
     authority_overload_config: Option<AuthorityOverloadConfig>,
     data_ingestion_dir: Option<PathBuf>,
     fullnode_run_with_range: Option<RunWithRange>,
+    consensus_network: Option<ConsensusNetwork>,
 }
 
 impl SwarmBuilder {
@@ -72,6 +74,7 @@ impl SwarmBuilder {
             authority_overload_config: None,
             data_ingestion_dir: None,
             fullnode_run_with_range: None,
+            consensus_network: None,
         }
     }
 }
@@ -97,6 +100,7 @@ impl<R> SwarmBuilder<R> {
             authority_overload_config: self.authority_overload_config,
             data_ingestion_dir: self.data_ingestion_dir,
             fullnode_run_with_range: self.fullnode_run_with_range,
+            consensus_network: self.consensus_network,
         }
     }
 
@@ -232,6 +236,12 @@ impl<R> SwarmBuilder<R> {
         self
     }
 
+    pub fn with_consensus_network(mut self, network: ConsensusNetwork) -> Self {
+        assert!(self.network_config.is_none());
+        self.consensus_network = Some(network);
+        self
+    }
+
     pub fn with_fullnode_run_with_range(mut self, run_with_range: Option<RunWithRange>) -> Self {
         if let Some(run_with_range) = run_with_range {
             self.fullnode_run_with_range = Some(run_with_range);
@@ -282,6 +292,10 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
                 config_builder = config_builder.with_data_ingestion_dir(path);
             }
 
+            if let Some(network) = self.consensus_network {
+                config_builder = config_builder.with_consensus_network(network);
+            }
+
             config_builder
                 .committee(self.committee)
                 .rng(self.rng)
@@ -9,7 +9,10 @@ pub const SUI_VALIDATOR_SERVER_NAME: &str = "sui";
 
 pub use acceptor::{TlsAcceptor, TlsConnectionInfo};
 pub use certgen::SelfSignedCertificate;
-pub use verifier::{AllowAll, Allower, CertVerifier, HashSetAllow, ValidatorAllowlist};
+pub use verifier::{
+    public_key_from_certificate, AllowAll, Allower, CertVerifier, HashSetAllow, ServerCertVerifier,
+    ValidatorAllowlist,
+};
 
 pub use rustls;
 
@@ -125,6 +128,42 @@ mod tests {
             .unwrap_err();
     }
 
+    #[test]
+    fn verify_server_cert() {
+        use rustls::client::ServerCertVerifier as _;
+
+        let mut rng = rand::thread_rng();
+        let server = Ed25519KeyPair::generate(&mut rng);
+        let other = Ed25519KeyPair::generate(&mut rng);
+        let verifier = ServerCertVerifier::new(
+            server.public().to_owned(),
+            SUI_VALIDATOR_SERVER_NAME.to_string(),
+        );
+        let wrong_name_cert = SelfSignedCertificate::new(server.copy().private(), "not-sui");
+        let server_cert = SelfSignedCertificate::new(server.private(), SUI_VALIDATOR_SERVER_NAME);
+        let other_cert = SelfSignedCertificate::new(other.private(), SUI_VALIDATOR_SERVER_NAME);
+        let server_name = rustls::ServerName::try_from(SUI_VALIDATOR_SERVER_NAME).unwrap();
+        let verify = |cert: &SelfSignedCertificate| {
+            verifier.verify_server_cert(
+                &cert.rustls_certificate(),
+                &[],
+                &server_name,
+                &mut std::iter::empty(),
+                &[],
+                std::time::SystemTime::now(),
+            )
+        };
+
+        // The expected server passes validation.
+        verify(&server_cert).unwrap();
+
+        // A certificate of another key fails validation.
+        verify(&other_cert).unwrap_err();
+
+        // A certificate of the expected key but with the wrong server name fails validation.
+        verify(&wrong_name_cert).unwrap_err();
+    }
+
     #[tokio::test]
     async fn axum_acceptor() {
         use fastcrypto::ed25519::Ed25519KeyPair;
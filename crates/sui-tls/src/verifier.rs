@@ -151,6 +151,76 @@ impl<A: Allower> rustls::server::ClientCertVerifier for CertVerifier<A> {
     }
 }
 
+/// A `rustls::client::ServerCertVerifier` that will ensure that the server provides a valid
+/// self-signed certificate for the expected public key and server name.
+#[derive(Clone, Debug)]
+pub struct ServerCertVerifier {
+    public_key: Ed25519PublicKey,
+    name: String,
+}
+
+impl ServerCertVerifier {
+    pub fn new(public_key: Ed25519PublicKey, name: String) -> Self {
+        Self { public_key, name }
+    }
+
+    pub fn rustls_client_config(
+        self,
+        certificates: Vec<rustls::Certificate>,
+        private_key: rustls::PrivateKey,
+    ) -> Result<rustls::ClientConfig, rustls::Error> {
+        let mut config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(std::sync::Arc::new(self))
+            .with_client_auth_cert(certificates, private_key)?;
+        config.alpn_protocols = vec![b"h2".to_vec()];
+
+        Ok(config)
+    }
+}
+
+impl rustls::client::ServerCertVerifier for ServerCertVerifier {
+    // Verifies this is a valid ed25519 self-signed certificate for the expected public key.
+    // The server name requested by the client is ignored in favor of the expected name.
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let public_key = public_key_from_certificate(end_entity)?;
+        if public_key != self.public_key {
+            return Err(rustls::Error::General(format!(
+                "invalid certificate: {:?} is not the expected server public key {:?}",
+                public_key, self.public_key,
+            )));
+        }
+
+        let (cert, chain, trustroots) = prepare_for_self_signed(end_entity, intermediates)?;
+        let now = webpki::Time::try_from(now).map_err(|_| rustls::Error::FailedToGetCurrentTime)?;
+        let cert = cert
+            .verify_for_usage(
+                SUPPORTED_SIG_ALGS,
+                &trustroots,
+                &chain,
+                now,
+                webpki::KeyUsage::server_auth(),
+                &[],
+            )
+            .map_err(pki_error)
+            .map(|_| cert)?;
+
+        let dns_nameref = webpki::SubjectNameRef::try_from_ascii_str(&self.name)
+            .map_err(|_| rustls::Error::UnsupportedNameType)?;
+        cert.verify_is_valid_for_subject_name(dns_nameref)
+            .map_err(pki_error)
+            .map(|_| rustls::client::ServerCertVerified::assertion())
+    }
+}
+
 type CertChainAndRoots<'a> = (
     webpki::EndEntityCert<'a>,
     Vec<&'a [u8]>,
@@ -189,7 +259,8 @@ fn pki_error(error: webpki::Error) -> rustls::Error {
     }
 }
 
-pub(crate) fn public_key_from_certificate(
+/// Extracts the ed25519 public key from a certificate.
+pub fn public_key_from_certificate(
     certificate: &rustls::Certificate,
 ) -> Result<Ed25519PublicKey, rustls::Error> {
     use x509_parser::{certificate::X509Certificate, prelude::FromDer};
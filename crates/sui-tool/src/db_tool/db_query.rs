@@ -0,0 +1,552 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail};
+use clap::Args;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Bound;
+use std::path::Path;
+use std::str::FromStr;
+use sui_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use sui_core::checkpoints::{CheckpointStore, CheckpointStoreReadOnly};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::digests::{
+    CheckpointContentsDigest, CheckpointDigest, ObjectDigest, TransactionDigest,
+    TransactionEffectsDigest, TransactionEventsDigest,
+};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ObjectKey;
+use typed_store::rocks::{be_fix_int_ser, DBMap};
+use typed_store::traits::Map;
+
+/// Key range filters for `query` and `diff`. Filters which do not apply to the keys of the
+/// queried table are rejected.
+#[derive(Args, Clone, Debug, Default)]
+#[command(rename_all = "kebab-case")]
+pub struct KeyFilter {
+    /// Only include objects whose id starts with this hex prefix
+    #[arg(long)]
+    pub object_id_prefix: Option<String>,
+    /// Only include object versions greater than or equal to this version
+    #[arg(long)]
+    pub min_version: Option<u64>,
+    /// Only include object versions less than or equal to this version
+    #[arg(long)]
+    pub max_version: Option<u64>,
+    /// Only include checkpoints with a sequence number greater than or equal to this one
+    #[arg(long)]
+    pub checkpoint_start: Option<CheckpointSequenceNumber>,
+    /// Only include checkpoints with a sequence number less than or equal to this one
+    #[arg(long)]
+    pub checkpoint_end: Option<CheckpointSequenceNumber>,
+}
+
+impl KeyFilter {
+    fn has_object_filters(&self) -> bool {
+        self.object_id_prefix.is_some() || self.min_version.is_some() || self.max_version.is_some()
+    }
+
+    fn has_checkpoint_filters(&self) -> bool {
+        self.checkpoint_start.is_some() || self.checkpoint_end.is_some()
+    }
+
+    /// Returns the smallest and largest object ids matching `object_id_prefix`
+    fn object_id_range(&self) -> anyhow::Result<Option<(ObjectID, ObjectID)>> {
+        let Some(prefix) = &self.object_id_prefix else {
+            return Ok(None);
+        };
+        let prefix = prefix.trim_start_matches("0x");
+        if prefix.len() > ObjectID::LENGTH * 2 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("Invalid object id prefix: {prefix}");
+        }
+        let width = ObjectID::LENGTH * 2;
+        let lower = ObjectID::from_str(&format!("0x{prefix:0<width$}"))?;
+        let upper = ObjectID::from_str(&format!("0x{prefix:f<width$}"))?;
+        Ok(Some((lower, upper)))
+    }
+
+    fn version_matches(&self, version: SequenceNumber) -> bool {
+        self.min_version.map_or(true, |v| version.value() >= v)
+            && self.max_version.map_or(true, |v| version.value() <= v)
+    }
+
+    fn object_bounds<K>(
+        &self,
+        key: impl Fn(ObjectID, SequenceNumber) -> K,
+    ) -> anyhow::Result<(Bound<K>, Bound<K>)> {
+        if self.has_checkpoint_filters() {
+            bail!("Checkpoint filters are not supported for object tables");
+        }
+        Ok(match self.object_id_range()? {
+            Some((lower, upper)) => (
+                Bound::Included(key(lower, SequenceNumber::MIN)),
+                Bound::Included(key(upper, SequenceNumber::MAX)),
+            ),
+            None => (Bound::Unbounded, Bound::Unbounded),
+        })
+    }
+}
+
+/// A key type of one of the tables supported by `query` and `diff`
+pub trait QueryKey: Serialize + DeserializeOwned {
+    /// Returns the range of keys to scan for the filter
+    fn bounds(filter: &KeyFilter) -> anyhow::Result<(Bound<Self>, Bound<Self>)> {
+        if filter.has_object_filters() || filter.has_checkpoint_filters() {
+            bail!("Key filters are not supported for this table");
+        }
+        Ok((Bound::Unbounded, Bound::Unbounded))
+    }
+
+    /// Returns true if the key matches the parts of the filter which can't be expressed as a range
+    fn matches(&self, _filter: &KeyFilter) -> bool {
+        true
+    }
+}
+
+impl QueryKey for ObjectKey {
+    fn bounds(filter: &KeyFilter) -> anyhow::Result<(Bound<Self>, Bound<Self>)> {
+        filter.object_bounds(ObjectKey)
+    }
+
+    fn matches(&self, filter: &KeyFilter) -> bool {
+        filter.version_matches(self.1)
+    }
+}
+
+impl QueryKey for ObjectRef {
+    fn bounds(filter: &KeyFilter) -> anyhow::Result<(Bound<Self>, Bound<Self>)> {
+        let (lower, upper) =
+            filter.object_bounds(|id, version| (id, version, ObjectDigest::MIN))?;
+        let upper = match upper {
+            Bound::Included((id, version, _)) => Bound::Included((id, version, ObjectDigest::MAX)),
+            bound => bound,
+        };
+        Ok((lower, upper))
+    }
+
+    fn matches(&self, filter: &KeyFilter) -> bool {
+        filter.version_matches(self.1)
+    }
+}
+
+// Only checkpoint tables of the supported tables are keyed by a plain integer
+impl QueryKey for CheckpointSequenceNumber {
+    fn bounds(filter: &KeyFilter) -> anyhow::Result<(Bound<Self>, Bound<Self>)> {
+        if filter.has_object_filters() {
+            bail!("Object filters are not supported for checkpoint tables");
+        }
+        Ok((
+            filter
+                .checkpoint_start
+                .map_or(Bound::Unbounded, Bound::Included),
+            filter
+                .checkpoint_end
+                .map_or(Bound::Unbounded, Bound::Included),
+        ))
+    }
+}
+
+impl QueryKey for TransactionDigest {}
+impl QueryKey for TransactionEffectsDigest {}
+impl QueryKey for (TransactionEventsDigest, usize) {}
+impl QueryKey for CheckpointDigest {}
+impl QueryKey for CheckpointContentsDigest {}
+
+/// Where the decoded rows are written to
+pub enum RowSink {
+    /// Pretty printed JSON on stdout
+    Stdout,
+    /// One JSON document per line in a file
+    Ndjson(BufWriter<File>),
+}
+
+impl RowSink {
+    pub fn new(output: Option<&Path>) -> anyhow::Result<Self> {
+        Ok(match output {
+            Some(path) => RowSink::Ndjson(BufWriter::new(File::create(path)?)),
+            None => RowSink::Stdout,
+        })
+    }
+
+    fn write(&mut self, row: &serde_json::Value) -> anyhow::Result<()> {
+        match self {
+            RowSink::Stdout => println!("{}", serde_json::to_string_pretty(row)?),
+            RowSink::Ndjson(writer) => {
+                serde_json::to_writer(&mut *writer, row)?;
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> anyhow::Result<()> {
+        if let RowSink::Ndjson(mut writer) = self {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn perpetual(db_path: &Path) -> AuthorityPerpetualTablesReadOnly {
+    AuthorityPerpetualTables::open_readonly(&db_path.join("store"))
+}
+
+fn checkpoints(db_path: &Path) -> CheckpointStoreReadOnly {
+    CheckpointStore::open_readonly(&db_path.join("checkpoints"))
+}
+
+/// Decodes the rows of `table_name` matching `filter` into JSON and writes them to `sink`.
+/// Returns the number of rows written.
+/// Until we use a proc macro to auto derive this, we have to make sure to update this function
+/// and `diff_table` when adding new tables.
+pub fn query_table(
+    db_path: &Path,
+    table_name: &str,
+    filter: &KeyFilter,
+    limit: Option<usize>,
+    sink: &mut RowSink,
+) -> anyhow::Result<usize> {
+    match table_name {
+        "objects" => query(&perpetual(db_path).objects, filter, limit, sink),
+        "live_owned_object_markers" => query(
+            &perpetual(db_path).live_owned_object_markers,
+            filter,
+            limit,
+            sink,
+        ),
+        "transactions" => query(&perpetual(db_path).transactions, filter, limit, sink),
+        "effects" => query(&perpetual(db_path).effects, filter, limit, sink),
+        "executed_effects" => query(&perpetual(db_path).executed_effects, filter, limit, sink),
+        "events" => query(&perpetual(db_path).events, filter, limit, sink),
+        "checkpoint_content" => query(
+            &checkpoints(db_path).checkpoint_content,
+            filter,
+            limit,
+            sink,
+        ),
+        "certified_checkpoints" => query(
+            &checkpoints(db_path).certified_checkpoints,
+            filter,
+            limit,
+            sink,
+        ),
+        "checkpoint_by_digest" => query(
+            &checkpoints(db_path).checkpoint_by_digest,
+            filter,
+            limit,
+            sink,
+        ),
+        "locally_computed_checkpoints" => query(
+            &checkpoints(db_path).locally_computed_checkpoints,
+            filter,
+            limit,
+            sink,
+        ),
+        _ => Err(anyhow!("Invalid or unsupported table: {}", table_name)),
+    }
+}
+
+/// Compares `table_name` between the dbs at `left_path` and `right_path` and writes a row to
+/// `sink` for every key which is missing on one side or has a different value on each side.
+/// Returns the number of diverging keys written.
+pub fn diff_table(
+    left_path: &Path,
+    right_path: &Path,
+    table_name: &str,
+    filter: &KeyFilter,
+    limit: Option<usize>,
+    sink: &mut RowSink,
+) -> anyhow::Result<usize> {
+    match table_name {
+        "objects" => diff(
+            &perpetual(left_path).objects,
+            &perpetual(right_path).objects,
+            filter,
+            limit,
+            sink,
+        ),
+        "live_owned_object_markers" => diff(
+            &perpetual(left_path).live_owned_object_markers,
+            &perpetual(right_path).live_owned_object_markers,
+            filter,
+            limit,
+            sink,
+        ),
+        "transactions" => diff(
+            &perpetual(left_path).transactions,
+            &perpetual(right_path).transactions,
+            filter,
+            limit,
+            sink,
+        ),
+        "effects" => diff(
+            &perpetual(left_path).effects,
+            &perpetual(right_path).effects,
+            filter,
+            limit,
+            sink,
+        ),
+        "executed_effects" => diff(
+            &perpetual(left_path).executed_effects,
+            &perpetual(right_path).executed_effects,
+            filter,
+            limit,
+            sink,
+        ),
+        "events" => diff(
+            &perpetual(left_path).events,
+            &perpetual(right_path).events,
+            filter,
+            limit,
+            sink,
+        ),
+        "checkpoint_content" => diff(
+            &checkpoints(left_path).checkpoint_content,
+            &checkpoints(right_path).checkpoint_content,
+            filter,
+            limit,
+            sink,
+        ),
+        "certified_checkpoints" => diff(
+            &checkpoints(left_path).certified_checkpoints,
+            &checkpoints(right_path).certified_checkpoints,
+            filter,
+            limit,
+            sink,
+        ),
+        "checkpoint_by_digest" => diff(
+            &checkpoints(left_path).checkpoint_by_digest,
+            &checkpoints(right_path).checkpoint_by_digest,
+            filter,
+            limit,
+            sink,
+        ),
+        "locally_computed_checkpoints" => diff(
+            &checkpoints(left_path).locally_computed_checkpoints,
+            &checkpoints(right_path).locally_computed_checkpoints,
+            filter,
+            limit,
+            sink,
+        ),
+        _ => Err(anyhow!("Invalid or unsupported table: {}", table_name)),
+    }
+}
+
+/// Iterates over the rows matching `filter`, along with the serialized form of their keys which
+/// defines their order in the db
+fn encoded_rows<'a, K, V>(
+    db_map: &'a DBMap<K, V>,
+    filter: &'a KeyFilter,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<(Vec<u8>, K, V)>> + 'a>
+where
+    K: QueryKey,
+    V: Serialize + DeserializeOwned,
+{
+    db_map.try_catch_up_with_primary()?;
+    Ok(db_map
+        .safe_range_iter(K::bounds(filter)?)
+        .filter(move |item| item.as_ref().map_or(true, |(key, _)| key.matches(filter)))
+        .map(|item| {
+            let (key, value) = item?;
+            Ok((be_fix_int_ser(&key)?, key, value))
+        }))
+}
+
+fn query<K, V>(
+    db_map: &DBMap<K, V>,
+    filter: &KeyFilter,
+    limit: Option<usize>,
+    sink: &mut RowSink,
+) -> anyhow::Result<usize>
+where
+    K: QueryKey,
+    V: Serialize + DeserializeOwned,
+{
+    let mut count = 0;
+    for item in encoded_rows(db_map, filter)?.take(limit.unwrap_or(usize::MAX)) {
+        let (_, key, value) = item?;
+        sink.write(&json!({
+            "key": serde_json::to_value(&key)?,
+            "value": serde_json::to_value(&value)?,
+        }))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn diff<K, V>(
+    left: &DBMap<K, V>,
+    right: &DBMap<K, V>,
+    filter: &KeyFilter,
+    limit: Option<usize>,
+    sink: &mut RowSink,
+) -> anyhow::Result<usize>
+where
+    K: QueryKey,
+    V: Serialize + DeserializeOwned,
+{
+    let mut left = encoded_rows(left, filter)?.peekable();
+    let mut right = encoded_rows(right, filter)?.peekable();
+    let mut count = 0;
+    while count < limit.unwrap_or(usize::MAX) {
+        // Errors are ordered first so that they are returned by the `next` call below
+        let ordering = match (left.peek(), right.peek()) {
+            (None, None) => break,
+            (Some(Ok((l, _, _))), Some(Ok((r, _, _)))) => l.cmp(r),
+            (Some(Err(_)), _) | (Some(_), None) => Ordering::Less,
+            (_, Some(Err(_))) | (None, Some(_)) => Ordering::Greater,
+        };
+        let (key, left_value, right_value) = match ordering {
+            Ordering::Less => {
+                let (_, key, value) = left.next().unwrap()?;
+                (key, Some(value), None)
+            }
+            Ordering::Greater => {
+                let (_, key, value) = right.next().unwrap()?;
+                (key, None, Some(value))
+            }
+            Ordering::Equal => {
+                let (_, key, left_value) = left.next().unwrap()?;
+                let (_, _, right_value) = right.next().unwrap()?;
+                if bcs::to_bytes(&left_value)? == bcs::to_bytes(&right_value)? {
+                    continue;
+                }
+                (key, Some(left_value), Some(right_value))
+            }
+        };
+        sink.write(&json!({
+            "key": serde_json::to_value(&key)?,
+            "left": serde_json::to_value(&left_value)?,
+            "right": serde_json::to_value(&right_value)?,
+        }))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typed_store::rocks::{MetricConf, ReadWriteOptions};
+
+    fn open_map<K, V>() -> DBMap<K, V> {
+        DBMap::open(
+            tempfile::tempdir().unwrap().into_path(),
+            MetricConf::default(),
+            None,
+            None,
+            &ReadWriteOptions::default(),
+        )
+        .unwrap()
+    }
+
+    fn read_rows(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn object_id_prefix_bounds() {
+        let filter = KeyFilter {
+            object_id_prefix: Some("0xab".to_string()),
+            ..Default::default()
+        };
+        let (lower, upper) = filter.object_id_range().unwrap().unwrap();
+        assert_eq!(lower.to_vec()[0], 0xab);
+        assert!(lower.to_vec()[1..].iter().all(|b| *b == 0));
+        assert_eq!(upper.to_vec()[0], 0xab);
+        assert!(upper.to_vec()[1..].iter().all(|b| *b == 0xff));
+
+        let filter = KeyFilter {
+            object_id_prefix: Some("0xzz".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.object_id_range().is_err());
+
+        let filter = KeyFilter {
+            checkpoint_start: Some(1),
+            ..Default::default()
+        };
+        assert!(<ObjectKey as QueryKey>::bounds(&filter).is_err());
+        assert!(<TransactionDigest as QueryKey>::bounds(&filter).is_err());
+    }
+
+    #[test]
+    fn query_and_diff_objects() {
+        let id_1 = ObjectID::from_str(
+            "0x1100000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        let id_2 = ObjectID::from_str(
+            "0xab00000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        let id_3 = ObjectID::from_str(
+            "0xab00000000000000000000000000000000000000000000000000000000000002",
+        )
+        .unwrap();
+        let left = open_map::<ObjectKey, u64>();
+        let right = open_map::<ObjectKey, u64>();
+        let rows = |id, values: &[u64]| {
+            values
+                .iter()
+                .enumerate()
+                .map(move |(v, value)| (ObjectKey(id, SequenceNumber::from_u64(v as u64)), *value))
+                .collect::<Vec<_>>()
+        };
+        left.multi_insert(
+            rows(id_1, &[1, 2])
+                .into_iter()
+                .chain(rows(id_2, &[3, 4, 5])),
+        )
+        .unwrap();
+        right
+            .multi_insert(rows(id_2, &[3, 4, 6]).into_iter().chain(rows(id_3, &[7])))
+            .unwrap();
+
+        let filter = KeyFilter {
+            object_id_prefix: Some("0xab".to_string()),
+            min_version: Some(1),
+            ..Default::default()
+        };
+        let output = tempfile::NamedTempFile::new().unwrap();
+        let mut sink = RowSink::new(Some(output.path())).unwrap();
+        assert_eq!(query(&left, &filter, None, &mut sink).unwrap(), 2);
+        sink.finish().unwrap();
+        let values: Vec<_> = read_rows(output.path())
+            .into_iter()
+            .map(|row| row["value"].as_u64().unwrap())
+            .collect();
+        assert_eq!(values, vec![4, 5]);
+
+        let output = tempfile::NamedTempFile::new().unwrap();
+        let mut sink = RowSink::new(Some(output.path())).unwrap();
+        assert_eq!(
+            diff(&left, &right, &KeyFilter::default(), None, &mut sink).unwrap(),
+            4
+        );
+        sink.finish().unwrap();
+        let rows: Vec<_> = read_rows(output.path())
+            .into_iter()
+            .map(|row| (row["left"].as_u64(), row["right"].as_u64()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Some(1), None),
+                (Some(2), None),
+                (Some(5), Some(6)),
+                (None, Some(7)),
+            ]
+        );
+    }
+}
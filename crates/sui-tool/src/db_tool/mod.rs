@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
+use self::db_query::{diff_table, query_table, KeyFilter, RowSink};
 use self::index_search::{search_index, SearchRange};
 use crate::db_tool::db_dump::{compact, print_table_metadata, prune_checkpoints, prune_objects};
 use anyhow::{anyhow, bail};
@@ -17,6 +18,7 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
 use typed_store::rocks::MetricConf;
 pub mod db_dump;
+mod db_query;
 mod index_search;
 
 #[derive(Parser)]
@@ -40,6 +42,8 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    Query(QueryOptions),
+    Diff(DiffOptions),
 }
 
 #[derive(Parser)]
@@ -167,6 +171,41 @@ pub struct SetCheckpointWatermarkOptions {
     highest_synced: Option<CheckpointSequenceNumber>,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct QueryOptions {
+    /// The name of the table to query
+    #[arg(long = "table-name", short = 't')]
+    table_name: String,
+    #[command(flatten)]
+    filter: KeyFilter,
+    /// The maximum number of rows to print
+    #[arg(long = "limit", short = 'l')]
+    limit: Option<usize>,
+    /// Write the rows as newline delimited JSON to this file instead of printing them
+    #[arg(long = "output", short = 'o')]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct DiffOptions {
+    /// The db to compare against
+    #[arg(long = "other-db-path")]
+    other_db_path: PathBuf,
+    /// The name of the table to compare
+    #[arg(long = "table-name", short = 't')]
+    table_name: String,
+    #[command(flatten)]
+    filter: KeyFilter,
+    /// The maximum number of diverging keys to print
+    #[arg(long = "limit", short = 'l')]
+    limit: Option<usize>,
+    /// Write the diverging keys as newline delimited JSON to this file instead of printing them
+    #[arg(long = "output", short = 'o')]
+    output: Option<PathBuf>,
+}
+
 pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
@@ -222,6 +261,27 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::Query(q) => {
+            let mut sink = RowSink::new(q.output.as_deref())?;
+            let count = query_table(&db_path, &q.table_name, &q.filter, q.limit, &mut sink)?;
+            sink.finish()?;
+            eprintln!("{} rows", count);
+            Ok(())
+        }
+        DbToolCommand::Diff(d) => {
+            let mut sink = RowSink::new(d.output.as_deref())?;
+            let count = diff_table(
+                &db_path,
+                &d.other_db_path,
+                &d.table_name,
+                &d.filter,
+                d.limit,
+                &mut sink,
+            )?;
+            sink.finish()?;
+            eprintln!("{} diverging keys", count);
+            Ok(())
+        }
     }
 }
 
@@ -30,7 +30,8 @@ use sui_types::object::{Object, Owner};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionDataAPI,
+    TransactionKind,
 };
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
@@ -51,12 +52,38 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// Gas payment and gas price to build transactions with as-is, instead of selecting a gas coin
+/// and looking up the reference gas price. This allows building transactions ahead of time, to
+/// be signed offline.
+#[derive(Clone, Debug, Default)]
+pub struct GasOverrides {
+    /// The coins to pay for gas with, at exactly these versions. If more than one coin is
+    /// provided, they are merged to pay for gas.
+    pub payment: Vec<ObjectRef>,
+    /// The gas price to use instead of the current reference gas price.
+    pub price: Option<u64>,
+}
+
 #[derive(Clone)]
-pub struct TransactionBuilder(Arc<dyn DataReader + Sync + Send>);
+pub struct TransactionBuilder(Arc<dyn DataReader + Sync + Send>, GasOverrides);
 
 impl TransactionBuilder {
     pub fn new(data_reader: Arc<dyn DataReader + Sync + Send>) -> Self {
-        Self(data_reader)
+        Self(data_reader, GasOverrides::default())
+    }
+
+    /// Build transactions at the gas price set in `overrides`, paying for gas with its coins unless
+    /// the transaction pays for gas with its input coins (e.g. `pay_sui`).
+    pub fn with_gas_overrides(mut self, overrides: GasOverrides) -> Self {
+        self.1 = overrides;
+        self
+    }
+
+    async fn gas_price(&self) -> Result<u64, anyhow::Error> {
+        match self.1.price {
+            Some(gas_price) => Ok(gas_price),
+            None => self.0.get_reference_gas_price().await,
+        }
     }
 
     async fn select_gas(
@@ -70,7 +97,27 @@ impl TransactionBuilder {
         if budget < gas_price {
             bail!("Gas budget {budget} is less than the reference gas price {gas_price}. The gas budget must be at least the current reference gas price of {gas_price}.")
         }
-        if let Some(gas) = input_gas {
+        if let Some(first) = self.1.payment.first() {
+            let gas = match input_gas {
+                Some(gas) => self.1.payment.iter().find(|(id, _, _)| *id == gas),
+                None => Some(first),
+            };
+            let Some(gas) = gas else {
+                bail!(
+                    "Gas object {} is not one of the provided gas payment coins",
+                    input_gas.unwrap()
+                );
+            };
+            ensure!(
+                !self
+                    .1
+                    .payment
+                    .iter()
+                    .any(|(id, _, _)| input_objects.contains(id)),
+                "Gas payment coins cannot be used as inputs of the transaction"
+            );
+            Ok(*gas)
+        } else if let Some(gas) = input_gas {
             self.get_object_ref(gas).await
         } else {
             let gas_objs = self.0.get_owned_objects(signer, GasCoin::type_()).await?;
@@ -108,18 +155,18 @@ impl TransactionBuilder {
         let mut builder = ProgrammableTransactionBuilder::new();
         self.single_transfer_object(&mut builder, object_id, recipient)
             .await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![object_id], gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(self.with_gas_payment(TransactionData::new(
             TransactionKind::programmable(builder.finish()),
             signer,
             gas,
             gas_budget,
             gas_price,
-        ))
+        )))
     }
 
     async fn single_transfer_object(
@@ -141,7 +188,7 @@ impl TransactionBuilder {
         amount: Option<u64>,
     ) -> anyhow::Result<TransactionData> {
         let object = self.get_object_ref(sui_object_id).await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         Ok(TransactionData::new_transfer_sui(
             recipient, signer, amount, object, gas_budget, gas_price,
         ))
@@ -170,7 +217,7 @@ impl TransactionBuilder {
             .await
             .into_iter()
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, input_coins, gas_price)
             .await?;
@@ -178,6 +225,7 @@ impl TransactionBuilder {
         TransactionData::new_pay(
             signer, coin_refs, recipients, amounts, gas, gas_budget, gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
     }
 
     pub async fn pay_sui(
@@ -203,7 +251,7 @@ impl TransactionBuilder {
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         TransactionData::new_pay_sui(
             signer,
             coin_refs,
@@ -238,7 +286,7 @@ impl TransactionBuilder {
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         Ok(TransactionData::new_pay_all_sui(
             signer,
             coin_refs,
@@ -283,19 +331,19 @@ impl TransactionBuilder {
         let gas_price = if let Some(gas_price) = gas_price {
             gas_price
         } else {
-            self.0.get_reference_gas_price().await?
+            self.gas_price().await?
         };
         let gas = self
             .select_gas(signer, gas, gas_budget, input_objects, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(self.with_gas_payment(TransactionData::new(
             TransactionKind::programmable(pt),
             signer,
             gas,
             gas_budget,
             gas_price,
-        ))
+        )))
     }
 
     pub async fn single_move_call(
@@ -449,18 +497,18 @@ impl TransactionBuilder {
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(sender, gas, gas_budget, vec![], gas_price)
             .await?;
-        Ok(TransactionData::new_module(
+        Ok(self.with_gas_payment(TransactionData::new_module(
             sender,
             gas,
             compiled_modules,
             dep_ids,
             gas_budget,
             gas_price,
-        ))
+        )))
     }
 
     pub async fn upgrade(
@@ -475,7 +523,7 @@ impl TransactionBuilder {
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(sender, gas, gas_budget, vec![], gas_price)
             .await?;
@@ -499,6 +547,7 @@ impl TransactionBuilder {
             gas_budget,
             gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
     }
 
     // TODO: consolidate this with Pay transactions
@@ -518,7 +567,7 @@ impl TransactionBuilder {
         let coin_object_ref = coin.object_ref();
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
@@ -537,6 +586,7 @@ impl TransactionBuilder {
             gas_budget,
             gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
     }
 
     // TODO: consolidate this with Pay transactions
@@ -556,7 +606,7 @@ impl TransactionBuilder {
         let coin_object_ref = coin.object_ref();
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
@@ -575,6 +625,7 @@ impl TransactionBuilder {
             gas_budget,
             gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
     }
 
     // TODO: consolidate this with Pay transactions
@@ -595,7 +646,7 @@ impl TransactionBuilder {
         let coin_to_merge_ref = self.get_object_ref(coin_to_merge).await?;
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(
                 signer,
@@ -620,6 +671,7 @@ impl TransactionBuilder {
             gas_budget,
             gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
     }
 
     pub async fn batch_transaction(
@@ -665,18 +717,18 @@ impl TransactionBuilder {
                 _ => None,
             })
             .collect();
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, inputs, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(self.with_gas_payment(TransactionData::new(
             TransactionKind::programmable(pt),
             signer,
             gas,
             gas_budget,
             gas_price,
-        ))
+        )))
     }
 
     pub async fn request_add_stake(
@@ -688,7 +740,7 @@ impl TransactionBuilder {
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, coins.clone(), gas_price)
             .await?;
@@ -738,13 +790,13 @@ impl TransactionBuilder {
             ));
             builder.finish()
         };
-        Ok(TransactionData::new_programmable(
+        Ok(self.with_gas_payment(TransactionData::new_programmable(
             signer,
             vec![gas],
             pt,
             gas_budget,
             gas_price,
-        ))
+        )))
     }
 
     pub async fn request_withdraw_stake(
@@ -755,7 +807,7 @@ impl TransactionBuilder {
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
         let staked_sui = self.get_object_ref(staked_sui).await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_price = self.gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![], gas_price)
             .await?;
@@ -773,6 +825,16 @@ impl TransactionBuilder {
             gas_budget,
             gas_price,
         )
+        .map(|data| self.with_gas_payment(data))
+    }
+
+    /// Pay for gas with all the provided gas payment coins, rather than only the one returned by
+    /// `select_gas`.
+    fn with_gas_payment(&self, mut data: TransactionData) -> TransactionData {
+        if !self.1.payment.is_empty() {
+            data.gas_data_mut().payment = self.1.payment.clone();
+        }
+        data
     }
 
     // TODO: we should add retrial to reduce the transaction building error rate
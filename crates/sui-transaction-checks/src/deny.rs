@@ -154,7 +154,8 @@ fn check_package_dependencies(
     package_store: &dyn BackingPackageStore,
 ) -> SuiResult {
     let deny_map = filter_config.get_package_deny_set();
-    if deny_map.is_empty() {
+    let family_deny_map = filter_config.get_package_upgrade_family_deny_set();
+    if deny_map.is_empty() && family_deny_map.is_empty() {
         return Ok(());
     }
     let mut dependencies = vec![];
@@ -206,6 +207,21 @@ fn check_package_dependencies(
             deny_map.contains(&dep),
             format!("Access to package {:?} is temporarily disabled", dep)
         );
+        if family_deny_map.is_empty() {
+            continue;
+        }
+        // Denying an upgrade family is keyed on the original package ID, which every version of
+        // the package shares. Dependencies that don't exist will fail at execution anyway.
+        if let Some(package) = package_store.get_package_object(&dep)? {
+            let original_id = package.move_package().original_package_id();
+            deny_if_true!(
+                family_deny_map.contains(&original_id),
+                format!(
+                    "Access to package {:?} (upgrade of {:?}) is temporarily disabled",
+                    dep, original_id
+                )
+            );
+        }
     }
     Ok(())
 }
@@ -46,12 +46,13 @@ use sui_sdk::{
     apis::ReadApi,
     sui_client_config::{SuiClientConfig, SuiEnv},
     wallet_context::WalletContext,
-    SUI_COIN_TYPE, SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_TESTNET_URL,
+    SuiClient, SUI_COIN_TYPE, SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_TESTNET_URL,
 };
+use sui_transaction_builder::{GasOverrides, TransactionBuilder};
 use sui_types::{
-    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress},
     crypto::{EmptySignInfo, SignatureScheme},
-    digests::TransactionDigest,
+    digests::{ObjectDigest, TransactionDigest},
     dynamic_field::DynamicFieldInfo,
     error::SuiError,
     gas_coin::GasCoin,
@@ -199,6 +200,10 @@ pub enum SuiClientCommands {
         /// <SIGNED_TX_BYTES>`.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Explicit gas payment and gas price, for building transactions to be signed offline.
+        #[clap(flatten)]
+        gas_payment: GasPaymentOpts,
     },
 
     /// Query the chain identifier from the rpc endpoint.
@@ -372,6 +377,10 @@ pub enum SuiClientCommands {
         /// <SIGNED_TX_BYTES>`.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Explicit gas payment and gas price, for building transactions to be signed offline.
+        #[clap(flatten)]
+        gas_payment: GasPaymentOpts,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -483,6 +492,10 @@ pub enum SuiClientCommands {
         /// <SIGNED_TX_BYTES>`.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Explicit gas payment and gas price, for building transactions to be signed offline.
+        #[clap(flatten)]
+        gas_payment: GasPaymentOpts,
     },
 
     /// Split a coin object into multiple coins.
@@ -572,6 +585,10 @@ pub enum SuiClientCommands {
         /// <SIGNED_TX_BYTES>`.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Explicit gas payment and gas price, for building transactions to be signed offline.
+        #[clap(flatten)]
+        gas_payment: GasPaymentOpts,
     },
 
     /// Transfer SUI, and pay gas with the same SUI coin object.
@@ -654,6 +671,10 @@ pub enum SuiClientCommands {
         /// <SIGNED_TX_BYTES>`.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Explicit gas payment and gas price, for building transactions to be signed offline.
+        #[clap(flatten)]
+        gas_payment: GasPaymentOpts,
     },
 
     /// Run the bytecode verifier on the package
@@ -759,6 +780,60 @@ pub enum SuiClientCommands {
     },
 }
 
+/// Gas payment to use as-is instead of resolving it against the network. This makes it possible to
+/// produce transaction bytes ahead of time (e.g. with `--serialize-unsigned-transaction`), sign
+/// them on an air-gapped machine with `sui keytool sign`, and submit them later with
+/// `sui client execute-signed-tx`.
+#[derive(Args, Clone, Debug, Default)]
+pub struct GasPaymentOpts {
+    /// Object references of the coins to pay gas with, formatted as `<ID>:<VERSION>:<DIGEST>`.
+    /// The versions and digests are used as provided, without looking up the latest version of
+    /// the coins. If more than one reference is provided, the coins are merged to pay for gas.
+    #[clap(long = "gas-object-ref", num_args(1..), value_parser = parse_object_ref)]
+    pub gas_object_refs: Vec<ObjectRef>,
+
+    /// Gas price to use for the transaction instead of the current reference gas price.
+    #[clap(long)]
+    pub reference_gas_price: Option<u64>,
+}
+
+impl GasPaymentOpts {
+    /// The gas object whose owner sends the transaction: either the one that was explicitly
+    /// requested, or the first of the provided gas object references.
+    fn gas_object_id(&self, gas: Option<ObjectID>) -> Option<ObjectID> {
+        gas.or_else(|| self.gas_object_refs.first().map(|(id, _, _)| *id))
+    }
+
+    /// A transaction builder which pays for gas with the provided coins and at the provided gas
+    /// price, instead of looking them up on the network.
+    fn transaction_builder(&self, client: &SuiClient) -> TransactionBuilder {
+        client
+            .transaction_builder()
+            .clone()
+            .with_gas_overrides(GasOverrides {
+                payment: self.gas_object_refs.clone(),
+                price: self.reference_gas_price,
+            })
+    }
+}
+
+/// Parse an object reference of the form `<ID>:<VERSION>:<DIGEST>`.
+pub(crate) fn parse_object_ref(s: &str) -> anyhow::Result<ObjectRef> {
+    let parts: Vec<_> = s.split(':').collect();
+    let [id, version, digest] = parts.as_slice() else {
+        bail!("Invalid object reference '{s}', expected <ID>:<VERSION>:<DIGEST>");
+    };
+    let id = ObjectID::from_str(id).map_err(|e| anyhow!("Invalid object ID '{id}': {e}"))?;
+    let version = SequenceNumber::from_u64(
+        version
+            .parse()
+            .map_err(|e| anyhow!("Invalid object version '{version}': {e}"))?,
+    );
+    let digest = ObjectDigest::from_str(digest)
+        .map_err(|e| anyhow!("Invalid object digest '{digest}': {e}"))?;
+    Ok((id, version, digest))
+}
+
 #[derive(serde::Deserialize)]
 struct FaucetResponse {
     error: Option<String>,
@@ -959,7 +1034,9 @@ impl SuiClientCommands {
                 with_unpublished_dependencies,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                gas_payment,
             } => {
+                let gas = gas_payment.gas_object_id(gas);
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
 
@@ -976,8 +1053,8 @@ impl SuiClientCommands {
                     )
                     .await?;
 
-                let data = client
-                    .transaction_builder()
+                let data = gas_payment
+                    .transaction_builder(&client)
                     .upgrade(
                         sender,
                         package_id,
@@ -1007,6 +1084,7 @@ impl SuiClientCommands {
                 with_unpublished_dependencies,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                gas_payment,
             } => {
                 if build_config.test_mode {
                     return Err(SuiError::ModulePublishFailure {
@@ -1023,6 +1101,7 @@ impl SuiClientCommands {
                     .into());
                 }
 
+                let gas = gas_payment.gas_object_id(gas);
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
 
@@ -1036,8 +1115,8 @@ impl SuiClientCommands {
                 )
                 .await?;
 
-                let data = client
-                    .transaction_builder()
+                let data = gas_payment
+                    .transaction_builder(&client)
                     .publish(
                         sender,
                         compiled_modules,
@@ -1136,9 +1215,19 @@ impl SuiClientCommands {
                 args,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                gas_payment,
             } => {
+                let gas = gas_payment.gas_object_id(gas);
                 let tx_data = construct_move_call_transaction(
-                    package, &module, &function, type_args, gas, gas_budget, gas_price, args,
+                    package,
+                    &module,
+                    &function,
+                    type_args,
+                    gas,
+                    gas_budget,
+                    gas_price,
+                    &gas_payment,
+                    args,
                     context,
                 )
                 .await?;
@@ -1158,12 +1247,13 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                gas_payment,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
                 let to = get_identity_address(Some(to), context)?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
+                let data = gas_payment
+                    .transaction_builder(&client)
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
                 serialize_or_execute!(
@@ -1207,6 +1297,7 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                gas_payment,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -1231,8 +1322,8 @@ impl SuiClientCommands {
                     .map_err(|e| anyhow!("{e}"))?;
                 let from = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
+                let data = gas_payment
+                    .transaction_builder(&client)
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
                 serialize_or_execute!(
@@ -2077,6 +2168,7 @@ async fn construct_move_call_transaction(
     gas: Option<ObjectID>,
     gas_budget: u64,
     gas_price: Option<u64>,
+    gas_payment: &GasPaymentOpts,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<TransactionData, anyhow::Error> {
@@ -2094,8 +2186,8 @@ async fn construct_move_call_transaction(
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
     let client = context.get_client().await?;
-    client
-        .transaction_builder()
+    gas_payment
+        .transaction_builder(&client)
         .move_call(
             sender, package, module, function, type_args, args, gas, gas_budget, gas_price,
         )
@@ -8,7 +8,10 @@ use move_command_line_common::{
     types::{ParsedFqName, ParsedModuleId, ParsedStructType, ParsedType},
 };
 use move_core_types::runtime_value::MoveValue;
-use sui_types::{base_types::ObjectID, Identifier};
+use sui_types::{
+    base_types::{ObjectID, ObjectRef},
+    Identifier,
+};
 
 use crate::{error, sp};
 
@@ -31,6 +34,10 @@ pub const GAS_BUDGET: &str = "gas-budget";
 pub const SUMMARY: &str = "summary";
 pub const GAS_COIN: &str = "gas-coin";
 pub const JSON: &str = "json";
+pub const GAS_OBJECT_REF: &str = "gas-object-ref";
+pub const REFERENCE_GAS_PRICE: &str = "reference-gas-price";
+pub const SERIALIZE_UNSIGNED: &str = "serialize-unsigned-transaction";
+pub const SERIALIZE_SIGNED: &str = "serialize-signed-transaction";
 
 // Types
 pub const U8: &str = "u8";
@@ -67,6 +74,10 @@ pub const COMMANDS: &[&str] = &[
     SUMMARY,
     GAS_COIN,
     JSON,
+    GAS_OBJECT_REF,
+    REFERENCE_GAS_PRICE,
+    SERIALIZE_UNSIGNED,
+    SERIALIZE_SIGNED,
 ];
 
 pub fn is_keyword(s: &str) -> bool {
@@ -100,6 +111,10 @@ pub struct ProgramMetadata {
     pub gas_object_id: Option<Spanned<ObjectID>>,
     pub json_set: bool,
     pub gas_budget: Spanned<u64>,
+    pub gas_object_refs: Vec<Spanned<ObjectRef>>,
+    pub gas_price: Option<Spanned<u64>>,
+    pub serialize_unsigned_set: bool,
+    pub serialize_signed_set: bool,
 }
 
 /// A parsed module access consisting of the address, module name, and function name.
@@ -3,7 +3,9 @@
 
 use crate::{
     client_ptb::{
-        ast::{GAS_BUDGET, GAS_COIN, JSON, SUMMARY, WARN_SHADOWS},
+        ast::{
+            GAS_BUDGET, GAS_COIN, GAS_OBJECT_REF, JSON, REFERENCE_GAS_PRICE, SUMMARY, WARN_SHADOWS,
+        },
         ptb::PTBPreview,
     },
     sp,
@@ -33,6 +35,15 @@ impl<'a> Display for PTBPreview<'a> {
         if let Some(gas_coin_id) = self.program_metadata.gas_object_id {
             builder.push_record([GAS_COIN, gas_coin_id.value.to_string().as_str()]);
         }
+        for sp!(_, (id, version, digest)) in &self.program_metadata.gas_object_refs {
+            builder.push_record([
+                GAS_OBJECT_REF,
+                format!("{id}:{}:{digest}", version.value()).as_str(),
+            ]);
+        }
+        if let Some(gas_price) = self.program_metadata.gas_price {
+            builder.push_record([REFERENCE_GAS_PRICE, gas_price.value.to_string().as_str()]);
+        }
         if self.program_metadata.json_set {
             builder.push_record([JSON, "true"]);
         }
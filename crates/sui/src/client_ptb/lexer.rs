@@ -324,6 +324,18 @@ impl<'l, I: Iterator<Item = &'l str>> Iterator for Lexer<'l, I> {
                         file.widen(c).map(|src| Lexeme(T::Upgrade, src))
                     }
 
+                    sp!(_, "gas-object-ref") => {
+                        if let Some(next) = self.peek() {
+                            break 'command self.unexpected(next);
+                        }
+
+                        let Some(object_ref) = self.eat_token() else {
+                            break 'command self.done(T::EarlyEof);
+                        };
+
+                        object_ref.widen(c).map(|src| Lexeme(T::GasObjectRef, src))
+                    }
+
                     sp!(_, _) => ident.widen(c).map(|src| Lexeme(T::Command, src)),
                 }
             }
@@ -8,9 +8,13 @@ use move_command_line_common::{
     parser::{parse_u128, parse_u16, parse_u256, parse_u32, parse_u64, parse_u8},
     types::{ParsedFqName, ParsedModuleId, ParsedStructType, ParsedType},
 };
-use sui_types::{base_types::ObjectID, Identifier};
+use sui_types::{
+    base_types::{ObjectID, ObjectRef},
+    Identifier,
+};
 
 use crate::{
+    client_commands::parse_object_ref,
     client_ptb::{
         ast::{all_keywords, COMMANDS},
         builder::{display_did_you_mean, find_did_you_means},
@@ -40,6 +44,10 @@ struct ProgramParsingState {
     json_set: bool,
     gas_object_id: Option<Spanned<ObjectID>>,
     gas_budget: Option<Spanned<u64>>,
+    gas_object_refs: Vec<Spanned<ObjectRef>>,
+    gas_price: Option<Spanned<u64>>,
+    serialize_unsigned_set: bool,
+    serialize_signed_set: bool,
 }
 
 impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
@@ -59,6 +67,10 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 json_set: false,
                 gas_object_id: None,
                 gas_budget: None,
+                gas_object_refs: vec![],
+                gas_price: None,
+                serialize_unsigned_set: false,
+                serialize_signed_set: false,
             },
         })
     }
@@ -103,6 +115,8 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 L(T::Command, A::JSON) => flag!(json_set),
                 L(T::Command, A::PREVIEW) => flag!(preview_set),
                 L(T::Command, A::WARN_SHADOWS) => flag!(warn_shadows_set),
+                L(T::Command, A::SERIALIZE_UNSIGNED) => flag!(serialize_unsigned_set),
+                L(T::Command, A::SERIALIZE_SIGNED) => flag!(serialize_signed_set),
                 L(T::Command, A::GAS_COIN) => {
                     let specifier = try_!(self.parse_gas_specifier());
                     self.state.gas_object_id = Some(specifier);
@@ -122,6 +136,25 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                         self.fast_forward_to_next_command();
                     }
                 }
+                L(T::GasObjectRef, object_ref) => match parse_object_ref(object_ref) {
+                    Ok(object_ref) => self.state.gas_object_refs.push(sp.wrap(object_ref)),
+                    Err(e) => self.state.errors.push(err!(sp, "{e}")),
+                },
+                L(T::Command, A::REFERENCE_GAS_PRICE) => {
+                    let price = try_!(self.parse_gas_budget()).widen_span(sp);
+                    if let Some(other) = self.state.gas_price.replace(price) {
+                        self.state.errors.extend([
+                            err!(
+                                other.span,
+                                "Multiple gas prices found. Gas price first set here.",
+                            ),
+                            err!(price.span => help: {
+                                "PTBs must have at most one gas price set."
+                            },"Gas price set again here."),
+                        ]);
+                        self.fast_forward_to_next_command();
+                    }
+                }
 
                 L(T::Command, A::TRANSFER_OBJECTS) => command!(self.parse_transfer_objects()),
                 L(T::Command, A::SPLIT_COINS) => command!(self.parse_split_coins()),
@@ -192,6 +225,26 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
             return Err(self.state.errors);
         };
 
+        if let (Some(gas_coin), Some(gas_object_ref)) =
+            (self.state.gas_object_id, self.state.gas_object_refs.first())
+        {
+            self.state.errors.extend([
+                err!(gas_coin.span, "Gas coin set here."),
+                err!(gas_object_ref.span => help: {
+                    "Use either --{} or --{}", A::GAS_COIN, A::GAS_OBJECT_REF
+                }, "Gas object reference set here, as well as a gas coin."),
+            ]);
+        }
+
+        if self.state.serialize_unsigned_set && self.state.serialize_signed_set {
+            self.state.errors.push(err!(
+                sp => help: {
+                    "Use either --{} or --{}", A::SERIALIZE_UNSIGNED, A::SERIALIZE_SIGNED
+                },
+                "Cannot serialize both the unsigned and the signed transaction."
+            ));
+        }
+
         if self.state.errors.is_empty() {
             Ok((
                 A::Program {
@@ -204,6 +257,10 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                     gas_object_id: self.state.gas_object_id,
                     json_set: self.state.json_set,
                     gas_budget,
+                    gas_object_refs: self.state.gas_object_refs,
+                    gas_price: self.state.gas_price,
+                    serialize_unsigned_set: self.state.serialize_unsigned_set,
+                    serialize_signed_set: self.state.serialize_signed_set,
                 },
             ))
         } else {
@@ -727,6 +784,7 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sui_types::{base_types::SequenceNumber, digests::ObjectDigest};
 
     #[test]
     fn test_parse() {
@@ -761,6 +819,50 @@ mod tests {
         insta::assert_debug_snapshot!(parsed);
     }
 
+    #[test]
+    fn test_parse_gas_payment() {
+        let digest = ObjectDigest::new([1; 32]);
+        let input = format!(
+            "--gas-object-ref 0x1:3:{digest} --gas-object-ref 0x2:5:{digest} \
+             --reference-gas-price 1000 --gas-budget 1"
+        );
+        let x = shlex::split(&input).unwrap();
+        let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+        let (_, metadata) = parser.parse().unwrap();
+        let gas_object_refs: Vec<_> = metadata
+            .gas_object_refs
+            .iter()
+            .map(|object_ref| object_ref.value)
+            .collect();
+        assert_eq!(
+            gas_object_refs,
+            vec![
+                (
+                    ObjectID::from_single_byte(1),
+                    SequenceNumber::from_u64(3),
+                    digest
+                ),
+                (
+                    ObjectID::from_single_byte(2),
+                    SequenceNumber::from_u64(5),
+                    digest
+                ),
+            ]
+        );
+        assert_eq!(metadata.gas_price.unwrap().value, 1000);
+
+        // Gas object references must be complete, and cannot be combined with a gas coin.
+        let inputs = vec![
+            "--gas-object-ref 0x1:3 --gas-budget 1".to_owned(),
+            format!("--gas-coin @0x1 --gas-object-ref 0x1:3:{digest} --gas-budget 1"),
+        ];
+        for input in inputs {
+            let x = shlex::split(&input).unwrap();
+            let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+            assert!(parser.parse().is_err());
+        }
+    }
+
     #[test]
     fn test_parse_args() {
         let inputs = vec![
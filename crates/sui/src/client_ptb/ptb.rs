@@ -14,6 +14,7 @@ use crate::{
 
 use anyhow::{anyhow, Error};
 use clap::{arg, Args, ValueHint};
+use fastcrypto::encoding::{Base64, Encoding};
 use move_core_types::account_address::AccountAddress;
 use serde::Serialize;
 use shared_crypto::intent::Intent;
@@ -27,7 +28,7 @@ use sui_types::{
     digests::TransactionDigest,
     gas::GasCostSummary,
     quorum_driver_types::ExecuteTransactionRequestType,
-    transaction::{ProgrammableTransaction, Transaction, TransactionData},
+    transaction::{ProgrammableTransaction, SenderSignedData, Transaction, TransactionData},
 };
 
 use super::{ast::ProgramMetadata, lexer::Lexer, parser::ProgramParser};
@@ -131,32 +132,49 @@ impl PTB {
             anyhow::bail!("No active address, cannot execute PTB");
         };
 
-        // find the gas coins if we have no gas coin given
-        let coins = if let Some(gas) = program_metadata.gas_object_id {
-            context.get_object_ref(gas.value).await?
+        // use the gas coins as-is if their object references were given, or find the gas coin if
+        // we have no gas coin given
+        let coins = if !program_metadata.gas_object_refs.is_empty() {
+            program_metadata
+                .gas_object_refs
+                .iter()
+                .map(|object_ref| object_ref.value)
+                .collect()
+        } else if let Some(gas) = program_metadata.gas_object_id {
+            vec![context.get_object_ref(gas.value).await?]
         } else {
-            context
+            vec![context
                 .gas_for_owner_budget(sender, program_metadata.gas_budget.value, BTreeSet::new())
                 .await?
                 .1
-                .object_ref()
+                .object_ref()]
         };
 
-        // get the gas price
-        let gas_price = context
-            .get_client()
-            .await?
-            .read_api()
-            .get_reference_gas_price()
-            .await?;
+        // get the gas price, unless one was explicitly provided
+        let gas_price = if let Some(gas_price) = program_metadata.gas_price {
+            gas_price.value
+        } else {
+            context
+                .get_client()
+                .await?
+                .read_api()
+                .get_reference_gas_price()
+                .await?
+        };
         // create the transaction data that will be sent to the network
         let tx_data = TransactionData::new_programmable(
             sender,
-            vec![coins],
+            coins,
             ptb,
             program_metadata.gas_budget.value,
             gas_price,
         );
+
+        if program_metadata.serialize_unsigned_set {
+            println!("{}", Base64::encode(bcs::to_bytes(&tx_data)?));
+            return Ok(());
+        }
+
         // sign the tx
         let signature =
             context
@@ -164,6 +182,16 @@ impl PTB {
                 .keystore
                 .sign_secure(&sender, &tx_data, Intent::sui_transaction())?;
 
+        if program_metadata.serialize_signed_set {
+            let sender_signed_data = SenderSignedData::new_from_sender_signature(
+                tx_data,
+                Intent::sui_transaction(),
+                signature,
+            );
+            println!("{}", Base64::encode(bcs::to_bytes(&sender_signed_data)?));
+            return Ok(());
+        }
+
         // execute the transaction
         let transaction_response = context
             .get_client()
@@ -301,6 +329,17 @@ pub fn ptb_description() -> clap::Command {
             --"gas-budget" <MIST>
             "The gas budget for the transaction, in MIST."
         ))
+        .arg(arg!(
+            --"gas-object-ref" <OBJECT_REF> ...
+            "The object reference <ID>:<VERSION>:<DIGEST> of a gas coin to use at exactly this \
+            version. Can be repeated to pay for gas with multiple coins. Cannot be combined with \
+            --gas-coin."
+        ))
+        .arg(arg!(
+            --"reference-gas-price" <MIST>
+            "The gas price for the transaction, in MIST. If not specified, the current reference \
+            gas price of the network is used."
+        ))
         .arg(arg!(
             --"make-move-vec" <MAKE_MOVE_VEC>
             "Given n-values of the same type, it constructs a vector. For non objects or an empty \
@@ -395,4 +434,14 @@ pub fn ptb_description() -> clap::Command {
             --"json"
             "Return command outputs in json format."
         ))
+        .arg(arg!(
+            --"serialize-unsigned-transaction"
+            "Instead of executing the transaction, serialize the bcs bytes of the unsigned \
+            transaction data using base64 encoding."
+        ))
+        .arg(arg!(
+            --"serialize-signed-transaction"
+            "Instead of executing the transaction, serialize the bcs bytes of the signed \
+            transaction data using base64 encoding."
+        ))
 }
@@ -55,11 +55,13 @@ pub enum Token {
     EarlyEof,
 
     // The following tokens are special -- they consume multiple shell tokens, to ensure we capture
-    // the path for a publish or an upgrade command.
+    // the path for a publish or an upgrade command, or the object reference of a gas coin.
     /// --publish \<shell-token\>
     Publish,
     /// --upgraded \<shell-token\>
     Upgrade,
+    /// --gas-object-ref \<shell-token\>
+    GasObjectRef,
 }
 
 impl<'l> Lexeme<'l> {
@@ -76,7 +78,14 @@ impl<'l> Lexeme<'l> {
 
     /// Returns true if this lexeme signifies the end of the current command.
     pub fn is_command_end(&self) -> bool {
-        self.is_terminal() || [Token::Command, Token::Publish, Token::Upgrade].contains(&self.0)
+        self.is_terminal()
+            || [
+                Token::Command,
+                Token::Publish,
+                Token::Upgrade,
+                Token::GasObjectRef,
+            ]
+            .contains(&self.0)
     }
 }
 
@@ -106,6 +115,7 @@ impl<'a> fmt::Display for Lexeme<'a> {
             T::EarlyEof | T::Eof => write!(f, "end of input"),
             T::Publish => write!(f, "command '--publish {:?}'", self.1),
             T::Upgrade => write!(f, "command '--upgrade {:?}'", self.1),
+            T::GasObjectRef => write!(f, "command '--gas-object-ref {:?}'", self.1),
         }
     }
 }
@@ -136,6 +146,7 @@ impl fmt::Display for Token {
             T::EarlyEof => write!(f, "unexpected end of input"),
             T::Publish => write!(f, "a '--publish' command"),
             T::Upgrade => write!(f, "an '--upgrade' command"),
+            T::GasObjectRef => write!(f, "a '--gas-object-ref' command"),
         }
     }
 }
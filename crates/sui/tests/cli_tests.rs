@@ -8,13 +8,15 @@ use std::str::FromStr;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::BuildConfig as MoveBuildConfig;
 use serde_json::json;
+use shared_crypto::intent::Intent;
 use sui::key_identity::{get_identity_address, KeyIdentity};
 use sui_test_transaction_builder::batch_make_transfer_transactions;
 use sui_types::object::Owner;
 use sui_types::transaction::{
-    TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+    TransactionDataAPI, TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
     TEST_ONLY_GAS_UNIT_FOR_PUBLISH, TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
     TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
@@ -22,7 +24,7 @@ use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands},
+    client_commands::{GasPaymentOpts, SuiClientCommandResult, SuiClientCommands},
     sui_commands::SuiCommand,
 };
 use sui_config::{
@@ -232,6 +234,7 @@ async fn test_ptb_publish_and_complex_arg_resolution() -> Result<(), anyhow::Err
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -264,6 +267,7 @@ async fn test_ptb_publish_and_complex_arg_resolution() -> Result<(), anyhow::Err
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -478,6 +482,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -529,6 +534,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -611,6 +617,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -651,6 +658,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -678,6 +686,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -702,6 +711,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -733,6 +743,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -754,6 +765,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_price: Some(12345),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -810,6 +822,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -877,6 +890,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -911,6 +925,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -934,6 +949,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         args: vec![SuiJsonValue::from_str(&shared_id.to_string()).unwrap()],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -986,6 +1002,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1020,6 +1037,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1062,6 +1080,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1114,6 +1133,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1148,6 +1168,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1190,6 +1211,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1242,6 +1264,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1276,6 +1299,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1318,6 +1342,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1372,6 +1397,7 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1440,6 +1466,7 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -1486,6 +1513,7 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -1541,6 +1569,7 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -1583,6 +1612,7 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -1626,6 +1656,7 @@ async fn test_package_publish_test_flag() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await;
@@ -1681,6 +1712,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1753,6 +1785,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1815,6 +1848,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -1920,6 +1954,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts::default(),
     }
     .execute(context)
     .await?;
@@ -2633,6 +2668,107 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_serialize_tx_with_explicit_gas_payment() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(SuiObjectResponseQuery::new_with_options(
+                SuiObjectDataOptions::new(),
+            )),
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let object_to_send = object_refs.first().unwrap().object().unwrap().object_id;
+    let gas_ref = object_refs.get(1).unwrap().object().unwrap().object_ref();
+    let gas_price = rgp * 2;
+
+    let tx_data = match (SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(address1),
+        object_id: object_to_send,
+        gas: None,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts {
+            gas_object_refs: vec![gas_ref],
+            reference_gas_price: Some(gas_price),
+        },
+    })
+    .execute(context)
+    .await?
+    {
+        SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) => tx_data,
+        _ => panic!("Expected the unsigned transaction to be serialized"),
+    };
+    assert_eq!(tx_data.gas(), &[gas_ref]);
+    assert_eq!(tx_data.gas_price(), gas_price);
+
+    // Sign the bytes separately, as would happen on an offline machine, and submit them.
+    let signature =
+        context
+            .config
+            .keystore
+            .sign_secure(&address, &tx_data, Intent::sui_transaction())?;
+    SuiClientCommands::ExecuteSignedTx {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        signatures: vec![Base64::encode(signature.as_ref())],
+    }
+    .execute(context)
+    .await?;
+
+    // The gas coins are used at exactly the given versions, even if they are no longer the latest
+    // ones, and all of them pay for gas.
+    let other_gas_ref = object_refs.get(2).unwrap().object().unwrap().object_ref();
+    let tx_data = match (SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(address1),
+        object_id: object_to_send,
+        gas: Some(other_gas_ref.0),
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts {
+            gas_object_refs: vec![gas_ref, other_gas_ref],
+            reference_gas_price: None,
+        },
+    })
+    .execute(context)
+    .await?
+    {
+        SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) => tx_data,
+        _ => panic!("Expected the unsigned transaction to be serialized"),
+    };
+    assert_eq!(tx_data.gas(), &[gas_ref, other_gas_ref]);
+    assert_eq!(tx_data.gas_price(), rgp);
+
+    // The gas object must be one of the provided gas coins.
+    let result = SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(address1),
+        object_id: object_to_send,
+        gas: Some(object_refs.get(3).unwrap().object().unwrap().object_id),
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+        gas_payment: GasPaymentOpts {
+            gas_object_refs: vec![gas_ref],
+            reference_gas_price: None,
+        },
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
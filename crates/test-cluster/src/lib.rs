@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use consensus_config::ConsensusNetwork;
 use futures::{future::join_all, StreamExt};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::ws_client::WsClient;
@@ -760,6 +761,7 @@ pub struct TestClusterBuilder {
     authority_overload_config: Option<AuthorityOverloadConfig>,
     data_ingestion_dir: Option<PathBuf>,
     fullnode_run_with_range: Option<RunWithRange>,
+    consensus_network: Option<ConsensusNetwork>,
 }
 
 impl TestClusterBuilder {
@@ -782,6 +784,7 @@ impl TestClusterBuilder {
             authority_overload_config: None,
             data_ingestion_dir: None,
             fullnode_run_with_range: None,
+            consensus_network: None,
         }
     }
 
@@ -938,6 +941,12 @@ impl TestClusterBuilder {
         self
     }
 
+    pub fn with_consensus_network(mut self, network: ConsensusNetwork) -> Self {
+        assert!(self.network_config.is_none());
+        self.consensus_network = Some(network);
+        self
+    }
+
     pub async fn build(mut self) -> TestCluster {
         // All test clusters receive a continuous stream of random JWKs.
         // If we later use zklogin authenticated transactions in tests we will need to supply
@@ -1033,6 +1042,10 @@ impl TestClusterBuilder {
             builder = builder.with_authority_overload_config(authority_overload_config);
         }
 
+        if let Some(network) = self.consensus_network {
+            builder = builder.with_consensus_network(network);
+        }
+
         if let Some(fullnode_rpc_port) = self.fullnode_rpc_port {
             builder = builder.with_fullnode_rpc_port(fullnode_rpc_port);
         }
@@ -241,6 +241,8 @@ fn extract_generics_names(generics: &Generics) -> Vec<Ident> {
 ///
 /// 2. Auto-generated `open` routine
 /// The function `open_tables_read_write` is generated which allows for specifying DB wide options and custom table configs as mentioned above
+/// The function `open_tables_in_memory` opens the tables against a database which keeps them in memory instead of in
+/// RocksDB, which is useful for tests and tools which do not need the data to outlive the process
 ///
 /// 3. Auto-generated `read_only_mode` handle
 /// This mode provides handle struct which opens the DB in read only mode and has certain features like dumping and counting the keys in the tables
@@ -508,6 +510,34 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of tables in read-write mode backed by an in-memory database
+            /// Nothing is written to disk and all data is lost once the tables are dropped
+            /// The merge operators, compaction filters and read-write options of the tables come from `default_options_override_fn`
+            #[allow(unused_parens)]
+            pub fn open_tables_in_memory(
+                metric_conf: typed_store::rocks::MetricConf,
+            ) -> Self {
+                let opt_cfs = [
+                    #(
+                        (stringify!(#cf_names), #default_options_override_fn_names()),
+                    )*
+                ];
+                let db = typed_store::rocks::open_cf_opts_in_memory(metric_conf, &opt_cfs);
+                let rwopt_cfs: std::collections::HashMap<&str, typed_store::rocks::ReadWriteOptions> = opt_cfs.iter().map(|q| (q.0, q.1.rw_options.clone())).collect();
+                let (
+                        #(
+                            #field_names
+                        ),*
+                ) = (#(
+                        DBMap::#inner_types::reopen(&db, Some(stringify!(#cf_names)), rwopt_cfs.get(stringify!(#cf_names)).unwrap_or(&typed_store::rocks::ReadWriteOptions::default())).expect(&format!("Cannot open {} CF.", stringify!(#cf_names))[..])
+                    ),*);
+                Self {
+                    #(
+                        #field_names: #post_process_fn(#field_names),
+                    )*
+                }
+            }
+
             /// Returns a list of the tables name and type pairs
             pub fn describe_tables() -> std::collections::BTreeMap<String, (String, String)> {
                 vec![#(
@@ -19,4 +19,6 @@ pub enum TypedStoreError {
     MetricsReporting,
     #[error("Transaction should be retried")]
     RetryableTransactionError,
+    #[error("sally write-ahead log error: {0}")]
+    WalError(String),
 }
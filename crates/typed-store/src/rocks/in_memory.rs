@@ -0,0 +1,641 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use im::OrdMap;
+use rocksdb::{
+    BoundColumnFamily, CStrLike, CompactOptions, CompactionDecision, Direction, Error,
+    IteratorMode, LiveFile, ReadOptions, WriteOptions,
+};
+
+use super::MetricConf;
+use crate::metrics::DBMetrics;
+use crate::TypedStoreError;
+
+/// The contents of a column family. Cloning it is cheap, as the clone shares its nodes with the
+/// original until either is modified, so iterators and snapshots can hold on to it while writes
+/// go on without copying the whole column family.
+type ColumnFamily = OrdMap<Vec<u8>, Vec<u8>>;
+
+type MergeFn =
+    dyn Fn(&[u8], Option<&[u8]>, &mut dyn Iterator<Item = &[u8]>) -> Option<Vec<u8>> + Send + Sync;
+
+type CompactionFilterFn = dyn FnMut(u32, &[u8], &[u8]) -> CompactionDecision + Send;
+
+/// The options of a column family of an `InMemoryDB` which change what is stored in it. They are
+/// set through `DBOptions`, alongside the equivalent RocksDB options.
+#[derive(Clone, Default)]
+pub struct InMemoryOptions {
+    merge_operator: Option<Arc<MergeFn>>,
+    compaction_filter: Option<Arc<Mutex<Box<CompactionFilterFn>>>>,
+}
+
+impl InMemoryOptions {
+    /// Merges written to the column family are combined with the stored value by `merge_fn` when
+    /// the batch containing them is written.
+    pub fn set_merge_operator<F>(&mut self, merge_fn: F)
+    where
+        F: Fn(&[u8], Option<&[u8]>, &mut dyn Iterator<Item = &[u8]>) -> Option<Vec<u8>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.merge_operator = Some(Arc::new(merge_fn));
+    }
+
+    /// Compactions of the column family remove or change the entries as `filter_fn` decides.
+    pub fn set_compaction_filter<F>(&mut self, filter_fn: F)
+    where
+        F: FnMut(u32, &[u8], &[u8]) -> CompactionDecision + Send + 'static,
+    {
+        self.compaction_filter = Some(Arc::new(Mutex::new(Box::new(filter_fn))));
+    }
+}
+
+impl fmt::Debug for InMemoryOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryOptions")
+            .field("merge_operator", &self.merge_operator.is_some())
+            .field("compaction_filter", &self.compaction_filter.is_some())
+            .finish()
+    }
+}
+
+/// A database which keeps each column family in an ordered map from raw keys to raw values,
+/// instead of in RocksDB. Reads, writes, merges, batches, snapshots and bounded iteration behave
+/// like they do on RocksDB, so `DBMap`s opened on it can be used by tests and tools which do not
+/// need their data to outlive the process.
+///
+/// The methods mirror the names and signatures of the RocksDB ones they stand in for, taking a
+/// column family name where RocksDB takes a handle. Compactions only run the compaction filter of
+/// the column family over the compacted range, and tuning operations, like flushes, are no-ops.
+#[derive(Debug)]
+pub struct InMemoryDB {
+    column_families: RwLock<HashMap<String, ColumnFamily>>,
+    options: RwLock<HashMap<String, InMemoryOptions>>,
+    pub metric_conf: MetricConf,
+}
+
+impl InMemoryDB {
+    pub fn new(metric_conf: MetricConf, opt_cfs: &[(&str, InMemoryOptions)]) -> Self {
+        DBMetrics::get().increment_num_active_dbs(&metric_conf.db_name);
+        let column_families = [rocksdb::DEFAULT_COLUMN_FAMILY_NAME]
+            .into_iter()
+            .chain(opt_cfs.iter().map(|(name, _)| *name))
+            .map(|name| (name.to_string(), ColumnFamily::new()))
+            .collect();
+        let options = opt_cfs
+            .iter()
+            .map(|(name, options)| (name.to_string(), options.clone()))
+            .collect();
+        Self {
+            column_families: RwLock::new(column_families),
+            options: RwLock::new(options),
+            metric_conf,
+        }
+    }
+
+    /// Returns the current contents of a column family. Later writes do not modify the returned
+    /// map, so it can be read without holding any lock.
+    fn column_family(&self, cf_name: &str) -> ColumnFamily {
+        self.column_families
+            .read()
+            .unwrap()
+            .get(cf_name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Column family {cf_name} does not exist"))
+    }
+
+    fn options(&self, cf_name: &str) -> InMemoryOptions {
+        self.options
+            .read()
+            .unwrap()
+            .get(cf_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn update_column_family(&self, cf_name: &str, update: impl FnOnce(&mut ColumnFamily)) {
+        let mut column_families = self.column_families.write().unwrap();
+        update(
+            column_families
+                .get_mut(cf_name)
+                .unwrap_or_else(|| panic!("Column family {cf_name} does not exist")),
+        );
+    }
+
+    /// Applies the operations to copies of the column families they write to, and only replaces
+    /// the column families once all of them succeeded, so a failed merge leaves the database
+    /// unchanged.
+    fn write_ops(
+        &self,
+        ops: impl IntoIterator<Item = (String, InMemoryBatchOp)>,
+    ) -> Result<(), TypedStoreError> {
+        let mut column_families = self.column_families.write().unwrap();
+        let mut updated = HashMap::new();
+        for (cf_name, op) in ops {
+            let column_family = match updated.entry(cf_name.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let column_family = column_families
+                        .get(entry.key())
+                        .cloned()
+                        .ok_or_else(|| TypedStoreError::UnregisteredColumn(entry.key().clone()))?;
+                    entry.insert(column_family)
+                }
+            };
+            match op {
+                InMemoryBatchOp::Put(key, value) => {
+                    column_family.insert(key, value);
+                }
+                InMemoryBatchOp::Merge(key, operand) => {
+                    let merge_operator =
+                        self.options(&cf_name).merge_operator.ok_or_else(|| {
+                            TypedStoreError::RocksDBError(format!(
+                                "Column family {cf_name} has no merge operator"
+                            ))
+                        })?;
+                    let merged = merge_operator(
+                        &key,
+                        column_family.get(key.as_slice()).map(Vec::as_slice),
+                        &mut std::iter::once(operand.as_slice()),
+                    )
+                    .ok_or_else(|| {
+                        TypedStoreError::RocksDBError(format!(
+                            "Merge operator of column family {cf_name} failed"
+                        ))
+                    })?;
+                    column_family.insert(key, merged);
+                }
+                InMemoryBatchOp::Delete(key) => {
+                    column_family.remove(key.as_slice());
+                }
+                InMemoryBatchOp::DeleteRange(from, to) => {
+                    if from < to {
+                        let keys: Vec<_> = column_family
+                            .range(from..to)
+                            .map(|(key, _)| key.clone())
+                            .collect();
+                        for key in keys {
+                            column_family.remove(key.as_slice());
+                        }
+                    }
+                }
+            }
+        }
+        column_families.extend(updated);
+        Ok(())
+    }
+
+    /// Runs the compaction filter of a column family over its entries from `start` to `end`,
+    /// both inclusive like for RocksDB compactions.
+    fn compact(&self, cf_name: &str, start: Option<&[u8]>, end: Option<&[u8]>) {
+        let Some(compaction_filter) = self.options(cf_name).compaction_filter else {
+            return;
+        };
+        let mut compaction_filter = compaction_filter.lock().unwrap();
+        let start = start.map_or(Bound::Unbounded, Bound::Included);
+        let end = end.map_or(Bound::Unbounded, Bound::Included);
+        self.update_column_family(cf_name, |column_family| {
+            let decisions: Vec<_> = column_family
+                .range::<_, [u8]>((start, end))
+                .filter_map(|(key, value)| match (*compaction_filter)(0, key, value) {
+                    CompactionDecision::Keep => None,
+                    CompactionDecision::Remove => Some((key.clone(), None)),
+                    CompactionDecision::Change(value) => Some((key.clone(), Some(value.to_vec()))),
+                })
+                .collect();
+            for (key, value) in decisions {
+                match value {
+                    Some(value) => column_family.insert(key, value),
+                    None => column_family.remove(key.as_slice()),
+                };
+            }
+        });
+    }
+
+    pub fn cf_handle(&self, _name: &str) -> Option<Arc<BoundColumnFamily<'_>>> {
+        None
+    }
+
+    pub fn has_cf(&self, name: &str) -> bool {
+        self.column_families.read().unwrap().contains_key(name)
+    }
+
+    pub fn create_cf<N: AsRef<str>>(&self, name: N, _opts: &rocksdb::Options) -> Result<(), Error> {
+        self.column_families
+            .write()
+            .unwrap()
+            .entry(name.as_ref().to_string())
+            .or_default();
+        Ok(())
+    }
+
+    /// Drops a column family along with its options, so a column family created again with the
+    /// same name has neither a merge operator nor a compaction filter, as on RocksDB.
+    pub fn drop_cf(&self, name: &str) -> Result<(), Error> {
+        self.column_families.write().unwrap().remove(name);
+        self.options.write().unwrap().remove(name);
+        Ok(())
+    }
+
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, Error> {
+        self.get_cf_opt(
+            rocksdb::DEFAULT_COLUMN_FAMILY_NAME,
+            key,
+            &ReadOptions::default(),
+        )
+    }
+
+    pub fn get_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf_name: &str,
+        key: K,
+        _readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.column_family(cf_name).get(key.as_ref()).cloned())
+    }
+
+    pub fn key_may_exist_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf_name: &str,
+        key: K,
+        _readopts: &ReadOptions,
+    ) -> bool {
+        self.column_family(cf_name).contains_key(key.as_ref())
+    }
+
+    pub fn put_cf_opt<K, V>(
+        &self,
+        cf_name: &str,
+        key: K,
+        value: V,
+        _writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.update_column_family(cf_name, |column_family| {
+            column_family.insert(key.as_ref().to_vec(), value.as_ref().to_vec());
+        });
+        Ok(())
+    }
+
+    pub fn delete_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf_name: &str,
+        key: K,
+        _writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.update_column_family(cf_name, |column_family| {
+            column_family.remove(key.as_ref());
+        });
+        Ok(())
+    }
+
+    /// Applies all the operations of the batch at once: readers either see all of them or none.
+    pub fn write_opt(
+        &self,
+        batch: InMemoryBatch,
+        _writeopts: &WriteOptions,
+    ) -> Result<(), TypedStoreError> {
+        self.write_ops(batch.ops)
+    }
+
+    pub fn raw_iterator_cf_opt(
+        &self,
+        cf_name: &str,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> InMemoryRawIter {
+        InMemoryRawIter::new(self.column_family(cf_name), lower_bound, upper_bound)
+    }
+
+    pub fn iterator_cf_opt(
+        &self,
+        cf_name: &str,
+        _readopts: ReadOptions,
+        mode: IteratorMode<'_>,
+    ) -> InMemoryIter {
+        InMemoryIter::new(self.raw_iterator_cf_opt(cf_name, None, None), mode)
+    }
+
+    pub fn snapshot(&self) -> InMemorySnapshot {
+        InMemorySnapshot {
+            column_families: self.column_families.read().unwrap().clone(),
+        }
+    }
+
+    pub fn property_int_value_cf(
+        &self,
+        _cf_name: &str,
+        _name: impl CStrLike,
+    ) -> Result<Option<u64>, Error> {
+        Ok(None)
+    }
+
+    pub fn compact_range_cf<K: AsRef<[u8]>>(
+        &self,
+        cf_name: &str,
+        start: Option<K>,
+        end: Option<K>,
+    ) {
+        self.compact(
+            cf_name,
+            start.as_ref().map(|start| start.as_ref()),
+            end.as_ref().map(|end| end.as_ref()),
+        )
+    }
+
+    pub fn compact_range_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf_name: &str,
+        start: Option<K>,
+        end: Option<K>,
+        _opts: &CompactOptions,
+    ) {
+        self.compact_range_cf(cf_name, start, end)
+    }
+
+    pub fn flush(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn flush_cf(&self, _cf_name: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn set_options_cf(&self, _cf_name: &str, _opts: &[(&str, &str)]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn try_catch_up_with_primary(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        Path::new("")
+    }
+
+    pub fn live_files(&self) -> Result<Vec<LiveFile>, Error> {
+        Ok(vec![])
+    }
+
+    pub fn cancel_all_background_work(&self, _wait: bool) {}
+}
+
+impl Drop for InMemoryDB {
+    fn drop(&mut self) {
+        DBMetrics::get().decrement_num_active_dbs(&self.metric_conf.db_name);
+    }
+}
+
+/// The contents of all the column families of an `InMemoryDB` at the time the snapshot was taken.
+pub struct InMemorySnapshot {
+    column_families: HashMap<String, ColumnFamily>,
+}
+
+impl InMemorySnapshot {
+    fn column_family(&self, cf_name: &str) -> ColumnFamily {
+        self.column_families
+            .get(cf_name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Column family {cf_name} does not exist"))
+    }
+
+    pub fn get_cf<K: AsRef<[u8]>>(&self, cf_name: &str, key: K) -> Option<Vec<u8>> {
+        self.column_family(cf_name).get(key.as_ref()).cloned()
+    }
+
+    pub fn raw_iterator_cf(
+        &self,
+        cf_name: &str,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> InMemoryRawIter {
+        InMemoryRawIter::new(self.column_family(cf_name), lower_bound, upper_bound)
+    }
+}
+
+enum InMemoryBatchOp {
+    Put(Vec<u8>, Vec<u8>),
+    /// Merges the operand into the value of the key, with the merge operator of the column family.
+    Merge(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+    /// Deletes the keys from the first key (inclusive) to the second one (exclusive).
+    DeleteRange(Vec<u8>, Vec<u8>),
+}
+
+/// Write operations on an `InMemoryDB`, which are applied atomically when the batch is written.
+/// Range deletes are visible as soon as the batch is written, whether or not the reader ignores
+/// range deletions.
+#[derive(Default)]
+pub struct InMemoryBatch {
+    ops: Vec<(String, InMemoryBatchOp)>,
+    size_in_bytes: usize,
+}
+
+impl InMemoryBatch {
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+
+    pub fn put_cf<K, V>(&mut self, cf_name: &str, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let (key, value) = (key.as_ref().to_vec(), value.as_ref().to_vec());
+        self.size_in_bytes += key.len() + value.len();
+        self.ops
+            .push((cf_name.to_string(), InMemoryBatchOp::Put(key, value)));
+    }
+
+    pub fn merge_cf<K, V>(&mut self, cf_name: &str, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let (key, value) = (key.as_ref().to_vec(), value.as_ref().to_vec());
+        self.size_in_bytes += key.len() + value.len();
+        self.ops
+            .push((cf_name.to_string(), InMemoryBatchOp::Merge(key, value)));
+    }
+
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf_name: &str, key: K) {
+        let key = key.as_ref().to_vec();
+        self.size_in_bytes += key.len();
+        self.ops
+            .push((cf_name.to_string(), InMemoryBatchOp::Delete(key)));
+    }
+
+    pub fn delete_range_cf<K: AsRef<[u8]>>(&mut self, cf_name: &str, from: K, to: K) {
+        let (from, to) = (from.as_ref().to_vec(), to.as_ref().to_vec());
+        self.size_in_bytes += from.len() + to.len();
+        self.ops
+            .push((cf_name.to_string(), InMemoryBatchOp::DeleteRange(from, to)));
+    }
+}
+
+/// An iterator over a column family of an `InMemoryDB`, with the same interface as the raw
+/// iterators of RocksDB. It iterates over the contents of the column family at the time it was
+/// created, between an inclusive lower bound and an exclusive upper bound.
+pub struct InMemoryRawIter {
+    column_family: ColumnFamily,
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+    // The key the iterator is positioned at, if it is valid
+    current: Option<Vec<u8>>,
+}
+
+impl InMemoryRawIter {
+    fn new(
+        column_family: ColumnFamily,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            column_family,
+            lower_bound,
+            upper_bound,
+            current: None,
+        }
+    }
+
+    /// Returns the first key within the iterator bounds which is after `start`.
+    fn first_from(&self, start: Bound<&[u8]>) -> Option<Vec<u8>> {
+        let start = match (start, self.lower_bound.as_deref()) {
+            (Bound::Included(key) | Bound::Excluded(key), Some(lower)) if key < lower => {
+                Bound::Included(lower)
+            }
+            (Bound::Unbounded, Some(lower)) => Bound::Included(lower),
+            (start, _) => start,
+        };
+        self.column_family
+            .range::<_, [u8]>((start, Bound::Unbounded))
+            .map(|(key, _)| key)
+            .next()
+            .filter(|key| self.upper_bound.as_ref().map_or(true, |upper| *key < upper))
+            .cloned()
+    }
+
+    /// Returns the last key within the iterator bounds which is before `end`.
+    fn last_before(&self, end: Bound<&[u8]>) -> Option<Vec<u8>> {
+        let end = match (end, self.upper_bound.as_deref()) {
+            (Bound::Included(key) | Bound::Excluded(key), Some(upper)) if key >= upper => {
+                Bound::Excluded(upper)
+            }
+            (Bound::Unbounded, Some(upper)) => Bound::Excluded(upper),
+            (end, _) => end,
+        };
+        self.column_family
+            .range::<_, [u8]>((Bound::Unbounded, end))
+            .map(|(key, _)| key)
+            .next_back()
+            .filter(|key| {
+                self.lower_bound
+                    .as_ref()
+                    .map_or(true, |lower| *key >= lower)
+            })
+            .cloned()
+    }
+
+    pub fn valid(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn key(&self) -> Option<&[u8]> {
+        self.current.as_deref()
+    }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        self.current
+            .as_ref()
+            .and_then(|key| self.column_family.get(key.as_slice()))
+            .map(Vec::as_slice)
+    }
+
+    pub fn next(&mut self) {
+        if let Some(current) = self.current.take() {
+            self.current = self.first_from(Bound::Excluded(&current));
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if let Some(current) = self.current.take() {
+            self.current = self.last_before(Bound::Excluded(&current));
+        }
+    }
+
+    pub fn seek<K: AsRef<[u8]>>(&mut self, key: K) {
+        self.current = self.first_from(Bound::Included(key.as_ref()));
+    }
+
+    pub fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) {
+        self.current = self.last_before(Bound::Included(key.as_ref()));
+    }
+
+    pub fn seek_to_first(&mut self) {
+        self.current = self.first_from(Bound::Unbounded);
+    }
+
+    pub fn seek_to_last(&mut self) {
+        self.current = self.last_before(Bound::Unbounded);
+    }
+
+    pub fn status(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// An iterator over the key-value pairs of a column family of an `InMemoryDB`, with the same
+/// items as the iterators of RocksDB.
+pub struct InMemoryIter {
+    raw: InMemoryRawIter,
+    direction: Direction,
+}
+
+impl InMemoryIter {
+    fn new(mut raw: InMemoryRawIter, mode: IteratorMode<'_>) -> Self {
+        let direction = match mode {
+            IteratorMode::Start => {
+                raw.seek_to_first();
+                Direction::Forward
+            }
+            IteratorMode::End => {
+                raw.seek_to_last();
+                Direction::Reverse
+            }
+            IteratorMode::From(key, Direction::Forward) => {
+                raw.seek(key);
+                Direction::Forward
+            }
+            IteratorMode::From(key, Direction::Reverse) => {
+                raw.seek_for_prev(key);
+                Direction::Reverse
+            }
+        };
+        Self { raw, direction }
+    }
+}
+
+impl Iterator for InMemoryIter {
+    type Item = Result<(Box<[u8]>, Box<[u8]>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key: Box<[u8]> = self.raw.key()?.into();
+        let value: Box<[u8]> = self.raw.value()?.into();
+        match self.direction {
+            Direction::Forward => self.raw.next(),
+            Direction::Reverse => self.raw.prev(),
+        }
+        Some(Ok((key, value)))
+    }
+}
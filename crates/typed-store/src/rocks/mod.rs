@@ -1,13 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 pub mod errors;
+pub(crate) mod in_memory;
 pub(crate) mod iter;
 pub(crate) mod keys;
 pub(crate) mod safe_iter;
 pub mod util;
 pub(crate) mod values;
 
-use self::{iter::Iter, keys::Keys, values::Values};
+pub use self::in_memory::InMemoryOptions;
+use self::in_memory::{InMemoryBatch, InMemoryDB, InMemoryIter, InMemoryRawIter, InMemorySnapshot};
+use self::{iter::Iter, keys::Keys, util::ttl_compaction_filter, values::Values};
 use crate::rocks::errors::typed_store_err_from_bcs_err;
 use crate::rocks::errors::typed_store_err_from_bincode_err;
 use crate::rocks::errors::typed_store_err_from_rocks_err;
@@ -23,15 +26,16 @@ use itertools::Itertools;
 use prometheus::{Histogram, HistogramTimer};
 use rocksdb::{
     checkpoint::Checkpoint, BlockBasedOptions, BottommostLevelCompaction, Cache, CompactOptions,
-    DBPinnableSlice, LiveFile, OptimisticTransactionDB, SnapshotWithThreadMode,
+    CompactionDecision, DBPinnableSlice, LiveFile, MergeOperands, OptimisticTransactionDB,
+    SnapshotWithThreadMode,
 };
 use rocksdb::{
-    properties, AsColumnFamilyRef, CStrLike, ColumnFamilyDescriptor, DBWithThreadMode, Error,
-    ErrorKind, IteratorMode, MultiThreaded, OptimisticTransactionOptions, ReadOptions, Transaction,
-    WriteBatch, WriteBatchWithTransaction, WriteOptions,
+    properties, CStrLike, ColumnFamilyDescriptor, DBWithThreadMode, Error, ErrorKind, IteratorMode,
+    MultiThreaded, OptimisticTransactionOptions, ReadOptions, Transaction, WriteBatch,
+    WriteBatchWithTransaction, WriteOptions,
 };
 use serde::{de::DeserializeOwned, Serialize};
-use std::ops::Bound;
+use std::ops::{Bound, Deref};
 use std::{
     borrow::Borrow,
     collections::BTreeMap,
@@ -256,6 +260,7 @@ impl Drop for OptimisticTransactionDBWrapper {
 pub enum RocksDB {
     DBWithThreadMode(DBWithThreadModeWrapper),
     OptimisticTransactionDB(OptimisticTransactionDBWrapper),
+    InMemory(InMemoryDB),
 }
 
 macro_rules! delegate_call {
@@ -263,6 +268,21 @@ macro_rules! delegate_call {
         match $self {
             Self::DBWithThreadMode(d) => d.underlying.$method($($args),*),
             Self::OptimisticTransactionDB(d) => d.underlying.$method($($args),*),
+            Self::InMemory(d) => d.$method($($args),*),
+        }
+    }
+}
+
+/// Same as `delegate_call`, for methods taking a column family as their first argument: RocksDB
+/// is passed the handle of the column family, and in-memory databases its name.
+macro_rules! delegate_cf_call {
+    ($self:ident.$method:ident($cf_name:ident$(, $args:ident)*)) => {
+        match $self {
+            Self::DBWithThreadMode(d) => d.underlying.$method(&$self.cf($cf_name)$(, $args)*),
+            Self::OptimisticTransactionDB(d) => {
+                d.underlying.$method(&$self.cf($cf_name)$(, $args)*)
+            }
+            Self::InMemory(d) => d.$method($cf_name$(, $args)*),
         }
     }
 }
@@ -278,54 +298,123 @@ impl RocksDB {
         delegate_call!(self.get(key))
     }
 
-    pub fn multi_get_cf<'a, 'b: 'a, K, I, W>(
-        &'a self,
+    pub fn multi_get_cf<K, I>(
+        &self,
+        cf_name: &str,
         keys: I,
         readopts: &ReadOptions,
     ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
-        I: IntoIterator<Item = (&'b W, K)>,
-        W: 'b + AsColumnFamilyRef,
+        I: IntoIterator<Item = K>,
     {
-        delegate_call!(self.multi_get_cf_opt(keys, readopts))
+        match self {
+            Self::DBWithThreadMode(d) => {
+                let cf = self.cf(cf_name);
+                d.underlying
+                    .multi_get_cf_opt(keys.into_iter().map(|key| (&cf, key)), readopts)
+            }
+            Self::OptimisticTransactionDB(d) => {
+                let cf = self.cf(cf_name);
+                d.underlying
+                    .multi_get_cf_opt(keys.into_iter().map(|key| (&cf, key)), readopts)
+            }
+            Self::InMemory(d) => keys
+                .into_iter()
+                .map(|key| d.get_cf_opt(cf_name, key, readopts))
+                .collect(),
+        }
     }
 
     pub fn batched_multi_get_cf_opt<I, K>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         keys: I,
         sorted_input: bool,
         readopts: &ReadOptions,
-    ) -> Vec<Result<Option<DBPinnableSlice<'_>>, Error>>
+    ) -> Vec<Result<Option<RocksDBValue<'_>>, Error>>
     where
         I: IntoIterator<Item = K>,
         K: AsRef<[u8]>,
     {
-        delegate_call!(self.batched_multi_get_cf_opt(cf, keys, sorted_input, readopts))
+        let pinned = match self {
+            Self::DBWithThreadMode(d) => d.underlying.batched_multi_get_cf_opt(
+                &self.cf(cf_name),
+                keys,
+                sorted_input,
+                readopts,
+            ),
+            Self::OptimisticTransactionDB(d) => d.underlying.batched_multi_get_cf_opt(
+                &self.cf(cf_name),
+                keys,
+                sorted_input,
+                readopts,
+            ),
+            Self::InMemory(d) => {
+                return keys
+                    .into_iter()
+                    .map(|key| {
+                        d.get_cf_opt(cf_name, key, readopts)
+                            .map(|value| value.map(RocksDBValue::Owned))
+                    })
+                    .collect()
+            }
+        };
+        pinned
+            .into_iter()
+            .map(|result| result.map(|value| value.map(RocksDBValue::Pinned)))
+            .collect()
     }
 
     pub fn property_int_value_cf(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         name: impl CStrLike,
     ) -> Result<Option<u64>, rocksdb::Error> {
-        delegate_call!(self.property_int_value_cf(cf, name))
+        delegate_cf_call!(self.property_int_value_cf(cf_name, name))
     }
 
     pub fn get_pinned_cf_opt<K: AsRef<[u8]>>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         key: K,
         readopts: &ReadOptions,
-    ) -> Result<Option<DBPinnableSlice<'_>>, rocksdb::Error> {
-        delegate_call!(self.get_pinned_cf_opt(cf, key, readopts))
+    ) -> Result<Option<RocksDBValue<'_>>, rocksdb::Error> {
+        match self {
+            Self::DBWithThreadMode(d) => d
+                .underlying
+                .get_pinned_cf_opt(&self.cf(cf_name), key, readopts)
+                .map(|value| value.map(RocksDBValue::Pinned)),
+            Self::OptimisticTransactionDB(d) => d
+                .underlying
+                .get_pinned_cf_opt(&self.cf(cf_name), key, readopts)
+                .map(|value| value.map(RocksDBValue::Pinned)),
+            Self::InMemory(d) => d
+                .get_cf_opt(cf_name, key, readopts)
+                .map(|value| value.map(RocksDBValue::Owned)),
+        }
     }
 
+    /// Returns the handle of a column family. In-memory databases have no column family handles,
+    /// so this always returns `None` for them.
     pub fn cf_handle(&self, name: &str) -> Option<Arc<rocksdb::BoundColumnFamily<'_>>> {
         delegate_call!(self.cf_handle(name))
     }
 
+    fn cf(&self, name: &str) -> Arc<rocksdb::BoundColumnFamily<'_>> {
+        self.cf_handle(name)
+            .expect("Map-keying column family should have been checked at DB creation")
+    }
+
+    pub fn has_cf(&self, name: &str) -> bool {
+        match self {
+            Self::DBWithThreadMode(_) | Self::OptimisticTransactionDB(_) => {
+                self.cf_handle(name).is_some()
+            }
+            Self::InMemory(d) => d.has_cf(name),
+        }
+    }
+
     pub fn create_cf<N: AsRef<str>>(
         &self,
         name: N,
@@ -340,12 +429,12 @@ impl RocksDB {
 
     pub fn delete_cf<K: AsRef<[u8]>>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         key: K,
         writeopts: &WriteOptions,
     ) -> Result<(), rocksdb::Error> {
         fail_point!("delete-cf-before");
-        let ret = delegate_call!(self.delete_cf_opt(cf, key, writeopts));
+        let ret = delegate_cf_call!(self.delete_cf_opt(cf_name, key, writeopts));
         fail_point!("delete-cf-after");
         #[allow(clippy::let_and_return)]
         ret
@@ -357,7 +446,7 @@ impl RocksDB {
 
     pub fn put_cf<K, V>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         key: K,
         value: V,
         writeopts: &WriteOptions,
@@ -367,7 +456,7 @@ impl RocksDB {
         V: AsRef<[u8]>,
     {
         fail_point!("put-cf-before");
-        let ret = delegate_call!(self.put_cf_opt(cf, key, value, writeopts));
+        let ret = delegate_cf_call!(self.put_cf_opt(cf_name, key, value, writeopts));
         fail_point!("put-cf-after");
         #[allow(clippy::let_and_return)]
         ret
@@ -375,11 +464,11 @@ impl RocksDB {
 
     pub fn key_may_exist_cf<K: AsRef<[u8]>>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         key: K,
         readopts: &ReadOptions,
     ) -> bool {
-        delegate_call!(self.key_may_exist_cf_opt(cf, key, readopts))
+        delegate_cf_call!(self.key_may_exist_cf_opt(cf_name, key, readopts))
     }
 
     pub fn try_catch_up_with_primary(&self) -> Result<(), rocksdb::Error> {
@@ -405,6 +494,9 @@ impl RocksDB {
                     .map_err(typed_store_err_from_rocks_err)?;
                 Ok(())
             }
+            (RocksDB::InMemory(db), RocksDBBatch::InMemory(batch)) => {
+                db.write_opt(batch, writeopts)
+            }
             _ => Err(TypedStoreError::RocksDBError(
                 "using invalid batch type for the database".to_string(),
             )),
@@ -420,6 +512,9 @@ impl RocksDB {
         match self {
             Self::OptimisticTransactionDB(db) => Ok(db.underlying.transaction()),
             Self::DBWithThreadMode(_) => panic!(),
+            Self::InMemory(_) => Err(TypedStoreError::RocksDBError(
+                "in-memory databases do not support transactions".to_string(),
+            )),
         }
     }
 
@@ -436,58 +531,78 @@ impl RocksDB {
                     .transaction_opt(&WriteOptions::default(), &tx_opts))
             }
             Self::DBWithThreadMode(_) => panic!(),
+            Self::InMemory(_) => Err(TypedStoreError::RocksDBError(
+                "in-memory databases do not support transactions".to_string(),
+            )),
         }
     }
 
+    /// Returns a raw iterator over a column family. The iterator only visits the keys from
+    /// `lower_bound` (inclusive) to `upper_bound` (exclusive).
     pub fn raw_iterator_cf<'a: 'b, 'b>(
         &'a self,
-        cf_handle: &impl AsColumnFamilyRef,
+        cf_name: &str,
         readopts: ReadOptions,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
     ) -> RocksDBRawIter<'b> {
         match self {
-            Self::DBWithThreadMode(db) => {
-                RocksDBRawIter::DB(db.underlying.raw_iterator_cf_opt(cf_handle, readopts))
+            Self::DBWithThreadMode(db) => RocksDBRawIter::DB(db.underlying.raw_iterator_cf_opt(
+                &self.cf(cf_name),
+                with_iterate_bounds(readopts, lower_bound, upper_bound),
+            )),
+            Self::OptimisticTransactionDB(db) => {
+                RocksDBRawIter::OptimisticTransactionDB(db.underlying.raw_iterator_cf_opt(
+                    &self.cf(cf_name),
+                    with_iterate_bounds(readopts, lower_bound, upper_bound),
+                ))
+            }
+            Self::InMemory(db) => {
+                RocksDBRawIter::InMemory(db.raw_iterator_cf_opt(cf_name, lower_bound, upper_bound))
             }
-            Self::OptimisticTransactionDB(db) => RocksDBRawIter::OptimisticTransactionDB(
-                db.underlying.raw_iterator_cf_opt(cf_handle, readopts),
-            ),
         }
     }
 
     pub fn iterator_cf<'a: 'b, 'b>(
         &'a self,
-        cf_handle: &impl AsColumnFamilyRef,
+        cf_name: &str,
         readopts: ReadOptions,
         mode: IteratorMode<'_>,
     ) -> RocksDBIter<'b> {
         match self {
-            Self::DBWithThreadMode(db) => {
-                RocksDBIter::DB(db.underlying.iterator_cf_opt(cf_handle, readopts, mode))
-            }
+            Self::DBWithThreadMode(db) => RocksDBIter::DB(db.underlying.iterator_cf_opt(
+                &self.cf(cf_name),
+                readopts,
+                mode,
+            )),
             Self::OptimisticTransactionDB(db) => RocksDBIter::OptimisticTransactionDB(
-                db.underlying.iterator_cf_opt(cf_handle, readopts, mode),
+                db.underlying
+                    .iterator_cf_opt(&self.cf(cf_name), readopts, mode),
             ),
+            Self::InMemory(db) => {
+                RocksDBIter::InMemory(db.iterator_cf_opt(cf_name, readopts, mode))
+            }
         }
     }
 
     pub fn compact_range_cf<K: AsRef<[u8]>>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         start: Option<K>,
         end: Option<K>,
     ) {
-        delegate_call!(self.compact_range_cf(cf, start, end))
+        delegate_cf_call!(self.compact_range_cf(cf_name, start, end))
     }
 
     pub fn compact_range_to_bottom<K: AsRef<[u8]>>(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         start: Option<K>,
         end: Option<K>,
     ) {
         let opt = &mut CompactOptions::default();
         opt.set_bottommost_level_compaction(BottommostLevelCompaction::ForceOptimized);
-        delegate_call!(self.compact_range_cf_opt(cf, start, end, opt))
+        delegate_cf_call!(self.compact_range_cf_opt(cf_name, start, end, opt))
     }
 
     pub fn flush(&self) -> Result<(), TypedStoreError> {
@@ -500,6 +615,7 @@ impl RocksDB {
             Self::OptimisticTransactionDB(d) => {
                 RocksDBSnapshot::OptimisticTransactionDB(d.underlying.snapshot())
             }
+            Self::InMemory(d) => RocksDBSnapshot::InMemory(d.snapshot()),
         }
     }
 
@@ -511,6 +627,11 @@ impl RocksDB {
             Self::OptimisticTransactionDB(d) => {
                 Checkpoint::new(&d.underlying).map_err(typed_store_err_from_rocks_err)?
             }
+            Self::InMemory(_) => {
+                return Err(TypedStoreError::RocksDBError(
+                    "in-memory databases cannot be checkpointed".to_string(),
+                ))
+            }
         };
         checkpoint
             .create_checkpoint(path)
@@ -518,51 +639,44 @@ impl RocksDB {
         Ok(())
     }
 
-    pub fn flush_cf(&self, cf: &impl AsColumnFamilyRef) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.flush_cf(cf))
+    pub fn flush_cf(&self, cf_name: &str) -> Result<(), rocksdb::Error> {
+        delegate_cf_call!(self.flush_cf(cf_name))
     }
 
     pub fn set_options_cf(
         &self,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         opts: &[(&str, &str)],
     ) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.set_options_cf(cf, opts))
+        delegate_cf_call!(self.set_options_cf(cf_name, opts))
     }
 
-    pub fn get_sampling_interval(&self) -> SamplingInterval {
+    fn metric_conf(&self) -> &MetricConf {
         match self {
-            Self::DBWithThreadMode(d) => d.metric_conf.read_sample_interval.new_from_self(),
-            Self::OptimisticTransactionDB(d) => d.metric_conf.read_sample_interval.new_from_self(),
+            Self::DBWithThreadMode(d) => &d.metric_conf,
+            Self::OptimisticTransactionDB(d) => &d.metric_conf,
+            Self::InMemory(d) => &d.metric_conf,
         }
     }
 
+    pub fn get_sampling_interval(&self) -> SamplingInterval {
+        self.metric_conf().read_sample_interval.new_from_self()
+    }
+
     pub fn multiget_sampling_interval(&self) -> SamplingInterval {
-        match self {
-            Self::DBWithThreadMode(d) => d.metric_conf.read_sample_interval.new_from_self(),
-            Self::OptimisticTransactionDB(d) => d.metric_conf.read_sample_interval.new_from_self(),
-        }
+        self.metric_conf().read_sample_interval.new_from_self()
     }
 
     pub fn write_sampling_interval(&self) -> SamplingInterval {
-        match self {
-            Self::DBWithThreadMode(d) => d.metric_conf.write_sample_interval.new_from_self(),
-            Self::OptimisticTransactionDB(d) => d.metric_conf.write_sample_interval.new_from_self(),
-        }
+        self.metric_conf().write_sample_interval.new_from_self()
     }
 
     pub fn iter_sampling_interval(&self) -> SamplingInterval {
-        match self {
-            Self::DBWithThreadMode(d) => d.metric_conf.iter_sample_interval.new_from_self(),
-            Self::OptimisticTransactionDB(d) => d.metric_conf.iter_sample_interval.new_from_self(),
-        }
+        self.metric_conf().iter_sample_interval.new_from_self()
     }
 
     pub fn db_name(&self) -> String {
-        let name = match self {
-            Self::DBWithThreadMode(d) => &d.metric_conf.db_name,
-            Self::OptimisticTransactionDB(d) => &d.metric_conf.db_name,
-        };
+        let name = &self.metric_conf().db_name;
         if name.is_empty() {
             self.default_db_name()
         } else {
@@ -583,46 +697,140 @@ impl RocksDB {
     }
 }
 
+/// Sets the bounds of the keys visited by iterators created with `readopts`, from `lower_bound`
+/// (inclusive) to `upper_bound` (exclusive).
+fn with_iterate_bounds(
+    mut readopts: ReadOptions,
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+) -> ReadOptions {
+    if let Some(lower_bound) = lower_bound {
+        readopts.set_iterate_lower_bound(lower_bound);
+    }
+    if let Some(upper_bound) = upper_bound {
+        readopts.set_iterate_upper_bound(upper_bound);
+    }
+    readopts
+}
+
+/// A value read from a `RocksDB`, either pinned by RocksDB or copied out of an in-memory database.
+pub enum RocksDBValue<'a> {
+    Pinned(DBPinnableSlice<'a>),
+    Owned(Vec<u8>),
+}
+
+impl Deref for RocksDBValue<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Pinned(value) => value,
+            Self::Owned(value) => value,
+        }
+    }
+}
+
 pub enum RocksDBSnapshot<'a> {
     DBWithThreadMode(rocksdb::Snapshot<'a>),
     OptimisticTransactionDB(SnapshotWithThreadMode<'a, OptimisticTransactionDB>),
+    InMemory(InMemorySnapshot),
 }
 
 impl<'a> RocksDBSnapshot<'a> {
-    pub fn multi_get_cf_opt<'b: 'a, K, I, W>(
-        &'a self,
+    /// Reads a key of a column family of `db` as it was when this snapshot was taken. The
+    /// snapshot must have been taken from `db`.
+    fn get_pinned_cf_opt<'b, K: AsRef<[u8]>>(
+        &self,
+        db: &'b RocksDB,
+        cf_name: &str,
+        key: K,
+        mut readopts: ReadOptions,
+    ) -> Result<Option<RocksDBValue<'b>>, rocksdb::Error> {
+        match self {
+            Self::DBWithThreadMode(s) => {
+                readopts.set_snapshot(s);
+                db.get_pinned_cf_opt(cf_name, key, &readopts)
+            }
+            Self::OptimisticTransactionDB(s) => {
+                readopts.set_snapshot(s);
+                db.get_pinned_cf_opt(cf_name, key, &readopts)
+            }
+            Self::InMemory(s) => Ok(s.get_cf(cf_name, key).map(RocksDBValue::Owned)),
+        }
+    }
+
+    /// Same as `RocksDB::raw_iterator_cf`, over the column family as it was when this snapshot
+    /// was taken. The snapshot must have been taken from `db`.
+    fn raw_iterator_cf<'b>(
+        &self,
+        db: &'b RocksDB,
+        cf_name: &str,
+        mut readopts: ReadOptions,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> RocksDBRawIter<'b> {
+        match self {
+            Self::DBWithThreadMode(s) => {
+                readopts.set_snapshot(s);
+                db.raw_iterator_cf(cf_name, readopts, lower_bound, upper_bound)
+            }
+            Self::OptimisticTransactionDB(s) => {
+                readopts.set_snapshot(s);
+                db.raw_iterator_cf(cf_name, readopts, lower_bound, upper_bound)
+            }
+            Self::InMemory(s) => {
+                RocksDBRawIter::InMemory(s.raw_iterator_cf(cf_name, lower_bound, upper_bound))
+            }
+        }
+    }
+
+    /// Reads keys of a column family of `db` as they were when this snapshot was taken. The
+    /// snapshot must have been taken from `db`.
+    pub fn multi_get_cf_opt<K, I>(
+        &self,
+        db: &RocksDB,
+        cf_name: &str,
         keys: I,
         readopts: ReadOptions,
     ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
-        I: IntoIterator<Item = (&'b W, K)>,
-        W: 'b + AsColumnFamilyRef,
+        I: IntoIterator<Item = K>,
     {
         match self {
-            Self::DBWithThreadMode(s) => s.multi_get_cf_opt(keys, readopts),
-            Self::OptimisticTransactionDB(s) => s.multi_get_cf_opt(keys, readopts),
+            Self::DBWithThreadMode(s) => {
+                let cf = db.cf(cf_name);
+                s.multi_get_cf_opt(keys.into_iter().map(|key| (&cf, key)), readopts)
+            }
+            Self::OptimisticTransactionDB(s) => {
+                let cf = db.cf(cf_name);
+                s.multi_get_cf_opt(keys.into_iter().map(|key| (&cf, key)), readopts)
+            }
+            Self::InMemory(s) => keys
+                .into_iter()
+                .map(|key| Ok(s.get_cf(cf_name, key)))
+                .collect(),
         }
     }
-    pub fn multi_get_cf<'b: 'a, K, I, W>(
-        &'a self,
+
+    pub fn multi_get_cf<K, I>(
+        &self,
+        db: &RocksDB,
+        cf_name: &str,
         keys: I,
     ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
-        I: IntoIterator<Item = (&'b W, K)>,
-        W: 'b + AsColumnFamilyRef,
+        I: IntoIterator<Item = K>,
     {
-        match self {
-            Self::DBWithThreadMode(s) => s.multi_get_cf(keys),
-            Self::OptimisticTransactionDB(s) => s.multi_get_cf(keys),
-        }
+        self.multi_get_cf_opt(db, cf_name, keys, ReadOptions::default())
     }
 }
 
 pub enum RocksDBBatch {
     Regular(rocksdb::WriteBatch),
     Transactional(rocksdb::WriteBatchWithTransaction<true>),
+    InMemory(InMemoryBatch),
 }
 
 macro_rules! delegate_batch_call {
@@ -630,6 +838,19 @@ macro_rules! delegate_batch_call {
         match $self {
             Self::Regular(b) => b.$method($($args),*),
             Self::Transactional(b) => b.$method($($args),*),
+            Self::InMemory(b) => b.$method($($args),*),
+        }
+    }
+}
+
+/// Same as `delegate_batch_call`, for methods writing to a column family of `db`: RocksDB batches
+/// are passed the handle of the column family, and in-memory ones its name.
+macro_rules! delegate_batch_cf_call {
+    ($self:ident.$method:ident($db:ident, $cf_name:ident$(, $args:ident)*)) => {
+        match $self {
+            Self::Regular(b) => b.$method(&$db.cf($cf_name)$(, $args)*),
+            Self::Transactional(b) => b.$method(&$db.cf($cf_name)$(, $args)*),
+            Self::InMemory(b) => b.$method($cf_name$(, $args)*),
         }
     }
 }
@@ -639,38 +860,43 @@ impl RocksDBBatch {
         delegate_batch_call!(self.size_in_bytes())
     }
 
-    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
-        delegate_batch_call!(self.delete_cf(cf, key))
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, db: &RocksDB, cf_name: &str, key: K) {
+        delegate_batch_cf_call!(self.delete_cf(db, cf_name, key))
     }
 
-    pub fn put_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    pub fn put_cf<K, V>(&mut self, db: &RocksDB, cf_name: &str, key: K, value: V)
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        delegate_batch_call!(self.put_cf(cf, key, value))
+        delegate_batch_cf_call!(self.put_cf(db, cf_name, key, value))
     }
 
-    pub fn merge_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    pub fn merge_cf<K, V>(&mut self, db: &RocksDB, cf_name: &str, key: K, value: V)
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        delegate_batch_call!(self.merge_cf(cf, key, value))
+        delegate_batch_cf_call!(self.merge_cf(db, cf_name, key, value))
     }
 
     pub fn delete_range_cf<K: AsRef<[u8]>>(
         &mut self,
-        cf: &impl AsColumnFamilyRef,
+        db: &RocksDB,
+        cf_name: &str,
         from: K,
         to: K,
     ) -> Result<(), TypedStoreError> {
         match self {
             Self::Regular(batch) => {
-                batch.delete_range_cf(cf, from, to);
+                batch.delete_range_cf(&db.cf(cf_name), from, to);
                 Ok(())
             }
             Self::Transactional(_) => panic!(),
+            Self::InMemory(batch) => {
+                batch.delete_range_cf(cf_name, from, to);
+                Ok(())
+            }
         }
     }
 }
@@ -815,8 +1041,9 @@ impl<K, V> DBMap<K, V> {
             .unwrap_or(rocksdb::DEFAULT_COLUMN_FAMILY_NAME)
             .to_owned();
 
-        db.cf_handle(&cf_key)
-            .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf_key.clone()))?;
+        if !db.has_cf(&cf_key) {
+            return Err(TypedStoreError::UnregisteredColumn(cf_key));
+        }
 
         Ok(DBMap::new(db.clone(), rw_options, &cf_key))
     }
@@ -827,6 +1054,7 @@ impl<K, V> DBMap<K, V> {
             RocksDB::OptimisticTransactionDB(_) => {
                 RocksDBBatch::Transactional(WriteBatchWithTransaction::<true>::default())
             }
+            RocksDB::InMemory(_) => RocksDBBatch::InMemory(InMemoryBatch::default()),
         };
         DBBatch::new(
             &self.rocksdb,
@@ -841,7 +1069,7 @@ impl<K, V> DBMap<K, V> {
         let from_buf = be_fix_int_ser(start)?;
         let to_buf = be_fix_int_ser(end)?;
         self.rocksdb
-            .compact_range_cf(&self.cf(), Some(from_buf), Some(to_buf));
+            .compact_range_cf(&self.cf, Some(from_buf), Some(to_buf));
         Ok(())
     }
 
@@ -851,11 +1079,12 @@ impl<K, V> DBMap<K, V> {
         start: Vec<u8>,
         end: Vec<u8>,
     ) -> Result<(), TypedStoreError> {
-        let cf = self
-            .rocksdb
-            .cf_handle(cf_name)
-            .expect("compact range: column family does not exist");
-        self.rocksdb.compact_range_cf(&cf, Some(start), Some(end));
+        assert!(
+            self.rocksdb.has_cf(cf_name),
+            "compact range: column family does not exist"
+        );
+        self.rocksdb
+            .compact_range_cf(cf_name, Some(start), Some(end));
         Ok(())
     }
 
@@ -867,37 +1096,50 @@ impl<K, V> DBMap<K, V> {
         let from_buf = be_fix_int_ser(start)?;
         let to_buf = be_fix_int_ser(end)?;
         self.rocksdb
-            .compact_range_to_bottom(&self.cf(), Some(from_buf), Some(to_buf));
+            .compact_range_to_bottom(&self.cf, Some(from_buf), Some(to_buf));
         Ok(())
     }
 
-    pub fn cf(&self) -> Arc<rocksdb::BoundColumnFamily<'_>> {
-        self.rocksdb
-            .cf_handle(&self.cf)
-            .expect("Map-keying column family should have been checked at DB creation")
+    pub(crate) fn cf_name(&self) -> &str {
+        &self.cf
+    }
+
+    /// Returns the RocksDB handle of the column family of this map. Maps opened on an in-memory
+    /// database have no column family handle, so this returns an error for them.
+    pub fn cf(&self) -> Result<Arc<rocksdb::BoundColumnFamily<'_>>, TypedStoreError> {
+        match *self.rocksdb {
+            RocksDB::DBWithThreadMode(_) | RocksDB::OptimisticTransactionDB(_) => Ok(self
+                .rocksdb
+                .cf_handle(&self.cf)
+                .expect("Map-keying column family should have been checked at DB creation")),
+            RocksDB::InMemory(_) => Err(TypedStoreError::RocksDBError(format!(
+                "column family {} of an in-memory database has no handle",
+                self.cf
+            ))),
+        }
     }
 
     pub fn iterator_cf(&self) -> RocksDBIter<'_> {
         self.rocksdb
-            .iterator_cf(&self.cf(), self.opts.readopts(), IteratorMode::Start)
+            .iterator_cf(&self.cf, self.opts.readopts(), IteratorMode::Start)
     }
 
     pub fn flush(&self) -> Result<(), TypedStoreError> {
         self.rocksdb
-            .flush_cf(&self.cf())
+            .flush_cf(&self.cf)
             .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
     }
 
     pub fn set_options(&self, opts: &[(&str, &str)]) -> Result<(), rocksdb::Error> {
-        self.rocksdb.set_options_cf(&self.cf(), opts)
+        self.rocksdb.set_options_cf(&self.cf, opts)
     }
 
     fn get_int_property(
         rocksdb: &RocksDB,
-        cf: &impl AsColumnFamilyRef,
+        cf_name: &str,
         property_name: &'static std::ffi::CStr,
     ) -> Result<i64, TypedStoreError> {
-        match rocksdb.property_int_value_cf(cf, property_name) {
+        match rocksdb.property_int_value_cf(cf_name, property_name) {
             Ok(Some(value)) => Ok(value.try_into().unwrap()),
             Ok(None) => Ok(0),
             Err(e) => Err(TypedStoreError::RocksDBError(e.into_string())),
@@ -908,7 +1150,7 @@ impl<K, V> DBMap<K, V> {
     fn multi_get_pinned<J>(
         &self,
         keys: impl IntoIterator<Item = J>,
-    ) -> Result<Vec<Option<DBPinnableSlice<'_>>>, TypedStoreError>
+    ) -> Result<Vec<Option<RocksDBValue<'_>>>, TypedStoreError>
     where
         J: Borrow<K>,
         K: Serialize,
@@ -931,7 +1173,7 @@ impl<K, V> DBMap<K, V> {
         let results: Result<Vec<_>, TypedStoreError> = self
             .rocksdb
             .batched_multi_get_cf_opt(
-                &self.cf(),
+                &self.cf,
                 keys_bytes?,
                 /*sorted_keys=*/ false,
                 &self.opts.readopts(),
@@ -959,13 +1201,12 @@ impl<K, V> DBMap<K, V> {
     }
 
     fn report_metrics(rocksdb: &Arc<RocksDB>, cf_name: &str, db_metrics: &Arc<DBMetrics>) {
-        let cf = rocksdb.cf_handle(cf_name).expect("Failed to get cf");
         db_metrics
             .cf_metrics
             .rocksdb_total_sst_files_size
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::TOTAL_SST_FILES_SIZE)
+                Self::get_int_property(rocksdb, cf_name, properties::TOTAL_SST_FILES_SIZE)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -973,7 +1214,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_total_blob_files_size
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, ROCKSDB_PROPERTY_TOTAL_BLOB_FILES_SIZE)
+                Self::get_int_property(rocksdb, cf_name, ROCKSDB_PROPERTY_TOTAL_BLOB_FILES_SIZE)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -981,7 +1222,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_size_all_mem_tables
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::SIZE_ALL_MEM_TABLES)
+                Self::get_int_property(rocksdb, cf_name, properties::SIZE_ALL_MEM_TABLES)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -989,7 +1230,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_num_snapshots
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::NUM_SNAPSHOTS)
+                Self::get_int_property(rocksdb, cf_name, properties::NUM_SNAPSHOTS)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -997,7 +1238,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_oldest_snapshot_time
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::OLDEST_SNAPSHOT_TIME)
+                Self::get_int_property(rocksdb, cf_name, properties::OLDEST_SNAPSHOT_TIME)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1005,7 +1246,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_actual_delayed_write_rate
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::ACTUAL_DELAYED_WRITE_RATE)
+                Self::get_int_property(rocksdb, cf_name, properties::ACTUAL_DELAYED_WRITE_RATE)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1013,7 +1254,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_is_write_stopped
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::IS_WRITE_STOPPED)
+                Self::get_int_property(rocksdb, cf_name, properties::IS_WRITE_STOPPED)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1021,7 +1262,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_block_cache_capacity
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::BLOCK_CACHE_CAPACITY)
+                Self::get_int_property(rocksdb, cf_name, properties::BLOCK_CACHE_CAPACITY)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1029,7 +1270,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_block_cache_usage
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::BLOCK_CACHE_USAGE)
+                Self::get_int_property(rocksdb, cf_name, properties::BLOCK_CACHE_USAGE)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1037,7 +1278,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_block_cache_pinned_usage
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::BLOCK_CACHE_PINNED_USAGE)
+                Self::get_int_property(rocksdb, cf_name, properties::BLOCK_CACHE_PINNED_USAGE)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1045,7 +1286,7 @@ impl<K, V> DBMap<K, V> {
             .rocskdb_estimate_table_readers_mem
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::ESTIMATE_TABLE_READERS_MEM)
+                Self::get_int_property(rocksdb, cf_name, properties::ESTIMATE_TABLE_READERS_MEM)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1053,7 +1294,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_estimated_num_keys
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::ESTIMATE_NUM_KEYS)
+                Self::get_int_property(rocksdb, cf_name, properties::ESTIMATE_NUM_KEYS)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1061,7 +1302,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_mem_table_flush_pending
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::MEM_TABLE_FLUSH_PENDING)
+                Self::get_int_property(rocksdb, cf_name, properties::MEM_TABLE_FLUSH_PENDING)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1069,7 +1310,7 @@ impl<K, V> DBMap<K, V> {
             .rocskdb_compaction_pending
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::COMPACTION_PENDING)
+                Self::get_int_property(rocksdb, cf_name, properties::COMPACTION_PENDING)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1077,7 +1318,7 @@ impl<K, V> DBMap<K, V> {
             .rocskdb_num_running_compactions
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::NUM_RUNNING_COMPACTIONS)
+                Self::get_int_property(rocksdb, cf_name, properties::NUM_RUNNING_COMPACTIONS)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1085,7 +1326,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_num_running_flushes
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::NUM_RUNNING_FLUSHES)
+                Self::get_int_property(rocksdb, cf_name, properties::NUM_RUNNING_FLUSHES)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1093,7 +1334,7 @@ impl<K, V> DBMap<K, V> {
             .rocksdb_estimate_oldest_key_time
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::ESTIMATE_OLDEST_KEY_TIME)
+                Self::get_int_property(rocksdb, cf_name, properties::ESTIMATE_OLDEST_KEY_TIME)
                     .unwrap_or(METRICS_ERROR),
             );
         db_metrics
@@ -1101,7 +1342,7 @@ impl<K, V> DBMap<K, V> {
             .rocskdb_background_errors
             .with_label_values(&[cf_name])
             .set(
-                Self::get_int_property(rocksdb, &cf, properties::BACKGROUND_ERRORS)
+                Self::get_int_property(rocksdb, cf_name, properties::BACKGROUND_ERRORS)
                     .unwrap_or(METRICS_ERROR),
             );
     }
@@ -1118,10 +1359,67 @@ impl<K, V> DBMap<K, V> {
         self.rocksdb.checkpoint(path)
     }
 
+    /// Takes a point-in-time snapshot of the underlying database. The snapshot can be used with
+    /// `get_at_snapshot` and `safe_iter_at_snapshot` on every map opened on the same database,
+    /// which gives consistent reads across several tables.
     pub fn snapshot(&self) -> Result<RocksDBSnapshot<'_>, TypedStoreError> {
         Ok(self.rocksdb.snapshot())
     }
 
+    /// Returns the value for the given key as it was when `snapshot` was taken.
+    /// The snapshot must have been taken from the database this map belongs to.
+    pub fn get_at_snapshot(
+        &self,
+        snapshot: &RocksDBSnapshot<'_>,
+        key: &K,
+    ) -> Result<Option<V>, TypedStoreError>
+    where
+        K: Serialize,
+        V: DeserializeOwned,
+    {
+        let key_buf = be_fix_int_ser(key)?;
+        let res = snapshot
+            .get_pinned_cf_opt(&self.rocksdb, &self.cf, &key_buf, self.opts.readopts())
+            .map_err(typed_store_err_from_rocks_err)?;
+        match res {
+            Some(data) => Ok(Some(
+                bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Same as `safe_iter_with_bounds`, but iterates over the map as it was when `snapshot`
+    /// was taken. The snapshot must have been taken from the database this map belongs to.
+    pub fn safe_iter_at_snapshot<'b>(
+        &'b self,
+        snapshot: &'b RocksDBSnapshot<'b>,
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> SafeIter<'b, K, V>
+    where
+        K: Serialize,
+    {
+        let (lower_bound, upper_bound) = Self::iterate_bounds(lower_bound, upper_bound);
+        let db_iter = snapshot.raw_iterator_cf(
+            &self.rocksdb,
+            &self.cf,
+            self.opts.readopts(),
+            lower_bound,
+            upper_bound,
+        );
+        let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
+        SafeIter::new(
+            self.cf.clone(),
+            db_iter,
+            _timer,
+            _perf_ctx,
+            bytes_scanned,
+            keys_scanned,
+            Some(self.db_metrics.clone()),
+        )
+    }
+
     pub fn table_summary(&self) -> eyre::Result<TableSummary> {
         let mut num_keys = 0;
         let mut key_bytes_total = 0;
@@ -1183,43 +1481,31 @@ impl<K, V> DBMap<K, V> {
         )
     }
 
-    // Creates a RocksDB read option with specified lower and upper bounds.
+    // Serializes the specified lower and upper bounds of an iteration.
     /// Lower bound is inclusive, and upper bound is exclusive.
-    fn create_read_options_with_bounds(
-        &self,
+    fn iterate_bounds(
         lower_bound: Option<K>,
         upper_bound: Option<K>,
-    ) -> ReadOptions
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>)
     where
         K: Serialize,
     {
-        let mut readopts = self.opts.readopts();
-        if let Some(lower_bound) = lower_bound {
-            let key_buf = be_fix_int_ser(&lower_bound).unwrap();
-            readopts.set_iterate_lower_bound(key_buf);
-        }
-        if let Some(upper_bound) = upper_bound {
-            let key_buf = be_fix_int_ser(&upper_bound).unwrap();
-            readopts.set_iterate_upper_bound(key_buf);
-        }
-        readopts
+        (
+            lower_bound.map(|lower_bound| be_fix_int_ser(&lower_bound).unwrap()),
+            upper_bound.map(|upper_bound| be_fix_int_ser(&upper_bound).unwrap()),
+        )
     }
 
-    // Creates a RocksDB read option with lower and upper bounds set corresponding to `range`.
-    fn create_read_options_with_range(&self, range: impl RangeBounds<K>) -> ReadOptions
+    // Serializes the bounds of an iteration over `range`, as an inclusive lower bound and an
+    // exclusive upper bound.
+    fn iterate_bounds_of_range(range: impl RangeBounds<K>) -> (Option<Vec<u8>>, Option<Vec<u8>>)
     where
         K: Serialize,
     {
-        let mut readopts = self.opts.readopts();
-
-        let lower_bound = range.start_bound();
-        let upper_bound = range.end_bound();
-
-        match lower_bound {
+        let lower_bound = match range.start_bound() {
             Bound::Included(lower_bound) => {
                 // Rocksdb lower bound is inclusive by default so nothing to do
-                let key_buf = be_fix_int_ser(&lower_bound).expect("Serialization must not fail");
-                readopts.set_iterate_lower_bound(key_buf);
+                Some(be_fix_int_ser(&lower_bound).expect("Serialization must not fail"))
             }
             Bound::Excluded(lower_bound) => {
                 let mut key_buf =
@@ -1227,32 +1513,33 @@ impl<K, V> DBMap<K, V> {
 
                 // Since we want exclusive, we need to increment the key to exclude the previous
                 big_endian_saturating_add_one(&mut key_buf);
-                readopts.set_iterate_lower_bound(key_buf);
+                Some(key_buf)
             }
-            Bound::Unbounded => (),
+            Bound::Unbounded => None,
         };
 
-        match upper_bound {
+        let upper_bound = match range.end_bound() {
             Bound::Included(upper_bound) => {
                 let mut key_buf =
                     be_fix_int_ser(&upper_bound).expect("Serialization must not fail");
 
                 // If the key is already at the limit, there's nowhere else to go, so no upper bound
-                if !is_max(&key_buf) {
+                if is_max(&key_buf) {
+                    None
+                } else {
                     // Since we want exclusive, we need to increment the key to get the upper bound
                     big_endian_saturating_add_one(&mut key_buf);
-                    readopts.set_iterate_upper_bound(key_buf);
+                    Some(key_buf)
                 }
             }
             Bound::Excluded(upper_bound) => {
                 // Rocksdb upper bound is inclusive by default so nothing to do
-                let key_buf = be_fix_int_ser(&upper_bound).expect("Serialization must not fail");
-                readopts.set_iterate_upper_bound(key_buf);
+                Some(be_fix_int_ser(&upper_bound).expect("Serialization must not fail"))
             }
-            Bound::Unbounded => (),
+            Bound::Unbounded => None,
         };
 
-        readopts
+        (lower_bound, upper_bound)
     }
 }
 
@@ -1383,7 +1670,7 @@ impl DBBatch {
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|k| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
-                self.batch.delete_cf(&db.cf(), k_buf);
+                self.batch.delete_cf(&self.rocksdb, &db.cf, k_buf);
 
                 Ok(())
             })?;
@@ -1412,7 +1699,35 @@ impl DBBatch {
         let from_buf = be_fix_int_ser(from)?;
         let to_buf = be_fix_int_ser(to)?;
 
-        self.batch.delete_range_cf(&db.cf(), from_buf, to_buf)?;
+        self.batch
+            .delete_range_cf(&self.rocksdb, &db.cf, from_buf, to_buf)?;
+        Ok(())
+    }
+
+    /// Inserts an already serialized (key, value) pair, used by sally to flush its memtable
+    pub(crate) fn insert_raw<K, V>(
+        &mut self,
+        db: &DBMap<K, V>,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        self.batch.put_cf(&self.rocksdb, &db.cf, key, value);
+        Ok(())
+    }
+
+    /// Deletes an already serialized key, used by sally to flush its memtable
+    pub(crate) fn delete_raw<K, V>(
+        &mut self,
+        db: &DBMap<K, V>,
+        key: &[u8],
+    ) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        self.batch.delete_cf(&self.rocksdb, &db.cf, key);
         Ok(())
     }
 
@@ -1431,7 +1746,7 @@ impl DBBatch {
             .try_for_each::<_, Result<_, TypedStoreError>>(|(k, v)| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
-                self.batch.put_cf(&db.cf(), k_buf, v_buf);
+                self.batch.put_cf(&self.rocksdb, &db.cf, k_buf, v_buf);
                 Ok(())
             })?;
         Ok(self)
@@ -1452,7 +1767,7 @@ impl DBBatch {
             .try_for_each::<_, Result<_, TypedStoreError>>(|(k, v)| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
-                self.batch.merge_cf(&db.cf(), k_buf, v_buf);
+                self.batch.merge_cf(&self.rocksdb, &db.cf, k_buf, v_buf);
                 Ok(())
             })?;
         Ok(self)
@@ -1471,7 +1786,7 @@ impl DBBatch {
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|(k, v)| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
-                self.batch.merge_cf(&db.cf(), k_buf, v);
+                self.batch.merge_cf(&self.rocksdb, &db.cf, k_buf, v);
                 Ok(())
             })?;
         Ok(self)
@@ -1513,7 +1828,7 @@ impl<'a> DBTransaction<'a> {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
                 self.transaction
-                    .put_cf(&db.cf(), k_buf, v_buf)
+                    .put_cf(&db.cf()?, k_buf, v_buf)
                     .map_err(typed_store_err_from_rocks_err)?;
                 Ok(())
             })?;
@@ -1534,7 +1849,7 @@ impl<'a> DBTransaction<'a> {
             .try_for_each::<_, Result<_, TypedStoreError>>(|k| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 self.transaction
-                    .delete_cf(&db.cf(), k_buf)
+                    .delete_cf(&db.cf()?, k_buf)
                     .map_err(typed_store_err_from_rocks_err)?;
                 Ok(())
             })?;
@@ -1559,7 +1874,7 @@ impl<'a> DBTransaction<'a> {
         let k_buf = be_fix_int_ser(key)?;
         match self
             .transaction
-            .get_for_update_cf_opt(&db.cf(), k_buf, true, &db.opts.readopts())
+            .get_for_update_cf_opt(&db.cf()?, k_buf, true, &db.opts.readopts())
             .map_err(typed_store_err_from_rocks_err)?
         {
             Some(data) => Ok(Some(
@@ -1576,7 +1891,7 @@ impl<'a> DBTransaction<'a> {
     ) -> Result<Option<V>, TypedStoreError> {
         let key_buf = be_fix_int_ser(key)?;
         self.transaction
-            .get_cf_opt(&db.cf(), key_buf, &db.opts.readopts())
+            .get_cf_opt(&db.cf()?, key_buf, &db.opts.readopts())
             .map_err(|e| TypedStoreError::RocksDBError(e.to_string()))
             .map(|res| res.and_then(|bytes| bcs::from_bytes::<V>(&bytes).ok()))
     }
@@ -1586,7 +1901,7 @@ impl<'a> DBTransaction<'a> {
         db: &DBMap<K, V>,
         keys: impl IntoIterator<Item = J>,
     ) -> Result<Vec<Option<V>>, TypedStoreError> {
-        let cf = db.cf();
+        let cf = db.cf()?;
         let keys_bytes: Result<Vec<_>, TypedStoreError> = keys
             .into_iter()
             .map(|k| Ok((&cf, be_fix_int_ser(k.borrow())?)))
@@ -1614,11 +1929,11 @@ impl<'a> DBTransaction<'a> {
     pub fn iter<K: DeserializeOwned, V: DeserializeOwned>(
         &'a self,
         db: &DBMap<K, V>,
-    ) -> Iter<'a, K, V> {
+    ) -> Result<Iter<'a, K, V>, TypedStoreError> {
         let db_iter = self
             .transaction
-            .raw_iterator_cf_opt(&db.cf(), db.opts.readopts());
-        Iter::new(
+            .raw_iterator_cf_opt(&db.cf()?, db.opts.readopts());
+        Ok(Iter::new(
             db.cf.clone(),
             RocksDBRawIter::OptimisticTransaction(db_iter),
             None,
@@ -1626,33 +1941,33 @@ impl<'a> DBTransaction<'a> {
             None,
             None,
             None,
-        )
+        ))
     }
 
     pub fn keys<K: DeserializeOwned, V: DeserializeOwned>(
         &'a self,
         db: &DBMap<K, V>,
-    ) -> Keys<'a, K> {
+    ) -> Result<Keys<'a, K>, TypedStoreError> {
         let mut db_iter = RocksDBRawIter::OptimisticTransaction(
             self.transaction
-                .raw_iterator_cf_opt(&db.cf(), db.opts.readopts()),
+                .raw_iterator_cf_opt(&db.cf()?, db.opts.readopts()),
         );
         db_iter.seek_to_first();
 
-        Keys::new(db_iter)
+        Ok(Keys::new(db_iter))
     }
 
     pub fn values<K: DeserializeOwned, V: DeserializeOwned>(
         &'a self,
         db: &DBMap<K, V>,
-    ) -> Values<'a, V> {
+    ) -> Result<Values<'a, V>, TypedStoreError> {
         let mut db_iter = RocksDBRawIter::OptimisticTransaction(
             self.transaction
-                .raw_iterator_cf_opt(&db.cf(), db.opts.readopts()),
+                .raw_iterator_cf_opt(&db.cf()?, db.opts.readopts()),
         );
         db_iter.seek_to_first();
 
-        Values::new(db_iter)
+        Ok(Values::new(db_iter))
     }
 
     pub fn commit(self) -> Result<(), TypedStoreError> {
@@ -1673,6 +1988,7 @@ macro_rules! delegate_iter_call {
             Self::DB(db) => db.$method($($args),*),
             Self::OptimisticTransactionDB(db) => db.$method($($args),*),
             Self::OptimisticTransaction(db) => db.$method($($args),*),
+            Self::InMemory(db) => db.$method($($args),*),
         }
     }
 }
@@ -1688,6 +2004,7 @@ pub enum RocksDBRawIter<'a> {
             Transaction<'a, rocksdb::OptimisticTransactionDB<MultiThreaded>>,
         >,
     ),
+    InMemory(InMemoryRawIter),
 }
 
 impl<'a> RocksDBRawIter<'a> {
@@ -1728,6 +2045,7 @@ pub enum RocksDBIter<'a> {
     OptimisticTransactionDB(
         rocksdb::DBIteratorWithThreadMode<'a, rocksdb::OptimisticTransactionDB<MultiThreaded>>,
     ),
+    InMemory(InMemoryIter),
 }
 
 impl<'a> Iterator for RocksDBIter<'a> {
@@ -1736,6 +2054,7 @@ impl<'a> Iterator for RocksDBIter<'a> {
         match self {
             Self::DB(db) => db.next(),
             Self::OptimisticTransactionDB(db) => db.next(),
+            Self::InMemory(db) => db.next(),
         }
     }
 }
@@ -1757,12 +2076,10 @@ where
         // [`rocksdb::DBWithThreadMode::key_may_exist_cf`] can have false positives,
         // but no false negatives. We use it to short-circuit the absent case
         let readopts = self.opts.readopts();
-        Ok(self
-            .rocksdb
-            .key_may_exist_cf(&self.cf(), &key_buf, &readopts)
+        Ok(self.rocksdb.key_may_exist_cf(&self.cf, &key_buf, &readopts)
             && self
                 .rocksdb
-                .get_pinned_cf_opt(&self.cf(), &key_buf, &readopts)
+                .get_pinned_cf_opt(&self.cf, &key_buf, &readopts)
                 .map_err(typed_store_err_from_rocks_err)?
                 .is_some())
     }
@@ -1795,7 +2112,7 @@ where
         let key_buf = be_fix_int_ser(key)?;
         let res = self
             .rocksdb
-            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())
+            .get_pinned_cf_opt(&self.cf, &key_buf, &self.opts.readopts())
             .map_err(typed_store_err_from_rocks_err)?;
         self.db_metrics
             .op_metrics
@@ -1831,7 +2148,7 @@ where
         let key_buf = be_fix_int_ser(key)?;
         let res = self
             .rocksdb
-            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())
+            .get_pinned_cf_opt(&self.cf, &key_buf, &self.opts.readopts())
             .map_err(typed_store_err_from_rocks_err)?;
         self.db_metrics
             .op_metrics
@@ -1875,7 +2192,7 @@ where
                 .report_metrics(&self.cf);
         }
         self.rocksdb
-            .put_cf(&self.cf(), &key_buf, &value_buf, &self.opts.writeopts())
+            .put_cf(&self.cf, &key_buf, &value_buf, &self.opts.writeopts())
             .map_err(typed_store_err_from_rocks_err)?;
         Ok(())
     }
@@ -1895,7 +2212,7 @@ where
         };
         let key_buf = be_fix_int_ser(key)?;
         self.rocksdb
-            .delete_cf(&self.cf(), key_buf, &self.opts.writeopts())
+            .delete_cf(&self.cf, key_buf, &self.opts.writeopts())
             .map_err(typed_store_err_from_rocks_err)?;
         self.db_metrics
             .op_metrics
@@ -1944,6 +2261,28 @@ where
         Ok(())
     }
 
+    /// Writes a range delete tombstone for the keys between `from` (inclusive) and `to`
+    /// (exclusive). The same caveat about ignore_range_deletions as for `schedule_delete_all`
+    /// applies. Write batches of optimistic transaction DBs do not support range deletes, so
+    /// on those the keys in the range are deleted one by one instead.
+    #[instrument(level = "trace", skip_all, err)]
+    fn schedule_delete_range(&self, from: &K, to: &K) -> Result<(), TypedStoreError> {
+        let mut batch = self.batch();
+        match *self.rocksdb {
+            RocksDB::DBWithThreadMode(_) | RocksDB::InMemory(_) => {
+                batch.schedule_delete_range(self, from, to)?
+            }
+            RocksDB::OptimisticTransactionDB(_) => {
+                let keys = self
+                    .safe_range_iter(from..to)
+                    .map(|item| item.map(|(k, _v)| k))
+                    .collect::<Result<Vec<_>, _>>()?;
+                batch.delete_batch(self, keys)?;
+            }
+        }
+        batch.write()
+    }
+
     fn is_empty(&self) -> bool {
         self.safe_iter().next().is_none()
     }
@@ -1953,7 +2292,7 @@ where
     fn unbounded_iter(&'a self) -> Self::Iterator {
         let db_iter = self
             .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+            .raw_iterator_cf(&self.cf, self.opts.readopts(), None, None);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -1974,8 +2313,10 @@ where
         lower_bound: Option<K>,
         upper_bound: Option<K>,
     ) -> Self::Iterator {
-        let readopts = self.create_read_options_with_bounds(lower_bound, upper_bound);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::iterate_bounds(lower_bound, upper_bound);
+        let db_iter =
+            self.rocksdb
+                .raw_iterator_cf(&self.cf, self.opts.readopts(), lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -1991,8 +2332,10 @@ where
     /// Similar to `iter_with_bounds` but allows specifying inclusivity/exclusivity of ranges explicitly.
     /// TODO: find better name
     fn range_iter(&'a self, range: impl RangeBounds<K>) -> Self::Iterator {
-        let readopts = self.create_read_options_with_range(range);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::iterate_bounds_of_range(range);
+        let db_iter =
+            self.rocksdb
+                .raw_iterator_cf(&self.cf, self.opts.readopts(), lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -2008,7 +2351,7 @@ where
     fn safe_iter(&'a self) -> Self::SafeIterator {
         let db_iter = self
             .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+            .raw_iterator_cf(&self.cf, self.opts.readopts(), None, None);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2026,8 +2369,10 @@ where
         lower_bound: Option<K>,
         upper_bound: Option<K>,
     ) -> Self::SafeIterator {
-        let readopts = self.create_read_options_with_bounds(lower_bound, upper_bound);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::iterate_bounds(lower_bound, upper_bound);
+        let db_iter =
+            self.rocksdb
+                .raw_iterator_cf(&self.cf, self.opts.readopts(), lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2041,8 +2386,10 @@ where
     }
 
     fn safe_range_iter(&'a self, range: impl RangeBounds<K>) -> Self::SafeIterator {
-        let readopts = self.create_read_options_with_range(range);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::iterate_bounds_of_range(range);
+        let db_iter =
+            self.rocksdb
+                .raw_iterator_cf(&self.cf, self.opts.readopts(), lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2058,7 +2405,7 @@ where
     fn keys(&'a self) -> Self::Keys {
         let mut db_iter = self
             .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+            .raw_iterator_cf(&self.cf, self.opts.readopts(), None, None);
         db_iter.seek_to_first();
 
         Keys::new(db_iter)
@@ -2067,7 +2414,7 @@ where
     fn values(&'a self) -> Self::Values {
         let mut db_iter = self
             .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+            .raw_iterator_cf(&self.cf, self.opts.readopts(), None, None);
         db_iter.seek_to_first();
 
         Values::new(db_iter)
@@ -2123,15 +2470,14 @@ where
     where
         J: Borrow<K>,
     {
-        let cf = self.cf();
         let keys_bytes = keys
             .into_iter()
-            .map(|k| (&cf, be_fix_int_ser(k.borrow()).unwrap()));
+            .map(|k| be_fix_int_ser(k.borrow()).unwrap());
         let chunked_keys = keys_bytes.into_iter().chunks(chunk_size);
         let snapshot = self.snapshot()?;
         let mut results = vec![];
         for chunk in chunked_keys.into_iter() {
-            let chunk_result = snapshot.multi_get_cf(chunk);
+            let chunk_result = snapshot.multi_get_cf(&self.rocksdb, &self.cf, chunk);
             let values_parsed: Result<Vec<_>, TypedStoreError> = chunk_result
                 .into_iter()
                 .map(|value_byte| {
@@ -2263,6 +2609,7 @@ impl Default for ReadWriteOptions {
 pub struct DBOptions {
     pub options: rocksdb::Options,
     pub rw_options: ReadWriteOptions,
+    pub in_memory_options: InMemoryOptions,
 }
 
 impl DBOptions {
@@ -2379,6 +2726,54 @@ impl DBOptions {
         self.options.set_min_write_buffer_number_to_merge(2);
         self
     }
+
+    // Expire entries older than `ttl` when they are compacted. `timestamp_ms` returns the unix
+    // time in milliseconds an entry is aged from, or None to keep the entry forever. Expired
+    // entries stay visible until a compaction reaches them, so readers that care must also check
+    // the timestamp. This replaces any compaction filter previously set on the table.
+    pub fn set_ttl<K, V, F>(self, ttl: Duration, timestamp_ms: F) -> DBOptions
+    where
+        K: DeserializeOwned + 'static,
+        V: DeserializeOwned + 'static,
+        F: Fn(&K, &V) -> Option<u64> + Clone + Send + 'static,
+    {
+        self.set_compaction_filter("ttl filter", ttl_compaction_filter(ttl, timestamp_ms))
+    }
+
+    // Combine the values written to the table with `DBBatch::merge_batch` with `merge_fn`, which
+    // is passed the key, the stored value if any, and the merge operands from oldest to newest.
+    // The operator is installed on both RocksDB and in-memory tables.
+    pub fn set_merge_operator<F>(mut self, name: &str, merge_fn: F) -> DBOptions
+    where
+        F: Fn(&[u8], Option<&[u8]>, &mut dyn Iterator<Item = &[u8]>) -> Option<Vec<u8>>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        let rocksdb_merge_fn = {
+            let merge_fn = merge_fn.clone();
+            move |key: &[u8], value: Option<&[u8]>, operands: &MergeOperands| {
+                merge_fn(key, value, &mut operands.iter())
+            }
+        };
+        self.options
+            .set_merge_operator(name, rocksdb_merge_fn.clone(), rocksdb_merge_fn);
+        self.in_memory_options.set_merge_operator(merge_fn);
+        self
+    }
+
+    // Remove or change entries of the table as `filter_fn` decides when they are compacted, on
+    // both RocksDB and in-memory tables. This replaces any compaction filter previously set on
+    // the table.
+    pub fn set_compaction_filter<F>(mut self, name: &str, filter_fn: F) -> DBOptions
+    where
+        F: FnMut(u32, &[u8], &[u8]) -> CompactionDecision + Clone + Send + 'static,
+    {
+        self.options.set_compaction_filter(name, filter_fn.clone());
+        self.in_memory_options.set_compaction_filter(filter_fn);
+        self
+    }
 }
 
 /// Creates a default RocksDB option, to be used when RocksDB option is unspecified.
@@ -2439,6 +2834,7 @@ pub fn default_db_options() -> DBOptions {
     DBOptions {
         options: opt,
         rw_options: ReadWriteOptions::default(),
+        in_memory_options: InMemoryOptions::default(),
     }
 }
 
@@ -2489,6 +2885,29 @@ fn prepare_db_options(db_options: Option<rocksdb::Options>) -> rocksdb::Options
     options
 }
 
+/// Opens a database which keeps the given column families in memory instead of in RocksDB.
+/// Each call creates a separate database, which loses all its data once dropped.
+pub fn open_cf_in_memory(metric_conf: MetricConf, opt_cfs: &[&str]) -> Arc<RocksDB> {
+    let opt_cfs: Vec<_> = opt_cfs
+        .iter()
+        .map(|name| (*name, InMemoryOptions::default()))
+        .collect();
+    Arc::new(RocksDB::InMemory(InMemoryDB::new(metric_conf, &opt_cfs)))
+}
+
+/// Same as `open_cf_in_memory`, with the merge operators and compaction filters of the options
+/// of each column family.
+pub fn open_cf_opts_in_memory(
+    metric_conf: MetricConf,
+    opt_cfs: &[(&str, DBOptions)],
+) -> Arc<RocksDB> {
+    let opt_cfs: Vec<_> = opt_cfs
+        .iter()
+        .map(|(name, options)| (*name, options.in_memory_options.clone()))
+        .collect();
+    Arc::new(RocksDB::InMemory(InMemoryDB::new(metric_conf, &opt_cfs)))
+}
+
 /// Opens a database with options, and a number of column families with individual options that are created if they do not exist.
 #[instrument(level="debug", skip_all, fields(path = ?path.as_ref()), err)]
 pub fn open_cf_opts<P: AsRef<Path>>(
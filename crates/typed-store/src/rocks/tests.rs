@@ -3,7 +3,10 @@
 use super::*;
 use crate::rocks::iter::{Iter, RevIter};
 use crate::rocks::safe_iter::{SafeIter, SafeRevIter};
-use crate::rocks::util::{is_ref_count_value, reference_count_merge_operator};
+use crate::rocks::util::{
+    empty_compaction_filter, is_ref_count_value, merge_reference_counts,
+    reference_count_merge_operator,
+};
 use crate::{reopen, retry_transaction, retry_transaction_forever};
 use rstest::rstest;
 use serde::Deserialize;
@@ -1082,6 +1085,249 @@ async fn test_multi_remove(#[values(true, false)] is_transactional: bool) {
     }
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_map_schedule_delete_range(#[values(true, false)] is_transactional: bool) {
+    let db: DBMap<i32, String> = if is_transactional {
+        open_map(temp_dir(), Some("table"), is_transactional)
+    } else {
+        DBMap::open(
+            temp_dir(),
+            MetricConf::default(),
+            None,
+            Some("table"),
+            &ReadWriteOptions::default().set_ignore_range_deletions(false),
+        )
+        .expect("Failed to open storage")
+    };
+
+    db.multi_insert((0..101).map(|i| (i, i.to_string())))
+        .expect("Failed to multi-insert");
+
+    db.schedule_delete_range(&50, &100)
+        .expect("Failed to delete range");
+
+    for k in 0..50 {
+        assert!(db.contains_key(&k).expect("Failed to query legal key"));
+    }
+    for k in 50..100 {
+        assert!(!db.contains_key(&k).expect("Failed to query legal key"));
+    }
+    // range operator is not inclusive of to
+    assert!(db.contains_key(&100).expect("Failed to query legal key"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_snapshot_across_maps(#[values(true, false)] is_transactional: bool) {
+    let rocks = open_rocksdb(temp_dir(), &["First_CF", "Second_CF"], is_transactional);
+    let db_cf_1 =
+        DBMap::<i32, String>::reopen(&rocks, Some("First_CF"), &ReadWriteOptions::default())
+            .expect("Failed to open storage");
+    let db_cf_2 =
+        DBMap::<i32, String>::reopen(&rocks, Some("Second_CF"), &ReadWriteOptions::default())
+            .expect("Failed to open storage");
+
+    db_cf_1.insert(&1, &"1".to_string()).unwrap();
+    db_cf_2.insert(&1, &"1".to_string()).unwrap();
+
+    let snapshot = db_cf_1.snapshot().unwrap();
+
+    db_cf_1.insert(&1, &"one".to_string()).unwrap();
+    db_cf_2.insert(&2, &"2".to_string()).unwrap();
+
+    // Both maps are read as of the snapshot.
+    assert_eq!(
+        db_cf_1.get_at_snapshot(&snapshot, &1).unwrap(),
+        Some("1".to_string())
+    );
+    assert_eq!(db_cf_2.get_at_snapshot(&snapshot, &2).unwrap(), None);
+    let entries: Vec<_> = db_cf_2
+        .safe_iter_at_snapshot(&snapshot, None, None)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries, vec![(1, "1".to_string())]);
+
+    // Reads outside of the snapshot see the latest writes.
+    assert_eq!(db_cf_1.get(&1).unwrap(), Some("one".to_string()));
+    assert_eq!(db_cf_2.safe_iter().count(), 2);
+}
+
+type TableReads = (
+    Vec<Vec<(u32, String)>>,
+    Vec<u32>,
+    Vec<String>,
+    Vec<Option<String>>,
+);
+
+// All the reads `test_in_memory_db_matches_rocksdb` compares across databases.
+fn read_table<'a>(table: &'a DBMap<u32, String>, snapshot: &'a RocksDBSnapshot<'a>) -> TableReads {
+    let iterations = vec![
+        table.safe_iter().map(Result::unwrap).collect::<Vec<_>>(),
+        table
+            .safe_iter_with_bounds(Some(15), Some(51))
+            .map(Result::unwrap)
+            .collect(),
+        table.safe_range_iter(15..=51).map(Result::unwrap).collect(),
+        table
+            .safe_range_iter((Bound::Excluded(16), Bound::Excluded(50)))
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter()
+            .skip_to(&57)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter()
+            .skip_prior_to(&57)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter()
+            .skip_to_last()
+            .reverse()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter_with_bounds(Some(15), Some(51))
+            .skip_to_last()
+            .reverse()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter_with_bounds(Some(15), Some(51))
+            .skip_prior_to(&100)
+            .unwrap()
+            .reverse()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .safe_iter_with_bounds(Some(15), Some(51))
+            .skip_to(&0)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect(),
+        table
+            .iter_with_bounds(Some(40), None)
+            .skip_to(&95)
+            .unwrap()
+            .collect(),
+        table
+            .safe_iter_at_snapshot(snapshot, Some(10), Some(40))
+            .map(Result::unwrap)
+            .collect(),
+    ];
+    let keys = table.keys().map(Result::unwrap).collect();
+    let values = table.values().map(Result::unwrap).collect();
+    let keys_to_get = [0, 1, 2, 5, 10, 30, 31, 110, 198, 500];
+    let gets = table
+        .multi_get(keys_to_get)
+        .unwrap()
+        .into_iter()
+        .chain(table.chunked_multi_get(keys_to_get, 3).unwrap())
+        .chain(
+            keys_to_get
+                .iter()
+                .map(|key| table.get_at_snapshot(snapshot, key).unwrap()),
+        )
+        .collect();
+    (iterations, keys, values, gets)
+}
+
+#[tokio::test]
+async fn test_in_memory_db_matches_rocksdb() {
+    let rocksdb = open_cf(temp_dir(), None, MetricConf::default(), &["table", "other"]).unwrap();
+    let in_memory = open_cf_in_memory(MetricConf::default(), &["table", "other"]);
+    // In-memory databases never ignore range deletions, so neither must RocksDB reads.
+    let rw_options = ReadWriteOptions::default().set_ignore_range_deletions(false);
+    let maps: Vec<_> = [rocksdb, in_memory]
+        .iter()
+        .map(|db| {
+            (
+                DBMap::<u32, String>::reopen(db, Some("table"), &rw_options).unwrap(),
+                DBMap::<u32, String>::reopen(db, Some("other"), &rw_options).unwrap(),
+            )
+        })
+        .collect();
+
+    for (table, other) in &maps {
+        table
+            .multi_insert((0..100).map(|i| (i * 2, i.to_string())))
+            .unwrap();
+        let mut batch = table.batch();
+        batch
+            .insert_batch(other, (0..10).map(|i| (i, i.to_string())))
+            .unwrap();
+        batch.delete_batch(table, [10, 20]).unwrap();
+        batch.schedule_delete_range(table, &100, &120).unwrap();
+        batch.write().unwrap();
+        table.remove(&30).unwrap();
+        table.insert(&31, &"31".to_string()).unwrap();
+        table.schedule_delete_range(&150, &160).unwrap();
+    }
+    let snapshots: Vec<_> = maps
+        .iter()
+        .map(|(table, _)| table.snapshot().unwrap())
+        .collect();
+    for (table, other) in &maps {
+        table.insert(&1, &"1".to_string()).unwrap();
+        table.remove(&0).unwrap();
+        other.remove(&5).unwrap();
+    }
+
+    let reads: Vec<_> = maps
+        .iter()
+        .zip(&snapshots)
+        .map(|((table, other), snapshot)| {
+            (read_table(table, snapshot), read_table(other, snapshot))
+        })
+        .collect();
+    assert_eq!(reads[0], reads[1]);
+
+    let (table_reads, other_reads) = &reads[1];
+    let keys = |entries: &Vec<(u32, String)>| entries.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    assert_eq!(
+        keys(&table_reads.0[1]),
+        vec![16, 18, 22, 24, 26, 28, 31, 32, 34, 36, 38, 40, 42, 44, 46, 48, 50]
+    );
+    assert_eq!(
+        keys(&table_reads.0[11]),
+        vec![12, 14, 16, 18, 22, 24, 26, 28, 31, 32, 34, 36, 38]
+    );
+    assert_eq!(other_reads.1, vec![0, 1, 2, 3, 4, 6, 7, 8, 9]);
+}
+
+#[tokio::test]
+async fn test_ttl_compaction_filter() {
+    // Values are the unix time in milliseconds at which they were written.
+    let options =
+        default_db_options().set_ttl::<i32, u64, _>(Duration::from_secs(60), |_k, v| Some(*v));
+    let rocks = open_cf_opts(
+        temp_dir(),
+        None,
+        MetricConf::default(),
+        &[("table", options.options.clone())],
+    )
+    .expect("Failed to open storage");
+    let in_memory = open_cf_opts_in_memory(MetricConf::default(), &[("table", options)]);
+
+    for db in [rocks, in_memory] {
+        let db = DBMap::<i32, u64>::reopen(&db, Some("table"), &ReadWriteOptions::default())
+            .expect("Failed to open storage");
+
+        db.insert(&1, &0).unwrap();
+        db.insert(&2, &u64::MAX).unwrap();
+        db.flush().unwrap();
+        db.compact_range(&0, &3).unwrap();
+
+        assert!(!db.contains_key(&1).unwrap());
+        assert!(db.contains_key(&2).unwrap());
+    }
+}
+
 #[tokio::test]
 async fn test_transactional() {
     let key = "key";
@@ -1291,9 +1537,9 @@ async fn test_transaction_read_your_write() {
             .unwrap(),
         vec![Some("11".to_string()), None]
     );
-    let keys: Vec<String> = tx.keys(&db).map(|x| x.unwrap()).collect();
+    let keys: Vec<String> = tx.keys(&db).unwrap().map(|x| x.unwrap()).collect();
     assert_eq!(keys, vec![key1.to_string()]);
-    let values: Vec<_> = tx.values(&db).collect();
+    let values: Vec<_> = tx.values(&db).unwrap().collect();
     assert_eq!(values, vec![Ok("11".to_string())]);
     assert!(tx.commit().is_ok());
 }
@@ -1469,6 +1715,77 @@ async fn refcount_with_compaction_test() {
     assert!(is_ref_count_value(&value));
 }
 
+#[tokio::test]
+async fn in_memory_refcount_with_compaction_test() {
+    let key = "key".to_string();
+    let options = default_db_options()
+        .set_merge_operator("refcount operator", merge_reference_counts)
+        .set_compaction_filter("empty filter", empty_compaction_filter);
+    let rocksdb = open_cf_opts_in_memory(MetricConf::default(), &[("table", options)]);
+    let db = DBMap::<String, ObjectWithRefCount>::reopen(
+        &rocksdb,
+        Some("table"),
+        &ReadWriteOptions::default(),
+    )
+    .unwrap();
+
+    let object = ObjectWithRefCount {
+        value: 3,
+        ref_count: 1,
+    };
+    for _ in 0..2 {
+        let mut batch = db.batch();
+        batch.merge_batch(&db, [(key.to_string(), object)]).unwrap();
+        batch.write().unwrap();
+    }
+    let value = db.get(&key).unwrap().unwrap();
+    assert_eq!(value.value, object.value);
+    assert_eq!(value.ref_count, 2);
+
+    // Snapshots do not see later merges.
+    let snapshot = db.snapshot().unwrap();
+    increment_counter(&db, &key, -1);
+    increment_counter(&db, &key, -1);
+    assert!(db.get(&key).is_err());
+    assert!(db.multi_get_raw_bytes([&key]).unwrap()[0]
+        .as_ref()
+        .unwrap()
+        .is_empty());
+    let value = db.get_at_snapshot(&snapshot, &key).unwrap().unwrap();
+    assert_eq!(value.ref_count, 2);
+
+    // Compacting removes the entry whose ref count went to zero.
+    db.compact_range(&key, &key).unwrap();
+    assert!(db.get_raw_bytes(&key).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn in_memory_unsupported_operations_test() {
+    let rocksdb = open_cf_in_memory(MetricConf::default(), &["table"]);
+    let db = DBMap::<String, String>::reopen(&rocksdb, Some("table"), &ReadWriteOptions::default())
+        .unwrap();
+    db.insert(&"key".to_string(), &"value".to_string()).unwrap();
+
+    assert!(db.cf().is_err());
+    assert!(db.transaction().is_err());
+    assert!(db.transaction_without_snapshot().is_err());
+
+    // Merging into a table without merge operator fails, and none of the batch is written.
+    let mut batch = db.batch();
+    batch
+        .insert_batch(&db, [("other".to_string(), "value".to_string())])
+        .unwrap();
+    batch
+        .partial_merge_batch(&db, [("key".to_string(), b"operand")])
+        .unwrap();
+    assert!(batch.write().is_err());
+    assert_eq!(
+        db.get(&"key".to_string()).unwrap(),
+        Some("value".to_string())
+    );
+    assert!(!db.contains_key(&"other".to_string()).unwrap());
+}
+
 fn open_map<P: AsRef<Path>, K, V>(
     path: P,
     opt_cf: Option<&str>,
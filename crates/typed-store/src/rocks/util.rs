@@ -1,16 +1,29 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use bincode::Options;
 use rocksdb::{CompactionDecision, MergeOperands};
+use serde::de::DeserializeOwned;
 use std::cmp::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// custom rocksdb merge operator used for storing objects with reference counts
 /// important: reference count field must be 64-bit integer and must be last in struct declaration
 /// should be used with immutable objects only
 pub fn reference_count_merge_operator(
-    _key: &[u8],
+    key: &[u8],
     stored_value: Option<&[u8]>,
     operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    merge_reference_counts(key, stored_value, &mut operands.iter())
+}
+
+/// Same as `reference_count_merge_operator`, with the signature of the merge operators installed
+/// by `DBOptions::set_merge_operator`
+pub fn merge_reference_counts(
+    _key: &[u8],
+    stored_value: Option<&[u8]>,
+    operands: &mut dyn Iterator<Item = &[u8]>,
 ) -> Option<Vec<u8>> {
     let (mut value, mut ref_count) = stored_value.map_or((None, 0), deserialize_ref_count_value);
 
@@ -37,6 +50,40 @@ pub fn empty_compaction_filter(_level: u32, _key: &[u8], value: &[u8]) -> Compac
     }
 }
 
+/// Builds a compaction filter removing the entries that `timestamp_ms` dates more than `ttl` ago.
+/// Entries that cannot be deserialized, or that `timestamp_ms` returns None for, are kept.
+pub fn ttl_compaction_filter<K, V, F>(
+    ttl: Duration,
+    timestamp_ms: F,
+) -> impl FnMut(u32, &[u8], &[u8]) -> CompactionDecision + Clone + Send + 'static
+where
+    K: DeserializeOwned + 'static,
+    V: DeserializeOwned + 'static,
+    F: Fn(&K, &V) -> Option<u64> + Clone + Send + 'static,
+{
+    move |_level, key, value| {
+        let config = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding();
+        let (Ok(key), Ok(value)) = (config.deserialize::<K>(key), bcs::from_bytes::<V>(value))
+        else {
+            return CompactionDecision::Keep;
+        };
+        match timestamp_ms(&key, &value) {
+            Some(timestamp_ms) if is_expired(timestamp_ms, ttl) => CompactionDecision::Remove,
+            _ => CompactionDecision::Keep,
+        }
+    }
+}
+
+/// Returns true if an entry dated at unix time `timestamp_ms` is older than `ttl`.
+pub fn is_expired(timestamp_ms: u64, ttl: Duration) -> bool {
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    timestamp_ms.saturating_add(ttl.as_millis() as u64) < now_ms
+}
+
 pub fn is_ref_count_value(value: &[u8]) -> bool {
     value.is_empty() || value.len() == 8
 }
@@ -59,6 +59,11 @@ use crate::{
     TypedStoreError,
 };
 
+#[cfg(test)]
+mod tests;
+pub mod wal;
+pub use wal::{SallyWal, SallyWalBatch, SallyWalColumn, SallyWalIter, SallyWalOptions};
+
 use crate::rocks::safe_iter::{SafeIter as RocksDBIter, SafeRevIter};
 use crate::rocks::{DBMapTableConfigMap, MetricConf};
 use crate::test_db::{TestDBIter, TestDBRevIter};
@@ -75,6 +80,9 @@ pub enum SallyRunMode {
     // backend stores, we should never use `FallbackToDB` as that would lose atomicity,
     // transactions and db recovery
     FallbackToDB,
+    // Serve reads/writes from a memtable and wal shared by all columns of the db, flushing to the
+    // backend stores once the memtable is full
+    MemtableAndWal(SallyWalColumn),
 }
 
 pub struct SallyConfig {
@@ -89,9 +97,9 @@ impl Default for SallyConfig {
     }
 }
 
-/// A Sally column could be anything that implements key value interface. We will eventually have
-/// Sally serve read/writes using its own memtable and wal when columns in the db are backend by more then
-/// one backend store (e.g different rocksdb instances and/or distributed key value stores)
+/// A Sally column could be anything that implements key value interface. When columns in the db are
+/// backed by more than one backend store (e.g different rocksdb instances and/or distributed key
+/// value stores), Sally serves read/writes using its own memtable and wal, see `SallyWal`
 pub enum SallyColumn<K, V> {
     RocksDB((DBMap<K, V>, SallyConfig)),
     TestDB((TestDB<K, V>, SallyConfig)),
@@ -106,6 +114,55 @@ impl<K, V> SallyColumn<K, V> {
     pub fn new_testdb(db: TestDB<K, V>) -> Self {
         SallyColumn::TestDB((db, SallyConfig::default()))
     }
+    /// Creates a column backed by the given rocksdb column family whose writes go through the
+    /// shared `wal`. `name` identifies the column in the wal and must be stable across restarts.
+    pub fn new_rocksdb_with_wal(
+        db: DBMap<K, V>,
+        wal: &SallyWal,
+        name: &str,
+    ) -> Result<Self, TypedStoreError>
+    where
+        K: 'static,
+        V: 'static,
+    {
+        wal.register(name, Box::new(db.clone()))?;
+        Ok(SallyColumn::RocksDB((
+            db,
+            SallyConfig {
+                mode: SallyRunMode::MemtableAndWal(SallyWalColumn::new(wal, name)),
+            },
+        )))
+    }
+    /// Creates a column backed by the given test db whose writes go through the shared `wal`.
+    /// `name` identifies the column in the wal and must be stable across restarts.
+    pub fn new_testdb_with_wal(
+        db: TestDB<K, V>,
+        wal: &SallyWal,
+        name: &str,
+    ) -> Result<Self, TypedStoreError>
+    where
+        K: 'static,
+        V: 'static,
+    {
+        wal.register(name, Box::new(db.clone()))?;
+        Ok(SallyColumn::TestDB((
+            db,
+            SallyConfig {
+                mode: SallyRunMode::MemtableAndWal(SallyWalColumn::new(wal, name)),
+            },
+        )))
+    }
+    /// Iterator of the backend store of the column, which bypasses the sally memtable
+    fn backend_iter(&self) -> SallyIter<'_, K, V>
+    where
+        K: Serialize + DeserializeOwned,
+        V: Serialize + DeserializeOwned,
+    {
+        match self {
+            SallyColumn::RocksDB((db_map, _)) => SallyIter::RocksDB(db_map.safe_iter()),
+            SallyColumn::TestDB((test_db, _)) => SallyIter::TestDB(test_db.safe_iter()),
+        }
+    }
     pub fn batch(&self) -> SallyWriteBatch {
         match self {
             SallyColumn::RocksDB((
@@ -120,6 +177,18 @@ impl<K, V> SallyColumn<K, V> {
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => SallyWriteBatch::TestDB(test_db.batch()),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => SallyWriteBatch::Wal(SallyWalBatch::new(column.wal())),
         }
     }
 }
@@ -149,6 +218,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.contains_key(key),
+            SallyColumn::RocksDB((
+                db_map,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.contains_key(key, |k| db_map.contains_key(k)),
+            SallyColumn::TestDB((
+                test_db,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.contains_key(key, |k| test_db.contains_key(k)),
         }
     }
     async fn get(&self, key: &K) -> Result<Option<V>, TypedStoreError> {
@@ -165,6 +246,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.get(key),
+            SallyColumn::RocksDB((
+                db_map,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.get(key, |k| db_map.get(k)),
+            SallyColumn::TestDB((
+                test_db,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.get(key, |k| test_db.get(k)),
         }
     }
     async fn get_raw_bytes(&self, key: &K) -> Result<Option<Vec<u8>>, TypedStoreError> {
@@ -181,6 +274,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.get_raw_bytes(key),
+            SallyColumn::RocksDB((
+                db_map,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.get_raw_bytes(key, |k| db_map.get_raw_bytes(k)),
+            SallyColumn::TestDB((
+                test_db,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.get_raw_bytes(key, |k| test_db.get_raw_bytes(k)),
         }
     }
     async fn is_empty(&self) -> bool {
@@ -197,6 +302,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.is_empty(),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => SallyIter::wal(self, column, None).next().is_none(),
         }
     }
     async fn iter(&'a self) -> Self::Iterator {
@@ -213,6 +330,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => SallyIter::TestDB(test_db.safe_iter()),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => SallyIter::wal(self, column, None),
         }
     }
     async fn keys(&'a self) -> Self::Keys {
@@ -229,6 +358,20 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => SallyKeys::TestDB(test_db.keys()),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => SallyKeys::Wal(Box::new(
+                SallyIter::wal(self, column, None).map(|item| item.map(|(key, _)| key)),
+            )),
         }
     }
     async fn values(&'a self) -> Self::Values {
@@ -245,6 +388,20 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => SallyValues::TestDB(test_db.values()),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => SallyValues::Wal(Box::new(
+                SallyIter::wal(self, column, None).map(|item| item.map(|(_, value)| value)),
+            )),
         }
     }
     async fn multi_get<J>(
@@ -267,6 +424,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.multi_get(keys),
+            SallyColumn::RocksDB((
+                db_map,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.multi_get(keys, |missing| db_map.multi_get(missing)),
+            SallyColumn::TestDB((
+                test_db,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => column.multi_get(keys, |missing| test_db.multi_get(missing)),
         }
     }
     async fn try_catch_up_with_primary(&self) -> Result<(), Self::Error> {
@@ -283,6 +452,18 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => Ok(test_db.try_catch_up_with_primary()?),
+            SallyColumn::RocksDB((
+                db_map,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => Ok(db_map.try_catch_up_with_primary()?),
+            SallyColumn::TestDB((
+                test_db,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => Ok(test_db.try_catch_up_with_primary()?),
         }
     }
 }
@@ -313,6 +494,22 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.try_extend(iter),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => {
+                let mut batch = SallyWalBatch::new(column.wal());
+                batch.insert_batch::<J, K, U, V>(column, iter)?;
+                batch.write()
+            }
         }
     }
     fn try_extend_from_slice(&mut self, slice: &[(J, U)]) -> Result<(), Self::Error> {
@@ -329,18 +526,37 @@ where
                     mode: SallyRunMode::FallbackToDB,
                 },
             )) => test_db.try_extend_from_slice(slice),
+            SallyColumn::RocksDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            ))
+            | SallyColumn::TestDB((
+                _,
+                SallyConfig {
+                    mode: SallyRunMode::MemtableAndWal(column),
+                },
+            )) => {
+                let mut batch = SallyWalBatch::new(column.wal());
+                batch.insert_batch::<J, K, U, V>(column, slice.iter().cloned())?;
+                batch.write()
+            }
         }
     }
 }
 
 /// A Sally write batch provides a mutable struct which holds a collection of db mutation operations and
 /// applies them atomically to the db.
-/// Once sally has its own memtable and wal, atomic commits across multiple db instances will be possible.
+/// Batches on columns running with sally's own memtable and wal can be committed atomically across
+/// multiple db instances.
 pub enum SallyWriteBatch {
     // Write batch for RocksDB backend when `fallback_to_db` is set as true
     RocksDB(DBBatch),
     // Write batch for btree map based backend
     TestDB(TestDBWriteBatch),
+    // Write batch for columns served by sally's memtable and wal
+    Wal(SallyWalBatch),
 }
 
 impl SallyWriteBatch {
@@ -348,6 +564,7 @@ impl SallyWriteBatch {
         match self {
             SallyWriteBatch::RocksDB(db_batch) => db_batch.write(),
             SallyWriteBatch::TestDB(write_batch) => write_batch.write(),
+            SallyWriteBatch::Wal(wal_batch) => wal_batch.write(),
         }
     }
     /// Deletes a set of keys given as an iterator
@@ -363,6 +580,21 @@ impl SallyWriteBatch {
             (SallyWriteBatch::TestDB(write_batch), SallyColumn::TestDB((test_db, _))) => {
                 write_batch.delete_batch(test_db, purged_vals)
             }
+            (
+                SallyWriteBatch::Wal(wal_batch),
+                SallyColumn::RocksDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                ))
+                | SallyColumn::TestDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                )),
+            ) => wal_batch.delete_batch(column, purged_vals),
             _ => unimplemented!(),
         }
     }
@@ -380,6 +612,21 @@ impl SallyWriteBatch {
             (SallyWriteBatch::TestDB(write_batch), SallyColumn::TestDB((test_db, _))) => {
                 write_batch.delete_range(test_db, from, to)
             }
+            (
+                SallyWriteBatch::Wal(wal_batch),
+                SallyColumn::RocksDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                ))
+                | SallyColumn::TestDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                )),
+            ) => wal_batch.delete_range(column, from, to),
             _ => unimplemented!(),
         }
     }
@@ -398,6 +645,21 @@ impl SallyWriteBatch {
                 write_batch.insert_batch(test_db, new_vals)?;
                 Ok(())
             }
+            (
+                SallyWriteBatch::Wal(wal_batch),
+                SallyColumn::RocksDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                ))
+                | SallyColumn::TestDB((
+                    _,
+                    SallyConfig {
+                        mode: SallyRunMode::MemtableAndWal(column),
+                    },
+                )),
+            ) => wal_batch.insert_batch(column, new_vals),
             _ => unimplemented!(),
         }
     }
@@ -408,19 +670,52 @@ pub enum SallyIter<'a, K, V> {
     // Iter for a rocksdb backed sally column when `fallback_to_db` is true
     RocksDB(RocksDBIter<'a, K, V>),
     TestDB(TestDBIter<'a, K, V>),
+    // Iter for a sally column served by its memtable and wal, which merges the unflushed writes
+    // of the column over the iter of its backend store
+    Wal(
+        (
+            &'a SallyColumn<K, V>,
+            &'a SallyWalColumn,
+            Box<SallyWalIter<SallyIter<'a, K, V>, K, V>>,
+        ),
+    ),
 }
 
-impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iterator for SallyIter<'a, K, V> {
+impl<'a, K: Serialize + DeserializeOwned, V: DeserializeOwned> Iterator for SallyIter<'a, K, V> {
     type Item = Result<(K, V), TypedStoreError>;
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             SallyIter::RocksDB(iter) => iter.next(),
             SallyIter::TestDB(iter) => iter.next(),
+            SallyIter::Wal((_, _, iter)) => iter.next(),
         }
     }
 }
 
-impl<'a, K: Serialize, V> SallyIter<'a, K, V> {
+impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> SallyIter<'a, K, V> {
+    /// Iterator over a column served by its memtable and wal, starting at `from` if given
+    fn wal(column: &'a SallyColumn<K, V>, wal: &'a SallyWalColumn, from: Option<&K>) -> Self {
+        let iter = match from {
+            Some(key) => wal.iter(|| column.backend_iter().skip_to(key), Some(key)),
+            None => wal.iter(|| Ok(column.backend_iter()), None),
+        };
+        SallyIter::Wal((column, wal, Box::new(iter)))
+    }
+
+    /// Iterator over a column served by its memtable and wal, starting at the last key before or
+    /// at `to` if given, or at the last key of the column otherwise
+    fn wal_from_prior(
+        column: &'a SallyColumn<K, V>,
+        wal: &'a SallyWalColumn,
+        to: Option<&K>,
+    ) -> Self {
+        match SallyRevIter::wal(column, wal, to).next() {
+            Some(Ok((prior, _))) => Self::wal(column, wal, Some(&prior)),
+            Some(Err(e)) => SallyIter::Wal((column, wal, Box::new(SallyWalIter::failed(e)))),
+            None => SallyIter::Wal((column, wal, Box::new(SallyWalIter::empty()))),
+        }
+    }
+
     /// Skips all the elements that are smaller than the given key,
     /// and either lands on the key or the first one greater than
     /// the key.
@@ -428,6 +723,7 @@ impl<'a, K: Serialize, V> SallyIter<'a, K, V> {
         let iter = match self {
             SallyIter::RocksDB(iter) => SallyIter::RocksDB(iter.skip_to(key)?),
             SallyIter::TestDB(iter) => SallyIter::TestDB(iter.skip_to(key)?),
+            SallyIter::Wal((column, wal, _)) => Self::wal(column, wal, Some(key)),
         };
         Ok(iter)
     }
@@ -439,6 +735,7 @@ impl<'a, K: Serialize, V> SallyIter<'a, K, V> {
         let iter = match self {
             SallyIter::RocksDB(iter) => SallyIter::RocksDB(iter.skip_prior_to(key)?),
             SallyIter::TestDB(iter) => SallyIter::TestDB(iter.skip_prior_to(key)?),
+            SallyIter::Wal((column, wal, _)) => Self::wal_from_prior(column, wal, Some(key)),
         };
         Ok(iter)
     }
@@ -448,6 +745,7 @@ impl<'a, K: Serialize, V> SallyIter<'a, K, V> {
         match self {
             SallyIter::RocksDB(iter) => SallyIter::RocksDB(iter.skip_to_last()),
             SallyIter::TestDB(iter) => SallyIter::TestDB(iter.skip_to_last()),
+            SallyIter::Wal((column, wal, _)) => Self::wal_from_prior(column, wal, None),
         }
     }
 
@@ -458,6 +756,11 @@ impl<'a, K: Serialize, V> SallyIter<'a, K, V> {
         match self {
             SallyIter::RocksDB(iter) => SallyRevIter::RocksDB(iter.reverse()),
             SallyIter::TestDB(iter) => SallyRevIter::TestDB(iter.reverse()),
+            SallyIter::Wal((column, wal, mut iter)) => match iter.peek() {
+                Some(Ok((key, _))) => SallyRevIter::wal(column, wal, Some(key)),
+                Some(Err(e)) => SallyRevIter::Wal(Box::new(SallyWalIter::failed(e.clone()))),
+                None => SallyRevIter::Wal(Box::new(SallyWalIter::empty())),
+            },
         }
     }
 }
@@ -466,9 +769,26 @@ pub enum SallyRevIter<'a, K, V> {
     // Iter for a rocksdb backed sally column when `fallback_to_db` is true
     RocksDB(SafeRevIter<'a, K, V>),
     TestDB(TestDBRevIter<'a, K, V>),
+    // Iter for a sally column served by its memtable and wal
+    Wal(Box<SallyWalIter<SallyRevIter<'a, K, V>, K, V>>),
+}
+
+impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> SallyRevIter<'a, K, V> {
+    /// Reverse iterator over a column served by its memtable and wal, starting at the last key
+    /// before or at `to` if given, or at the last key of the column otherwise
+    fn wal(column: &'a SallyColumn<K, V>, wal: &'a SallyWalColumn, to: Option<&K>) -> Self {
+        let iter = match to {
+            Some(key) => wal.rev_iter(
+                || Ok(column.backend_iter().skip_prior_to(key)?.reverse()),
+                Some(key),
+            ),
+            None => wal.rev_iter(|| Ok(column.backend_iter().skip_to_last().reverse()), None),
+        };
+        SallyRevIter::Wal(Box::new(iter))
+    }
 }
 
-impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iterator for SallyRevIter<'a, K, V> {
+impl<'a, K: Serialize + DeserializeOwned, V: DeserializeOwned> Iterator for SallyRevIter<'a, K, V> {
     type Item = Result<(K, V), TypedStoreError>;
 
     /// Will give the next item backwards
@@ -476,6 +796,7 @@ impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iterator for SallyRevIter<'a,
         match self {
             SallyRevIter::RocksDB(rev_iter) => rev_iter.next(),
             SallyRevIter::TestDB(rev_iter) => rev_iter.next(),
+            SallyRevIter::Wal(rev_iter) => rev_iter.next(),
         }
     }
 }
@@ -485,6 +806,8 @@ pub enum SallyKeys<'a, K> {
     // Iter for a rocksdb backed sally column when `fallback_to_db` is true
     RocksDB(Keys<'a, K>),
     TestDB(TestDBKeys<'a, K>),
+    // Iter for a sally column served by its memtable and wal
+    Wal(Box<dyn Iterator<Item = Result<K, TypedStoreError>> + 'a>),
 }
 
 impl<'a, K: DeserializeOwned> Iterator for SallyKeys<'a, K> {
@@ -494,6 +817,7 @@ impl<'a, K: DeserializeOwned> Iterator for SallyKeys<'a, K> {
         match self {
             SallyKeys::RocksDB(keys) => keys.next(),
             SallyKeys::TestDB(iter) => iter.next(),
+            SallyKeys::Wal(iter) => iter.next(),
         }
     }
 }
@@ -503,6 +827,8 @@ pub enum SallyValues<'a, V> {
     // Iter for a rocksdb backed sally column when `fallback_to_db` is true
     RocksDB(Values<'a, V>),
     TestDB(TestDBValues<'a, V>),
+    // Iter for a sally column served by its memtable and wal
+    Wal(Box<dyn Iterator<Item = Result<V, TypedStoreError>> + 'a>),
 }
 
 impl<'a, V: DeserializeOwned> Iterator for SallyValues<'a, V> {
@@ -512,6 +838,7 @@ impl<'a, V: DeserializeOwned> Iterator for SallyValues<'a, V> {
         match self {
             SallyValues::RocksDB(values) => values.next(),
             SallyValues::TestDB(iter) => iter.next(),
+            SallyValues::Wal(iter) => iter.next(),
         }
     }
 }
@@ -0,0 +1,294 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crate::rocks::ReadWriteOptions;
+use std::io::Write;
+
+fn temp_dir() -> std::path::PathBuf {
+    tempfile::tempdir()
+        .expect("Failed to open temporary directory")
+        .into_path()
+}
+
+fn open_map<K, V>(path: &std::path::Path) -> DBMap<K, V> {
+    DBMap::<K, V>::open(
+        path,
+        MetricConf::default(),
+        None,
+        None,
+        &ReadWriteOptions::default(),
+    )
+    .expect("failed to open rocksdb")
+}
+
+#[tokio::test]
+async fn test_wal_batch_across_rocksdb_instances() {
+    let wal = SallyWal::open(temp_dir(), SallyWalOptions::default()).unwrap();
+    let db1 = open_map::<i32, String>(&temp_dir());
+    let db2 = open_map::<String, u64>(&temp_dir());
+    let col1 = SallyColumn::new_rocksdb_with_wal(db1.clone(), &wal, "col1").unwrap();
+    let col2 = SallyColumn::new_rocksdb_with_wal(db2.clone(), &wal, "col2").unwrap();
+
+    let mut batch = col1.batch();
+    batch
+        .insert_batch(&col1, (1..10).map(|i| (i, i.to_string())))
+        .unwrap();
+    batch
+        .insert_batch(&col2, (1..10).map(|i| (i.to_string(), i as u64)))
+        .unwrap();
+    batch.delete_batch(&col2, ["1".to_string()]).unwrap();
+    batch.delete_range(&col1, &5, &8).unwrap();
+    batch.write().await.unwrap();
+
+    // Reads are served from the memtable before anything reaches the backends
+    assert!(wal.has_unflushed_writes());
+    assert!(db1.is_empty());
+    assert_eq!(col1.get(&1).await.unwrap(), Some("1".to_string()));
+    assert_eq!(col1.get(&6).await.unwrap(), None);
+    assert!(!col2.contains_key(&"1".to_string()).await.unwrap());
+    assert_eq!(
+        col2.multi_get(["2".to_string(), "1".to_string()])
+            .await
+            .unwrap(),
+        vec![Some(2), None]
+    );
+
+    wal.flush().unwrap();
+    assert!(!wal.has_unflushed_writes());
+    assert_eq!(db1.get(&1).unwrap(), Some("1".to_string()));
+    assert_eq!(db1.get(&6).unwrap(), None);
+    assert_eq!(db2.get(&"1".to_string()).unwrap(), None);
+    assert_eq!(db2.get(&"9".to_string()).unwrap(), Some(9));
+    let keys: Vec<_> = col1.keys().await.map(|k| k.unwrap()).collect();
+    assert_eq!(keys, vec![1, 2, 3, 4, 8, 9]);
+}
+
+#[tokio::test]
+async fn test_wal_iter_merges_memtable() {
+    let wal = SallyWal::open(temp_dir(), SallyWalOptions::default()).unwrap();
+    let db = open_map::<i32, String>(&temp_dir());
+    for i in 0..10 {
+        db.insert(&i, &format!("db{i}")).unwrap();
+    }
+    let col = SallyColumn::new_rocksdb_with_wal(db.clone(), &wal, "col").unwrap();
+
+    let mut batch = col.batch();
+    batch.delete_range(&col, &2, &5).unwrap();
+    batch
+        .insert_batch(&col, [(3, "wal3".to_string()), (6, "wal6".to_string())])
+        .unwrap();
+    batch.delete_batch(&col, [7, 9]).unwrap();
+    batch
+        .insert_batch(&col, [(12, "wal12".to_string())])
+        .unwrap();
+    batch.write().await.unwrap();
+
+    let expected = vec![
+        (0, "db0".to_string()),
+        (1, "db1".to_string()),
+        (3, "wal3".to_string()),
+        (5, "db5".to_string()),
+        (6, "wal6".to_string()),
+        (8, "db8".to_string()),
+        (12, "wal12".to_string()),
+    ];
+    let items: Vec<_> = col.iter().await.map(|item| item.unwrap()).collect();
+    assert_eq!(items, expected);
+    let keys: Vec<_> = col.keys().await.map(|key| key.unwrap()).collect();
+    assert_eq!(keys, vec![0, 1, 3, 5, 6, 8, 12]);
+    assert!(!col.is_empty().await);
+    // Iterating does not flush the memtable
+    assert!(wal.has_unflushed_writes());
+    assert_eq!(db.get(&3).unwrap(), Some("db3".to_string()));
+
+    let items: Vec<_> = col
+        .iter()
+        .await
+        .skip_to(&2)
+        .unwrap()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(items, vec![3, 5, 6, 8, 12]);
+    let items: Vec<_> = col
+        .iter()
+        .await
+        .skip_prior_to(&9)
+        .unwrap()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(items, vec![8, 12]);
+    let items: Vec<_> = col
+        .iter()
+        .await
+        .skip_to_last()
+        .reverse()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(items, vec![12, 8, 6, 5, 3, 1, 0]);
+    let items: Vec<_> = col
+        .iter()
+        .await
+        .skip_prior_to(&4)
+        .unwrap()
+        .reverse()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(items, vec![3, 1, 0]);
+
+    wal.flush().unwrap();
+    let items: Vec<_> = col.iter().await.map(|item| item.unwrap()).collect();
+    assert_eq!(items, expected);
+}
+
+#[test]
+fn test_wal_cross_wal_batch() {
+    let wal1 = SallyWal::open(temp_dir(), SallyWalOptions::default()).unwrap();
+    let wal2 = SallyWal::open(temp_dir(), SallyWalOptions::default()).unwrap();
+    let col1 = SallyColumn::new_testdb_with_wal(TestDB::<i32, i32>::open(), &wal1, "col").unwrap();
+    let col2 = SallyColumn::new_testdb_with_wal(TestDB::<i32, i32>::open(), &wal2, "col").unwrap();
+    assert!(SallyColumn::new_testdb_with_wal(TestDB::<i32, i32>::open(), &wal1, "col").is_err());
+
+    let mut batch = col1.batch();
+    assert_eq!(
+        batch.insert_batch(&col2, [(1, 1)]),
+        Err(TypedStoreError::CrossDBBatch)
+    );
+}
+
+#[tokio::test]
+async fn test_wal_replay_after_crash() {
+    let wal_path = temp_dir();
+    let db1 = TestDB::<i32, String>::open();
+    let db2 = TestDB::<i32, String>::open();
+    {
+        let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+        let col1 = SallyColumn::new_testdb_with_wal(db1.clone(), &wal, "col1").unwrap();
+        let col2 = SallyColumn::new_testdb_with_wal(db2.clone(), &wal, "col2").unwrap();
+        let mut batch = col1.batch();
+        batch.insert_batch(&col1, [(1, "a".to_string())]).unwrap();
+        batch.insert_batch(&col2, [(2, "b".to_string())]).unwrap();
+        batch.write().await.unwrap();
+        let mut batch = col1.batch();
+        batch.delete_batch(&col1, [1]).unwrap();
+        batch.insert_batch(&col1, [(3, "c".to_string())]).unwrap();
+        batch.write().await.unwrap();
+        // Simulate a crash in the middle of a flush, where only one backend was written to
+        db2.insert(&2, &"b".to_string()).unwrap();
+    }
+
+    let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+    assert!(wal.has_unflushed_writes());
+    // Flushing is not possible until every column in the log has a backend
+    assert_eq!(
+        wal.flush(),
+        Err(TypedStoreError::UnregisteredColumn("col1".to_string()))
+    );
+    let col1 = SallyColumn::new_testdb_with_wal(db1.clone(), &wal, "col1").unwrap();
+    let col2 = SallyColumn::new_testdb_with_wal(db2.clone(), &wal, "col2").unwrap();
+    assert_eq!(col1.get(&1).await.unwrap(), None);
+    assert_eq!(col1.get(&3).await.unwrap(), Some("c".to_string()));
+    assert_eq!(col2.get(&2).await.unwrap(), Some("b".to_string()));
+
+    wal.flush().unwrap();
+    assert_eq!(db1.get(&1).unwrap(), None);
+    assert_eq!(db1.get(&3).unwrap(), Some("c".to_string()));
+    assert_eq!(db2.get(&2).unwrap(), Some("b".to_string()));
+
+    // Nothing is replayed once the log has been flushed
+    drop(col1);
+    drop(col2);
+    drop(wal);
+    let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+    assert!(!wal.has_unflushed_writes());
+}
+
+#[tokio::test]
+async fn test_wal_replay_discards_torn_record() {
+    let wal_path = temp_dir();
+    let db = TestDB::<i32, i32>::open();
+    let log_path = {
+        let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+        let col = SallyColumn::new_testdb_with_wal(db.clone(), &wal, "col").unwrap();
+        let mut batch = col.batch();
+        batch.insert_batch(&col, [(1, 1), (2, 2)]).unwrap();
+        batch.write().await.unwrap();
+        wal.path().to_path_buf()
+    };
+    let valid_len = std::fs::metadata(&log_path).unwrap().len();
+    // Append a partially written record
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&log_path)
+        .unwrap();
+    file.write_all(&[42, 0, 0, 0, 1, 2, 3]).unwrap();
+    drop(file);
+
+    let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+    assert_eq!(std::fs::metadata(&log_path).unwrap().len(), valid_len);
+    let col = SallyColumn::new_testdb_with_wal(db.clone(), &wal, "col").unwrap();
+    assert_eq!(col.get(&2).await.unwrap(), Some(2));
+
+    // New records are appended after the last valid one
+    let mut batch = col.batch();
+    batch.insert_batch(&col, [(3, 3)]).unwrap();
+    batch.write().await.unwrap();
+    drop(col);
+    drop(wal);
+    let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+    let col = SallyColumn::new_testdb_with_wal(db, &wal, "col").unwrap();
+    assert_eq!(col.get(&1).await.unwrap(), Some(1));
+    assert_eq!(col.get(&3).await.unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn test_wal_replay_fails_on_corrupted_record() {
+    let wal_path = temp_dir();
+    let db = TestDB::<i32, i32>::open();
+    let log_path = {
+        let wal = SallyWal::open(&wal_path, SallyWalOptions::default()).unwrap();
+        let col = SallyColumn::new_testdb_with_wal(db.clone(), &wal, "col").unwrap();
+        for i in 0..2 {
+            let mut batch = col.batch();
+            batch.insert_batch(&col, [(i, i)]).unwrap();
+            batch.write().await.unwrap();
+        }
+        wal.path().to_path_buf()
+    };
+    // Corrupt the payload of the first record, which is followed by a valid one
+    let mut log = std::fs::read(&log_path).unwrap();
+    log[wal::RECORD_HEADER_SIZE] ^= 0xff;
+    std::fs::write(&log_path, &log).unwrap();
+
+    assert!(matches!(
+        SallyWal::open(&wal_path, SallyWalOptions::default()),
+        Err(TypedStoreError::WalError(_))
+    ));
+    // The log is left as is, for it to be inspected
+    assert_eq!(std::fs::read(&log_path).unwrap(), log);
+}
+
+#[tokio::test]
+async fn test_wal_flushes_full_memtable() {
+    let wal = SallyWal::open(
+        temp_dir(),
+        SallyWalOptions {
+            max_memtable_size_bytes: 64,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let db = TestDB::<u64, u64>::open();
+    let col = SallyColumn::new_testdb_with_wal(db.clone(), &wal, "col").unwrap();
+    let mut batch = col.batch();
+    batch.insert_batch(&col, (0..2).map(|i| (i, i))).unwrap();
+    batch.write().await.unwrap();
+    assert!(wal.has_unflushed_writes());
+    assert!(db.is_empty());
+
+    let mut batch = col.batch();
+    batch.insert_batch(&col, (2..4).map(|i| (i, i))).unwrap();
+    batch.write().await.unwrap();
+    assert!(!wal.has_unflushed_writes());
+    assert_eq!(std::fs::metadata(wal.path()).unwrap().len(), 0);
+    assert_eq!(db.get(&3).unwrap(), Some(3));
+}
@@ -0,0 +1,875 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sally's own write-ahead log and memtable.
+//!
+//! When the columns of a sally db are backed by more than one storage instance, writes cannot be
+//! committed atomically by any single backend. In that case every write batch is first appended
+//! to a shared write-ahead log and applied to an in-memory memtable which serves reads. Once the
+//! memtable grows past a threshold (or on an explicit `flush`), its contents are written out to
+//! each backend and the log is truncated. Since the memtable only holds the latest state of every
+//! key, flushing is idempotent and a crash at any point is recovered by replaying the log on
+//! restart and flushing again.
+//!
+//! Every write batch is appended to the log as one frame of `[payload length][checksum][payload]`
+//! and fsynced before the write is acknowledged. Only the last frame of the log can be torn by a
+//! crash, so on replay a torn tail is truncated while a corrupted frame anywhere else is an error.
+//! Iterators do not flush the memtable, they merge the unflushed writes of their column over the
+//! iterator of its backend store instead.
+use crate::{
+    rocks::{
+        be_fix_int_ser,
+        errors::{
+            typed_store_err_from_bcs_err, typed_store_err_from_bincode_err,
+            typed_store_err_from_rocks_err,
+        },
+        DBMap,
+    },
+    test_db::{TestDB, TestDBWriteBatch, WriteBatchOp},
+    TypedStoreError,
+};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, VecDeque},
+    fs::{File, OpenOptions},
+    hash::Hasher,
+    io::{Read, Write},
+    iter::Peekable,
+    marker::PhantomData,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+use tracing::{error, info, warn};
+use twox_hash::XxHash64;
+
+pub const SALLY_WAL_FILE_NAME: &str = "sally.wal";
+
+// Every record in the log is framed as `[payload length: u32][checksum: u64][payload]`
+pub(crate) const RECORD_HEADER_SIZE: usize = 12;
+
+fn typed_store_err_from_io_err(err: std::io::Error) -> TypedStoreError {
+    TypedStoreError::WalError(err.to_string())
+}
+
+fn checksum(payload: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(payload);
+    hasher.finish()
+}
+
+/// Options to configure the sally write-ahead log
+#[derive(Clone, Debug)]
+pub struct SallyWalOptions {
+    /// Approximate size of the memtable after which it is flushed to the backend stores
+    pub max_memtable_size_bytes: usize,
+}
+
+impl Default for SallyWalOptions {
+    fn default() -> Self {
+        Self {
+            max_memtable_size_bytes: 64 << 20,
+        }
+    }
+}
+
+/// Outcome of decoding the frame at the start of a buffer
+enum DecodedRecord {
+    Valid(Vec<SallyWalOp>, usize),
+    // The frame does not fit in the buffer or fails its checksum, and ends the buffer
+    Torn,
+    // The frame fails its checksum but is followed by more frames
+    Corrupted,
+}
+
+/// A single mutation recorded in the log. Keys and values are stored in their serialized form so
+/// that one log can span columns of different types.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) enum SallyWalOp {
+    Insert {
+        column: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        column: String,
+        key: Vec<u8>,
+    },
+    DeleteRange {
+        column: String,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+}
+
+impl SallyWalOp {
+    fn column(&self) -> &str {
+        match self {
+            SallyWalOp::Insert { column, .. }
+            | SallyWalOp::Delete { column, .. }
+            | SallyWalOp::DeleteRange { column, .. } => column,
+        }
+    }
+}
+
+/// Unflushed state of a single column. A `None` entry is a point tombstone, and range deletes are
+/// kept separately so they can be applied to the backend before the surviving entries.
+#[derive(Default, Debug)]
+pub(crate) struct ColumnMemtable {
+    entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    range_deletes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ColumnMemtable {
+    /// Applies an op to the memtable and returns the approximate number of bytes it added
+    fn apply(&mut self, op: SallyWalOp) -> usize {
+        match op {
+            SallyWalOp::Insert { key, value, .. } => {
+                let size = key.len() + value.len();
+                self.entries.insert(key, Some(value));
+                size
+            }
+            SallyWalOp::Delete { key, .. } => {
+                let size = key.len();
+                self.entries.insert(key, None);
+                size
+            }
+            SallyWalOp::DeleteRange { from, to, .. } => {
+                // Entries written before the range delete are shadowed by it, entries written
+                // after it are kept in `entries` and win over the range delete on flush
+                self.entries.retain(|k, _| k < &from || k >= &to);
+                let size = from.len() + to.len();
+                self.range_deletes.push((from, to));
+                size
+            }
+        }
+    }
+
+    /// Returns `Some(Some(value))` if the key was written, `Some(None)` if it was deleted and
+    /// `None` if the memtable has no information about the key
+    fn lookup(&self, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        if let Some(value) = self.entries.get(key) {
+            return Some(value.clone());
+        }
+        if self
+            .range_deletes
+            .iter()
+            .any(|(from, to)| from.as_slice() <= key && key < to.as_slice())
+        {
+            return Some(None);
+        }
+        None
+    }
+
+    /// Copies the entries of the memtable within the given key range, along with its range
+    /// deletes, so that the memtable can be merged into an iterator of the backend store
+    fn snapshot(&self, range: (Bound<Vec<u8>>, Bound<Vec<u8>>)) -> ColumnMemtableSnapshot {
+        ColumnMemtableSnapshot {
+            entries: self
+                .entries
+                .range(range)
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            range_deletes: self.range_deletes.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ColumnMemtableSnapshot {
+    entries: VecDeque<(Vec<u8>, Option<Vec<u8>>)>,
+    range_deletes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ColumnMemtableSnapshot {
+    fn next_key(&self, reverse: bool) -> Option<&Vec<u8>> {
+        let entry = if reverse {
+            self.entries.back()
+        } else {
+            self.entries.front()
+        };
+        entry.map(|(key, _)| key)
+    }
+
+    fn is_range_deleted(&self, key: &[u8]) -> bool {
+        self.range_deletes
+            .iter()
+            .any(|(from, to)| from.as_slice() <= key && key < to.as_slice())
+    }
+}
+
+/// A backend store which sally flushes its memtable into
+pub(crate) trait SallyFlushTarget: Send + Sync {
+    fn flush(&self, memtable: &ColumnMemtable) -> Result<(), TypedStoreError>;
+}
+
+impl<K, V> SallyFlushTarget for DBMap<K, V> {
+    fn flush(&self, memtable: &ColumnMemtable) -> Result<(), TypedStoreError> {
+        let mut batch = self.batch();
+        // Range deletes are expanded to point deletes because rocksdb range tombstones are
+        // ignored by reads on a DBMap unless `ignore_range_deletions` is disabled
+        for (from, to) in &memtable.range_deletes {
+            let mut iter =
+                self.rocksdb
+                    .raw_iterator_cf(self.cf_name(), self.opts.readopts(), None, None);
+            iter.seek(from);
+            while let Some(key) = iter.key() {
+                if key >= to.as_slice() {
+                    break;
+                }
+                batch.delete_raw(self, key)?;
+                iter.next();
+            }
+            iter.status().map_err(typed_store_err_from_rocks_err)?;
+        }
+        for (key, value) in &memtable.entries {
+            match value {
+                Some(value) => batch.insert_raw(self, key, value)?,
+                None => batch.delete_raw(self, key)?,
+            }
+        }
+        batch.write()
+    }
+}
+
+impl<K, V> SallyFlushTarget for TestDB<K, V> {
+    fn flush(&self, memtable: &ColumnMemtable) -> Result<(), TypedStoreError> {
+        let mut batch = TestDBWriteBatch::default();
+        for (from, to) in &memtable.range_deletes {
+            batch.ops.push_back(WriteBatchOp::DeleteRange((
+                self.rows.clone(),
+                self.name.clone(),
+                (from.clone(), to.clone()),
+            )));
+        }
+        let (inserts, deletes): (Vec<_>, Vec<_>) =
+            memtable.entries.iter().partition(|(_, v)| v.is_some());
+        batch.ops.push_back(WriteBatchOp::DeleteBatch((
+            self.rows.clone(),
+            self.name.clone(),
+            deletes.into_iter().map(|(k, _)| k.clone()).collect(),
+        )));
+        batch.ops.push_back(WriteBatchOp::InsertBatch((
+            self.rows.clone(),
+            self.name.clone(),
+            inserts
+                .into_iter()
+                .filter_map(|(k, v)| v.clone().map(|v| (k.clone(), v)))
+                .collect(),
+        )));
+        batch.write()
+    }
+}
+
+struct SallyWalState {
+    file: File,
+    // Length of the log up to the end of its last acknowledged frame
+    len: u64,
+    // Set when a failed write could not be rolled back, after which the log is not appended to
+    poisoned: bool,
+    memtable: BTreeMap<String, ColumnMemtable>,
+    memtable_size_bytes: usize,
+}
+
+struct SallyWalInner {
+    path: PathBuf,
+    options: SallyWalOptions,
+    state: Mutex<SallyWalState>,
+    targets: RwLock<BTreeMap<String, Box<dyn SallyFlushTarget>>>,
+}
+
+/// A handle to a sally write-ahead log and its memtable. It is cheap to clone and is shared by all
+/// the columns of a sally db running in `SallyRunMode::MemtableAndWal`.
+#[derive(Clone)]
+pub struct SallyWal {
+    inner: Arc<SallyWalInner>,
+}
+
+impl SallyWal {
+    /// Opens the log in the given directory, replaying any records which were not flushed to the
+    /// backend stores before the last shutdown or crash. A torn record at the tail of the log is
+    /// discarded, a corrupted record before the tail fails the open.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        options: SallyWalOptions,
+    ) -> Result<Self, TypedStoreError> {
+        std::fs::create_dir_all(path.as_ref()).map_err(typed_store_err_from_io_err)?;
+        let path = path.as_ref().join(SALLY_WAL_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .map_err(typed_store_err_from_io_err)?;
+        let mut buf = vec![];
+        file.read_to_end(&mut buf)
+            .map_err(typed_store_err_from_io_err)?;
+
+        let mut memtable: BTreeMap<String, ColumnMemtable> = BTreeMap::new();
+        let mut memtable_size_bytes = 0;
+        let mut offset = 0;
+        let mut num_records = 0;
+        while offset < buf.len() {
+            match Self::decode_record(&buf[offset..]) {
+                DecodedRecord::Valid(ops, len) => {
+                    for op in ops {
+                        memtable_size_bytes += memtable
+                            .entry(op.column().to_owned())
+                            .or_default()
+                            .apply(op);
+                    }
+                    offset += len;
+                    num_records += 1;
+                }
+                DecodedRecord::Torn => break,
+                DecodedRecord::Corrupted => {
+                    return Err(TypedStoreError::WalError(format!(
+                        "corrupted record at offset {offset} of {path:?}"
+                    )));
+                }
+            }
+        }
+        if offset < buf.len() {
+            warn!(
+                "Discarding {} bytes of a torn record at the end of {:?}",
+                buf.len() - offset,
+                path
+            );
+            file.set_len(offset as u64)
+                .map_err(typed_store_err_from_io_err)?;
+            file.sync_all().map_err(typed_store_err_from_io_err)?;
+        }
+        if num_records > 0 {
+            info!("Replayed {num_records} records from {:?}", path);
+        }
+
+        Ok(Self {
+            inner: Arc::new(SallyWalInner {
+                path,
+                options,
+                state: Mutex::new(SallyWalState {
+                    file,
+                    len: offset as u64,
+                    poisoned: false,
+                    memtable,
+                    memtable_size_bytes,
+                }),
+                targets: RwLock::new(BTreeMap::new()),
+            }),
+        })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Returns true if there are writes in the memtable which are not yet flushed to the backends
+    pub fn has_unflushed_writes(&self) -> bool {
+        !self.inner.state.lock().unwrap().memtable.is_empty()
+    }
+
+    /// Registers the backend store of a column under the given name. The name identifies the
+    /// column in the log, so it must be stable across restarts.
+    pub(crate) fn register(
+        &self,
+        column: &str,
+        target: Box<dyn SallyFlushTarget>,
+    ) -> Result<(), TypedStoreError> {
+        let mut targets = self.inner.targets.write().unwrap();
+        if targets.contains_key(column) {
+            return Err(TypedStoreError::WalError(format!(
+                "column {column} is already registered"
+            )));
+        }
+        targets.insert(column.to_owned(), target);
+        Ok(())
+    }
+
+    /// Durably appends the ops to the log as one record and applies them to the memtable. The
+    /// record is fsynced before the write returns.
+    pub(crate) fn write(&self, ops: Vec<SallyWalOp>) -> Result<(), TypedStoreError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let payload = bcs::to_bytes(&ops).map_err(typed_store_err_from_bcs_err)?;
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&checksum(&payload).to_le_bytes());
+        record.extend_from_slice(&payload);
+
+        let mut state = self.inner.state.lock().unwrap();
+        if state.poisoned {
+            return Err(TypedStoreError::WalError(format!(
+                "{:?} has a partially written record, it must be reopened",
+                self.inner.path
+            )));
+        }
+        if let Err(e) = state
+            .file
+            .write_all(&record)
+            .and_then(|_| state.file.sync_data())
+        {
+            // Drop the partially written record so that later records are not appended after it,
+            // which would turn it into a corrupted record in the middle of the log
+            if let Err(e) = state.file.set_len(state.len) {
+                error!("Failed to truncate a partially written sally wal record: {e}");
+                state.poisoned = true;
+            }
+            return Err(typed_store_err_from_io_err(e));
+        }
+        state.len += record.len() as u64;
+        for op in ops {
+            let size = state
+                .memtable
+                .entry(op.column().to_owned())
+                .or_default()
+                .apply(op);
+            state.memtable_size_bytes += size;
+        }
+        if state.memtable_size_bytes >= self.inner.options.max_memtable_size_bytes {
+            // The batch is already durable in the log, so a failed flush is retried on the next
+            // write or recovered on restart
+            if let Err(e) = self.flush_locked(&mut state) {
+                error!("Failed to flush sally memtable: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the memtable out to the backend stores and truncates the log
+    pub fn flush(&self) -> Result<(), TypedStoreError> {
+        let mut state = self.inner.state.lock().unwrap();
+        self.flush_locked(&mut state)
+    }
+
+    fn flush_locked(&self, state: &mut SallyWalState) -> Result<(), TypedStoreError> {
+        if state.memtable.is_empty() {
+            return Ok(());
+        }
+        let targets = self.inner.targets.read().unwrap();
+        // Make sure every column can be flushed before writing to any backend
+        if let Some(column) = state.memtable.keys().find(|c| !targets.contains_key(*c)) {
+            return Err(TypedStoreError::UnregisteredColumn(column.clone()));
+        }
+        for (column, memtable) in &state.memtable {
+            targets[column].flush(memtable)?;
+        }
+        // Only drop the log once every backend holds the flushed state
+        state.file.set_len(0).map_err(typed_store_err_from_io_err)?;
+        state.file.sync_all().map_err(typed_store_err_from_io_err)?;
+        state.len = 0;
+        state.poisoned = false;
+        state.memtable.clear();
+        state.memtable_size_bytes = 0;
+        Ok(())
+    }
+
+    /// Looks up a serialized key of a column in the memtable
+    pub(crate) fn lookup(&self, column: &str, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        self.inner
+            .state
+            .lock()
+            .unwrap()
+            .memtable
+            .get(column)
+            .and_then(|memtable| memtable.lookup(key))
+    }
+
+    /// Copies the memtable of a column within the given key range and opens an iterator of its
+    /// backend store with `backend`. Both happen under the memtable lock, so that no flush can move
+    /// writes from the memtable to the backend in between and hide them from the iterator.
+    fn snapshot<I>(
+        &self,
+        column: &str,
+        range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        backend: impl FnOnce() -> Result<I, TypedStoreError>,
+    ) -> Result<(ColumnMemtableSnapshot, I), TypedStoreError> {
+        let state = self.inner.state.lock().unwrap();
+        let snapshot = state
+            .memtable
+            .get(column)
+            .map(|memtable| memtable.snapshot(range))
+            .unwrap_or_default();
+        Ok((snapshot, backend()?))
+    }
+
+    fn decode_record(buf: &[u8]) -> DecodedRecord {
+        if buf.len() < RECORD_HEADER_SIZE {
+            return DecodedRecord::Torn;
+        }
+        let len = u32::from_le_bytes(buf[0..4].try_into().unwrap()) as usize;
+        let expected_checksum = u64::from_le_bytes(buf[4..12].try_into().unwrap());
+        let Some(payload) = buf.get(RECORD_HEADER_SIZE..RECORD_HEADER_SIZE + len) else {
+            return DecodedRecord::Torn;
+        };
+        let ops = (checksum(payload) == expected_checksum)
+            .then(|| bcs::from_bytes(payload).ok())
+            .flatten();
+        match ops {
+            Some(ops) => DecodedRecord::Valid(ops, RECORD_HEADER_SIZE + len),
+            // The space of the last record can be allocated before its payload is written
+            None if RECORD_HEADER_SIZE + len == buf.len() => DecodedRecord::Torn,
+            None => DecodedRecord::Corrupted,
+        }
+    }
+}
+
+/// The per column handle to a shared sally write-ahead log
+#[derive(Clone)]
+pub struct SallyWalColumn {
+    wal: SallyWal,
+    name: String,
+}
+
+impl SallyWalColumn {
+    pub(crate) fn new(wal: &SallyWal, name: &str) -> Self {
+        Self {
+            wal: wal.clone(),
+            name: name.to_owned(),
+        }
+    }
+
+    pub fn wal(&self) -> &SallyWal {
+        &self.wal
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reads a key from the memtable, falling back to the backend store if the memtable has no
+    /// information about it
+    pub(crate) fn get<K: Serialize, V: DeserializeOwned>(
+        &self,
+        key: &K,
+        fallback: impl FnOnce(&K) -> Result<Option<V>, TypedStoreError>,
+    ) -> Result<Option<V>, TypedStoreError> {
+        let key_buf = be_fix_int_ser(key)?;
+        match self.wal.lookup(&self.name, &key_buf) {
+            Some(Some(value)) => Ok(Some(
+                bcs::from_bytes(&value).map_err(typed_store_err_from_bcs_err)?,
+            )),
+            Some(None) => Ok(None),
+            None => fallback(key),
+        }
+    }
+
+    pub(crate) fn get_raw_bytes<K: Serialize>(
+        &self,
+        key: &K,
+        fallback: impl FnOnce(&K) -> Result<Option<Vec<u8>>, TypedStoreError>,
+    ) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let key_buf = be_fix_int_ser(key)?;
+        match self.wal.lookup(&self.name, &key_buf) {
+            Some(value) => Ok(value),
+            None => fallback(key),
+        }
+    }
+
+    pub(crate) fn contains_key<K: Serialize>(
+        &self,
+        key: &K,
+        fallback: impl FnOnce(&K) -> Result<bool, TypedStoreError>,
+    ) -> Result<bool, TypedStoreError> {
+        let key_buf = be_fix_int_ser(key)?;
+        match self.wal.lookup(&self.name, &key_buf) {
+            Some(value) => Ok(value.is_some()),
+            None => fallback(key),
+        }
+    }
+
+    /// Reads a set of keys, only going to the backend store for the keys missing in the memtable
+    pub(crate) fn multi_get<J: Borrow<K>, K: Serialize, V: DeserializeOwned>(
+        &self,
+        keys: impl IntoIterator<Item = J>,
+        fallback: impl FnOnce(Vec<&K>) -> Result<Vec<Option<V>>, TypedStoreError>,
+    ) -> Result<Vec<Option<V>>, TypedStoreError> {
+        let keys: Vec<J> = keys.into_iter().collect();
+        let mut results = Vec::with_capacity(keys.len());
+        let mut missing = vec![];
+        for (i, key) in keys.iter().enumerate() {
+            let key_buf = be_fix_int_ser(key.borrow())?;
+            match self.wal.lookup(&self.name, &key_buf) {
+                Some(Some(value)) => results.push(Some(
+                    bcs::from_bytes(&value).map_err(typed_store_err_from_bcs_err)?,
+                )),
+                Some(None) => results.push(None),
+                None => {
+                    results.push(None);
+                    missing.push(i);
+                }
+            }
+        }
+        if !missing.is_empty() {
+            let fetched = fallback(missing.iter().map(|i| keys[*i].borrow()).collect())?;
+            for (i, value) in missing.into_iter().zip(fetched) {
+                results[i] = value;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Returns an iterator merging the unflushed writes of the column over the iterator of the
+    /// backend store opened by `backend`, which must be positioned at `from`. Keys before `from`
+    /// are skipped.
+    pub(crate) fn iter<I, K, V>(
+        &self,
+        backend: impl FnOnce() -> Result<I, TypedStoreError>,
+        from: Option<&K>,
+    ) -> SallyWalIter<I, K, V>
+    where
+        I: Iterator,
+        K: Serialize,
+    {
+        let lower = match from.map(be_fix_int_ser).transpose() {
+            Ok(lower) => lower.map_or(Bound::Unbounded, Bound::Included),
+            Err(e) => return SallyWalIter::failed(e),
+        };
+        match self
+            .wal
+            .snapshot(&self.name, (lower, Bound::Unbounded), backend)
+        {
+            Ok((snapshot, backend)) => SallyWalIter::new(backend, snapshot, false),
+            Err(e) => SallyWalIter::failed(e),
+        }
+    }
+
+    /// Returns an iterator merging the unflushed writes of the column over the reverse iterator
+    /// of the backend store opened by `backend`, which must be positioned at `to`. Keys after `to`
+    /// are skipped.
+    pub(crate) fn rev_iter<I, K, V>(
+        &self,
+        backend: impl FnOnce() -> Result<I, TypedStoreError>,
+        to: Option<&K>,
+    ) -> SallyWalIter<I, K, V>
+    where
+        I: Iterator,
+        K: Serialize,
+    {
+        let upper = match to.map(be_fix_int_ser).transpose() {
+            Ok(upper) => upper.map_or(Bound::Unbounded, Bound::Included),
+            Err(e) => return SallyWalIter::failed(e),
+        };
+        match self
+            .wal
+            .snapshot(&self.name, (Bound::Unbounded, upper), backend)
+        {
+            Ok((snapshot, backend)) => SallyWalIter::new(backend, snapshot, true),
+            Err(e) => SallyWalIter::failed(e),
+        }
+    }
+}
+
+/// An iterator over a column served by the sally memtable and wal. The unflushed writes of the
+/// column, as of when the iterator was created, are merged over an iterator of the backend store
+/// so that iterating does not require flushing the memtable.
+pub struct SallyWalIter<I, K, V> {
+    backend: Option<Peekable<I>>,
+    memtable: ColumnMemtableSnapshot,
+    reverse: bool,
+    peeked: Option<Option<Result<(K, V), TypedStoreError>>>,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<I, K, V> SallyWalIter<I, K, V> {
+    fn new(backend: I, memtable: ColumnMemtableSnapshot, reverse: bool) -> Self
+    where
+        I: Iterator,
+    {
+        Self {
+            backend: Some(backend.peekable()),
+            memtable,
+            reverse,
+            peeked: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// An iterator which yields nothing
+    pub(crate) fn empty() -> Self {
+        Self {
+            backend: None,
+            memtable: ColumnMemtableSnapshot::default(),
+            reverse: false,
+            peeked: Some(None),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// An iterator which only yields the given error
+    pub(crate) fn failed(err: TypedStoreError) -> Self {
+        Self {
+            peeked: Some(Some(Err(err))),
+            ..Self::empty()
+        }
+    }
+}
+
+impl<I, K, V> SallyWalIter<I, K, V>
+where
+    I: Iterator<Item = Result<(K, V), TypedStoreError>>,
+    K: Serialize + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    /// Returns the next item without advancing the iterator
+    pub(crate) fn peek(&mut self) -> Option<&Result<(K, V), TypedStoreError>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.merge_next());
+        }
+        self.peeked.as_ref().unwrap().as_ref()
+    }
+
+    fn merge_next(&mut self) -> Option<Result<(K, V), TypedStoreError>> {
+        let config = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding();
+        loop {
+            let backend = self.backend.as_mut()?;
+            let backend_key = match backend.peek() {
+                Some(Ok((key, _))) => Some(be_fix_int_ser(key)),
+                Some(Err(_)) => return backend.next(),
+                None => None,
+            };
+            let backend_key = match backend_key.transpose() {
+                Ok(key) => key,
+                Err(e) => {
+                    backend.next();
+                    return Some(Err(e));
+                }
+            };
+            let from_memtable = match (self.memtable.next_key(self.reverse), &backend_key) {
+                (None, None) => return None,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (Some(memtable_key), Some(backend_key)) if self.reverse => {
+                    memtable_key >= backend_key
+                }
+                (Some(memtable_key), Some(backend_key)) => memtable_key <= backend_key,
+            };
+            if !from_memtable {
+                let item = backend.next();
+                if self
+                    .memtable
+                    .is_range_deleted(backend_key.as_ref().unwrap())
+                {
+                    continue;
+                }
+                return item;
+            }
+            let (key, value) = if self.reverse {
+                self.memtable.entries.pop_back()
+            } else {
+                self.memtable.entries.pop_front()
+            }
+            .unwrap();
+            // The memtable shadows the backend store
+            if backend_key.as_ref() == Some(&key) {
+                backend.next();
+            }
+            let Some(value) = value else {
+                continue;
+            };
+            return Some(
+                config
+                    .deserialize(&key)
+                    .map_err(typed_store_err_from_bincode_err)
+                    .and_then(|key| {
+                        bcs::from_bytes(&value)
+                            .map(|value| (key, value))
+                            .map_err(typed_store_err_from_bcs_err)
+                    }),
+            );
+        }
+    }
+}
+
+impl<I, K, V> Iterator for SallyWalIter<I, K, V>
+where
+    I: Iterator<Item = Result<(K, V), TypedStoreError>>,
+    K: Serialize + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    type Item = Result<(K, V), TypedStoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.peeked.take() {
+            Some(item) => item,
+            None => self.merge_next(),
+        }
+    }
+}
+
+/// A write batch which is committed atomically through the sally write-ahead log
+pub struct SallyWalBatch {
+    wal: SallyWal,
+    ops: Vec<SallyWalOp>,
+}
+
+impl SallyWalBatch {
+    pub(crate) fn new(wal: &SallyWal) -> Self {
+        Self {
+            wal: wal.clone(),
+            ops: vec![],
+        }
+    }
+
+    fn check_wal(&self, column: &SallyWalColumn) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&self.wal.inner, &column.wal.inner) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        Ok(())
+    }
+
+    pub fn write(self) -> Result<(), TypedStoreError> {
+        self.wal.write(self.ops)
+    }
+
+    pub(crate) fn delete_batch<J: Borrow<K>, K: Serialize>(
+        &mut self,
+        column: &SallyWalColumn,
+        purged_vals: impl IntoIterator<Item = J>,
+    ) -> Result<(), TypedStoreError> {
+        self.check_wal(column)?;
+        for key in purged_vals {
+            self.ops.push(SallyWalOp::Delete {
+                column: column.name.clone(),
+                key: be_fix_int_ser(key.borrow())?,
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn delete_range<K: Serialize>(
+        &mut self,
+        column: &SallyWalColumn,
+        from: &K,
+        to: &K,
+    ) -> Result<(), TypedStoreError> {
+        self.check_wal(column)?;
+        self.ops.push(SallyWalOp::DeleteRange {
+            column: column.name.clone(),
+            from: be_fix_int_ser(from)?,
+            to: be_fix_int_ser(to)?,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn insert_batch<J: Borrow<K>, K: Serialize, U: Borrow<V>, V: Serialize>(
+        &mut self,
+        column: &SallyWalColumn,
+        new_vals: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<(), TypedStoreError> {
+        self.check_wal(column)?;
+        for (key, value) in new_vals {
+            self.ops.push(SallyWalOp::Insert {
+                column: column.name.clone(),
+                key: be_fix_int_ser(key.borrow())?,
+                value: bcs::to_bytes(value.borrow()).map_err(typed_store_err_from_bcs_err)?,
+            });
+        }
+        Ok(())
+    }
+}
@@ -8,10 +8,11 @@ use std::{
     marker::PhantomData,
     ops::RangeBounds,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use crate::{
-    rocks::{be_fix_int_ser, errors::typed_store_err_from_bcs_err},
+    rocks::{be_fix_int_ser, errors::typed_store_err_from_bcs_err, util::is_expired},
     Map, TypedStoreError,
 };
 use bincode::Options;
@@ -42,6 +43,40 @@ impl<K, V> TestDB<K, V> {
     pub fn batch(&self) -> TestDBWriteBatch {
         TestDBWriteBatch::default()
     }
+
+    /// Returns a point-in-time copy of this table, which is not affected by later writes.
+    pub fn snapshot(&self) -> Self {
+        TestDB {
+            rows: Arc::new(RwLock::new(self.rows.read().unwrap().clone())),
+            name: Alphanumeric.sample_string(&mut rand::thread_rng(), 16),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> TestDB<K, V> {
+    /// Removes the entries that `timestamp_ms` dates more than `ttl` ago. This is what the
+    /// compaction filter installed by `DBOptions::set_ttl` does for RocksDB tables.
+    pub fn remove_expired(
+        &self,
+        ttl: Duration,
+        timestamp_ms: impl Fn(&K, &V) -> Option<u64>,
+    ) -> Result<(), TypedStoreError> {
+        let config = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding();
+        let mut locked = self.rows.write().unwrap();
+        locked.retain(|raw_key, raw_value| {
+            let (Ok(key), Ok(value)) = (
+                config.deserialize::<K>(raw_key),
+                bcs::from_bytes::<V>(raw_value),
+            ) else {
+                return true;
+            };
+            !timestamp_ms(&key, &value).is_some_and(|ts| is_expired(ts, ttl))
+        });
+        Ok(())
+    }
 }
 
 #[self_referencing(pub_extras)]
@@ -271,6 +306,12 @@ where
         Ok(())
     }
 
+    fn schedule_delete_range(&self, from: &K, to: &K) -> Result<(), TypedStoreError> {
+        let mut batch = self.batch();
+        batch.delete_range(self, from, to)?;
+        batch.write()
+    }
+
     fn is_empty(&self) -> bool {
         let locked = self.rows.read().unwrap();
         locked.is_empty()
@@ -518,6 +559,7 @@ impl TestDBWriteBatch {
 #[cfg(test)]
 mod test {
     use crate::{test_db::TestDB, Map};
+    use std::time::Duration;
 
     #[test]
     fn test_contains_key() {
@@ -824,4 +866,51 @@ mod test {
             assert_eq!(Some(v), val);
         }
     }
+
+    #[test]
+    fn test_schedule_delete_range() {
+        let db: TestDB<i32, String> = TestDB::open();
+        db.multi_insert((0..101).map(|i| (i, i.to_string())))
+            .expect("Failed to multi-insert");
+
+        db.schedule_delete_range(&50, &100)
+            .expect("Failed to delete range");
+
+        for k in 0..50 {
+            assert!(db.contains_key(&k).expect("Failed to query legal key"));
+        }
+        for k in 50..100 {
+            assert!(!db.contains_key(&k).expect("Failed to query legal key"));
+        }
+        // range is not inclusive of `to`
+        assert!(db.contains_key(&100).expect("Failed to query legal key"));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let db: TestDB<i32, String> = TestDB::open();
+        db.insert(&1, &"1".to_string()).expect("Failed to insert");
+
+        let snapshot = db.snapshot();
+        db.insert(&1, &"one".to_string()).expect("Failed to insert");
+        db.insert(&2, &"2".to_string()).expect("Failed to insert");
+
+        assert_eq!(snapshot.get(&1).unwrap(), Some("1".to_string()));
+        assert_eq!(snapshot.get(&2).unwrap(), None);
+        assert_eq!(db.get(&1).unwrap(), Some("one".to_string()));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        // Values are the unix time in milliseconds at which they were written.
+        let db: TestDB<i32, u64> = TestDB::open();
+        db.insert(&1, &0).expect("Failed to insert");
+        db.insert(&2, &u64::MAX).expect("Failed to insert");
+
+        db.remove_expired(Duration::from_secs(60), |_k, v| Some(*v))
+            .expect("Failed to remove expired entries");
+
+        assert!(!db.contains_key(&1).unwrap());
+        assert!(db.contains_key(&2).unwrap());
+    }
 }
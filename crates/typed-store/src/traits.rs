@@ -51,6 +51,9 @@ where
     /// Uses delete range on the entire key range
     fn schedule_delete_all(&self) -> Result<(), TypedStoreError>;
 
+    /// Uses delete range on the keys between `from` (inclusive) and `to` (exclusive)
+    fn schedule_delete_range(&self, from: &K, to: &K) -> Result<(), TypedStoreError>;
+
     /// Returns true if the map is empty, otherwise false.
     fn is_empty(&self) -> bool;
 
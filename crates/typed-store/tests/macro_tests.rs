@@ -302,6 +302,65 @@ async fn macro_transactional_test() {
     assert_eq!(tables.table1.get(&key), Ok(Some("1".to_string())));
 }
 
+#[tokio::test]
+async fn macro_in_memory_test() {
+    let tables = Tables::open_tables_in_memory(MetricConf::default());
+    let mut batch = tables.table1.batch();
+    batch
+        .insert_batch(
+            &tables.table1,
+            (1..10).map(|i| (i.to_string(), i.to_string())),
+        )
+        .unwrap();
+    batch
+        .insert_batch(&tables.table2, (1..10).map(|i| (i, i.to_string())))
+        .unwrap();
+    batch.delete_batch(&tables.table2, [5]).unwrap();
+    batch.write().unwrap();
+
+    assert_eq!(
+        tables.table1.get(&"3".to_string()),
+        Ok(Some("3".to_string()))
+    );
+    let keys: Vec<_> = tables
+        .table2
+        .safe_iter_with_bounds(Some(3), Some(8))
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(keys, vec![3, 4, 6, 7]);
+    let keys: Vec<_> = tables
+        .table2
+        .safe_iter()
+        .skip_to_last()
+        .reverse()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(keys, vec![9, 8, 7, 6, 4, 3, 2, 1]);
+
+    // Every in-memory instance is independent, even when opened from the same struct
+    let other_tables = Tables::open_tables_in_memory(MetricConf::default());
+    assert!(other_tables.table1.is_empty());
+    assert!(!tables.table1.is_empty());
+
+    // Snapshots are not affected by later writes, range deletes or clears
+    let snapshot = tables.table2.snapshot().unwrap();
+    tables.table2.schedule_delete_range(&2, &7).unwrap();
+    let keys: Vec<_> = tables
+        .table2
+        .safe_iter()
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(keys, vec![1, 7, 8, 9]);
+    tables.table2.unsafe_clear().unwrap();
+    assert!(tables.table2.is_empty());
+    let keys: Vec<_> = tables
+        .table2
+        .safe_iter_at_snapshot(&snapshot, None, None)
+        .map(|item| item.unwrap().0)
+        .collect();
+    assert_eq!(keys, vec![1, 2, 3, 4, 6, 7, 8, 9]);
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesCustomOptions {